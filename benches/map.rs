@@ -0,0 +1,76 @@
+//! Times rendering, saving, loading and pathfinding on generated maps of growing size:
+//!
+//! ```text
+//! cargo bench
+//! cargo bench -- 20000            # a generated map with this many notes
+//! cargo bench -- ~/maps/big.json  # an existing (unencrypted) map file
+//! ```
+
+use std::{path::Path, process::ExitCode};
+
+use tmmpr::{
+    bench::{MapShape, generate_map, run_map_benchmarks},
+    utils::{MapData, MapFileContents, read_map_file},
+};
+
+/// Note counts of the maps generated when no size or map file is given
+const DEFAULT_SIZES: &[usize] = &[100, 1_000, 8_000];
+/// Runs of each operation
+const RUNS: usize = 10;
+
+fn main() -> ExitCode {
+    // Cargo passes `--bench` to benchmark binaries
+    let args: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .collect();
+
+    let maps: Vec<(String, MapData)> = if args.is_empty() {
+        DEFAULT_SIZES
+            .iter()
+            .map(|&notes| generated(notes))
+            .collect()
+    } else {
+        let mut maps = vec![];
+        for arg in &args {
+            match arg.parse::<usize>() {
+                Ok(notes) => maps.push(generated(notes)),
+                Err(_) => match read_map_file(Path::new(arg)) {
+                    Ok(MapFileContents::Plain(map_data)) => maps.push((arg.clone(), *map_data)),
+                    Ok(MapFileContents::Encrypted(_)) => {
+                        eprintln!("{arg}: encrypted maps can't be benchmarked");
+                        return ExitCode::FAILURE;
+                    }
+                    Err(err) => {
+                        eprintln!("{arg}: {err}");
+                        return ExitCode::FAILURE;
+                    }
+                },
+            }
+        }
+        maps
+    };
+
+    for (name, map_data) in maps {
+        println!(
+            "{name}: {} notes, {} connections",
+            map_data.notes.len(),
+            map_data.connections.len()
+        );
+        match run_map_benchmarks(&map_data, RUNS) {
+            Ok(results) => results.iter().for_each(|result| println!("  {result}")),
+            Err(err) => {
+                eprintln!("  {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn generated(notes: usize) -> (String, MapData) {
+    (
+        format!("generated map ({notes} notes)"),
+        generate_map(MapShape::new(notes)),
+    )
+}
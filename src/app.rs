@@ -1,11 +1,17 @@
 //! Core application state and screen management.
 
-use crate::states::{MapState, SettingsState, StartState};
+use crate::{
+    states::{BackupsState, MapState, SettingsState, StartState},
+    utils::Workspace,
+};
 
 pub struct App {
     /// Set to `false` to exit the main loop.
     pub running: bool,
     pub screen: Screen,
+    /// Directory of maps opened from the Start screen; kept across screens while its
+    /// maps are open.
+    pub workspace: Option<Workspace>,
 }
 
 impl App {
@@ -13,12 +19,23 @@ impl App {
         App {
             running: true,
             screen: Screen::Start(StartState::new()),
+            workspace: None,
         }
     }
 
     pub fn quit(&mut self) {
         self.running = false;
     }
+
+    /// True if the current screen has changed since it was last drawn.
+    pub fn needs_redraw(&self) -> bool {
+        match &self.screen {
+            Screen::Start(start_state) => start_state.needs_clear_and_redraw,
+            Screen::Map(map_state) => map_state.ui_state.needs_clear_and_redraw,
+            Screen::Settings(settings_state) => settings_state.needs_clear_and_redraw,
+            Screen::Backups(backups_state) => backups_state.needs_clear_and_redraw,
+        }
+    }
 }
 
 /// Application screens. Each variant holds its own state to avoid
@@ -28,4 +45,5 @@ pub enum Screen {
     Start(StartState),
     Map(MapState),
     Settings(SettingsState),
+    Backups(BackupsState),
 }
@@ -0,0 +1,108 @@
+use crate::{graph::MapBuilder, utils::MapData};
+
+/// Words the content of generated notes is made of
+const WORDS: &[&str] = &[
+    "plan",
+    "draft",
+    "review",
+    "budget",
+    "launch",
+    "research",
+    "design",
+    "meeting",
+    "notes",
+    "release",
+    "backlog",
+    "idea",
+    "question",
+    "follow up",
+    "deadline",
+    "team",
+];
+
+/// Horizontal and vertical distance between the cells generated notes are placed in
+const CELL_WIDTH: usize = 32;
+const CELL_HEIGHT: usize = 9;
+
+/// Size of a generated map.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct MapShape {
+    pub notes: usize,
+    /// Connections going out of each note (except near the end of the map)
+    pub connections_per_note: usize,
+    /// Maps generated with the same shape and seed are identical
+    pub seed: u64,
+}
+
+impl MapShape {
+    pub fn new(notes: usize) -> Self {
+        Self {
+            notes,
+            connections_per_note: 2,
+            seed: 1,
+        }
+    }
+}
+
+/// Generates a map to benchmark with: notes of one to four lines laid out in a rough
+/// square grid, each connected to the next note and to random notes close by.
+///
+/// Every note can reach the last one, so a path from note 0 to the last note exists.
+pub fn generate_map(shape: MapShape) -> MapData {
+    let mut rng = XorShift::new(shape.seed);
+    let columns = (shape.notes as f64).sqrt().ceil().max(1.0) as usize;
+
+    let mut builder = MapBuilder::new();
+    for id in 0..shape.notes {
+        let lines = 1 + rng.below(4);
+        let content: Vec<String> = (0..lines)
+            .map(|_| {
+                let words = 1 + rng.below(4);
+                (0..words)
+                    .map(|_| WORDS[rng.below(WORDS.len())])
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+        let x = (id % columns) * CELL_WIDTH + rng.below(CELL_WIDTH / 3);
+        let y = (id / columns) * CELL_HEIGHT + rng.below(CELL_HEIGHT / 3);
+        builder = builder.note(content.join("\n")).at(x as isize, y as isize);
+    }
+
+    // Connections stay within about two rows of the grid, like on a real map
+    for from in 0..shape.notes.saturating_sub(1) {
+        builder = builder.connect(from, from + 1);
+        let reach = (2 * columns).min(shape.notes - from - 1);
+        for _ in 1..shape.connections_per_note {
+            builder = builder.connect(from, from + 1 + rng.below(reach));
+        }
+    }
+
+    builder.build()
+}
+
+/// Small deterministic random number generator (xorshift64*), so generated maps don't
+/// depend on a random number crate.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        // Zero would only ever produce zeros
+        Self(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// A number in `0..bound`; 0 for a bound of 0.
+    fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            return 0;
+        }
+        (self.next() % bound as u64) as usize
+    }
+}
@@ -0,0 +1,103 @@
+use ratatui::{Terminal, backend::TestBackend};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+use tempfile::tempdir;
+
+use crate::{
+    graph::algorithms::weighted_shortest_path,
+    states::{MapState, settings::Settings},
+    ui::render_map,
+    utils::{MapData, TmmprError, read_map_file, write_map_data},
+};
+
+/// Size of the terminal maps are rendered to
+const SCREEN_WIDTH: u16 = 200;
+const SCREEN_HEIGHT: u16 = 60;
+
+/// Timings of repeated runs of one operation.
+#[derive(PartialEq, Clone, Debug)]
+pub struct BenchResult {
+    pub name: &'static str,
+    pub runs: usize,
+    pub mean: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl fmt::Display for BenchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<12} {:>4} runs   mean {:>10.3?}   min {:>10.3?}   max {:>10.3?}",
+            self.name, self.runs, self.mean, self.min, self.max
+        )
+    }
+}
+
+/// Runs `operation` `runs` times (at least once) and times each run.
+pub fn measure(name: &'static str, runs: usize, mut operation: impl FnMut()) -> BenchResult {
+    let runs = runs.max(1);
+    let mut timings = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let start = Instant::now();
+        operation();
+        timings.push(start.elapsed());
+    }
+
+    BenchResult {
+        name,
+        runs,
+        mean: timings.iter().sum::<Duration>() / runs as u32,
+        min: timings.iter().copied().min().unwrap_or_default(),
+        max: timings.iter().copied().max().unwrap_or_default(),
+    }
+}
+
+/// Times the operations whose speed matters most on large maps:
+/// * `render` - drawing the map screen, with the view on the middle of the map
+/// * `save` - serializing the map and writing it to a file
+/// * `load` - reading and parsing the saved file
+/// * `pathfinding` - the weighted shortest path from the first to the last note
+///
+/// The map file is written to a temporary directory; settings are the defaults.
+pub fn run_map_benchmarks(map_data: &MapData, runs: usize) -> Result<Vec<BenchResult>, TmmprError> {
+    let dir = tempdir()?;
+    let path = dir.path().join("bench.json");
+    let mut results = vec![];
+
+    let mut map_state = MapState::new_with_settings(path.clone(), Settings::new());
+    map_state.load_map_data(map_data.clone());
+    map_state.viewport.screen_width = SCREEN_WIDTH as usize;
+    map_state.viewport.screen_height = SCREEN_HEIGHT as usize;
+    if let Some(middle) = map_data.notes.get(&(map_data.notes.len() / 2)) {
+        map_state.viewport.center_on(middle.x, middle.y);
+    }
+    let Ok(mut terminal) = Terminal::new(TestBackend::new(SCREEN_WIDTH, SCREEN_HEIGHT));
+    results.push(measure("render", runs, || {
+        map_state.clear_and_redraw();
+        let _ = terminal.draw(|frame| render_map(frame, &mut map_state));
+    }));
+
+    let mut buffer = vec![];
+    let mut saved = Ok(());
+    results.push(measure("save", runs, || {
+        saved = write_map_data(&path, map_data, false, &mut buffer);
+    }));
+    saved?;
+
+    let mut loaded = Ok(());
+    results.push(measure("load", runs, || {
+        loaded = read_map_file(&path).map(|_| ());
+    }));
+    loaded?;
+
+    let graph = map_data.graph();
+    let last = map_data.notes.keys().max().copied().unwrap_or_default();
+    results.push(measure("pathfinding", runs, || {
+        let _ = weighted_shortest_path(&graph, 0, last);
+    }));
+
+    Ok(results)
+}
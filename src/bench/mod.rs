@@ -0,0 +1,10 @@
+//! Benchmarks of rendering, saving, loading and pathfinding on large generated maps,
+//! run with `cargo bench` (see `benches/map.rs`).
+
+mod generator;
+mod harness;
+#[cfg(test)]
+mod tests;
+
+pub use generator::*;
+pub use harness::*;
@@ -0,0 +1,48 @@
+use crate::{
+    bench::{MapShape, generate_map},
+    graph::algorithms::shortest_path,
+};
+
+#[test]
+fn test_generated_map_has_the_requested_size() {
+    let map = generate_map(MapShape::new(500));
+
+    assert_eq!(map.notes.len(), 500);
+    assert_eq!(map.next_note_id_counter, 500);
+    // Two per note, fewer where random ones repeat or near the end of the map
+    assert!(map.connections.len() > 800 && map.connections.len() <= 998);
+    assert!(map.notes.values().all(|note| !note.content.is_empty()));
+}
+
+#[test]
+fn test_same_seed_generates_the_same_map() {
+    let shape = MapShape::new(200);
+
+    assert_eq!(generate_map(shape), generate_map(shape));
+    assert_ne!(
+        generate_map(shape),
+        generate_map(MapShape { seed: 2, ..shape })
+    );
+}
+
+#[test]
+fn test_last_note_is_reachable() {
+    let map = generate_map(MapShape {
+        notes: 300,
+        connections_per_note: 1,
+        seed: 7,
+    });
+
+    let path = shortest_path(&map.graph(), 0, 299).unwrap();
+    assert_eq!(path.first(), Some(&0));
+    assert_eq!(path.last(), Some(&299));
+}
+
+#[test]
+fn test_tiny_maps() {
+    assert!(generate_map(MapShape::new(0)).notes.is_empty());
+
+    let map = generate_map(MapShape::new(1));
+    assert_eq!(map.notes.len(), 1);
+    assert!(map.connections.is_empty());
+}
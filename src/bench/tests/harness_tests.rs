@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use crate::bench::{MapShape, generate_map, measure, run_map_benchmarks};
+
+#[test]
+fn test_measure_times_every_run() {
+    let mut calls = 0;
+    let result = measure("count", 5, || calls += 1);
+
+    assert_eq!(calls, 5);
+    assert_eq!(result.runs, 5);
+    assert!(result.min <= result.mean && result.mean <= result.max);
+
+    // Always runs at least once
+    let result = measure("once", 0, || {});
+    assert_eq!(result.runs, 1);
+    assert!(result.max < Duration::from_secs(1));
+}
+
+#[test]
+fn test_run_map_benchmarks() {
+    let map = generate_map(MapShape::new(50));
+
+    let results = run_map_benchmarks(&map, 2).unwrap();
+
+    let names: Vec<&str> = results.iter().map(|result| result.name).collect();
+    assert_eq!(names, vec!["render", "save", "load", "pathfinding"]);
+    assert!(results.iter().all(|result| result.runs == 2));
+    assert!(results[0].to_string().starts_with("render"));
+}
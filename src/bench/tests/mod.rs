@@ -0,0 +1,2 @@
+mod generator_tests;
+mod harness_tests;
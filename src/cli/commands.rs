@@ -0,0 +1,243 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    graph::algorithms::graph_stats,
+    states::map::{ExportFormat, LayersState, SearchState},
+    utils::{
+        MapData, MapFileContents, TmmprError, export_dot, export_freemind, export_html,
+        export_mermaid, export_opml, export_svg, map_name, map_schema, read_map_file,
+        repair_map_data, search_notes, unlock_map_data, verify_map_checksum,
+    },
+};
+
+/// Environment variable holding the passphrase of encrypted map files
+pub const PASSPHRASE_VAR: &str = "TMMPR_PASSPHRASE";
+
+/// Printed for `tmmpr --help` and after invalid arguments.
+pub const USAGE: &str = "\
+Usage: tmmpr                                       open the terminal UI
+       tmmpr export --format <format> <map> [-o <path>]
+                                                   write the map in another format
+       tmmpr stats [--json] <map>                  print note and connection counts
+       tmmpr search <pattern> <map>...             print the notes matching a pattern
+       tmmpr check <map>...                        report maps that don't load
+       tmmpr schema                                print the JSON Schema of map files
+
+Formats: mermaid, svg, opml, freemind, html, dot
+Encrypted maps are opened with the passphrase in TMMPR_PASSPHRASE.";
+
+/// A command given on the command line.
+#[derive(PartialEq, Debug)]
+pub enum CliCommand {
+    /// `export --format <format> <map> [-o <path>]`: writes the map in another format,
+    /// to standard output without a path
+    Export {
+        format: ExportFormat,
+        map: PathBuf,
+        output: Option<PathBuf>,
+    },
+    /// `stats [--json] <map>`: prints the numbers of the statistics overlay (`i`)
+    Stats { map: PathBuf, json: bool },
+    /// `search <pattern> <map>...`: prints the first matching line of every note
+    /// matching the pattern, like `:grep`
+    Search { pattern: String, maps: Vec<PathBuf> },
+    /// `check <map>...`: reads the maps, reporting where the first one that doesn't
+    /// load is invalid, or that it doesn't match its checksum
+    Check { maps: Vec<PathBuf> },
+    /// `schema`: prints the JSON Schema of map files, see [`map_schema`]
+    Schema,
+    /// `--help`
+    Help,
+}
+
+/// Parses the command line arguments, without the program name. None starts the TUI;
+/// invalid arguments are reported with the reason.
+pub fn parse_args(args: &[String]) -> Result<Option<CliCommand>, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(None);
+    };
+
+    match command.as_str() {
+        "-h" | "--help" | "help" => Ok(Some(CliCommand::Help)),
+        "export" => {
+            let mut format = None;
+            let mut output = None;
+            let mut map = None;
+            let mut rest = rest.iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "-f" | "--format" => {
+                        let name = rest.next().ok_or("--format needs a format")?;
+                        format = Some(
+                            ExportFormat::from_name(name)
+                                .ok_or(format!("unknown format: {name}"))?,
+                        );
+                    }
+                    "-o" | "--output" => {
+                        output = Some(PathBuf::from(rest.next().ok_or("--output needs a path")?));
+                    }
+                    _ if map.is_none() => map = Some(PathBuf::from(arg)),
+                    _ => return Err(format!("unexpected argument: {arg}")),
+                }
+            }
+            Ok(Some(CliCommand::Export {
+                format: format.ok_or("export needs --format")?,
+                map: map.ok_or("export needs a map file")?,
+                output,
+            }))
+        }
+        "stats" => match rest {
+            [map] => Ok(Some(CliCommand::Stats {
+                map: PathBuf::from(map),
+                json: false,
+            })),
+            [flag, map] | [map, flag] if flag == "--json" => Ok(Some(CliCommand::Stats {
+                map: PathBuf::from(map),
+                json: true,
+            })),
+            _ => Err(String::from("stats needs a map file")),
+        },
+        "search" => match rest {
+            [pattern, maps @ ..] if !maps.is_empty() => Ok(Some(CliCommand::Search {
+                pattern: pattern.clone(),
+                maps: maps.iter().map(PathBuf::from).collect(),
+            })),
+            _ => Err(String::from("search needs a pattern and a map file")),
+        },
+        "check" if !rest.is_empty() => Ok(Some(CliCommand::Check {
+            maps: rest.iter().map(PathBuf::from).collect(),
+        })),
+        "check" => Err(String::from("check needs a map file")),
+        "schema" if rest.is_empty() => Ok(Some(CliCommand::Schema)),
+        _ => Err(format!("unknown command: {command}")),
+    }
+}
+
+/// Runs a command, writing its output to `out`. Returns the exit status: 0, or 1 for
+/// a search that found nothing.
+pub fn run_cli(
+    command: &CliCommand,
+    out: &mut dyn Write,
+    passphrase: Option<&str>,
+) -> Result<u8, TmmprError> {
+    match command {
+        CliCommand::Help => writeln!(out, "{USAGE}")?,
+        CliCommand::Export {
+            format,
+            map,
+            output,
+        } => {
+            let map_data = read_map_data(map, passphrase)?;
+            let document = export_map(&map_data, *format, &map_name(map));
+            match output {
+                Some(path) => std::fs::write(path, document)?,
+                None => out.write_all(document.as_bytes())?,
+            }
+        }
+        CliCommand::Stats { map, json } => {
+            let stats = graph_stats(&read_map_data(map, passphrase)?.graph());
+            let fields = [
+                ("notes", stats.node_count),
+                ("connections", stats.edge_count),
+                ("orphans", stats.orphans.len()),
+                ("leaves", stats.leaves.len()),
+                ("max_depth", stats.max_depth),
+                ("groups", stats.component_count),
+            ];
+            if *json {
+                let object: serde_json::Map<String, serde_json::Value> = fields
+                    .iter()
+                    .map(|&(name, value)| (name.to_string(), value.into()))
+                    .collect();
+                writeln!(out, "{}", serde_json::Value::Object(object))?;
+            } else {
+                for (name, value) in fields {
+                    writeln!(out, "{}: {value}", name.replace('_', " "))?;
+                }
+            }
+        }
+        CliCommand::Search { pattern, maps } => {
+            let search = SearchState::with_query(pattern);
+            if pattern.is_empty() || search.is_invalid() {
+                return Err(TmmprError::Validation(format!(
+                    "invalid pattern: {pattern}"
+                )));
+            }
+
+            let mut found = false;
+            for map in maps {
+                let map_data = read_map_data(map, passphrase)?;
+                let layers = LayersState::from_layers(map_data.layers);
+                for search_match in search_notes(map, &search, &map_data.notes, &layers) {
+                    found = true;
+                    writeln!(
+                        out,
+                        "{}:{}: {}",
+                        map.display(),
+                        search_match.note_id,
+                        search_match.line
+                    )?;
+                }
+            }
+            return Ok(if found { 0 } else { 1 });
+        }
+        CliCommand::Check { maps } => {
+            for map in maps {
+                let mut map_data = read_map_data(map, passphrase)
+                    .map_err(|err| TmmprError::Validation(format!("{}: {err}", map.display())))?;
+                let repairs = repair_map_data(&mut map_data);
+                if !repairs.is_empty() {
+                    return Err(TmmprError::Validation(format!(
+                        "{}: {}",
+                        map.display(),
+                        repairs.join("; ")
+                    )));
+                }
+                if !verify_map_checksum(map)? {
+                    return Err(TmmprError::Validation(format!(
+                        "{}: doesn't match the checksum saved with it, it may be incomplete or changed by another program",
+                        map.display()
+                    )));
+                }
+                writeln!(out, "{}: ok", map.display())?;
+            }
+        }
+        CliCommand::Schema => out.write_all(map_schema().as_bytes())?,
+    }
+    Ok(0)
+}
+
+/// Reads a map file, decrypting it with the passphrase if it's encrypted.
+fn read_map_data(path: &Path, passphrase: Option<&str>) -> Result<MapData, TmmprError> {
+    match read_map_file(path)? {
+        MapFileContents::Plain(map_data) => Ok(*map_data),
+        MapFileContents::Encrypted(encrypted) => {
+            let passphrase = passphrase.ok_or_else(|| {
+                TmmprError::Validation(format!(
+                    "{} is encrypted, set {PASSPHRASE_VAR} to open it",
+                    path.display()
+                ))
+            })?;
+            let (map_data, _) = unlock_map_data(&encrypted, passphrase).map_err(|_| {
+                TmmprError::Validation(format!("wrong passphrase for {}", path.display()))
+            })?;
+            Ok(map_data)
+        }
+    }
+}
+
+/// The whole map in the format, as `:export` writes it.
+fn export_map(map_data: &MapData, format: ExportFormat, title: &str) -> String {
+    let (notes, connections) = (&map_data.notes, &map_data.connections);
+    match format {
+        ExportFormat::Mermaid => export_mermaid(notes, connections),
+        ExportFormat::Svg => export_svg(notes, connections),
+        ExportFormat::Opml => export_opml(title, notes, connections),
+        ExportFormat::FreeMind => export_freemind(title, notes, connections),
+        ExportFormat::Html => export_html(title, notes, connections),
+        ExportFormat::Dot => export_dot(title, notes, connections),
+    }
+}
@@ -0,0 +1,8 @@
+//! Commands run from the shell without starting the TUI, for scripts and CI:
+//! `tmmpr export`, `tmmpr stats` and `tmmpr search`.
+
+mod commands;
+#[cfg(test)]
+mod tests;
+
+pub use commands::*;
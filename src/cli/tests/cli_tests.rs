@@ -0,0 +1,251 @@
+use std::path::{Path, PathBuf};
+
+use tempfile::tempdir;
+
+use crate::{
+    cli::{CliCommand, parse_args, run_cli},
+    graph::MapBuilder,
+    states::map::ExportFormat,
+    utils::{FileKey, TmmprError, map_schema, write_encrypted_map_data, write_map_data},
+};
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+}
+
+fn write_test_map(path: &Path) {
+    let map_data = MapBuilder::new()
+        .note("Plan the launch")
+        .note("Build")
+        .note("Ship")
+        .note("Loose end")
+        .connect(0, 1)
+        .connect(1, 2)
+        .build();
+    write_map_data(path, &map_data, false, &mut vec![]).unwrap();
+}
+
+fn run(command: &CliCommand, passphrase: Option<&str>) -> (Result<u8, TmmprError>, String) {
+    let mut out = vec![];
+    let result = run_cli(command, &mut out, passphrase);
+    (result, String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn test_parse_args() {
+    assert_eq!(parse_args(&[]), Ok(None));
+    assert_eq!(parse_args(&args("--help")), Ok(Some(CliCommand::Help)));
+    assert_eq!(
+        parse_args(&args("export --format dot map.json")),
+        Ok(Some(CliCommand::Export {
+            format: ExportFormat::Dot,
+            map: PathBuf::from("map.json"),
+            output: None,
+        }))
+    );
+    assert_eq!(
+        parse_args(&args("export map.json -o out.mmd -f mermaid")),
+        Ok(Some(CliCommand::Export {
+            format: ExportFormat::Mermaid,
+            map: PathBuf::from("map.json"),
+            output: Some(PathBuf::from("out.mmd")),
+        }))
+    );
+    assert_eq!(
+        parse_args(&args("stats map.json --json")),
+        Ok(Some(CliCommand::Stats {
+            map: PathBuf::from("map.json"),
+            json: true,
+        }))
+    );
+    assert_eq!(
+        parse_args(&args("search term a.json b.json")),
+        Ok(Some(CliCommand::Search {
+            pattern: String::from("term"),
+            maps: vec![PathBuf::from("a.json"), PathBuf::from("b.json")],
+        }))
+    );
+
+    for invalid in [
+        "export map.json",
+        "export --format png map.json",
+        "export --format dot",
+        "export --format dot a.json b.json",
+        "stats",
+        "stats a.json b.json",
+        "search term",
+        "check",
+        "schema map.json",
+        "map.json",
+    ] {
+        assert!(parse_args(&args(invalid)).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn test_export_and_stats() {
+    let dir = tempdir().unwrap();
+    let map = dir.path().join("launch.json");
+    write_test_map(&map);
+
+    let (result, out) = run(
+        &CliCommand::Export {
+            format: ExportFormat::Dot,
+            map: map.clone(),
+            output: None,
+        },
+        None,
+    );
+    assert_eq!(result.unwrap(), 0);
+    assert!(out.starts_with("digraph \"launch\" {\n"), "{out}");
+    assert!(out.contains("    n0 -> n1;\n"), "{out}");
+
+    let output = dir.path().join("launch.mmd");
+    let (result, out) = run(
+        &CliCommand::Export {
+            format: ExportFormat::Mermaid,
+            map: map.clone(),
+            output: Some(output.clone()),
+        },
+        None,
+    );
+    assert_eq!(result.unwrap(), 0);
+    assert_eq!(out, "");
+    assert!(
+        std::fs::read_to_string(&output)
+            .unwrap()
+            .starts_with("flowchart TD\n")
+    );
+
+    let (result, out) = run(
+        &CliCommand::Stats {
+            map: map.clone(),
+            json: false,
+        },
+        None,
+    );
+    assert_eq!(result.unwrap(), 0);
+    assert_eq!(
+        out,
+        "notes: 4\nconnections: 2\norphans: 1\nleaves: 1\nmax depth: 2\ngroups: 2\n"
+    );
+
+    let (_, out) = run(&CliCommand::Stats { map, json: true }, None);
+    let stats: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(stats["connections"], 2);
+    assert_eq!(stats["max_depth"], 2);
+}
+
+#[test]
+fn test_search() {
+    let dir = tempdir().unwrap();
+    let map = dir.path().join("launch.json");
+    write_test_map(&map);
+    let search = |pattern: &str| CliCommand::Search {
+        pattern: pattern.to_string(),
+        maps: vec![map.clone()],
+    };
+
+    let (result, out) = run(&search("^(plan|ship)"), None);
+    assert_eq!(result.unwrap(), 0);
+    assert_eq!(
+        out,
+        format!("{0}:0: Plan the launch\n{0}:2: Ship\n", map.display())
+    );
+
+    let (result, out) = run(&search("deploy"), None);
+    assert_eq!(result.unwrap(), 1);
+    assert_eq!(out, "");
+
+    assert!(matches!(
+        run(&search("(unclosed"), None).0,
+        Err(TmmprError::Validation(_))
+    ));
+}
+
+#[test]
+fn test_encrypted_map_needs_passphrase() {
+    let dir = tempdir().unwrap();
+    let map = dir.path().join("secret.json");
+    let map_data = MapBuilder::new().note("Hidden").build();
+    write_encrypted_map_data(&map, &map_data, &FileKey::new("hunter2"), &mut vec![]).unwrap();
+    let stats = CliCommand::Stats { map, json: false };
+
+    assert!(matches!(
+        run(&stats, None).0,
+        Err(TmmprError::Validation(_))
+    ));
+    assert!(matches!(
+        run(&stats, Some("wrong")).0,
+        Err(TmmprError::Validation(_))
+    ));
+    let (result, out) = run(&stats, Some("hunter2"));
+    assert_eq!(result.unwrap(), 0);
+    assert!(out.starts_with("notes: 1\n"), "{out}");
+}
+
+#[test]
+fn test_check_and_schema() {
+    let dir = tempdir().unwrap();
+    let map = dir.path().join("launch.json");
+    write_test_map(&map);
+    let broken = dir.path().join("broken.json");
+    std::fs::write(&broken, r#"{"view_pos": {"x": "left", "y": 0}}"#).unwrap();
+
+    let (result, out) = run(
+        &CliCommand::Check {
+            maps: vec![map.clone()],
+        },
+        None,
+    );
+    assert_eq!(result.unwrap(), 0);
+    assert_eq!(out, format!("{}: ok\n", map.display()));
+
+    let (result, _) = run(
+        &CliCommand::Check {
+            maps: vec![map.clone(), broken.clone()],
+        },
+        None,
+    );
+    let message = result.unwrap_err().to_string();
+    assert!(
+        message.starts_with(&format!(
+            "{}: invalid file contents at `view_pos.x`",
+            broken.display()
+        )),
+        "{message}"
+    );
+
+    // A map changed without updating its checksum
+    let map_json = std::fs::read_to_string(&map).unwrap();
+    std::fs::write(&map, map_json.replace("Loose end", "Loose ends")).unwrap();
+    let (result, _) = run(&CliCommand::Check { maps: vec![map] }, None);
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("doesn't match the checksum saved with it")
+    );
+
+    // A map stacking a note that isn't there
+    let dangling = dir.path().join("dangling.json");
+    let mut map_data = MapBuilder::new().note("Alone").build();
+    map_data.render_order.push(3);
+    std::fs::write(&dangling, serde_json::to_string(&map_data).unwrap()).unwrap();
+    let (result, _) = run(
+        &CliCommand::Check {
+            maps: vec![dangling],
+        },
+        None,
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .ends_with("render_order: lists note 3, which doesn't exist")
+    );
+
+    let (result, out) = run(&CliCommand::Schema, None);
+    assert_eq!(result.unwrap(), 0);
+    assert_eq!(out, map_schema());
+}
@@ -0,0 +1,379 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    fmt,
+};
+
+use crate::graph::Graph;
+
+/// The graph has a cycle, so its nodes have no topological order.
+#[derive(PartialEq, Clone, Debug)]
+pub struct CycleError {
+    /// Ids of the nodes on one cycle, in edge order. The last node has an edge back to the first.
+    pub cycle: Vec<usize>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path: Vec<String> = self
+            .cycle
+            .iter()
+            .chain(self.cycle.first())
+            .map(usize::to_string)
+            .collect();
+        write!(f, "graph contains a cycle: {}", path.join(" -> "))
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+/// Returns true if following edges from some node can lead back to it (self-loops included).
+pub fn has_cycle<T>(graph: &Graph<T>) -> bool {
+    find_cycle(graph).is_some()
+}
+
+/// Finds one cycle in the graph, returning its node ids in edge order.
+///
+/// Nodes are searched in ascending id order, so the result is deterministic.
+pub fn find_cycle<T>(graph: &Graph<T>) -> Option<Vec<usize>> {
+    let mut finished: HashSet<usize> = HashSet::new();
+
+    for start in graph.node_ids() {
+        if finished.contains(&start) {
+            continue;
+        }
+
+        // Current DFS path: (node, index of the next neighbor to visit)
+        let mut path: Vec<(usize, usize)> = vec![(start, 0)];
+        let mut on_path: HashSet<usize> = HashSet::from([start]);
+
+        while let Some((node, next)) = path.last_mut() {
+            let node = *node;
+            let Some(&neighbor) = graph.neighbors(node).get(*next) else {
+                path.pop();
+                on_path.remove(&node);
+                finished.insert(node);
+                continue;
+            };
+            *next += 1;
+
+            if on_path.contains(&neighbor) {
+                let cycle_start = path.iter().position(|&(id, _)| id == neighbor)?;
+                return Some(path[cycle_start..].iter().map(|&(id, _)| id).collect());
+            }
+            if !finished.contains(&neighbor) {
+                path.push((neighbor, 0));
+                on_path.insert(neighbor);
+            }
+        }
+    }
+
+    None
+}
+
+/// Orders the nodes so every edge points from an earlier node to a later one.
+///
+/// When several nodes are ready at once, the one with the smallest id comes first,
+/// so the order is deterministic.
+///
+/// # Errors
+/// Returns a [`CycleError`] holding one of the cycles if the graph isn't acyclic.
+pub fn topological_sort<T>(graph: &Graph<T>) -> Result<Vec<usize>, CycleError> {
+    let mut in_degrees: HashMap<usize, usize> = graph
+        .node_ids()
+        .into_iter()
+        .map(|id| (id, graph.predecessors(id).len()))
+        .collect();
+
+    let mut ready: BinaryHeap<Reverse<usize>> = in_degrees
+        .iter()
+        .filter(|&(_, &degree)| degree == 0)
+        .map(|(&id, _)| Reverse(id))
+        .collect();
+
+    let mut order = Vec::with_capacity(graph.node_count());
+    while let Some(Reverse(id)) = ready.pop() {
+        order.push(id);
+        for &next in graph.neighbors(id) {
+            if let Some(degree) = in_degrees.get_mut(&next) {
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push(Reverse(next));
+                }
+            }
+        }
+    }
+
+    if order.len() < graph.node_count() {
+        return Err(CycleError {
+            cycle: find_cycle(graph).unwrap_or_default(),
+        });
+    }
+
+    Ok(order)
+}
+
+/// Splits the graph into strongly connected components: maximal groups of nodes
+/// that can all reach each other. Every node belongs to exactly one component.
+///
+/// Ids within a component are in ascending order. Components come in reverse
+/// topological order: no component has an edge to a component listed after it.
+pub fn strongly_connected_components<T>(graph: &Graph<T>) -> Vec<Vec<usize>> {
+    // Tarjan's algorithm, iterative so deep graphs can't overflow the call stack
+    let mut indices: HashMap<usize, usize> = HashMap::new();
+    let mut low_links: HashMap<usize, usize> = HashMap::new();
+    let mut stack: Vec<usize> = Vec::new();
+    let mut on_stack: HashSet<usize> = HashSet::new();
+    let mut components = Vec::new();
+
+    for start in graph.node_ids() {
+        if indices.contains_key(&start) {
+            continue;
+        }
+
+        // (node, index of the next neighbor to visit)
+        let mut calls: Vec<(usize, usize)> = Vec::new();
+        let mut discovered = Some(start);
+        loop {
+            if let Some(id) = discovered.take() {
+                let index = indices.len();
+                indices.insert(id, index);
+                low_links.insert(id, index);
+                stack.push(id);
+                on_stack.insert(id);
+                calls.push((id, 0));
+            }
+            let Some((node, next)) = calls.last_mut() else {
+                break;
+            };
+            let node = *node;
+
+            if let Some(&neighbor) = graph.neighbors(node).get(*next) {
+                *next += 1;
+                if !indices.contains_key(&neighbor) {
+                    discovered = Some(neighbor);
+                } else if on_stack.contains(&neighbor) {
+                    let low = low_links[&node].min(indices[&neighbor]);
+                    low_links.insert(node, low);
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                let low = low_links[&parent].min(low_links[&node]);
+                low_links.insert(parent, low);
+            }
+
+            // Node is the root of a component: everything above it on the stack belongs to it
+            if low_links[&node] == indices[&node] {
+                let mut component = Vec::new();
+                while let Some(id) = stack.pop() {
+                    on_stack.remove(&id);
+                    component.push(id);
+                    if id == node {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
+
+/// Finds a path with the fewest edges from `from` to `to`, following edge direction.
+///
+/// Returns the node ids along the path, both ends included, or None if `to` can't be
+/// reached. A node's path to itself is just that node.
+pub fn shortest_path<T>(graph: &Graph<T>, from: usize, to: usize) -> Option<Vec<usize>> {
+    if !graph.contains(from) || !graph.contains(to) {
+        return None;
+    }
+
+    // Node each visited node was first reached from
+    let mut parents: HashMap<usize, usize> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    let mut visited = HashSet::from([from]);
+
+    while let Some(id) = queue.pop_front() {
+        if id == to {
+            let mut path = vec![to];
+            while let Some(&parent) = parents.get(path.last()?) {
+                path.push(parent);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for &next in graph.neighbors(id) {
+            if visited.insert(next) {
+                parents.insert(next, id);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds the path with the smallest total edge weight from `from` to `to`, following
+/// edge direction (Dijkstra's algorithm).
+///
+/// Returns the node ids along the path, both ends included, and its total weight, or
+/// None if `to` can't be reached. Nodes are settled in (distance, id) order, so ties
+/// between equally light paths are broken deterministically.
+pub fn weighted_shortest_path<T>(
+    graph: &Graph<T>,
+    from: usize,
+    to: usize,
+) -> Option<(Vec<usize>, u64)> {
+    if !graph.contains(from) || !graph.contains(to) {
+        return None;
+    }
+
+    let mut distances: HashMap<usize, u64> = HashMap::from([(from, 0)]);
+    // Node each settled node was reached from on its lightest path
+    let mut parents: HashMap<usize, usize> = HashMap::new();
+    let mut settled: HashSet<usize> = HashSet::new();
+    let mut queue = BinaryHeap::from([Reverse((0, from))]);
+
+    while let Some(Reverse((distance, id))) = queue.pop() {
+        if !settled.insert(id) {
+            continue;
+        }
+        if id == to {
+            let mut path = vec![to];
+            while let Some(&parent) = parents.get(path.last()?) {
+                path.push(parent);
+            }
+            path.reverse();
+            return Some((path, distance));
+        }
+
+        for &next in graph.neighbors(id) {
+            let weight = graph.edge_weight(id, next).unwrap_or(1);
+            let candidate = distance + u64::from(weight);
+            if distances.get(&next).is_none_or(|&best| candidate < best) {
+                distances.insert(next, candidate);
+                parents.insert(next, id);
+                queue.push(Reverse((candidate, next)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Ids of every node that can be reached from `id` by following edges, `id` included,
+/// in ascending order. Empty if `id` isn't in the graph.
+pub fn reachable_from<T>(graph: &Graph<T>, id: usize) -> Vec<usize> {
+    let mut reachable: Vec<usize> = graph.bfs(id).collect();
+    reachable.sort_unstable();
+    reachable
+}
+
+/// Ids of nodes with no edges in or out, in ascending order.
+pub fn orphans<T>(graph: &Graph<T>) -> Vec<usize> {
+    graph
+        .node_ids()
+        .into_iter()
+        .filter(|&id| graph.neighbors(id).is_empty() && graph.predecessors(id).is_empty())
+        .collect()
+}
+
+/// Ids of nodes that have incoming edges but no outgoing ones, in ascending order.
+pub fn leaves<T>(graph: &Graph<T>) -> Vec<usize> {
+    graph
+        .node_ids()
+        .into_iter()
+        .filter(|&id| graph.neighbors(id).is_empty() && !graph.predecessors(id).is_empty())
+        .collect()
+}
+
+/// Splits the graph into groups of nodes linked by edges in either direction.
+///
+/// Ids within a component are in ascending order and components are ordered by
+/// their smallest id. Orphans form components of their own.
+pub fn weakly_connected_components<T>(graph: &Graph<T>) -> Vec<Vec<usize>> {
+    let mut seen: HashSet<usize> = HashSet::new();
+    let mut components = Vec::new();
+
+    for start in graph.node_ids() {
+        if !seen.insert(start) {
+            continue;
+        }
+
+        let mut component = vec![];
+        let mut stack = vec![start];
+        while let Some(id) = stack.pop() {
+            component.push(id);
+            for &next in graph.neighbors(id).iter().chain(graph.predecessors(id)) {
+                if seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        component.sort_unstable();
+        components.push(component);
+    }
+
+    components
+}
+
+/// Number of edges on the longest chain of nodes, following edge direction.
+///
+/// Nodes on a cycle can all reach each other, so a cycle counts as a single step
+/// of the chain. An empty graph or one without edges has depth 0.
+pub fn max_depth<T>(graph: &Graph<T>) -> usize {
+    let components = strongly_connected_components(graph);
+    let component_of: HashMap<usize, usize> = components
+        .iter()
+        .enumerate()
+        .flat_map(|(index, ids)| ids.iter().map(move |&id| (id, index)))
+        .collect();
+
+    // Components come sinks first, so every successor's depth is known when it's needed
+    let mut depths: Vec<usize> = vec![0; components.len()];
+    for (index, ids) in components.iter().enumerate() {
+        depths[index] = ids
+            .iter()
+            .flat_map(|&id| graph.neighbors(id))
+            .map(|next| component_of[next])
+            .filter(|&next| next != index)
+            .map(|next| depths[next] + 1)
+            .max()
+            .unwrap_or(0);
+    }
+
+    depths.into_iter().max().unwrap_or(0)
+}
+
+/// Summary numbers of a graph's structure, see [`graph_stats`].
+#[derive(PartialEq, Clone, Debug)]
+pub struct GraphStats {
+    pub node_count: usize,
+    pub edge_count: usize,
+    /// See [`orphans`]
+    pub orphans: Vec<usize>,
+    /// See [`leaves`]
+    pub leaves: Vec<usize>,
+    /// See [`max_depth`]
+    pub max_depth: usize,
+    /// Number of [`weakly_connected_components`]
+    pub component_count: usize,
+}
+
+/// Collects node and edge counts, orphans, leaves, depth and component count in one go.
+pub fn graph_stats<T>(graph: &Graph<T>) -> GraphStats {
+    GraphStats {
+        node_count: graph.node_count(),
+        edge_count: graph.edge_count(),
+        orphans: orphans(graph),
+        leaves: leaves(graph),
+        max_depth: max_depth(graph),
+        component_count: weakly_connected_components(graph).len(),
+    }
+}
@@ -0,0 +1,108 @@
+use ratatui::style::Color;
+use std::collections::HashSet;
+
+use crate::{
+    graph::Graph,
+    states::map::{Connection, Note, Side, ViewPos},
+    utils::MapData,
+};
+
+/// Horizontal distance between notes that weren't given a position with [`MapBuilder::at`].
+const DEFAULT_SPACING: isize = 20;
+
+/// Builds map data in code, without a `MapState` or any file on disk.
+///
+/// Notes get ids in the order they are added, starting at 0. A note without an explicit
+/// position is placed in a row, [`DEFAULT_SPACING`] cells to the right of the previous one.
+///
+/// ```
+/// use tmmpr::graph::MapBuilder;
+///
+/// let map = MapBuilder::new()
+///     .note("build")
+///     .note("deploy")
+///     .at(40, 5)
+///     .connect(0, 1)
+///     .build();
+///
+/// assert_eq!(map.notes.len(), 2);
+/// assert_eq!(map.notes[&1].x, 40);
+/// assert_eq!(map.graph().neighbors(0), &[1]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MapBuilder {
+    notes: Vec<Note>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl MapBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a note with the given content; its id is the number of notes added before it.
+    pub fn note(mut self, content: impl Into<String>) -> Self {
+        let x = self.notes.last().map_or(0, |note| note.x + DEFAULT_SPACING);
+        let y = self.notes.last().map_or(0, |note| note.y);
+        self.notes
+            .push(Note::new(x, y, content.into(), Color::White));
+        self
+    }
+
+    /// Moves the most recently added note to (`x`, `y`). Does nothing before the first note.
+    pub fn at(mut self, x: isize, y: isize) -> Self {
+        if let Some(note) = self.notes.last_mut() {
+            note.x = x;
+            note.y = y;
+        }
+        self
+    }
+
+    /// Connects note `from` to note `to`. Connections to ids that were never added and
+    /// repeats of an earlier connection are left out of the built map.
+    pub fn connect(mut self, from: usize, to: usize) -> Self {
+        self.edges.push((from, to));
+        self
+    }
+
+    /// Produces map data ready to be saved or loaded, with the view at the origin.
+    pub fn build(self) -> MapData {
+        let count = self.notes.len();
+
+        let mut connections: Vec<Connection> = Vec::new();
+        let mut added = HashSet::new();
+        for (from, to) in self.edges {
+            if from >= count || to >= count || !added.insert((from, to)) {
+                continue;
+            }
+            connections.push(Connection {
+                from_id: from,
+                from_side: Side::Right,
+                to_id: to,
+                to_side: Side::Left,
+                color: Color::White,
+                waypoints: vec![],
+                weight: None,
+                layer: None,
+            });
+        }
+
+        MapData {
+            view_pos: ViewPos::new(),
+            next_note_id_counter: count,
+            notes: self.notes.into_iter().enumerate().collect(),
+            render_order: (0..count).collect(),
+            connections,
+            regions: vec![],
+            hyperedges: vec![],
+            layers: vec![],
+            view_state: None,
+            trash: vec![],
+        }
+    }
+
+    /// Produces the graph of the built map, see [`MapData::graph`].
+    pub fn build_graph(self) -> Graph<String> {
+        self.build().graph()
+    }
+}
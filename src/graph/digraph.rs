@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use crate::graph::{Bfs, Dfs, Node};
+
+/// A directed graph of positioned nodes.
+///
+/// Nodes are identified by `usize` ids chosen by the caller (for maps these are the
+/// note ids). Edges are directed and weighted (1 unless given); parallel edges between
+/// the same pair of nodes are stored once.
+///
+/// # Type Parameters
+/// * `T` - The type of data stored in each node
+#[derive(PartialEq, Clone, Debug)]
+pub struct Graph<T> {
+    nodes: HashMap<usize, Node<T>>,
+    /// Outgoing edges, in insertion order
+    outgoing: HashMap<usize, Vec<usize>>,
+    /// Incoming edges, in insertion order
+    incoming: HashMap<usize, Vec<usize>>,
+    /// Weight of each (from, to) edge
+    weights: HashMap<(usize, usize), u32>,
+}
+
+impl<T> Default for Graph<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Graph<T> {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self {
+            nodes: HashMap::new(),
+            outgoing: HashMap::new(),
+            incoming: HashMap::new(),
+            weights: HashMap::new(),
+        }
+    }
+
+    /// Inserts a node, replacing any node that already has this id.
+    pub fn add_node(&mut self, id: usize, node: Node<T>) {
+        self.nodes.insert(id, node);
+    }
+
+    /// Adds a directed edge with weight 1. Returns false (and adds nothing) if either
+    /// endpoint isn't in the graph or the edge already exists.
+    pub fn add_edge(&mut self, from: usize, to: usize) -> bool {
+        self.add_weighted_edge(from, to, 1)
+    }
+
+    /// Adds a directed edge with the given weight. Returns false (and adds nothing) if
+    /// either endpoint isn't in the graph or the edge already exists.
+    pub fn add_weighted_edge(&mut self, from: usize, to: usize, weight: u32) -> bool {
+        if !self.nodes.contains_key(&from) || !self.nodes.contains_key(&to) {
+            return false;
+        }
+
+        let targets = self.outgoing.entry(from).or_default();
+        if targets.contains(&to) {
+            return false;
+        }
+        targets.push(to);
+        self.incoming.entry(to).or_default().push(from);
+        self.weights.insert((from, to), weight);
+
+        true
+    }
+
+    pub fn node(&self, id: usize) -> Option<&Node<T>> {
+        self.nodes.get(&id)
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        self.nodes.contains_key(&id)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.outgoing.values().map(Vec::len).sum()
+    }
+
+    /// Ids of all nodes, in ascending order.
+    pub fn node_ids(&self) -> Vec<usize> {
+        let mut ids: Vec<usize> = self.nodes.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// All edges as (from, to) pairs, ordered by source id then insertion order.
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        self.node_ids()
+            .into_iter()
+            .flat_map(|from| self.neighbors(from).iter().map(move |&to| (from, to)))
+            .collect()
+    }
+
+    /// Weight of the edge from `from` to `to`, None if there is no such edge.
+    pub fn edge_weight(&self, from: usize, to: usize) -> Option<u32> {
+        self.weights.get(&(from, to)).copied()
+    }
+
+    /// Nodes this node has an edge to.
+    pub fn neighbors(&self, id: usize) -> &[usize] {
+        self.outgoing.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Nodes that have an edge to this node.
+    pub fn predecessors(&self, id: usize) -> &[usize] {
+        self.incoming.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Breadth-first traversal along edge direction, starting at (and including) `start`.
+    ///
+    /// Yields nothing if `start` isn't in the graph.
+    pub fn bfs(&self, start: usize) -> Bfs<'_, T> {
+        Bfs::new(self, start)
+    }
+
+    /// Depth-first (pre-order) traversal along edge direction, starting at (and including) `start`.
+    ///
+    /// Yields nothing if `start` isn't in the graph.
+    pub fn dfs(&self, start: usize) -> Dfs<'_, T> {
+        Dfs::new(self, start)
+    }
+}
@@ -1,3 +1,13 @@
+pub mod algorithms;
+mod builder;
+mod digraph;
 mod node;
+pub mod routing;
+#[cfg(test)]
+mod tests;
+mod traversal;
 
+pub use builder::*;
+pub use digraph::*;
 pub use node::*;
+pub use traversal::*;
@@ -3,25 +3,26 @@ use serde::{Deserialize, Serialize};
 /// A node in a graph with position and arbitrary data.
 ///
 /// # Coordinates
-/// This library uses `usize` for coordinates, representing positions in a positive coordinate space.
+/// This library uses `isize` for coordinates, so positions may lie left of and above
+/// the origin.
 ///
 /// # Type Parameters
 /// * `T` - The type of data stored in this node
 #[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
 pub struct Node<T> {
-    pub x: usize,
-    pub y: usize,
+    pub x: isize,
+    pub y: isize,
     pub data: T,
 }
 
 impl<T> Node<T> {
     /// Creates a new node at the specified position with the given data.
-    pub fn new(x: usize, y: usize, data: T) -> Self {
+    pub fn new(x: isize, y: isize, data: T) -> Self {
         Self { x, y, data }
     }
 
     /// Returns the position of this node as a tuple.
-    pub fn position(&self) -> (usize, usize) {
+    pub fn position(&self) -> (isize, isize) {
         (self.x, self.y)
     }
 }
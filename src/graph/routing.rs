@@ -0,0 +1,243 @@
+//! Connection routing: how the line between two nodes is laid out.
+//!
+//! A [`RoutingStrategy`] turns two anchor points (a point on a node's edge and the
+//! side it leaves through) into the ordered points of a path. The strategy is picked
+//! per call, so library users can route differently from the TUI, which always uses
+//! [`Orthogonal`].
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::utils::{get_offset_point, orthogonal_path};
+pub use crate::{states::map::Side, utils::Point};
+
+/// A rectangular area paths should not pass through, such as a node's box.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Obstacle {
+    pub x: isize,
+    pub y: isize,
+    pub width: isize,
+    pub height: isize,
+}
+
+impl Obstacle {
+    pub fn new(x: isize, y: isize, width: isize, height: isize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns true if the point is one of the rectangle's cells.
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.x
+            && point.x < self.x + self.width
+            && point.y >= self.y
+            && point.y < self.y + self.height
+    }
+}
+
+/// Lays out the path of a connection.
+pub trait RoutingStrategy {
+    /// Returns the path from `start` to `end` as ordered points, both ends included.
+    ///
+    /// `start_side` is the side of the start node the path leaves through and
+    /// `end_side` the side of the end node it enters through. Strategies that don't
+    /// route around things ignore `obstacles`.
+    fn route(
+        &self,
+        start: Point,
+        start_side: Side,
+        end: Point,
+        end_side: Side,
+        obstacles: &[Obstacle],
+    ) -> Vec<Point>;
+}
+
+/// Horizontal and vertical segments in a fixed shape (C, S, U or corner) chosen from
+/// the sides and the relative position of the ends. The routing the TUI draws.
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct Orthogonal;
+
+impl RoutingStrategy for Orthogonal {
+    fn route(
+        &self,
+        start: Point,
+        start_side: Side,
+        end: Point,
+        end_side: Side,
+        _obstacles: &[Obstacle],
+    ) -> Vec<Point> {
+        orthogonal_path(start, start_side, end, end_side)
+    }
+}
+
+/// A single straight segment from start to end, usually diagonal.
+///
+/// Meant for renderers that can draw sloped lines, e.g. with Braille dot characters.
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct Direct;
+
+impl RoutingStrategy for Direct {
+    fn route(
+        &self,
+        start: Point,
+        _start_side: Side,
+        end: Point,
+        _end_side: Side,
+        _obstacles: &[Obstacle],
+    ) -> Vec<Point> {
+        vec![start, end]
+    }
+}
+
+/// Horizontal and vertical segments that go around obstacles, preferring short paths
+/// with few bends.
+///
+/// Searches a grid around the ends and obstacles. Falls back to [`Orthogonal`] if the
+/// ends are walled in or the search area would be larger than `max_cells`.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct ObstacleAvoiding {
+    /// Extra cost of a bend, in cells of path length
+    pub bend_cost: usize,
+    /// Largest grid the search may cover
+    pub max_cells: usize,
+}
+
+impl Default for ObstacleAvoiding {
+    fn default() -> Self {
+        Self {
+            bend_cost: 4,
+            max_cells: 250_000,
+        }
+    }
+}
+
+/// Free space kept around the ends and obstacles when searching for a route
+const SEARCH_MARGIN: isize = 4;
+
+const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+impl RoutingStrategy for ObstacleAvoiding {
+    fn route(
+        &self,
+        start: Point,
+        start_side: Side,
+        end: Point,
+        end_side: Side,
+        obstacles: &[Obstacle],
+    ) -> Vec<Point> {
+        let start_off = get_offset_point(start, start_side);
+        let end_off = get_offset_point(end, end_side);
+
+        match self.find_route(start_off, end_off, obstacles) {
+            Some(route) => {
+                let mut points = vec![start];
+                points.extend(route);
+                points.push(end);
+                points
+            }
+            None => orthogonal_path(start, start_side, end, end_side),
+        }
+    }
+}
+
+impl ObstacleAvoiding {
+    /// Cheapest grid route from `from` to `to`, reduced to its end and corner points.
+    fn find_route(&self, from: Point, to: Point, obstacles: &[Obstacle]) -> Option<Vec<Point>> {
+        let min_x = obstacles.iter().map(|o| o.x).chain([from.x, to.x]).min()? - SEARCH_MARGIN;
+        let min_y = obstacles.iter().map(|o| o.y).chain([from.y, to.y]).min()? - SEARCH_MARGIN;
+        let max_x = obstacles
+            .iter()
+            .map(|o| o.x + o.width)
+            .chain([from.x, to.x])
+            .max()?
+            + SEARCH_MARGIN;
+        let max_y = obstacles
+            .iter()
+            .map(|o| o.y + o.height)
+            .chain([from.y, to.y])
+            .max()?
+            + SEARCH_MARGIN;
+        let cells = (max_x - min_x + 1).unsigned_abs() * (max_y - min_y + 1).unsigned_abs();
+        if cells > self.max_cells {
+            return None;
+        }
+
+        let is_free = |point: Point| {
+            point.x >= min_x
+                && point.x <= max_x
+                && point.y >= min_y
+                && point.y <= max_y
+                && (point == from
+                    || point == to
+                    || !obstacles.iter().any(|obstacle| obstacle.contains(point)))
+        };
+
+        // Dijkstra over (position, direction of the last step); None before the first step
+        type State = (isize, isize, Option<usize>);
+        let mut costs: HashMap<State, usize> = HashMap::from([((from.x, from.y, None), 0)]);
+        let mut parents: HashMap<State, State> = HashMap::new();
+        let mut queue = BinaryHeap::from([Reverse((0, from.x, from.y, None))]);
+
+        while let Some(Reverse((cost, x, y, direction))) = queue.pop() {
+            let state = (x, y, direction);
+            if costs.get(&state).is_some_and(|&best| cost > best) {
+                continue;
+            }
+            if (x, y) == (to.x, to.y) {
+                let mut cells = vec![Point { x, y }];
+                let mut current = state;
+                while let Some(&parent) = parents.get(&current) {
+                    cells.push(Point {
+                        x: parent.0,
+                        y: parent.1,
+                    });
+                    current = parent;
+                }
+                cells.reverse();
+                return Some(corners(&cells));
+            }
+
+            for (index, (dx, dy)) in DIRECTIONS.into_iter().enumerate() {
+                let next = Point {
+                    x: x + dx,
+                    y: y + dy,
+                };
+                if !is_free(next) {
+                    continue;
+                }
+                let bend = direction.is_some_and(|previous| previous != index);
+                let next_cost = cost + 1 + if bend { self.bend_cost } else { 0 };
+                let next_state = (next.x, next.y, Some(index));
+                if costs.get(&next_state).is_none_or(|&best| next_cost < best) {
+                    costs.insert(next_state, next_cost);
+                    parents.insert(next_state, state);
+                    queue.push(Reverse((next_cost, next.x, next.y, Some(index))));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Drops the points in the middle of straight runs, keeping the ends and every bend.
+fn corners(cells: &[Point]) -> Vec<Point> {
+    let mut points: Vec<Point> = cells.first().copied().into_iter().collect();
+    for window in cells.windows(3) {
+        let (a, b, c) = (window[0], window[1], window[2]);
+        let straight = (a.x == b.x && b.x == c.x) || (a.y == b.y && b.y == c.y);
+        if !straight {
+            points.push(b);
+        }
+    }
+    if cells.len() > 1 {
+        points.extend(cells.last());
+    }
+    points
+}
@@ -0,0 +1,201 @@
+use crate::graph::{
+    Graph, Node,
+    algorithms::{
+        CycleError, GraphStats, find_cycle, graph_stats, has_cycle, leaves, max_depth, orphans,
+        reachable_from, shortest_path, strongly_connected_components, topological_sort,
+        weakly_connected_components, weighted_shortest_path,
+    },
+};
+
+fn create_graph(node_count: usize, edges: &[(usize, usize)]) -> Graph<()> {
+    let mut graph = Graph::new();
+    for id in 0..node_count {
+        graph.add_node(id, Node::new(id as isize * 10, 0, ()));
+    }
+    for &(from, to) in edges {
+        graph.add_edge(from, to);
+    }
+    graph
+}
+
+#[test]
+fn test_topological_sort_orders_dependencies() {
+    // 3 -> 1 -> 0, 3 -> 2 -> 0, 4 isolated
+    let graph = create_graph(5, &[(3, 1), (3, 2), (1, 0), (2, 0)]);
+
+    assert!(!has_cycle(&graph));
+    assert_eq!(find_cycle(&graph), None);
+    // Smallest ready id first
+    assert_eq!(topological_sort(&graph), Ok(vec![3, 1, 2, 0, 4]));
+}
+
+#[test]
+fn test_topological_sort_empty_graph() {
+    let graph = create_graph(0, &[]);
+
+    assert!(!has_cycle(&graph));
+    assert_eq!(topological_sort(&graph), Ok(vec![]));
+    assert!(strongly_connected_components(&graph).is_empty());
+}
+
+#[test]
+fn test_topological_sort_reports_cycle() {
+    // 0 -> 1 -> 2 -> 3 -> 1
+    let graph = create_graph(4, &[(0, 1), (1, 2), (2, 3), (3, 1)]);
+
+    assert!(has_cycle(&graph));
+    let err = topological_sort(&graph).unwrap_err();
+    assert_eq!(
+        err,
+        CycleError {
+            cycle: vec![1, 2, 3]
+        }
+    );
+    assert_eq!(err.to_string(), "graph contains a cycle: 1 -> 2 -> 3 -> 1");
+}
+
+#[test]
+fn test_self_loop_is_a_cycle() {
+    let graph = create_graph(2, &[(0, 1), (1, 1)]);
+
+    assert_eq!(find_cycle(&graph), Some(vec![1]));
+    assert!(topological_sort(&graph).is_err());
+}
+
+#[test]
+fn test_strongly_connected_components() {
+    // {0, 1, 2} cycle -> {3, 4} cycle -> 5, plus isolated 6
+    let graph = create_graph(7, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (4, 5)]);
+
+    assert_eq!(
+        strongly_connected_components(&graph),
+        vec![vec![5], vec![3, 4], vec![0, 1, 2], vec![6]]
+    );
+}
+
+#[test]
+fn test_strongly_connected_components_of_dag_are_single_nodes() {
+    let graph = create_graph(3, &[(0, 1), (1, 2)]);
+
+    assert_eq!(
+        strongly_connected_components(&graph),
+        vec![vec![2], vec![1], vec![0]]
+    );
+}
+
+#[test]
+fn test_long_chain_does_not_overflow() {
+    let edges: Vec<(usize, usize)> = (0..9_999).map(|id| (id, id + 1)).collect();
+    let graph = create_graph(10_000, &edges);
+
+    assert!(!has_cycle(&graph));
+    assert_eq!(strongly_connected_components(&graph).len(), 10_000);
+}
+
+#[test]
+fn test_shortest_path_prefers_fewest_edges() {
+    // 0 -> 1 -> 2 -> 3 and the shortcut 0 -> 4 -> 3
+    let graph = create_graph(6, &[(0, 1), (1, 2), (2, 3), (0, 4), (4, 3)]);
+
+    assert_eq!(shortest_path(&graph, 0, 3), Some(vec![0, 4, 3]));
+    assert_eq!(shortest_path(&graph, 1, 3), Some(vec![1, 2, 3]));
+    assert_eq!(shortest_path(&graph, 2, 2), Some(vec![2]));
+}
+
+#[test]
+fn test_shortest_path_follows_edge_direction() {
+    let graph = create_graph(3, &[(0, 1)]);
+
+    assert_eq!(shortest_path(&graph, 1, 0), None);
+    assert_eq!(shortest_path(&graph, 0, 2), None);
+    assert_eq!(shortest_path(&graph, 0, 99), None);
+}
+
+#[test]
+fn test_reachable_from() {
+    let graph = create_graph(5, &[(3, 1), (1, 0), (0, 1), (2, 4)]);
+
+    assert_eq!(reachable_from(&graph, 3), vec![0, 1, 3]);
+    assert_eq!(reachable_from(&graph, 0), vec![0, 1]);
+    assert_eq!(reachable_from(&graph, 4), vec![4]);
+    assert!(reachable_from(&graph, 99).is_empty());
+}
+
+#[test]
+fn test_weighted_shortest_path_prefers_lighter_route() {
+    // 0 -> 3 directly costs 10, 0 -> 1 -> 2 -> 3 costs 3
+    let mut graph = create_graph(4, &[(0, 1), (1, 2), (2, 3)]);
+    graph.add_weighted_edge(0, 3, 10);
+
+    assert_eq!(shortest_path(&graph, 0, 3), Some(vec![0, 3]));
+    assert_eq!(
+        weighted_shortest_path(&graph, 0, 3),
+        Some((vec![0, 1, 2, 3], 3))
+    );
+    assert_eq!(graph.edge_weight(0, 3), Some(10));
+    assert_eq!(graph.edge_weight(0, 1), Some(1));
+    assert_eq!(graph.edge_weight(3, 0), None);
+}
+
+#[test]
+fn test_weighted_shortest_path_unreachable_and_missing() {
+    let graph = create_graph(3, &[(0, 1)]);
+
+    assert_eq!(weighted_shortest_path(&graph, 0, 0), Some((vec![0], 0)));
+    assert_eq!(weighted_shortest_path(&graph, 1, 0), None);
+    assert_eq!(weighted_shortest_path(&graph, 0, 2), None);
+    assert_eq!(weighted_shortest_path(&graph, 0, 9), None);
+}
+
+#[test]
+fn test_orphans_and_leaves() {
+    // 0 -> 1 -> 2, 0 -> 3, 4 isolated, 5 <-> 6
+    let graph = create_graph(7, &[(0, 1), (1, 2), (0, 3), (5, 6), (6, 5)]);
+
+    assert_eq!(orphans(&graph), vec![4]);
+    assert_eq!(leaves(&graph), vec![2, 3]);
+}
+
+#[test]
+fn test_weakly_connected_components_ignore_direction() {
+    // 1 -> 0 <- 2, 3 -> 4, 5 isolated
+    let graph = create_graph(6, &[(1, 0), (2, 0), (3, 4)]);
+
+    assert_eq!(
+        weakly_connected_components(&graph),
+        vec![vec![0, 1, 2], vec![3, 4], vec![5]]
+    );
+}
+
+#[test]
+fn test_max_depth() {
+    assert_eq!(max_depth(&create_graph(0, &[])), 0);
+    assert_eq!(max_depth(&create_graph(3, &[])), 0);
+    // Longest chain 0 -> 1 -> 2 -> 3, shortcut 0 -> 3 doesn't matter
+    assert_eq!(
+        max_depth(&create_graph(4, &[(0, 1), (1, 2), (2, 3), (0, 3)])),
+        3
+    );
+    // The 1 <-> 2 cycle counts as one step: 0 -> {1, 2} -> 3
+    assert_eq!(
+        max_depth(&create_graph(4, &[(0, 1), (1, 2), (2, 1), (2, 3)])),
+        2
+    );
+}
+
+#[test]
+fn test_graph_stats() {
+    let graph = create_graph(5, &[(0, 1), (0, 2), (2, 3)]);
+
+    assert_eq!(
+        graph_stats(&graph),
+        GraphStats {
+            node_count: 5,
+            edge_count: 3,
+            orphans: vec![4],
+            leaves: vec![1, 3],
+            max_depth: 2,
+            component_count: 2,
+        }
+    );
+}
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use crate::{
+    graph::MapBuilder,
+    states::{MapState, settings::Settings},
+    utils::MapData,
+};
+
+#[test]
+fn test_notes_get_sequential_ids_and_row_layout() {
+    let map = MapBuilder::new()
+        .note("a")
+        .note("b")
+        .at(10, 8)
+        .note("c")
+        .build();
+
+    assert_eq!(map.next_note_id_counter, 3);
+    assert_eq!(map.render_order, vec![0, 1, 2]);
+    assert_eq!((map.notes[&0].x, map.notes[&0].y), (0, 0));
+    assert_eq!((map.notes[&1].x, map.notes[&1].y), (10, 8));
+    // Placed relative to the note before it
+    assert_eq!((map.notes[&2].x, map.notes[&2].y), (30, 8));
+    assert_eq!(map.notes[&2].content, "c");
+}
+
+#[test]
+fn test_invalid_and_duplicate_connections_are_skipped() {
+    let map = MapBuilder::new()
+        .note("a")
+        .note("b")
+        .connect(0, 1)
+        .connect(0, 1)
+        .connect(1, 5)
+        .build();
+
+    assert_eq!(map.connections.len(), 1);
+    assert_eq!(map.connections[0].from_id, 0);
+    assert_eq!(map.connections[0].to_id, 1);
+}
+
+#[test]
+fn test_build_graph() {
+    let graph = MapBuilder::new()
+        .note("a")
+        .note("b")
+        .note("c")
+        .connect(0, 1)
+        .connect(1, 2)
+        .build_graph();
+
+    assert_eq!(graph.bfs(0).collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert_eq!(graph.node(2).unwrap().data, "c");
+}
+
+#[test]
+fn test_built_map_loads_and_round_trips() {
+    let map = MapBuilder::new().note("a").note("b").connect(0, 1).build();
+
+    let mut map_state = MapState::new_with_settings(PathBuf::new(), Settings::new());
+    map_state.load_map_data(map.clone());
+
+    assert_eq!(MapData::from_map_state(&mut map_state), map);
+}
@@ -0,0 +1,5 @@
+// This file organizes all the test submodules
+mod algorithms_tests;
+mod builder_tests;
+mod routing_tests;
+mod traversal_tests;
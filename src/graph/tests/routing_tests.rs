@@ -0,0 +1,115 @@
+use crate::{
+    graph::routing::{
+        Direct, Obstacle, ObstacleAvoiding, Orthogonal, Point, RoutingStrategy, Side,
+    },
+    utils::orthogonal_path,
+};
+
+fn point(x: isize, y: isize) -> Point {
+    Point { x, y }
+}
+
+/// True if every segment of the path is horizontal or vertical
+fn is_axis_aligned(path: &[Point]) -> bool {
+    path.windows(2)
+        .all(|segment| segment[0].x == segment[1].x || segment[0].y == segment[1].y)
+}
+
+/// Every cell the path passes through, corners included
+fn path_cells(path: &[Point]) -> Vec<Point> {
+    let mut cells = vec![];
+    for segment in path.windows(2) {
+        let (from, to) = (segment[0], segment[1]);
+        let steps = (to.x - from.x).abs().max((to.y - from.y).abs());
+        for step in 0..=steps {
+            cells.push(point(
+                from.x + (to.x - from.x).signum() * step,
+                from.y + (to.y - from.y).signum() * step,
+            ));
+        }
+    }
+    cells
+}
+
+#[test]
+fn test_orthogonal_matches_default_path() {
+    let (start, end) = (point(10, 5), point(40, 20));
+
+    let path = Orthogonal.route(start, Side::Right, end, Side::Left, &[]);
+
+    assert_eq!(path, orthogonal_path(start, Side::Right, end, Side::Left));
+    assert_eq!(path.first(), Some(&start));
+    assert_eq!(path.last(), Some(&end));
+    assert!(is_axis_aligned(&path));
+}
+
+#[test]
+fn test_direct_is_a_single_segment() {
+    let path = Direct.route(point(0, 0), Side::Right, point(30, 12), Side::Top, &[]);
+
+    assert_eq!(path, vec![point(0, 0), point(30, 12)]);
+}
+
+#[test]
+fn test_obstacle_avoiding_goes_around_obstacle() {
+    let (start, end) = (point(0, 10), point(40, 10));
+    let wall = Obstacle::new(15, 0, 10, 21);
+
+    let path = ObstacleAvoiding::default().route(start, Side::Right, end, Side::Left, &[wall]);
+
+    assert_eq!(path.first(), Some(&start));
+    assert_eq!(path.last(), Some(&end));
+    assert!(is_axis_aligned(&path));
+    assert!(!path_cells(&path).iter().any(|&cell| wall.contains(cell)));
+    // The default routing would go straight through the wall
+    let direct = Orthogonal.route(start, Side::Right, end, Side::Left, &[wall]);
+    assert!(path_cells(&direct).iter().any(|&cell| wall.contains(cell)));
+}
+
+#[test]
+fn test_obstacle_avoiding_prefers_straight_line() {
+    let (start, end) = (point(0, 10), point(40, 10));
+
+    let path = ObstacleAvoiding::default().route(start, Side::Right, end, Side::Left, &[]);
+
+    assert_eq!(path, vec![start, point(2, 10), point(38, 10), end]);
+}
+
+#[test]
+fn test_obstacle_avoiding_falls_back_when_walled_in() {
+    let (start, end) = (point(0, 10), point(40, 10));
+    // Boxes the end's offset point in completely
+    let cage = [
+        Obstacle::new(36, 7, 5, 1),
+        Obstacle::new(36, 13, 5, 1),
+        Obstacle::new(36, 7, 1, 7),
+        Obstacle::new(40, 7, 1, 7),
+    ];
+    let strategy = ObstacleAvoiding {
+        max_cells: 10,
+        ..ObstacleAvoiding::default()
+    };
+
+    let expected = orthogonal_path(start, Side::Right, end, Side::Left);
+    assert_eq!(
+        ObstacleAvoiding::default().route(start, Side::Right, end, Side::Left, &cage),
+        expected
+    );
+    // Search area too large
+    assert_eq!(
+        strategy.route(start, Side::Right, end, Side::Left, &[]),
+        expected
+    );
+}
+
+#[test]
+fn test_strategies_are_interchangeable() {
+    let strategies: [&dyn RoutingStrategy; 3] =
+        [&Orthogonal, &Direct, &ObstacleAvoiding::default()];
+
+    for strategy in strategies {
+        let path = strategy.route(point(0, 0), Side::Bottom, point(20, 20), Side::Top, &[]);
+        assert_eq!(path.first(), Some(&point(0, 0)));
+        assert_eq!(path.last(), Some(&point(20, 20)));
+    }
+}
@@ -0,0 +1,69 @@
+use crate::graph::{Graph, Node};
+
+/// 0 -> 1 -> 3
+/// 0 -> 2 -> 3
+/// 4 (isolated)
+fn create_diamond_graph() -> Graph<&'static str> {
+    let mut graph = Graph::new();
+    for (id, name) in ["a", "b", "c", "d", "e"].into_iter().enumerate() {
+        graph.add_node(id, Node::new(id as isize * 10, 0, name));
+    }
+    graph.add_edge(0, 1);
+    graph.add_edge(0, 2);
+    graph.add_edge(1, 3);
+    graph.add_edge(2, 3);
+    graph
+}
+
+#[test]
+fn test_neighbors_and_predecessors() {
+    let graph = create_diamond_graph();
+
+    assert_eq!(graph.neighbors(0), &[1, 2]);
+    assert_eq!(graph.predecessors(3), &[1, 2]);
+    assert!(graph.neighbors(4).is_empty());
+    assert!(graph.neighbors(99).is_empty());
+}
+
+#[test]
+fn test_add_edge_rejects_duplicates_and_missing_nodes() {
+    let mut graph = create_diamond_graph();
+
+    assert!(!graph.add_edge(0, 1));
+    assert!(!graph.add_edge(0, 99));
+    assert!(!graph.add_edge(99, 0));
+    assert_eq!(graph.edge_count(), 4);
+    assert_eq!(graph.edges(), vec![(0, 1), (0, 2), (1, 3), (2, 3)]);
+}
+
+#[test]
+fn test_bfs_visits_by_distance() {
+    let graph = create_diamond_graph();
+
+    assert_eq!(graph.bfs(0).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+    assert_eq!(graph.bfs(2).collect::<Vec<_>>(), vec![2, 3]);
+    assert_eq!(graph.bfs(4).collect::<Vec<_>>(), vec![4]);
+    assert_eq!(graph.bfs(99).count(), 0);
+}
+
+#[test]
+fn test_dfs_goes_deep_first() {
+    let graph = create_diamond_graph();
+
+    assert_eq!(graph.dfs(0).collect::<Vec<_>>(), vec![0, 1, 3, 2]);
+    assert_eq!(graph.dfs(99).count(), 0);
+}
+
+#[test]
+fn test_traversals_terminate_on_cycles() {
+    let mut graph = Graph::new();
+    for id in 0..3 {
+        graph.add_node(id, Node::new(0, 0, ()));
+    }
+    graph.add_edge(0, 1);
+    graph.add_edge(1, 2);
+    graph.add_edge(2, 0);
+
+    assert_eq!(graph.bfs(1).collect::<Vec<_>>(), vec![1, 2, 0]);
+    assert_eq!(graph.dfs(1).collect::<Vec<_>>(), vec![1, 2, 0]);
+}
@@ -0,0 +1,94 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::graph::Graph;
+
+/// Breadth-first iterator over node ids, created by [`Graph::bfs`].
+///
+/// Every reachable node is yielded exactly once, in order of distance from the start.
+pub struct Bfs<'a, T> {
+    graph: &'a Graph<T>,
+    queue: VecDeque<usize>,
+    visited: HashSet<usize>,
+}
+
+impl<'a, T> Bfs<'a, T> {
+    pub(crate) fn new(graph: &'a Graph<T>, start: usize) -> Self {
+        let mut queue = VecDeque::new();
+        let mut visited = HashSet::new();
+        if graph.contains(start) {
+            queue.push_back(start);
+            visited.insert(start);
+        }
+
+        Self {
+            graph,
+            queue,
+            visited,
+        }
+    }
+}
+
+impl<T> Iterator for Bfs<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let id = self.queue.pop_front()?;
+
+        for &next in self.graph.neighbors(id) {
+            if self.visited.insert(next) {
+                self.queue.push_back(next);
+            }
+        }
+
+        Some(id)
+    }
+}
+
+/// Depth-first (pre-order) iterator over node ids, created by [`Graph::dfs`].
+///
+/// Neighbors are explored in the order their edges were added.
+/// Every reachable node is yielded exactly once.
+pub struct Dfs<'a, T> {
+    graph: &'a Graph<T>,
+    stack: Vec<usize>,
+    visited: HashSet<usize>,
+}
+
+impl<'a, T> Dfs<'a, T> {
+    pub(crate) fn new(graph: &'a Graph<T>, start: usize) -> Self {
+        let stack = if graph.contains(start) {
+            vec![start]
+        } else {
+            vec![]
+        };
+
+        Self {
+            graph,
+            stack,
+            visited: HashSet::new(),
+        }
+    }
+}
+
+impl<T> Iterator for Dfs<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while let Some(id) = self.stack.pop() {
+            if !self.visited.insert(id) {
+                continue;
+            }
+
+            // Pushed in reverse so the first neighbor is explored first
+            for &next in self.graph.neighbors(id).iter().rev() {
+                if !self.visited.contains(&next) {
+                    self.stack.push(next);
+                }
+            }
+
+            return Some(id);
+        }
+
+        None
+    }
+}
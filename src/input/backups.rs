@@ -0,0 +1,23 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{app::Screen, input::AppAction, states::BackupsState};
+
+/// Handles keyboard input for the Backups Screen.
+///
+/// Both restoring a backup and leaving return to the map screen with the same session.
+pub fn backups_kh(backups_state: &mut BackupsState, key: KeyEvent) -> AppAction {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            return AppAction::Switch(Screen::Map(backups_state.take_map_state()));
+        }
+        KeyCode::Char('j') | KeyCode::Down => backups_state.select_next(),
+        KeyCode::Char('k') | KeyCode::Up => backups_state.select_previous(),
+        KeyCode::Enter | KeyCode::Char('r') if backups_state.restore_selected() => {
+            return AppAction::Switch(Screen::Map(backups_state.take_map_state()));
+        }
+        _ => {}
+    }
+
+    backups_state.needs_clear_and_redraw = true;
+    AppAction::Continue
+}
@@ -0,0 +1,28 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::states::Dialog;
+
+/// Handles a key while a dialog is shown and returns the action chosen, if any.
+///
+/// A button's key chooses that button, Esc cancels, and Enter chooses the button with
+/// focus, which h / l, the arrow keys and Tab move. Other keys are ignored, so nothing
+/// reaches the screen below while the dialog is open.
+pub fn dialog_kh<A: Clone>(dialog: &mut Dialog<A>, key: KeyEvent) -> Option<A> {
+    if let Some(action) = dialog.action_for_key(key.code) {
+        return Some(action);
+    }
+
+    match key.code {
+        KeyCode::Esc => Some(dialog.cancel.clone()),
+        KeyCode::Enter => dialog.focused_action(),
+        KeyCode::Right | KeyCode::Tab | KeyCode::Char('l') => {
+            dialog.focus_next();
+            None
+        }
+        KeyCode::Left | KeyCode::BackTab | KeyCode::Char('h') => {
+            dialog.focus_previous();
+            None
+        }
+        _ => None,
+    }
+}
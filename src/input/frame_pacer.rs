@@ -0,0 +1,51 @@
+use std::time::{Duration, Instant};
+
+/// Shortest time between two redraws, about 60 per second
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// How long to wait for input while nothing is waiting to be drawn. Also how often the
+/// map screen's timers (auto-save, backups, file checks) run while idle.
+pub const IDLE_POLL_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Paces the main loop: a changed screen is redrawn at most once per
+/// [`FRAME_INTERVAL`], and input arriving in between is handled first.
+///
+/// Holding a key (e.g. `l` to pan) then updates the state once per key repeat but
+/// redraws once per frame, instead of queueing up a redraw for every repeat.
+#[derive(Debug)]
+pub struct FramePacer {
+    last_draw: Option<Instant>,
+}
+
+impl Default for FramePacer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FramePacer {
+    pub fn new() -> Self {
+        Self { last_draw: None }
+    }
+
+    /// True if a frame has passed since the last redraw.
+    pub fn can_draw(&self, now: Instant) -> bool {
+        self.last_draw
+            .is_none_or(|last_draw| now.duration_since(last_draw) >= FRAME_INTERVAL)
+    }
+
+    pub fn mark_drawn(&mut self, now: Instant) {
+        self.last_draw = Some(now);
+    }
+
+    /// How long to wait for input: until the next redraw is allowed if one is
+    /// pending, otherwise [`IDLE_POLL_TIMEOUT`].
+    pub fn poll_timeout(&self, redraw_pending: bool, now: Instant) -> Duration {
+        if !redraw_pending {
+            return IDLE_POLL_TIMEOUT;
+        }
+        self.last_draw.map_or(Duration::ZERO, |last_draw| {
+            FRAME_INTERVAL.saturating_sub(now.duration_since(last_draw))
+        })
+    }
+}
@@ -1,18 +1,30 @@
 use crate::{
     app::{App, Screen},
     input::{
-        map::{map_delete_kh, map_edit_kh, map_normal_kh, map_visual_kh},
-        settings_kh, start_kh,
+        acts_as_press, backups_kh,
+        map::{
+            map_command_line_kh, map_connection_table_kh, map_delete_kh, map_dialog_kh,
+            map_diff_view_kh, map_edit_kh, map_external_change_kh, map_help_kh, map_layer_panel_kh,
+            map_milestone_picker_kh, map_milestone_prompt_kh, map_milestone_tab_kh, map_normal_kh,
+            map_note_search_kh, map_passphrase_kh, map_region_kh, map_search_kh,
+            map_snapshot_picker_kh, map_title_prompt_kh, map_trash_kh, map_visual_kh,
+            map_workspace_picker_kh, map_workspace_search_kh, paste_text,
+        },
+        paste_keys, settings_kh, start_kh,
     },
     states::{
         MapState,
-        map::{Mode, Notification},
+        map::{Mode, help_page_for_mode},
+    },
+    utils::{
+        RealFileSystem, WorkspaceMatch, create_map_file, load_map_file, load_map_file_unchecked,
+        open_workspace, open_workspace_match, save_map_file_in_background, switch_map_file,
+        unlock_map_file,
     },
-    utils::{RealFileSystem, create_map_file, load_map_file, save_with_notification},
 };
 use color_eyre::Result;
-use crossterm::event::{self, Event, KeyEvent, KeyEventKind};
-use std::path::PathBuf;
+use crossterm::event::{self, Event, KeyCode, KeyEvent};
+use std::{path::PathBuf, time::Duration};
 
 #[derive(PartialEq, Debug)]
 pub enum AppAction {
@@ -22,67 +34,224 @@ pub enum AppAction {
     CreateMapFile(PathBuf),
     SaveMapFile(PathBuf),
     LoadMapFile(PathBuf),
+    /// Loads a map file without checking its checksum, after the user chose to open it
+    /// anyway
+    LoadMapFileUnchecked(PathBuf),
+    /// Loads an encrypted map file with the passphrase entered for it
+    UnlockMapFile(PathBuf, String),
+    /// Opens a directory of maps as a workspace, starting with its first map
+    OpenWorkspace(PathBuf),
+    /// Saves the open map and loads another one of its workspace
+    SwitchMapFile(PathBuf),
+    /// Like `SwitchMapFile`, then shows the found note with the search query's matches
+    OpenWorkspaceMatch(WorkspaceMatch, String),
 }
 
+/// Most events handled before the screen is redrawn, so a flood of input can't hold
+/// off redraws and the map screen's timers indefinitely
+const MAX_EVENTS_PER_FRAME: usize = 256;
+
 /// Main event loop handler that polls terminal events and dispatches them to screen-specific handlers.
 ///
+/// Waits up to `timeout` for an event, then also handles every event that has arrived
+/// meanwhile, so that held keys (e.g. `l` to pan) update the state for each repeat but
+/// cause only one redraw. See [`crate::input::FramePacer`].
+///
 /// This function is intentionally not tested because:
 /// 1. It's a thin orchestrator over fully-tested components
 /// 2. All called functions are tested individually (start_kh, settings_kh, map_kh, etc.)
 /// 3. The integration points for crossterm are better tested via manual testing
 /// 4. Adding mocks would add complexity without significant value
-pub fn handle_events(app: &mut App) -> Result<()> {
-    // 50ms timeout balances responsiveness with CPU usage
-    if event::poll(std::time::Duration::from_millis(50))? {
-        match event::read()? {
-            Event::Key(key) if key.kind == KeyEventKind::Press => {
-                let app_action = match &mut app.screen {
-                    Screen::Start(start_state) => start_kh(start_state, key, &RealFileSystem),
-                    Screen::Settings(settings_state) => {
-                        settings_kh(settings_state, key, &RealFileSystem)
-                    }
-                    Screen::Map(map_state) => map_kh(map_state, key),
-                };
+pub fn handle_events(app: &mut App, timeout: Duration) -> Result<()> {
+    if !event::poll(timeout)? {
+        return Ok(());
+    }
+
+    let mut handled = 0;
+    loop {
+        handle_event(app, event::read()?);
+        handled += 1;
+        if !app.running || handled >= MAX_EVENTS_PER_FRAME || !event::poll(Duration::ZERO)? {
+            return Ok(());
+        }
+    }
+}
+
+/// Dispatches one terminal event to the current screen and carries out the resulting
+/// action.
+fn handle_event(app: &mut App, event: Event) {
+    match event {
+        Event::Key(key) if acts_as_press(key.kind) => {
+            let app_action = match &mut app.screen {
+                Screen::Start(start_state) => start_kh(start_state, key, &RealFileSystem),
+                Screen::Settings(settings_state) => {
+                    settings_kh(settings_state, key, &RealFileSystem)
+                }
+                Screen::Map(map_state) => {
+                    let app_action = map_kh(map_state, key);
+                    map_state.advance_tutorial();
+                    app_action
+                }
+                Screen::Backups(backups_state) => backups_kh(backups_state, key),
+            };
 
-                match app_action {
-                    AppAction::Continue => {}
-                    AppAction::Quit => app.quit(),
-                    AppAction::Switch(screen) => app.screen = screen,
-                    AppAction::CreateMapFile(path) => create_map_file(app, &path),
-                    AppAction::SaveMapFile(path) => {
-                        let Screen::Map(map_state) = &mut app.screen else {
-                            unreachable!("SaveMapFile triggered outside map screen")
-                        };
-                        let _ = save_with_notification(
-                            map_state,
-                            &path,
-                            Notification::SaveSuccess,
-                            Notification::SaveFail,
-                        );
-                    }
-                    AppAction::LoadMapFile(path) => load_map_file(app, &path),
+            match app_action {
+                AppAction::Continue => {}
+                AppAction::Quit => app.quit(),
+                AppAction::Switch(screen) => app.screen = screen,
+                AppAction::CreateMapFile(path) => create_map_file(app, &path),
+                AppAction::SaveMapFile(path) => {
+                    let Screen::Map(map_state) = &mut app.screen else {
+                        unreachable!("SaveMapFile triggered outside map screen")
+                    };
+                    save_map_file_in_background(map_state, &path, true);
+                }
+                AppAction::LoadMapFile(path) => load_map_file(app, &path),
+                AppAction::LoadMapFileUnchecked(path) => load_map_file_unchecked(app, &path),
+                AppAction::UnlockMapFile(path, passphrase) => {
+                    unlock_map_file(app, &path, &passphrase)
+                }
+                AppAction::OpenWorkspace(dir) => open_workspace(app, &dir),
+                AppAction::SwitchMapFile(path) => switch_map_file(app, &path),
+                AppAction::OpenWorkspaceMatch(found, query) => {
+                    open_workspace_match(app, &found, &query)
                 }
             }
+        }
 
-            Event::Resize(_, _) => match &mut app.screen {
-                Screen::Start(start_state) => start_state.needs_clear_and_redraw = true,
-                Screen::Settings(settings_state) => settings_state.needs_clear_and_redraw = true,
-                Screen::Map(map_state) => map_state.clear_and_redraw(),
-            },
+        // Typed into a note as a whole; anywhere else key by key, as without bracketed
+        // paste
+        Event::Paste(text) => match &mut app.screen {
+            Screen::Map(map_state) if pastes_into_note(map_state) => {
+                paste_text(map_state, &text);
+                map_state.clear_and_redraw();
+            }
+            _ => {
+                for key in paste_keys(&text) {
+                    handle_event(app, Event::Key(key));
+                }
+            }
+        },
 
-            _ => {}
-        }
+        Event::Resize(_, _) => match &mut app.screen {
+            Screen::Start(start_state) => start_state.needs_clear_and_redraw = true,
+            Screen::Settings(settings_state) => settings_state.needs_clear_and_redraw = true,
+            Screen::Map(map_state) => map_state.clear_and_redraw(),
+            Screen::Backups(backups_state) => backups_state.needs_clear_and_redraw = true,
+        },
+
+        _ => {}
     }
-    Ok(())
 }
 
 /// Dispatches key events to mode-specific handlers in the map screen.
 pub fn map_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    if let Some(overlay_kh) = map_overlay_kh(map_state) {
+        return overlay_kh(map_state, key);
+    }
+
+    // F1 opens the help page of the current mode; Normal mode handles it (and `?`)
+    // itself
+    if key.code == KeyCode::F(1) && map_state.mode != Mode::Normal {
+        map_state
+            .ui_state
+            .show_help(help_page_for_mode(map_state.mode));
+        map_state.clear_and_redraw();
+        return AppAction::Continue;
+    }
+
     match &map_state.mode {
         Mode::Normal => map_normal_kh(map_state, key, &RealFileSystem),
-        Mode::Visual | Mode::VisualMove | Mode::VisualConnect => map_visual_kh(map_state, key),
+        Mode::Visual
+        | Mode::VisualMove
+        | Mode::VisualConnect
+        | Mode::VisualWaypoint
+        | Mode::VisualBox => map_visual_kh(map_state, key),
         Mode::Edit | Mode::EditNormal | Mode::EditInsert => map_edit_kh(map_state, key),
-        // Delete mode requires user confirmation before actually deleting
+        // Delete mode is confirmed in a dialog, see `MapState::confirm_delete`
         Mode::Delete => map_delete_kh(map_state, key),
+        Mode::Region => map_region_kh(map_state, key),
     }
 }
+
+/// Whether pasted text goes into the note being edited, i.e. the note is in (insert)
+/// Edit mode and nothing is shown over it.
+pub fn pastes_into_note(map_state: &MapState) -> bool {
+    matches!(map_state.mode, Mode::Edit | Mode::EditInsert) && map_overlay_kh(map_state).is_none()
+}
+
+/// The key handler of whatever is shown over the map and takes all keys (help, dialogs,
+/// prompts, pickers...), None if keys go to the current mode.
+fn map_overlay_kh(map_state: &MapState) -> Option<fn(&mut MapState, KeyEvent) -> AppAction> {
+    // The help screen covers the whole map, in whichever mode it was opened
+    if map_state.ui_state.is_help_visible() {
+        return Some(map_help_kh);
+    }
+    // So do dialogs, e.g. the discard and delete confirmations
+    if map_state.ui_state.dialog.is_some() {
+        return Some(|map_state, key| map_dialog_kh(map_state, key, &RealFileSystem));
+    }
+    // A change of the map file on disk has to be resolved first
+    if map_state.persistence.external_change.is_some() {
+        return Some(map_external_change_kh);
+    }
+    // Passphrase prompt intercepts all input while shown
+    if map_state.ui_state.passphrase_prompt.is_some() {
+        return Some(map_passphrase_kh);
+    }
+    if map_state.ui_state.command_line.is_some() {
+        return Some(map_command_line_kh);
+    }
+    if map_state.ui_state.title_prompt.is_some() {
+        return Some(map_title_prompt_kh);
+    }
+    if map_state
+        .ui_state
+        .search
+        .as_ref()
+        .is_some_and(|search| search.typing)
+    {
+        return Some(map_search_kh);
+    }
+    if map_state
+        .ui_state
+        .note_search
+        .as_ref()
+        .is_some_and(|note_search| note_search.search.typing)
+    {
+        return Some(map_note_search_kh);
+    }
+    // So do the milestone prompts and the read-only milestone tab
+    if map_state.milestones.name_prompt.is_some() {
+        return Some(map_milestone_prompt_kh);
+    }
+    if map_state.milestones.picker.is_some() {
+        return Some(map_milestone_picker_kh);
+    }
+    if map_state.diff_view.is_some() {
+        return Some(map_diff_view_kh);
+    }
+    if map_state.ui_state.snapshot_picker.is_some() {
+        return Some(map_snapshot_picker_kh);
+    }
+    if map_state.trash.browser.is_some() {
+        return Some(map_trash_kh);
+    }
+    if map_state.ui_state.connection_table.is_some() {
+        return Some(map_connection_table_kh);
+    }
+    if map_state.milestones.showing_milestone {
+        return Some(map_milestone_tab_kh);
+    }
+    if map_state.layers_state.panel.is_some() {
+        return Some(map_layer_panel_kh);
+    }
+    if map_state.ui_state.workspace_picker.is_some() {
+        return Some(map_workspace_picker_kh);
+    }
+    if map_state.ui_state.workspace_search.is_some() {
+        return Some(map_workspace_search_kh);
+    }
+
+    None
+}
@@ -0,0 +1,87 @@
+use crossterm::{
+    event::{
+        DisableBracketedPaste, EnableBracketedPaste, KeyCode, KeyEvent, KeyEventKind, KeyModifiers,
+        KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    execute,
+    terminal::supports_keyboard_enhancement,
+};
+use std::{
+    io::stdout,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Set while the terminal reports keys with the flags pushed by
+/// [`enable_keyboard_enhancement`]
+static ENHANCED: AtomicBool = AtomicBool::new(false);
+
+/// Asks the terminal to report keys with the kitty keyboard protocol, if it supports it:
+/// unambiguous escape codes, so e.g. Ctrl+Shift combinations and Esc can be told apart
+/// from other keys, and whether a key was pressed, repeated or released.
+///
+/// Returns whether the terminal supports it. Undone by [`disable_keyboard_enhancement`].
+pub fn enable_keyboard_enhancement() -> bool {
+    if !supports_keyboard_enhancement().unwrap_or(false) {
+        return false;
+    }
+    let pushed = execute!(
+        stdout(),
+        PushKeyboardEnhancementFlags(
+            KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+        )
+    )
+    .is_ok();
+    ENHANCED.store(pushed, Ordering::Relaxed);
+    pushed
+}
+
+/// Puts the terminal's key reporting back the way it was, if
+/// [`enable_keyboard_enhancement`] changed it. Also called on a crash, before the
+/// terminal is restored.
+pub fn disable_keyboard_enhancement() {
+    if ENHANCED.swap(false, Ordering::Relaxed) {
+        let _ = execute!(stdout(), PopKeyboardEnhancementFlags);
+    }
+}
+
+/// Asks the terminal to report pasted text as a whole instead of typing it in key by
+/// key. Undone by [`disable_bracketed_paste`].
+pub fn enable_bracketed_paste() {
+    let _ = execute!(stdout(), EnableBracketedPaste);
+}
+
+/// Has the terminal type pasted text in key by key again. Also called on a crash,
+/// before the terminal is restored.
+pub fn disable_bracketed_paste() {
+    let _ = execute!(stdout(), DisableBracketedPaste);
+}
+
+/// Pasted text with its line breaks turned into `\n`, whichever kind the clipboard had.
+pub fn normalize_line_breaks(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// The key presses that type in pasted text, for where a paste isn't inserted as a
+/// whole: line breaks are Enter and tabs Tab, the way they arrived before the terminal
+/// reported pastes.
+pub fn paste_keys(text: &str) -> Vec<KeyEvent> {
+    normalize_line_breaks(text)
+        .chars()
+        .map(|c| {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                '\t' => KeyCode::Tab,
+                c => KeyCode::Char(c),
+            };
+            KeyEvent::new(code, KeyModifiers::NONE)
+        })
+        .collect()
+}
+
+/// Whether a key event is handled as a key press. Held keys are reported as repeats
+/// once the terminal reports event types, and act like pressing the key again, e.g. to
+/// keep panning; releases aren't bound to anything.
+pub fn acts_as_press(kind: KeyEventKind) -> bool {
+    matches!(kind, KeyEventKind::Press | KeyEventKind::Repeat)
+}
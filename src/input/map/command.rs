@@ -0,0 +1,38 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    input::AppAction,
+    states::{MapState, map::Mode},
+};
+
+/// Handles typing on the `:` command line. Intercepts all input while it's shown.
+///
+/// It's opened with `:` in Normal or Visual mode, where the selection is kept for
+/// commands such as `:export selection`. Enter runs the command, Esc (or Backspace on an empty line) closes the line.
+pub fn map_command_line_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    if let Some(command) = map_state.ui_state.command_line.as_mut() {
+        match key.code {
+            KeyCode::Esc => map_state.ui_state.command_line = None,
+            KeyCode::Enter => {
+                let command = map_state.ui_state.command_line.take().unwrap_or_default();
+                map_state.run_command(&command);
+                // Opened from Visual mode, the command may have replaced the notes (e.g.
+                // by loading a snapshot), leaving no note to stay in Visual mode on
+                if map_state.mode == Mode::Visual
+                    && map_state.notes_state.selected_note_id().is_none()
+                {
+                    map_state.mode = Mode::Normal;
+                }
+            }
+            KeyCode::Backspace if command.is_empty() => map_state.ui_state.command_line = None,
+            KeyCode::Backspace => {
+                command.pop();
+            }
+            KeyCode::Char(c) => command.push(c),
+            _ => {}
+        }
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
@@ -0,0 +1,35 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    input::{AppAction, map::cycle_color},
+    states::MapState,
+};
+
+/// Handles the connection table (`:connections`). Intercepts all input while it's shown.
+pub fn map_connection_table_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    let Some(table) = map_state.ui_state.connection_table.as_mut() else {
+        return AppAction::Continue;
+    };
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => map_state.ui_state.connection_table = None,
+        KeyCode::Char('j') | KeyCode::Down => table.select_next(),
+        KeyCode::Char('k') | KeyCode::Up => table.select_previous(),
+        KeyCode::Char(' ') => table.toggle_mark(),
+        KeyCode::Char('a') => table.toggle_mark_all(),
+        KeyCode::Char('s') => map_state.sort_connection_table(false),
+        KeyCode::Char('S') => map_state.sort_connection_table(true),
+        KeyCode::Char('d') => map_state.delete_table_connections(),
+        // The highlighted connection's next color, for all of them
+        KeyCode::Char('e') => {
+            if let Some(index) = table.selected_connection() {
+                let color = cycle_color(map_state.connections_state.connections()[index].color);
+                map_state.recolor_table_connections(color);
+            }
+        }
+        _ => {}
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
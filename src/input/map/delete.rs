@@ -5,19 +5,14 @@ use crate::{
     states::{MapState, map::Mode},
 };
 
+/// Handles Delete mode once its confirmation dialog is gone, see
+/// [`MapState::confirm_delete`]: Esc goes back to Visual mode, `d` asks again.
 pub fn map_delete_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
     match key.code {
         KeyCode::Esc => {
             map_state.mode = Mode::Visual;
         }
-        KeyCode::Char('d') => {
-            let selected_note_id = map_state.notes_state.expect_selected_note_id();
-
-            map_state.persistence.mark_dirty();
-            map_state.notes_state.remove(selected_note_id);
-            map_state.connections_state.remove_note(selected_note_id);
-            map_state.mode = Mode::Normal;
-        }
+        KeyCode::Char('d') => map_state.confirm_delete(),
         _ => {}
     }
 
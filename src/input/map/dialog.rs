@@ -0,0 +1,51 @@
+use crossterm::event::KeyEvent;
+
+use crate::{
+    app::Screen,
+    input::{AppAction, dialog_kh},
+    states::{
+        MapState, SettingsState, StartState,
+        map::{DiscardMenuType, MapDialogAction, Mode},
+    },
+    utils::FileSystem,
+};
+
+/// Handles keyboard input while a dialog is shown over the map, carrying out the action
+/// of the button chosen.
+pub fn map_dialog_kh(map_state: &mut MapState, key: KeyEvent, fs: &dyn FileSystem) -> AppAction {
+    let Some(dialog) = &mut map_state.ui_state.dialog else {
+        return AppAction::Continue;
+    };
+    let Some(action) = dialog_kh(dialog, key) else {
+        // Moving the focus
+        map_state.clear_and_redraw();
+        return AppAction::Continue;
+    };
+
+    map_state.ui_state.close_dialog();
+    match action {
+        MapDialogAction::Cancel => {
+            if map_state.mode == Mode::Delete {
+                map_state.mode = Mode::Visual;
+            }
+        }
+        MapDialogAction::Discard(DiscardMenuType::Start) => {
+            return AppAction::Switch(Screen::Start(StartState::new_with_fs(fs)));
+        }
+        MapDialogAction::Discard(DiscardMenuType::Settings) => {
+            // Preserve file path to return to after closing settings
+            return AppAction::Switch(Screen::Settings(SettingsState::new_with_fs(
+                map_state.persistence.file_write_path.clone(),
+                fs,
+            )));
+        }
+        MapDialogAction::DeleteNotes => map_state.delete_selected_note(),
+        MapDialogAction::DeleteKeepingConnections => {
+            map_state.delete_selected_note_keeping_connections()
+        }
+        MapDialogAction::EmptyTrash => map_state.empty_trash(),
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
@@ -0,0 +1,23 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    input::{AppAction, map::scroll_viewport},
+    states::MapState,
+};
+
+/// Handles input while a diff view is shown. Intercepts all input.
+///
+/// Only scrolling and closing the view are possible.
+pub fn map_diff_view_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => map_state.diff_view = None,
+        _ => {
+            if let Some(diff_view) = map_state.diff_view.as_mut() {
+                scroll_viewport(diff_view.map_state.as_mut(), key);
+            }
+        }
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
@@ -2,7 +2,7 @@ use std::io::stdout;
 
 use crossterm::{
     cursor::SetCursorStyle,
-    event::{KeyCode, KeyEvent},
+    event::{KeyCode, KeyEvent, KeyModifiers},
     execute,
 };
 
@@ -13,10 +13,13 @@ use crate::{
             append, backspace_char, cursor_pos_beginning, cursor_pos_end, insert_char,
             jump_back_a_word, jump_forward_a_word, move_cursor_down, move_cursor_left,
             move_cursor_right, move_cursor_right_norm, move_cursor_up, remove_char,
-            switch_to_modal_insert_mode, switch_to_modal_normal_mode,
+            switch_to_modal_insert_mode, switch_to_modal_normal_mode, toggle_checkbox_at_cursor,
         },
     },
-    states::{MapState, map::Mode},
+    states::{
+        MapState,
+        map::{Mode, NoteSearch},
+    },
 };
 
 /// Handles keyboard input for Edit mode.
@@ -31,6 +34,7 @@ pub fn map_edit_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
                         Mode::Edit => {
                             cursor_pos_beginning(&mut map_state.notes_state);
                             map_state.notes_state.deselect();
+                            map_state.ui_state.note_search = None;
 
                             let _ = execute!(stdout(), SetCursorStyle::SteadyBar);
                             map_state.mode = Mode::Normal;
@@ -45,9 +49,20 @@ pub fn map_edit_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
                     }
                 }
 
+                KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    open_note_search(map_state)
+                }
+                KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    map_state.cycle_note_matches(true)
+                }
+                KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    map_state.cycle_note_matches(false)
+                }
                 KeyCode::Char(c) => insert_char(map_state, c),
                 KeyCode::Enter => insert_char(map_state, '\n'),
                 KeyCode::Backspace => backspace_char(map_state),
+                KeyCode::Tab => toggle_checkbox_at_cursor(map_state),
+                KeyCode::F(7) => map_state.cycle_spelling_suggestion(),
                 KeyCode::Left => move_cursor_left(&mut map_state.notes_state),
                 KeyCode::Right => move_cursor_right(&mut map_state.notes_state),
                 KeyCode::Up => move_cursor_up(&mut map_state.notes_state),
@@ -59,6 +74,7 @@ pub fn map_edit_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
             KeyCode::Esc => {
                 cursor_pos_beginning(&mut map_state.notes_state);
                 map_state.notes_state.deselect();
+                map_state.ui_state.note_search = None;
 
                 let _ = execute!(stdout(), SetCursorStyle::SteadyBar);
                 map_state.mode = Mode::Normal;
@@ -74,6 +90,11 @@ pub fn map_edit_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
             KeyCode::Char('b') => jump_back_a_word(&mut map_state.notes_state),
             KeyCode::Char('a') => append(map_state),
             KeyCode::Char('x') => remove_char(map_state),
+            KeyCode::Char('/') => open_note_search(map_state),
+            KeyCode::Char('n') => map_state.cycle_note_matches(true),
+            KeyCode::Char('N') => map_state.cycle_note_matches(false),
+            KeyCode::Tab => toggle_checkbox_at_cursor(map_state),
+            KeyCode::F(7) => map_state.cycle_spelling_suggestion(),
             _ => {}
         },
         _ => unreachable!("Bug: shouldn't call Edit key handling when not in Edit Mode"),
@@ -82,3 +103,9 @@ pub fn map_edit_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
     map_state.clear_and_redraw();
     AppAction::Continue
 }
+
+/// Starts typing a search within the note being edited, from the cursor.
+fn open_note_search(map_state: &mut MapState) {
+    let cursor = map_state.notes_state.cursor_pos();
+    map_state.ui_state.note_search = Some(NoteSearch::new(cursor));
+}
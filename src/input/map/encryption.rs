@@ -0,0 +1,21 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{input::AppAction, states::MapState};
+
+/// Handles typing into the passphrase prompt. Intercepts all input while the prompt is shown.
+pub fn map_passphrase_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    if let Some(prompt) = map_state.ui_state.passphrase_prompt.as_mut() {
+        match key.code {
+            KeyCode::Esc => map_state.ui_state.passphrase_prompt = None,
+            KeyCode::Enter => map_state.submit_passphrase(),
+            KeyCode::Backspace => {
+                prompt.input.pop();
+            }
+            KeyCode::Char(c) => prompt.input.push(c),
+            _ => {}
+        }
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
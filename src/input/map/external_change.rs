@@ -0,0 +1,45 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{input::AppAction, states::MapState, utils::MergeChoice};
+
+/// Handles the choice after the map file was changed by another program. Intercepts all
+/// input while the prompt is shown.
+pub fn map_external_change_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    let Some(change) = map_state.persistence.external_change.as_mut() else {
+        return AppAction::Continue;
+    };
+
+    // Resolving merge conflicts
+    if let Some(merge) = &change.merge {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down
+                if change.selected_conflict + 1 < merge.conflicts.len() =>
+            {
+                change.selected_conflict += 1;
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                change.selected_conflict = change.selected_conflict.saturating_sub(1);
+            }
+            KeyCode::Char('m') => map_state.choose_merge_version(MergeChoice::Mine),
+            KeyCode::Char('t') => map_state.choose_merge_version(MergeChoice::Theirs),
+            KeyCode::Enter => map_state.finish_merge(),
+            KeyCode::Esc => change.merge = None,
+            _ => {}
+        }
+
+        map_state.clear_and_redraw();
+        return AppAction::Continue;
+    }
+
+    match key.code {
+        KeyCode::Char('r') => map_state.reload_external_change(),
+        KeyCode::Char('k') => map_state.keep_local_over_external_change(),
+        KeyCode::Char('m') => map_state.merge_external_change(),
+        KeyCode::Char('d') => change.show_diff = !change.show_diff,
+        KeyCode::Esc => change.show_diff = false,
+        _ => {}
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
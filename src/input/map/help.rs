@@ -0,0 +1,59 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    input::AppAction,
+    states::{
+        MapState,
+        map::{HELP_PAGES, search_help},
+    },
+};
+
+/// Handles keyboard input while the help screen is shown, which intercepts all input.
+///
+/// `/` searches the key bindings of every page; Enter opens the page of the first match.
+pub fn map_help_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    if let Some(query) = &mut map_state.ui_state.help_search {
+        match key.code {
+            KeyCode::Esc => map_state.ui_state.help_search = None,
+            KeyCode::Enter => {
+                let locale = map_state.settings.locale();
+                if let Some(first) = search_help(locale, query).first() {
+                    let page = first.page;
+                    map_state.ui_state.help_search = None;
+                    map_state.ui_state.show_help(page);
+                }
+            }
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            KeyCode::Char(c) => query.push(c),
+            _ => {}
+        }
+    } else {
+        match key.code {
+            KeyCode::F(1) | KeyCode::Char('?') | KeyCode::Esc => map_state.ui_state.hide_help(),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => help_next_page(map_state),
+            KeyCode::Left | KeyCode::Char('h') => help_previous_page(map_state),
+            KeyCode::Char('/') => map_state.ui_state.help_search = Some(String::new()),
+            _ => {}
+        }
+    }
+
+    map_state.clear_and_redraw();
+
+    AppAction::Continue
+}
+
+/// Cycles to the next help page, from the last one back to the first
+pub fn help_next_page(map_state: &mut MapState) {
+    if let Some(current_page) = &mut map_state.ui_state.help_screen {
+        *current_page = *current_page % HELP_PAGES.len() + 1;
+    }
+}
+
+/// Cycles to the previous help page, from the first one to the last
+pub fn help_previous_page(map_state: &mut MapState) {
+    if let Some(current_page) = &mut map_state.ui_state.help_screen {
+        *current_page = (*current_page + HELP_PAGES.len() - 2) % HELP_PAGES.len() + 1;
+    }
+}
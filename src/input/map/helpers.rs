@@ -1,127 +1,160 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::style::Color;
 use std::cmp::Reverse;
 
 use crate::states::{
     MapState,
-    map::{Mode, Side},
+    map::{Mode, RepeatableAction, Side},
 };
 
-/// Cycles to the next help page (1→2→3→4→5→1)
-pub fn help_next_page(map_state: &mut MapState) {
-    if let Some(current_page) = &mut map_state.ui_state.help_screen {
-        map_state.ui_state.help_screen = Some(match current_page {
-            1 => 2,
-            2 => 3,
-            3 => 4,
-            4 => 5,
-            5 => 1,
-            _ => unreachable!(),
-        });
-    }
-}
-
-/// Cycles to the previous help page (1→5→4→3→2→1)
-pub fn help_previous_page(map_state: &mut MapState) {
-    if let Some(current_page) = &mut map_state.ui_state.help_screen {
-        map_state.ui_state.help_screen = Some(match current_page {
-            1 => 5,
-            2 => 1,
-            3 => 2,
-            4 => 3,
-            5 => 4,
-            _ => unreachable!(),
-        });
-    }
-}
-
-/// Moves the viewport by a specified amount along the x or y axis.
-/// Uses saturating subtraction to prevent underflow when moving in negative direction.
+/// Moves the viewport by a specified amount along the x or y axis. The canvas extends
+/// in all directions, so the view can move past the origin.
 pub fn move_viewport(map_state: &mut MapState, axis: &str, amount: isize) {
     match axis {
-        "x" => {
-            if amount > 0 {
-                map_state.viewport.view_pos.x += amount as usize;
-            } else {
-                map_state.viewport.view_pos.x = map_state
-                    .viewport
-                    .view_pos
-                    .x
-                    .saturating_sub(amount.abs() as usize);
-            }
-        }
-        "y" => {
-            if amount > 0 {
-                map_state.viewport.view_pos.y += amount as usize;
-            } else {
-                map_state.viewport.view_pos.y = map_state
-                    .viewport
-                    .view_pos
-                    .y
-                    .saturating_sub(amount.abs() as usize);
-            }
-        }
+        "x" => map_state.viewport.view_pos.x += amount,
+        "y" => map_state.viewport.view_pos.y += amount,
         _ => {}
     }
 
     map_state.persistence.mark_dirty();
 }
 
+/// Scrolls the viewport with the Normal mode keys (h/j/k/l, arrows, with Shift by 5),
+/// for read-only views such as an open milestone. Other keys are ignored.
+pub fn scroll_viewport(map_state: &mut MapState, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('h') => move_viewport(map_state, "x", -1),
+        KeyCode::Left if key.modifiers == KeyModifiers::NONE => move_viewport(map_state, "x", -1),
+        KeyCode::Char('H') => move_viewport(map_state, "x", -5),
+        KeyCode::Left if key.modifiers == KeyModifiers::SHIFT => move_viewport(map_state, "x", -5),
+        KeyCode::Char('j') => move_viewport(map_state, "y", 1),
+        KeyCode::Down if key.modifiers == KeyModifiers::NONE => move_viewport(map_state, "y", 1),
+        KeyCode::Char('J') => move_viewport(map_state, "y", 5),
+        KeyCode::Down if key.modifiers == KeyModifiers::SHIFT => move_viewport(map_state, "y", 5),
+        KeyCode::Char('k') => move_viewport(map_state, "y", -1),
+        KeyCode::Up if key.modifiers == KeyModifiers::NONE => move_viewport(map_state, "y", -1),
+        KeyCode::Char('K') => move_viewport(map_state, "y", -5),
+        KeyCode::Up if key.modifiers == KeyModifiers::SHIFT => move_viewport(map_state, "y", -5),
+        KeyCode::Char('l') => move_viewport(map_state, "x", 1),
+        KeyCode::Right if key.modifiers == KeyModifiers::NONE => move_viewport(map_state, "x", 1),
+        KeyCode::Char('L') => move_viewport(map_state, "x", 5),
+        KeyCode::Right if key.modifiers == KeyModifiers::SHIFT => move_viewport(map_state, "x", 5),
+        _ => {}
+    }
+}
+
 /// Moves the selected note and automatically pans the viewport to keep it visible.
 ///
-/// Viewport follows the note when it would move beyond screen edges, creating a
-/// smooth panning effect. Uses saturating subtraction to prevent coordinate underflow.
+/// Viewport follows the note once it comes within the scroll margin (see
+/// `Settings::scroll_margin`) of a screen edge, creating a smooth panning effect. Notes
+/// can move past the origin, the canvas extends in all directions.
+///
+/// The rest of a multi-selection moves along by the same amount, and notes that
+/// end up inside an auto-tagging region get its tag.
 ///
 /// # Panics
 /// If no note is selected.
 pub fn move_note(map_state: &mut MapState, axis: &str, amount: isize) {
+    let previous_centers: Vec<(usize, (isize, isize))> = map_state
+        .notes_state
+        .selection()
+        .into_iter()
+        .filter_map(|id| {
+            let note = map_state.notes_state.notes().get(&id)?;
+            Some((id, note.center()))
+        })
+        .collect();
+
+    if map_state.notes_state.has_multi_selection() {
+        let selected_note_id = map_state.notes_state.expect_selected_note_id();
+        let selection = map_state.notes_state.selection();
+
+        for id in selection.into_iter().filter(|&id| id != selected_note_id) {
+            if let Some(note) = map_state.notes_state.get_mut(id) {
+                match axis {
+                    "x" => note.x += amount,
+                    "y" => note.y += amount,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let margin = map_state.settings.scroll_margin;
     let note = map_state.notes_state.expect_selected_note_mut();
     let (note_width, note_height) = note.get_dimensions();
 
     match axis {
         "x" => {
-            if amount > 0 {
-                note.x += amount as usize;
-                if note.x + note_width as usize
-                    > map_state.viewport.view_pos.x + map_state.viewport.screen_width
-                {
-                    map_state.viewport.view_pos.x += amount as usize;
-                }
-            } else {
-                note.x = note.x.saturating_sub(amount.abs() as usize);
-                if note.x < map_state.viewport.view_pos.x {
-                    map_state.viewport.view_pos.x = map_state
-                        .viewport
-                        .view_pos
-                        .x
-                        .saturating_sub(amount.abs() as usize);
-                }
-            }
+            note.x += amount;
+            let x = note.x;
+            map_state
+                .viewport
+                .follow_horizontally(x, note_width as usize, amount, margin);
         }
         "y" => {
-            if amount > 0 {
-                note.y += amount as usize;
-                // Account for bottom info bar (3 lines) when checking visibility
-                if note.y as isize + note_height as isize
-                    > map_state.viewport.view_pos.y as isize
-                        + map_state.viewport.screen_height as isize
-                        - 3
-                {
-                    map_state.viewport.view_pos.y += amount as usize;
-                }
-            } else {
-                note.y = note.y.saturating_sub(amount.abs() as usize);
-                if note.y < map_state.viewport.view_pos.y {
-                    map_state.viewport.view_pos.y = map_state
-                        .viewport
-                        .view_pos
-                        .y
-                        .saturating_sub(amount.abs() as usize);
-                }
-            }
+            note.y += amount;
+            let y = note.y;
+            map_state
+                .viewport
+                .follow_vertically(y, note_height as usize, amount, margin);
         }
         _ => {}
     }
 
+    match axis {
+        "x" => map_state.repeat.track_move(amount, 0),
+        "y" => map_state.repeat.track_move(0, amount),
+        _ => {}
+    }
+
+    map_state.tag_notes_entering_regions(&previous_centers);
+    map_state.persistence.mark_dirty();
+}
+
+/// Applies the last repeatable action again (`.` key).
+///
+/// Actions that work on a note (recolor, move, delete) apply to the currently
+/// selected note and do nothing when there is no selection.
+pub fn repeat_last_action(map_state: &mut MapState) {
+    let Some(action) = map_state.repeat.last_action else {
+        return;
+    };
+
+    if action != RepeatableAction::AddNote && map_state.notes_state.selected_note_id().is_none() {
+        return;
+    }
+    if matches!(
+        action,
+        RepeatableAction::Move { .. } | RepeatableAction::Delete
+    ) && map_state.selection_is_locked()
+    {
+        return;
+    }
+
+    match action {
+        RepeatableAction::AddNote => map_state.add_note(),
+        RepeatableAction::Recolor => recolor_selection(map_state),
+        RepeatableAction::Move { dx, dy } => {
+            if dx != 0 {
+                move_note(map_state, "x", dx);
+            }
+            if dy != 0 {
+                move_note(map_state, "y", dy);
+            }
+        }
+        RepeatableAction::Delete => map_state.delete_selected_note(),
+    }
+}
+
+/// Cycles the selected note's color and gives every other multi-selected note the same color.
+pub fn recolor_selection(map_state: &mut MapState) {
+    let color = cycle_color(map_state.notes_state.expect_selected_note().color);
+    for id in map_state.notes_state.selection() {
+        if let Some(note) = map_state.notes_state.get_mut(id) {
+            note.color = color;
+        }
+    }
     map_state.persistence.mark_dirty();
 }
 
@@ -146,8 +179,8 @@ pub fn switch_notes_focus(map_state: &mut MapState, key: &str) {
     let candidate_ids: Vec<usize> = notes
         .iter()
         .filter(|(id, note)| {
-            let dx = (note.x as isize - selected_note.x as isize).abs();
-            let dy = (note.y as isize - selected_note.y as isize).abs();
+            let dx = note.x.abs_diff(selected_note.x);
+            let dy = note.y.abs_diff(selected_note.y);
 
             // Cone of selection: primary axis distance must exceed secondary axis distance
             let is_in_direction = match key {
@@ -167,55 +200,58 @@ pub fn switch_notes_focus(map_state: &mut MapState, key: &str) {
     let closest_note_id_option = match key {
         "j" | "Down" => candidate_ids.iter().min_by_key(|&&id| {
             let note = &notes[&id];
-            let x_dist = (note.x as isize - selected_note.x as isize).abs() as usize;
+            let x_dist = note.x.abs_diff(selected_note.x);
             (note.y, x_dist)
         }),
         "k" | "Up" => candidate_ids.iter().max_by_key(|&&id| {
             let note = &notes[&id];
-            let x_dist = (note.x as isize - selected_note.x as isize).abs() as usize;
+            let x_dist = note.x.abs_diff(selected_note.x);
             (note.y, Reverse(x_dist))
         }),
         "l" | "Right" => candidate_ids.iter().min_by_key(|&&id| {
             let note = &notes[&id];
-            let y_dist = (note.y as isize - selected_note.y as isize).abs() as usize;
+            let y_dist = note.y.abs_diff(selected_note.y);
             (note.x, y_dist)
         }),
         "h" | "Left" => candidate_ids.iter().max_by_key(|&&id| {
             let note = &notes[&id];
-            let y_dist = (note.y as isize - selected_note.y as isize).abs() as usize;
+            let y_dist = note.y.abs_diff(selected_note.y);
             (note.x, Reverse(y_dist))
         }),
         _ => None,
     };
 
     if let Some(&id) = closest_note_id_option {
-        map_state.notes_state.deselect();
+        focus_note(map_state, id);
+    }
+}
 
-        map_state.notes_state.select(id);
+/// Selects another note instead of the selected one and centers the viewport on it.
+/// In Visual (Connection) mode the note becomes the end of the focused connection.
+///
+/// # Panics
+/// If no note is selected.
+pub fn focus_note(map_state: &mut MapState, id: usize) {
+    map_state.notes_state.deselect();
 
-        if let Some(note) = map_state.notes_state.notes().get(&id) {
-            map_state.viewport.view_pos.x =
-                note.x.saturating_sub(map_state.viewport.screen_width / 2);
-            map_state.viewport.view_pos.y =
-                note.y.saturating_sub(map_state.viewport.screen_height / 2);
-        }
+    map_state.notes_state.select(id);
 
-        // Update connection endpoint if in visual connection mode
-        if map_state.mode == Mode::VisualConnect {
-            if let Some(focused_connection) =
-                map_state.connections_state.focused_connection.as_mut()
-            {
-                // Prevent self-connections
-                if id == focused_connection.from_id {
-                    focused_connection.to_id = None;
-                    focused_connection.to_side = None;
-                } else {
-                    focused_connection.to_id = Some(id);
-                    focused_connection.to_side = Some(map_state.settings.default_end_side);
-                }
+    if let Some(note) = map_state.notes_state.notes().get(&id) {
+        map_state.viewport.center_on(note.x, note.y);
+    }
 
-                map_state.persistence.mark_dirty();
-            }
+    // Update connection endpoint if in visual connection mode
+    if map_state.mode == Mode::VisualConnect {
+        if let Some(focused_connection) = map_state.connections_state.focused_connection.as_mut() {
+            // A connection back to the note it starts at loops out of the same side
+            let to_side = if id == focused_connection.from_id {
+                focused_connection.from_side
+            } else {
+                map_state.settings.default_end_side
+            };
+            focused_connection.to = Some((id, to_side));
+
+            map_state.persistence.mark_dirty();
         }
     }
 }
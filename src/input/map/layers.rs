@@ -0,0 +1,66 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    input::AppAction,
+    states::{
+        MapState,
+        map::{LayerPanel, LayersState},
+    },
+};
+
+/// Handles the layer panel. Intercepts all input while the panel is shown.
+pub fn map_layer_panel_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    let layers_state = &mut map_state.layers_state;
+    let Some(panel) = layers_state.panel.as_mut() else {
+        return AppAction::Continue;
+    };
+    let selected = panel.selected;
+
+    if panel.editing_name {
+        match key.code {
+            KeyCode::Enter | KeyCode::Esc => panel.editing_name = false,
+            KeyCode::Backspace => edit_name(layers_state, selected, |name| {
+                name.pop();
+            }),
+            KeyCode::Char(c) => edit_name(layers_state, selected, |name| name.push(c)),
+            _ => {}
+        }
+        map_state.persistence.mark_dirty();
+        map_state.clear_and_redraw();
+        return AppAction::Continue;
+    }
+
+    match key.code {
+        KeyCode::Esc => layers_state.panel = None,
+        KeyCode::Char('j') | KeyCode::Down => layers_state.select_next(),
+        KeyCode::Char('k') | KeyCode::Up => layers_state.select_previous(),
+        KeyCode::Enter => layers_state.active = selected,
+        KeyCode::Char('v') => {
+            layers_state.toggle_hidden(selected);
+            map_state.persistence.mark_dirty();
+        }
+        KeyCode::Char('l') => {
+            layers_state.toggle_locked(selected);
+            map_state.persistence.mark_dirty();
+        }
+        KeyCode::Char('a') => {
+            let index = layers_state.add(String::new());
+            layers_state.panel = Some(LayerPanel {
+                selected: index,
+                editing_name: true,
+            });
+            map_state.persistence.mark_dirty();
+        }
+        KeyCode::Char('r') => panel.editing_name = true,
+        _ => {}
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
+
+fn edit_name(layers_state: &mut LayersState, index: usize, edit: impl FnOnce(&mut String)) {
+    if let Some(layer) = layers_state.get_mut(index) {
+        edit(&mut layer.name);
+    }
+}
@@ -0,0 +1,66 @@
+use chrono::Local;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    input::{AppAction, map::scroll_viewport},
+    states::{MapState, map::Notification},
+};
+
+/// Handles typing a new milestone's name. Intercepts all input while the prompt is shown.
+pub fn map_milestone_prompt_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    if let Some(name) = map_state.milestones.name_prompt.as_mut() {
+        match key.code {
+            KeyCode::Esc => map_state.milestones.name_prompt = None,
+            KeyCode::Enter => {
+                let name = map_state.milestones.name_prompt.take().unwrap_or_default();
+                let notification = match map_state.create_milestone(&name, Local::now()) {
+                    Ok(_) => Notification::MilestoneSuccess,
+                    Err(_) => Notification::MilestoneFail,
+                };
+                map_state.ui_state.set_notification(notification);
+            }
+            KeyCode::Backspace => {
+                name.pop();
+            }
+            KeyCode::Char(c) => name.push(c),
+            _ => {}
+        }
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
+
+/// Handles the milestone list. Intercepts all input while the list is shown.
+pub fn map_milestone_picker_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    if let Some(picker) = map_state.milestones.picker.as_mut() {
+        match key.code {
+            KeyCode::Esc => map_state.milestones.picker = None,
+            KeyCode::Char('j') | KeyCode::Down => picker.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => picker.select_previous(),
+            KeyCode::Enter => map_state.open_selected_milestone(),
+            _ => {}
+        }
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
+
+/// Handles input while the read-only milestone tab is shown.
+///
+/// Only scrolling and switching or closing the tab are possible.
+pub fn map_milestone_tab_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    match key.code {
+        KeyCode::Tab | KeyCode::Esc => map_state.milestones.switch_tab(),
+        KeyCode::Char('x') => map_state.milestones.close(),
+        _ => {
+            if let Some(open) = map_state.milestones.open.as_mut() {
+                scroll_viewport(open.map_state.as_mut(), key);
+            }
+        }
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
@@ -1,17 +1,47 @@
+mod command;
+mod connection_table;
 mod delete;
+mod dialog;
+mod diff_view;
 mod edit;
+mod encryption;
+mod external_change;
+mod help;
 mod helpers;
+mod layers;
+mod milestones;
 mod normal;
+mod region;
+mod search;
+mod snapshots;
 #[cfg(test)]
 mod tests;
 mod text_editing;
+mod title;
+mod trash;
 mod vim;
 mod visual;
+mod workspace;
 
+pub use command::*;
+pub use connection_table::*;
 pub use delete::*;
+pub use dialog::*;
+pub use diff_view::*;
 pub use edit::*;
+pub use encryption::*;
+pub use external_change::*;
+pub use help::*;
 pub use helpers::*;
+pub use layers::*;
+pub use milestones::*;
 pub use normal::*;
+pub use region::*;
+pub use search::*;
+pub use snapshots::*;
 pub use text_editing::*;
+pub use title::*;
+pub use trash::*;
 pub use vim::*;
 pub use visual::*;
+pub use workspace::*;
@@ -4,75 +4,54 @@ use crate::{
     app::Screen,
     input::{
         AppAction,
-        map::{help_next_page, help_previous_page, move_viewport},
+        map::{move_viewport, repeat_last_action},
     },
-    states::{MapState, SettingsState, StartState, map::DiscardMenuType},
-    utils::FileSystem,
+    states::{
+        BackupsState, MapState, SettingsState, StartState,
+        map::{DiscardMenuType, Notification, SearchState, help_page_for_mode},
+    },
+    utils::{FileSystem, finish_background_save, read_clipboard_text},
 };
 
 /// Handles keyboard input for Normal Mode in the Map Screen.
-///
-/// Help and discard confirmation menus take priority and intercept all input when shown.
 pub fn map_normal_kh(map_state: &mut MapState, key: KeyEvent, fs: &dyn FileSystem) -> AppAction {
-    // Help menu intercepts all input when visible
-    if map_state.ui_state.is_help_visible() {
-        match key.code {
-            KeyCode::F(1) | KeyCode::Char('?') | KeyCode::Esc => map_state.ui_state.hide_help(),
-            KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => help_next_page(map_state),
-            KeyCode::Left | KeyCode::Char('h') => help_previous_page(map_state),
+    // Second key of a two-key command; any other key just cancels the first one
+    if let Some(first_key) = map_state.ui_state.pending_key.take() {
+        match (first_key, key.code) {
+            ('z', KeyCode::Char('f')) => map_state.fit_to_content(),
+            ('g', KeyCode::Char('g')) => map_state.go_to_origin(),
             _ => {}
         }
 
         map_state.clear_and_redraw();
-
-        return AppAction::Continue;
-    }
-
-    // Discard confirmation menu intercepts all input when triggered
-    if let Some(discard_menu_type) = &map_state.ui_state.confirm_discard_menu {
-        match key.code {
-            KeyCode::Esc => {
-                map_state.ui_state.hide_discard_menu();
-                map_state.clear_and_redraw();
-            }
-            KeyCode::Char('q') => {
-                match discard_menu_type {
-                    DiscardMenuType::Start => {
-                        return AppAction::Switch(Screen::Start(StartState::new_with_fs(fs)));
-                    }
-                    DiscardMenuType::Settings => {
-                        // Preserve file path to return to after closing settings
-                        return AppAction::Switch(Screen::Settings(SettingsState::new_with_fs(
-                            map_state.persistence.file_write_path.clone(),
-                            fs,
-                        )));
-                    }
-                }
-            }
-            _ => {}
-        }
-
         return AppAction::Continue;
     }
 
     match key.code {
         KeyCode::Char('q') => {
-            // Require saving or explicit confirmation before exiting
-            if !map_state.persistence.has_unsaved_changes {
+            // A save still being written has to succeed before the map counts as saved
+            finish_background_save(map_state, true);
+            // Require saving or explicit confirmation before exiting, except for the
+            // tutorial map which is thrown away
+            if !map_state.persistence.has_unsaved_changes || map_state.tutorial.is_some() {
                 return AppAction::Switch(Screen::Start(StartState::new_with_fs(fs)));
             } else {
-                map_state.ui_state.show_discard_menu(DiscardMenuType::Start);
-                map_state.clear_and_redraw();
+                map_state.show_discard_menu(DiscardMenuType::Start);
             }
         }
 
-        KeyCode::F(1) | KeyCode::Char('?') => map_state.ui_state.show_help(1),
+        KeyCode::F(1) | KeyCode::Char('?') => map_state
+            .ui_state
+            .show_help(help_page_for_mode(map_state.mode)),
 
         KeyCode::Char('s') => {
             return AppAction::SaveMapFile(map_state.persistence.file_write_path.clone());
         }
 
+        KeyCode::Char('W') => map_state.open_workspace_picker(),
+
         KeyCode::Char('o') => {
+            finish_background_save(map_state, true);
             // Require saving or explicit confirmation before opening settings
             if !map_state.persistence.has_unsaved_changes {
                 // Preserve file path to return to after closing settings
@@ -81,10 +60,7 @@ pub fn map_normal_kh(map_state: &mut MapState, key: KeyEvent, fs: &dyn FileSyste
                     fs,
                 )));
             } else {
-                map_state
-                    .ui_state
-                    .show_discard_menu(DiscardMenuType::Settings);
-                map_state.clear_and_redraw();
+                map_state.show_discard_menu(DiscardMenuType::Settings);
             }
         }
 
@@ -111,9 +87,49 @@ pub fn map_normal_kh(map_state: &mut MapState, key: KeyEvent, fs: &dyn FileSyste
         KeyCode::Right if key.modifiers == KeyModifiers::SHIFT => move_viewport(map_state, "x", 5),
 
         KeyCode::Char('a') => map_state.add_note(),
+        KeyCode::Char('.') => repeat_last_action(map_state),
+        KeyCode::Char('u') => map_state.undo(),
+
+        KeyCode::Char('R') => map_state.add_region(),
+        // Selects the region under the viewport center
+        KeyCode::Char('r') => map_state.select_region(),
+        KeyCode::Char('p') => map_state.paste_clipboard(read_clipboard_text()),
         // Selects the note closest to viewport center
         KeyCode::Char('v') => map_state.select_note(),
 
+        // Milestones are stored unencrypted, so they'd leak an encrypted map
+        KeyCode::Char('M') if map_state.persistence.file_key.is_some() => map_state
+            .ui_state
+            .set_notification(Notification::MilestoneEncrypted),
+        KeyCode::Char('M') => map_state.milestones.name_prompt = Some(String::new()),
+        KeyCode::Char('O') => match map_state.open_milestone_picker() {
+            Ok(_) => {}
+            Err(_) => map_state
+                .ui_state
+                .set_notification(Notification::MilestoneFail),
+        },
+        KeyCode::Tab => map_state.milestones.switch_tab(),
+        // The session moves along, so nothing needs saving first
+        KeyCode::Char('b') => {
+            return AppAction::Switch(Screen::Backups(BackupsState::new(map_state.take())));
+        }
+        KeyCode::Esc => {
+            map_state.ui_state.clear_path();
+            map_state.ui_state.search = None;
+        }
+        KeyCode::Char('i') => map_state.ui_state.show_stats = !map_state.ui_state.show_stats,
+        // Cycle search matches while a search is highlighted, orphaned notes otherwise
+        KeyCode::Char('n' | 'N') if map_state.ui_state.search.is_some() => {
+            map_state.cycle_search_matches(key.code == KeyCode::Char('n'))
+        }
+        KeyCode::Char('n') => map_state.focus_next_orphan(),
+        KeyCode::Char('/') => map_state.ui_state.search = Some(SearchState::new()),
+        KeyCode::Char('z') => map_state.ui_state.pending_key = Some('z'),
+        KeyCode::Char('g') => map_state.ui_state.pending_key = Some('g'),
+        KeyCode::Char('G') => map_state.go_to_content_end(),
+        KeyCode::Char(':') => map_state.ui_state.command_line = Some(String::new()),
+        KeyCode::Char('y') => map_state.layers_state.open_panel(),
+
         _ => {}
     }
 
@@ -0,0 +1,117 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    input::{AppAction, map::cycle_color},
+    states::{MapState, map::Mode},
+};
+
+/// Handles keyboard input for Region mode (editing a background region).
+///
+/// While the label is being typed, all character keys go to the label.
+pub fn map_region_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    if map_state.regions_state.editing_label {
+        match key.code {
+            KeyCode::Enter | KeyCode::Esc => map_state.regions_state.editing_label = false,
+            KeyCode::Backspace => {
+                if let Some(region) = map_state.regions_state.selected_region_mut() {
+                    region.label.pop();
+                    map_state.persistence.mark_dirty();
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(region) = map_state.regions_state.selected_region_mut() {
+                    region.label.push(c);
+                    map_state.persistence.mark_dirty();
+                }
+            }
+            _ => {}
+        }
+
+        map_state.clear_and_redraw();
+        return AppAction::Continue;
+    }
+
+    match key.code {
+        KeyCode::Esc => {
+            map_state.regions_state.deselect();
+            map_state.mode = Mode::Normal;
+        }
+
+        KeyCode::Char('r') => map_state.regions_state.editing_label = true,
+
+        KeyCode::Char('e') => {
+            if let Some(region) = map_state.regions_state.selected_region_mut() {
+                region.color = cycle_color(region.color);
+                map_state.persistence.mark_dirty();
+            }
+        }
+
+        KeyCode::Char('n') => map_state.regions_state.select_next(),
+
+        KeyCode::Char('s') => map_state.select_notes_in_region(),
+
+        KeyCode::Char('a') => {
+            map_state.arrange_region();
+        }
+
+        KeyCode::Char('o') => {
+            map_state.regions_state.arrange_order = map_state.regions_state.arrange_order.next();
+        }
+
+        KeyCode::Char('t') => {
+            if let Some(region) = map_state.regions_state.selected_region_mut() {
+                region.auto_tag = !region.auto_tag;
+                map_state.persistence.mark_dirty();
+            }
+        }
+
+        KeyCode::Char('d') => map_state.delete_selected_region(false),
+        KeyCode::Char('D') => map_state.delete_selected_region(true),
+
+        // hjkl / arrows move the region, Shift+arrows move it 5x faster
+        KeyCode::Char('h') => map_state.move_selected_region(-1, 0),
+        KeyCode::Left if key.modifiers == KeyModifiers::NONE => {
+            map_state.move_selected_region(-1, 0)
+        }
+        KeyCode::Left if key.modifiers == KeyModifiers::SHIFT => {
+            map_state.move_selected_region(-5, 0)
+        }
+        KeyCode::Char('j') => map_state.move_selected_region(0, 1),
+        KeyCode::Down if key.modifiers == KeyModifiers::NONE => {
+            map_state.move_selected_region(0, 1)
+        }
+        KeyCode::Down if key.modifiers == KeyModifiers::SHIFT => {
+            map_state.move_selected_region(0, 5)
+        }
+        KeyCode::Char('k') => map_state.move_selected_region(0, -1),
+        KeyCode::Up if key.modifiers == KeyModifiers::NONE => map_state.move_selected_region(0, -1),
+        KeyCode::Up if key.modifiers == KeyModifiers::SHIFT => {
+            map_state.move_selected_region(0, -5)
+        }
+        KeyCode::Char('l') => map_state.move_selected_region(1, 0),
+        KeyCode::Right if key.modifiers == KeyModifiers::NONE => {
+            map_state.move_selected_region(1, 0)
+        }
+        KeyCode::Right if key.modifiers == KeyModifiers::SHIFT => {
+            map_state.move_selected_region(5, 0)
+        }
+
+        // HJKL resize: H/L narrower/wider, K/J shorter/taller
+        KeyCode::Char('H') => resize_region(map_state, -2, 0),
+        KeyCode::Char('L') => resize_region(map_state, 2, 0),
+        KeyCode::Char('K') => resize_region(map_state, 0, -1),
+        KeyCode::Char('J') => resize_region(map_state, 0, 1),
+
+        _ => {}
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
+
+fn resize_region(map_state: &mut MapState, d_width: isize, d_height: isize) {
+    if let Some(region) = map_state.regions_state.selected_region_mut() {
+        region.resize(d_width, d_height);
+        map_state.persistence.mark_dirty();
+    }
+}
@@ -0,0 +1,102 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    input::{AppAction, map::focus_note},
+    states::{
+        MapState,
+        map::{Mode, Notification},
+    },
+};
+
+/// Handles typing the `/` search query. Intercepts all input while it's typed.
+///
+/// Every change highlights the matches and moves the view to the first one. Enter keeps
+/// the matches highlighted for `n`/`N`, Esc (or Backspace on an empty query) cancels.
+/// While connecting notes, Enter instead makes the note of the current match the end of
+/// the connection.
+pub fn map_search_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    if let Some(search) = map_state.ui_state.search.as_mut() {
+        match key.code {
+            KeyCode::Esc => map_state.ui_state.search = None,
+            KeyCode::Enter if search.query.is_empty() => map_state.ui_state.search = None,
+            KeyCode::Enter if map_state.mode == Mode::VisualConnect => {
+                let target = search.current.as_ref().map(|current| current.note_id);
+                map_state.ui_state.search = None;
+                match target {
+                    Some(id) => focus_note(map_state, id),
+                    None => map_state
+                        .ui_state
+                        .set_notification(Notification::PatternNotFound),
+                }
+            }
+            KeyCode::Enter => {
+                search.typing = false;
+                if search.current.is_none() {
+                    map_state.ui_state.search = None;
+                    map_state
+                        .ui_state
+                        .set_notification(Notification::PatternNotFound);
+                }
+            }
+            KeyCode::Backspace if search.query.is_empty() => map_state.ui_state.search = None,
+            KeyCode::Backspace => {
+                search.pop();
+                map_state.jump_to_search_match(true);
+            }
+            KeyCode::Char(c) => {
+                search.push(c);
+                map_state.jump_to_search_match(true);
+            }
+            _ => {}
+        }
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
+
+/// Handles typing the query of the search within the note being edited. Intercepts all
+/// input while it's typed.
+///
+/// Every change moves the cursor to the first match from where it was when the search
+/// was opened. Enter keeps the matches highlighted for `n`/`N` (`Ctrl+N`/`Ctrl+P`), Esc
+/// (or Backspace on an empty query) cancels and puts the cursor back.
+pub fn map_note_search_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    if let Some(note_search) = map_state.ui_state.note_search.as_mut() {
+        let origin = note_search.origin;
+        let search = &mut note_search.search;
+        match key.code {
+            KeyCode::Esc => {
+                map_state.ui_state.note_search = None;
+                map_state.notes_state.set_cursor_pos(origin);
+            }
+            KeyCode::Enter if search.query.is_empty() => map_state.ui_state.note_search = None,
+            KeyCode::Enter => {
+                search.typing = false;
+                if search.current.is_none() {
+                    map_state.ui_state.note_search = None;
+                    map_state
+                        .ui_state
+                        .set_notification(Notification::PatternNotFound);
+                }
+            }
+            KeyCode::Backspace if search.query.is_empty() => {
+                map_state.ui_state.note_search = None;
+                map_state.notes_state.set_cursor_pos(origin);
+            }
+            KeyCode::Backspace | KeyCode::Char(_) => {
+                match key.code {
+                    KeyCode::Char(c) => search.push(c),
+                    _ => search.pop(),
+                }
+                if !map_state.jump_to_note_match(origin, true) {
+                    map_state.notes_state.set_cursor_pos(origin);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
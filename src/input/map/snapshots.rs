@@ -0,0 +1,24 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{input::AppAction, states::MapState};
+
+/// Handles the snapshot list. Intercepts all input while the list is shown.
+///
+/// While the differences to a snapshot are shown, any key returns to the list.
+pub fn map_snapshot_picker_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    if let Some(picker) = map_state.ui_state.snapshot_picker.as_mut() {
+        match key.code {
+            _ if picker.diff.is_some() => picker.diff = None,
+            KeyCode::Esc => map_state.ui_state.snapshot_picker = None,
+            KeyCode::Char('j') | KeyCode::Down => picker.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => picker.select_previous(),
+            KeyCode::Enter => map_state.load_selected_snapshot(),
+            KeyCode::Char('d') => map_state.diff_selected_snapshot(),
+            KeyCode::Char('v') => map_state.view_selected_snapshot_diff(),
+            _ => {}
+        }
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
@@ -0,0 +1,1075 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::Color;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tempfile::tempdir;
+
+use crate::{
+    input::handler::map_kh,
+    states::{
+        MapState,
+        map::{Command, ConnectionSort, ExportFormat, ExportScope, Mode, NoteStatus, Notification},
+    },
+    utils::{
+        CommandInput, FileKey, NoteChange, NoteListFormat, Substitution, UserCommand,
+        list_snapshots, plugins_dir, save_map_file,
+        test_utils::{MockFileSystem, TempFileSystem},
+    },
+};
+
+fn create_test_map_state(map_path: &Path) -> MapState {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = MapState::new_with_fs(map_path.to_path_buf(), &mock_fs);
+    map_state.viewport.screen_width = 100;
+    map_state.viewport.screen_height = 50;
+    map_state
+}
+
+fn create_key_event(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn run_command(map_state: &mut MapState, command: &str) {
+    map_kh(map_state, create_key_event(KeyCode::Char(':')));
+    for c in command.chars() {
+        map_kh(map_state, create_key_event(KeyCode::Char(c)));
+    }
+    map_kh(map_state, create_key_event(KeyCode::Enter));
+}
+
+#[test]
+fn test_parse_commands() {
+    assert_eq!(
+        Command::parse("export mermaid"),
+        Ok(Command::Export(
+            ExportScope::Map,
+            ExportFormat::Mermaid,
+            None
+        ))
+    );
+    assert_eq!(
+        Command::parse("  export   mermaid /tmp/my map.mmd "),
+        Ok(Command::Export(
+            ExportScope::Map,
+            ExportFormat::Mermaid,
+            Some(PathBuf::from("/tmp/my map.mmd"))
+        ))
+    );
+    assert_eq!(
+        Command::parse("export svg slides.svg"),
+        Ok(Command::Export(
+            ExportScope::Map,
+            ExportFormat::Svg,
+            Some(PathBuf::from("slides.svg"))
+        ))
+    );
+    assert_eq!(
+        Command::parse("export dot"),
+        Ok(Command::Export(ExportScope::Map, ExportFormat::Dot, None))
+    );
+    assert_eq!(
+        Command::parse("export freemind"),
+        Ok(Command::Export(
+            ExportScope::Map,
+            ExportFormat::FreeMind,
+            None
+        ))
+    );
+    assert_eq!(
+        Command::parse("export branch opml notes/branch.opml"),
+        Ok(Command::Export(
+            ExportScope::Branch,
+            ExportFormat::Opml,
+            Some(PathBuf::from("notes/branch.opml"))
+        ))
+    );
+    assert_eq!(
+        Command::parse("export selection mm"),
+        Ok(Command::Export(
+            ExportScope::Selection,
+            ExportFormat::FreeMind,
+            None
+        ))
+    );
+    assert_eq!(
+        Command::parse("export selection"),
+        Err(String::from("export selection"))
+    );
+    assert_eq!(
+        Command::parse("import ~/outlines/book plan.opml"),
+        Ok(Command::Import(PathBuf::from("~/outlines/book plan.opml")))
+    );
+    assert_eq!(Command::parse("import"), Err(String::from("import")));
+    assert_eq!(
+        Command::parse("export pdf"),
+        Err(String::from("export pdf"))
+    );
+    assert_eq!(Command::parse("export"), Err(String::from("export")));
+    assert_eq!(Command::parse("wq"), Err(String::from("wq")));
+    assert_eq!(
+        Command::parse("%s/old name/new/g"),
+        Ok(Command::Substitute(Substitution {
+            pattern: String::from("old name"),
+            replacement: String::from("new"),
+            global: true,
+            regex: false,
+            ignore_case: false,
+        }))
+    );
+    assert_eq!(Command::parse("%s/old"), Err(String::from("%s/old")));
+    assert_eq!(Command::parse("encrypt"), Ok(Command::Encrypt));
+    assert_eq!(Command::parse(" decrypt "), Ok(Command::Decrypt));
+    assert_eq!(
+        Command::parse("encrypt now"),
+        Err(String::from("encrypt now"))
+    );
+    assert_eq!(
+        Command::parse("snapshot before the big reorg"),
+        Ok(Command::Snapshot(String::from("before the big reorg")))
+    );
+    assert_eq!(Command::parse("snapshot"), Err(String::from("snapshot")));
+    assert_eq!(Command::parse("snapshots"), Ok(Command::Snapshots));
+    assert_eq!(Command::parse("trash"), Ok(Command::Trash));
+    assert_eq!(Command::parse("fit"), Ok(Command::Fit));
+    assert_eq!(Command::parse("connections"), Ok(Command::Connections));
+    assert_eq!(
+        Command::parse("import csv ~/lists/reading list.csv"),
+        Ok(Command::ImportNotes(
+            NoteListFormat::Csv,
+            PathBuf::from("~/lists/reading list.csv")
+        ))
+    );
+    assert_eq!(
+        Command::parse("import lines"),
+        Err(String::from("import lines"))
+    );
+    assert_eq!(
+        Command::parse("diff ../old plan.json"),
+        Ok(Command::Diff(PathBuf::from("../old plan.json")))
+    );
+    assert_eq!(Command::parse("diff"), Err(String::from("diff")));
+    assert_eq!(
+        Command::parse("grep  due (friday|monday) "),
+        Ok(Command::Grep(String::from("due (friday|monday)")))
+    );
+    assert_eq!(Command::parse("grep"), Err(String::from("grep")));
+}
+
+#[test]
+fn test_export_mermaid_next_to_map_file() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state.paste_dsl("Idea -> Draft").unwrap();
+
+    run_command(&mut map_state, "export mermaid");
+
+    assert_eq!(map_state.ui_state.command_line, None);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::ExportSuccess)
+    );
+    let document = fs::read_to_string(dir.path().join("plan.mmd")).unwrap();
+    assert!(document.starts_with("flowchart TD\n"));
+    assert!(document.contains("n0 --> n1"));
+}
+
+#[test]
+fn test_export_svg_next_to_map_file() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state.paste_dsl("Idea -> Draft").unwrap();
+
+    run_command(&mut map_state, "export svg");
+
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::ExportSuccess)
+    );
+    let document = fs::read_to_string(dir.path().join("plan.svg")).unwrap();
+    assert!(document.starts_with("<svg"));
+    assert!(document.contains(">Idea</tspan>"));
+}
+
+#[test]
+fn test_export_html_next_to_map_file() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state.paste_dsl("Idea -> Draft").unwrap();
+
+    run_command(&mut map_state, "export html");
+
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::ExportSuccess)
+    );
+    let document = fs::read_to_string(dir.path().join("plan.html")).unwrap();
+    assert!(document.contains("<title>plan</title>"));
+    assert!(document.contains(">Draft</tspan>"));
+}
+
+#[test]
+fn test_export_mermaid_to_given_path() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    let export_path = dir.path().join("diagram.md");
+
+    run_command(
+        &mut map_state,
+        &format!("export mermaid {}", export_path.display()),
+    );
+
+    assert!(export_path.exists());
+
+    // A directory that doesn't exist can't be written to
+    run_command(&mut map_state, "export mermaid /nonexistent/dir/x.mmd");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::ExportFail)
+    );
+}
+
+/// Id of the note with the given content.
+fn note_id(map_state: &MapState, content: &str) -> usize {
+    map_state
+        .notes_state
+        .notes()
+        .iter()
+        .find(|(_, note)| note.content == content)
+        .map(|(&id, _)| id)
+        .unwrap()
+}
+
+#[test]
+fn test_export_selection_from_visual_mode() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state
+        .paste_dsl("Plan -> Public\nPlan -> Secret\nPublic -> Secret")
+        .unwrap();
+    let plan = note_id(&map_state, "Plan");
+    let public = note_id(&map_state, "Public");
+    map_state.notes_state.select_many(&[plan, public]);
+    map_state.mode = Mode::Visual;
+
+    run_command(&mut map_state, "export selection mermaid");
+
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::ExportSuccess)
+    );
+    // The selection is kept to go on working with it
+    assert_eq!(map_state.mode, Mode::Visual);
+    assert_eq!(map_state.notes_state.selection(), vec![plan, public]);
+
+    let document = fs::read_to_string(dir.path().join("plan.mmd")).unwrap();
+    assert!(document.contains("Plan") && document.contains("Public"));
+    assert!(!document.contains("Secret"));
+    assert_eq!(document.matches("-->").count(), 1);
+}
+
+#[test]
+fn test_export_branch_follows_connections_from_selected_note() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state
+        .paste_dsl("Book -> Part 1 -> Chapter\nBook -> Part 2\nNotes")
+        .unwrap();
+    map_state.notes_state.select(note_id(&map_state, "Part 1"));
+    map_state.mode = Mode::Visual;
+
+    run_command(&mut map_state, "export branch opml");
+
+    let mut imported = create_test_map_state(&dir.path().join("other.json"));
+    run_command(
+        &mut imported,
+        &format!("import {}", dir.path().join("plan.opml").display()),
+    );
+    let mut labels: Vec<&str> = imported
+        .notes_state
+        .notes()
+        .values()
+        .map(|note| note.content.as_str())
+        .collect();
+    labels.sort_unstable();
+    assert_eq!(labels, vec!["Chapter", "Part 1"]);
+    assert_eq!(imported.connections_state.connections().len(), 1);
+}
+
+#[test]
+fn test_export_selection_needs_a_selected_note() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state.paste_dsl("Idea -> Draft").unwrap();
+    assert_eq!(map_state.notes_state.selected_note_id(), None);
+
+    for scope in ["selection", "branch"] {
+        run_command(&mut map_state, &format!("export {scope} mermaid"));
+        assert_eq!(
+            map_state.ui_state.notifications.latest(),
+            Some(&Notification::ExportNothingSelected)
+        );
+    }
+    assert!(!dir.path().join("plan.mmd").exists());
+}
+
+#[test]
+fn test_export_and_import_outline_round_trip() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state
+        .paste_dsl("Book -> Part 1 -> Chapter\nBook -> Part 2")
+        .unwrap();
+
+    for (format, file) in [("opml", "plan.opml"), ("freemind", "plan.mm")] {
+        run_command(&mut map_state, &format!("export {format}"));
+        assert_eq!(
+            map_state.ui_state.notifications.latest(),
+            Some(&Notification::ExportSuccess)
+        );
+
+        let mut imported = create_test_map_state(&dir.path().join("other.json"));
+        run_command(
+            &mut imported,
+            &format!("import {}", dir.path().join(file).display()),
+        );
+
+        assert_eq!(
+            imported.ui_state.notifications.latest(),
+            Some(&Notification::ImportSuccess)
+        );
+        let mut labels: Vec<&str> = imported
+            .notes_state
+            .notes()
+            .values()
+            .map(|note| note.content.as_str())
+            .collect();
+        labels.sort_unstable();
+        assert_eq!(labels, vec!["Book", "Chapter", "Part 1", "Part 2"]);
+        assert_eq!(imported.connections_state.connections().len(), 3);
+    }
+}
+
+#[test]
+fn test_import_rejects_other_files() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    let not_an_outline = dir.path().join("notes.txt");
+    fs::write(&not_an_outline, "just text").unwrap();
+
+    run_command(
+        &mut map_state,
+        &format!("import {}", not_an_outline.display()),
+    );
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::ImportFail)
+    );
+
+    run_command(&mut map_state, "import /nonexistent/outline.opml");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::ImportFail)
+    );
+    assert!(map_state.notes_state.notes().is_empty());
+}
+
+#[test]
+fn test_unknown_command_and_cancel() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+
+    run_command(&mut map_state, "frobnicate");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::UnknownCommand)
+    );
+
+    // Keys typed on the command line aren't Normal mode commands
+    map_kh(&mut map_state, create_key_event(KeyCode::Char(':')));
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('a')));
+    assert!(map_state.notes_state.notes().is_empty());
+    assert_eq!(map_state.ui_state.command_line, Some(String::from("a")));
+
+    // Backspace on an empty line closes it
+    map_kh(&mut map_state, create_key_event(KeyCode::Backspace));
+    map_kh(&mut map_state, create_key_event(KeyCode::Backspace));
+    assert_eq!(map_state.ui_state.command_line, None);
+}
+
+fn type_passphrase(map_state: &mut MapState, passphrase: &str) {
+    for c in passphrase.chars() {
+        map_kh(map_state, create_key_event(KeyCode::Char(c)));
+    }
+    map_kh(map_state, create_key_event(KeyCode::Enter));
+}
+
+#[test]
+fn test_encrypt_command_saves_map_file_encrypted() {
+    let dir = tempdir().unwrap();
+    let map_path = dir.path().join("journal.json");
+    let mut map_state = create_test_map_state(&map_path);
+    map_state.paste_dsl("Dear diary").unwrap();
+
+    run_command(&mut map_state, "encrypt");
+    type_passphrase(&mut map_state, "secret");
+    // Asked for a second time before anything is encrypted
+    assert!(map_state.persistence.file_key.is_none());
+    type_passphrase(&mut map_state, "secret");
+
+    assert_eq!(map_state.ui_state.passphrase_prompt, None);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::MapEncrypted)
+    );
+    let contents = fs::read_to_string(&map_path).unwrap();
+    assert!(contents.contains("encrypted_map"));
+    assert!(!contents.contains("Dear diary"));
+    // Notes aren't encrypted with the map file passphrase
+    assert!(!map_state.vault.has_passphrase());
+}
+
+#[test]
+fn test_encrypt_command_passphrase_mismatch() {
+    let dir = tempdir().unwrap();
+    let map_path = dir.path().join("journal.json");
+    let mut map_state = create_test_map_state(&map_path);
+
+    run_command(&mut map_state, "encrypt");
+    type_passphrase(&mut map_state, "secret");
+    type_passphrase(&mut map_state, "secert");
+
+    assert_eq!(map_state.ui_state.passphrase_prompt, None);
+    assert!(map_state.persistence.file_key.is_none());
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::PassphraseMismatch)
+    );
+    assert!(!map_path.exists());
+}
+
+#[test]
+fn test_decrypt_command_saves_map_file_unencrypted() {
+    let dir = tempdir().unwrap();
+    let map_path = dir.path().join("journal.json");
+    let mut map_state = create_test_map_state(&map_path);
+    map_state.paste_dsl("Dear diary").unwrap();
+    run_command(&mut map_state, "encrypt");
+    type_passphrase(&mut map_state, "secret");
+    type_passphrase(&mut map_state, "secret");
+
+    run_command(&mut map_state, "decrypt");
+
+    assert!(map_state.persistence.file_key.is_none());
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::MapDecrypted)
+    );
+    assert!(
+        fs::read_to_string(&map_path)
+            .unwrap()
+            .contains("Dear diary")
+    );
+}
+
+#[test]
+fn test_no_milestones_for_encrypted_map_file() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("journal.json"));
+    map_state.persistence.file_key = Some(FileKey::new("secret"));
+
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('M')));
+
+    assert_eq!(map_state.milestones.name_prompt, None);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::MilestoneEncrypted)
+    );
+}
+
+#[test]
+fn test_snapshot_keeps_unsaved_changes() {
+    let dir = tempdir().unwrap();
+    let map_path = dir.path().join("plan.json");
+    let mut map_state = create_test_map_state(&map_path);
+    map_state.paste_dsl("Idea -> Draft").unwrap();
+    map_state.persistence.mark_dirty();
+
+    run_command(&mut map_state, "snapshot before reorg");
+
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::SnapshotSuccess)
+    );
+    assert!(map_state.persistence.has_unsaved_changes);
+    assert!(!map_path.exists());
+    let snapshots = list_snapshots(&map_path).unwrap();
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0].name, "before reorg");
+    assert_eq!(
+        map_state
+            .read_map_copy(&snapshots[0].path)
+            .unwrap()
+            .notes
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn test_load_snapshot_from_picker() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state.paste_dsl("Idea -> Draft").unwrap();
+    run_command(&mut map_state, "snapshot two notes");
+    map_state.paste_dsl("Review").unwrap();
+    map_state.persistence.mark_clean();
+
+    run_command(&mut map_state, "snapshots");
+    assert_eq!(
+        map_state
+            .ui_state
+            .snapshot_picker
+            .as_ref()
+            .unwrap()
+            .snapshots
+            .len(),
+        1
+    );
+    map_kh(&mut map_state, create_key_event(KeyCode::Enter));
+
+    assert_eq!(map_state.ui_state.snapshot_picker, None);
+    assert_eq!(map_state.notes_state.notes().len(), 2);
+    assert!(map_state.persistence.has_unsaved_changes);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::SnapshotLoaded)
+    );
+}
+
+#[test]
+fn test_diff_against_snapshot() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state.paste_dsl("Idea").unwrap();
+    run_command(&mut map_state, "snapshot one note");
+    map_state.paste_dsl("Review").unwrap();
+
+    run_command(&mut map_state, "snapshots");
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('d')));
+
+    let picker = map_state.ui_state.snapshot_picker.as_ref().unwrap();
+    let diff = picker.diff.as_ref().unwrap();
+    assert_eq!(diff.removed, vec![String::from("Review")]);
+    assert!(diff.added.is_empty());
+
+    // Any key returns to the list, Esc then closes it
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('x')));
+    assert_eq!(
+        map_state.ui_state.snapshot_picker.as_ref().unwrap().diff,
+        None
+    );
+    map_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert_eq!(map_state.ui_state.snapshot_picker, None);
+    assert_eq!(map_state.notes_state.notes().len(), 2);
+}
+
+#[test]
+fn test_diff_with_file_shows_removed_notes_on_canvas() {
+    let dir = tempdir().unwrap();
+    let mut old_map = create_test_map_state(&dir.path().join("old.json"));
+    old_map.paste_dsl("Idea -> Draft").unwrap();
+    save_map_file(&mut old_map, &dir.path().join("old.json")).unwrap();
+
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state.paste_dsl("Idea").unwrap();
+
+    run_command(
+        &mut map_state,
+        &format!("diff {}", dir.path().join("old.json").display()),
+    );
+
+    let diff_view = map_state.diff_view.as_ref().unwrap();
+    assert_eq!(diff_view.name, "old");
+    let shown = &diff_view.map_state;
+    // The note only the old file has is drawn back in, marked as removed
+    assert_eq!(shown.notes_state.notes().len(), 2);
+    assert_eq!(shown.connections_state.connections().len(), 1);
+    let changes = shown.ui_state.map_changes.as_ref().unwrap();
+    assert_eq!(changes.notes.get(&1), Some(&NoteChange::Removed));
+    assert_eq!(changes.connections_removed.len(), 1);
+
+    // The open map itself is untouched, and Esc closes the view
+    assert_eq!(map_state.notes_state.notes().len(), 1);
+    map_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert_eq!(map_state.diff_view, None);
+}
+
+#[test]
+fn test_diff_without_differences_or_file() {
+    let dir = tempdir().unwrap();
+    let map_path = dir.path().join("plan.json");
+    let mut map_state = create_test_map_state(&map_path);
+    map_state.paste_dsl("Idea").unwrap();
+    save_map_file(&mut map_state, &map_path).unwrap();
+
+    run_command(&mut map_state, &format!("diff {}", map_path.display()));
+    assert_eq!(map_state.diff_view, None);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::NoDifferences)
+    );
+
+    run_command(&mut map_state, "diff missing.json");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::DiffFail)
+    );
+}
+
+#[test]
+fn test_view_snapshot_differences_on_canvas() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state.paste_dsl("Idea").unwrap();
+    run_command(&mut map_state, "snapshot one note");
+    map_state.paste_dsl("Review").unwrap();
+
+    run_command(&mut map_state, "snapshots");
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('v')));
+
+    assert_eq!(map_state.ui_state.snapshot_picker, None);
+    let diff_view = map_state.diff_view.as_ref().unwrap();
+    assert_eq!(diff_view.name, "one note");
+    let changes = diff_view.map_state.ui_state.map_changes.as_ref().unwrap();
+    assert_eq!(changes.notes.get(&1), Some(&NoteChange::Added));
+}
+
+#[test]
+fn test_substitute_across_notes_and_undo() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state.paste_dsl("Login -> Login form\nSignup").unwrap();
+    map_state.persistence.mark_clean();
+
+    run_command(&mut map_state, "%s/Login/Sign-in/g");
+
+    let contents = |map_state: &MapState| {
+        let mut contents: Vec<String> = map_state
+            .notes_state
+            .notes()
+            .values()
+            .map(|note| note.content.clone())
+            .collect();
+        contents.sort();
+        contents
+    };
+    assert_eq!(
+        contents(&map_state),
+        vec!["Sign-in", "Sign-in form", "Signup"]
+    );
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::Replaced(2))
+    );
+    assert!(map_state.persistence.has_unsaved_changes);
+
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('u')));
+    assert_eq!(contents(&map_state), vec!["Login", "Login form", "Signup"]);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::Undone)
+    );
+
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('u')));
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::NothingToUndo)
+    );
+}
+
+#[test]
+fn test_substitute_without_matches_or_with_invalid_regex() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state.paste_dsl("Idea").unwrap();
+
+    run_command(&mut map_state, "%s/missing/x/");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::PatternNotFound)
+    );
+
+    run_command(&mut map_state, "%s/(/x/r");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::InvalidPattern)
+    );
+    assert!(map_state.history.is_empty());
+}
+
+#[test]
+fn test_restore_and_empty_from_trash_browser() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state.paste_dsl("Idea -> Draft\nReview").unwrap();
+    for id in [0, 2] {
+        map_state.notes_state.select(id);
+        map_state.delete_selected_note();
+    }
+
+    run_command(&mut map_state, "trash");
+    assert!(map_state.trash.browser.is_some());
+    // The most recently deleted note is highlighted
+    map_kh(&mut map_state, create_key_event(KeyCode::Enter));
+    assert!(map_state.notes_state.notes().contains_key(&2));
+    assert_eq!(map_state.trash.notes().len(), 1);
+
+    // Emptying the trash asks first
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('X')));
+    assert_eq!(map_state.trash.notes().len(), 1);
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('X')));
+    assert!(map_state.trash.is_empty());
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::TrashEmptied)
+    );
+
+    map_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert_eq!(map_state.trash.browser, None);
+}
+
+#[test]
+fn test_sort_recolor_and_delete_in_connection_table() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state
+        .paste_dsl("Cedar -> Ash\nBirch -> Ash\nAsh -> Birch")
+        .unwrap();
+    map_state.persistence.mark_clean();
+
+    run_command(&mut map_state, "connections");
+    // Sorted by the note they start at: Ash, Birch, Cedar
+    let table = map_state.ui_state.connection_table.as_ref().unwrap();
+    assert_eq!(table.rows, [2, 1, 0]);
+
+    // Then by the note they end at, the other way round
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('s')));
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('S')));
+    let table = map_state.ui_state.connection_table.as_ref().unwrap();
+    assert_eq!(table.sort, ConnectionSort::To);
+    assert_eq!(table.rows, [2, 1, 0]);
+
+    map_kh(&mut map_state, create_key_event(KeyCode::Char(' ')));
+    map_kh(&mut map_state, create_key_event(KeyCode::Char(' ')));
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('e')));
+    let connections = map_state.connections_state.connections();
+    assert_ne!(connections[1].color, Color::White);
+    assert_eq!(connections[2].color, connections[1].color);
+    assert_eq!(connections[0].color, Color::White);
+    assert!(map_state.persistence.has_unsaved_changes);
+
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('d')));
+    assert_eq!(map_state.connections_state.connections().len(), 1);
+    assert_eq!(map_state.connections_state.connections()[0].from_id, 0);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::ConnectionsDeleted(2))
+    );
+    let table = map_state.ui_state.connection_table.as_ref().unwrap();
+    assert_eq!((table.rows.as_slice(), table.selected), ([0].as_slice(), 0));
+    assert!(table.marked.is_empty());
+
+    map_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert_eq!(map_state.ui_state.connection_table, None);
+}
+
+#[test]
+fn test_import_note_list_in_grid() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state.persistence.mark_clean();
+    let list = dir.path().join("reading.txt");
+    fs::write(&list, "Dune\nEmma\n\nUlysses\nBeloved\n").unwrap();
+
+    run_command(&mut map_state, &format!("import lines {}", list.display()));
+
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::NotesImported(4))
+    );
+    assert!(map_state.persistence.has_unsaved_changes);
+    let notes = map_state.notes_state.notes();
+    let (x, y) = map_state.viewport.center();
+    // Two rows of two, starting at the viewport center
+    assert_eq!((notes[&0].x, notes[&0].y), (x, y));
+    assert_eq!(notes[&1].y, y);
+    assert!(notes[&1].x > x);
+    assert_eq!(notes[&2].x, x);
+    assert!(notes[&2].y > y);
+}
+
+#[test]
+fn test_import_note_list_failures() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    let csv = dir.path().join("notes.csv");
+    fs::write(&csv, "content,x,y\nFine,1,2\nBroken,one,2\n").unwrap();
+
+    run_command(&mut map_state, &format!("import csv {}", csv.display()));
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::NoteListInvalidRow(3))
+    );
+
+    run_command(&mut map_state, "import paragraphs /nonexistent/notes.txt");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::NoteListFail)
+    );
+    assert!(map_state.notes_state.notes().is_empty());
+}
+
+#[test]
+fn test_filter_by_status() {
+    assert_eq!(Command::parse("filter"), Ok(Command::Filter(None)));
+    assert_eq!(
+        Command::parse("filter doing"),
+        Ok(Command::Filter(Some(NoteStatus::Doing)))
+    );
+    assert_eq!(
+        Command::parse("filter someday"),
+        Err(String::from("filter someday"))
+    );
+
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    for (text, status) in [
+        ("Write", Some(NoteStatus::Done)),
+        ("Edit", Some(NoteStatus::Done)),
+        ("Print", None),
+    ] {
+        let id = map_state
+            .notes_state
+            .add(0, 0, String::from(text), Color::White);
+        map_state.notes_state.get_mut(id).unwrap().status = status;
+    }
+
+    run_command(&mut map_state, "filter done");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::Filtered(2))
+    );
+    assert_eq!(map_state.ui_state.status_filter, Some(NoteStatus::Done));
+
+    run_command(&mut map_state, "filter");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::FilterCleared)
+    );
+    assert_eq!(map_state.ui_state.status_filter, None);
+}
+
+#[test]
+fn test_run_plugin_functions() {
+    assert_eq!(
+        Command::parse("plugin shout"),
+        Ok(Command::Plugin(String::from("shout")))
+    );
+    assert_eq!(
+        Command::parse("plugin shout loud"),
+        Err(String::from("plugin shout loud"))
+    );
+
+    let dir = tempdir().unwrap();
+    let plugins_dir = plugins_dir(dir.path());
+    fs::create_dir_all(&plugins_dir).unwrap();
+    fs::write(
+        plugins_dir.join("shout.rhai"),
+        r#"
+        fn shout() {
+            for note in notes() {
+                set_content(note.id, note.content.to_upper());
+            }
+        }
+        fn on_note_created(id) {
+            set_content(id, "New idea");
+            connect(0, id);
+        }
+        fn on_save() {
+            write_file("titles.txt", notes().map(|note| note.content).reduce(|a, b| a + "," + b, ""));
+        }
+        "#,
+    )
+    .unwrap();
+    let fs = TempFileSystem {
+        home_path: dir.path().to_path_buf(),
+    };
+    let map_path = dir.path().join("plan.json");
+    let mut map_state = MapState::new_with_fs(map_path.clone(), &fs);
+    map_state.viewport.screen_width = 100;
+    map_state.viewport.screen_height = 50;
+    map_state
+        .notes_state
+        .add(0, 0, String::from("Write"), Color::White);
+    let locked = map_state
+        .notes_state
+        .add(30, 0, String::from("Print"), Color::White);
+    map_state.notes_state.get_mut(locked).unwrap().locked = true;
+
+    run_command(&mut map_state, "plugin shout");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::PluginRan)
+    );
+    assert_eq!(map_state.notes_state.notes()[&0].content, "WRITE");
+    assert_eq!(map_state.notes_state.notes()[&locked].content, "Print");
+    assert!(map_state.persistence.has_unsaved_changes);
+
+    run_command(&mut map_state, "plugin whisper");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::PluginFunctionMissing)
+    );
+
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('a')));
+    let id = map_state.notes_state.expect_selected_note_id();
+    assert_eq!(map_state.notes_state.notes()[&id].content, "New idea");
+    assert_eq!(map_state.connections_state.connections()[0].to_id, id);
+    map_kh(&mut map_state, create_key_event(KeyCode::Esc));
+
+    save_map_file(&mut map_state, &map_path).unwrap();
+    assert_eq!(
+        fs::read_to_string(dir.path().join("titles.txt")).unwrap(),
+        ",WRITE,Print,New idea"
+    );
+}
+
+#[test]
+fn test_run_user_commands() {
+    assert_eq!(
+        Command::parse("run upper"),
+        Ok(Command::Run(String::from("upper")))
+    );
+    assert_eq!(Command::parse("run"), Err(String::from("run")));
+
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state.settings.user_commands = vec![
+        UserCommand {
+            name: String::from("upper"),
+            command: String::from("tr a-z A-Z"),
+            input: CommandInput::Note,
+            replace: true,
+        },
+        UserCommand {
+            name: String::from("count"),
+            command: format!("grep -c content > '{}'", dir.path().join("count").display()),
+            input: CommandInput::Map,
+            replace: false,
+        },
+        UserCommand {
+            name: String::from("broken"),
+            command: String::from("exit 1"),
+            input: CommandInput::Map,
+            replace: false,
+        },
+    ];
+    let id = map_state
+        .notes_state
+        .add(0, 0, String::from("Write\nthe plan"), Color::White);
+    map_state
+        .notes_state
+        .add(30, 0, String::from("Print"), Color::White);
+
+    run_command(&mut map_state, "run upper");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::UserCommandNoNote)
+    );
+
+    map_state.notes_state.select(id);
+    map_state.mode = Mode::Visual;
+    run_command(&mut map_state, "run upper");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::UserCommandDone)
+    );
+    assert_eq!(
+        map_state.notes_state.notes()[&id].content,
+        "WRITE\nTHE PLAN"
+    );
+    map_state.undo();
+    assert_eq!(
+        map_state.notes_state.notes()[&id].content,
+        "Write\nthe plan"
+    );
+
+    map_state.notes_state.get_mut(id).unwrap().locked = true;
+    run_command(&mut map_state, "run upper");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::NoteLocked)
+    );
+
+    run_command(&mut map_state, "run count");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::UserCommandDone)
+    );
+    assert_eq!(fs::read_to_string(dir.path().join("count")).unwrap(), "2\n");
+
+    run_command(&mut map_state, "run broken");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::UserCommandFailed)
+    );
+    run_command(&mut map_state, "run missing");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::UnknownUserCommand)
+    );
+}
+
+#[test]
+fn test_fit_shows_every_note() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state
+        .notes_state
+        .add(200, 100, String::from("A"), Color::White);
+    map_state
+        .notes_state
+        .add(240, 120, String::from("B"), Color::White);
+    let bounds = map_state.content_bounds().unwrap();
+
+    run_command(&mut map_state, "fit");
+
+    let view_pos = &map_state.viewport.view_pos;
+    assert!(view_pos.x <= bounds.x);
+    assert!(view_pos.x + 100 >= bounds.x + bounds.width);
+    assert!(view_pos.y <= bounds.y);
+    assert!(view_pos.y + 47 >= bounds.y + bounds.height);
+    assert_eq!(map_state.ui_state.notifications.latest(), None);
+}
+
+#[test]
+fn test_fit_reports_map_larger_than_screen() {
+    let dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&dir.path().join("plan.json"));
+    map_state
+        .notes_state
+        .add(30, 40, String::from("A"), Color::White);
+    map_state
+        .notes_state
+        .add(500, 60, String::from("B"), Color::White);
+
+    run_command(&mut map_state, "fit");
+
+    assert_eq!(map_state.viewport.view_pos.x, 30);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::MapLargerThanScreen)
+    );
+}
@@ -3,10 +3,13 @@ use ratatui::style::Color;
 use std::path::PathBuf;
 
 use crate::{
-    input::{AppAction, map::delete::map_delete_kh},
+    input::{
+        AppAction,
+        map::{delete::map_delete_kh, dialog::map_dialog_kh},
+    },
     states::{
         MapState,
-        map::{Connection, Mode, Side},
+        map::{Connection, MapDialogAction, Mode, Side},
     },
     utils::test_utils::MockFileSystem,
 };
@@ -32,9 +35,13 @@ fn test_delete_kh_escape_switches_to_visual() {
         .notes_state
         .add(10, 10, String::from("Note 0"), Color::White);
     map_state.notes_state.select(0);
-    map_state.mode = Mode::Delete;
+    map_state.confirm_delete();
 
-    let result = map_delete_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    let result = map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Esc),
+        &MockFileSystem::new(),
+    );
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.mode, Mode::Visual);
@@ -49,9 +56,13 @@ fn test_delete_single_note() {
         .notes_state
         .add(50, 25, String::from("Test Note"), Color::White);
     map_state.notes_state.select(0);
-    map_state.mode = Mode::Delete;
+    map_state.confirm_delete();
 
-    let result = map_delete_kh(&mut map_state, create_key_event(KeyCode::Char('d')));
+    let result = map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('d')),
+        &MockFileSystem::new(),
+    );
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.persistence.has_unsaved_changes, true); // Should be set to true
@@ -77,9 +88,13 @@ fn test_delete_note_with_multiple_notes() {
         .add(80, 40, String::from("Note 2"), Color::White);
 
     map_state.notes_state.select(1);
-    map_state.mode = Mode::Delete;
+    map_state.confirm_delete();
 
-    let result = map_delete_kh(&mut map_state, create_key_event(KeyCode::Char('d')));
+    let result = map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('d')),
+        &MockFileSystem::new(),
+    );
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.persistence.has_unsaved_changes, true);
@@ -117,37 +132,50 @@ fn test_delete_note_with_multiple_connections() {
     let connection1 = Connection {
         from_id: 0,
         from_side: Side::Right,
-        to_id: Some(1),
-        to_side: Some(Side::Left),
+        to_id: 1,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection1);
+    map_state.connections_state.focused_connection = Some(connection1.into());
     map_state.connections_state.stash_connection();
 
     let connection2 = Connection {
         from_id: 1,
         from_side: Side::Right,
-        to_id: Some(2),
-        to_side: Some(Side::Left),
+        to_id: 2,
+        to_side: Side::Left,
         color: Color::Green,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection2);
+    map_state.connections_state.focused_connection = Some(connection2.into());
     map_state.connections_state.stash_connection();
 
     // Add an unrelated connection: 0->2 (should be preserved)
     let connection3 = Connection {
         from_id: 0,
         from_side: Side::Bottom,
-        to_id: Some(2),
-        to_side: Some(Side::Top),
+        to_id: 2,
+        to_side: Side::Top,
         color: Color::Blue,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection3);
+    map_state.connections_state.focused_connection = Some(connection3.clone().into());
     map_state.connections_state.stash_connection();
 
-    map_state.mode = Mode::Delete;
+    map_state.confirm_delete();
 
-    let result = map_delete_kh(&mut map_state, create_key_event(KeyCode::Char('d')));
+    let result = map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('d')),
+        &MockFileSystem::new(),
+    );
 
     assert_eq!(result, AppAction::Continue);
 
@@ -209,16 +237,23 @@ fn test_delete_note_as_connection_target() {
     let connection = Connection {
         from_id: 1,
         from_side: Side::Right,
-        to_id: Some(0),
-        to_side: Some(Side::Left),
+        to_id: 0,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection);
+    map_state.connections_state.focused_connection = Some(connection.into());
     map_state.connections_state.stash_connection();
 
-    map_state.mode = Mode::Delete;
+    map_state.confirm_delete();
 
-    let result = map_delete_kh(&mut map_state, create_key_event(KeyCode::Char('d')));
+    let result = map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('d')),
+        &MockFileSystem::new(),
+    );
 
     assert_eq!(result, AppAction::Continue);
 
@@ -263,16 +298,23 @@ fn test_delete_note_as_connection_source() {
     let connection = Connection {
         from_id: 0,
         from_side: Side::Right,
-        to_id: Some(1),
-        to_side: Some(Side::Left),
+        to_id: 1,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection);
+    map_state.connections_state.focused_connection = Some(connection.into());
     map_state.connections_state.stash_connection();
 
-    map_state.mode = Mode::Delete;
+    map_state.confirm_delete();
 
-    let result = map_delete_kh(&mut map_state, create_key_event(KeyCode::Char('d')));
+    let result = map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('d')),
+        &MockFileSystem::new(),
+    );
 
     assert_eq!(result, AppAction::Continue);
 
@@ -308,13 +350,12 @@ fn test_delete_kh_other_keys_ignored() {
         .notes_state
         .add(50, 25, String::from("Test Note"), Color::White);
     map_state.notes_state.select(0);
-    map_state.mode = Mode::Delete;
+    map_state.confirm_delete();
 
     // Test various other keys
     let test_keys = vec![
         KeyCode::Char('a'),
         KeyCode::Char('x'),
-        KeyCode::Enter,
         KeyCode::Backspace,
         KeyCode::Delete,
         KeyCode::Tab,
@@ -325,7 +366,11 @@ fn test_delete_kh_other_keys_ignored() {
     ];
 
     for key in test_keys {
-        let result = map_delete_kh(&mut map_state, create_key_event(key));
+        let result = map_dialog_kh(
+            &mut map_state,
+            create_key_event(key),
+            &MockFileSystem::new(),
+        );
 
         // Should not change anything for unhandled keys
         assert_eq!(result, AppAction::Continue);
@@ -353,9 +398,13 @@ fn test_delete_note_render_order() {
 
     // Test deleting first note in render order
     map_state.notes_state.select(2);
-    map_state.mode = Mode::Delete;
+    map_state.confirm_delete();
 
-    let result = map_delete_kh(&mut map_state, create_key_event(KeyCode::Char('d')));
+    let result = map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('d')),
+        &MockFileSystem::new(),
+    );
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(*map_state.notes_state.render_order(), vec![0, 1]); // Note 2 removed from front
@@ -382,9 +431,13 @@ fn test_delete_note_last_in_render_order() {
 
     // Test deleting last note in render order
     map_state.notes_state.select(2);
-    map_state.mode = Mode::Delete;
+    map_state.confirm_delete();
 
-    let result = map_delete_kh(&mut map_state, create_key_event(KeyCode::Char('d')));
+    let result = map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('d')),
+        &MockFileSystem::new(),
+    );
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(*map_state.notes_state.render_order(), vec![0, 1]); // Note 2 removed from end
@@ -402,9 +455,13 @@ fn test_delete_note_clears_and_redraws() {
         .notes_state
         .add(50, 25, String::from("Test Note"), Color::White);
     map_state.notes_state.select(0);
-    map_state.mode = Mode::Delete;
+    map_state.confirm_delete();
 
-    let _result = map_delete_kh(&mut map_state, create_key_event(KeyCode::Char('d')));
+    let _result = map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('d')),
+        &MockFileSystem::new(),
+    );
 
     // Should call clear_and_redraw() which sets needs_clear_and_redraw to true
     assert_eq!(map_state.ui_state.needs_clear_and_redraw, true);
@@ -418,10 +475,216 @@ fn test_escape_clears_and_redraws() {
         .add(10, 10, String::from("Note 0"), Color::White);
     map_state.notes_state.select(0);
     map_state.ui_state.mark_redrawn();
-    map_state.mode = Mode::Delete;
+    map_state.confirm_delete();
 
-    let _result = map_delete_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    let _result = map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Esc),
+        &MockFileSystem::new(),
+    );
 
     // Should call clear_and_redraw() which sets needs_clear_and_redraw to true
     assert_eq!(map_state.ui_state.needs_clear_and_redraw, true);
 }
+
+#[test]
+fn test_delete_multi_selection() {
+    let mut map_state = create_test_map_state();
+    for i in 0..3 {
+        map_state
+            .notes_state
+            .add(10 * i, 10, format!("Note {}", i), Color::White);
+    }
+    map_state.connections_state.add(Connection {
+        from_id: 0,
+        from_side: Side::Right,
+        to_id: 2,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    });
+    map_state.notes_state.select_many(&[0, 1]);
+    map_state.confirm_delete();
+
+    map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('d')),
+        &MockFileSystem::new(),
+    );
+
+    assert_eq!(map_state.notes_state.render_order(), &[2]);
+    assert!(map_state.connections_state.connections().is_empty());
+    assert!(!map_state.notes_state.has_multi_selection());
+    assert_eq!(map_state.mode, Mode::Normal);
+}
+
+#[test]
+fn test_delete_dialog_enter_chooses_focused_button() {
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(10, 10, String::from("Note 0"), Color::White);
+    map_state.notes_state.select(0);
+    map_state.confirm_delete();
+
+    // Back to Visual mode has focus first
+    map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Enter),
+        &MockFileSystem::new(),
+    );
+    assert_eq!(map_state.mode, Mode::Visual);
+    assert_eq!(map_state.notes_state.notes().len(), 1);
+
+    map_state.confirm_delete();
+    map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('l')),
+        &MockFileSystem::new(),
+    );
+    map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Enter),
+        &MockFileSystem::new(),
+    );
+    assert!(map_state.notes_state.notes().is_empty());
+    assert_eq!(map_state.mode, Mode::Normal);
+}
+
+#[test]
+fn test_delete_mode_without_dialog_asks_again() {
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(10, 10, String::from("Note 0"), Color::White);
+    map_state.notes_state.select(0);
+    map_state.mode = Mode::Delete;
+
+    map_delete_kh(&mut map_state, create_key_event(KeyCode::Char('d')));
+
+    assert!(map_state.ui_state.dialog.is_some());
+    assert_eq!(map_state.notes_state.notes().len(), 1);
+}
+
+fn connect(map_state: &mut MapState, from_id: usize, to_id: usize) {
+    map_state.connections_state.add(Connection {
+        from_id,
+        from_side: Side::Right,
+        to_id,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    });
+}
+
+/// Notes 0-3, with 0 connected to 1 and 2, and 1 connected to 3
+fn create_connected_map_state() -> MapState {
+    let mut map_state = create_test_map_state();
+    for (x, title) in [(10, "Hub"), (30, "Left"), (50, "Right"), (70, "Far")] {
+        map_state
+            .notes_state
+            .add(x, 10, String::from(title), Color::White);
+    }
+    connect(&mut map_state, 0, 1);
+    connect(&mut map_state, 2, 0);
+    connect(&mut map_state, 1, 3);
+    map_state
+}
+
+#[test]
+fn test_delete_dialog_previews_affected_connections() {
+    let mut map_state = create_connected_map_state();
+    map_state.notes_state.select(0);
+
+    assert_eq!(map_state.connections_of_selection(), (2, vec![1, 2]));
+
+    map_state.confirm_delete();
+    let dialog = map_state.ui_state.dialog.as_ref().unwrap();
+    assert_eq!(
+        dialog.message,
+        vec![
+            String::from("This also removes 2 connections"),
+            String::from("leading to: Left, Right"),
+        ]
+    );
+    assert_eq!(
+        dialog.action_for_key(KeyCode::Char('p')),
+        Some(MapDialogAction::DeleteKeepingConnections)
+    );
+}
+
+#[test]
+fn test_delete_preview_leaves_out_connections_within_selection() {
+    let mut map_state = create_connected_map_state();
+    map_state.notes_state.select_many(&[0, 1]);
+
+    // 0-1 goes with both notes, 0-2 and 1-3 lead out of the selection
+    assert_eq!(map_state.connections_of_selection(), (3, vec![2, 3]));
+}
+
+#[test]
+fn test_delete_dialog_without_connections_offers_no_placeholder() {
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(10, 10, String::from("Alone"), Color::White);
+    map_state.notes_state.select(0);
+
+    map_state.confirm_delete();
+
+    let dialog = map_state.ui_state.dialog.as_ref().unwrap();
+    assert!(dialog.message.is_empty());
+    assert_eq!(dialog.action_for_key(KeyCode::Char('p')), None);
+}
+
+#[test]
+fn test_delete_keeping_connections_reattaches_to_placeholder() {
+    let mut map_state = create_connected_map_state();
+    map_state.notes_state.select(0);
+    map_state.confirm_delete();
+
+    map_dialog_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('p')),
+        &MockFileSystem::new(),
+    );
+
+    assert_eq!(map_state.mode, Mode::Normal);
+    assert!(!map_state.notes_state.notes().contains_key(&0));
+    let placeholder = &map_state.notes_state.notes()[&4];
+    assert_eq!(placeholder.content, "(deleted)");
+    assert_eq!((placeholder.x, placeholder.y), (10, 10));
+
+    let mut ends: Vec<(usize, usize)> = map_state
+        .connections_state
+        .connections()
+        .iter()
+        .map(|connection| (connection.from_id, connection.to_id))
+        .collect();
+    ends.sort();
+    assert_eq!(ends, vec![(1, 3), (2, 4), (4, 1)]);
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_delete_keeping_connections_drops_connections_within_selection() {
+    let mut map_state = create_connected_map_state();
+    map_state.notes_state.select_many(&[0, 1]);
+
+    map_state.delete_selected_note_keeping_connections();
+
+    // One placeholder each for 0 (connected to 2) and 1 (connected to 3)
+    assert_eq!(map_state.notes_state.notes().len(), 4);
+    assert_eq!(map_state.connections_state.connections().len(), 2);
+    assert!(
+        map_state
+            .connections_state
+            .connections()
+            .iter()
+            .all(|connection| connection.from_id >= 2 && connection.to_id >= 2)
+    );
+}
@@ -3,9 +3,15 @@ use ratatui::style::Color;
 use std::path::PathBuf;
 
 use crate::{
-    input::{AppAction, map::edit::map_edit_kh},
-    states::{MapState, map::Mode},
-    utils::test_utils::MockFileSystem,
+    input::{
+        AppAction,
+        map::{edit::map_edit_kh, map_note_search_kh},
+    },
+    states::{
+        MapState,
+        map::{Mode, Notification},
+    },
+    utils::{Dictionary, test_utils::MockFileSystem},
 };
 
 fn create_test_map_state() -> MapState {
@@ -647,3 +653,267 @@ fn test_cursor_bounds_edge_cases() {
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.notes_state.cursor_pos(), 0); // Should stay at 0
 }
+
+#[test]
+fn test_tab_toggles_checkbox_on_cursor_line() {
+    let mut map_state = create_test_map_state();
+
+    map_state.notes_state.add(
+        50,
+        25,
+        String::from("Trip\n- [ ] tickets\n- [x] hotel"),
+        Color::White,
+    );
+    map_state.notes_state.select(0);
+
+    for mode in [Mode::Edit, Mode::EditInsert, Mode::EditNormal] {
+        map_state.mode = mode;
+        // On "tickets"
+        map_state.notes_state.set_cursor_pos(10);
+
+        map_edit_kh(&mut map_state, create_key_event(KeyCode::Tab));
+        assert_eq!(
+            map_state.notes_state.expect_selected_note().content,
+            "Trip\n- [x] tickets\n- [x] hotel"
+        );
+        assert_eq!(map_state.notes_state.cursor_pos(), 10);
+
+        map_edit_kh(&mut map_state, create_key_event(KeyCode::Tab));
+        assert_eq!(
+            map_state.notes_state.expect_selected_note().content,
+            "Trip\n- [ ] tickets\n- [x] hotel"
+        );
+    }
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_tab_ignored_outside_checklist_items() {
+    let mut map_state = create_test_map_state();
+
+    map_state
+        .notes_state
+        .add(50, 25, String::from("Trip\n- [ ] tickets"), Color::White);
+    map_state.notes_state.select(0);
+    map_state.mode = Mode::Edit;
+    map_state.notes_state.set_cursor_pos(2);
+
+    map_edit_kh(&mut map_state, create_key_event(KeyCode::Tab));
+
+    assert_eq!(
+        map_state.notes_state.expect_selected_note().content,
+        "Trip\n- [ ] tickets"
+    );
+    assert!(!map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_long_note_scrolls_with_cursor() {
+    let mut map_state = create_test_map_state();
+    let lines: Vec<String> = (0..30).map(|line| format!("line {line}")).collect();
+    let content = lines.join("\n");
+    map_state
+        .notes_state
+        .add(0, 0, content.clone(), Color::White);
+    map_state.notes_state.select(0);
+    map_state.mode = Mode::Edit;
+    let line_start = |line: usize| content.find(&format!("line {line}\n")).unwrap();
+
+    // Scrolled just far enough for the cursor's line to be the last one shown
+    map_state.notes_state.set_cursor_pos(line_start(20));
+    assert_eq!(map_state.notes_state.scroll_to_cursor(10, 0), 11);
+    map_state.notes_state.set_edit_scroll(11);
+
+    // Stays put while the cursor is in the box
+    map_state.notes_state.set_cursor_pos(line_start(15));
+    assert_eq!(map_state.notes_state.scroll_to_cursor(10, 0), 11);
+
+    map_state.notes_state.set_cursor_pos(line_start(5));
+    assert_eq!(map_state.notes_state.scroll_to_cursor(10, 0), 5);
+
+    // A box with room for the whole note isn't scrolled
+    assert_eq!(map_state.notes_state.scroll_to_cursor(40, 0), 0);
+
+    // Selecting a note starts it from the top
+    map_state.notes_state.select(0);
+    assert_eq!(map_state.notes_state.edit_scroll(), 0);
+}
+
+#[test]
+fn test_long_note_keeps_scroll_margin_around_cursor() {
+    let mut map_state = create_test_map_state();
+    let lines: Vec<String> = (0..30).map(|line| format!("line {line}")).collect();
+    let content = lines.join("\n");
+    map_state
+        .notes_state
+        .add(0, 0, content.clone(), Color::White);
+    map_state.notes_state.select(0);
+    map_state.mode = Mode::Edit;
+    let line_start = |line: usize| content.find(&format!("line {line}")).unwrap();
+
+    // Two lines are kept below the cursor's line
+    map_state.notes_state.set_cursor_pos(line_start(20));
+    assert_eq!(map_state.notes_state.scroll_to_cursor(10, 2), 13);
+    map_state.notes_state.set_edit_scroll(13);
+
+    // And above it
+    map_state.notes_state.set_cursor_pos(line_start(14));
+    assert_eq!(map_state.notes_state.scroll_to_cursor(10, 2), 12);
+
+    // But not past the end of the note
+    map_state.notes_state.set_cursor_pos(line_start(29));
+    assert_eq!(map_state.notes_state.scroll_to_cursor(10, 2), 20);
+
+    // A margin too large for the box keeps the cursor near the middle, with four lines
+    // below it
+    map_state.notes_state.set_edit_scroll(0);
+    map_state.notes_state.set_cursor_pos(line_start(20));
+    assert_eq!(map_state.notes_state.scroll_to_cursor(10, 10), 15);
+}
+
+#[test]
+fn test_f7_cycles_spelling_suggestions() {
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(50, 25, String::from("a smal note"), Color::White);
+    map_state.notes_state.select(0);
+    map_state.mode = Mode::Edit;
+    let content =
+        |map_state: &MapState| map_state.notes_state.expect_selected_note().content.clone();
+
+    // Spell checking is off until a dictionary is chosen
+    map_state.notes_state.set_cursor_pos(6);
+    map_edit_kh(&mut map_state, create_key_event(KeyCode::F(7)));
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::SpellCheckOff)
+    );
+    assert_eq!(content(&map_state), "a smal note");
+
+    map_state.settings.spell_dictionary = Some(String::from("en_US"));
+    map_state.spelling.set_dictionary(
+        "en_US",
+        Dictionary::from_hunspell("4\na\nsmall\nseal\nnote\n", ""),
+    );
+
+    // Replaced with each suggestion in turn, then back to the word as typed
+    map_edit_kh(&mut map_state, create_key_event(KeyCode::F(7)));
+    assert_eq!(content(&map_state), "a seal note");
+    assert_eq!(map_state.notes_state.cursor_pos(), 6);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::SpellingSuggestion(1))
+    );
+    assert!(map_state.persistence.has_unsaved_changes);
+
+    map_edit_kh(&mut map_state, create_key_event(KeyCode::F(7)));
+    assert_eq!(content(&map_state), "a small note");
+    assert_eq!(map_state.notes_state.cursor_pos(), 7);
+
+    map_edit_kh(&mut map_state, create_key_event(KeyCode::F(7)));
+    assert_eq!(content(&map_state), "a smal note");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::SpellingRestored)
+    );
+
+    // Nothing to suggest for a word spelled right
+    map_state.notes_state.set_cursor_pos(11);
+    map_edit_kh(&mut map_state, create_key_event(KeyCode::F(7)));
+    assert_eq!(content(&map_state), "a smal note");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::NoSpellingSuggestions)
+    );
+}
+
+#[test]
+fn test_search_within_edited_note() {
+    let mut map_state = create_test_map_state();
+    map_state.notes_state.add(
+        50,
+        25,
+        String::from("Milk\nbread\nmore milk\ncheese"),
+        Color::White,
+    );
+    map_state.notes_state.select(0);
+    map_state.mode = Mode::Edit;
+    map_state.notes_state.set_cursor_pos(7);
+    let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+
+    // Ctrl+F doesn't type an f, the query moves the cursor as it's typed
+    map_edit_kh(&mut map_state, ctrl('f'));
+    for c in "milk".chars() {
+        map_note_search_kh(&mut map_state, create_key_event(KeyCode::Char(c)));
+    }
+    assert_eq!(
+        map_state.notes_state.expect_selected_note().content,
+        "Milk\nbread\nmore milk\ncheese"
+    );
+    assert_eq!(map_state.notes_state.cursor_pos(), 16);
+
+    map_note_search_kh(&mut map_state, create_key_event(KeyCode::Enter));
+    assert!(
+        !map_state
+            .ui_state
+            .note_search
+            .as_ref()
+            .unwrap()
+            .search
+            .typing
+    );
+
+    // Matches are cycled from the cursor, wrapping around the note
+    map_edit_kh(&mut map_state, ctrl('n'));
+    assert_eq!(map_state.notes_state.cursor_pos(), 0);
+    map_edit_kh(&mut map_state, ctrl('p'));
+    assert_eq!(map_state.notes_state.cursor_pos(), 16);
+
+    // Esc while typing a new query puts the cursor back
+    map_state.notes_state.set_cursor_pos(3);
+    map_edit_kh(&mut map_state, ctrl('f'));
+    for c in "chee".chars() {
+        map_note_search_kh(&mut map_state, create_key_event(KeyCode::Char(c)));
+    }
+    assert_eq!(map_state.notes_state.cursor_pos(), 21);
+    map_note_search_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert_eq!(map_state.notes_state.cursor_pos(), 3);
+    assert!(map_state.ui_state.note_search.is_none());
+
+    // `/` and `n` / `N` in Edit Normal mode; leaving Edit mode ends the search
+    map_state.mode = Mode::EditNormal;
+    map_edit_kh(&mut map_state, create_key_event(KeyCode::Char('/')));
+    map_note_search_kh(&mut map_state, create_key_event(KeyCode::Char('e')));
+    map_note_search_kh(&mut map_state, create_key_event(KeyCode::Enter));
+    assert_eq!(map_state.notes_state.cursor_pos(), 7);
+    map_edit_kh(&mut map_state, create_key_event(KeyCode::Char('n')));
+    assert_eq!(map_state.notes_state.cursor_pos(), 14);
+    map_edit_kh(&mut map_state, create_key_event(KeyCode::Char('N')));
+    assert_eq!(map_state.notes_state.cursor_pos(), 7);
+
+    map_edit_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert!(map_state.ui_state.note_search.is_none());
+}
+
+#[test]
+fn test_search_within_note_without_match() {
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(50, 25, String::from("apples"), Color::White);
+    map_state.notes_state.select(0);
+    map_state.mode = Mode::EditNormal;
+    map_state.notes_state.set_cursor_pos(2);
+
+    map_edit_kh(&mut map_state, create_key_event(KeyCode::Char('/')));
+    map_note_search_kh(&mut map_state, create_key_event(KeyCode::Char('z')));
+    assert_eq!(map_state.notes_state.cursor_pos(), 2);
+
+    map_note_search_kh(&mut map_state, create_key_event(KeyCode::Enter));
+    assert!(map_state.ui_state.note_search.is_none());
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::PatternNotFound)
+    );
+}
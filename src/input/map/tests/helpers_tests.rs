@@ -2,13 +2,13 @@ use ratatui::style::Color;
 use std::path::PathBuf;
 
 use crate::{
-    input::map::helpers::{
-        cycle_color, cycle_side, help_next_page, help_previous_page, move_note, move_viewport,
-        switch_notes_focus,
+    input::map::{
+        help::{help_next_page, help_previous_page},
+        helpers::{cycle_color, cycle_side, move_note, move_viewport, switch_notes_focus},
     },
     states::{
         MapState,
-        map::{Connection, Mode, Side},
+        map::{DraftConnection, Mode, Side},
     },
     utils::test_utils::MockFileSystem,
 };
@@ -117,14 +117,14 @@ fn test_move_viewport_x_negative() {
 }
 
 #[test]
-fn test_move_viewport_x_negative_saturating() {
+fn test_move_viewport_x_negative_past_origin() {
     let mut map_state = create_test_map_state();
     map_state.viewport.view_pos.x = 3;
     map_state.viewport.view_pos.y = 20;
 
     move_viewport(&mut map_state, "x", -10);
 
-    assert_eq!(map_state.viewport.view_pos.x, 0); // Should saturate at 0
+    assert_eq!(map_state.viewport.view_pos.x, -7); // The canvas goes on left of the origin
     assert_eq!(map_state.viewport.view_pos.y, 20);
     assert_eq!(map_state.persistence.has_unsaved_changes, true);
 }
@@ -156,7 +156,7 @@ fn test_move_viewport_y_negative() {
 }
 
 #[test]
-fn test_move_viewport_y_negative_saturating() {
+fn test_move_viewport_y_negative_past_origin() {
     let mut map_state = create_test_map_state();
     map_state.viewport.view_pos.x = 10;
     map_state.viewport.view_pos.y = 5;
@@ -164,7 +164,7 @@ fn test_move_viewport_y_negative_saturating() {
     move_viewport(&mut map_state, "y", -10);
 
     assert_eq!(map_state.viewport.view_pos.x, 10);
-    assert_eq!(map_state.viewport.view_pos.y, 0); // Should saturate at 0
+    assert_eq!(map_state.viewport.view_pos.y, -5); // The canvas goes on above the origin
     assert_eq!(map_state.persistence.has_unsaved_changes, true);
 }
 
@@ -272,6 +272,24 @@ fn test_move_note_x_negative_viewport_adjustment() {
     assert_eq!(map_state.persistence.has_unsaved_changes, true);
 }
 
+#[test]
+fn test_move_note_x_negative_past_origin() {
+    let mut map_state = create_test_map_state();
+
+    map_state
+        .notes_state
+        .add(2, 20, String::from("Test"), Color::White);
+    map_state.notes_state.select(0);
+    map_state.viewport.view_pos.x = 0;
+    map_state.viewport.view_pos.y = 0;
+
+    move_note(&mut map_state, "x", -5);
+
+    // The note goes left of the origin and the viewport follows it
+    assert_eq!(map_state.notes_state.notes()[&0].x, -3);
+    assert_eq!(map_state.viewport.view_pos.x, -3);
+}
+
 #[test]
 fn test_move_note_y_positive_simple() {
     let mut map_state = create_test_map_state();
@@ -362,6 +380,55 @@ fn test_move_note_y_negative_viewport_adjustment() {
     assert_eq!(map_state.persistence.has_unsaved_changes, true);
 }
 
+#[test]
+fn test_move_note_keeps_scroll_margin() {
+    let mut map_state = create_test_map_state();
+    map_state.settings.scroll_margin = 5;
+
+    // Right edge of the note at 80, 20 cells from the screen edge
+    map_state
+        .notes_state
+        .add(59, 20, String::from("Test"), Color::White);
+    map_state.notes_state.select(0);
+    let width = map_state.notes_state.notes()[&0].get_dimensions().0 as usize;
+    assert_eq!(59 + width, 80);
+
+    // Still outside the margin
+    move_note(&mut map_state, "x", 15);
+    assert_eq!(map_state.viewport.view_pos.x, 0);
+
+    // Pans just far enough to keep 5 cells to the edge
+    move_note(&mut map_state, "x", 3);
+    assert_eq!(map_state.viewport.view_pos.x, 3);
+    move_note(&mut map_state, "x", 1);
+    assert_eq!(map_state.viewport.view_pos.x, 4);
+
+    // And the same towards the top, where the note ends up 5 rows below the edge
+    map_state.viewport.view_pos.y = 10;
+    move_note(&mut map_state, "y", -3);
+    assert_eq!(map_state.viewport.view_pos.y, 10);
+    move_note(&mut map_state, "y", -4);
+    assert_eq!(map_state.notes_state.notes()[&0].y, 13);
+    assert_eq!(map_state.viewport.view_pos.y, 8);
+}
+
+#[test]
+fn test_move_note_cut_off_doesnt_jump_the_view() {
+    let mut map_state = create_test_map_state();
+    map_state.settings.scroll_margin = 10;
+
+    // Mostly past the bottom of the screen (47 rows above the bar)
+    map_state
+        .notes_state
+        .add(40, 46, String::from("Test"), Color::White);
+    map_state.notes_state.select(0);
+
+    move_note(&mut map_state, "y", 1);
+
+    // Follows the note by as much as it moved
+    assert_eq!(map_state.viewport.view_pos.y, 1);
+}
+
 #[test]
 fn test_move_note_invalid_axis() {
     let mut map_state = create_test_map_state();
@@ -396,7 +463,7 @@ fn test_switch_notes_focus_down_j() {
 
     // Should switch to note 1
     assert_eq!(map_state.notes_state.selected_note_id(), Some(1));
-    // Render order should be updated (selected note moved to back)
+    // Selecting doesn't change the stacking order
     assert_eq!(*map_state.notes_state.render_order(), vec![0, 1]);
 }
 
@@ -417,8 +484,8 @@ fn test_switch_notes_focus_up_k() {
 
     // Should switch to note 0
     assert_eq!(map_state.notes_state.selected_note_id(), Some(0));
-    // Render order should be updated
-    assert_eq!(*map_state.notes_state.render_order(), vec![1, 0]);
+    // Selecting doesn't change the stacking order
+    assert_eq!(*map_state.notes_state.render_order(), vec![0, 1]);
 }
 
 #[test]
@@ -458,7 +525,7 @@ fn test_switch_notes_focus_left_h() {
 
     // Should switch to note 0
     assert_eq!(map_state.notes_state.selected_note_id(), Some(0));
-    assert_eq!(*map_state.notes_state.render_order(), vec![1, 0]);
+    assert_eq!(*map_state.notes_state.render_order(), vec![0, 1]);
 }
 
 #[test]
@@ -505,13 +572,7 @@ fn test_switch_notes_focus_with_visual_connection() {
 
     // Set up visual connection mode
     map_state.mode = Mode::VisualConnect;
-    let connection = Connection {
-        from_id: 0,
-        from_side: Side::Right,
-        to_id: None,
-        to_side: None,
-        color: Color::White,
-    };
+    let connection = DraftConnection::new(0, Side::Right, Color::White);
     map_state.connections_state.focused_connection = Some(connection);
 
     // Add notes
@@ -528,10 +589,9 @@ fn test_switch_notes_focus_with_visual_connection() {
     // Should update the focused connection
     assert_eq!(map_state.notes_state.selected_note_id(), Some(1));
     if let Some(focused_conn) = &map_state.connections_state.focused_connection {
-        assert_eq!(focused_conn.to_id, Some(1));
         assert_eq!(
-            focused_conn.to_side,
-            Some(map_state.settings.default_end_side)
+            focused_conn.to,
+            Some((1, map_state.settings.default_end_side))
         );
     }
     assert_eq!(map_state.persistence.has_unsaved_changes, true);
@@ -543,13 +603,7 @@ fn test_switch_notes_focus_with_visual_connection_same_note() {
 
     // Set up visual connection mode
     map_state.mode = Mode::VisualConnect;
-    let connection = Connection {
-        from_id: 0,
-        from_side: Side::Right,
-        to_id: None,
-        to_side: None,
-        color: Color::White,
-    };
+    let connection = DraftConnection::new(0, Side::Right, Color::White);
     map_state.connections_state.focused_connection = Some(connection);
 
     map_state
@@ -565,22 +619,21 @@ fn test_switch_notes_focus_with_visual_connection_same_note() {
     // Check that fields changed to target note id
     assert_eq!(map_state.notes_state.selected_note_id(), Some(1));
     if let Some(focused_conn) = &map_state.connections_state.focused_connection {
-        assert_eq!(focused_conn.to_id, Some(1));
         assert_eq!(
-            focused_conn.to_side,
-            Some(map_state.settings.default_end_side)
+            focused_conn.to,
+            Some((1, map_state.settings.default_end_side))
         );
     }
 
     // Jump back to the connection source (from_id) note
     switch_notes_focus(&mut map_state, "h");
 
-    // Should reset to_id and to_side
-    // This is to prevent being able to make connection from source note to source note
+    // Makes a connection from the source note back to itself, looping out of the
+    // side it starts at
     assert_eq!(map_state.notes_state.selected_note_id(), Some(0));
     if let Some(focused_conn) = &map_state.connections_state.focused_connection {
-        assert_eq!(focused_conn.to_id, None);
-        assert_eq!(focused_conn.to_side, None);
+        assert_eq!(focused_conn.to, Some((0, focused_conn.from_side)));
+        assert!(focused_conn.completed().unwrap().is_self_loop());
     }
 }
 
@@ -0,0 +1,120 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::Color;
+use std::path::PathBuf;
+
+use crate::{
+    input::handler::map_kh,
+    states::{
+        MapState,
+        map::{Connection, Mode, Notification, Side},
+    },
+    utils::test_utils::MockFileSystem,
+};
+
+fn create_test_map_state() -> MapState {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = MapState::new_with_fs(PathBuf::from("/test/path"), &mock_fs);
+    map_state.viewport.screen_width = 100;
+    map_state.viewport.screen_height = 50;
+    map_state
+}
+
+fn create_key_event(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn type_keys(map_state: &mut MapState, keys: &str) {
+    for c in keys.chars() {
+        map_kh(map_state, create_key_event(KeyCode::Char(c)));
+    }
+}
+
+/// Adds a "questions" layer through the panel and makes it the active one.
+fn add_questions_layer(map_state: &mut MapState) {
+    type_keys(map_state, "ya");
+    type_keys(map_state, "questions");
+    map_kh(map_state, create_key_event(KeyCode::Enter));
+    map_kh(map_state, create_key_event(KeyCode::Enter));
+    map_kh(map_state, create_key_event(KeyCode::Esc));
+}
+
+#[test]
+fn test_layer_panel_adds_and_activates_layer() {
+    let mut map_state = create_test_map_state();
+    add_questions_layer(&mut map_state);
+
+    assert_eq!(map_state.layers_state.panel, None);
+    assert_eq!(map_state.layers_state.layers()[1].name, "questions");
+    assert_eq!(map_state.layers_state.active, 1);
+    assert!(map_state.persistence.has_unsaved_changes);
+
+    // New notes go on the active layer
+    type_keys(&mut map_state, "a");
+    let id = map_state.notes_state.expect_selected_note_id();
+    assert_eq!(map_state.notes_state.notes()[&id].layer(), 1);
+}
+
+#[test]
+fn test_hidden_and_locked_layers_cannot_be_selected() {
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(50, 25, String::from("fact"), Color::White);
+    add_questions_layer(&mut map_state);
+    map_state
+        .notes_state
+        .add(50, 25, String::from("why?"), Color::White);
+    map_state.notes_state.get_mut(1).unwrap().set_layer(1);
+    map_state.connections_state.add(Connection {
+        from_id: 0,
+        from_side: Side::Right,
+        to_id: 1,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    });
+
+    // Hide "questions": only the fact can be selected and the connection isn't drawn
+    type_keys(&mut map_state, "yv");
+    map_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert!(map_state.note_is_hidden(1));
+    assert!(map_state.connection_is_hidden(&map_state.connections_state.connections()[0]));
+    map_state.notes_state.get_mut(0).unwrap().x = 90;
+    type_keys(&mut map_state, "v");
+    assert_eq!(map_state.notes_state.selected_note_id(), Some(0));
+    map_kh(&mut map_state, create_key_event(KeyCode::Esc));
+
+    // Lock the default layer too: nothing can be selected, and no notes added
+    type_keys(&mut map_state, "ykl");
+    map_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert!(!map_state.note_is_selectable(0));
+    type_keys(&mut map_state, "va");
+    assert_eq!(map_state.mode, Mode::Normal);
+    assert_eq!(map_state.notes_state.notes().len(), 2);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::LayerLocked)
+    );
+}
+
+#[test]
+fn test_move_selection_to_active_layer() {
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(50, 25, String::from("a"), Color::White);
+    map_state
+        .notes_state
+        .add(60, 25, String::from("b"), Color::White);
+    add_questions_layer(&mut map_state);
+
+    map_state.notes_state.select_many(&[0, 1]);
+    map_state.mode = Mode::Visual;
+    type_keys(&mut map_state, "Y");
+
+    let notes = map_state.notes_state.notes();
+    assert_eq!((notes[&0].layer(), notes[&1].layer()), (1, 1));
+    assert_eq!(notes[&0].layer, Some(1));
+}
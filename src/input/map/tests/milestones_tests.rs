@@ -0,0 +1,170 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::Color;
+use std::path::Path;
+use tempfile::tempdir;
+
+use crate::{
+    input::handler::map_kh,
+    states::{
+        MapState,
+        map::{Mode, Notification},
+    },
+    utils::{read_milestones, test_utils::MockFileSystem},
+};
+
+fn create_test_map_state(map_path: &Path) -> MapState {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = MapState::new_with_fs(map_path.to_path_buf(), &mock_fs);
+    map_state.viewport.screen_width = 100;
+    map_state.viewport.screen_height = 50;
+    map_state
+}
+
+fn create_key_event(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn press_keys(map_state: &mut MapState, keys: &str) {
+    for c in keys.chars() {
+        map_kh(map_state, create_key_event(KeyCode::Char(c)));
+    }
+}
+
+/// Creates a milestone through the Normal mode prompt
+fn create_milestone(map_state: &mut MapState, name: &str) {
+    press_keys(map_state, "M");
+    press_keys(map_state, name);
+    map_kh(map_state, create_key_event(KeyCode::Enter));
+}
+
+#[test]
+fn test_create_milestone() {
+    let temp_dir = tempdir().unwrap();
+    let map_path = temp_dir.path().join("map.json");
+    let mut map_state = create_test_map_state(&map_path);
+    map_state
+        .notes_state
+        .add(10, 10, String::from("Idea"), Color::White);
+
+    press_keys(&mut map_state, "M");
+    assert_eq!(map_state.milestones.name_prompt.as_deref(), Some(""));
+    // Typed keys go to the name, not to Normal mode
+    press_keys(&mut map_state, "v1a");
+    assert_eq!(map_state.mode, Mode::Normal);
+    map_kh(&mut map_state, create_key_event(KeyCode::Enter));
+
+    assert_eq!(map_state.milestones.name_prompt, None);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::MilestoneSuccess)
+    );
+    let milestones = read_milestones(&map_path).unwrap();
+    assert_eq!(milestones.len(), 1);
+    assert_eq!(milestones[0].name, "v1a");
+    assert_eq!(milestones[0].map.notes[&0].content, "Idea");
+}
+
+#[test]
+fn test_empty_milestone_name_uses_date() {
+    let temp_dir = tempdir().unwrap();
+    let map_path = temp_dir.path().join("map.json");
+    let mut map_state = create_test_map_state(&map_path);
+
+    create_milestone(&mut map_state, "");
+
+    let milestones = read_milestones(&map_path).unwrap();
+    assert_eq!(
+        milestones[0].name,
+        milestones[0]
+            .created_at
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    );
+}
+
+#[test]
+fn test_cancel_milestone_prompt() {
+    let temp_dir = tempdir().unwrap();
+    let map_path = temp_dir.path().join("map.json");
+    let mut map_state = create_test_map_state(&map_path);
+
+    press_keys(&mut map_state, "Mdraft");
+    map_kh(&mut map_state, create_key_event(KeyCode::Esc));
+
+    assert_eq!(map_state.milestones.name_prompt, None);
+    assert!(read_milestones(&map_path).unwrap().is_empty());
+}
+
+#[test]
+fn test_open_milestone_read_only_in_second_tab() {
+    let temp_dir = tempdir().unwrap();
+    let map_path = temp_dir.path().join("map.json");
+    let mut map_state = create_test_map_state(&map_path);
+    map_state
+        .notes_state
+        .add(10, 10, String::from("Old"), Color::White);
+    create_milestone(&mut map_state, "first");
+    map_state
+        .notes_state
+        .add(40, 10, String::from("New"), Color::White);
+    create_milestone(&mut map_state, "second");
+    map_state.notes_state.get_mut(0).unwrap().content = String::from("Changed");
+
+    press_keys(&mut map_state, "O");
+    let picker = map_state.milestones.picker.as_ref().unwrap();
+    // Newest first
+    assert_eq!(picker.milestones[0].name, "second");
+
+    press_keys(&mut map_state, "j");
+    map_kh(&mut map_state, create_key_event(KeyCode::Enter));
+
+    assert_eq!(map_state.milestones.picker, None);
+    assert!(map_state.milestones.showing_milestone);
+    let open = map_state.milestones.open.as_ref().unwrap();
+    assert_eq!(open.name, "first");
+    assert_eq!(open.map_state.notes_state.notes().len(), 1);
+    assert_eq!(open.map_state.notes_state.notes()[&0].content, "Old");
+
+    // Editing keys do nothing in the milestone tab, scrolling moves only the snapshot
+    press_keys(&mut map_state, "aRll");
+    let open = map_state.milestones.open.as_ref().unwrap();
+    assert_eq!(open.map_state.notes_state.notes().len(), 1);
+    assert!(open.map_state.regions_state.regions().is_empty());
+    assert_eq!(open.map_state.viewport.view_pos.x, 2);
+    assert_eq!(map_state.viewport.view_pos.x, 0);
+    assert_eq!(map_state.notes_state.notes()[&0].content, "Changed");
+
+    // Tab switches back and forth, x closes the milestone
+    map_kh(&mut map_state, create_key_event(KeyCode::Tab));
+    assert!(!map_state.milestones.showing_milestone);
+    map_kh(&mut map_state, create_key_event(KeyCode::Tab));
+    assert!(map_state.milestones.showing_milestone);
+    press_keys(&mut map_state, "x");
+    assert_eq!(map_state.milestones.open, None);
+    assert!(!map_state.milestones.showing_milestone);
+
+    // Nothing to switch to once closed
+    map_kh(&mut map_state, create_key_event(KeyCode::Tab));
+    assert!(!map_state.milestones.showing_milestone);
+}
+
+#[test]
+fn test_milestone_picker_without_milestones() {
+    let temp_dir = tempdir().unwrap();
+    let mut map_state = create_test_map_state(&temp_dir.path().join("map.json"));
+
+    press_keys(&mut map_state, "O");
+    assert!(
+        map_state
+            .milestones
+            .picker
+            .as_ref()
+            .unwrap()
+            .milestones
+            .is_empty()
+    );
+
+    map_kh(&mut map_state, create_key_event(KeyCode::Enter));
+    assert_eq!(map_state.milestones.open, None);
+    assert_eq!(map_state.milestones.picker, None);
+}
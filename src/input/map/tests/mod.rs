@@ -1,7 +1,12 @@
+mod command_tests;
 mod delete_tests;
 mod edit_tests;
 mod helpers_tests;
+mod layers_tests;
+mod milestones_tests;
 mod normal_tests;
+mod region_tests;
+mod search_tests;
 mod text_editing_tests;
 mod vim_tests;
 mod visual_tests;
@@ -4,10 +4,13 @@ use std::path::PathBuf;
 
 use crate::{
     app::Screen,
-    input::{AppAction, map::normal::map_normal_kh},
+    input::{
+        AppAction,
+        map::{dialog::map_dialog_kh, help::map_help_kh, normal::map_normal_kh},
+    },
     states::{
         MapState,
-        map::{DiscardMenuType, Mode},
+        map::{DiscardMenuType, MapDialogAction, Mode, Notification, RepeatableAction},
     },
     utils::test_utils::MockFileSystem,
 };
@@ -26,6 +29,15 @@ fn create_key_event(code: KeyCode) -> KeyEvent {
     KeyEvent::new(code, KeyModifiers::NONE)
 }
 
+/// Where the shown discard menu's `q` leads, if one is shown.
+fn discard_menu_destination(map_state: &MapState) -> Option<MapDialogAction> {
+    map_state
+        .ui_state
+        .dialog
+        .as_ref()
+        .and_then(|dialog| dialog.action_for_key(KeyCode::Char('q')))
+}
+
 fn create_key_event_with_mods(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
     KeyEvent::new(code, modifiers)
 }
@@ -42,7 +54,7 @@ fn test_toggle_help_screen_with_f1() {
     let result = map_normal_kh(&mut map_state, create_key_event(KeyCode::F(1)), &mock_fs);
 
     assert_eq!(result, AppAction::Continue);
-    assert_eq!(map_state.ui_state.help_screen, Some(1));
+    assert_eq!(map_state.ui_state.help_screen, Some(2));
     assert_eq!(map_state.ui_state.needs_clear_and_redraw, true);
 }
 
@@ -60,18 +72,17 @@ fn test_toggle_help_screen_with_question_mark() {
     );
 
     assert_eq!(result, AppAction::Continue);
-    assert_eq!(map_state.ui_state.help_screen, Some(1));
+    assert_eq!(map_state.ui_state.help_screen, Some(2));
     assert_eq!(map_state.ui_state.needs_clear_and_redraw, true);
 }
 
 #[test]
 fn test_close_help_screen_with_f1() {
-    let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
     map_state.ui_state.show_help(1);
 
-    let result = map_normal_kh(&mut map_state, create_key_event(KeyCode::F(1)), &mock_fs);
+    let result = map_help_kh(&mut map_state, create_key_event(KeyCode::F(1)));
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.ui_state.help_screen, None);
@@ -80,16 +91,11 @@ fn test_close_help_screen_with_f1() {
 
 #[test]
 fn test_close_help_screen_with_question_mark() {
-    let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
     map_state.ui_state.show_help(1);
 
-    let result = map_normal_kh(
-        &mut map_state,
-        create_key_event(KeyCode::Char('?')),
-        &mock_fs,
-    );
+    let result = map_help_kh(&mut map_state, create_key_event(KeyCode::Char('?')));
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.ui_state.help_screen, None);
@@ -98,12 +104,11 @@ fn test_close_help_screen_with_question_mark() {
 
 #[test]
 fn test_close_help_screen_with_escape() {
-    let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
     map_state.ui_state.show_help(3);
 
-    let result = map_normal_kh(&mut map_state, create_key_event(KeyCode::Esc), &mock_fs);
+    let result = map_help_kh(&mut map_state, create_key_event(KeyCode::Esc));
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.ui_state.help_screen, None);
@@ -112,12 +117,11 @@ fn test_close_help_screen_with_escape() {
 
 #[test]
 fn test_help_next_page_with_right_arrow() {
-    let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
     map_state.ui_state.show_help(1);
 
-    let result = map_normal_kh(&mut map_state, create_key_event(KeyCode::Right), &mock_fs);
+    let result = map_help_kh(&mut map_state, create_key_event(KeyCode::Right));
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.ui_state.help_screen, Some(2));
@@ -126,16 +130,11 @@ fn test_help_next_page_with_right_arrow() {
 
 #[test]
 fn test_help_next_page_with_l() {
-    let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
     map_state.ui_state.show_help(2);
 
-    let result = map_normal_kh(
-        &mut map_state,
-        create_key_event(KeyCode::Char('l')),
-        &mock_fs,
-    );
+    let result = map_help_kh(&mut map_state, create_key_event(KeyCode::Char('l')));
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.ui_state.help_screen, Some(3));
@@ -144,12 +143,11 @@ fn test_help_next_page_with_l() {
 
 #[test]
 fn test_help_next_page_with_tab() {
-    let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
     map_state.ui_state.show_help(4);
 
-    let result = map_normal_kh(&mut map_state, create_key_event(KeyCode::Tab), &mock_fs);
+    let result = map_help_kh(&mut map_state, create_key_event(KeyCode::Tab));
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.ui_state.help_screen, Some(5));
@@ -158,12 +156,11 @@ fn test_help_next_page_with_tab() {
 
 #[test]
 fn test_help_next_page_wraps_to_first() {
-    let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
     map_state.ui_state.show_help(5);
 
-    let result = map_normal_kh(&mut map_state, create_key_event(KeyCode::Right), &mock_fs);
+    let result = map_help_kh(&mut map_state, create_key_event(KeyCode::Right));
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.ui_state.help_screen, Some(1));
@@ -172,12 +169,11 @@ fn test_help_next_page_wraps_to_first() {
 
 #[test]
 fn test_help_previous_page_with_left_arrow() {
-    let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
     map_state.ui_state.show_help(2);
 
-    let result = map_normal_kh(&mut map_state, create_key_event(KeyCode::Left), &mock_fs);
+    let result = map_help_kh(&mut map_state, create_key_event(KeyCode::Left));
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.ui_state.help_screen, Some(1));
@@ -186,16 +182,11 @@ fn test_help_previous_page_with_left_arrow() {
 
 #[test]
 fn test_help_previous_page_with_h() {
-    let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
     map_state.ui_state.show_help(3);
 
-    let result = map_normal_kh(
-        &mut map_state,
-        create_key_event(KeyCode::Char('h')),
-        &mock_fs,
-    );
+    let result = map_help_kh(&mut map_state, create_key_event(KeyCode::Char('h')));
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.ui_state.help_screen, Some(2));
@@ -204,12 +195,11 @@ fn test_help_previous_page_with_h() {
 
 #[test]
 fn test_help_previous_page_wraps_to_last() {
-    let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
     map_state.ui_state.show_help(1);
 
-    let result = map_normal_kh(&mut map_state, create_key_event(KeyCode::Left), &mock_fs);
+    let result = map_help_kh(&mut map_state, create_key_event(KeyCode::Left));
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.ui_state.help_screen, Some(5));
@@ -218,7 +208,6 @@ fn test_help_previous_page_wraps_to_last() {
 
 #[test]
 fn test_help_screen_blocks_other_input() {
-    let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
     map_state.ui_state.show_help(1);
@@ -226,11 +215,7 @@ fn test_help_screen_blocks_other_input() {
     map_state.viewport.view_pos.y = 10;
 
     // Try to move viewport - should be blocked by help screen
-    let result = map_normal_kh(
-        &mut map_state,
-        create_key_event(KeyCode::Char('j')),
-        &mock_fs,
-    );
+    let result = map_help_kh(&mut map_state, create_key_event(KeyCode::Char('j')));
 
     assert_eq!(result, AppAction::Continue);
     // Viewport should not have moved
@@ -240,6 +225,52 @@ fn test_help_screen_blocks_other_input() {
     assert_eq!(map_state.ui_state.help_screen, Some(1));
 }
 
+#[test]
+fn test_help_search_opens_page_of_first_match() {
+    let mut map_state = create_test_map_state();
+    map_state.ui_state.show_help(1);
+
+    map_help_kh(&mut map_state, create_key_event(KeyCode::Char('/')));
+    assert_eq!(map_state.ui_state.help_search, Some(String::new()));
+
+    for c in "waypx".chars() {
+        map_help_kh(&mut map_state, create_key_event(KeyCode::Char(c)));
+    }
+    map_help_kh(&mut map_state, create_key_event(KeyCode::Backspace));
+    assert_eq!(map_state.ui_state.help_search.as_deref(), Some("wayp"));
+
+    // Paging keys are typed into the query while searching
+    map_help_kh(&mut map_state, create_key_event(KeyCode::Char('l')));
+    map_help_kh(&mut map_state, create_key_event(KeyCode::Backspace));
+    assert_eq!(map_state.ui_state.help_screen, Some(1));
+
+    map_help_kh(&mut map_state, create_key_event(KeyCode::Enter));
+    assert_eq!(map_state.ui_state.help_search, None);
+    assert_eq!(map_state.ui_state.help_screen, Some(4));
+}
+
+#[test]
+fn test_help_search_escape_returns_to_page() {
+    let mut map_state = create_test_map_state();
+    map_state.ui_state.show_help(3);
+    map_state.ui_state.help_search = Some("no such binding".to_string());
+
+    // Nothing to open
+    map_help_kh(&mut map_state, create_key_event(KeyCode::Enter));
+    assert!(map_state.ui_state.help_search.is_some());
+
+    map_help_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert_eq!(map_state.ui_state.help_search, None);
+    assert_eq!(map_state.ui_state.help_screen, Some(3));
+}
+
+#[test]
+fn test_show_help_clamps_page() {
+    let mut map_state = create_test_map_state();
+    map_state.ui_state.show_help(9);
+    assert_eq!(map_state.ui_state.help_screen, Some(5));
+}
+
 // ==================== DISCARD MENU TESTS ====================
 
 #[test]
@@ -247,12 +278,12 @@ fn test_discard_menu_cancel_with_escape() {
     let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
-    map_state.ui_state.show_discard_menu(DiscardMenuType::Start);
+    map_state.show_discard_menu(DiscardMenuType::Start);
 
-    let result = map_normal_kh(&mut map_state, create_key_event(KeyCode::Esc), &mock_fs);
+    let result = map_dialog_kh(&mut map_state, create_key_event(KeyCode::Esc), &mock_fs);
 
     assert_eq!(result, AppAction::Continue);
-    assert_eq!(map_state.ui_state.confirm_discard_menu, None);
+    assert_eq!(map_state.ui_state.dialog, None);
     assert_eq!(map_state.ui_state.needs_clear_and_redraw, true);
 }
 
@@ -261,9 +292,9 @@ fn test_discard_menu_confirm_to_start_screen() {
     let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
-    map_state.ui_state.show_discard_menu(DiscardMenuType::Start);
+    map_state.show_discard_menu(DiscardMenuType::Start);
 
-    let result = map_normal_kh(
+    let result = map_dialog_kh(
         &mut map_state,
         create_key_event(KeyCode::Char('q')),
         &mock_fs,
@@ -283,11 +314,9 @@ fn test_discard_menu_confirm_to_settings_screen() {
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
     map_state.persistence.file_write_path = PathBuf::from("/test/map.json");
-    map_state
-        .ui_state
-        .show_discard_menu(DiscardMenuType::Settings);
+    map_state.show_discard_menu(DiscardMenuType::Settings);
 
-    let result = map_normal_kh(
+    let result = map_dialog_kh(
         &mut map_state,
         create_key_event(KeyCode::Char('q')),
         &mock_fs,
@@ -306,12 +335,12 @@ fn test_discard_menu_blocks_other_input() {
     let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
-    map_state.ui_state.show_discard_menu(DiscardMenuType::Start);
+    map_state.show_discard_menu(DiscardMenuType::Start);
     map_state.viewport.view_pos.x = 10;
     map_state.viewport.view_pos.y = 10;
 
     // Try to move viewport - should be blocked
-    let result = map_normal_kh(
+    let result = map_dialog_kh(
         &mut map_state,
         create_key_event(KeyCode::Char('j')),
         &mock_fs,
@@ -323,8 +352,8 @@ fn test_discard_menu_blocks_other_input() {
     assert_eq!(map_state.viewport.view_pos.y, 10);
     // Still showing discard menu
     assert_eq!(
-        map_state.ui_state.confirm_discard_menu,
-        Some(DiscardMenuType::Start)
+        discard_menu_destination(&map_state),
+        Some(MapDialogAction::Discard(DiscardMenuType::Start))
     );
 }
 
@@ -366,8 +395,8 @@ fn test_quit_when_can_exit_is_false_shows_discard_menu() {
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(
-        map_state.ui_state.confirm_discard_menu,
-        Some(DiscardMenuType::Start)
+        discard_menu_destination(&map_state),
+        Some(MapDialogAction::Discard(DiscardMenuType::Start))
     );
     assert_eq!(map_state.ui_state.needs_clear_and_redraw, true);
 }
@@ -434,12 +463,34 @@ fn test_open_settings_when_can_exit_is_false_shows_discard_menu() {
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(
-        map_state.ui_state.confirm_discard_menu,
-        Some(DiscardMenuType::Settings)
+        discard_menu_destination(&map_state),
+        Some(MapDialogAction::Discard(DiscardMenuType::Settings))
     );
     assert_eq!(map_state.ui_state.needs_clear_and_redraw, true);
 }
 
+#[test]
+fn test_open_backups_keeps_unsaved_session() {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = create_test_map_state();
+    map_state.add_note();
+    map_state.mode = Mode::Normal;
+
+    let result = map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('b')),
+        &mock_fs,
+    );
+
+    match result {
+        AppAction::Switch(Screen::Backups(backups_state)) => {
+            assert_eq!(backups_state.map_state.notes_state.notes().len(), 1);
+            assert!(backups_state.map_state.persistence.has_unsaved_changes);
+        }
+        _ => panic!("Expected AppAction::Switch to Backups screen"),
+    }
+}
+
 // ==================== VIEWPORT NAVIGATION TESTS ====================
 
 #[test]
@@ -480,7 +531,7 @@ fn test_move_viewport_left_with_arrow() {
 }
 
 #[test]
-fn test_move_viewport_left_saturates_at_zero() {
+fn test_move_viewport_left_past_origin() {
     let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
@@ -493,7 +544,7 @@ fn test_move_viewport_left_saturates_at_zero() {
     );
 
     assert_eq!(result, AppAction::Continue);
-    assert_eq!(map_state.viewport.view_pos.x, 0); // Should not underflow
+    assert_eq!(map_state.viewport.view_pos.x, -1);
 }
 
 #[test]
@@ -645,7 +696,7 @@ fn test_move_viewport_up_with_arrow() {
 }
 
 #[test]
-fn test_move_viewport_up_saturates_at_zero() {
+fn test_move_viewport_up_past_origin() {
     let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
@@ -658,7 +709,7 @@ fn test_move_viewport_up_saturates_at_zero() {
     );
 
     assert_eq!(result, AppAction::Continue);
-    assert_eq!(map_state.viewport.view_pos.y, 0); // Should not underflow
+    assert_eq!(map_state.viewport.view_pos.y, -1);
 }
 
 #[test]
@@ -901,7 +952,7 @@ fn test_select_note_with_single_note() {
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.notes_state.selected_note_id(), Some(0));
     assert_eq!(map_state.mode, Mode::Visual);
-    assert_eq!(*map_state.notes_state.render_order(), vec![0]); // Should be moved to back
+    assert_eq!(*map_state.notes_state.render_order(), vec![0]);
 }
 
 #[test]
@@ -935,11 +986,11 @@ fn test_select_closest_note_to_center() {
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.notes_state.selected_note_id(), Some(1)); // Note 1 is closest
     assert_eq!(map_state.mode, Mode::Visual);
-    assert_eq!(*map_state.notes_state.render_order(), vec![0, 2, 1]); // Note 1 moved to back
+    assert_eq!(*map_state.notes_state.render_order(), vec![0, 1, 2]); // Stacking is unchanged
 }
 
 #[test]
-fn test_select_note_updates_render_order() {
+fn test_select_note_keeps_render_order() {
     let mock_fs = MockFileSystem::new();
     let mut map_state = create_test_map_state();
     map_state.mode = Mode::Normal;
@@ -969,8 +1020,8 @@ fn test_select_note_updates_render_order() {
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.notes_state.selected_note_id(), Some(0));
-    // Note 0 should be moved to the back of render order
-    assert_eq!(*map_state.notes_state.render_order(), vec![1, 2, 0]);
+    // Note 0 stays behind the others
+    assert_eq!(*map_state.notes_state.render_order(), vec![0, 1, 2]);
 }
 
 // ==================== MISC TESTS ====================
@@ -1006,3 +1057,233 @@ fn test_normal_mode_continues() {
 
     assert_eq!(result, AppAction::Continue);
 }
+
+#[test]
+fn test_repeat_add_note_in_normal_mode() {
+    let mut map_state = create_test_map_state();
+    let fs = MockFileSystem::new();
+
+    map_normal_kh(&mut map_state, create_key_event(KeyCode::Char('a')), &fs);
+    map_state.notes_state.deselect();
+    map_state.mode = Mode::Normal;
+
+    map_normal_kh(&mut map_state, create_key_event(KeyCode::Char('.')), &fs);
+
+    assert_eq!(map_state.notes_state.notes().len(), 2);
+    assert_eq!(map_state.mode, Mode::Edit);
+}
+
+#[test]
+fn test_repeat_without_selection_skips_note_actions() {
+    let mut map_state = create_test_map_state();
+    let fs = MockFileSystem::new();
+    map_state
+        .notes_state
+        .add(10, 10, String::from("note"), Color::White);
+    map_state.repeat.record(RepeatableAction::Delete);
+
+    map_normal_kh(&mut map_state, create_key_event(KeyCode::Char('.')), &fs);
+
+    assert_eq!(map_state.notes_state.notes().len(), 1);
+}
+
+#[test]
+fn test_escape_clears_highlighted_path() {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = create_test_map_state();
+    map_state.ui_state.highlighted_path = vec![0, 1];
+
+    map_normal_kh(&mut map_state, create_key_event(KeyCode::Esc), &mock_fs);
+
+    assert!(map_state.ui_state.highlighted_path.is_empty());
+}
+
+#[test]
+fn test_toggle_graph_stats() {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = create_test_map_state();
+
+    map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('i')),
+        &mock_fs,
+    );
+    assert!(map_state.ui_state.show_stats);
+
+    map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('i')),
+        &mock_fs,
+    );
+    assert!(!map_state.ui_state.show_stats);
+}
+
+#[test]
+fn test_next_orphan_cycles_viewport() {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = create_test_map_state();
+    // "C" and "D" have no connections
+    map_state.paste_dsl("A -> B\nC\nD").unwrap();
+    let orphan_centers: Vec<(isize, isize)> = [2, 3]
+        .iter()
+        .map(|id| map_state.notes_state.notes()[id].center())
+        .collect();
+
+    for expected in [2, 3, 2] {
+        map_normal_kh(
+            &mut map_state,
+            create_key_event(KeyCode::Char('n')),
+            &mock_fs,
+        );
+
+        assert_eq!(map_state.ui_state.last_orphan, Some(expected));
+        assert_eq!(map_state.viewport.center(), orphan_centers[expected - 2]);
+    }
+}
+
+#[test]
+fn test_next_orphan_without_orphans() {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = create_test_map_state();
+    map_state.paste_dsl("A -> B").unwrap();
+    let view_pos = map_state.viewport.view_pos.clone();
+
+    map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('n')),
+        &mock_fs,
+    );
+
+    assert_eq!(map_state.viewport.view_pos, view_pos);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::NoOrphans)
+    );
+}
+
+#[test]
+fn test_zf_fits_map_on_screen() {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(200, 100, String::from("A"), Color::White);
+    map_state
+        .notes_state
+        .add(240, 120, String::from("B"), Color::White);
+    let bounds = map_state.content_bounds().unwrap();
+
+    map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('z')),
+        &mock_fs,
+    );
+    assert_eq!(map_state.ui_state.pending_key, Some('z'));
+    map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('f')),
+        &mock_fs,
+    );
+
+    assert_eq!(map_state.ui_state.pending_key, None);
+    assert!(map_state.persistence.has_unsaved_changes);
+    // Map's center is in the middle of the area above the 3 line bar
+    let view_pos = &map_state.viewport.view_pos;
+    assert_eq!(view_pos.x + 50, bounds.x + bounds.width / 2);
+    assert_eq!(view_pos.y + 47 / 2, bounds.y + bounds.height / 2);
+}
+
+#[test]
+fn test_z_prefix_cancelled_by_other_key() {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(200, 100, String::from("A"), Color::White);
+
+    map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('z')),
+        &mock_fs,
+    );
+    map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('x')),
+        &mock_fs,
+    );
+    // "x" only cancelled the prefix, and "f" alone does nothing
+    map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('f')),
+        &mock_fs,
+    );
+
+    assert_eq!(map_state.ui_state.pending_key, None);
+    assert_eq!(map_state.viewport.view_pos.x, 0);
+    assert_eq!(map_state.viewport.view_pos.y, 0);
+}
+
+#[test]
+fn test_gg_goes_to_origin() {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = create_test_map_state();
+    map_state.viewport.view_pos.x = 120;
+    map_state.viewport.view_pos.y = 80;
+
+    map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('g')),
+        &mock_fs,
+    );
+    assert_eq!(map_state.ui_state.pending_key, Some('g'));
+    map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('g')),
+        &mock_fs,
+    );
+
+    assert_eq!(map_state.ui_state.pending_key, None);
+    assert_eq!(map_state.viewport.view_pos.x, 0);
+    assert_eq!(map_state.viewport.view_pos.y, 0);
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_capital_g_goes_to_end_of_map() {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(20, 10, String::from("Start"), Color::White);
+    map_state
+        .notes_state
+        .add(400, 300, String::from("End"), Color::White);
+    let bounds = map_state.content_bounds().unwrap();
+
+    map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('G')),
+        &mock_fs,
+    );
+
+    // The last note's bottom right corner is at the bottom right above the 3 line bar
+    let view_pos = &map_state.viewport.view_pos;
+    assert_eq!(view_pos.x + 100, bounds.x + bounds.width);
+    assert_eq!(view_pos.y + 47, bounds.y + bounds.height);
+}
+
+#[test]
+fn test_capital_g_on_empty_map() {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = create_test_map_state();
+    map_state.viewport.view_pos.x = 30;
+
+    map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('G')),
+        &mock_fs,
+    );
+
+    assert_eq!(map_state.viewport.view_pos.x, 30);
+    assert!(!map_state.persistence.has_unsaved_changes);
+}
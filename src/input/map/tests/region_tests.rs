@@ -0,0 +1,449 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::Color;
+use std::path::PathBuf;
+
+use crate::{
+    input::map::{move_note, normal::map_normal_kh, region::map_region_kh, visual::map_visual_kh},
+    states::{
+        MapState,
+        map::{ArrangeOrder, Connection, Mode, Notification, Side},
+    },
+    utils::test_utils::MockFileSystem,
+};
+
+fn create_test_map_state() -> MapState {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = MapState::new_with_fs(PathBuf::from("/test/path"), &mock_fs);
+    map_state.viewport.screen_width = 100;
+    map_state.viewport.screen_height = 50;
+    map_state.persistence.mark_clean();
+    map_state
+}
+
+fn create_key_event(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn press_keys(map_state: &mut MapState, keys: &str) {
+    for c in keys.chars() {
+        map_region_kh(map_state, create_key_event(KeyCode::Char(c)));
+    }
+}
+
+/// Adds a region through Normal mode and finishes typing its label
+fn create_region_with_label(map_state: &mut MapState, label: &str) {
+    map_normal_kh(
+        map_state,
+        create_key_event(KeyCode::Char('R')),
+        &MockFileSystem::new(),
+    );
+    press_keys(map_state, label);
+    map_region_kh(map_state, create_key_event(KeyCode::Enter));
+}
+
+#[test]
+fn test_add_region_and_type_label() {
+    let mut map_state = create_test_map_state();
+
+    create_region_with_label(&mut map_state, "Done");
+
+    assert_eq!(map_state.mode, Mode::Region);
+    assert!(!map_state.regions_state.editing_label);
+    let region = map_state.regions_state.selected_region().unwrap();
+    assert_eq!(region.label, "Done");
+    assert_eq!((region.x, region.y), map_state.viewport.center());
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_label_keys_are_not_commands() {
+    let mut map_state = create_test_map_state();
+    map_normal_kh(
+        &mut map_state,
+        create_key_event(KeyCode::Char('R')),
+        &MockFileSystem::new(),
+    );
+
+    // 'd' would delete the region outside of label editing
+    press_keys(&mut map_state, "dh");
+    map_region_kh(&mut map_state, create_key_event(KeyCode::Backspace));
+
+    assert_eq!(map_state.regions_state.regions().len(), 1);
+    assert_eq!(
+        map_state.regions_state.selected_region().unwrap().label,
+        "d"
+    );
+}
+
+#[test]
+fn test_move_and_resize_region() {
+    let mut map_state = create_test_map_state();
+    create_region_with_label(&mut map_state, "Doing");
+    let before = map_state.regions_state.selected_region().unwrap().clone();
+
+    press_keys(&mut map_state, "llj");
+    map_region_kh(
+        &mut map_state,
+        KeyEvent::new(KeyCode::Right, KeyModifiers::SHIFT),
+    );
+    press_keys(&mut map_state, "LJ");
+
+    let after = map_state.regions_state.selected_region().unwrap();
+    assert_eq!((after.x, after.y), (before.x + 7, before.y + 1));
+    assert_eq!(
+        (after.width, after.height),
+        (before.width + 2, before.height + 1)
+    );
+}
+
+#[test]
+fn test_rename_region() {
+    let mut map_state = create_test_map_state();
+    create_region_with_label(&mut map_state, "A");
+
+    press_keys(&mut map_state, "rB");
+    map_region_kh(&mut map_state, create_key_event(KeyCode::Esc));
+
+    assert_eq!(
+        map_state.regions_state.selected_region().unwrap().label,
+        "AB"
+    );
+    // Esc only finished the label, still in Region mode
+    assert_eq!(map_state.mode, Mode::Region);
+}
+
+#[test]
+fn test_delete_region_returns_to_normal() {
+    let mut map_state = create_test_map_state();
+    create_region_with_label(&mut map_state, "Backlog");
+
+    press_keys(&mut map_state, "d");
+
+    assert!(map_state.regions_state.regions().is_empty());
+    assert_eq!(map_state.mode, Mode::Normal);
+}
+
+#[test]
+fn test_escape_deselects_region() {
+    let mut map_state = create_test_map_state();
+    create_region_with_label(&mut map_state, "Backlog");
+
+    map_region_kh(&mut map_state, create_key_event(KeyCode::Esc));
+
+    assert_eq!(map_state.mode, Mode::Normal);
+    assert_eq!(map_state.regions_state.selected_index(), None);
+}
+
+#[test]
+fn test_select_region_from_normal_mode() {
+    let mut map_state = create_test_map_state();
+    let fs = MockFileSystem::new();
+
+    // Nothing to select yet
+    map_normal_kh(&mut map_state, create_key_event(KeyCode::Char('r')), &fs);
+    assert_eq!(map_state.mode, Mode::Normal);
+
+    create_region_with_label(&mut map_state, "Backlog");
+    map_region_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    map_normal_kh(&mut map_state, create_key_event(KeyCode::Char('r')), &fs);
+
+    assert_eq!(map_state.mode, Mode::Region);
+    assert_eq!(map_state.regions_state.selected_index(), Some(0));
+}
+
+#[test]
+fn test_select_notes_in_region() {
+    let mut map_state = create_test_map_state();
+    create_region_with_label(&mut map_state, "Doing");
+    let (x, y) = map_state.viewport.center();
+
+    let lower = map_state
+        .notes_state
+        .add(x + 2, y + 6, String::from("b"), Color::White);
+    let upper = map_state
+        .notes_state
+        .add(x + 2, y + 1, String::from("a"), Color::White);
+    // Center falls outside the region
+    map_state
+        .notes_state
+        .add(x + 50, y, String::from("c"), Color::White);
+
+    assert_eq!(map_state.notes_in_region(0), vec![upper, lower]);
+
+    press_keys(&mut map_state, "s");
+
+    assert_eq!(map_state.mode, Mode::Visual);
+    assert_eq!(map_state.regions_state.selected_index(), None);
+    // Topmost note becomes the primary selection
+    assert_eq!(map_state.notes_state.selected_note_id(), Some(upper));
+    assert_eq!(map_state.notes_state.selection(), vec![lower, upper]);
+}
+
+#[test]
+fn test_select_notes_in_empty_region_does_nothing() {
+    let mut map_state = create_test_map_state();
+    create_region_with_label(&mut map_state, "Doing");
+
+    press_keys(&mut map_state, "s");
+
+    assert_eq!(map_state.mode, Mode::Region);
+    assert_eq!(map_state.notes_state.selected_note_id(), None);
+}
+
+#[test]
+fn test_moving_note_into_region_adds_tag() {
+    let mut map_state = create_test_map_state();
+    create_region_with_label(&mut map_state, "Done Today");
+    map_region_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    let (x, y) = map_state.viewport.center();
+
+    // Note's center is 11 cells left of the region's edge
+    let id = map_state
+        .notes_state
+        .add(x - 21, y, String::from("Ship it"), Color::White);
+    map_state.notes_state.select(id);
+    map_state.mode = Mode::VisualMove;
+
+    move_note(&mut map_state, "x", 10);
+    assert_eq!(map_state.notes_state.notes()[&id].content, "Ship it");
+
+    move_note(&mut map_state, "x", 1);
+    assert_eq!(
+        map_state.notes_state.notes()[&id].content,
+        "Ship it #done-today"
+    );
+
+    // Moving within the region, or back out and in again, doesn't tag twice
+    move_note(&mut map_state, "x", 1);
+    move_note(&mut map_state, "x", -2);
+    move_note(&mut map_state, "x", 2);
+    assert_eq!(
+        map_state.notes_state.notes()[&id].content,
+        "Ship it #done-today"
+    );
+}
+
+#[test]
+fn test_auto_tag_can_be_turned_off() {
+    let mut map_state = create_test_map_state();
+    create_region_with_label(&mut map_state, "Done");
+    assert!(map_state.regions_state.selected_region().unwrap().auto_tag);
+
+    press_keys(&mut map_state, "t");
+    assert!(!map_state.regions_state.selected_region().unwrap().auto_tag);
+
+    map_region_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    let (x, y) = map_state.viewport.center();
+    let id = map_state
+        .notes_state
+        .add(x - 30, y, String::from("Ship it"), Color::White);
+    map_state.notes_state.select(id);
+    move_note(&mut map_state, "x", 30);
+
+    assert_eq!(map_state.notes_state.notes()[&id].content, "Ship it");
+}
+
+/// Adds notes inside a "Lane" region and returns their ids in creation order
+fn create_lane_with_notes(map_state: &mut MapState, contents: &[&str]) -> Vec<usize> {
+    create_region_with_label(map_state, "Lane");
+    map_state
+        .regions_state
+        .selected_region_mut()
+        .unwrap()
+        .auto_tag = false;
+    let (x, y) = map_state.viewport.center();
+
+    contents
+        .iter()
+        .enumerate()
+        .map(|(i, content)| {
+            map_state
+                .notes_state
+                .add(x + 3 + i as isize, y + 1, content.to_string(), Color::White)
+        })
+        .collect()
+}
+
+/// Ids of the notes sorted by their position in the grid
+fn grid_order(map_state: &MapState) -> Vec<usize> {
+    let mut ids: Vec<usize> = map_state.notes_state.notes().keys().copied().collect();
+    ids.sort_by_key(|id| {
+        let note = &map_state.notes_state.notes()[id];
+        (note.y, note.x)
+    });
+    ids
+}
+
+#[test]
+fn test_arrange_region_by_title() {
+    let mut map_state = create_test_map_state();
+    let ids = create_lane_with_notes(&mut map_state, &["charlie", "Alpha", "bravo"]);
+    map_state.persistence.mark_clean();
+
+    press_keys(&mut map_state, "a");
+
+    assert_eq!(grid_order(&map_state), vec![ids[1], ids[2], ids[0]]);
+    // Grid starts inside the region's border
+    let region = map_state.regions_state.selected_region().unwrap();
+    let first = &map_state.notes_state.notes()[&ids[1]];
+    assert_eq!((first.x, first.y), (region.x + 2, region.y + 2));
+    assert!(map_state.persistence.has_unsaved_changes);
+    assert_eq!(map_state.notes_in_region(0).len(), 3);
+}
+
+#[test]
+fn test_arrange_region_by_priority_and_recency() {
+    let mut map_state = create_test_map_state();
+    let ids = create_lane_with_notes(&mut map_state, &["b", "a #p1", "c #p2"]);
+
+    press_keys(&mut map_state, "o");
+    assert_eq!(
+        map_state.regions_state.arrange_order,
+        ArrangeOrder::Priority
+    );
+    press_keys(&mut map_state, "a");
+    assert_eq!(grid_order(&map_state), vec![ids[1], ids[2], ids[0]]);
+
+    press_keys(&mut map_state, "o");
+    press_keys(&mut map_state, "a");
+    assert_eq!(grid_order(&map_state), vec![ids[2], ids[1], ids[0]]);
+
+    // No connections, so ordering by weight falls back to title
+    press_keys(&mut map_state, "o");
+    press_keys(&mut map_state, "a");
+    assert_eq!(grid_order(&map_state), vec![ids[1], ids[0], ids[2]]);
+
+    // Back to title
+    press_keys(&mut map_state, "o");
+    assert_eq!(map_state.regions_state.arrange_order, ArrangeOrder::Title);
+}
+
+#[test]
+fn test_arrange_region_by_connection_weight() {
+    let mut map_state = create_test_map_state();
+    let ids = create_lane_with_notes(&mut map_state, &["a", "b", "c"]);
+    let connect = |from_id: usize, to_id: usize, weight: Option<u32>| Connection {
+        from_id,
+        from_side: Side::Right,
+        to_id,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight,
+        layer: None,
+    };
+    // Totals: a = 1, b = 1 + 5 = 6, c = 5
+    map_state
+        .connections_state
+        .add(connect(ids[0], ids[1], None));
+    map_state
+        .connections_state
+        .add(connect(ids[1], ids[2], Some(5)));
+
+    map_state.regions_state.arrange_order = ArrangeOrder::Weight;
+    press_keys(&mut map_state, "a");
+
+    assert_eq!(grid_order(&map_state), vec![ids[1], ids[2], ids[0]]);
+}
+
+/// Groups notes 0 and 1 (of three) through Visual mode and labels the group "Backend"
+fn create_group(map_state: &mut MapState) {
+    for (x, y) in [(20, 10), (50, 12), (20, 40)] {
+        map_state
+            .notes_state
+            .add(x, y, String::from("Note"), Color::White);
+    }
+    map_state.notes_state.select_many(&[0, 1]);
+    map_state.mode = Mode::Visual;
+
+    map_visual_kh(map_state, create_key_event(KeyCode::Char('g')));
+    press_keys(map_state, "Backend");
+    map_region_kh(map_state, create_key_event(KeyCode::Enter));
+}
+
+#[test]
+fn test_group_selected_notes() {
+    let mut map_state = create_test_map_state();
+    create_group(&mut map_state);
+
+    assert_eq!(map_state.mode, Mode::Region);
+    assert_eq!(map_state.notes_state.selected_note_id(), None);
+    let group = map_state.regions_state.selected_region().unwrap();
+    assert_eq!(group.label, "Backend");
+    assert_eq!(group.members, vec![0, 1]);
+    // Drawn around both notes (x 20 to 70, y 10 to 15) with 2 cells to spare
+    assert_eq!(
+        (group.x, group.y, group.width, group.height),
+        (18, 8, 55, 10)
+    );
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_moving_group_moves_members() {
+    let mut map_state = create_test_map_state();
+    create_group(&mut map_state);
+
+    press_keys(&mut map_state, "lj");
+
+    let notes = map_state.notes_state.notes();
+    assert_eq!((notes[&0].x, notes[&0].y), (21, 11));
+    assert_eq!((notes[&1].x, notes[&1].y), (51, 13));
+    assert_eq!((notes[&2].x, notes[&2].y), (20, 40));
+
+    // A group selects and counts its members rather than the notes inside it
+    map_state.notes_state.get_mut(1).unwrap().x = 200;
+    assert_eq!(map_state.notes_in_region(0), vec![0, 1]);
+}
+
+#[test]
+fn test_delete_group_keeps_or_deletes_members() {
+    let mut map_state = create_test_map_state();
+    create_group(&mut map_state);
+
+    press_keys(&mut map_state, "d");
+    assert_eq!(map_state.mode, Mode::Normal);
+    assert!(map_state.regions_state.regions().is_empty());
+    assert_eq!(map_state.notes_state.notes().len(), 3);
+
+    let mut map_state = create_test_map_state();
+    create_group(&mut map_state);
+
+    press_keys(&mut map_state, "D");
+    assert!(map_state.regions_state.regions().is_empty());
+    let ids: Vec<&usize> = map_state.notes_state.notes().keys().collect();
+    assert_eq!(ids, vec![&2]);
+}
+
+#[test]
+fn test_locked_members_stay_put() {
+    let mut map_state = create_test_map_state();
+    create_group(&mut map_state);
+    map_state.notes_state.get_mut(1).unwrap().locked = true;
+
+    press_keys(&mut map_state, "l");
+    let notes = map_state.notes_state.notes();
+    assert_eq!((notes[&0].x, notes[&1].x), (20, 50));
+    assert_eq!(map_state.regions_state.selected_region().unwrap().x, 18);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::NoteLocked)
+    );
+
+    press_keys(&mut map_state, "D");
+    let ids: Vec<&usize> = map_state.notes_state.notes().keys().collect();
+    assert_eq!(ids.len(), 2);
+    assert!(map_state.notes_state.notes().contains_key(&1));
+}
+
+#[test]
+fn test_deleted_note_leaves_group() {
+    let mut map_state = create_test_map_state();
+    create_group(&mut map_state);
+    map_region_kh(&mut map_state, create_key_event(KeyCode::Esc));
+
+    map_state.notes_state.select(1);
+    map_state.delete_selected_note();
+
+    assert_eq!(map_state.regions_state.regions()[0].members, vec![0]);
+}
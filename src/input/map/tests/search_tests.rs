@@ -0,0 +1,105 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::PathBuf;
+
+use crate::{
+    input::handler::map_kh,
+    states::{MapState, map::Notification},
+    utils::test_utils::MockFileSystem,
+};
+
+fn create_test_map_state() -> MapState {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = MapState::new_with_fs(PathBuf::from("/test/path"), &mock_fs);
+    map_state.viewport.screen_width = 100;
+    map_state.viewport.screen_height = 50;
+    map_state
+}
+
+fn create_key_event(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn type_keys(map_state: &mut MapState, keys: &str) {
+    for c in keys.chars() {
+        map_kh(map_state, create_key_event(KeyCode::Char(c)));
+    }
+}
+
+/// Notes "todo: a" at the top, "todo: b, todo: c" below it and "done" below that.
+fn create_searchable_map_state() -> MapState {
+    let mut map_state = create_test_map_state();
+    map_state
+        .paste_dsl("todo: a\ntodo: b, todo: c\ndone")
+        .unwrap();
+    for (id, y) in [(0, 10), (1, 50), (2, 90)] {
+        let note = map_state.notes_state.get_mut(id).unwrap();
+        note.x = 100;
+        note.y = y;
+    }
+    map_state
+}
+
+fn current_match(map_state: &MapState) -> Option<(usize, usize)> {
+    let search = map_state.ui_state.search.as_ref()?;
+    let current = search.current.as_ref()?;
+    Some((current.note_id, current.range.start))
+}
+
+#[test]
+fn test_typing_moves_to_first_match() {
+    let mut map_state = create_searchable_map_state();
+
+    type_keys(&mut map_state, "/todo: [bc]");
+
+    assert!(map_state.ui_state.search.as_ref().unwrap().typing);
+    assert_eq!(current_match(&map_state), Some((1, 0)));
+    assert_eq!(
+        map_state.viewport.center(),
+        map_state.notes_state.notes()[&1].center()
+    );
+
+    // Typed keys go to the query, not to Normal mode
+    assert_eq!(map_state.notes_state.notes().len(), 3);
+}
+
+#[test]
+fn test_n_and_shift_n_cycle_matches() {
+    let mut map_state = create_searchable_map_state();
+    type_keys(&mut map_state, "/todo");
+    map_kh(&mut map_state, create_key_event(KeyCode::Enter));
+    assert_eq!(current_match(&map_state), Some((0, 0)));
+
+    let mut visited = vec![];
+    for _ in 0..3 {
+        type_keys(&mut map_state, "n");
+        visited.push(current_match(&map_state).unwrap());
+    }
+    assert_eq!(visited, vec![(1, 0), (1, 9), (0, 0)]);
+
+    type_keys(&mut map_state, "N");
+    assert_eq!(current_match(&map_state), Some((1, 9)));
+
+    // Esc clears the search and `n` goes back to orphaned notes
+    map_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert_eq!(map_state.ui_state.search, None);
+    type_keys(&mut map_state, "n");
+    assert_eq!(map_state.ui_state.last_orphan, Some(0));
+}
+
+#[test]
+fn test_search_without_matches_closes_on_enter() {
+    let mut map_state = create_searchable_map_state();
+    type_keys(&mut map_state, "/missing");
+    map_kh(&mut map_state, create_key_event(KeyCode::Enter));
+
+    assert_eq!(map_state.ui_state.search, None);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::PatternNotFound)
+    );
+
+    type_keys(&mut map_state, "/x");
+    map_kh(&mut map_state, create_key_event(KeyCode::Backspace));
+    map_kh(&mut map_state, create_key_event(KeyCode::Backspace));
+    assert_eq!(map_state.ui_state.search, None);
+}
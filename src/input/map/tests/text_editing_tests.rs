@@ -4,7 +4,7 @@ use std::path::PathBuf;
 use crate::{
     input::map::{
         cursor_pos_beginning, remove_char,
-        text_editing::{backspace_char, insert_char, move_cursor_down, move_cursor_up},
+        text_editing::{backspace_char, insert_char, move_cursor_down, move_cursor_up, paste_text},
     },
     states::MapState,
     utils::test_utils::MockFileSystem,
@@ -990,3 +990,65 @@ fn test_multiline_editing() {
         "Hello!\nWorld"
     );
 }
+
+// ============================================================================
+// Tests for paste_text
+// ============================================================================
+
+#[test]
+fn test_paste_multiple_lines() {
+    let mut map_state = create_test_map_state();
+
+    map_state
+        .notes_state
+        .add(10, 10, String::from("Todo:"), Color::White);
+    map_state.notes_state.select(0);
+    map_state.notes_state.set_cursor_pos(5);
+
+    paste_text(&mut map_state, "\r\n- milk\r\n- 卵");
+
+    assert_eq!(
+        map_state.notes_state.notes().get(&0).unwrap().content,
+        "Todo:\n- milk\n- 卵"
+    );
+    assert_eq!(
+        map_state.notes_state.cursor_pos(),
+        "Todo:\n- milk\n- 卵".len()
+    );
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_paste_is_undone_at_once() {
+    let mut map_state = create_test_map_state();
+
+    map_state
+        .notes_state
+        .add(10, 10, String::from("Hello"), Color::White);
+    map_state.notes_state.select(0);
+    map_state.notes_state.set_cursor_pos(5);
+
+    paste_text(&mut map_state, " there,\nworld");
+    assert_eq!(map_state.history.steps().len(), 1);
+
+    map_state.undo();
+    assert_eq!(
+        map_state.notes_state.notes().get(&0).unwrap().content,
+        "Hello"
+    );
+}
+
+#[test]
+fn test_paste_nothing() {
+    let mut map_state = create_test_map_state();
+
+    map_state
+        .notes_state
+        .add(10, 10, String::from("Hello"), Color::White);
+    map_state.notes_state.select(0);
+
+    paste_text(&mut map_state, "");
+
+    assert!(map_state.history.steps().is_empty());
+    assert!(!map_state.persistence.has_unsaved_changes);
+}
@@ -3,12 +3,15 @@ use ratatui::style::Color;
 use std::path::PathBuf;
 
 use crate::{
-    input::{AppAction, map::visual::map_visual_kh},
+    input::{AppAction, handler::map_kh, map::visual::map_visual_kh},
     states::{
         MapState,
-        map::{Connection, Mode, Side},
+        map::{
+            Connection, DraftConnection, Hyperedge, Mode, NoteStatus, Notification,
+            RepeatableAction, Side,
+        },
     },
-    utils::test_utils::MockFileSystem,
+    utils::{Point, test_utils::MockFileSystem},
 };
 
 fn create_test_map_state() -> MapState {
@@ -101,6 +104,7 @@ fn test_visual_enter_delete_mode() {
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(map_state.mode, Mode::Delete);
+    assert!(map_state.ui_state.dialog.is_some());
 }
 
 #[test]
@@ -144,8 +148,7 @@ fn test_visual_create_new_connection() {
     assert!(map_state.connections_state.focused_connection.is_some());
     let connection = map_state.connections_state.focused_connection.unwrap();
     assert_eq!(connection.from_id, 0);
-    assert_eq!(connection.to_id, None);
-    assert_eq!(connection.to_side, None);
+    assert_eq!(connection.to, None);
     assert_eq!(connection.color, Color::White);
 }
 
@@ -167,11 +170,14 @@ fn test_visual_enter_connection_mode_with_existing_connection() {
     let connection = Connection {
         from_id: 0,
         from_side: Side::Right,
-        to_id: Some(1),
-        to_side: Some(Side::Left),
+        to_id: 1,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection);
+    map_state.connections_state.focused_connection = Some(connection.into());
     map_state.connections_state.stash_connection();
 
     let result = map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('c')));
@@ -531,12 +537,14 @@ fn test_connection_mode_exit_with_c() {
     map_state.mode = Mode::VisualConnect;
 
     // Set up a focused connection
-    map_state.connections_state.focused_connection = Some(Connection {
+    map_state.connections_state.focused_connection = Some(DraftConnection {
         from_id: 0,
         from_side: Side::Right,
-        to_id: Some(1),
-        to_side: Some(Side::Left),
+        to: Some((1, Side::Left)),
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     });
 
     let result = map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('c')));
@@ -558,13 +566,8 @@ fn test_connection_mode_rotate_from_side() {
     map_state.mode = Mode::VisualConnect;
 
     // Set up a focused connection starting from note 0
-    map_state.connections_state.focused_connection = Some(Connection {
-        from_id: 0,
-        from_side: Side::Top,
-        to_id: None,
-        to_side: None,
-        color: Color::White,
-    });
+    map_state.connections_state.focused_connection =
+        Some(DraftConnection::new(0, Side::Top, Color::White));
 
     let result = map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('r')));
 
@@ -590,12 +593,14 @@ fn test_connection_mode_rotate_to_side() {
     map_state.mode = Mode::VisualConnect;
 
     // Set up a focused connection with note 1 as target
-    map_state.connections_state.focused_connection = Some(Connection {
+    map_state.connections_state.focused_connection = Some(DraftConnection {
         from_id: 0,
         from_side: Side::Right,
-        to_id: Some(1),
-        to_side: Some(Side::Left),
+        to: Some((1, Side::Left)),
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     });
 
     let result = map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('r')));
@@ -605,7 +610,7 @@ fn test_connection_mode_rotate_to_side() {
 
     // to_side should have cycled
     let connection = map_state.connections_state.focused_connection.unwrap();
-    assert_ne!(connection.to_side, Some(Side::Left)); // Should have changed
+    assert_ne!(connection.to, Some((1, Side::Left))); // Should have changed
 }
 
 #[test]
@@ -628,31 +633,40 @@ fn test_connection_mode_cycle_connections() {
     let connection1 = Connection {
         from_id: 0,
         from_side: Side::Right,
-        to_id: Some(1),
-        to_side: Some(Side::Left),
+        to_id: 1,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection1);
+    map_state.connections_state.focused_connection = Some(connection1.into());
     map_state.connections_state.stash_connection();
 
     let connection2 = Connection {
         from_id: 0,
         from_side: Side::Bottom,
-        to_id: Some(2),
-        to_side: Some(Side::Top),
+        to_id: 2,
+        to_side: Side::Top,
         color: Color::Green,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection2);
+    map_state.connections_state.focused_connection = Some(connection2.into());
     map_state.connections_state.stash_connection();
 
     let connection3 = Connection {
         from_id: 0,
         from_side: Side::Top,
-        to_id: Some(1),
-        to_side: Some(Side::Bottom),
+        to_id: 1,
+        to_side: Side::Bottom,
         color: Color::Blue,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection3);
+    map_state.connections_state.focused_connection = Some(connection3.into());
     map_state.connections_state.stash_connection();
 
     // Set up focused connection and editing index (connection1 was at index 0)
@@ -663,27 +677,39 @@ fn test_connection_mode_cycle_connections() {
     let result = map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('n')));
     assert_eq!(result, AppAction::Continue);
     assert!(map_state.connections_state.focused_connection.is_some());
-    let focused = map_state.connections_state.focused_connection.unwrap();
+    let focused = map_state
+        .connections_state
+        .focused_connection
+        .clone()
+        .unwrap();
     assert_eq!(focused.from_side, Side::Bottom);
-    assert_eq!(focused.to_id, Some(2));
+    assert_eq!(focused.to.map(|(to_id, _)| to_id), Some(2));
     assert_eq!(focused.color, Color::Green);
 
     // Second cycle - should move to connection3
     let result = map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('n')));
     assert_eq!(result, AppAction::Continue);
     assert!(map_state.connections_state.focused_connection.is_some());
-    let focused = map_state.connections_state.focused_connection.unwrap();
+    let focused = map_state
+        .connections_state
+        .focused_connection
+        .clone()
+        .unwrap();
     assert_eq!(focused.from_side, Side::Top);
-    assert_eq!(focused.to_id, Some(1));
+    assert_eq!(focused.to.map(|(to_id, _)| to_id), Some(1));
     assert_eq!(focused.color, Color::Blue);
 
     // Third cycle - should wrap around back to connection1
     let result = map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('n')));
     assert_eq!(result, AppAction::Continue);
     assert!(map_state.connections_state.focused_connection.is_some());
-    let focused = map_state.connections_state.focused_connection.unwrap();
+    let focused = map_state
+        .connections_state
+        .focused_connection
+        .clone()
+        .unwrap();
     assert_eq!(focused.from_side, Side::Right);
-    assert_eq!(focused.to_id, Some(1));
+    assert_eq!(focused.to.map(|(to_id, _)| to_id), Some(1));
     assert_eq!(focused.color, Color::White);
 }
 
@@ -704,21 +730,18 @@ fn test_connection_mode_cycle_connections_not_editing() {
     let existing_connection = Connection {
         from_id: 0,
         from_side: Side::Bottom,
-        to_id: Some(1),
-        to_side: Some(Side::Top),
+        to_id: 1,
+        to_side: Side::Top,
         color: Color::Green,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(existing_connection);
+    map_state.connections_state.focused_connection = Some(existing_connection.into());
     map_state.connections_state.stash_connection();
 
     // Set up a focused connection (partial - being created)
-    let focused_connection = Connection {
-        from_id: 0,
-        from_side: Side::Right,
-        to_id: None,
-        to_side: None,
-        color: Color::Blue,
-    };
+    let focused_connection = DraftConnection::new(0, Side::Right, Color::Blue);
     map_state.connections_state.focused_connection = Some(focused_connection);
 
     let result = map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('n')));
@@ -727,9 +750,13 @@ fn test_connection_mode_cycle_connections_not_editing() {
 
     // Should NOT cycle when creating a new connection (not editing an existing one)
     // The focused connection should remain unchanged
-    let focused = map_state.connections_state.focused_connection.unwrap();
+    let focused = map_state
+        .connections_state
+        .focused_connection
+        .clone()
+        .unwrap();
     assert_eq!(focused.from_side, Side::Right); // Should be unchanged
-    assert_eq!(focused.to_id, None); // Should be unchanged
+    assert_eq!(focused.to, None); // Should be unchanged
     assert_eq!(focused.color, Color::Blue); // Should be unchanged
 
     // The existing connection should still be in the vector
@@ -756,12 +783,15 @@ fn test_connection_mode_delete_connection() {
     let connection = Connection {
         from_id: 0,
         from_side: Side::Right,
-        to_id: Some(1),
-        to_side: Some(Side::Left),
+        to_id: 1,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
 
-    map_state.connections_state.focused_connection = Some(connection);
+    map_state.connections_state.focused_connection = Some(connection.into());
     map_state.connections_state.editing_connection_index = Some(0);
 
     let result = map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('d')));
@@ -783,13 +813,7 @@ fn test_connection_mode_delete_not_editing() {
     map_state.notes_state.select(0);
     map_state.mode = Mode::VisualConnect;
 
-    let connection = Connection {
-        from_id: 0,
-        from_side: Side::Right,
-        to_id: None,
-        to_side: None,
-        color: Color::White,
-    };
+    let connection = DraftConnection::new(0, Side::Right, Color::White);
 
     map_state.connections_state.focused_connection = Some(connection);
 
@@ -815,13 +839,8 @@ fn test_connection_mode_switch_focus_for_target() {
     map_state.mode = Mode::VisualConnect;
 
     // Creating a new connection
-    map_state.connections_state.focused_connection = Some(Connection {
-        from_id: 0,
-        from_side: Side::Right,
-        to_id: None,
-        to_side: None,
-        color: Color::White,
-    });
+    map_state.connections_state.focused_connection =
+        Some(DraftConnection::new(0, Side::Right, Color::White));
 
     // Test all direction keys
     let _ = map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('j')));
@@ -847,13 +866,8 @@ fn test_connection_mode_cycle_color() {
     map_state.notes_state.select(0);
     map_state.mode = Mode::VisualConnect;
 
-    map_state.connections_state.focused_connection = Some(Connection {
-        from_id: 0,
-        from_side: Side::Right,
-        to_id: None,
-        to_side: None,
-        color: Color::White,
-    });
+    map_state.connections_state.focused_connection =
+        Some(DraftConnection::new(0, Side::Right, Color::White));
 
     let result = map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('e')));
 
@@ -893,13 +907,8 @@ fn test_connection_mode_clear_and_redraw() {
     map_state.notes_state.select(0);
     map_state.mode = Mode::VisualConnect;
 
-    map_state.connections_state.focused_connection = Some(Connection {
-        from_id: 0,
-        from_side: Side::Right,
-        to_id: None,
-        to_side: None,
-        color: Color::White,
-    });
+    map_state.connections_state.focused_connection =
+        Some(DraftConnection::new(0, Side::Right, Color::White));
 
     let _result = map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('e')));
 
@@ -916,13 +925,8 @@ fn test_connection_mode_unhandled_keys() {
     map_state.notes_state.select(0);
     map_state.mode = Mode::VisualConnect;
 
-    map_state.connections_state.focused_connection = Some(Connection {
-        from_id: 0,
-        from_side: Side::Right,
-        to_id: None,
-        to_side: None,
-        color: Color::White,
-    });
+    map_state.connections_state.focused_connection =
+        Some(DraftConnection::new(0, Side::Right, Color::White));
 
     // Test various unhandled keys
     let test_keys = vec![
@@ -964,21 +968,27 @@ fn test_multiple_notes_with_connections() {
     let connection1 = Connection {
         from_id: 0,
         from_side: Side::Right,
-        to_id: Some(1),
-        to_side: Some(Side::Left),
+        to_id: 1,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection1);
+    map_state.connections_state.focused_connection = Some(connection1.into());
     map_state.connections_state.stash_connection();
 
     let connection2 = Connection {
         from_id: 1,
         from_side: Side::Right,
-        to_id: Some(2),
-        to_side: Some(Side::Left),
+        to_id: 2,
+        to_side: Side::Left,
         color: Color::Green,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection2);
+    map_state.connections_state.focused_connection = Some(connection2.into());
     map_state.connections_state.stash_connection();
 
     // Enter connection mode on note 0
@@ -990,7 +1000,7 @@ fn test_multiple_notes_with_connections() {
     // Confirm it is the 0->1 connection
     let focused_connection = map_state.connections_state.focused_connection.unwrap();
     assert_eq!(focused_connection.from_id, 0);
-    assert_eq!(focused_connection.to_id, Some(1));
+    assert_eq!(focused_connection.to.map(|(to_id, _)| to_id), Some(1));
     assert_eq!(focused_connection.color, Color::White);
 }
 
@@ -1057,8 +1067,7 @@ fn test_connection_mode_with_partial_connection() {
 
     let connection = map_state.connections_state.focused_connection.unwrap();
     assert_eq!(connection.from_id, 0);
-    assert_eq!(connection.to_id, None);
-    assert_eq!(connection.to_side, None);
+    assert_eq!(connection.to, None);
 }
 
 #[test]
@@ -1078,12 +1087,15 @@ fn test_stash_and_cycle_connections_wraparound() {
     let connection = Connection {
         from_id: 0,
         from_side: Side::Right,
-        to_id: Some(1),
-        to_side: Some(Side::Left),
+        to_id: 1,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
 
-    map_state.connections_state.focused_connection = Some(connection);
+    map_state.connections_state.focused_connection = Some(connection.into());
     map_state.connections_state.editing_connection_index = Some(0);
 
     let result = map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('n')));
@@ -1094,5 +1106,904 @@ fn test_stash_and_cycle_connections_wraparound() {
     // The same connection
     let focused_connection = map_state.connections_state.focused_connection.unwrap();
     assert_eq!(focused_connection.from_id, 0);
-    assert_eq!(focused_connection.to_id, Some(1));
+    assert_eq!(focused_connection.to.map(|(to_id, _)| to_id), Some(1));
+}
+
+// ============================================================================
+// REPEAT (.) TESTS
+// ============================================================================
+
+#[test]
+fn test_repeat_recolor_on_other_note() {
+    let mut map_state = create_test_map_state();
+    let first = map_state
+        .notes_state
+        .add(10, 10, String::from("first"), Color::White);
+    let second = map_state
+        .notes_state
+        .add(40, 10, String::from("second"), Color::White);
+    map_state.notes_state.select(first);
+    map_state.mode = Mode::Visual;
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('e')));
+    let recolored = map_state.notes_state.notes()[&first].color;
+
+    map_state.notes_state.select(second);
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('.')));
+
+    assert_eq!(map_state.notes_state.notes()[&second].color, recolored);
+}
+
+#[test]
+fn test_repeat_move_session_as_single_delta() {
+    let mut map_state = create_test_map_state();
+    let first = map_state
+        .notes_state
+        .add(10, 10, String::from("first"), Color::White);
+    let second = map_state
+        .notes_state
+        .add(40, 20, String::from("second"), Color::White);
+    map_state.notes_state.select(first);
+    map_state.mode = Mode::Visual;
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('m')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('l')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('L')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('j')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('m')));
+
+    assert_eq!(
+        map_state.repeat.last_action,
+        Some(RepeatableAction::Move { dx: 6, dy: 1 })
+    );
+
+    map_state.notes_state.select(second);
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('.')));
+
+    let note = &map_state.notes_state.notes()[&second];
+    assert_eq!((note.x, note.y), (46, 21));
+}
+
+#[test]
+fn test_move_session_without_movement_keeps_last_action() {
+    let mut map_state = create_test_map_state();
+    let id = map_state
+        .notes_state
+        .add(10, 10, String::from("note"), Color::White);
+    map_state.notes_state.select(id);
+    map_state.mode = Mode::Visual;
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('e')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('m')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Esc));
+
+    assert_eq!(
+        map_state.repeat.last_action,
+        Some(RepeatableAction::Recolor)
+    );
+}
+
+#[test]
+fn test_repeat_delete_removes_selected_note() {
+    let mut map_state = create_test_map_state();
+    let first = map_state
+        .notes_state
+        .add(10, 10, String::from("first"), Color::White);
+    let second = map_state
+        .notes_state
+        .add(40, 10, String::from("second"), Color::White);
+    map_state.notes_state.select(first);
+    map_state.delete_selected_note();
+
+    map_state.notes_state.select(second);
+    map_state.mode = Mode::Visual;
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('.')));
+
+    assert!(map_state.notes_state.notes().is_empty());
+    assert_eq!(map_state.mode, Mode::Normal);
+}
+
+// ============================================================================
+// WAYPOINT MODE TESTS
+// ============================================================================
+
+/// Note 0 at (10,10) connected to note 1 at (60,10); the connection is being edited
+fn create_map_state_editing_connection() -> MapState {
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(10, 10, String::from("Note 0"), Color::White);
+    map_state
+        .notes_state
+        .add(60, 10, String::from("Note 1"), Color::White);
+    map_state.notes_state.select(0);
+    map_state.connections_state.focused_connection = Some(DraftConnection {
+        from_id: 0,
+        from_side: Side::Right,
+        to: Some((1, Side::Left)),
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    });
+    map_state.mode = Mode::VisualConnect;
+    map_state
+}
+
+fn focused_waypoints(map_state: &MapState) -> Vec<Point> {
+    map_state
+        .connections_state
+        .focused_connection
+        .as_ref()
+        .unwrap()
+        .waypoints
+        .clone()
+}
+
+#[test]
+fn test_waypoint_mode_requires_complete_connection() {
+    let mut map_state = create_map_state_editing_connection();
+    if let Some(connection) = map_state.connections_state.focused_connection.as_mut() {
+        connection.to = None;
+    }
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('w')));
+
+    assert_eq!(map_state.mode, Mode::VisualConnect);
+}
+
+#[test]
+fn test_add_waypoint_halfway_between_endpoints() {
+    let mut map_state = create_map_state_editing_connection();
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('w')));
+    assert_eq!(map_state.mode, Mode::VisualWaypoint);
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('a')));
+
+    let notes = map_state.notes_state.notes();
+    let (start_x, start_y) = notes[&0].get_connection_point(Side::Right);
+    let (end_x, end_y) = notes[&1].get_connection_point(Side::Left);
+    assert_eq!(
+        focused_waypoints(&map_state),
+        vec![Point {
+            x: (start_x + end_x) / 2,
+            y: (start_y + end_y) / 2,
+        }]
+    );
+    assert_eq!(map_state.connections_state.selected_waypoint, Some(0));
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_move_and_delete_waypoint() {
+    let mut map_state = create_map_state_editing_connection();
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('w')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('a')));
+    let added = focused_waypoints(&map_state)[0];
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('J')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('h')));
+
+    assert_eq!(
+        focused_waypoints(&map_state),
+        vec![Point {
+            x: added.x - 1,
+            y: added.y + 5
+        }]
+    );
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('d')));
+
+    assert!(focused_waypoints(&map_state).is_empty());
+    assert_eq!(map_state.connections_state.selected_waypoint, None);
+}
+
+#[test]
+fn test_waypoints_are_kept_when_connection_is_stashed() {
+    let mut map_state = create_map_state_editing_connection();
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('w')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('a')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('a')));
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert_eq!(map_state.mode, Mode::VisualConnect);
+    assert_eq!(map_state.connections_state.selected_waypoint, None);
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('c')));
+
+    assert_eq!(
+        map_state.connections_state.connections()[0].waypoints.len(),
+        2
+    );
+}
+
+// ============================================================================
+// MULTI-SELECTION TESTS
+// ============================================================================
+
+#[test]
+fn test_group_move_keeps_shape() {
+    let mut map_state = create_test_map_state();
+    let first = map_state
+        .notes_state
+        .add(10, 20, String::from("a"), Color::White);
+    let second = map_state
+        .notes_state
+        .add(2, 30, String::from("b"), Color::White);
+    map_state.notes_state.select_many(&[first, second]);
+    map_state.mode = Mode::VisualMove;
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('l')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('j')));
+
+    let notes = map_state.notes_state.notes();
+    assert_eq!((notes[&first].x, notes[&first].y), (11, 21));
+    assert_eq!((notes[&second].x, notes[&second].y), (3, 31));
+
+    // The group keeps its shape past the origin too
+    map_visual_kh(
+        &mut map_state,
+        create_key_event_with_modifiers(KeyCode::Char('H'), KeyModifiers::SHIFT),
+    );
+
+    let notes = map_state.notes_state.notes();
+    assert_eq!(notes[&first].x, 6);
+    assert_eq!(notes[&second].x, -2);
+}
+
+#[test]
+fn test_recolor_applies_to_whole_selection() {
+    let mut map_state = create_test_map_state();
+    let first = map_state
+        .notes_state
+        .add(10, 20, String::from("a"), Color::White);
+    let second = map_state
+        .notes_state
+        .add(40, 20, String::from("b"), Color::Blue);
+    let other = map_state
+        .notes_state
+        .add(70, 20, String::from("c"), Color::White);
+    map_state.notes_state.select_many(&[first, second]);
+    map_state.mode = Mode::Visual;
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('e')));
+
+    let notes = map_state.notes_state.notes();
+    assert_eq!(notes[&first].color, Color::Black);
+    assert_eq!(notes[&second].color, Color::Black);
+    assert_eq!(notes[&other].color, Color::White);
+}
+
+#[test]
+fn test_escape_clears_multi_selection() {
+    let mut map_state = create_test_map_state();
+    let first = map_state
+        .notes_state
+        .add(10, 20, String::from("a"), Color::White);
+    let second = map_state
+        .notes_state
+        .add(40, 20, String::from("b"), Color::White);
+    map_state.notes_state.select_many(&[first, second]);
+    map_state.mode = Mode::Visual;
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Esc));
+
+    assert!(!map_state.notes_state.has_multi_selection());
+    assert!(map_state.notes_state.selection().is_empty());
+}
+
+#[test]
+fn test_fan_out_from_focused_note_to_selection() {
+    let mut map_state = create_test_map_state();
+    let source = map_state
+        .notes_state
+        .add(10, 20, String::from("cause"), Color::White);
+    let first = map_state
+        .notes_state
+        .add(40, 10, String::from("effect"), Color::White);
+    let second = map_state
+        .notes_state
+        .add(40, 30, String::from("effect"), Color::White);
+    map_state.notes_state.select_many(&[source, second, first]);
+    map_state.mode = Mode::Visual;
+
+    map_visual_kh(
+        &mut map_state,
+        create_key_event_with_modifiers(KeyCode::Char('F'), KeyModifiers::SHIFT),
+    );
+
+    assert_eq!(
+        map_state.hyperedges_state.hyperedges(),
+        [Hyperedge {
+            from_id: source,
+            from_side: map_state.settings.default_start_side,
+            to_ids: vec![first, second],
+            color: Color::White,
+            layer: None,
+        }]
+    );
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::FannedOut(2))
+    );
+    assert!(map_state.persistence.has_unsaved_changes);
+
+    // Alone, the note loses its fan-outs
+    map_state.notes_state.clear_multi_selection();
+    map_visual_kh(
+        &mut map_state,
+        create_key_event_with_modifiers(KeyCode::Char('F'), KeyModifiers::SHIFT),
+    );
+    assert!(map_state.hyperedges_state.hyperedges().is_empty());
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::FanOutsRemoved(1))
+    );
+
+    map_visual_kh(
+        &mut map_state,
+        create_key_event_with_modifiers(KeyCode::Char('F'), KeyModifiers::SHIFT),
+    );
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::NoFanOutTargets)
+    );
+}
+
+// ============================================================================
+// PATH SEARCH TESTS
+// ============================================================================
+
+/// Notes 0 -> 1 -> 2 in a row and an unconnected note 3
+fn create_chain_map_state() -> MapState {
+    let mut map_state = create_test_map_state();
+    for i in 0..4 {
+        map_state
+            .notes_state
+            .add(30 * i, 10, format!("Note {}", i), Color::White);
+    }
+    for (from_id, to_id) in [(0, 1), (1, 2)] {
+        map_state.connections_state.add(Connection {
+            from_id,
+            from_side: Side::Right,
+            to_id,
+            to_side: Side::Left,
+            color: Color::White,
+            waypoints: vec![],
+            weight: None,
+            layer: None,
+        });
+    }
+    map_state.mode = Mode::Visual;
+    map_state
+}
+
+fn find_path(map_state: &mut MapState, from: usize, to: usize) {
+    map_state.notes_state.select(from);
+    map_visual_kh(map_state, create_key_event(KeyCode::Char('P')));
+    map_state.notes_state.select(to);
+    map_visual_kh(map_state, create_key_event(KeyCode::Char('P')));
+}
+
+#[test]
+fn test_find_path_highlights_connections() {
+    let mut map_state = create_chain_map_state();
+
+    map_state.notes_state.select(0);
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('P')));
+    assert_eq!(map_state.ui_state.path_start, Some(0));
+
+    map_state.notes_state.select(2);
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('P')));
+
+    assert_eq!(map_state.ui_state.path_start, None);
+    assert_eq!(map_state.ui_state.highlighted_path, vec![0, 1, 2]);
+    assert!(map_state.ui_state.path_links(1, 0));
+    assert!(!map_state.ui_state.path_links(0, 2));
+}
+
+#[test]
+fn test_find_path_against_connection_direction() {
+    let mut map_state = create_chain_map_state();
+
+    find_path(&mut map_state, 2, 0);
+
+    assert_eq!(map_state.ui_state.highlighted_path, vec![2, 1, 0]);
+}
+
+#[test]
+fn test_find_path_without_connection() {
+    let mut map_state = create_chain_map_state();
+
+    find_path(&mut map_state, 0, 3);
+
+    assert!(map_state.ui_state.highlighted_path.is_empty());
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::NoPath)
+    );
+}
+
+#[test]
+fn test_escape_cancels_path_search() {
+    let mut map_state = create_chain_map_state();
+    find_path(&mut map_state, 0, 1);
+
+    // Starting a new search clears the old path
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('P')));
+    assert!(map_state.ui_state.highlighted_path.is_empty());
+    assert_eq!(map_state.ui_state.path_start, Some(1));
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert_eq!(map_state.ui_state.path_start, None);
+}
+
+#[test]
+fn test_find_path_avoids_heavy_connections() {
+    let mut map_state = create_chain_map_state();
+    // A direct but heavy shortcut from 0 to 2
+    map_state.connections_state.add(Connection {
+        from_id: 0,
+        from_side: Side::Bottom,
+        to_id: 2,
+        to_side: Side::Bottom,
+        color: Color::White,
+        waypoints: vec![],
+        weight: Some(5),
+        layer: None,
+    });
+
+    find_path(&mut map_state, 0, 2);
+
+    assert_eq!(map_state.ui_state.highlighted_path, vec![0, 1, 2]);
+}
+
+#[test]
+fn test_connection_mode_adjust_weight() {
+    let mut map_state = create_test_map_state();
+    map_state.mode = Mode::VisualConnect;
+    map_state.connections_state.focused_connection = Some(DraftConnection {
+        from_id: 0,
+        from_side: Side::Right,
+        to: Some((1, Side::Left)),
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    });
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('+')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('=')));
+    assert!(map_state.persistence.has_unsaved_changes);
+    let connection = map_state.connections_state.focused_connection.as_ref();
+    assert_eq!(connection.unwrap().weight, Some(3));
+
+    // Weight never drops below 1, which is stored as no weight
+    for _ in 0..3 {
+        map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('-')));
+    }
+    let connection = map_state.connections_state.focused_connection.as_ref();
+    assert_eq!(connection.unwrap().weight, None);
+    assert_eq!(connection.unwrap().weight(), 1);
+}
+
+// ============================================================================
+// VISUAL BOX MODE TESTS
+// ============================================================================
+
+/// Notes 0 and 1 side by side on one row, note 2 further down.
+fn create_box_select_map_state(start_id: usize) -> MapState {
+    let mut map_state = create_test_map_state();
+    for (x, y) in [(10, 10), (50, 10), (10, 40)] {
+        map_state
+            .notes_state
+            .add(x, y, String::from("Note"), Color::White);
+    }
+    map_state.notes_state.select(start_id);
+    map_state.mode = Mode::Visual;
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('b')));
+    map_state
+}
+
+#[test]
+fn test_box_select_grows_selection() {
+    let mut map_state = create_box_select_map_state(0);
+    assert_eq!(map_state.mode, Mode::VisualBox);
+    assert_eq!(map_state.notes_state.selection(), vec![0]);
+
+    // The box starts covering note 0 (x 10 to 30), so 20 more cells reach note 1
+    for _ in 0..4 {
+        map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('L')));
+    }
+    assert_eq!(map_state.notes_state.selection(), vec![0, 1]);
+    assert_eq!(map_state.notes_state.selected_note_id(), Some(0));
+
+    // Shrinking it lets go of note 1 again
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('h')));
+    assert_eq!(map_state.notes_state.selection(), vec![0]);
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('l')));
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Enter));
+    assert_eq!(map_state.mode, Mode::Visual);
+    assert_eq!(map_state.ui_state.box_select, None);
+    assert_eq!(map_state.notes_state.selection(), vec![0, 1]);
+
+    // The box selection is an ordinary multi-selection, e.g. for recoloring
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('e')));
+    let notes = map_state.notes_state.notes();
+    assert_ne!(notes[&1].color, Color::White);
+    assert_eq!(notes[&2].color, Color::White);
+}
+
+#[test]
+fn test_box_select_towards_origin_and_cancel() {
+    let mut map_state = create_box_select_map_state(2);
+
+    // From note 2 (y 40 to 43) up to note 0's bottom border at y 13
+    for _ in 0..6 {
+        map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('K')));
+    }
+    assert_eq!(map_state.notes_state.selection(), vec![0, 2]);
+    assert_eq!(map_state.notes_state.selected_note_id(), Some(2));
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert_eq!(map_state.mode, Mode::Visual);
+    assert_eq!(map_state.notes_state.selection(), vec![2]);
+    assert!(!map_state.notes_state.has_multi_selection());
+}
+
+// ============================================================================
+// ALIGN (a) TESTS
+// ============================================================================
+
+/// Three notes of different widths, selected together.
+fn create_align_map_state(positions: [(isize, isize); 3]) -> MapState {
+    let mut map_state = create_test_map_state();
+    for ((x, y), content) in positions
+        .into_iter()
+        .zip(["a", "a much wider note here", "b"])
+    {
+        map_state
+            .notes_state
+            .add(x, y, String::from(content), Color::White);
+    }
+    map_state.notes_state.select_many(&[0, 1, 2]);
+    map_state.mode = Mode::Visual;
+    map_state
+}
+
+fn press_align(map_state: &mut MapState, key: char) {
+    map_visual_kh(map_state, create_key_event(KeyCode::Char('a')));
+    map_visual_kh(map_state, create_key_event(KeyCode::Char(key)));
+}
+
+fn note_positions(map_state: &MapState) -> Vec<(isize, isize)> {
+    (0..3)
+        .map(|id| {
+            let note = &map_state.notes_state.notes()[&id];
+            (note.x, note.y)
+        })
+        .collect()
+}
+
+#[test]
+fn test_align_edges() {
+    let mut map_state = create_align_map_state([(30, 5), (12, 20), (50, 40)]);
+
+    press_align(&mut map_state, 'h');
+    assert_eq!(
+        note_positions(&map_state),
+        vec![(12, 5), (12, 20), (12, 40)]
+    );
+    assert!(map_state.persistence.has_unsaved_changes);
+
+    // Right edges meet at the wider note's (width 25 instead of 21)
+    press_align(&mut map_state, 'l');
+    assert_eq!(
+        note_positions(&map_state),
+        vec![(16, 5), (12, 20), (16, 40)]
+    );
+
+    press_align(&mut map_state, 'j');
+    assert_eq!(
+        note_positions(&map_state),
+        vec![(16, 40), (12, 40), (16, 40)]
+    );
+    assert_eq!(map_state.mode, Mode::Visual);
+    assert_eq!(map_state.ui_state.pending_key, None);
+}
+
+#[test]
+fn test_distribute_evenly() {
+    // Widths 21, 25 and 21: 28 free cells between x 0 and 95 make gaps of 14
+    let mut map_state = create_align_map_state([(0, 0), (25, 3), (74, 6)]);
+
+    press_align(&mut map_state, 'x');
+    assert_eq!(note_positions(&map_state), vec![(0, 0), (35, 3), (74, 6)]);
+
+    // Heights are all 4: 0 to 21 leaves 9 free cells, 5 for the first gap and 4 for the second
+    map_state.notes_state.get_mut(2).unwrap().y = 17;
+    press_align(&mut map_state, 'y');
+    assert_eq!(note_positions(&map_state), vec![(0, 0), (35, 9), (74, 17)]);
+}
+
+#[test]
+fn test_align_needs_multi_selection() {
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(10, 10, String::from("Note"), Color::White);
+    map_state.notes_state.select(0);
+    map_state.mode = Mode::Visual;
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('a')));
+    assert_eq!(map_state.ui_state.pending_key, None);
+}
+
+#[test]
+fn test_zz_centers_on_selected_note() {
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(300, 200, String::from("Far away"), Color::White);
+    map_state.notes_state.select(0);
+    map_state.mode = Mode::Visual;
+    let (x, y) = map_state.notes_state.notes()[&0].center();
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('z')));
+    assert_eq!(map_state.ui_state.pending_key, Some('z'));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('z')));
+
+    assert_eq!(map_state.ui_state.pending_key, None);
+    assert_eq!(map_state.viewport.view_pos.x, x - 50);
+    assert_eq!(map_state.viewport.view_pos.y, y - 25);
+    assert_eq!(map_state.mode, Mode::Visual);
+}
+
+#[test]
+fn test_z_then_other_key_does_nothing() {
+    let mut map_state = create_test_map_state();
+    map_state
+        .notes_state
+        .add(300, 200, String::from("Far away"), Color::White);
+    map_state.notes_state.select(0);
+    map_state.mode = Mode::Visual;
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('z')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('m')));
+
+    // The `m` only cancels the `z`
+    assert_eq!(map_state.mode, Mode::Visual);
+    assert_eq!(map_state.viewport.view_pos.x, 0);
+    assert_eq!(map_state.viewport.view_pos.y, 0);
+}
+
+#[test]
+fn test_t_edits_note_title() {
+    let mut map_state = create_test_map_state();
+    let id = map_state
+        .notes_state
+        .add(10, 10, String::from("buy tickets"), Color::White);
+    map_state.notes_state.select(id);
+    map_state.mode = Mode::Visual;
+
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('t')));
+    assert_eq!(map_state.ui_state.title_prompt, Some(String::new()));
+    for c in "Trip".chars() {
+        map_kh(&mut map_state, create_key_event(KeyCode::Char(c)));
+    }
+    map_kh(&mut map_state, create_key_event(KeyCode::Enter));
+
+    assert_eq!(map_state.ui_state.title_prompt, None);
+    assert_eq!(map_state.mode, Mode::Visual);
+    let note = &map_state.notes_state.notes()[&id];
+    assert_eq!(note.title.as_deref(), Some("Trip"));
+    assert_eq!(note.content, "buy tickets");
+    assert!(map_state.persistence.has_unsaved_changes);
+
+    // Starts from the current title; Esc keeps it, an empty one removes it
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('t')));
+    assert_eq!(map_state.ui_state.title_prompt, Some(String::from("Trip")));
+    map_kh(&mut map_state, create_key_event(KeyCode::Backspace));
+    map_kh(&mut map_state, create_key_event(KeyCode::Esc));
+    assert_eq!(
+        map_state.notes_state.notes()[&id].title.as_deref(),
+        Some("Trip")
+    );
+
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('t')));
+    for _ in 0..4 {
+        map_kh(&mut map_state, create_key_event(KeyCode::Backspace));
+    }
+    map_kh(&mut map_state, create_key_event(KeyCode::Enter));
+    assert_eq!(map_state.notes_state.notes()[&id].title, None);
+}
+
+#[test]
+fn test_s_cycles_status_of_selection() {
+    let mut map_state = create_test_map_state();
+    let first = map_state
+        .notes_state
+        .add(10, 10, String::from("a"), Color::White);
+    let second = map_state
+        .notes_state
+        .add(40, 10, String::from("b"), Color::White);
+    map_state.notes_state.get_mut(second).unwrap().status = Some(NoteStatus::Done);
+    map_state.notes_state.select_many(&[first, second]);
+    map_state.mode = Mode::Visual;
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('s')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('s')));
+
+    // Both follow the selected note's status
+    for id in [first, second] {
+        assert_eq!(
+            map_state.notes_state.notes()[&id].status,
+            Some(NoteStatus::Doing)
+        );
+    }
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_locked_note_cannot_be_moved_edited_or_deleted() {
+    let mut map_state = create_test_map_state();
+    let id = map_state
+        .notes_state
+        .add(10, 10, String::from("Reference"), Color::White);
+    map_state.notes_state.select(id);
+    map_state.mode = Mode::Visual;
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('L')));
+    assert!(map_state.notes_state.notes()[&id].locked);
+
+    for key in ['m', 'i', 'd', 't'] {
+        map_state.ui_state.notifications.clear();
+        map_visual_kh(&mut map_state, create_key_event(KeyCode::Char(key)));
+        assert_eq!(map_state.mode, Mode::Visual);
+        assert_eq!(
+            map_state.ui_state.notifications.latest(),
+            Some(&Notification::NoteLocked)
+        );
+    }
+    assert_eq!(map_state.ui_state.title_prompt, None);
+
+    // Repeating a move made before locking doesn't move it either
+    map_state
+        .repeat
+        .record(RepeatableAction::Move { dx: 5, dy: 0 });
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('.')));
+    assert_eq!(map_state.notes_state.notes()[&id].x, 10);
+
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('L')));
+    assert!(!map_state.notes_state.notes()[&id].locked);
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('m')));
+    assert_eq!(map_state.mode, Mode::VisualMove);
+}
+
+#[test]
+fn test_restack_selected_notes() {
+    let mut map_state = create_test_map_state();
+    for x in [10, 12, 14, 16] {
+        map_state
+            .notes_state
+            .add(x, 10, String::from("Card"), Color::White);
+    }
+    map_state.notes_state.select(1);
+    map_state.mode = Mode::Visual;
+
+    let mut press = |key: char| {
+        map_visual_kh(&mut map_state, create_key_event(KeyCode::Char(key)));
+        map_state.notes_state.render_order().clone()
+    };
+    assert_eq!(press(']'), vec![0, 2, 1, 3]);
+    assert_eq!(press('}'), vec![0, 2, 3, 1]);
+    assert_eq!(press(']'), vec![0, 2, 3, 1]);
+    assert_eq!(press('['), vec![0, 2, 1, 3]);
+    assert_eq!(press('{'), vec![1, 0, 2, 3]);
+
+    // Several notes keep their order among themselves
+    map_state.notes_state.select_many(&[3, 0]);
+    let mut press = |key: char| {
+        map_visual_kh(&mut map_state, create_key_event(KeyCode::Char(key)));
+        map_state.notes_state.render_order().clone()
+    };
+    assert_eq!(press(']'), vec![1, 2, 0, 3]);
+    assert_eq!(press('{'), vec![0, 3, 1, 2]);
+    assert_eq!(press('}'), vec![1, 2, 0, 3]);
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_connect_to_note_found_by_search() {
+    let mut map_state = create_test_map_state();
+    let start = map_state
+        .notes_state
+        .add(10, 10, String::from("Start"), Color::White);
+    map_state
+        .notes_state
+        .add(40, 10, String::from("Nearby"), Color::White);
+    let far = map_state
+        .notes_state
+        .add(900, 400, String::from("Far away"), Color::White);
+    map_state.notes_state.select(start);
+    map_state.mode = Mode::Visual;
+
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('C')));
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('/')));
+    for c in "away".chars() {
+        map_kh(&mut map_state, create_key_event(KeyCode::Char(c)));
+    }
+    map_kh(&mut map_state, create_key_event(KeyCode::Enter));
+
+    assert_eq!(map_state.mode, Mode::VisualConnect);
+    assert_eq!(map_state.ui_state.search, None);
+    assert_eq!(map_state.notes_state.selected_note_id(), Some(far));
+    let connection = map_state.connections_state.focused_connection.as_ref();
+    assert_eq!(
+        connection.and_then(|connection| connection.to),
+        Some((far, map_state.settings.default_end_side))
+    );
+
+    // Without a match the connection keeps its end
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('/')));
+    map_kh(&mut map_state, create_key_event(KeyCode::Char('?')));
+    map_kh(&mut map_state, create_key_event(KeyCode::Enter));
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::PatternNotFound)
+    );
+    assert_eq!(map_state.notes_state.selected_note_id(), Some(far));
+}
+
+#[test]
+fn test_duplicate_connection_is_refused_unless_allowed() {
+    let mut map_state = create_test_map_state();
+    let a = map_state
+        .notes_state
+        .add(10, 10, String::from("A"), Color::White);
+    let b = map_state
+        .notes_state
+        .add(60, 10, String::from("B"), Color::White);
+    map_state.connections_state.add(Connection {
+        from_id: a,
+        from_side: Side::Right,
+        to_id: b,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    });
+    map_state.settings.default_start_side = Side::Left;
+    map_state.settings.default_end_side = Side::Right;
+
+    // The same sides in the other direction
+    map_state.notes_state.select(b);
+    map_state.mode = Mode::Visual;
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('C')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('h')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('c')));
+    assert_eq!(map_state.mode, Mode::VisualConnect);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::DuplicateConnection)
+    );
+    assert_eq!(map_state.connections_state.connections().len(), 1);
+
+    // Another side is a different connection
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('r')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('c')));
+    assert_eq!(map_state.mode, Mode::Visual);
+    assert_eq!(map_state.connections_state.connections().len(), 2);
+
+    // A copy of the first connection once parallel ones are allowed
+    map_state.settings.parallel_connections = true;
+    map_state.settings.default_start_side = Side::Right;
+    map_state.settings.default_end_side = Side::Left;
+    assert_eq!(map_state.notes_state.selected_note_id(), Some(a));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('C')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('l')));
+    map_visual_kh(&mut map_state, create_key_event(KeyCode::Char('c')));
+    assert_eq!(map_state.mode, Mode::Visual);
+    assert_eq!(map_state.connections_state.connections().len(), 3);
 }
@@ -1,6 +1,13 @@
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::states::{MapState, map::NotesState};
+use crate::{
+    input::normalize_line_breaks,
+    states::{
+        MapState,
+        map::{NotesState, UndoStep},
+    },
+    utils::toggle_checkbox,
+};
 
 pub fn cursor_pos_beginning(notes_state: &mut NotesState) {
     notes_state.set_cursor_pos(0);
@@ -71,6 +78,47 @@ pub fn insert_char(map_state: &mut MapState, c: char) {
     move_cursor_right(&mut map_state.notes_state);
 }
 
+/// Inserts pasted text at the cursor as one edit, which a single `u` takes back, and
+/// moves the cursor after it. Line breaks are inserted as they are rather than acting
+/// like Enter.
+///
+/// Panics if no note is selected.
+pub fn paste_text(map_state: &mut MapState, text: &str) {
+    let text = normalize_line_breaks(text);
+    if text.is_empty() {
+        return;
+    }
+    map_state.persistence.mark_dirty();
+
+    let id = map_state
+        .notes_state
+        .selected_note_id()
+        .expect("Bug: pasting into a note with none selected");
+    let cursor_pos = map_state.notes_state.cursor_pos();
+    let note = map_state.notes_state.expect_selected_note_mut();
+    let previous = note.content.clone();
+    note.content.insert_str(cursor_pos, &text);
+
+    map_state
+        .history
+        .push(UndoStep::NoteContents(vec![(id, previous)]));
+    map_state
+        .notes_state
+        .set_cursor_pos(cursor_pos + text.len());
+}
+
+/// Checks or unchecks the checklist item on the cursor's line, see [`toggle_checkbox`].
+///
+/// Panics if no note is selected.
+pub fn toggle_checkbox_at_cursor(map_state: &mut MapState) {
+    let cursor_pos = map_state.notes_state.cursor_pos();
+    let note = map_state.notes_state.expect_selected_note_mut();
+
+    if toggle_checkbox(&mut note.content, cursor_pos) {
+        map_state.persistence.mark_dirty();
+    }
+}
+
 /// Panics if no note is selected.
 pub fn move_cursor_up(notes_state: &mut NotesState) {
     let note = notes_state.expect_selected_note();
@@ -0,0 +1,27 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{input::AppAction, states::MapState};
+
+/// Handles typing the selected note's title. Intercepts all input while the prompt is
+/// shown.
+///
+/// Enter sets the title (an empty one removes it), Esc leaves it as it was.
+pub fn map_title_prompt_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    if let Some(title) = map_state.ui_state.title_prompt.as_mut() {
+        match key.code {
+            KeyCode::Esc => map_state.ui_state.title_prompt = None,
+            KeyCode::Enter => {
+                let title = map_state.ui_state.title_prompt.take().unwrap_or_default();
+                map_state.set_selected_note_title(&title);
+            }
+            KeyCode::Backspace => {
+                title.pop();
+            }
+            KeyCode::Char(c) => title.push(c),
+            _ => {}
+        }
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
@@ -0,0 +1,19 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{input::AppAction, states::MapState};
+
+/// Handles the trash browser. Intercepts all input while it's shown.
+pub fn map_trash_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => map_state.trash.close_browser(),
+        KeyCode::Char('j') | KeyCode::Down => map_state.trash.select_next(),
+        KeyCode::Char('k') | KeyCode::Up => map_state.trash.select_previous(),
+        KeyCode::Enter | KeyCode::Char('r') => map_state.restore_trashed_note(),
+        KeyCode::Char('x') => map_state.purge_trashed_note(),
+        KeyCode::Char('X') => map_state.confirm_empty_trash(),
+        _ => {}
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
@@ -4,20 +4,27 @@ use ratatui::style::Color;
 use crate::{
     input::{
         AppAction,
-        map::{cycle_color, cycle_side, move_note, switch_notes_focus},
+        map::{
+            cycle_color, cycle_side, move_note, recolor_selection, repeat_last_action,
+            switch_notes_focus,
+        },
     },
     states::{
         MapState,
-        map::{Connection, Mode},
+        map::{Align, DraftConnection, Mode, Notification, RepeatableAction, Restack, SearchState},
     },
 };
 
 pub fn map_visual_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
     if map_state.mode == Mode::VisualMove {
         match key.code {
-            KeyCode::Char('m') => map_state.mode = Mode::Visual,
+            KeyCode::Char('m') => {
+                map_state.repeat.finish_move();
+                map_state.mode = Mode::Visual;
+            }
 
             KeyCode::Esc => {
+                map_state.repeat.finish_move();
                 map_state.notes_state.deselect();
                 map_state.mode = Mode::Normal
             }
@@ -51,8 +58,107 @@ pub fn map_visual_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
         return AppAction::Continue;
     }
 
+    if map_state.mode == Mode::VisualBox {
+        match key.code {
+            KeyCode::Char('b') | KeyCode::Enter => map_state.finish_box_select(),
+            KeyCode::Esc => map_state.cancel_box_select(),
+
+            // Vim-style movement: hjkl and arrow keys. Shift modifier increases step size to 5.
+            KeyCode::Char('h') => map_state.move_box_corner(-1, 0),
+            KeyCode::Left if key.modifiers == KeyModifiers::NONE => {
+                map_state.move_box_corner(-1, 0)
+            }
+            KeyCode::Char('H') => map_state.move_box_corner(-5, 0),
+            KeyCode::Left if key.modifiers == KeyModifiers::SHIFT => {
+                map_state.move_box_corner(-5, 0)
+            }
+
+            KeyCode::Char('j') => map_state.move_box_corner(0, 1),
+            KeyCode::Down if key.modifiers == KeyModifiers::NONE => map_state.move_box_corner(0, 1),
+            KeyCode::Char('J') => map_state.move_box_corner(0, 5),
+            KeyCode::Down if key.modifiers == KeyModifiers::SHIFT => {
+                map_state.move_box_corner(0, 5)
+            }
+
+            KeyCode::Char('k') => map_state.move_box_corner(0, -1),
+            KeyCode::Up if key.modifiers == KeyModifiers::NONE => map_state.move_box_corner(0, -1),
+            KeyCode::Char('K') => map_state.move_box_corner(0, -5),
+            KeyCode::Up if key.modifiers == KeyModifiers::SHIFT => map_state.move_box_corner(0, -5),
+
+            KeyCode::Char('l') => map_state.move_box_corner(1, 0),
+            KeyCode::Right if key.modifiers == KeyModifiers::NONE => {
+                map_state.move_box_corner(1, 0)
+            }
+            KeyCode::Char('L') => map_state.move_box_corner(5, 0),
+            KeyCode::Right if key.modifiers == KeyModifiers::SHIFT => {
+                map_state.move_box_corner(5, 0)
+            }
+
+            _ => {}
+        }
+
+        // Early return to prevent falling through to other mode handlers
+        map_state.clear_and_redraw();
+        return AppAction::Continue;
+    }
+
+    if map_state.mode == Mode::VisualWaypoint {
+        match key.code {
+            KeyCode::Char('w') | KeyCode::Esc => {
+                map_state.connections_state.selected_waypoint = None;
+                map_state.mode = Mode::VisualConnect;
+            }
+
+            KeyCode::Char('a') => map_state.add_waypoint(),
+            KeyCode::Char('n') => map_state.connections_state.select_next_waypoint(),
+            KeyCode::Char('d') => {
+                map_state.connections_state.remove_selected_waypoint();
+                map_state.persistence.mark_dirty();
+            }
+
+            // Vim-style movement: hjkl and arrow keys. Shift modifier increases step size to 5.
+            KeyCode::Char('h') => move_waypoint(map_state, -1, 0),
+            KeyCode::Left if key.modifiers == KeyModifiers::NONE => move_waypoint(map_state, -1, 0),
+            KeyCode::Char('H') => move_waypoint(map_state, -5, 0),
+            KeyCode::Left if key.modifiers == KeyModifiers::SHIFT => {
+                move_waypoint(map_state, -5, 0)
+            }
+
+            KeyCode::Char('j') => move_waypoint(map_state, 0, 1),
+            KeyCode::Down if key.modifiers == KeyModifiers::NONE => move_waypoint(map_state, 0, 1),
+            KeyCode::Char('J') => move_waypoint(map_state, 0, 5),
+            KeyCode::Down if key.modifiers == KeyModifiers::SHIFT => move_waypoint(map_state, 0, 5),
+
+            KeyCode::Char('k') => move_waypoint(map_state, 0, -1),
+            KeyCode::Up if key.modifiers == KeyModifiers::NONE => move_waypoint(map_state, 0, -1),
+            KeyCode::Char('K') => move_waypoint(map_state, 0, -5),
+            KeyCode::Up if key.modifiers == KeyModifiers::SHIFT => move_waypoint(map_state, 0, -5),
+
+            KeyCode::Char('l') => move_waypoint(map_state, 1, 0),
+            KeyCode::Right if key.modifiers == KeyModifiers::NONE => move_waypoint(map_state, 1, 0),
+            KeyCode::Char('L') => move_waypoint(map_state, 5, 0),
+            KeyCode::Right if key.modifiers == KeyModifiers::SHIFT => {
+                move_waypoint(map_state, 5, 0)
+            }
+
+            _ => {}
+        }
+
+        // Early return to prevent falling through to other mode handlers
+        map_state.clear_and_redraw();
+        return AppAction::Continue;
+    }
+
     if map_state.mode == Mode::VisualConnect {
         match key.code {
+            KeyCode::Char('c')
+                if !map_state.settings.parallel_connections
+                    && map_state.connections_state.focused_is_duplicate() =>
+            {
+                map_state
+                    .ui_state
+                    .set_notification(Notification::DuplicateConnection);
+            }
             KeyCode::Char('c') => {
                 map_state.connections_state.stash_connection();
 
@@ -71,12 +177,10 @@ pub fn map_visual_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
                         focused_connection.from_side = cycle_side(focused_connection.from_side);
                     }
 
-                    if let Some(to_id) = focused_connection.to_id {
-                        if to_id == selected_note_id {
-                            // Safe: to_side always exists when to_id exists
-                            focused_connection.to_side =
-                                Some(cycle_side(focused_connection.to_side.unwrap()));
-                        }
+                    if let Some((to_id, to_side)) = focused_connection.to.as_mut()
+                        && *to_id == selected_note_id
+                    {
+                        *to_side = cycle_side(*to_side);
                     }
                 }
             }
@@ -114,6 +218,20 @@ pub fn map_visual_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
                 }
             }
 
+            // Waypoints can only be placed once both endpoints are known
+            KeyCode::Char('w') => {
+                if let Some(focused_connection) = &map_state.connections_state.focused_connection
+                    && focused_connection.to.is_some()
+                {
+                    map_state.connections_state.selected_waypoint =
+                        focused_connection.waypoints.len().checked_sub(1);
+                    map_state.mode = Mode::VisualWaypoint;
+                }
+            }
+
+            // Pick the target endpoint among the notes matching a search (see map_search_kh)
+            KeyCode::Char('/') => map_state.ui_state.search = Some(SearchState::new()),
+
             // Reuse note focus switching to select target endpoint for connection
             KeyCode::Char('j') => switch_notes_focus(map_state, "j"),
             KeyCode::Down => switch_notes_focus(map_state, "Down"),
@@ -133,6 +251,20 @@ pub fn map_visual_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
                 }
             }
 
+            KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Char('-') => {
+                if let Some(focused_connection) =
+                    map_state.connections_state.focused_connection.as_mut()
+                {
+                    let delta = if key.code == KeyCode::Char('-') {
+                        -1
+                    } else {
+                        1
+                    };
+                    focused_connection.adjust_weight(delta);
+                    map_state.persistence.mark_dirty();
+                }
+            }
+
             _ => {}
         }
 
@@ -141,13 +273,47 @@ pub fn map_visual_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
         return AppAction::Continue;
     }
 
+    // Second key of `zz` (center on the note); any other key just cancels it
+    if map_state.ui_state.pending_key == Some('z') {
+        map_state.ui_state.pending_key = None;
+        if key.code == KeyCode::Char('z') {
+            map_state.center_on_selected_note();
+        }
+
+        map_state.clear_and_redraw();
+        return AppAction::Continue;
+    }
+
+    // Second key of `a` (align); any other key just cancels it
+    if map_state.ui_state.pending_key.take() == Some('a') {
+        let align = match key.code {
+            KeyCode::Char('h') | KeyCode::Left => Some(Align::Left),
+            KeyCode::Char('l') | KeyCode::Right => Some(Align::Right),
+            KeyCode::Char('k') | KeyCode::Up => Some(Align::Top),
+            KeyCode::Char('j') | KeyCode::Down => Some(Align::Bottom),
+            KeyCode::Char('x') => Some(Align::DistributeHorizontally),
+            KeyCode::Char('y') => Some(Align::DistributeVertically),
+            _ => None,
+        };
+        if let Some(align) = align {
+            map_state.align_selection(align);
+        }
+
+        map_state.clear_and_redraw();
+        return AppAction::Continue;
+    }
+
     match key.code {
         KeyCode::Esc => {
             map_state.notes_state.deselect();
+            map_state.ui_state.path_start = None;
             map_state.mode = Mode::Normal
         }
         KeyCode::Char('i') => map_state.switch_to_edit_mode(),
-        KeyCode::Char('m') => map_state.mode = Mode::VisualMove,
+        KeyCode::Char('m') if !map_state.selection_is_locked() => {
+            map_state.repeat.start_move();
+            map_state.mode = Mode::VisualMove;
+        }
         // Enter connection edit mode. Finds and focuses the first connection associated with this note.
         KeyCode::Char('c') => {
             let selected_note_id = map_state.notes_state.expect_selected_note_id();
@@ -166,19 +332,36 @@ pub fn map_visual_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
         KeyCode::Char('C') => {
             let selected_note_id = map_state.notes_state.expect_selected_note_id();
 
-            map_state.connections_state.focused_connection = Some(Connection {
-                from_id: selected_note_id,
-                from_side: map_state.settings.default_start_side,
-                to_id: None,
-                to_side: None,
-                color: Color::White,
-            });
+            let mut connection = DraftConnection::new(
+                selected_note_id,
+                map_state.settings.default_start_side,
+                Color::White,
+            );
+            connection.set_layer(map_state.layers_state.active);
+            map_state.connections_state.focused_connection = Some(connection);
 
             map_state.mode = Mode::VisualConnect;
             map_state.persistence.mark_dirty();
         }
 
-        KeyCode::Char('d') => map_state.mode = Mode::Delete,
+        KeyCode::Char('F') => map_state.fan_out_selection(),
+
+        KeyCode::Char('d') => map_state.confirm_delete(),
+        KeyCode::Char(':') => map_state.ui_state.command_line = Some(String::new()),
+        KeyCode::Char('t') => map_state.open_title_prompt(),
+        KeyCode::Char('s') => map_state.cycle_selection_status(),
+        KeyCode::Char('L') => map_state.toggle_selection_lock(),
+        KeyCode::Char(']') => map_state.restack_selection(Restack::Up),
+        KeyCode::Char('[') => map_state.restack_selection(Restack::Down),
+        KeyCode::Char('}') => map_state.restack_selection(Restack::Front),
+        KeyCode::Char('{') => map_state.restack_selection(Restack::Back),
+        KeyCode::Char('b') => map_state.start_box_select(),
+        KeyCode::Char('g') => map_state.group_selection(),
+        KeyCode::Char('Y') => map_state.move_selection_to_active_layer(),
+        KeyCode::Char('z') => map_state.ui_state.pending_key = Some('z'),
+        KeyCode::Char('a') if map_state.notes_state.has_multi_selection() => {
+            map_state.ui_state.pending_key = Some('a')
+        }
 
         KeyCode::Char('j') => switch_notes_focus(map_state, "j"),
         KeyCode::Down => switch_notes_focus(map_state, "Down"),
@@ -190,12 +373,16 @@ pub fn map_visual_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
         KeyCode::Right => switch_notes_focus(map_state, "Right"),
 
         KeyCode::Char('e') => {
-            let note = map_state.notes_state.expect_selected_note_mut();
-
-            note.color = cycle_color(note.color);
-            map_state.persistence.mark_dirty();
+            recolor_selection(map_state);
+            map_state.repeat.record(RepeatableAction::Recolor);
         }
 
+        KeyCode::Char('.') => repeat_last_action(map_state),
+
+        KeyCode::Char('X') => map_state.toggle_note_encryption(),
+
+        KeyCode::Char('P') => map_state.find_path_to_selected(),
+
         _ => {}
     }
 
@@ -203,3 +390,8 @@ pub fn map_visual_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
     map_state.clear_and_redraw();
     AppAction::Continue
 }
+
+fn move_waypoint(map_state: &mut MapState, dx: isize, dy: isize) {
+    map_state.connections_state.move_selected_waypoint(dx, dy);
+    map_state.persistence.mark_dirty();
+}
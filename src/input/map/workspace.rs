@@ -0,0 +1,56 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{input::AppAction, states::MapState};
+
+/// Handles the workspace's map list. Intercepts all input while the list is shown.
+pub fn map_workspace_picker_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    if let Some(picker) = map_state.ui_state.workspace_picker.as_mut() {
+        match key.code {
+            KeyCode::Esc => map_state.ui_state.workspace_picker = None,
+            KeyCode::Char('j') | KeyCode::Down => picker.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => picker.select_previous(),
+            KeyCode::Enter => {
+                let selected = picker.selected_map().cloned();
+                map_state.ui_state.workspace_picker = None;
+                map_state.clear_and_redraw();
+                if let Some(path) = selected
+                    && path != map_state.persistence.file_write_path
+                {
+                    return AppAction::SwitchMapFile(path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
+
+/// Handles the results of a `:grep` over the workspace. Intercepts all input while
+/// the results are shown.
+pub fn map_workspace_search_kh(map_state: &mut MapState, key: KeyEvent) -> AppAction {
+    if let Some(results) = map_state.ui_state.workspace_search.as_mut() {
+        match key.code {
+            KeyCode::Esc => map_state.ui_state.workspace_search = None,
+            KeyCode::Char('j') | KeyCode::Down => results.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => results.select_previous(),
+            KeyCode::Enter => {
+                let query = results.query.clone();
+                let selected = results.selected_result().cloned();
+                map_state.ui_state.workspace_search = None;
+                match selected {
+                    Some(found) if found.path == map_state.persistence.file_write_path => {
+                        map_state.show_search_match(&query, found.note_id);
+                    }
+                    Some(found) => return AppAction::OpenWorkspaceMatch(found, query),
+                    None => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    map_state.clear_and_redraw();
+    AppAction::Continue
+}
@@ -1,13 +1,24 @@
 //! This module handles terminal events, focusing on keyboard input
 //! to control the application's state and behavior.
 
+mod backups;
+mod dialog;
+mod frame_pacer;
 mod handler;
+mod keyboard;
 mod map;
 mod settings;
 mod start;
 #[cfg(test)]
 mod tests;
 
+pub use backups::backups_kh;
+pub use dialog::dialog_kh;
+pub use frame_pacer::{FRAME_INTERVAL, FramePacer, IDLE_POLL_TIMEOUT};
 pub use handler::{AppAction, handle_events};
+pub use keyboard::{
+    acts_as_press, disable_bracketed_paste, disable_keyboard_enhancement, enable_bracketed_paste,
+    enable_keyboard_enhancement, normalize_line_breaks, paste_keys,
+};
 pub use settings::settings_kh;
 pub use start::start_kh;
@@ -7,7 +7,7 @@ use crate::{
         SettingsState, StartState,
         settings::{DiscardExitTo, SelectedToggle, SettingsType, save_settings_with_fs},
     },
-    utils::FileSystem,
+    utils::{FileSystem, available_dictionaries, dictionary_dirs},
 };
 use crossterm::event::{KeyCode, KeyEvent};
 
@@ -97,6 +97,23 @@ pub fn settings_kh(
         return AppAction::Continue;
     }
 
+    // Status bar format prompt takes all input when shown
+    if let Some(format) = &mut settings_state.format_prompt {
+        match key.code {
+            KeyCode::Esc => settings_state.format_prompt = None,
+            // Limit to 114 (58 and 56 for rows) chars to fit UI display width
+            KeyCode::Char(c) if format.len() < 114 => format.push(c),
+            KeyCode::Backspace => {
+                format.pop();
+            }
+            KeyCode::Enter => settings_state.submit_format(),
+            _ => {}
+        }
+
+        settings_state.needs_clear_and_redraw = true;
+        return AppAction::Continue;
+    }
+
     match key.code {
         KeyCode::Char('q') => {
             if settings_state.can_exit {
@@ -151,6 +168,46 @@ pub fn settings_kh(
                     settings_state.settings.settings_mut().edit_modal =
                         !settings_state.settings.settings().edit_modal
                 }
+                SelectedToggle::Toggle7 => settings_state.settings.settings_mut().cycle_locale(),
+                SelectedToggle::Toggle8 => {
+                    settings_state.settings.settings_mut().compress_maps =
+                        !settings_state.settings.settings().compress_maps
+                }
+                SelectedToggle::Toggle9 => {
+                    settings_state.settings.settings_mut().compact_json =
+                        !settings_state.settings.settings().compact_json
+                }
+                SelectedToggle::Toggle10 => settings_state
+                    .settings
+                    .settings_mut()
+                    .cycle_backup_retention(),
+                SelectedToggle::Toggle11 => settings_state
+                    .settings
+                    .settings_mut()
+                    .cycle_recent_files_limit(),
+                SelectedToggle::Toggle12 => settings_state.open_format_prompt(),
+                SelectedToggle::Toggle13 => {
+                    settings_state.settings.settings_mut().note_uuids =
+                        !settings_state.settings.settings().note_uuids
+                }
+                SelectedToggle::Toggle14 => {
+                    settings_state.settings.settings_mut().parallel_connections =
+                        !settings_state.settings.settings().parallel_connections
+                }
+                SelectedToggle::Toggle15 => {
+                    let dirs = dictionary_dirs(fs.get_home_dir().as_deref());
+                    settings_state
+                        .settings
+                        .settings_mut()
+                        .cycle_spell_dictionary(&available_dictionaries(&dirs))
+                }
+                SelectedToggle::Toggle16 => {
+                    settings_state.settings.settings_mut().persist_undo =
+                        !settings_state.settings.settings().persist_undo
+                }
+                SelectedToggle::Toggle17 => {
+                    settings_state.settings.settings_mut().cycle_scroll_margin()
+                }
                 _ => {}
             }
         }
@@ -1,16 +1,75 @@
 //! Start screen input handling
 
 use crate::{
-    input::AppAction,
+    app::Screen,
+    input::{AppAction, dialog_kh},
     states::{
-        StartState,
-        start::{FocusedInputBox, SelectedStartButton},
+        MapState, StartState,
+        start::{FocusedInputBox, SelectedStartButton, StartDialogAction},
     },
-    utils::FileSystem,
+    utils::{FileSystem, discard_emergency_save},
 };
 use crossterm::event::{KeyCode, KeyEvent};
 
 pub fn start_kh(start_state: &mut StartState, key: KeyEvent, fs: &impl FileSystem) -> AppAction {
+    // The launch warnings panel takes all input until it's closed
+    if !start_state.health_issues.is_empty() {
+        match key.code {
+            KeyCode::Char('f') => start_state.fix_health_issues_with_fs(fs),
+            KeyCode::Esc | KeyCode::Enter => start_state.dismiss_health_issues(),
+            KeyCode::Char('q') => return AppAction::Quit,
+            _ => {}
+        }
+        return AppAction::Continue;
+    }
+
+    if let Some(dialog) = &mut start_state.dialog {
+        let action = dialog_kh(dialog, key);
+        start_state.clear_and_redraw();
+        match action {
+            Some(StartDialogAction::Cancel) => start_state.dialog = None,
+            Some(StartDialogAction::OpenAnyway(path)) => {
+                start_state.dialog = None;
+                return AppAction::LoadMapFileUnchecked(path);
+            }
+            Some(StartDialogAction::RestoreCrashChanges(path)) => {
+                start_state.dialog = None;
+                start_state.pending_crash_restore = Some(path.clone());
+                return AppAction::LoadMapFile(path);
+            }
+            Some(StartDialogAction::DiscardCrashChanges(path)) => {
+                start_state.dialog = None;
+                // A file that can't be deleted is offered again on the next start
+                let _ = discard_emergency_save(&path);
+                start_state.offer_crash_changes_with_fs(fs);
+            }
+            // Moving the focus
+            None => {}
+        }
+        return AppAction::Continue;
+    }
+
+    // The passphrase prompt of an encrypted map takes all input until it's submitted or
+    // canceled
+    if let Some(prompt) = &mut start_state.passphrase_prompt {
+        match key.code {
+            KeyCode::Char(c) => prompt.input.push(c),
+            KeyCode::Backspace => {
+                prompt.input.pop();
+            }
+            KeyCode::Esc => start_state.passphrase_prompt = None,
+            KeyCode::Enter => {
+                if let Some(prompt) = start_state.passphrase_prompt.take() {
+                    start_state.clear_and_redraw();
+                    return AppAction::UnlockMapFile(prompt.path, prompt.input);
+                }
+            }
+            _ => {}
+        }
+        start_state.clear_and_redraw();
+        return AppAction::Continue;
+    }
+
     // Input mode has different keybindings - handle separately from start menu navigation
     if start_state.input_path {
         match key.code {
@@ -82,40 +141,36 @@ pub fn start_kh(start_state: &mut StartState, key: KeyEvent, fs: &impl FileSyste
             SelectedStartButton::CreateSelect => {
                 start_state.input_path = true;
                 start_state.display_err_msg = None;
+                start_state.load_error = None;
                 start_state.input_path_string = Some(String::new());
                 start_state.input_path_name = Some(String::new());
             }
+            SelectedStartButton::Tutorial => {
+                return AppAction::Switch(Screen::Map(MapState::new_tutorial_with_fs(fs)));
+            }
             _ => {}
         },
 
         _ => {}
     }
 
-    // Recent paths may not be available if there were errors loading them
-    if let Ok(recent_paths) = &start_state.recent_paths {
+    if let SelectedStartButton::Recent(_) = start_state.selected_button {
         match key.code {
-            KeyCode::Enter => match start_state.selected_button {
-                SelectedStartButton::Recent1 => {
-                    if let Some(path) = &recent_paths.recent_path_1 {
-                        return start_state.submit_path_with_fs(Some(path.to_path_buf()), fs);
-                    }
-                }
-                SelectedStartButton::Recent2 => {
-                    if let Some(path) = &recent_paths.recent_path_2 {
-                        return start_state.submit_path_with_fs(Some(path.to_path_buf()), fs);
-                    }
-                }
-                SelectedStartButton::Recent3 => {
-                    if let Some(path) = &recent_paths.recent_path_3 {
-                        return start_state.submit_path_with_fs(Some(path.to_path_buf()), fs);
-                    }
+            KeyCode::Enter => {
+                if let Some(path) = start_state.selected_recent_path() {
+                    return start_state.submit_path_with_fs(Some(path), fs);
                 }
-                _ => {}
-            },
+            }
+            KeyCode::Char('p') => start_state.toggle_pin_selected_with_fs(fs),
+            KeyCode::Char('d') => start_state.remove_selected_with_fs(fs),
             _ => {}
         }
     }
 
+    if let KeyCode::Char('D') = key.code {
+        start_state.remove_missing_with_fs(fs);
+    }
+
     start_state.clear_and_redraw();
     AppAction::Continue
 }
@@ -0,0 +1,51 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::Color;
+use std::path::PathBuf;
+
+use crate::{
+    app::Screen,
+    input::{AppAction, backups::backups_kh},
+    states::{BackupsState, MapState, settings::Settings},
+};
+
+fn create_backups_state() -> BackupsState {
+    let mut map_state =
+        MapState::new_with_settings(PathBuf::from("/test/map.json"), Settings::new());
+    map_state
+        .notes_state
+        .add(0, 0, String::from("Unsaved note"), Color::White);
+    map_state.persistence.mark_dirty();
+    BackupsState::new(map_state)
+}
+
+#[test]
+fn test_esc_returns_to_the_same_session() {
+    let mut backups_state = create_backups_state();
+
+    let result = backups_kh(
+        &mut backups_state,
+        KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+    );
+
+    match result {
+        AppAction::Switch(Screen::Map(map_state)) => {
+            assert_eq!(map_state.notes_state.notes()[&0].content, "Unsaved note");
+            assert!(map_state.persistence.has_unsaved_changes);
+            assert!(map_state.ui_state.needs_clear_and_redraw);
+        }
+        _ => panic!("Expected AppAction::Switch to Map screen"),
+    }
+}
+
+#[test]
+fn test_restore_without_backups_stays_on_screen() {
+    let mut backups_state = create_backups_state();
+
+    let result = backups_kh(
+        &mut backups_state,
+        KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+    );
+
+    assert_eq!(result, AppAction::Continue);
+    assert!(backups_state.needs_clear_and_redraw);
+}
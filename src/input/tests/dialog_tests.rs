@@ -0,0 +1,101 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::Color;
+
+use crate::{input::dialog_kh, states::Dialog};
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Choice {
+    Cancel,
+    No,
+    Yes,
+}
+
+fn create_dialog() -> Dialog<Choice> {
+    Dialog::new("Continue?", Choice::Cancel)
+        .line("Something will happen")
+        .button("[ n ] - No", KeyCode::Char('n'), Choice::No, Color::Green)
+        .button("[ y ] - Yes", KeyCode::Char('y'), Choice::Yes, Color::Red)
+}
+
+fn create_key_event(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+#[test]
+fn test_dialog_button_keys_choose_their_action() {
+    let mut dialog = create_dialog();
+
+    assert_eq!(
+        dialog_kh(&mut dialog, create_key_event(KeyCode::Char('y'))),
+        Some(Choice::Yes)
+    );
+    assert_eq!(
+        dialog_kh(&mut dialog, create_key_event(KeyCode::Char('n'))),
+        Some(Choice::No)
+    );
+}
+
+#[test]
+fn test_dialog_esc_cancels() {
+    let mut dialog = create_dialog();
+
+    assert_eq!(
+        dialog_kh(&mut dialog, create_key_event(KeyCode::Esc)),
+        Some(Choice::Cancel)
+    );
+}
+
+#[test]
+fn test_dialog_button_with_esc_key_overrides_cancel() {
+    let mut dialog = Dialog::new("Leave?", Choice::Cancel).button(
+        "[ ESC ] - Stay",
+        KeyCode::Esc,
+        Choice::No,
+        Color::Green,
+    );
+
+    assert_eq!(
+        dialog_kh(&mut dialog, create_key_event(KeyCode::Esc)),
+        Some(Choice::No)
+    );
+}
+
+#[test]
+fn test_dialog_focus_wraps_around() {
+    let mut dialog = create_dialog();
+    assert_eq!(dialog.focused, 0);
+
+    assert_eq!(dialog_kh(&mut dialog, create_key_event(KeyCode::Tab)), None);
+    assert_eq!(dialog.focused, 1);
+    dialog_kh(&mut dialog, create_key_event(KeyCode::Char('l')));
+    assert_eq!(dialog.focused, 0);
+    dialog_kh(&mut dialog, create_key_event(KeyCode::Left));
+    assert_eq!(dialog.focused, 1);
+    dialog_kh(&mut dialog, create_key_event(KeyCode::Char('h')));
+    assert_eq!(dialog.focused, 0);
+}
+
+#[test]
+fn test_dialog_enter_chooses_focused_button() {
+    let mut dialog = create_dialog();
+
+    assert_eq!(
+        dialog_kh(&mut dialog, create_key_event(KeyCode::Enter)),
+        Some(Choice::No)
+    );
+    dialog.focus_next();
+    assert_eq!(
+        dialog_kh(&mut dialog, create_key_event(KeyCode::Enter)),
+        Some(Choice::Yes)
+    );
+}
+
+#[test]
+fn test_dialog_ignores_other_keys() {
+    let mut dialog = create_dialog();
+
+    for code in [KeyCode::Char('j'), KeyCode::Up, KeyCode::Backspace] {
+        assert_eq!(dialog_kh(&mut dialog, create_key_event(code)), None);
+    }
+    assert_eq!(dialog.focused, 0);
+}
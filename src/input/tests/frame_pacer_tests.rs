@@ -0,0 +1,44 @@
+use std::time::{Duration, Instant};
+
+use crate::input::{FRAME_INTERVAL, FramePacer, IDLE_POLL_TIMEOUT};
+
+#[test]
+fn test_first_frame_is_drawn_right_away() {
+    let pacer = FramePacer::new();
+    let now = Instant::now();
+
+    assert!(pacer.can_draw(now));
+    assert_eq!(pacer.poll_timeout(true, now), Duration::ZERO);
+}
+
+#[test]
+fn test_redraws_wait_for_the_next_frame() {
+    let mut pacer = FramePacer::new();
+    let drawn = Instant::now();
+    pacer.mark_drawn(drawn);
+
+    let soon = drawn + Duration::from_millis(10);
+    assert!(!pacer.can_draw(soon));
+    // Input is still handled until the frame is over
+    assert_eq!(
+        pacer.poll_timeout(true, soon),
+        FRAME_INTERVAL - Duration::from_millis(10)
+    );
+
+    let next_frame = drawn + FRAME_INTERVAL;
+    assert!(pacer.can_draw(next_frame));
+    assert_eq!(pacer.poll_timeout(true, next_frame), Duration::ZERO);
+}
+
+#[test]
+fn test_idle_loop_waits_longer() {
+    let mut pacer = FramePacer::new();
+    let drawn = Instant::now();
+    pacer.mark_drawn(drawn);
+
+    assert_eq!(pacer.poll_timeout(false, drawn), IDLE_POLL_TIMEOUT);
+    assert_eq!(
+        pacer.poll_timeout(false, drawn + Duration::from_secs(5)),
+        IDLE_POLL_TIMEOUT
+    );
+}
@@ -3,9 +3,12 @@ use ratatui::style::Color;
 use std::path::PathBuf;
 
 use crate::{
-    input::handler::{AppAction, map_kh},
-    states::{MapState, map::Mode},
-    utils::test_utils::MockFileSystem,
+    input::handler::{AppAction, map_kh, pastes_into_note},
+    states::{
+        MapState,
+        map::{DiscardMenuType, ExternalChange, Mode, help_page_for_mode},
+    },
+    utils::{MapData, MapDiff, test_utils::MockFileSystem},
 };
 
 fn create_map_state_using_mock_filesystem(path: PathBuf) -> MapState {
@@ -38,7 +41,12 @@ fn test_map_kh_normal_mode() {
         | AppAction::Switch(_)
         | AppAction::CreateMapFile(_)
         | AppAction::SaveMapFile(_)
-        | AppAction::LoadMapFile(_) => {
+        | AppAction::LoadMapFile(_)
+        | AppAction::LoadMapFileUnchecked(_)
+        | AppAction::UnlockMapFile(..)
+        | AppAction::OpenWorkspace(_)
+        | AppAction::SwitchMapFile(_)
+        | AppAction::OpenWorkspaceMatch(..) => {
             // Any of these are valid responses
             assert!(true);
         }
@@ -66,7 +74,12 @@ fn test_map_kh_visual_mode() {
         | AppAction::Switch(_)
         | AppAction::CreateMapFile(_)
         | AppAction::SaveMapFile(_)
-        | AppAction::LoadMapFile(_) => {
+        | AppAction::LoadMapFile(_)
+        | AppAction::LoadMapFileUnchecked(_)
+        | AppAction::UnlockMapFile(..)
+        | AppAction::OpenWorkspace(_)
+        | AppAction::SwitchMapFile(_)
+        | AppAction::OpenWorkspaceMatch(..) => {
             assert!(true);
         }
     }
@@ -97,7 +110,12 @@ fn test_map_kh_edit_mode_non_modal() {
         | AppAction::Switch(_)
         | AppAction::CreateMapFile(_)
         | AppAction::SaveMapFile(_)
-        | AppAction::LoadMapFile(_) => {
+        | AppAction::LoadMapFile(_)
+        | AppAction::LoadMapFileUnchecked(_)
+        | AppAction::UnlockMapFile(..)
+        | AppAction::OpenWorkspace(_)
+        | AppAction::SwitchMapFile(_)
+        | AppAction::OpenWorkspaceMatch(..) => {
             assert!(true);
         }
     }
@@ -123,7 +141,12 @@ fn test_map_kh_edit_mode_modal_normal() {
         | AppAction::Switch(_)
         | AppAction::CreateMapFile(_)
         | AppAction::SaveMapFile(_)
-        | AppAction::LoadMapFile(_) => {
+        | AppAction::LoadMapFile(_)
+        | AppAction::LoadMapFileUnchecked(_)
+        | AppAction::UnlockMapFile(..)
+        | AppAction::OpenWorkspace(_)
+        | AppAction::SwitchMapFile(_)
+        | AppAction::OpenWorkspaceMatch(..) => {
             assert!(true);
         }
     }
@@ -153,7 +176,12 @@ fn test_map_kh_edit_mode_modal_insert() {
         | AppAction::Switch(_)
         | AppAction::CreateMapFile(_)
         | AppAction::SaveMapFile(_)
-        | AppAction::LoadMapFile(_) => {
+        | AppAction::LoadMapFile(_)
+        | AppAction::LoadMapFileUnchecked(_)
+        | AppAction::UnlockMapFile(..)
+        | AppAction::OpenWorkspace(_)
+        | AppAction::SwitchMapFile(_)
+        | AppAction::OpenWorkspaceMatch(..) => {
             assert!(true);
         }
     }
@@ -179,7 +207,12 @@ fn test_map_kh_delete_mode() {
         | AppAction::Switch(_)
         | AppAction::CreateMapFile(_)
         | AppAction::SaveMapFile(_)
-        | AppAction::LoadMapFile(_) => {
+        | AppAction::LoadMapFile(_)
+        | AppAction::LoadMapFileUnchecked(_)
+        | AppAction::UnlockMapFile(..)
+        | AppAction::OpenWorkspace(_)
+        | AppAction::SwitchMapFile(_)
+        | AppAction::OpenWorkspaceMatch(..) => {
             assert!(true);
         }
     }
@@ -301,12 +334,100 @@ fn test_map_kh_maintains_state_integrity() {
         | Mode::Visual
         | Mode::VisualMove
         | Mode::VisualConnect
+        | Mode::VisualWaypoint
+        | Mode::VisualBox
         | Mode::Edit
         | Mode::EditNormal
         | Mode::EditInsert
-        | Mode::Delete => {
+        | Mode::Delete
+        | Mode::Region => {
             // All valid modes
             assert!(true);
         }
     }
 }
+
+#[test]
+fn test_map_kh_external_change_intercepts_input() {
+    let mut map_state = create_map_state_using_mock_filesystem(PathBuf::from("/test/path"));
+    map_state.persistence.external_change = Some(ExternalChange {
+        disk_map: MapData::from_map_state(&mut create_map_state_using_mock_filesystem(
+            PathBuf::from("/test/path"),
+        )),
+        modified: None,
+        diff: MapDiff::default(),
+        show_diff: false,
+        merge: None,
+        selected_conflict: 0,
+    });
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+
+    // Normal mode keys don't reach the map
+    map_kh(&mut map_state, key('a'));
+    assert_eq!(map_state.mode, Mode::Normal);
+    assert!(map_state.notes_state.notes().is_empty());
+
+    map_kh(&mut map_state, key('d'));
+    assert!(
+        map_state
+            .persistence
+            .external_change
+            .as_ref()
+            .unwrap()
+            .show_diff
+    );
+    map_kh(
+        &mut map_state,
+        KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+    );
+    assert!(
+        !map_state
+            .persistence
+            .external_change
+            .as_ref()
+            .unwrap()
+            .show_diff
+    );
+
+    map_kh(&mut map_state, key('k'));
+    assert!(map_state.persistence.external_change.is_none());
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_map_kh_dialog_intercepts_input() {
+    let mut map_state = create_map_state_using_mock_filesystem(PathBuf::from("/test/path"));
+    map_state.show_discard_menu(DiscardMenuType::Start);
+    let view_pos = map_state.viewport.view_pos.clone();
+
+    let result = map_kh(&mut map_state, KeyEvent::from(KeyCode::Char('j')));
+
+    assert_eq!(result, AppAction::Continue);
+    assert_eq!(map_state.viewport.view_pos, view_pos);
+    assert!(map_state.ui_state.dialog.is_some());
+
+    map_kh(&mut map_state, KeyEvent::from(KeyCode::Esc));
+    assert_eq!(map_state.ui_state.dialog, None);
+}
+
+#[test]
+fn test_pastes_into_note_only_while_typing_in_it() {
+    let mut map_state = create_map_state_using_mock_filesystem(PathBuf::from("/test/path"));
+    map_state
+        .notes_state
+        .add(0, 0, String::from("Note"), Color::White);
+    map_state.notes_state.select(0);
+
+    map_state.mode = Mode::Normal;
+    assert!(!pastes_into_note(&map_state));
+    map_state.mode = Mode::EditNormal;
+    assert!(!pastes_into_note(&map_state));
+    map_state.mode = Mode::EditInsert;
+    assert!(pastes_into_note(&map_state));
+
+    // Help shown over the note takes the keys instead
+    map_state.mode = Mode::Edit;
+    assert!(pastes_into_note(&map_state));
+    map_state.ui_state.show_help(help_page_for_mode(Mode::Edit));
+    assert!(!pastes_into_note(&map_state));
+}
@@ -0,0 +1,31 @@
+use crossterm::event::{KeyCode, KeyEventKind};
+
+use crate::input::{acts_as_press, normalize_line_breaks, paste_keys};
+
+#[test]
+fn test_held_keys_act_as_presses() {
+    assert!(acts_as_press(KeyEventKind::Press));
+    // Reported for a held key once the terminal reports event types
+    assert!(acts_as_press(KeyEventKind::Repeat));
+    assert!(!acts_as_press(KeyEventKind::Release));
+}
+
+#[test]
+fn test_normalize_line_breaks() {
+    assert_eq!(normalize_line_breaks("a\r\nb\rc\nd"), "a\nb\nc\nd");
+}
+
+#[test]
+fn test_paste_keys() {
+    let codes: Vec<KeyCode> = paste_keys("a\r\n\tb").iter().map(|key| key.code).collect();
+
+    assert_eq!(
+        codes,
+        [
+            KeyCode::Char('a'),
+            KeyCode::Enter,
+            KeyCode::Tab,
+            KeyCode::Char('b')
+        ]
+    );
+}
@@ -1,3 +1,7 @@
+mod backups_tests;
+mod dialog_tests;
+mod frame_pacer_tests;
 mod handler_tests;
+mod keyboard_tests;
 mod settings_tests;
 mod start_tests;
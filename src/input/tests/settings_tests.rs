@@ -194,6 +194,47 @@ fn test_context_page_other_keys() {
     assert!(state.needs_clear_and_redraw);
 }
 
+#[test]
+fn test_format_prompt_typing_and_confirm() {
+    let mock_fs = MockFileSystem::new();
+    let mut state = create_default_settings_state();
+    state.selected_toggle = SelectedToggle::Toggle12;
+
+    settings_kh(&mut state, create_key_event(KeyCode::Enter), &mock_fs);
+    assert_eq!(state.format_prompt.as_deref(), Some("{mode}|{x}, {y}"));
+    assert!(!state.can_exit);
+
+    for _ in 0..8 {
+        settings_kh(&mut state, create_key_event(KeyCode::Backspace), &mock_fs);
+    }
+    for c in "{notes}".chars() {
+        settings_kh(&mut state, create_key_event(KeyCode::Char(c)), &mock_fs);
+    }
+    // Keys go to the prompt instead of leaving the screen
+    let result = settings_kh(&mut state, create_key_event(KeyCode::Char('q')), &mock_fs);
+    assert_eq!(result, AppAction::Continue);
+    settings_kh(&mut state, create_key_event(KeyCode::Backspace), &mock_fs);
+    settings_kh(&mut state, create_key_event(KeyCode::Enter), &mock_fs);
+
+    assert_eq!(state.format_prompt, None);
+    assert_eq!(
+        state.settings.settings().status_bar_format.as_deref(),
+        Some("{mode}|{notes}")
+    );
+}
+
+#[test]
+fn test_format_prompt_esc_keeps_format() {
+    let mock_fs = MockFileSystem::new();
+    let mut state = create_default_settings_state();
+    state.format_prompt = Some(String::from("{clock}"));
+
+    settings_kh(&mut state, create_key_event(KeyCode::Esc), &mock_fs);
+
+    assert_eq!(state.format_prompt, None);
+    assert_eq!(state.settings.settings().status_bar_format, None);
+}
+
 #[test]
 fn test_input_prompt_esc() {
     let mock_fs = MockFileSystem::new();
@@ -3,12 +3,18 @@
 #[cfg(test)]
 mod tests {
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-    use std::path::PathBuf;
+    use std::{fs, path::PathBuf};
 
     use crate::{
+        app::Screen,
         input::{AppAction, start::start_kh},
-        states::start::{FocusedInputBox, RecentPaths, SelectedStartButton, StartState},
-        utils::{IoErrorKind, test_utils::MockFileSystem},
+        states::start::{
+            FocusedInputBox, HealthIssue, RecentPaths, SelectedStartButton, StartState,
+        },
+        utils::{
+            CryptoError, IoErrorKind, emergency_save_path,
+            test_utils::{MockFileSystem, TempFileSystem},
+        },
     };
 
     fn create_key_event(code: KeyCode) -> KeyEvent {
@@ -20,9 +26,12 @@ mod tests {
         let mut state = StartState::new_with_fs(&mock_fs);
         // Override recent_paths with test data to avoid filesystem interactions
         state.recent_paths = Ok(RecentPaths {
-            recent_path_1: Some(PathBuf::from("/test/path1.json")),
-            recent_path_2: Some(PathBuf::from("/test/path2.json")),
-            recent_path_3: Some(PathBuf::from("/test/path3.json")),
+            pinned: vec![],
+            recent: vec![
+                PathBuf::from("/test/path1.json"),
+                PathBuf::from("/test/path2.json"),
+                PathBuf::from("/test/path3.json"),
+            ],
         });
         state
     }
@@ -39,20 +48,20 @@ mod tests {
     #[test]
     fn test_navigation_with_k_and_up() {
         let mut state = create_test_start_state();
-        state.selected_button = SelectedStartButton::Recent1;
+        state.selected_button = SelectedStartButton::Recent(0);
 
         // Test 'k' key
         let key = create_key_event(KeyCode::Char('k'));
         let mock_fs = MockFileSystem::new();
         start_kh(&mut state, key, &mock_fs);
-        assert_eq!(state.selected_button, SelectedStartButton::CreateSelect);
+        assert_eq!(state.selected_button, SelectedStartButton::Tutorial);
 
         // Test Up arrow
-        state.selected_button = SelectedStartButton::Recent2;
+        state.selected_button = SelectedStartButton::Recent(1);
         let key = create_key_event(KeyCode::Up);
         let mock_fs = MockFileSystem::new();
         start_kh(&mut state, key, &mock_fs);
-        assert_eq!(state.selected_button, SelectedStartButton::Recent1);
+        assert_eq!(state.selected_button, SelectedStartButton::Recent(0));
     }
 
     #[test]
@@ -63,13 +72,13 @@ mod tests {
         let key = create_key_event(KeyCode::Char('j'));
         let mock_fs = MockFileSystem::new();
         start_kh(&mut state, key, &mock_fs);
-        assert_eq!(state.selected_button, SelectedStartButton::Recent1);
+        assert_eq!(state.selected_button, SelectedStartButton::Tutorial);
 
         // Test Down arrow
         let key = create_key_event(KeyCode::Down);
         let mock_fs = MockFileSystem::new();
         start_kh(&mut state, key, &mock_fs);
-        assert_eq!(state.selected_button, SelectedStartButton::Recent2);
+        assert_eq!(state.selected_button, SelectedStartButton::Recent(0));
     }
 
     #[test]
@@ -84,11 +93,27 @@ mod tests {
         assert_eq!(state.selected_button, SelectedStartButton::CreateSelect);
 
         // Test that we can't go down from Recent3
-        state.selected_button = SelectedStartButton::Recent3;
+        state.selected_button = SelectedStartButton::Recent(2);
         let key = create_key_event(KeyCode::Char('j'));
         let mock_fs = MockFileSystem::new();
         start_kh(&mut state, key, &mock_fs);
-        assert_eq!(state.selected_button, SelectedStartButton::Recent3);
+        assert_eq!(state.selected_button, SelectedStartButton::Recent(2));
+    }
+
+    #[test]
+    fn test_enter_on_tutorial_opens_tutorial_map() {
+        let mut state = create_test_start_state();
+        state.selected_button = SelectedStartButton::Tutorial;
+
+        let mock_fs = MockFileSystem::new();
+        let result = start_kh(&mut state, create_key_event(KeyCode::Enter), &mock_fs);
+
+        let AppAction::Switch(Screen::Map(map_state)) = result else {
+            panic!("Expected the map screen, got {result:?}");
+        };
+        assert!(map_state.tutorial.is_some());
+        assert!(!map_state.notes_state.notes().is_empty());
+        assert!(!map_state.persistence.has_unsaved_changes);
     }
 
     #[test]
@@ -119,13 +144,16 @@ mod tests {
         let mock_fs = MockFileSystem::new();
         // Use paths that don't exist
         state.recent_paths = Ok(RecentPaths {
-            recent_path_1: Some(PathBuf::from("/nonexistent/path1.json")),
-            recent_path_2: Some(PathBuf::from("/nonexistent/path2.json")),
-            recent_path_3: Some(PathBuf::from("/nonexistent/path3.json")),
+            pinned: vec![],
+            recent: vec![
+                PathBuf::from("/nonexistent/path1.json"),
+                PathBuf::from("/nonexistent/path2.json"),
+                PathBuf::from("/nonexistent/path3.json"),
+            ],
         });
 
         // Test Recent1 - should return Continue since file doesn't exist
-        state.selected_button = SelectedStartButton::Recent1;
+        state.selected_button = SelectedStartButton::Recent(0);
         let key = create_key_event(KeyCode::Enter);
         let result = start_kh(&mut state, key, &mock_fs);
         assert_eq!(result, AppAction::Continue);
@@ -135,7 +163,7 @@ mod tests {
         state.display_err_msg = None;
 
         // Test Recent2
-        state.selected_button = SelectedStartButton::Recent2;
+        state.selected_button = SelectedStartButton::Recent(1);
         let result = start_kh(&mut state, key, &mock_fs);
         assert_eq!(result, AppAction::Continue);
         assert_eq!(state.display_err_msg, Some(IoErrorKind::FileRead));
@@ -144,7 +172,7 @@ mod tests {
         state.display_err_msg = None;
 
         // Test Recent3
-        state.selected_button = SelectedStartButton::Recent3;
+        state.selected_button = SelectedStartButton::Recent(2);
         let result = start_kh(&mut state, key, &mock_fs);
         assert_eq!(result, AppAction::Continue);
         assert_eq!(state.display_err_msg, Some(IoErrorKind::FileRead));
@@ -339,24 +367,49 @@ mod tests {
     }
 
     #[test]
-    fn test_recent_paths_with_none_values() {
+    fn test_recent_paths_empty_list() {
         let mut state = create_test_start_state();
         let mock_fs = MockFileSystem::new();
-        // Set recent paths with None values
+        // No recent paths listed
         state.recent_paths = Ok(RecentPaths {
-            recent_path_1: None,
-            recent_path_2: None,
-            recent_path_3: None,
+            pinned: vec![],
+            recent: vec![],
         });
-        state.selected_button = SelectedStartButton::Recent1;
+        state.selected_button = SelectedStartButton::Recent(0);
 
         let key = create_key_event(KeyCode::Enter);
         let result = start_kh(&mut state, key, &mock_fs);
 
-        // Should return Continue since the path is None
+        // Should return Continue since there is no path
         assert_eq!(result, AppAction::Continue);
     }
 
+    #[test]
+    fn test_pin_and_remove_keys_keep_selection_on_entry() {
+        let mut state = create_test_start_state();
+        let mock_fs = MockFileSystem::new();
+
+        // Pinning the last entry moves it, and the highlight, to the top
+        state.selected_button = SelectedStartButton::Recent(2);
+        start_kh(&mut state, create_key_event(KeyCode::Char('p')), &mock_fs);
+        assert_eq!(state.selected_button, SelectedStartButton::Recent(0));
+        assert_eq!(
+            state.recent_paths.as_ref().unwrap().pinned,
+            vec![PathBuf::from("/test/path3.json")]
+        );
+
+        // Removing the last entry moves the highlight up to the new last one
+        state.selected_button = SelectedStartButton::Recent(2);
+        start_kh(&mut state, create_key_event(KeyCode::Char('d')), &mock_fs);
+        assert_eq!(state.selected_button, SelectedStartButton::Recent(1));
+        assert_eq!(state.recent_paths.as_ref().unwrap().len(), 2);
+
+        // None of the test paths exist, so they are all removed
+        start_kh(&mut state, create_key_event(KeyCode::Char('D')), &mock_fs);
+        assert_eq!(state.selected_button, SelectedStartButton::CreateSelect);
+        assert!(state.recent_paths.as_ref().unwrap().is_empty());
+    }
+
     #[test]
     fn test_enter_on_recent_paths_nonexistent_file() {
         let mut state = create_test_start_state();
@@ -364,7 +417,7 @@ mod tests {
         // Keep the default test paths (which don't exist)
 
         // Test Recent1 with non-existent file
-        state.selected_button = SelectedStartButton::Recent1;
+        state.selected_button = SelectedStartButton::Recent(0);
         let key = create_key_event(KeyCode::Enter);
         let result = start_kh(&mut state, key, &mock_fs);
 
@@ -373,4 +426,121 @@ mod tests {
         // Should have set an error message
         assert_eq!(state.display_err_msg, Some(IoErrorKind::FileRead));
     }
+
+    #[test]
+    fn test_health_panel_takes_input_until_dismissed() {
+        let mut state = create_test_start_state();
+        let mock_fs = MockFileSystem::new();
+        state.health_issues = vec![HealthIssue::HomeDirMissing];
+
+        // Menu keys don't reach the start menu while the panel is open
+        start_kh(&mut state, create_key_event(KeyCode::Char('j')), &mock_fs);
+        assert_eq!(state.selected_button, SelectedStartButton::CreateSelect);
+
+        start_kh(&mut state, create_key_event(KeyCode::Esc), &mock_fs);
+        assert!(state.health_issues.is_empty());
+
+        start_kh(&mut state, create_key_event(KeyCode::Char('j')), &mock_fs);
+        assert_eq!(state.selected_button, SelectedStartButton::Tutorial);
+    }
+
+    #[test]
+    fn test_health_panel_fix_key_checks_again() {
+        let mut state = create_test_start_state();
+        let mock_fs = MockFileSystem::new();
+        state.health_issues = vec![HealthIssue::ConfigDirCreate(PathBuf::from(
+            "/mock/home/.config/tmmpr/",
+        ))];
+
+        let result = start_kh(&mut state, create_key_event(KeyCode::Char('f')), &mock_fs);
+
+        assert_eq!(result, AppAction::Continue);
+        // Directory creation succeeds on the mock filesystem, so nothing is left
+        assert!(state.health_issues.is_empty());
+    }
+
+    #[test]
+    fn test_passphrase_prompt_submits_unlock() {
+        let mut state = create_test_start_state();
+        let mock_fs = MockFileSystem::new();
+        state.ask_map_passphrase(
+            PathBuf::from("/test/journal.json"),
+            Some(CryptoError::WrongPassphrase),
+        );
+
+        // Menu keys are typed into the prompt
+        for c in "jk".chars() {
+            start_kh(&mut state, create_key_event(KeyCode::Char(c)), &mock_fs);
+        }
+        start_kh(&mut state, create_key_event(KeyCode::Backspace), &mock_fs);
+        assert_eq!(state.selected_button, SelectedStartButton::CreateSelect);
+
+        let result = start_kh(&mut state, create_key_event(KeyCode::Enter), &mock_fs);
+
+        assert_eq!(
+            result,
+            AppAction::UnlockMapFile(PathBuf::from("/test/journal.json"), String::from("j"))
+        );
+        assert_eq!(state.passphrase_prompt, None);
+    }
+
+    #[test]
+    fn test_passphrase_prompt_escape_cancels() {
+        let mut state = create_test_start_state();
+        let mock_fs = MockFileSystem::new();
+        state.ask_map_passphrase(PathBuf::from("/test/journal.json"), None);
+
+        let result = start_kh(&mut state, create_key_event(KeyCode::Esc), &mock_fs);
+
+        assert_eq!(result, AppAction::Continue);
+        assert_eq!(state.passphrase_prompt, None);
+    }
+
+    #[test]
+    fn test_checksum_warning_opens_map_anyway() {
+        let mut state = create_test_start_state();
+        let mock_fs = MockFileSystem::new();
+        let path = PathBuf::from("/test/synced.json");
+        state.warn_checksum_mismatch(path.clone());
+
+        // Menu keys don't reach the menu
+        let result = start_kh(&mut state, create_key_event(KeyCode::Char('j')), &mock_fs);
+        assert_eq!(result, AppAction::Continue);
+        assert_eq!(state.selected_button, SelectedStartButton::CreateSelect);
+
+        let result = start_kh(&mut state, create_key_event(KeyCode::Char('o')), &mock_fs);
+        assert_eq!(result, AppAction::LoadMapFileUnchecked(path.clone()));
+        assert_eq!(state.dialog, None);
+
+        state.warn_checksum_mismatch(path);
+        let result = start_kh(&mut state, create_key_event(KeyCode::Esc), &mock_fs);
+        assert_eq!(result, AppAction::Continue);
+        assert_eq!(state.dialog, None);
+    }
+
+    #[test]
+    fn test_crash_changes_dialog_restores_or_discards() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let fs = TempFileSystem {
+            home_path: temp_dir.path().to_path_buf(),
+        };
+        let map_path = temp_dir.path().join("plan.json");
+        let mut state = create_test_start_state();
+        state.recent_paths = Ok(RecentPaths {
+            pinned: vec![],
+            recent: vec![map_path.clone()],
+        });
+        fs::write(emergency_save_path(&map_path), "{}").unwrap();
+
+        state.offer_crash_changes_with_fs(&fs);
+        let result = start_kh(&mut state, create_key_event(KeyCode::Char('r')), &fs);
+        assert_eq!(result, AppAction::LoadMapFile(map_path.clone()));
+        assert_eq!(state.pending_crash_restore, Some(map_path.clone()));
+
+        state.offer_crash_changes_with_fs(&fs);
+        let result = start_kh(&mut state, create_key_event(KeyCode::Char('d')), &fs);
+        assert_eq!(result, AppAction::Continue);
+        assert_eq!(state.dialog, None);
+        assert!(!emergency_save_path(&map_path).exists());
+    }
 }
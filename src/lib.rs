@@ -10,7 +10,52 @@
 //!
 //! ## Library Usage
 //!
-//! TODO
+//! [`graph::Graph`] stores positioned nodes and directed edges and can be walked
+//! with [`graph::Graph::neighbors`] or the BFS/DFS iterators:
+//!
+//! ```
+//! use tmmpr::graph::{Graph, Node};
+//!
+//! let mut graph = Graph::new();
+//! graph.add_node(0, Node::new(0, 0, "root"));
+//! graph.add_node(1, Node::new(20, 0, "child"));
+//! graph.add_node(2, Node::new(40, 0, "grandchild"));
+//! graph.add_edge(0, 1);
+//! graph.add_edge(1, 2);
+//!
+//! assert_eq!(graph.neighbors(0), &[1]);
+//! assert_eq!(graph.bfs(0).collect::<Vec<_>>(), vec![0, 1, 2]);
+//! ```
+//!
+//! [`graph::MapBuilder`] puts together map data without a running app or files on disk.
+//!
+//! [`graph::algorithms`] orders dependency graphs and finds cycles:
+//!
+//! ```
+//! use tmmpr::graph::{Graph, Node, algorithms::{has_cycle, topological_sort}};
+//!
+//! let mut graph = Graph::new();
+//! graph.add_node(0, Node::new(0, 0, "deploy"));
+//! graph.add_node(1, Node::new(20, 0, "build"));
+//! graph.add_edge(1, 0);
+//!
+//! assert!(!has_cycle(&graph));
+//! assert_eq!(topological_sort(&graph), Ok(vec![1, 0]));
+//! ```
+//!
+//! [`graph::routing`] lays out connection paths with a strategy chosen per call:
+//!
+//! ```
+//! use tmmpr::graph::routing::{Obstacle, ObstacleAvoiding, Point, RoutingStrategy, Side};
+//!
+//! let start = Point { x: 0, y: 10 };
+//! let end = Point { x: 40, y: 10 };
+//! let wall = Obstacle::new(15, 0, 10, 21);
+//!
+//! let path = ObstacleAvoiding::default().route(start, Side::Right, end, Side::Left, &[wall]);
+//! assert_eq!(path.first(), Some(&start));
+//! assert_eq!(path.last(), Some(&end));
+//! ```
 //!
 //! Other modules are internal and subject to change.
 
@@ -19,6 +64,10 @@ pub mod graph;
 #[doc(hidden)]
 pub mod app;
 #[doc(hidden)]
+pub mod bench;
+#[doc(hidden)]
+pub mod cli;
+#[doc(hidden)]
 pub mod input;
 #[doc(hidden)]
 pub mod states;
@@ -1,55 +1,131 @@
-use std::io::stdout;
+use std::{
+    env,
+    io::stdout,
+    panic::{AssertUnwindSafe, catch_unwind},
+    process::ExitCode,
+    time::Instant,
+};
 
+use chrono::Local;
 use color_eyre::Result;
 use crossterm::{cursor::SetCursorStyle, execute};
 use ratatui::DefaultTerminal;
 use tmmpr::{
     app::{App, Screen},
-    input::handle_events,
-    ui::{render_map, render_settings, render_start},
+    cli::{CliCommand, PASSPHRASE_VAR, USAGE, parse_args, run_cli},
+    input::{
+        FramePacer, disable_bracketed_paste, disable_keyboard_enhancement, enable_bracketed_paste,
+        enable_keyboard_enhancement, handle_events,
+    },
+    ui::{render_backups, render_map, render_settings, render_start},
+    utils::{TmmprError, crash_report, emergency_save, install_panic_hook},
 };
 
-fn main() -> color_eyre::Result<()> {
+fn main() -> color_eyre::Result<ExitCode> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match parse_args(&args) {
+        Ok(Some(command)) => return Ok(run_headless(&command)),
+        Ok(None) => {}
+        Err(message) => {
+            eprintln!("tmmpr: {message}\n\n{USAGE}");
+            return Ok(ExitCode::from(2));
+        }
+    }
+
     color_eyre::install()?;
     let terminal = ratatui::init();
+    enable_keyboard_enhancement();
+    enable_bracketed_paste();
+    install_panic_hook();
     let mut app = App::new();
-    let result = run(terminal, &mut app);
+    let result = catch_unwind(AssertUnwindSafe(|| run(terminal, &mut app)));
+    disable_keyboard_enhancement();
+    disable_bracketed_paste();
     ratatui::restore();
-    result
+    match result {
+        Ok(result) => result.map(|_| ExitCode::SUCCESS),
+        Err(_) => Ok(crash_exit(&mut app)),
+    }
+}
+
+/// Saves the open map's unsaved changes to a recovery file after a panic, which the
+/// panic hook has already reported, and says what happened to them.
+fn crash_exit(app: &mut App) -> ExitCode {
+    let saved = match &mut app.screen {
+        // The map may be left half-changed by the panic, so saving it may panic too
+        Screen::Map(map_state) => catch_unwind(AssertUnwindSafe(|| emergency_save(map_state)))
+            .unwrap_or_else(|_| {
+                Some(Err(TmmprError::Validation(String::from(
+                    "saving the map failed as well",
+                ))))
+            }),
+        _ => None,
+    };
+    eprintln!("\n{}", crash_report(saved.as_ref()));
+    ExitCode::from(101)
+}
+
+/// Runs a command given on the command line, without the terminal UI.
+fn run_headless(command: &CliCommand) -> ExitCode {
+    let passphrase = env::var(PASSPHRASE_VAR).ok();
+    match run_cli(command, &mut stdout().lock(), passphrase.as_deref()) {
+        Ok(status) => ExitCode::from(status),
+        Err(err) => {
+            eprintln!("tmmpr: {err}");
+            ExitCode::from(2)
+        }
+    }
 }
 
 /// Main event loop using on-demand rendering to reduce CPU usage.
-/// Each screen state tracks whether it needs redrawing instead of rendering every frame.
+/// Each screen state tracks whether it needs redrawing instead of rendering every frame,
+/// and redraws are capped at one per frame (see [`FramePacer`]).
 fn run(mut terminal: DefaultTerminal, app: &mut App) -> Result<()> {
     let _ = execute!(stdout(), SetCursorStyle::SteadyBar);
+    let mut pacer = FramePacer::new();
 
     while app.running {
-        match &mut app.screen {
-            Screen::Start(start_state) => {
-                if start_state.needs_clear_and_redraw {
-                    terminal.draw(|frame| render_start(frame, start_state))?;
-                    start_state.needs_clear_and_redraw = false;
-                }
-            }
-            Screen::Settings(settings_state) => {
-                if settings_state.needs_clear_and_redraw {
-                    terminal.draw(|frame| render_settings(frame, settings_state))?;
-                    settings_state.needs_clear_and_redraw = false;
-                }
-            }
-            Screen::Map(map_state) => {
-                // Periodic auto-save and backup creation (respects user settings)
-                map_state.auto_save_if_needed();
-                map_state.auto_backup_if_needed();
-
-                if map_state.ui_state.needs_clear_and_redraw {
-                    terminal.draw(|frame| render_map(frame, map_state))?;
-                    map_state.ui_state.mark_redrawn();
-                }
-            }
-        };
-
-        handle_events(app)?;
+        if let Screen::Map(map_state) = &mut app.screen {
+            // Changes to the map file by other programs are caught before auto-save
+            // can overwrite them
+            map_state.check_external_change();
+            // Periodic auto-save and backup creation (respects user settings)
+            map_state.auto_save_if_needed();
+            map_state.auto_backup_if_needed();
+            map_state.refresh_bar_clock(Local::now());
+            map_state.ui_state.expire_notifications(Instant::now());
+        }
+
+        if app.needs_redraw() && pacer.can_draw(Instant::now()) {
+            draw(&mut terminal, app)?;
+            pacer.mark_drawn(Instant::now());
+        }
+
+        handle_events(app, pacer.poll_timeout(app.needs_redraw(), Instant::now()))?;
+    }
+
+    Ok(())
+}
+
+/// Draws the current screen and marks it as drawn.
+fn draw(terminal: &mut DefaultTerminal, app: &mut App) -> Result<()> {
+    match &mut app.screen {
+        Screen::Start(start_state) => {
+            terminal.draw(|frame| render_start(frame, start_state))?;
+            start_state.needs_clear_and_redraw = false;
+        }
+        Screen::Settings(settings_state) => {
+            terminal.draw(|frame| render_settings(frame, settings_state))?;
+            settings_state.needs_clear_and_redraw = false;
+        }
+        Screen::Backups(backups_state) => {
+            terminal.draw(|frame| render_backups(frame, backups_state))?;
+            backups_state.needs_clear_and_redraw = false;
+        }
+        Screen::Map(map_state) => {
+            terminal.draw(|frame| render_map(frame, map_state))?;
+            map_state.ui_state.mark_redrawn();
+        }
     }
 
     Ok(())
@@ -0,0 +1,118 @@
+use chrono::NaiveDateTime;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    states::MapState,
+    utils::{MapData, list_backups, map_name},
+};
+
+/// One of the open map's backups.
+#[derive(PartialEq, Debug)]
+pub struct BackupEntry {
+    pub path: PathBuf,
+    /// When the backup was made, from its file name
+    pub created_at: NaiveDateTime,
+    /// Made when the map was opened (only the day is known), not during a session
+    pub on_load: bool,
+    /// The backup's contents, None if it can't be read (or decrypted with the map's key)
+    pub map_data: Option<MapData>,
+}
+
+impl BackupEntry {
+    pub fn note_count(&self) -> Option<usize> {
+        self.map_data.as_ref().map(|map_data| map_data.notes.len())
+    }
+}
+
+/// The backups screen: the open map's backups, to preview and restore one of them.
+#[derive(PartialEq, Debug)]
+pub struct BackupsState {
+    pub needs_clear_and_redraw: bool,
+    /// The open map, kept as it is to return to
+    pub map_state: Box<MapState>,
+    /// Newest first
+    pub backups: Vec<BackupEntry>,
+    /// Index of the highlighted backup
+    pub selected: usize,
+    /// No backups directory is set in the settings
+    pub backups_disabled: bool,
+}
+
+impl BackupsState {
+    /// Lists the backups of the open map in the backups directory from the settings.
+    pub fn new(map_state: MapState) -> BackupsState {
+        let backups = match &map_state.settings.backups_path {
+            Some(backups_path) => read_backups(Path::new(backups_path), &map_state),
+            None => vec![],
+        };
+
+        BackupsState {
+            needs_clear_and_redraw: true,
+            backups_disabled: map_state.settings.backups_path.is_none(),
+            map_state: Box::new(map_state),
+            backups,
+            selected: 0,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.backups.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn selected_backup(&self) -> Option<&BackupEntry> {
+        self.backups.get(self.selected)
+    }
+
+    /// Replaces the open map's contents with the highlighted backup's. Returns false if
+    /// there's no backup or it couldn't be read.
+    pub fn restore_selected(&mut self) -> bool {
+        let Some(map_data) = self
+            .backups
+            .get(self.selected)
+            .and_then(|backup| backup.map_data.clone())
+        else {
+            return false;
+        };
+
+        self.map_state.restore_backup(map_data);
+        true
+    }
+
+    /// Moves the open map out, to return to the map screen.
+    pub fn take_map_state(&mut self) -> MapState {
+        let mut map_state = self.map_state.take();
+        map_state.clear_and_redraw();
+        map_state
+    }
+}
+
+/// Reads the map's backups in the directory. Encrypted backups are decrypted with the
+/// key the map was opened with.
+fn read_backups(backups_path: &Path, map_state: &MapState) -> Vec<BackupEntry> {
+    let name = map_name(&map_state.persistence.file_write_path);
+
+    list_backups(backups_path, &name)
+        .into_iter()
+        .map(|(path, created_at)| {
+            let map_data = map_state.read_map_copy(&path);
+
+            let on_load = path
+                .file_name()
+                .and_then(|file_name| file_name.to_str()?.strip_prefix(name.as_str()))
+                .is_some_and(|rest| rest.starts_with("-load-backup-"));
+
+            BackupEntry {
+                path,
+                created_at,
+                on_load,
+                map_data,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,91 @@
+use crossterm::event::KeyCode;
+use ratatui::style::Color;
+
+/// A button of a [`Dialog`], chosen with its key or with Enter while it has focus.
+#[derive(PartialEq, Clone, Debug)]
+pub struct DialogButton<A> {
+    pub label: String,
+    pub key: KeyCode,
+    pub action: A,
+    pub color: Color,
+}
+
+/// A modal dialog: a question with a few buttons, which takes all input while shown.
+///
+/// `A` is what the screen showing the dialog does for each button; see
+/// [`crate::input::dialog_kh`] for the keys.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Dialog<A> {
+    pub title: String,
+    /// Lines shown below the title
+    pub message: Vec<String>,
+    pub buttons: Vec<DialogButton<A>>,
+    /// Index of the button Enter chooses
+    pub focused: usize,
+    /// Chosen by Esc, unless a button has that key
+    pub cancel: A,
+}
+
+impl<A: Clone> Dialog<A> {
+    pub fn new(title: impl Into<String>, cancel: A) -> Self {
+        Self {
+            title: title.into(),
+            message: Vec::new(),
+            buttons: Vec::new(),
+            focused: 0,
+            cancel,
+        }
+    }
+
+    /// Adds a line below the title.
+    pub fn line(mut self, line: impl Into<String>) -> Self {
+        self.message.push(line.into());
+        self
+    }
+
+    /// Adds a button to the right of the others.
+    pub fn button(
+        mut self,
+        label: impl Into<String>,
+        key: KeyCode,
+        action: A,
+        color: Color,
+    ) -> Self {
+        self.buttons.push(DialogButton {
+            label: label.into(),
+            key,
+            action,
+            color,
+        });
+        self
+    }
+
+    /// Moves the focus one button to the right, from the last back to the first.
+    pub fn focus_next(&mut self) {
+        if !self.buttons.is_empty() {
+            self.focused = (self.focused + 1) % self.buttons.len();
+        }
+    }
+
+    /// Moves the focus one button to the left, from the first to the last.
+    pub fn focus_previous(&mut self) {
+        if !self.buttons.is_empty() {
+            self.focused = (self.focused + self.buttons.len() - 1) % self.buttons.len();
+        }
+    }
+
+    /// Action of the button with focus.
+    pub fn focused_action(&self) -> Option<A> {
+        self.buttons
+            .get(self.focused)
+            .map(|button| button.action.clone())
+    }
+
+    /// Action of the button chosen with `key`.
+    pub fn action_for_key(&self, key: KeyCode) -> Option<A> {
+        self.buttons
+            .iter()
+            .find(|button| button.key == key)
+            .map(|button| button.action.clone())
+    }
+}
@@ -0,0 +1,58 @@
+use crate::states::map::Note;
+
+/// Rubber-band rectangle of Visual (Box) mode, in canvas coordinates.
+///
+/// One corner stays at the top-left of the note that was selected when the box was
+/// started; the opposite corner is moved with hjkl.
+#[derive(PartialEq, Clone, Debug)]
+pub struct BoxSelect {
+    pub anchor: (isize, isize),
+    pub corner: (isize, isize),
+    /// Note selected before the box was started, restored if it's cancelled
+    pub original: usize,
+}
+
+impl BoxSelect {
+    /// Starts a box covering `note`.
+    pub fn new(original: usize, note: &Note) -> Self {
+        let (width, height) = note.get_dimensions();
+        Self {
+            anchor: (note.x, note.y),
+            corner: (
+                note.x + width.saturating_sub(1) as isize,
+                note.y + height.saturating_sub(1) as isize,
+            ),
+            original,
+        }
+    }
+
+    /// Moves the free corner.
+    pub fn move_corner(&mut self, dx: isize, dy: isize) {
+        self.corner.0 += dx;
+        self.corner.1 += dy;
+    }
+
+    /// Top-left and bottom-right cells covered by the box (both inclusive).
+    pub fn bounds(&self) -> ((isize, isize), (isize, isize)) {
+        (
+            (
+                self.anchor.0.min(self.corner.0),
+                self.anchor.1.min(self.corner.1),
+            ),
+            (
+                self.anchor.0.max(self.corner.0),
+                self.anchor.1.max(self.corner.1),
+            ),
+        )
+    }
+
+    /// True if any cell of the note, border included, is inside the box.
+    pub fn intersects(&self, note: &Note) -> bool {
+        let ((left, top), (right, bottom)) = self.bounds();
+        let (width, height) = note.get_dimensions();
+        let note_right = note.x + width.saturating_sub(1) as isize;
+        let note_bottom = note.y + height.saturating_sub(1) as isize;
+
+        note.x <= right && note_right >= left && note.y <= bottom && note_bottom >= top
+    }
+}
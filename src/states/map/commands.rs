@@ -0,0 +1,199 @@
+use std::path::PathBuf;
+
+use super::NoteStatus;
+use crate::utils::{NoteListFormat, Substitution};
+
+/// A command typed on the `:` command line in Normal or Visual mode.
+#[derive(PartialEq, Debug)]
+pub enum Command {
+    /// `export [selection|branch] <format> [path]`: writes the map, or just part of it,
+    /// in another format, by default next to the map file with the format's extension
+    Export(ExportScope, ExportFormat, Option<PathBuf>),
+    /// `import <path>`: adds the notes of an OPML or FreeMind outline at the viewport
+    /// center
+    Import(PathBuf),
+    /// `import lines|paragraphs|csv <path>`: adds the notes listed in a plain text or
+    /// CSV file, laid out in a grid from the viewport center
+    ImportNotes(NoteListFormat, PathBuf),
+    /// `encrypt`: encrypts the map file at rest with a passphrase, or changes it
+    Encrypt,
+    /// `decrypt`: stores the map file unencrypted again
+    Decrypt,
+    /// `snapshot <name>`: saves a named, timestamped snapshot of the map in its
+    /// snapshots directory
+    Snapshot(String),
+    /// `snapshots`: lists the map's snapshots to load one or compare it with the map
+    Snapshots,
+    /// `diff <path>`: highlights on the canvas how the open map differs from another
+    /// map file, such as an older copy
+    Diff(PathBuf),
+    /// `%s/pattern/replacement/[gri]`: replaces text in every note, see [`Substitution`]
+    Substitute(Substitution),
+    /// `grep <pattern>`: searches the notes of every map in the workspace, listing the
+    /// matching notes to open
+    Grep(String),
+    /// `trash`: lists the deleted notes to restore them or delete them for good
+    Trash,
+    /// `connections`: lists every connection in a table to delete or recolor many at once
+    Connections,
+    /// `fit`: moves the view so every note is on screen at once, like `zf`
+    Fit,
+    /// `filter <status>`: dims the notes without the status; `filter` alone shows all
+    /// notes again
+    Filter(Option<NoteStatus>),
+    /// `plugin <function>`: runs a function without parameters defined by the plugin
+    /// scripts, see [`crate::utils::Plugins`]
+    Plugin(String),
+    /// `run <name>`: runs an external program defined in the settings, see
+    /// [`crate::utils::UserCommand`]
+    Run(String),
+}
+
+/// Which notes `:export` writes.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ExportScope {
+    /// Every note
+    Map,
+    /// The notes selected in Visual mode
+    Selection,
+    /// The selected note and the notes reachable from it by following connections
+    Branch,
+}
+
+impl ExportScope {
+    /// Parses the scope name used on the command line.
+    pub fn from_name(name: &str) -> Option<ExportScope> {
+        match name {
+            "selection" => Some(ExportScope::Selection),
+            "branch" => Some(ExportScope::Branch),
+            _ => None,
+        }
+    }
+}
+
+/// A format the map can be exported to.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    /// Mermaid `flowchart TD`
+    Mermaid,
+    /// An image with the terminal layout and colors
+    Svg,
+    /// OPML 2.0 outline
+    Opml,
+    /// FreeMind mind map
+    FreeMind,
+    /// A page to explore the map in a browser
+    Html,
+    /// Graphviz `digraph`
+    Dot,
+}
+
+impl ExportFormat {
+    /// Parses the format name used on the command line.
+    pub fn from_name(name: &str) -> Option<ExportFormat> {
+        match name {
+            "mermaid" => Some(ExportFormat::Mermaid),
+            "svg" => Some(ExportFormat::Svg),
+            "opml" => Some(ExportFormat::Opml),
+            "freemind" | "mm" => Some(ExportFormat::FreeMind),
+            "html" => Some(ExportFormat::Html),
+            "dot" | "graphviz" => Some(ExportFormat::Dot),
+            _ => None,
+        }
+    }
+
+    /// Extension of the default export path.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Mermaid => "mmd",
+            ExportFormat::Svg => "svg",
+            ExportFormat::Opml => "opml",
+            ExportFormat::FreeMind => "mm",
+            ExportFormat::Html => "html",
+            ExportFormat::Dot => "dot",
+        }
+    }
+}
+
+impl Command {
+    /// Parses the text typed after `:`. Returns the unrecognized input as the error.
+    pub fn parse(input: &str) -> Result<Command, String> {
+        // The pattern and replacement may contain spaces, so this isn't split into words
+        if let Some(substitution) = input.trim_start().strip_prefix("%s") {
+            return Substitution::parse(substitution)
+                .map(Command::Substitute)
+                .ok_or(input.trim().to_string());
+        }
+
+        let mut words = input.split_whitespace();
+
+        match (words.next(), words.next()) {
+            (Some("export"), Some(first)) => {
+                let (scope, format) = match ExportScope::from_name(first) {
+                    Some(scope) => (scope, words.next().unwrap_or_default()),
+                    None => (ExportScope::Map, first),
+                };
+                let format = ExportFormat::from_name(format).ok_or(input.trim().to_string())?;
+                let path = words.collect::<Vec<_>>().join(" ");
+                Ok(Command::Export(
+                    scope,
+                    format,
+                    (!path.is_empty()).then(|| PathBuf::from(path)),
+                ))
+            }
+            (Some("import"), Some(first)) => {
+                if let Some(format) = NoteListFormat::from_name(first) {
+                    let path = words.collect::<Vec<_>>().join(" ");
+                    if path.is_empty() {
+                        return Err(input.trim().to_string());
+                    }
+                    return Ok(Command::ImportNotes(format, PathBuf::from(path)));
+                }
+
+                let path = std::iter::once(first)
+                    .chain(words)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Ok(Command::Import(PathBuf::from(path)))
+            }
+            (Some("encrypt"), None) => Ok(Command::Encrypt),
+            (Some("decrypt"), None) => Ok(Command::Decrypt),
+            (Some("snapshot"), Some(first)) => {
+                let name = std::iter::once(first)
+                    .chain(words)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Ok(Command::Snapshot(name))
+            }
+            (Some("snapshots"), None) => Ok(Command::Snapshots),
+            (Some("trash"), None) => Ok(Command::Trash),
+            (Some("connections"), None) => Ok(Command::Connections),
+            (Some("fit"), None) => Ok(Command::Fit),
+            (Some("filter"), None) => Ok(Command::Filter(None)),
+            (Some("filter"), Some(status)) if words.next().is_none() => {
+                NoteStatus::from_name(status)
+                    .map(|status| Command::Filter(Some(status)))
+                    .ok_or(input.trim().to_string())
+            }
+            (Some("plugin"), Some(function)) if words.next().is_none() => {
+                Ok(Command::Plugin(function.to_string()))
+            }
+            (Some("run"), Some(name)) if words.next().is_none() => {
+                Ok(Command::Run(name.to_string()))
+            }
+            // The pattern is the rest of the line, spaces included
+            (Some("grep"), Some(_)) => {
+                let pattern = input.trim_start().strip_prefix("grep").unwrap_or_default();
+                Ok(Command::Grep(pattern.trim().to_string()))
+            }
+            (Some("diff"), Some(first)) => {
+                let path = std::iter::once(first)
+                    .chain(words)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Ok(Command::Diff(PathBuf::from(path)))
+            }
+            _ => Err(input.trim().to_string()),
+        }
+    }
+}
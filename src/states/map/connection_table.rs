@@ -0,0 +1,89 @@
+use std::collections::BTreeSet;
+
+/// Column the connection table is sorted by.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum ConnectionSort {
+    /// Title of the note the connection starts at
+    #[default]
+    From,
+    /// Title of the note the connection ends at
+    To,
+    Color,
+    Weight,
+}
+
+impl ConnectionSort {
+    /// The column after this one, back to the first after the last.
+    pub fn next(self) -> ConnectionSort {
+        match self {
+            ConnectionSort::From => ConnectionSort::To,
+            ConnectionSort::To => ConnectionSort::Color,
+            ConnectionSort::Color => ConnectionSort::Weight,
+            ConnectionSort::Weight => ConnectionSort::From,
+        }
+    }
+}
+
+/// Table of every connection in the map shown over it (`:connections`), to look them
+/// over and delete or recolor many at once.
+#[derive(PartialEq, Debug, Default)]
+pub struct ConnectionTable {
+    /// Indices of the connections, in the order they're listed
+    pub rows: Vec<usize>,
+    /// Index of the highlighted row
+    pub selected: usize,
+    pub sort: ConnectionSort,
+    pub descending: bool,
+    /// Indices of the connections marked for deleting or recoloring together
+    pub marked: BTreeSet<usize>,
+}
+
+impl ConnectionTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.rows.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Index of the highlighted connection, None if there are none.
+    pub fn selected_connection(&self) -> Option<usize> {
+        self.rows.get(self.selected).copied()
+    }
+
+    /// Marks or unmarks the highlighted connection and moves on to the next one.
+    pub fn toggle_mark(&mut self) {
+        if let Some(index) = self.selected_connection() {
+            if !self.marked.remove(&index) {
+                self.marked.insert(index);
+            }
+            self.select_next();
+        }
+    }
+
+    /// Marks every connection, or none if they all are already.
+    pub fn toggle_mark_all(&mut self) {
+        if self.marked.len() == self.rows.len() {
+            self.marked.clear();
+        } else {
+            self.marked = self.rows.iter().copied().collect();
+        }
+    }
+
+    /// Connections a bulk action applies to: the marked ones, or the highlighted one
+    /// if none are marked.
+    pub fn targets(&self) -> Vec<usize> {
+        if self.marked.is_empty() {
+            self.selected_connection().into_iter().collect()
+        } else {
+            self.marked.iter().copied().collect()
+        }
+    }
+}
@@ -1,25 +1,160 @@
 use ratatui::style::Color;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::enums::Side;
+use crate::utils::Point;
 
 /// Represents a directional connection between notes in the map.
 ///
-/// Connections can be in-progress (only `from` specified) or complete (both `from` and `to`).
-/// This allows drawing connections interactively before the user selects a target note.
-#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+/// Stored connections always lead somewhere; one still being drawn by the user is a
+/// [`DraftConnection`].
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
 pub struct Connection {
     pub from_id: usize,
     pub from_side: Side,
-    /// None for in-progress connections being drawn by the user
-    pub to_id: Option<usize>,
-    /// None for in-progress connections being drawn by the user
-    pub to_side: Option<Side>,
+    pub to_id: usize,
+    pub to_side: Side,
     /// Custom serde implementation in utils handles Color serialization
     #[serde(with = "crate::utils")]
+    #[schemars(with = "String")]
     pub color: Color,
+    /// User-placed bend points the path is routed through, in order from start to end
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub waypoints: Vec<Point>,
+    /// How strong or costly the link is; None means the default weight of 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u32>,
+    /// Index of the connection's layer; None means the first (default) layer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<usize>,
 }
+
+impl Connection {
+    /// The connection's weight, 1 if none was set.
+    pub fn weight(&self) -> u32 {
+        self.weight.unwrap_or(1)
+    }
+
+    /// Raises or lowers the weight by `delta`, never going below 1.
+    /// A weight of 1 is stored as None, so unweighted maps are saved unchanged.
+    pub fn adjust_weight(&mut self, delta: i64) {
+        self.weight = adjusted_weight(self.weight(), delta);
+    }
+
+    /// Index of the connection's layer.
+    pub fn layer(&self) -> usize {
+        self.layer.unwrap_or(0)
+    }
+
+    /// Puts the connection on a layer, stored like [`Note::set_layer`](super::Note::set_layer).
+    pub fn set_layer(&mut self, index: usize) {
+        self.layer = (index != 0).then_some(index);
+    }
+
+    /// True if the connection leads from a note back to itself.
+    pub fn is_self_loop(&self) -> bool {
+        self.to_id == self.from_id
+    }
+
+    /// True if the connection starts or ends at the note.
+    pub fn touches(&self, id: usize) -> bool {
+        self.from_id == id || self.to_id == id
+    }
+
+    /// True if both connections join the same sides of the same two notes, in either
+    /// direction, so their lines would be drawn on top of each other.
+    pub fn has_same_ends(&self, other: &Connection) -> bool {
+        let start = (self.from_id, self.from_side);
+        let end = (self.to_id, self.to_side);
+        let other_start = (other.from_id, other.from_side);
+        let other_end = (other.to_id, other.to_side);
+        (start, end) == (other_start, other_end) || (start, end) == (other_end, other_start)
+    }
+}
+
+/// A connection being drawn or edited by the user, which doesn't lead anywhere until
+/// its end note is chosen. Stored as a [`Connection`] once it does, see
+/// [`DraftConnection::completed`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct DraftConnection {
+    pub from_id: usize,
+    pub from_side: Side,
+    /// Note the connection leads to and the side it enters it through, None while
+    /// it's being drawn
+    pub to: Option<(usize, Side)>,
+    pub color: Color,
+    pub waypoints: Vec<Point>,
+    pub weight: Option<u32>,
+    pub layer: Option<usize>,
+}
+
+impl DraftConnection {
+    /// Starts a connection from a note's side, without an end yet.
+    pub fn new(from_id: usize, from_side: Side, color: Color) -> Self {
+        Self {
+            from_id,
+            from_side,
+            to: None,
+            color,
+            waypoints: vec![],
+            weight: None,
+            layer: None,
+        }
+    }
+
+    /// The connection's weight, 1 if none was set.
+    pub fn weight(&self) -> u32 {
+        self.weight.unwrap_or(1)
+    }
+
+    /// Raises or lowers the weight like [`Connection::adjust_weight`].
+    pub fn adjust_weight(&mut self, delta: i64) {
+        self.weight = adjusted_weight(self.weight(), delta);
+    }
+
+    /// Puts the connection on a layer, stored like [`Connection::set_layer`].
+    pub fn set_layer(&mut self, index: usize) {
+        self.layer = (index != 0).then_some(index);
+    }
+
+    /// The connection as it's stored, None while it has no end.
+    pub fn completed(&self) -> Option<Connection> {
+        let (to_id, to_side) = self.to?;
+        Some(Connection {
+            from_id: self.from_id,
+            from_side: self.from_side,
+            to_id,
+            to_side,
+            color: self.color,
+            waypoints: self.waypoints.clone(),
+            weight: self.weight,
+            layer: self.layer,
+        })
+    }
+}
+
+impl From<Connection> for DraftConnection {
+    fn from(connection: Connection) -> Self {
+        Self {
+            from_id: connection.from_id,
+            from_side: connection.from_side,
+            to: Some((connection.to_id, connection.to_side)),
+            color: connection.color,
+            waypoints: connection.waypoints,
+            weight: connection.weight,
+            layer: connection.layer,
+        }
+    }
+}
+
+/// `weight` changed by `delta`, never going below 1, as stored: None for 1.
+fn adjusted_weight(weight: u32, delta: i64) -> Option<u32> {
+    let weight = (i64::from(weight) + delta).clamp(1, i64::from(u32::MAX)) as u32;
+    (weight != 1).then_some(weight)
+}
+
 /// Manages the bidirectional relationship between connections and notes.
 ///
 /// Maintains two synchronized data structures:
@@ -27,6 +162,7 @@ pub struct Connection {
 /// - `connection_index`: Fast lookup from note ID to connection indices
 ///
 /// This encapsulation ensures the index is always consistent with the connections vector.
+/// Only the connections are saved; the index is rebuilt from them when a map is loaded.
 #[derive(PartialEq, Debug)]
 struct ConnectionManager {
     connections: Vec<Connection>,
@@ -46,14 +182,30 @@ impl ConnectionManager {
     /// Creates a manager from existing data, rebuilding the index.
     /// Used when loading from disk.
     fn from_connections(connections: Vec<Connection>) -> Self {
-        let mut manager = Self::new();
-        for connection in connections {
-            if connection.to_id.is_some() {
-                // Validate
-                manager.add(connection);
+        let mut manager = Self {
+            connections,
+            connection_index: HashMap::new(),
+        };
+        manager.rebuild_index();
+        manager
+    }
+
+    /// Builds the index again from the connections, dropping whatever it held.
+    fn rebuild_index(&mut self) {
+        self.connection_index.clear();
+        for (index, connection) in self.connections.iter().enumerate() {
+            self.connection_index
+                .entry(connection.from_id)
+                .or_default()
+                .push(index);
+            // A note's connection back to itself is listed for it once
+            if !connection.is_self_loop() {
+                self.connection_index
+                    .entry(connection.to_id)
+                    .or_default()
+                    .push(index);
             }
         }
-        manager
     }
 
     /// Adds a connection and updates the index for both endpoints.
@@ -61,21 +213,20 @@ impl ConnectionManager {
     fn add(&mut self, connection: Connection) -> usize {
         let index = self.connections.len();
 
-        self.connections.push(connection);
-
         self.connection_index
             .entry(connection.from_id)
             .or_default()
             .push(index);
 
-        self.connection_index
-            .entry(
-                connection
-                    .to_id
-                    .expect("connections should always have an endpoint"),
-            )
-            .or_default()
-            .push(index);
+        // A note's connection back to itself is listed for it once
+        if !connection.is_self_loop() {
+            self.connection_index
+                .entry(connection.to_id)
+                .or_default()
+                .push(index);
+        }
+
+        self.connections.push(connection);
 
         index
     }
@@ -151,9 +302,11 @@ impl ConnectionManager {
 pub struct ConnectionsState {
     manager: ConnectionManager,
     /// Connection currently being created or edited by the user.
-    pub focused_connection: Option<Connection>,
+    pub focused_connection: Option<DraftConnection>,
     /// Original position in the connections vector when a connection was removed for editing.
     pub editing_connection_index: Option<usize>,
+    /// Waypoint of the focused connection being edited in Visual (Waypoint) mode
+    pub selected_waypoint: Option<usize>,
 }
 
 impl ConnectionsState {
@@ -162,6 +315,7 @@ impl ConnectionsState {
             manager: ConnectionManager::new(),
             focused_connection: None,
             editing_connection_index: None,
+            selected_waypoint: None,
         }
     }
 
@@ -170,34 +324,159 @@ impl ConnectionsState {
         self.manager.connections()
     }
 
+    /// True if the focused connection has both ends and a stored connection already
+    /// joins the same sides of the same notes.
+    pub fn focused_is_duplicate(&self) -> bool {
+        self.focused_connection
+            .as_ref()
+            .and_then(DraftConnection::completed)
+            .is_some_and(|focused| {
+                self.connections()
+                    .iter()
+                    .any(|connection| connection.has_same_ends(&focused))
+            })
+    }
+
+    /// How many cells aside a connection is drawn, so it doesn't cover the other
+    /// connections between the same two notes: 0 for the first of them, then 1, -1, 2,
+    /// -2 and so on. The focused connection comes after the stored ones.
+    ///
+    /// Offsets are to the right of the direction from the lower to the higher note id,
+    /// so connections going either way get different lanes.
+    pub fn parallel_offset(&self, connection: &Connection) -> isize {
+        let to_id = connection.to_id;
+        let same_notes = |other: &&Connection| {
+            (other.from_id, other.to_id) == (connection.from_id, to_id)
+                || (other.from_id, other.to_id) == (to_id, connection.from_id)
+        };
+        let parallel: Vec<&Connection> = self
+            .get_connections_for_note(connection.from_id)
+            .into_iter()
+            .filter(same_notes)
+            .collect();
+        let lane = parallel
+            .iter()
+            .position(|other| std::ptr::eq(*other, connection))
+            .unwrap_or(parallel.len()) as isize;
+
+        let offset = if lane % 2 == 1 {
+            (lane + 1) / 2
+        } else {
+            -(lane / 2)
+        };
+        if connection.from_id < to_id {
+            offset
+        } else {
+            -offset
+        }
+    }
+
     #[cfg(test)]
     pub fn connection_index(&self) -> &HashMap<usize, Vec<usize>> {
         self.manager.connection_index()
     }
 
+    /// Builds the note-to-connections lookup again from the stored connections. It isn't
+    /// saved with the map, so loading always does this; anything changing the
+    /// connections behind the state's back should too.
+    pub fn rebuild_index(&mut self) {
+        self.manager.rebuild_index();
+    }
+
     // For deserialization
     pub fn from_connections(connections: Vec<Connection>) -> Self {
         Self {
             manager: ConnectionManager::from_connections(connections),
             focused_connection: None,
             editing_connection_index: None,
+            selected_waypoint: None,
         }
     }
 
+    /// Adds a connection directly, without going through the focused connection.
+    pub fn add(&mut self, connection: Connection) {
+        self.manager.add(connection);
+    }
+
     /// Finalizes the focused connection by adding it to the permanent connections list.
-    /// Incomplete connections (missing `to_id`) are discarded.
+    /// One without an end yet is discarded.
     pub fn stash_connection(&mut self) {
-        if let Some(connection) = self.focused_connection.take() {
-            if connection.to_id.is_some() {
-                self.manager.add(connection);
-            }
+        if let Some(connection) = self
+            .focused_connection
+            .take()
+            .and_then(|focused| focused.completed())
+        {
+            self.manager.add(connection);
+        }
+    }
+
+    /// Deletes a stored connection.
+    pub fn remove(&mut self, index: usize) -> Connection {
+        self.manager.remove(index)
+    }
+
+    /// Changes the color of a stored connection.
+    pub fn set_color(&mut self, index: usize, color: Color) {
+        if let Some(connection) = self.manager.connections.get_mut(index) {
+            connection.color = color;
         }
     }
 
     /// Removes a connection from permanent storage and makes it the focused connection for editing.
     pub fn take_out_connection(&mut self, index: usize) {
         let connection = self.manager.remove(index);
-        self.focused_connection = Some(connection);
+        self.focused_connection = Some(connection.into());
+    }
+
+    /// Inserts a waypoint into the focused connection right after the selected one
+    /// (or at the end if none is selected) and selects it.
+    pub fn insert_waypoint(&mut self, point: Point) {
+        if let Some(connection) = self.focused_connection.as_mut() {
+            let index = match self.selected_waypoint {
+                Some(selected) => (selected + 1).min(connection.waypoints.len()),
+                None => connection.waypoints.len(),
+            };
+            connection.waypoints.insert(index, point);
+            self.selected_waypoint = Some(index);
+        }
+    }
+
+    /// Removes the selected waypoint; the previous one (if any) becomes selected.
+    pub fn remove_selected_waypoint(&mut self) {
+        if let Some(connection) = self.focused_connection.as_mut()
+            && let Some(selected) = self.selected_waypoint
+            && selected < connection.waypoints.len()
+        {
+            connection.waypoints.remove(selected);
+            self.selected_waypoint = if connection.waypoints.is_empty() {
+                None
+            } else {
+                Some(selected.saturating_sub(1))
+            };
+        }
+    }
+
+    /// Selects the next waypoint of the focused connection, wrapping around.
+    pub fn select_next_waypoint(&mut self) {
+        if let Some(connection) = &self.focused_connection
+            && !connection.waypoints.is_empty()
+        {
+            self.selected_waypoint = Some(match self.selected_waypoint {
+                Some(selected) => (selected + 1) % connection.waypoints.len(),
+                None => 0,
+            });
+        }
+    }
+
+    /// Moves the selected waypoint.
+    pub fn move_selected_waypoint(&mut self, dx: isize, dy: isize) {
+        if let Some(connection) = self.focused_connection.as_mut()
+            && let Some(selected) = self.selected_waypoint
+            && let Some(waypoint) = connection.waypoints.get_mut(selected)
+        {
+            waypoint.x += dx;
+            waypoint.y += dy;
+        }
     }
 
     /// Gets all connection INDICES (within a vector of connections) for a given note
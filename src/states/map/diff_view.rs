@@ -0,0 +1,12 @@
+use crate::states::MapState;
+
+/// Differences between the open map and another version of it, highlighted on a
+/// read-only canvas shown in place of the map.
+#[derive(PartialEq, Debug)]
+pub struct DiffView {
+    /// The other version's file or snapshot name
+    pub name: String,
+    /// The open map plus the notes and connections only the other version has, with
+    /// the differences in its `ui_state.map_changes`
+    pub map_state: Box<MapState>,
+}
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Represents the application's current input mode, similar to Vim.
@@ -7,16 +8,49 @@ pub enum Mode {
     Visual,
     VisualMove,
     VisualConnect,
+    /// Editing the bend points of the focused connection
+    VisualWaypoint,
+    /// Growing a selection rectangle; every note it touches joins the selection
+    VisualBox,
     Edit,
     EditNormal,
     EditInsert,
     Delete,
+    /// Editing a background region; no note is selected
+    Region,
+}
+
+/// Alignment of the selected notes (`a` then a key in Visual mode).
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Align {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Even gaps between the notes, from left to right, keeping the outermost ones in place
+    DistributeHorizontally,
+    /// Even gaps between the notes, from top to bottom, keeping the outermost ones in place
+    DistributeVertically,
+}
+
+/// Where the selected notes go in the stacking order of overlapping notes (`]`, `[`,
+/// `}` and `{` in Visual mode).
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Restack {
+    /// In front of every other note
+    Front,
+    /// Behind every other note
+    Back,
+    /// In front of the note just above them
+    Up,
+    /// Behind the note just below them
+    Down,
 }
 
 /// Represents which side of a note a connection is attached to.
 ///
 /// Used to specify the connection point on both the source and target notes.
-#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, Debug)]
 pub enum Side {
     Top,
     Bottom,
@@ -32,12 +66,127 @@ pub enum Notification {
     BackupSuccess,
     BackupFail,
     BackupRecordFail,
+    PasteSuccess,
+    PasteFail,
+    /// Line of the pasted text with an arrow missing a note on either side
+    PasteInvalidLine(usize),
+    /// No clipboard to paste from, e.g. without a display server
+    ClipboardUnavailable,
+    MilestoneSuccess,
+    MilestoneFail,
+    NoPath,
+    NoOrphans,
+    /// `zf` / `:fit` on a map too large for the screen, which shows its top left part
+    MapLargerThanScreen,
+    ExportSuccess,
+    ExportFail,
+    /// `:export selection|branch` with no note selected
+    ExportNothingSelected,
+    ImportSuccess,
+    ImportFail,
+    /// Number of notes added from a note list
+    NotesImported(usize),
+    NoteListFail,
+    /// Line of the CSV row that isn't a note
+    NoteListInvalidRow(usize),
+    /// Number of notes with the status filtered by
+    Filtered(usize),
+    FilterCleared,
+    /// A move, edit or delete of a locked note
+    NoteLocked,
+    UnknownCommand,
+    MapReloaded,
+    MergeSuccess,
+    MapEncrypted,
+    MapDecrypted,
+    PassphraseMismatch,
+    MilestoneEncrypted,
+    BackupRestored,
+    SnapshotSuccess,
+    SnapshotFail,
+    SnapshotLoaded,
+    DiffFail,
+    NoDifferences,
+    /// Number of replacements made by `:%s`
+    Replaced(usize),
+    PatternNotFound,
+    InvalidPattern,
+    Undone,
+    NothingToUndo,
+    /// The active layer is hidden or locked
+    LayerLocked,
+    /// Confirming a connection that another one already draws, see
+    /// `Settings::parallel_connections`
+    DuplicateConnection,
+    /// The map wasn't opened from a workspace
+    NoWorkspace,
+    /// The chosen map of the workspace couldn't be opened
+    MapSwitchFail,
+    /// The map opened doesn't match the checksum saved with it
+    ChecksumMismatch,
+    /// Ids in the map loaded that didn't hold together were repaired, with how many
+    MapRepaired(usize),
+    /// A note was taken out of the trash, with the number of connections restored
+    NoteRestored(usize),
+    TrashEmptied,
+    /// Connections deleted from the connection table, with how many
+    ConnectionsDeleted(usize),
+    /// Fan-out connection made from the focused note, with how many notes it leads to
+    FannedOut(usize),
+    /// Fan-outs removed from the focused note, with how many
+    FanOutsRemoved(usize),
+    /// Fanning out with only one note selected, which has no fan-outs to remove
+    NoFanOutTargets,
+    /// `F7` in Edit mode without a spell checking dictionary chosen in the settings
+    SpellCheckOff,
+    /// The spell checking dictionary chosen in the settings couldn't be loaded
+    DictionaryMissing,
+    /// A misspelled word replaced with a suggestion, with its number
+    SpellingSuggestion(usize),
+    /// A misspelled word put back as typed after the last suggestion
+    SpellingRestored,
+    /// `F7` on a word that's spelled right, or that nothing close to was found for
+    NoSpellingSuggestions,
+    /// `:plugin <function>` ran the function of the plugin scripts
+    PluginRan,
+    /// No plugin script defines the function given to `:plugin`
+    PluginFunctionMissing,
+    /// A plugin script didn't compile or failed while running; see `errors.log` in the
+    /// plugins directory
+    PluginFailed,
+    /// `:run` with a name no user command in the settings has
+    UnknownUserCommand,
+    /// A user command taking the selected note's content run without a selected note
+    UserCommandNoNote,
+    /// A user command's program ran successfully
+    UserCommandDone,
+    /// A user command's program couldn't be started or exited with an error
+    UserCommandFailed,
+    /// Unsaved changes from a crash put back into the map opened
+    CrashChangesRestored,
+    /// The unsaved changes from a crash couldn't be read
+    CrashChangesRestoreFail,
 }
 
 /// Tracks the user's intended destination when discarding unsaved changes.
 /// Used to route correctly after the discard confirmation.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Clone, Copy, Debug)]
 pub enum DiscardMenuType {
     Start,
     Settings,
 }
+
+/// What the buttons of the map screen's dialogs do.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum MapDialogAction {
+    /// Closes the dialog, leaving Delete mode for Visual mode
+    Cancel,
+    /// Leaves for another screen without saving
+    Discard(DiscardMenuType),
+    /// Deletes the selected notes
+    DeleteNotes,
+    /// Deletes the selected notes, re-attaching their connections to placeholder notes
+    DeleteKeepingConnections,
+    /// Deletes the notes in the trash for good
+    EmptyTrash,
+}
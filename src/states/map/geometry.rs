@@ -1,8 +1,70 @@
+use ratatui::layout::Rect;
+
+use crate::utils::Point;
+
+/// A position on the infinite canvas, in the same units as note and region positions.
+///
+/// Canvas and screen positions are different types so one can't be passed where the
+/// other is expected; [`ViewportState::to_screen`](super::ViewportState::to_screen) and
+/// [`ViewportState::to_canvas`](super::ViewportState::to_canvas) convert between them.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct CanvasPos {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl CanvasPos {
+    pub fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+}
+
+/// Note and region positions are stored as canvas coordinates.
+impl From<(isize, isize)> for CanvasPos {
+    fn from((x, y): (isize, isize)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+/// Connection path points are canvas coordinates.
+impl From<Point> for CanvasPos {
+    fn from(point: Point) -> Self {
+        Self::new(point.x, point.y)
+    }
+}
+
+/// A position in terminal cells relative to the top left of the frame.
+///
+/// May lie outside the frame (negative, or past its right or bottom edge) while a
+/// partly visible note or connection is being drawn; [`ScreenPos::cell_in`] gives the
+/// buffer cell only for positions that can actually be drawn.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ScreenPos {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl ScreenPos {
+    pub fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    /// The buffer cell at this position, or None if it's outside `area`.
+    pub fn cell_in(self, area: Rect) -> Option<(u16, u16)> {
+        let inside = self.x >= area.left() as isize
+            && self.x < area.right() as isize
+            && self.y >= area.top() as isize
+            && self.y < area.bottom() as isize;
+        inside.then_some((self.x as u16, self.y as u16))
+    }
+}
+
 /// A rectangle representation that uses signed integers (`isize`) for its coordinates.
 ///
 /// This is crucial for performing screen-space calculations where coordinates can
 /// temporarily become negative (e.g., a note is partially off-screen to the left)
 /// before being clipped to the viewport boundaries.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct SignedRect {
     pub x: isize,
     pub y: isize,
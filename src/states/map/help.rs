@@ -0,0 +1,317 @@
+use ratatui::style::Color;
+
+use super::Mode;
+use crate::utils::{Locale, tr};
+
+/// A key binding of the map screen as listed in the help.
+#[derive(PartialEq, Debug)]
+pub struct Binding {
+    /// Keys as written in the help; `Shift`, `Arrows`, `Backspace` and `Characters` are
+    /// translated, see [`key_label`]
+    pub keys: &'static str,
+    /// Catalog key of what the keys do; may span several lines
+    pub action: &'static str,
+}
+
+/// Part of a help page.
+#[derive(PartialEq, Debug)]
+pub enum HelpBlock {
+    /// Lines of a catalog entry, shown as they are
+    Text(&'static str),
+    /// A heading (catalog key) over a list of key bindings
+    Bindings(&'static str, &'static [Binding]),
+}
+
+/// A page of the map screen's help.
+#[derive(PartialEq, Debug)]
+pub struct HelpPage {
+    /// Catalog key of the title
+    pub title: &'static str,
+    pub color: Color,
+    /// Modes whose help this page is, opened by F1 / ? in them
+    pub modes: &'static [Mode],
+    /// Blocks shown side by side, each column top to bottom
+    pub columns: &'static [&'static [HelpBlock]],
+}
+
+const fn bind(keys: &'static str, action: &'static str) -> Binding {
+    Binding { keys, action }
+}
+
+/// The help pages, generated from this list of every key binding of the map screen.
+/// A binding added to the input handlers is added here, with its action text in every
+/// locale, so the help can't drift apart from the keys (see `help_tests`).
+pub const HELP_PAGES: &[HelpPage] = &[
+    HelpPage {
+        title: "help.page1.title",
+        color: Color::White,
+        modes: &[],
+        columns: &[&[HelpBlock::Text("help.page1")]],
+    },
+    HelpPage {
+        title: "help.page2.title",
+        color: Color::White,
+        modes: &[Mode::Normal, Mode::Region],
+        columns: &[&[
+            HelpBlock::Bindings(
+                "help.section.general",
+                &[
+                    bind("F1 / ?", "help.action.toggle_help"),
+                    bind("q", "help.action.quit"),
+                    bind("s", "help.action.save"),
+                    bind("o", "help.action.settings"),
+                    bind("W", "help.action.workspace"),
+                    bind("M", "help.action.milestone_save"),
+                    bind("O", "help.action.milestone_open"),
+                    bind("b", "help.action.backups"),
+                    bind("i", "help.action.stats"),
+                    bind("n", "help.action.next_orphan"),
+                    bind("/", "help.action.search"),
+                    bind(":", "help.action.command_line"),
+                ],
+            ),
+            HelpBlock::Bindings(
+                "help.section.viewport",
+                &[
+                    bind("h j k l / Arrows", "help.action.viewport_1"),
+                    bind("H J K L / Shift+Arrows", "help.action.viewport_5"),
+                    bind("zf", "help.action.fit"),
+                    bind("gg", "help.action.go_to_origin"),
+                    bind("G", "help.action.go_to_end"),
+                ],
+            ),
+            HelpBlock::Bindings(
+                "help.section.notes",
+                &[
+                    bind("a", "help.action.add_note"),
+                    bind("v", "help.action.select_note"),
+                    bind("p", "help.action.paste"),
+                    bind(".", "help.action.repeat_normal"),
+                    bind("u", "help.action.undo_replace"),
+                ],
+            ),
+            HelpBlock::Bindings(
+                "help.section.regions",
+                &[
+                    bind("R", "help.action.add_region"),
+                    bind("r", "help.action.edit_region"),
+                ],
+            ),
+            HelpBlock::Text("help.text.groups"),
+            HelpBlock::Bindings("help.section.layers", &[bind("y", "help.action.layers")]),
+        ]],
+    },
+    HelpPage {
+        title: "help.page3.title",
+        color: Color::Yellow,
+        modes: &[Mode::Visual, Mode::VisualBox, Mode::Delete],
+        columns: &[&[
+            HelpBlock::Bindings(
+                "help.section.general",
+                &[
+                    bind("Esc", "help.action.to_normal"),
+                    bind("i", "help.action.to_edit"),
+                    bind("m", "help.action.to_move"),
+                    bind("c", "help.action.to_connect"),
+                    bind("C", "help.action.new_connection"),
+                    bind("F", "help.action.fan_out"),
+                    bind("d", "help.action.delete_note"),
+                    bind("e", "help.action.note_color"),
+                    bind("X", "help.action.encrypt"),
+                    bind(".", "help.action.repeat_visual"),
+                    bind("P", "help.action.path"),
+                    bind("zz", "help.action.center_note"),
+                    bind("b", "help.action.box_select"),
+                    bind("g", "help.action.group"),
+                    bind("Y", "help.action.to_layer"),
+                    bind("a", "help.action.align"),
+                    bind("t", "help.action.title"),
+                    bind("s", "help.action.status"),
+                    bind("L", "help.action.lock"),
+                    bind("] / [", "help.action.restack_step"),
+                    bind("} / {", "help.action.restack_end"),
+                    bind(":", "help.action.command_line_visual"),
+                ],
+            ),
+            HelpBlock::Bindings(
+                "help.section.focus",
+                &[bind("h j k l / Arrows", "help.action.focus")],
+            ),
+            HelpBlock::Text("help.text.focus"),
+        ]],
+    },
+    HelpPage {
+        title: "help.page4.title",
+        color: Color::Yellow,
+        modes: &[Mode::VisualMove, Mode::VisualConnect, Mode::VisualWaypoint],
+        columns: &[
+            &[HelpBlock::Bindings(
+                "help.section.visual_move",
+                &[
+                    bind("m", "help.action.to_visual"),
+                    bind("Esc", "help.action.to_normal"),
+                    bind("h j k l / Arrows", "help.action.move_note_1"),
+                    bind("H J K L / Shift+Arrows", "help.action.move_note_5"),
+                ],
+            )],
+            &[
+                HelpBlock::Bindings(
+                    "help.section.visual_connect",
+                    &[
+                        bind("c", "help.action.to_visual"),
+                        bind("r", "help.action.rotate_side"),
+                        bind("n", "help.action.next_connection"),
+                        bind("d", "help.action.delete_connection"),
+                        bind("e", "help.action.connection_color"),
+                        bind("+ / -", "help.action.weight"),
+                        bind("w", "help.action.waypoints"),
+                    ],
+                ),
+                HelpBlock::Bindings(
+                    "help.section.target",
+                    &[
+                        bind("h j k l / Arrows", "help.action.focus"),
+                        bind("/", "help.action.search_target"),
+                    ],
+                ),
+            ],
+        ],
+    },
+    HelpPage {
+        title: "help.page5.title",
+        color: Color::Blue,
+        modes: &[Mode::Edit, Mode::EditNormal, Mode::EditInsert],
+        columns: &[&[
+            HelpBlock::Text("help.text.edit"),
+            HelpBlock::Bindings(
+                "help.section.edit",
+                &[
+                    bind(
+                        "Characters / Enter / Backspace / Arrows",
+                        "help.action.type",
+                    ),
+                    bind("Tab", "help.action.toggle_checkbox"),
+                    bind("F7", "help.action.spelling"),
+                    bind("Ctrl+F", "help.action.note_search"),
+                    bind("Esc", "help.action.exit_edit"),
+                ],
+            ),
+            HelpBlock::Bindings(
+                "help.section.edit_normal",
+                &[
+                    bind("h j k l", "help.action.cursor"),
+                    bind("g", "help.action.cursor_start"),
+                    bind("G", "help.action.cursor_end"),
+                    bind("w", "help.action.next_word"),
+                    bind("b", "help.action.previous_word"),
+                    bind("i", "help.action.insert"),
+                    bind("a", "help.action.append"),
+                    bind("x", "help.action.delete_char"),
+                    bind("Tab", "help.action.toggle_checkbox"),
+                    bind("F7", "help.action.spelling"),
+                    bind("/", "help.action.note_search_normal"),
+                    bind("Esc", "help.action.exit_edit"),
+                ],
+            ),
+            HelpBlock::Bindings(
+                "help.section.edit_insert",
+                &[
+                    bind(
+                        "Characters / Enter / Backspace / Arrows",
+                        "help.action.type",
+                    ),
+                    bind("Tab", "help.action.toggle_checkbox"),
+                    bind("F7", "help.action.spelling"),
+                    bind("Ctrl+F", "help.action.note_search"),
+                    bind("Esc", "help.action.to_edit_normal"),
+                ],
+            ),
+        ]],
+    },
+];
+
+/// Words of [`Binding::keys`] that are translated
+const KEY_WORDS: &[(&str, &str)] = &[
+    ("Shift", "help.key.shift"),
+    ("Arrows", "help.key.arrows"),
+    ("Backspace", "help.key.backspace"),
+    ("Characters", "help.key.characters"),
+];
+
+/// The keys of a binding in the locale's words.
+pub fn key_label(locale: Locale, keys: &str) -> String {
+    KEY_WORDS
+        .iter()
+        .fold(keys.to_string(), |label, (word, key)| {
+            label.replace(word, tr(locale, key))
+        })
+}
+
+/// The help page (numbered from 1) with the bindings of a mode.
+pub fn help_page_for_mode(mode: Mode) -> usize {
+    HELP_PAGES
+        .iter()
+        .position(|page| page.modes.contains(&mode))
+        .map_or(1, |index| index + 1)
+}
+
+/// A key binding found by [`search_help`].
+#[derive(PartialEq, Clone, Debug)]
+pub struct HelpMatch {
+    /// Page the binding is on, numbered from 1
+    pub page: usize,
+    /// Heading of its section
+    pub section: &'static str,
+    pub keys: String,
+    pub action: &'static str,
+}
+
+/// Key bindings whose keys or action contain the query, ignoring case, in the order
+/// of the pages. A binding listed on several pages is found on each.
+pub fn search_help(locale: Locale, query: &str) -> Vec<HelpMatch> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return vec![];
+    }
+
+    let mut matches = vec![];
+    for (index, page) in HELP_PAGES.iter().enumerate() {
+        for block in page.columns.iter().flat_map(|column| column.iter()) {
+            let HelpBlock::Bindings(heading, bindings) = block else {
+                continue;
+            };
+            for binding in *bindings {
+                let keys = key_label(locale, binding.keys);
+                let action = tr(locale, binding.action);
+                if keys.to_lowercase().contains(&query) || action.to_lowercase().contains(&query) {
+                    matches.push(HelpMatch {
+                        page: index + 1,
+                        section: tr(locale, heading),
+                        keys,
+                        action,
+                    });
+                }
+            }
+        }
+    }
+    matches
+}
+
+/// Every catalog key the help pages use, to check that each locale has them.
+pub fn help_catalog_keys() -> Vec<&'static str> {
+    let mut keys = vec![];
+    for page in HELP_PAGES {
+        keys.push(page.title);
+        for block in page.columns.iter().flat_map(|column| column.iter()) {
+            match block {
+                HelpBlock::Text(key) => keys.push(key),
+                HelpBlock::Bindings(heading, bindings) => {
+                    keys.push(heading);
+                    keys.extend(bindings.iter().map(|binding| binding.action));
+                }
+            }
+        }
+    }
+    keys.extend(KEY_WORDS.iter().map(|(_, key)| *key));
+    keys
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// Most undo steps kept; the oldest is dropped beyond this.
+const MAX_UNDO_STEPS: usize = 100;
+
+/// A change that `u` in Normal mode can take back.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum UndoStep {
+    /// Note contents from before a search-and-replace, by note id
+    NoteContents(Vec<(usize, String)>),
+}
+
+/// Undo steps of the session, most recent last.
+#[derive(PartialEq, Debug)]
+pub struct UndoHistory {
+    steps: Vec<UndoStep>,
+}
+
+impl Default for UndoHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UndoHistory {
+    pub fn new() -> Self {
+        Self { steps: vec![] }
+    }
+
+    /// For loading the steps kept from an earlier session, oldest first.
+    pub fn from_steps(mut steps: Vec<UndoStep>) -> Self {
+        let excess = steps.len().saturating_sub(MAX_UNDO_STEPS);
+        steps.drain(..excess);
+        Self { steps }
+    }
+
+    /// For saving, oldest first.
+    pub fn steps(&self) -> &[UndoStep] {
+        &self.steps
+    }
+
+    pub fn push(&mut self, step: UndoStep) {
+        if self.steps.len() == MAX_UNDO_STEPS {
+            self.steps.remove(0);
+        }
+        self.steps.push(step);
+    }
+
+    pub fn pop(&mut self) -> Option<UndoStep> {
+        self.steps.pop()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Forgets every step, e.g. when the whole map is replaced.
+    pub fn clear(&mut self) {
+        self.steps.clear();
+    }
+}
@@ -0,0 +1,76 @@
+use ratatui::style::Color;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::states::map::Side;
+
+/// A connection fanning out from one note to several others, drawn as a trunk that
+/// splits into a branch for each target.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
+pub struct Hyperedge {
+    pub from_id: usize,
+    pub from_side: Side,
+    /// Notes the branches lead to; each is entered through the side facing the trunk
+    pub to_ids: Vec<usize>,
+    /// Custom serde implementation in utils handles Color serialization
+    #[serde(with = "crate::utils")]
+    #[schemars(with = "String")]
+    pub color: Color,
+    /// Index of the fan-out's layer; None means the first (default) layer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<usize>,
+}
+
+impl Hyperedge {
+    /// Index of the fan-out's layer.
+    pub fn layer(&self) -> usize {
+        self.layer.unwrap_or(0)
+    }
+
+    /// Whether the note is the fan-out's source or one of its targets.
+    pub fn touches(&self, id: usize) -> bool {
+        self.from_id == id || self.to_ids.contains(&id)
+    }
+}
+
+/// Fan-out connections of the map, kept apart from the one-to-one connections.
+#[derive(PartialEq, Debug, Default)]
+pub struct HyperedgesState {
+    hyperedges: Vec<Hyperedge>,
+}
+
+impl HyperedgesState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// For deserialization
+    pub fn from_hyperedges(hyperedges: Vec<Hyperedge>) -> Self {
+        Self { hyperedges }
+    }
+
+    /// For serialization
+    pub fn hyperedges(&self) -> &[Hyperedge] {
+        &self.hyperedges
+    }
+
+    pub fn add(&mut self, hyperedge: Hyperedge) {
+        self.hyperedges.push(hyperedge);
+    }
+
+    /// Removes the fan-outs starting at the note, returns how many there were.
+    pub fn remove_from(&mut self, id: usize) -> usize {
+        let count = self.hyperedges.len();
+        self.hyperedges.retain(|hyperedge| hyperedge.from_id != id);
+        count - self.hyperedges.len()
+    }
+
+    /// Takes a deleted note out of every fan-out; fan-outs starting at it or left
+    /// without targets are removed.
+    pub fn remove_note(&mut self, id: usize) {
+        self.hyperedges.retain_mut(|hyperedge| {
+            hyperedge.to_ids.retain(|&to_id| to_id != id);
+            hyperedge.from_id != id && !hyperedge.to_ids.is_empty()
+        });
+    }
+}
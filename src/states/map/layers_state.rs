@@ -0,0 +1,146 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Name of the layer every map starts with; notes and connections without a layer are on it
+pub const DEFAULT_LAYER_NAME: &str = "default";
+
+/// A named layer of notes and connections that can be hidden or locked as a whole.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
+pub struct Layer {
+    pub name: String,
+    /// Hidden layers aren't drawn and their notes can't be selected
+    #[serde(default)]
+    pub hidden: bool,
+    /// Locked layers are drawn, but their notes can't be selected (so not moved,
+    /// edited or deleted)
+    #[serde(default)]
+    pub locked: bool,
+}
+
+impl Layer {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            hidden: false,
+            locked: false,
+        }
+    }
+}
+
+/// The layer list shown over the map (`y` in Normal mode).
+#[derive(PartialEq, Debug, Default)]
+pub struct LayerPanel {
+    /// Index of the highlighted layer
+    pub selected: usize,
+    /// True while the highlighted layer's name is being typed
+    pub editing_name: bool,
+}
+
+/// The map's layers and the one new notes and connections are put on.
+///
+/// Notes and connections refer to layers by index; layers can be added and renamed but
+/// not removed, so the indices stay valid.
+#[derive(PartialEq, Debug)]
+pub struct LayersState {
+    layers: Vec<Layer>,
+    /// Index of the layer new notes and connections are put on
+    pub active: usize,
+    /// Intercepts all input while shown
+    pub panel: Option<LayerPanel>,
+}
+
+impl Default for LayersState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LayersState {
+    pub fn new() -> Self {
+        Self::from_layers(vec![])
+    }
+
+    /// Layers as loaded from a map file; maps saved without layers get the default one.
+    pub fn from_layers(mut layers: Vec<Layer>) -> Self {
+        if layers.is_empty() {
+            layers.push(Layer::new(DEFAULT_LAYER_NAME.to_string()));
+        }
+        Self {
+            layers,
+            active: 0,
+            panel: None,
+        }
+    }
+
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Layers to write to the map file: none while only the untouched default layer
+    /// exists, so maps that don't use layers are saved unchanged.
+    pub fn layers_for_saving(&self) -> Vec<Layer> {
+        if self.layers == [Layer::new(DEFAULT_LAYER_NAME.to_string())] {
+            vec![]
+        } else {
+            self.layers.clone()
+        }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Layer> {
+        self.layers.get_mut(index)
+    }
+
+    /// Adds a layer and returns its index.
+    pub fn add(&mut self, name: String) -> usize {
+        self.layers.push(Layer::new(name));
+        self.layers.len() - 1
+    }
+
+    /// True if the layer isn't drawn. Unknown layers are treated as visible.
+    pub fn is_hidden(&self, index: usize) -> bool {
+        self.layers.get(index).is_some_and(|layer| layer.hidden)
+    }
+
+    /// True if notes on the layer can't be selected: it's hidden or locked.
+    pub fn is_frozen(&self, index: usize) -> bool {
+        self.layers
+            .get(index)
+            .is_some_and(|layer| layer.hidden || layer.locked)
+    }
+
+    pub fn toggle_hidden(&mut self, index: usize) {
+        if let Some(layer) = self.layers.get_mut(index) {
+            layer.hidden = !layer.hidden;
+        }
+    }
+
+    pub fn toggle_locked(&mut self, index: usize) {
+        if let Some(layer) = self.layers.get_mut(index) {
+            layer.locked = !layer.locked;
+        }
+    }
+
+    /// Shows the layer panel with the active layer highlighted.
+    pub fn open_panel(&mut self) {
+        self.panel = Some(LayerPanel {
+            selected: self.active,
+            editing_name: false,
+        });
+    }
+
+    /// Moves the panel's highlight down, stopping at the last layer.
+    pub fn select_next(&mut self) {
+        let count = self.layers.len();
+        if let Some(panel) = &mut self.panel
+            && panel.selected + 1 < count
+        {
+            panel.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if let Some(panel) = &mut self.panel {
+            panel.selected = panel.selected.saturating_sub(1);
+        }
+    }
+}
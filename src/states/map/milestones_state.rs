@@ -0,0 +1,57 @@
+use crate::{states::MapState, utils::Milestone};
+
+/// List of a map's milestones shown for picking one to open.
+#[derive(PartialEq, Debug)]
+pub struct MilestonePicker {
+    pub milestones: Vec<Milestone>,
+    /// Index of the highlighted milestone
+    pub selected: usize,
+}
+
+impl MilestonePicker {
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.milestones.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+/// A milestone opened read-only in the second tab.
+#[derive(PartialEq, Debug)]
+pub struct OpenMilestone {
+    pub name: String,
+    /// The snapshot, loaded into its own map state so it can be viewed and scrolled
+    pub map_state: Box<MapState>,
+}
+
+/// Milestone prompts and the milestone tab of the map screen.
+#[derive(PartialEq, Debug, Default)]
+pub struct MilestonesState {
+    /// Name being typed for a new milestone; intercepts all input while shown
+    pub name_prompt: Option<String>,
+    /// Intercepts all input while shown
+    pub picker: Option<MilestonePicker>,
+    pub open: Option<OpenMilestone>,
+    /// True while the milestone tab is shown instead of the live map
+    pub showing_milestone: bool,
+}
+
+impl MilestonesState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Switches between the live map and the open milestone, if there is one.
+    pub fn switch_tab(&mut self) {
+        self.showing_milestone = self.open.is_some() && !self.showing_milestone;
+    }
+
+    pub fn close(&mut self) {
+        self.open = None;
+        self.showing_milestone = false;
+    }
+}
@@ -1,21 +1,61 @@
+mod box_select;
+mod commands;
+mod connection_table;
 mod connections_state;
+mod diff_view;
 mod enums;
 mod geometry;
+mod help;
+mod history;
+mod hyperedges_state;
+mod layers_state;
+mod milestones_state;
 mod note;
 mod notes_state;
+mod notifications;
+mod path_cache;
 mod persistence;
+mod regions_state;
+mod repeat;
+mod search;
+mod snapshots_state;
+mod spelling_state;
 mod state;
 #[cfg(test)]
 mod tests;
+mod trash_state;
+mod tutorial;
 mod ui_state;
+mod vault;
 mod viewport;
+mod workspace_picker;
 
+pub use box_select::*;
+pub use commands::*;
+pub use connection_table::*;
 pub use connections_state::*;
+pub use diff_view::*;
 pub use enums::*;
 pub use geometry::*;
+pub use help::*;
+pub use history::*;
+pub use hyperedges_state::*;
+pub use layers_state::*;
+pub use milestones_state::*;
 pub use note::*;
 pub use notes_state::*;
+pub use notifications::*;
+pub use path_cache::*;
 pub use persistence::*;
+pub use regions_state::*;
+pub use repeat::*;
+pub use search::*;
+pub use snapshots_state::*;
+pub use spelling_state::*;
 pub use state::*;
+pub use trash_state::*;
+pub use tutorial::*;
 pub use ui_state::*;
+pub use vault::*;
 pub use viewport::*;
+pub use workspace_picker::*;
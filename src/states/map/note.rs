@@ -1,70 +1,186 @@
 use super::enums::Side;
+use crate::utils::EncryptedText;
 use ratatui::style::Color;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use unicode_width::UnicodeWidthStr;
+use uuid::Uuid;
 
 /// A node on the mind map canvas with position, content, and visual styling.
 ///
 /// Notes are the fundamental building blocks of the mind map. Each note occupies
 /// a position on an infinite 2D plane and can be connected to other notes.
-#[derive(PartialEq, Serialize, Deserialize, Clone, Debug)]
+#[derive(PartialEq, Serialize, Deserialize, JsonSchema, Clone, Debug)]
 pub struct Note {
-    pub x: usize,
-    pub y: usize,
+    pub x: isize,
+    pub y: isize,
     pub content: String,
+    /// Shown in bold on the top border and used instead of the first line of `content`
+    /// to name the note. Not encrypted along with the content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
     /// Custom serialization needed to convert between ratatui's Color and a persistable format
     #[serde(with = "crate::utils")]
+    #[schemars(with = "String")]
     pub color: Color,
+    /// Set for notes encrypted with the session passphrase.
+    ///
+    /// While such a note is locked, `content` is empty and this holds the only copy of
+    /// the text. Once unlocked, `content` holds the plaintext and this is refreshed on save.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted: Option<EncryptedText>,
+    /// Index of the note's layer; None means the first (default) layer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<usize>,
+    /// Progress of the task the note stands for, shown as a badge on its border
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<NoteStatus>,
+    /// Locked notes can't be moved, edited or deleted until they are unlocked
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
+    /// Identifies the note across maps and to other tools, unlike its id which is only
+    /// unique within the map. Set when note UUIDs are enabled in the settings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
 }
 
 impl Note {
-    pub fn new(x: usize, y: usize, content: String, color: Color) -> Note {
+    pub fn new(x: isize, y: isize, content: String, color: Color) -> Note {
         Note {
             x,
             y,
             content,
+            title: None,
             color,
+            encrypted: None,
+            layer: None,
+            status: None,
+            locked: false,
+            uuid: None,
         }
     }
 
+    /// Moves the note to the next status, from none through each [`NoteStatus`] back to
+    /// none.
+    pub fn cycle_status(&mut self) {
+        self.status = match self.status {
+            None => Some(NoteStatus::Todo),
+            Some(NoteStatus::Todo) => Some(NoteStatus::Doing),
+            Some(NoteStatus::Doing) => Some(NoteStatus::Done),
+            Some(NoteStatus::Done) => Some(NoteStatus::Blocked),
+            Some(NoteStatus::Blocked) => None,
+        };
+    }
+
+    /// Index of the note's layer.
+    pub fn layer(&self) -> usize {
+        self.layer.unwrap_or(0)
+    }
+
+    /// Puts the note on a layer; the first layer is stored as None, so maps that don't
+    /// use layers are saved unchanged.
+    pub fn set_layer(&mut self, index: usize) {
+        self.layer = (index != 0).then_some(index);
+    }
+
+    /// Sets the note's title; a blank one removes it.
+    pub fn set_title(&mut self, title: &str) {
+        let title = title.trim();
+        self.title = (!title.is_empty()).then(|| title.to_string());
+    }
+
     /// Returns the rendered dimensions (width, height) including 2-cell border padding.
     ///
     /// Height is calculated by counting newlines rather than using `lines()` to
-    /// preserve trailing empty lines that would otherwise be ignored.
+    /// preserve trailing empty lines that would otherwise be ignored. The note is wide
+    /// enough for its title, which is drawn on the border with a space on each side.
     pub fn get_dimensions(&self) -> (u16, u16) {
         let height = (1 + self.content.matches('\n').count()) as u16;
 
+        let title_width = self.title.as_deref().map_or(0, |title| title.width() + 2);
         let width = self
             .content
             .lines()
             .map(|line| line.width())
             .max()
-            .unwrap_or(0) as u16;
+            .unwrap_or(0)
+            .max(title_width) as u16;
 
         enforce_note_dimensions(width, height)
     }
 
+    /// Returns the canvas coordinates of the note's center.
+    pub fn center(&self) -> (isize, isize) {
+        let (note_width, note_height) = self.get_dimensions();
+        (
+            self.x + (note_width / 2) as isize,
+            self.y + (note_height / 2) as isize,
+        )
+    }
+
     /// Returns the canvas coordinates where a connection line should attach to this note.
     ///
     /// The point is centered on the specified side.
-    pub fn get_connection_point(&self, side: Side) -> (usize, usize) {
+    pub fn get_connection_point(&self, side: Side) -> (isize, isize) {
         let (note_width, note_height) = self.get_dimensions();
 
         match side {
             Side::Right => (
-                (self.x + note_width as usize - 1),
-                (self.y + (note_height / 2) as usize),
+                (self.x + note_width as isize - 1),
+                (self.y + (note_height / 2) as isize),
             ),
-            Side::Left => (self.x, (self.y + (note_height / 2) as usize)),
-            Side::Top => (self.x + (note_width / 2) as usize, self.y),
+            Side::Left => (self.x, (self.y + (note_height / 2) as isize)),
+            Side::Top => (self.x + (note_width / 2) as isize, self.y),
             Side::Bottom => (
-                self.x + (note_width / 2) as usize,
-                self.y + note_height as usize - 1,
+                self.x + (note_width / 2) as isize,
+                self.y + note_height as isize - 1,
             ),
         }
     }
 }
 
+/// Status of a note used as a task, independent of its color.
+#[derive(PartialEq, Eq, Clone, Copy, Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteStatus {
+    Todo,
+    Doing,
+    Done,
+    Blocked,
+}
+
+impl NoteStatus {
+    /// Parses the status name used on the command line and in map files.
+    pub fn from_name(name: &str) -> Option<NoteStatus> {
+        match name {
+            "todo" => Some(NoteStatus::Todo),
+            "doing" => Some(NoteStatus::Doing),
+            "done" => Some(NoteStatus::Done),
+            "blocked" => Some(NoteStatus::Blocked),
+            _ => None,
+        }
+    }
+
+    /// Key of the badge text in the locale files.
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            NoteStatus::Todo => "status.todo",
+            NoteStatus::Doing => "status.doing",
+            NoteStatus::Done => "status.done",
+            NoteStatus::Blocked => "status.blocked",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            NoteStatus::Todo => Color::Blue,
+            NoteStatus::Doing => Color::Yellow,
+            NoteStatus::Done => Color::Green,
+            NoteStatus::Blocked => Color::Red,
+        }
+    }
+}
+
 fn enforce_note_dimensions(width: u16, height: u16) -> (u16, u16) {
     let width = (width + 2).max(20) + 1; // borders, min, cursor
     let height = (height + 2).max(4); // borders, min
@@ -1,6 +1,7 @@
-use super::note::Note;
+use super::{Restack, note::Note};
 use ratatui::style::Color;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
 
 #[derive(PartialEq, Debug)]
 pub struct NotesState {
@@ -8,10 +9,18 @@ pub struct NotesState {
     /// Used to generate unique IDs for new notes
     next_note_id_counter: usize,
     selected_note_id: Option<usize>,
+    /// Notes that move, recolor and delete together with the selected note.
+    /// Empty unless several notes were selected at once; includes the selected note otherwise.
+    multi_selection: BTreeSet<usize>,
     /// Z-index ordering for note rendering (back to front)
     render_order: Vec<usize>,
     /// Byte index of the cursor position within the selected note's content
     cursor_pos: usize,
+    /// Lines of the selected note's content scrolled out of its box at the top while
+    /// it's edited, see [`NotesState::scroll_to_cursor`]
+    edit_scroll: usize,
+    /// Gives new notes a UUID, see [`NotesState::use_uuids`]
+    assign_uuids: bool,
 }
 
 impl NotesState {
@@ -20,8 +29,11 @@ impl NotesState {
             notes: HashMap::new(),
             next_note_id_counter: 0,
             selected_note_id: None,
+            multi_selection: BTreeSet::new(),
             render_order: vec![],
             cursor_pos: 0,
+            edit_scroll: 0,
+            assign_uuids: false,
         }
     }
 
@@ -35,8 +47,11 @@ impl NotesState {
             notes,
             next_note_id_counter,
             selected_note_id: None,
+            multi_selection: BTreeSet::new(),
             render_order,
             cursor_pos: 0,
+            edit_scroll: 0,
+            assign_uuids: false,
         }
     }
 
@@ -87,14 +102,44 @@ impl NotesState {
     }
 
     /// Creates a new note, returns its id
-    pub fn add(&mut self, x: usize, y: usize, text: String, color: Color) -> usize {
+    pub fn add(&mut self, x: isize, y: isize, text: String, color: Color) -> usize {
         let id = self.next_note_id_counter;
-        self.notes.insert(id, Note::new(x, y, text, color));
+        let mut note = Note::new(x, y, text, color);
+        if self.assign_uuids {
+            note.uuid = Some(Uuid::new_v4());
+        }
+        self.notes.insert(id, note);
         self.render_order.push(id);
         self.next_note_id_counter += 1;
         id
     }
 
+    /// Gives every note without a UUID a new one, and every note added from now on.
+    /// Returns how many existing notes got one.
+    pub fn use_uuids(&mut self) -> usize {
+        self.assign_uuids = true;
+        let mut assigned = 0;
+        for note in self.notes.values_mut().filter(|note| note.uuid.is_none()) {
+            note.uuid = Some(Uuid::new_v4());
+            assigned += 1;
+        }
+        assigned
+    }
+
+    /// Puts a removed note back under its old id, or under a new one if another note
+    /// has taken it. Returns the id it got.
+    pub fn restore(&mut self, id: usize, note: Note) -> usize {
+        let id = if self.notes.contains_key(&id) {
+            self.next_note_id_counter
+        } else {
+            id
+        };
+        self.notes.insert(id, note);
+        self.render_order.push(id);
+        self.next_note_id_counter = self.next_note_id_counter.max(id + 1);
+        id
+    }
+
     /// Removes a note by ID and updates the render order
     pub fn remove(&mut self, id: usize) {
         self.notes.remove(&id);
@@ -104,6 +149,7 @@ impl NotesState {
         }
 
         self.selected_note_id = None;
+        self.multi_selection.remove(&id);
     }
 
     /// Panics if the note id doesn't exist or is not in the render order
@@ -120,16 +166,52 @@ impl NotesState {
         );
     }
 
+    /// Selecting a note leaves its place in the render order alone, see [`Self::restack`].
+    ///
     /// Panics if the note id doesn't exist or is not in the render order
     pub fn select(&mut self, id: usize) {
         self.expect_id_exists(id);
 
         self.selected_note_id = Some(id);
+        self.edit_scroll = 0;
+    }
 
-        // Bring to front of render order
-        let pos = self.render_order.iter().position(|&x| x == id).unwrap();
-        let item = self.render_order.remove(pos);
-        self.render_order.push(item);
+    /// Moves the selected notes in the render order, keeping their order among
+    /// themselves. Returns false if they were already there.
+    pub fn restack(&mut self, restack: Restack) -> bool {
+        let selection = self.selection();
+        let mut selected: Vec<bool> = self
+            .render_order
+            .iter()
+            .map(|id| selection.contains(id))
+            .collect();
+        let previous = self.render_order.clone();
+
+        match restack {
+            // Stable sorts, so the other notes keep their order too
+            Restack::Front => self.render_order.sort_by_key(|id| selection.contains(id)),
+            Restack::Back => self.render_order.sort_by_key(|id| !selection.contains(id)),
+            // Each selected note swaps places with the unselected note next to it, going
+            // from the side it moves to so a note only moves once
+            Restack::Up => {
+                for index in (0..self.render_order.len().saturating_sub(1)).rev() {
+                    if selected[index] && !selected[index + 1] {
+                        self.render_order.swap(index, index + 1);
+                        selected.swap(index, index + 1);
+                    }
+                }
+            }
+            Restack::Down => {
+                for index in 1..self.render_order.len() {
+                    if selected[index] && !selected[index - 1] {
+                        self.render_order.swap(index, index - 1);
+                        selected.swap(index, index - 1);
+                    }
+                }
+            }
+        }
+
+        self.render_order != previous
     }
 
     /// Panics if no note is selected.
@@ -138,17 +220,65 @@ impl NotesState {
             .selected_note_id
             .take()
             .expect("Bug: deselect() called with no note selected");
+        self.multi_selection.clear();
+    }
+
+    /// Selects several notes at once. The first id becomes the selected note.
+    ///
+    /// Does nothing if `ids` is empty.
+    /// # Panics
+    /// If any of the ids doesn't exist or is not in the render order
+    pub fn select_many(&mut self, ids: &[usize]) {
+        let Some(&first) = ids.first() else {
+            return;
+        };
+
+        self.select(first);
+        self.multi_selection = ids.iter().copied().collect();
+        if self.multi_selection.len() == 1 {
+            self.multi_selection.clear();
+        }
     }
 
-    /// Finds the note closest to the given coordinates
-    pub fn find_closest_note(&self, x: usize, y: usize) -> Option<usize> {
+    pub fn clear_multi_selection(&mut self) {
+        self.multi_selection.clear();
+    }
+
+    pub fn has_multi_selection(&self) -> bool {
+        !self.multi_selection.is_empty()
+    }
+
+    pub fn is_in_multi_selection(&self, id: usize) -> bool {
+        self.multi_selection.contains(&id)
+    }
+
+    /// Ids of every note an operation on the selection should affect:
+    /// the multi-selection if there is one, otherwise just the selected note.
+    pub fn selection(&self) -> Vec<usize> {
+        if self.multi_selection.is_empty() {
+            self.selected_note_id.into_iter().collect()
+        } else {
+            self.multi_selection.iter().copied().collect()
+        }
+    }
+
+    /// Mutable access to any note by id.
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut Note> {
+        self.notes.get_mut(&id)
+    }
+
+    /// Finds the note closest to the given coordinates among those `selectable` returns
+    /// true for
+    pub fn find_closest_note(
+        &self,
+        x: isize,
+        y: isize,
+        selectable: impl Fn(&Note) -> bool,
+    ) -> Option<usize> {
         self.notes()
             .iter()
-            .min_by_key(|(_, note)| {
-                let distance =
-                    (note.x as isize - x as isize).abs() + (note.y as isize - y as isize).abs();
-                distance as usize
-            })
+            .filter(|(_, note)| selectable(note))
+            .min_by_key(|(_, note)| note.x.abs_diff(x) + note.y.abs_diff(y))
             .map(|(id, _)| *id)
     }
 
@@ -163,4 +293,36 @@ impl NotesState {
         let note = self.expect_selected_note();
         self.cursor_pos = pos.min(note.content.len());
     }
+
+    /// First line of the selected note's content to show in a box with room for `rows`
+    /// lines, scrolled from [`Self::edit_scroll`] just as far as needed to show the
+    /// cursor's line with `margin` lines above and below it, where the note has them.
+    /// 0 if no note is selected.
+    pub fn scroll_to_cursor(&self, rows: usize, margin: usize) -> usize {
+        let Some(note) = self.selected_note_id.and_then(|id| self.notes.get(&id)) else {
+            return 0;
+        };
+        let lines = 1 + note.content.matches('\n').count();
+        let before_cursor = note.content.get(..self.cursor_pos).unwrap_or(&note.content);
+        let cursor_line = before_cursor.matches('\n').count();
+        let rows = rows.max(1);
+        let margin = margin.min((rows - 1) / 2);
+
+        self.edit_scroll.min(lines.saturating_sub(rows)).clamp(
+            (cursor_line + margin)
+                .min(lines - 1)
+                .saturating_sub(rows - 1),
+            cursor_line.saturating_sub(margin),
+        )
+    }
+
+    /// Lines of the selected note's content scrolled out of its box at the top while
+    /// it's edited.
+    pub fn edit_scroll(&self) -> usize {
+        self.edit_scroll
+    }
+
+    pub fn set_edit_scroll(&mut self, edit_scroll: usize) {
+        self.edit_scroll = edit_scroll;
+    }
 }
@@ -0,0 +1,79 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use super::Notification;
+
+/// How long a notification stays on screen
+pub const NOTIFICATION_DURATION: Duration = Duration::from_secs(4);
+
+/// Most notifications shown at once; the oldest goes first when another one comes in
+pub const MAX_NOTIFICATIONS: usize = 4;
+
+/// A notification with the time it was shown at.
+#[derive(PartialEq, Debug)]
+pub struct Toast {
+    pub notification: Notification,
+    pub shown_at: Instant,
+}
+
+/// Notifications shown stacked above the bar, oldest first, each until it expires.
+#[derive(PartialEq, Debug, Default)]
+pub struct NotificationQueue {
+    toasts: VecDeque<Toast>,
+}
+
+impl NotificationQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, notification: Notification) {
+        self.push_at(notification, Instant::now());
+    }
+
+    /// Shows a notification as of `now`. A notification that is already shown moves to
+    /// the top of the stack and starts over instead of being shown twice.
+    pub fn push_at(&mut self, notification: Notification, now: Instant) {
+        self.toasts
+            .retain(|toast| toast.notification != notification);
+        if self.toasts.len() == MAX_NOTIFICATIONS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Toast {
+            notification,
+            shown_at: now,
+        });
+    }
+
+    /// Removes the notifications shown for longer than [`NOTIFICATION_DURATION`].
+    /// Returns true if any were removed, so the screen is redrawn without them.
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let count = self.toasts.len();
+        self.toasts
+            .retain(|toast| now.duration_since(toast.shown_at) < NOTIFICATION_DURATION);
+        self.toasts.len() != count
+    }
+
+    /// The notification shown last.
+    pub fn latest(&self) -> Option<&Notification> {
+        self.toasts.back().map(|toast| &toast.notification)
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Toast> {
+        self.toasts.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.toasts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.toasts.clear();
+    }
+}
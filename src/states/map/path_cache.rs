@@ -0,0 +1,110 @@
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use super::{Connection, ConnectionsState, Note, Side};
+use crate::utils::{Point, connection_path};
+
+/// Identifies a connection: its notes and the sides it leaves and enters them through.
+type PathKey = (usize, Side, usize, Side);
+
+/// A connection and how many cells aside it's drawn (see
+/// [`ConnectionsState::parallel_offset`]).
+type LaneKey = (PathKey, isize);
+
+/// A computed path and what it was computed from.
+#[derive(PartialEq, Debug)]
+struct CachedPath {
+    start: (isize, isize),
+    end: (isize, isize),
+    waypoints: Vec<Point>,
+    path: Rc<[Point]>,
+}
+
+/// The last computed path of every drawn connection, so a map that isn't being edited
+/// doesn't route its connections again on every frame.
+///
+/// A path is computed again only once its connection points move (the note was moved or
+/// resized), or its waypoints change. Changing a side makes it a different connection.
+#[derive(PartialEq, Debug, Default)]
+pub struct PathCache {
+    paths: HashMap<LaneKey, CachedPath>,
+}
+
+impl PathCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The path of a connection between the two notes drawn `offset` cells aside,
+    /// computed only if its cached path is out of date.
+    pub fn path(
+        &mut self,
+        connection: &Connection,
+        start_note: &Note,
+        end_note: &Note,
+        offset: isize,
+    ) -> Rc<[Point]> {
+        let key = (path_key(connection), offset);
+        let (_, start_side, _, end_side) = key.0;
+        let start = start_note.get_connection_point(start_side);
+        let end = end_note.get_connection_point(end_side);
+
+        if let Some(cached) = self.paths.get(&key)
+            && cached.start == start
+            && cached.end == end
+            && cached.waypoints == connection.waypoints
+        {
+            return Rc::clone(&cached.path);
+        }
+
+        let path: Rc<[Point]> = connection_path(connection, start_note, end_note, offset).into();
+        self.paths.insert(
+            key,
+            CachedPath {
+                start,
+                end,
+                waypoints: connection.waypoints.clone(),
+                path: Rc::clone(&path),
+            },
+        );
+        path
+    }
+
+    /// Forgets the paths of connections that no longer exist. Only scans the connections
+    /// once there are more paths than connections, so it's cheap to call every frame.
+    pub fn prune(&mut self, connections_state: &ConnectionsState) {
+        let connections = connections_state.connections();
+        if self.paths.len() <= connections.len() {
+            return;
+        }
+        let keys: HashSet<LaneKey> = connections
+            .iter()
+            .map(|connection| {
+                (
+                    path_key(connection),
+                    connections_state.parallel_offset(connection),
+                )
+            })
+            .collect();
+        self.paths.retain(|key, _| keys.contains(key));
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+fn path_key(connection: &Connection) -> PathKey {
+    (
+        connection.from_id,
+        connection.from_side,
+        connection.to_id,
+        connection.to_side,
+    )
+}
@@ -1,9 +1,93 @@
 use std::{
+    fmt,
     path::PathBuf,
-    time::{Duration, Instant},
+    thread::JoinHandle,
+    time::{Duration, Instant, SystemTime},
 };
 
-use crate::utils::get_duration_rt;
+use crate::utils::{FileKey, MapData, MapDiff, MapMerge, TmmprError, get_duration_rt};
+
+/// How often the map file is checked for changes made by other programs
+const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The map file was changed on disk by something else, e.g. a sync tool.
+///
+/// Intercepts all input until the user reloads the file or keeps their version.
+#[derive(PartialEq, Debug)]
+pub struct ExternalChange {
+    /// The map as it is on disk now
+    pub disk_map: MapData,
+    /// Modification time of the file when it was read
+    pub modified: Option<SystemTime>,
+    /// Differences between the open map and the file
+    pub diff: MapDiff,
+    /// True while the differences are listed
+    pub show_diff: bool,
+    /// Merge of the file into the open map, while its conflicts are being resolved
+    pub merge: Option<MapMerge>,
+    /// Index of the highlighted merge conflict
+    pub selected_conflict: usize,
+}
+
+/// What a background save's thread hands back: the result, the data it wrote and the
+/// serialization buffer, to reuse its allocation.
+pub type SaveOutcome = (Result<(), TmmprError>, MapData, Vec<u8>);
+
+/// A save of the map running on a background thread, see
+/// [`crate::utils::save_map_file_in_background`].
+///
+/// Dropping it waits for the thread, so a map file is never left half-written when the
+/// map screen is left or the app quits.
+pub struct BackgroundSave {
+    pub path: PathBuf,
+    /// Show a notification when the save is done, as for saves the user asked for
+    pub notify: bool,
+    handle: Option<JoinHandle<SaveOutcome>>,
+}
+
+impl BackgroundSave {
+    pub fn new(path: PathBuf, notify: bool, handle: JoinHandle<SaveOutcome>) -> Self {
+        Self {
+            path,
+            notify,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle
+            .as_ref()
+            .is_none_or(|handle| handle.is_finished())
+    }
+
+    /// Waits for the thread if it's still writing. Returns None if it panicked.
+    pub fn join(mut self) -> Option<SaveOutcome> {
+        self.handle.take()?.join().ok()
+    }
+}
+
+impl Drop for BackgroundSave {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl PartialEq for BackgroundSave {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path && self.notify == other.notify
+    }
+}
+
+impl fmt::Debug for BackgroundSave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackgroundSave")
+            .field("path", &self.path)
+            .field("notify", &self.notify)
+            .finish_non_exhaustive()
+    }
+}
 
 /// Tracks file persistence, auto-save timing, and backup state for a map.
 #[derive(PartialEq, Debug)]
@@ -16,6 +100,23 @@ pub struct PersistenceState {
     pub has_unsaved_changes: bool,
     pub last_save: Instant,
     pub runtime_backup_timestamp: Instant,
+    /// Modification time of the map file when it was last loaded, saved or kept, to
+    /// notice changes made by other programs
+    pub disk_modified: Option<SystemTime>,
+    /// The map file's contents when it was last loaded or saved: the common base for
+    /// merging in changes made by other programs
+    pub base_map: Option<MapData>,
+    pub last_disk_check: Instant,
+    pub external_change: Option<ExternalChange>,
+    /// Serialized map data of the last save, kept to reuse its allocation
+    pub save_buffer: Vec<u8>,
+    /// Key the map file is encrypted at rest with, None for a plain map file
+    pub file_key: Option<FileKey>,
+    /// Save still being written by a background thread
+    pub background_save: Option<BackgroundSave>,
+    /// Set for maps opened from a damaged file (see [`crate::utils::recover_map_data`]),
+    /// which aren't auto-saved until they're saved with `s`
+    pub recovered: bool,
 }
 
 impl PersistenceState {
@@ -25,6 +126,14 @@ impl PersistenceState {
             has_unsaved_changes: false,
             last_save: Instant::now(),
             runtime_backup_timestamp: Instant::now(),
+            disk_modified: None,
+            base_map: None,
+            last_disk_check: Instant::now(),
+            external_change: None,
+            save_buffer: Vec::new(),
+            file_key: None,
+            background_save: None,
+            recovered: false,
         }
     }
 
@@ -36,9 +145,11 @@ impl PersistenceState {
         self.has_unsaved_changes = false;
     }
 
-    /// Only auto-saves if there are unsaved changes AND the interval has elapsed
+    /// Only auto-saves if there are unsaved changes AND the interval has elapsed, and
+    /// never a recovered map
     pub fn should_save(&self, interval_seconds: usize) -> bool {
         self.has_unsaved_changes
+            && !self.recovered
             && self.last_save.elapsed() > Duration::from_secs(interval_seconds as u64)
     }
 
@@ -56,4 +167,16 @@ impl PersistenceState {
     pub fn reset_backup_timer(&mut self) {
         self.runtime_backup_timestamp = Instant::now();
     }
+
+    /// Checks for external changes at most once per [`DISK_CHECK_INTERVAL`], and not
+    /// while one is waiting for the user or our own save is still being written.
+    pub fn should_check_disk(&self) -> bool {
+        self.external_change.is_none()
+            && self.background_save.is_none()
+            && self.last_disk_check.elapsed() >= DISK_CHECK_INTERVAL
+    }
+
+    pub fn reset_disk_check_timer(&mut self) {
+        self.last_disk_check = Instant::now();
+    }
 }
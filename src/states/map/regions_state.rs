@@ -0,0 +1,316 @@
+use ratatui::style::Color;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Smallest size a region can be resized to, including its border
+pub const MIN_REGION_WIDTH: usize = 10;
+pub const MIN_REGION_HEIGHT: usize = 4;
+
+/// Empty cells kept between arranged notes and between notes and the region border
+const ARRANGE_GAP: usize = 1;
+
+/// Empty cells between a new group's border and the notes it's created around
+const GROUP_PADDING: isize = 2;
+
+/// Order notes are laid out in when a region is auto-arranged.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum ArrangeOrder {
+    /// Alphabetically by first line, ignoring case
+    #[default]
+    Title,
+    /// By `#p1`, `#p2`, ... tag (lowest number first), untagged notes last
+    Priority,
+    /// Most recently created first
+    Recency,
+    /// By the total weight of the note's connections, heaviest first
+    Weight,
+}
+
+impl ArrangeOrder {
+    pub fn next(self) -> Self {
+        match self {
+            ArrangeOrder::Title => ArrangeOrder::Priority,
+            ArrangeOrder::Priority => ArrangeOrder::Recency,
+            ArrangeOrder::Recency => ArrangeOrder::Weight,
+            ArrangeOrder::Weight => ArrangeOrder::Title,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ArrangeOrder::Title => "title",
+            ArrangeOrder::Priority => "priority",
+            ArrangeOrder::Recency => "recency",
+            ArrangeOrder::Weight => "weight",
+        }
+    }
+}
+
+/// Reads a note's priority from its first `#p<number>` tag, e.g. `#p1`.
+pub fn note_priority(content: &str) -> Option<usize> {
+    content
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("#p")?.parse().ok())
+}
+
+/// A labeled rectangular area drawn beneath notes (e.g. "Backlog", "Doing", "Done").
+///
+/// Plain regions only give the canvas spatial structure; they don't own the notes inside
+/// them. A region with members is a group: its member notes move with it, wherever
+/// they are.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
+pub struct Region {
+    pub x: isize,
+    pub y: isize,
+    pub width: usize,
+    pub height: usize,
+    pub label: String,
+    /// Custom serde implementation in utils handles Color serialization
+    #[serde(with = "crate::utils")]
+    #[schemars(with = "String")]
+    pub color: Color,
+    /// Notes moved into the region get the `#label` tag appended
+    #[serde(default)]
+    pub auto_tag: bool,
+    /// Ids of the notes in the group, empty for plain regions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<usize>,
+}
+
+impl Region {
+    pub fn new(x: isize, y: isize, label: String, color: Color) -> Self {
+        Self {
+            x,
+            y,
+            width: 40,
+            height: 15,
+            label,
+            color,
+            auto_tag: true,
+            members: vec![],
+        }
+    }
+
+    /// A group around notes covering the given canvas rectangle (top-left and
+    /// bottom-right corner, inclusive), with room for the label and a gap around them.
+    pub fn group(
+        members: Vec<usize>,
+        (left, top): (isize, isize),
+        (right, bottom): (isize, isize),
+        color: Color,
+    ) -> Self {
+        let x = left - GROUP_PADDING;
+        let y = top - GROUP_PADDING;
+        Self {
+            x,
+            y,
+            width: ((right + GROUP_PADDING + 1 - x) as usize).max(MIN_REGION_WIDTH),
+            height: ((bottom + GROUP_PADDING + 1 - y) as usize).max(MIN_REGION_HEIGHT),
+            label: String::new(),
+            color,
+            auto_tag: false,
+            members,
+        }
+    }
+
+    pub fn is_group(&self) -> bool {
+        !self.members.is_empty()
+    }
+
+    /// The tag given to notes moved into the region: `#` followed by the
+    /// lowercased label with whitespace replaced by `-`. None for unlabeled regions.
+    pub fn tag(&self) -> Option<String> {
+        let words: Vec<String> = self
+            .label
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        if words.is_empty() {
+            return None;
+        }
+        Some(format!("#{}", words.join("-")))
+    }
+
+    /// Returns true if the canvas point lies inside the region (border included).
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        x >= self.x
+            && x < self.x + self.width as isize
+            && y >= self.y
+            && y < self.y + self.height as isize
+    }
+
+    pub fn center(&self) -> (isize, isize) {
+        (
+            self.x + self.width as isize / 2,
+            self.y + self.height as isize / 2,
+        )
+    }
+
+    /// Grows or shrinks the region, never below the minimum size.
+    pub fn resize(&mut self, d_width: isize, d_height: isize) {
+        self.width = self
+            .width
+            .saturating_add_signed(d_width)
+            .max(MIN_REGION_WIDTH);
+        self.height = self
+            .height
+            .saturating_add_signed(d_height)
+            .max(MIN_REGION_HEIGHT);
+    }
+
+    pub fn move_by(&mut self, dx: isize, dy: isize) {
+        self.x += dx;
+        self.y += dy;
+    }
+
+    /// Lays out notes of the given (width, height) sizes in a row-major grid inside
+    /// the region and returns their new top-left positions, in the same order.
+    ///
+    /// Every grid cell is as large as the largest note. As many columns are used as
+    /// fit the region's width (at least one); the region grows taller if the rows
+    /// don't fit.
+    pub fn grid_positions(&mut self, sizes: &[(u16, u16)]) -> Vec<(isize, isize)> {
+        if sizes.is_empty() {
+            return vec![];
+        }
+
+        let cell_width = sizes.iter().map(|&(w, _)| w as usize).max().unwrap_or(0) + ARRANGE_GAP;
+        let cell_height = sizes.iter().map(|&(_, h)| h as usize).max().unwrap_or(0) + ARRANGE_GAP;
+
+        // Inside the border, with a gap on each side
+        let inner_width = self.width.saturating_sub(2 + ARRANGE_GAP);
+        let columns = (inner_width / cell_width).max(1);
+        let rows = sizes.len().div_ceil(columns);
+
+        let needed_height = 2 + ARRANGE_GAP + rows * cell_height;
+        self.height = self.height.max(needed_height);
+
+        let origin_x = self.x + 1 + ARRANGE_GAP as isize;
+        let origin_y = self.y + 1 + ARRANGE_GAP as isize;
+        (0..sizes.len())
+            .map(|i| {
+                (
+                    origin_x + ((i % columns) * cell_width) as isize,
+                    origin_y + ((i / columns) * cell_height) as isize,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Background regions of the map and which one is being edited.
+#[derive(PartialEq, Debug)]
+pub struct RegionsState {
+    /// Drawn in order, so later regions are drawn over earlier ones
+    regions: Vec<Region>,
+    /// Index of the region being edited in Region mode
+    selected: Option<usize>,
+    /// Typed characters go to the selected region's label while true
+    pub editing_label: bool,
+    /// Order used by the next auto-arrange
+    pub arrange_order: ArrangeOrder,
+}
+
+impl Default for RegionsState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegionsState {
+    pub fn new() -> Self {
+        Self {
+            regions: Vec::new(),
+            selected: None,
+            editing_label: false,
+            arrange_order: ArrangeOrder::default(),
+        }
+    }
+
+    /// For deserialization
+    pub fn from_regions(regions: Vec<Region>) -> Self {
+        Self {
+            regions,
+            ..Self::new()
+        }
+    }
+
+    /// For serialization
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn selected_region(&self) -> Option<&Region> {
+        self.selected.and_then(|index| self.regions.get(index))
+    }
+
+    pub fn selected_region_mut(&mut self) -> Option<&mut Region> {
+        self.selected.and_then(|index| self.regions.get_mut(index))
+    }
+
+    /// Adds a region and selects it, returns its index
+    pub fn add(&mut self, region: Region) -> usize {
+        self.regions.push(region);
+        let index = self.regions.len() - 1;
+        self.selected = Some(index);
+        index
+    }
+
+    /// Removes the selected region, if any, and returns it
+    pub fn remove_selected(&mut self) -> Option<Region> {
+        self.editing_label = false;
+        let index = self.selected.take()?;
+        Some(self.regions.remove(index))
+    }
+
+    /// Takes a deleted note out of every group; groups left without members become
+    /// plain regions.
+    pub fn remove_note(&mut self, id: usize) {
+        for region in &mut self.regions {
+            region.members.retain(|&member| member != id);
+        }
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index < self.regions.len() {
+            self.selected = Some(index);
+        }
+    }
+
+    pub fn deselect(&mut self) {
+        self.selected = None;
+        self.editing_label = false;
+    }
+
+    /// Selects the next region in drawing order, wrapping around.
+    pub fn select_next(&mut self) {
+        if self.regions.is_empty() {
+            return;
+        }
+        self.selected = Some(match self.selected {
+            Some(index) => (index + 1) % self.regions.len(),
+            None => 0,
+        });
+    }
+
+    /// Finds the region containing the point; if several do, the topmost one.
+    /// Falls back to the region with the nearest center.
+    pub fn find_closest(&self, x: isize, y: isize) -> Option<usize> {
+        if let Some(index) = self.regions.iter().rposition(|r| r.contains(x, y)) {
+            return Some(index);
+        }
+
+        self.regions
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, region)| {
+                let (center_x, center_y) = region.center();
+                center_x.abs_diff(x).pow(2) + center_y.abs_diff(y).pow(2)
+            })
+            .map(|(index, _)| index)
+    }
+}
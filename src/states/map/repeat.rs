@@ -0,0 +1,63 @@
+/// A structural change that `.` can apply again.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum RepeatableAction {
+    AddNote,
+    /// Cycle the selected note's color once
+    Recolor,
+    /// Move the selected note by the total distance of the last Move session
+    Move {
+        dx: isize,
+        dy: isize,
+    },
+    Delete,
+}
+
+/// Remembers the last repeatable action for the `.` key.
+///
+/// Moves are tracked per Move session: every step between entering and leaving
+/// Visual (Move) mode adds up to a single repeatable delta.
+#[derive(PartialEq, Debug)]
+pub struct RepeatState {
+    pub last_action: Option<RepeatableAction>,
+    /// Distance moved so far in the current Move session, None outside of one
+    move_delta: Option<(isize, isize)>,
+}
+
+impl Default for RepeatState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RepeatState {
+    pub fn new() -> Self {
+        Self {
+            last_action: None,
+            move_delta: None,
+        }
+    }
+
+    pub fn record(&mut self, action: RepeatableAction) {
+        self.last_action = Some(action);
+    }
+
+    pub fn start_move(&mut self) {
+        self.move_delta = Some((0, 0));
+    }
+
+    pub fn track_move(&mut self, dx: isize, dy: isize) {
+        if let Some((total_dx, total_dy)) = &mut self.move_delta {
+            *total_dx += dx;
+            *total_dy += dy;
+        }
+    }
+
+    /// Ends the Move session, recording it unless the note ended up where it started.
+    pub fn finish_move(&mut self) {
+        if let Some((dx, dy)) = self.move_delta.take()
+            && (dx, dy) != (0, 0)
+        {
+            self.record(RepeatableAction::Move { dx, dy });
+        }
+    }
+}
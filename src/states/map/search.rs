@@ -0,0 +1,155 @@
+use std::{collections::HashMap, ops::Range};
+
+use regex::{Regex, RegexBuilder};
+
+use crate::states::map::Note;
+
+/// A match of the search pattern: the note and the byte range within its content, or
+/// within its title.
+#[derive(PartialEq, Clone, Debug)]
+pub struct SearchMatch {
+    pub note_id: usize,
+    pub range: Range<usize>,
+    pub in_title: bool,
+}
+
+/// Search within the note being edited (`/` in Edit Normal mode, `Ctrl+F` in the
+/// other Edit modes), moving the cursor from match to match.
+#[derive(PartialEq, Debug)]
+pub struct NoteSearch {
+    pub search: SearchState,
+    /// Cursor position when the search was opened, the first match is looked for from
+    /// here and Esc goes back to it
+    pub origin: usize,
+}
+
+impl NoteSearch {
+    pub fn new(origin: usize) -> Self {
+        Self {
+            search: SearchState::new(),
+            origin,
+        }
+    }
+}
+
+/// Incremental search over note contents (`/` in Normal mode).
+///
+/// The query is a regular expression, matched case-insensitively unless it contains an
+/// uppercase letter. Matches are found on demand rather than stored, so they stay
+/// correct while notes are edited.
+#[derive(Debug)]
+pub struct SearchState {
+    pub query: String,
+    /// True while the query is being typed; intercepts all input
+    pub typing: bool,
+    /// The match `n`/`N` last moved to
+    pub current: Option<SearchMatch>,
+    /// None while the query is empty or not a valid regular expression
+    matcher: Option<Regex>,
+}
+
+impl PartialEq for SearchState {
+    fn eq(&self, other: &Self) -> bool {
+        self.query == other.query && self.typing == other.typing && self.current == other.current
+    }
+}
+
+impl Default for SearchState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearchState {
+    pub fn new() -> Self {
+        Self {
+            query: String::new(),
+            typing: true,
+            current: None,
+            matcher: None,
+        }
+    }
+
+    /// A search for an already typed query.
+    pub fn with_query(query: &str) -> Self {
+        let mut search = Self::new();
+        search.query = query.to_string();
+        search.typing = false;
+        search.compile();
+        search
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.compile();
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.compile();
+    }
+
+    fn compile(&mut self) {
+        let ignore_case = !self.query.chars().any(char::is_uppercase);
+        self.matcher = (!self.query.is_empty())
+            .then(|| {
+                RegexBuilder::new(&self.query)
+                    .case_insensitive(ignore_case)
+                    .build()
+                    .ok()
+            })
+            .flatten();
+        self.current = None;
+    }
+
+    /// True for a query that isn't a valid regular expression (yet).
+    pub fn is_invalid(&self) -> bool {
+        !self.query.is_empty() && self.matcher.is_none()
+    }
+
+    /// Byte ranges of the non-empty matches in `text`.
+    pub fn matches_in(&self, text: &str) -> Vec<Range<usize>> {
+        self.matcher.as_ref().map_or(vec![], |matcher| {
+            matcher
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| m.range())
+                .collect()
+        })
+    }
+
+    /// Matches in a note, those in its title first.
+    pub fn matches_in_note(&self, note_id: usize, note: &Note) -> Vec<SearchMatch> {
+        let title = note.title.as_deref().unwrap_or_default();
+        let in_title = self.matches_in(title).into_iter().map(|range| SearchMatch {
+            note_id,
+            range,
+            in_title: true,
+        });
+        let in_content = self
+            .matches_in(&note.content)
+            .into_iter()
+            .map(|range| SearchMatch {
+                note_id,
+                range,
+                in_title: false,
+            });
+        in_title.chain(in_content).collect()
+    }
+
+    /// Every match on the canvas, ordered top to bottom and left to right by note
+    /// position, then by position in the note (title first). Notes `skip` returns true
+    /// for are left out.
+    pub fn all_matches(
+        &self,
+        notes: &HashMap<usize, Note>,
+        skip: impl Fn(usize) -> bool,
+    ) -> Vec<SearchMatch> {
+        let mut ids: Vec<usize> = notes.keys().copied().filter(|&id| !skip(id)).collect();
+        ids.sort_by_key(|id| (notes[id].y, notes[id].x, *id));
+
+        ids.into_iter()
+            .flat_map(|note_id| self.matches_in_note(note_id, &notes[&note_id]))
+            .collect()
+    }
+}
@@ -0,0 +1,37 @@
+use crate::utils::{MapDiff, Snapshot};
+
+/// List of a map's snapshots shown for loading one or comparing it with the open map.
+#[derive(PartialEq, Debug)]
+pub struct SnapshotPicker {
+    /// Newest first
+    pub snapshots: Vec<Snapshot>,
+    /// Index of the highlighted snapshot
+    pub selected: usize,
+    /// How the highlighted snapshot differs from the open map, shown instead of the
+    /// list while set
+    pub diff: Option<MapDiff>,
+}
+
+impl SnapshotPicker {
+    pub fn new(snapshots: Vec<Snapshot>) -> Self {
+        Self {
+            snapshots,
+            selected: 0,
+            diff: None,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.snapshots.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn selected_snapshot(&self) -> Option<&Snapshot> {
+        self.snapshots.get(self.selected)
+    }
+}
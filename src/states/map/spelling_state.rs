@@ -0,0 +1,55 @@
+use std::ops::Range;
+
+use crate::utils::Dictionary;
+
+/// Suggestions being cycled through for a misspelled word with `F7` in Edit mode.
+#[derive(PartialEq, Debug)]
+pub struct SuggestionCycle {
+    pub note_id: usize,
+    /// Byte position of the word in the note's content
+    pub start: usize,
+    /// The word as it was typed, put back after the last suggestion
+    pub original: String,
+    pub suggestions: Vec<String>,
+    /// Index of the suggestion in place of the word; `suggestions.len()` for the original
+    pub index: usize,
+}
+
+impl SuggestionCycle {
+    /// The word currently in the note.
+    pub fn current(&self) -> &str {
+        self.suggestions.get(self.index).unwrap_or(&self.original)
+    }
+
+    /// Byte range of the current word in the note's content.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.start + self.current().len()
+    }
+}
+
+/// Spell checking of the note being edited, with the dictionary chosen in the settings.
+#[derive(PartialEq, Debug, Default)]
+pub struct SpellingState {
+    /// Name and contents of the loaded dictionary; loaded the first time a note is
+    /// edited, and again if another one is chosen
+    dictionary: Option<(String, Dictionary)>,
+    pub cycle: Option<SuggestionCycle>,
+}
+
+impl SpellingState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The loaded dictionary if it's the one with the name.
+    pub fn dictionary(&self, name: &str) -> Option<&Dictionary> {
+        self.dictionary
+            .as_ref()
+            .filter(|(loaded, _)| loaded == name)
+            .map(|(_, dictionary)| dictionary)
+    }
+
+    pub fn set_dictionary(&mut self, name: &str, dictionary: Dictionary) {
+        self.dictionary = Some((name.to_string(), dictionary));
+    }
+}
@@ -1,15 +1,58 @@
-use crossterm::{cursor::SetCursorStyle, execute};
+use chrono::{DateTime, Local};
+use crossterm::{cursor::SetCursorStyle, event::KeyCode, execute};
 use ratatui::style::Color;
-use std::{io::stdout, path::PathBuf};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    io::stdout,
+    path::{Path, PathBuf},
+};
 
 use crate::{
+    graph::{
+        Graph,
+        algorithms::{orphans, reachable_from, weighted_shortest_path},
+    },
     states::{
-        map::{ConnectionsState, Mode, NotesState, PersistenceState, UIState, ViewportState},
+        Dialog,
+        map::{
+            Align, ArrangeOrder, BoxSelect, Command, Connection, ConnectionSort, ConnectionTable,
+            ConnectionsState, DiffView, DiscardMenuType, ExportFormat, ExportScope, ExternalChange,
+            Hyperedge, HyperedgesState, LayersState, MapDialogAction, MilestonePicker,
+            MilestonesState, Mode, Note, NoteStatus, NotesState, Notification, OpenMilestone,
+            PassphrasePrompt, PassphrasePurpose, PersistenceState, Region, RegionsState,
+            RepeatState, RepeatableAction, Restack, SearchMatch, SearchState, Side, SignedRect,
+            SnapshotPicker, SpellingState, SuggestionCycle, TrashState, TrashedNote, Tutorial,
+            UIState, UndoHistory, UndoStep, VaultState, ViewState, ViewportState, WorkspacePicker,
+            WorkspaceSearch, note_priority, tutorial_map,
+        },
         settings::{Settings, SettingsType, get_settings_with_fs},
     },
-    utils::{FileSystem, IoErrorKind, handle_runtime_backup, save_map_file},
+    utils::{
+        BarField, CommandInput, Dictionary, DslError, DslGraph, FileKey, FileSystem, IoErrorKind,
+        MapData, MapFileContents, MapMerge, MergeChoice, Milestone, NoteChange, NoteListError,
+        NoteListFormat, OutlineError, PluginEdit, PluginError, PluginMap, PluginNote, Plugins,
+        Point, RealFileSystem, RecoveryReport, StatusBarFormat, Substitution, TmmprError,
+        Workspace, add_milestone, build_graph, decrypt_map_data, dictionary_dirs, diff_maps,
+        export_dot, export_freemind, export_html, export_mermaid, export_opml, export_svg,
+        file_modified, finish_background_save, get_color_name_in_string, grid_layout,
+        handle_runtime_backup, is_compressed_map_path, list_snapshots, map_changes, map_name,
+        note_title, parse_dsl, parse_note_list, parse_outline, plugins_dir, read_map_file,
+        read_milestones, repair_map_data, run_program, save_map_file, save_map_file_in_background,
+        search_workspace, snapshot_path, snapshots_dir, tr, tr_args, word_at, write_map_copy,
+    },
 };
 
+/// Most titles of connected notes listed by the delete confirmation
+const DELETE_PREVIEW_TITLES: usize = 5;
+
+/// Most skipped values listed after opening a damaged map file
+const RECOVERY_PREVIEW_LINES: usize = 6;
+
+/// Farthest a new note is moved from the viewport center to keep it clear of other
+/// notes, in columns (a row counts as two, see [`MapState::free_position_near`])
+const MAX_NUDGE: isize = 60;
+
 /// Core state for the map view where users create and edit notes and connections.
 ///
 /// This is the main working state of the application, handling note creation/editing,
@@ -20,8 +63,25 @@ pub struct MapState {
     pub viewport: ViewportState,
     pub notes_state: NotesState,
     pub connections_state: ConnectionsState,
+    pub regions_state: RegionsState,
+    pub hyperedges_state: HyperedgesState,
+    pub layers_state: LayersState,
     pub persistence: PersistenceState,
     pub ui_state: UIState,
+    pub vault: VaultState,
+    pub repeat: RepeatState,
+    pub history: UndoHistory,
+    pub milestones: MilestonesState,
+    /// Shown in place of the map while set
+    pub diff_view: Option<DiffView>,
+    /// Workspace the map was opened in, whose other maps can be switched to
+    pub workspace: Option<Workspace>,
+    /// Set on the tutorial map, whose current step is shown in the bar
+    pub tutorial: Option<Tutorial>,
+    /// Deleted notes, saved with the map
+    pub trash: TrashState,
+    pub spelling: SpellingState,
+    pub plugins: Plugins,
     pub settings: Settings,
     pub settings_err_msg: Option<IoErrorKind>,
 }
@@ -33,15 +93,187 @@ impl MapState {
             SettingsType::Custom(settings) => (settings, None),
         };
 
+        let mut map_state = MapState {
+            settings_err_msg,
+            ..MapState::new_with_settings(file_write_path, settings)
+        };
+        map_state.load_plugins_with_fs(fs);
+        map_state
+    }
+
+    /// Creates an empty map state with the given settings, without reading any files.
+    pub fn new_with_settings(file_write_path: PathBuf, settings: Settings) -> MapState {
+        let mut notes_state = NotesState::new();
+        if settings.note_uuids {
+            notes_state.use_uuids();
+        }
+
         MapState {
             mode: Mode::Normal,
             viewport: ViewportState::new(),
-            notes_state: NotesState::new(),
+            notes_state,
             connections_state: ConnectionsState::new(),
+            regions_state: RegionsState::new(),
+            hyperedges_state: HyperedgesState::new(),
+            layers_state: LayersState::new(),
             persistence: PersistenceState::new(file_write_path),
             ui_state: UIState::new(),
-            settings: settings,
-            settings_err_msg: settings_err_msg,
+            vault: VaultState::new(),
+            repeat: RepeatState::new(),
+            history: UndoHistory::new(),
+            milestones: MilestonesState::new(),
+            diff_view: None,
+            workspace: None,
+            tutorial: None,
+            trash: TrashState::new(),
+            spelling: SpellingState::new(),
+            plugins: Plugins::default(),
+            settings,
+            settings_err_msg: None,
+        }
+    }
+
+    /// Opens the tutorial: a generated sample map with guided steps in the bar.
+    ///
+    /// The map is only kept in memory; `s` saves it to the config directory.
+    pub fn new_tutorial_with_fs(fs: &dyn FileSystem) -> MapState {
+        let path = fs
+            .get_home_dir()
+            .unwrap_or_default()
+            .join(".config/tmmpr/tutorial.json");
+        let mut map_state = MapState::new_with_fs(path, fs);
+
+        map_state.load_map_data(tutorial_map(map_state.settings.locale()));
+        map_state.persistence.mark_clean();
+        map_state.tutorial = Some(Tutorial::new(&map_state));
+        map_state
+    }
+
+    /// Moves the tutorial on to its next step once the user did the current one.
+    pub fn advance_tutorial(&mut self) {
+        let Some(mut tutorial) = self.tutorial.take() else {
+            return;
+        };
+        if tutorial.advance(self) {
+            self.ui_state.request_redraw();
+        }
+        self.tutorial = Some(tutorial);
+    }
+
+    /// Moves the map state out, e.g. into the backups screen, leaving an empty one with
+    /// the same settings behind.
+    pub fn take(&mut self) -> MapState {
+        let empty = MapState::new_with_settings(PathBuf::new(), self.settings.clone());
+        std::mem::replace(self, empty)
+    }
+
+    /// Path search between two notes (`P` in Visual mode, pressed on each end).
+    ///
+    /// The first press remembers the selected note as the start. The second press
+    /// highlights the lightest path of connections (by connection weight) to the now
+    /// selected note, following connection direction when possible and against it otherwise.
+    pub fn find_path_to_selected(&mut self) {
+        let selected_note_id = self.notes_state.expect_selected_note_id();
+
+        let Some(start) = self.ui_state.path_start.take() else {
+            self.ui_state.clear_path();
+            self.ui_state.path_start = Some(selected_note_id);
+            return;
+        };
+
+        let graph = self.graph();
+        let path = weighted_shortest_path(&graph, start, selected_note_id)
+            .or_else(|| {
+                let (mut path, weight) = weighted_shortest_path(&graph, selected_note_id, start)?;
+                path.reverse();
+                Some((path, weight))
+            })
+            .map(|(path, _)| path);
+
+        match path {
+            Some(path) => self.ui_state.highlighted_path = path,
+            None => self.ui_state.set_notification(Notification::NoPath),
+        }
+    }
+
+    /// Replaces the map's contents with loaded map data, repairing ids that don't hold
+    /// together first (see [`repair_map_data`]). A repaired map is saved with the repairs.
+    pub fn load_map_data(&mut self, mut map_data: MapData) {
+        let repairs = repair_map_data(&mut map_data);
+        if !repairs.is_empty() {
+            self.persistence.mark_dirty();
+            self.ui_state
+                .set_notification(Notification::MapRepaired(repairs.len()));
+        }
+        self.viewport.view_pos = map_data.view_pos;
+        self.notes_state = NotesState::from_map_data(
+            map_data.notes,
+            map_data.next_note_id_counter,
+            map_data.render_order,
+        );
+        // Notes of maps made before UUIDs were enabled get theirs now, saved with the map
+        if self.settings.note_uuids && self.notes_state.use_uuids() > 0 {
+            self.persistence.mark_dirty();
+        }
+        self.connections_state = ConnectionsState::from_connections(map_data.connections);
+        self.regions_state = RegionsState::from_regions(map_data.regions);
+        self.hyperedges_state = HyperedgesState::from_hyperedges(map_data.hyperedges);
+        self.layers_state = LayersState::from_layers(map_data.layers);
+        self.trash = TrashState::from_notes(map_data.trash);
+    }
+
+    /// Where the user is in the map, to be saved with it. None if there's nothing to
+    /// restore beyond the view position.
+    pub fn view_state(&self) -> Option<ViewState> {
+        let mut selection: Vec<usize> = self.notes_state.selected_note_id().into_iter().collect();
+        let mut others: Vec<usize> = self
+            .notes_state
+            .selection()
+            .into_iter()
+            .filter(|id| !selection.contains(id))
+            .collect();
+        others.sort_unstable();
+        selection.extend(others);
+
+        let view_state = ViewState {
+            selection,
+            help_page: self.ui_state.help_screen,
+            search: self
+                .ui_state
+                .search
+                .as_ref()
+                .map(|search| search.query.clone())
+                .filter(|query| !query.is_empty()),
+            active_layer: self.layers_state.active,
+        };
+        (!view_state.is_empty()).then_some(view_state)
+    }
+
+    /// Resumes where the map was left off when it was saved: selects the notes again (in
+    /// Visual mode), highlights the search matches and reopens the help page.
+    ///
+    /// Notes that no longer exist or can't be selected are left out.
+    pub fn restore_view_state(&mut self, view_state: ViewState) {
+        if view_state.active_layer < self.layers_state.layers().len() {
+            self.layers_state.active = view_state.active_layer;
+        }
+
+        let selection: Vec<usize> = view_state
+            .selection
+            .into_iter()
+            .filter(|&id| self.note_is_selectable(id))
+            .collect();
+        if !selection.is_empty() {
+            self.notes_state.select_many(&selection);
+            self.mode = Mode::Visual;
+        }
+
+        if let Some(query) = view_state.search {
+            self.ui_state.search = Some(SearchState::with_query(&query));
+        }
+
+        if let Some(page) = view_state.help_page {
+            self.ui_state.show_help(page);
         }
     }
 
@@ -49,66 +281,2722 @@ impl MapState {
         self.ui_state.request_redraw();
     }
 
-    /// Adds a new, empty note at the center of the viewport and enters edit mode.
+    /// Adds a new, empty note at the center of the viewport, or in the nearest free
+    /// space if that would cover another note, and enters edit mode.
+    ///
+    /// Refused with a notification if the active layer is hidden or locked.
     pub fn add_note(&mut self) {
+        if self.layers_state.is_frozen(self.layers_state.active) {
+            self.ui_state.set_notification(Notification::LayerLocked);
+            return;
+        }
         self.persistence.mark_dirty();
 
-        let (note_x, note_y) = self.viewport.center();
+        let (center_x, center_y) = self.viewport.center();
+        let size = Note::new(0, 0, String::new(), Color::White).get_dimensions();
+        let (note_x, note_y) = self.free_position_near(center_x, center_y, size);
 
         let id = self
             .notes_state
             .add(note_x, note_y, String::from(""), Color::White);
+        if let Some(note) = self.notes_state.get_mut(id) {
+            note.set_layer(self.layers_state.active);
+        }
         self.notes_state.select(id);
+        self.repeat.record(RepeatableAction::AddNote);
+        self.run_plugin_function("on_note_created", &[id]);
 
         self.switch_to_edit_mode();
     }
 
-    /// Switches to Edit mode, using modal editing if enabled in settings.
+    /// The position nearest to `(x, y)` for a note of the given size that keeps a cell
+    /// clear of every visible note, or `(x, y)` itself if there is none within
+    /// [`MAX_NUDGE`].
     ///
-    /// Block cursor provides visual feedback that modal editing is active (vim-style).
-    pub fn switch_to_edit_mode(&mut self) {
-        if self.settings.edit_modal {
-            let _ = execute!(stdout(), SetCursorStyle::SteadyBlock);
-            self.mode = Mode::EditNormal;
-        } else {
-            self.mode = Mode::Edit;
+    /// Terminal cells are about twice as tall as they are wide, so a row away counts as
+    /// much as two columns.
+    fn free_position_near(
+        &self,
+        x: isize,
+        y: isize,
+        (width, height): (u16, u16),
+    ) -> (isize, isize) {
+        let (width, height) = (width as isize, height as isize);
+        let search_area = SignedRect {
+            x: x - MAX_NUDGE,
+            y: y - MAX_NUDGE / 2,
+            width: 2 * MAX_NUDGE + width,
+            height: MAX_NUDGE + height,
+        };
+        // Grown by a cell on each side, so borders don't end up touching
+        let obstacles: Vec<SignedRect> = self
+            .notes_state
+            .notes()
+            .iter()
+            .filter(|&(&id, _)| !self.note_is_hidden(id))
+            .map(|(_, note)| {
+                let (note_width, note_height) = note.get_dimensions();
+                SignedRect {
+                    x: note.x - 1,
+                    y: note.y - 1,
+                    width: note_width as isize + 2,
+                    height: note_height as isize + 2,
+                }
+            })
+            .filter(|obstacle| obstacle.intersection(&search_area).is_some())
+            .collect();
+        let is_free = |(dx, dy): (isize, isize)| {
+            let rect = SignedRect {
+                x: x + dx,
+                y: y + dy,
+                width,
+                height,
+            };
+            obstacles
+                .iter()
+                .all(|obstacle| rect.intersection(obstacle).is_none())
+        };
+
+        for distance in 0..=MAX_NUDGE {
+            let mut ring: Vec<(isize, isize)> = (-distance..=distance)
+                .flat_map(|dx| (-distance / 2..=distance / 2).map(move |dy| (dx, dy)))
+                .filter(|&(dx, dy)| dx.abs().max(2 * dy.abs()) == distance)
+                .collect();
+            ring.sort_by_key(|&(dx, dy)| (dx * dx + 4 * dy * dy, dy, dx));
+            if let Some((dx, dy)) = ring.into_iter().find(|&offset| is_free(offset)) {
+                return (x + dx, y + dy);
+            }
         }
+        (x, y)
     }
 
-    /// Selects the note closest to the viewport center and enters Visual mode.
+    /// Bounding rectangle of all notes in canvas coordinates, None for an empty map.
+    pub fn content_bounds(&self) -> Option<SignedRect> {
+        let notes = self.notes_state.notes();
+        let left = notes.values().map(|note| note.x).min()?;
+        let top = notes.values().map(|note| note.y).min()?;
+        let right = notes
+            .values()
+            .map(|note| note.x + note.get_dimensions().0 as isize)
+            .max()?;
+        let bottom = notes
+            .values()
+            .map(|note| note.y + note.get_dimensions().1 as isize)
+            .max()?;
+
+        Some(SignedRect {
+            x: left,
+            y: top,
+            width: right - left,
+            height: bottom - top,
+        })
+    }
+
+    /// Moves the viewport so the whole map is on screen, centered (`zf` in Normal mode,
+    /// `:fit`).
     ///
-    /// Uses Manhattan distance from viewport center to note top-left corner.
-    pub fn select_note(&mut self) {
-        let (screen_center_x, screen_center_y) = self.viewport.center();
+    /// Maps larger than the screen show their top left part, with a notification saying
+    /// so; there is no zoom yet.
+    pub fn fit_to_content(&mut self) {
+        if let Some(bounds) = self.content_bounds() {
+            self.viewport.fit(&bounds);
+            self.persistence.mark_dirty();
+            if !self.viewport.fits(&bounds) {
+                self.ui_state
+                    .set_notification(Notification::MapLargerThanScreen);
+            }
+        }
+    }
+
+    /// Moves the viewport back to the origin of the canvas (`gg` in Normal mode).
+    pub fn go_to_origin(&mut self) {
+        self.viewport.view_pos.x = 0;
+        self.viewport.view_pos.y = 0;
+        self.persistence.mark_dirty();
+    }
+
+    /// Moves the viewport to the far end of the map, the bottom right corner of all
+    /// notes (`G` in Normal mode).
+    pub fn go_to_content_end(&mut self) {
+        if let Some(bounds) = self.content_bounds() {
+            self.viewport.show_end_of(&bounds);
+            self.persistence.mark_dirty();
+        }
+    }
+
+    /// Moves the viewport so the selected note is in the middle of the screen (`zz` in
+    /// Visual mode).
+    pub fn center_on_selected_note(&mut self) {
+        if let Some(id) = self.notes_state.selected_note_id() {
+            let (x, y) = self.notes_state.notes()[&id].center();
+            self.viewport.center_on(x, y);
+            self.persistence.mark_dirty();
+        }
+    }
+
+    /// Moves the viewport to the next note without any connections, cycling in id order.
+    /// Shows a notification instead if every note is connected.
+    pub fn focus_next_orphan(&mut self) {
+        let orphans = orphans(&self.graph());
+
+        let Some(&first) = orphans.first() else {
+            self.ui_state.last_orphan = None;
+            self.ui_state.set_notification(Notification::NoOrphans);
+            return;
+        };
+        let next = self
+            .ui_state
+            .last_orphan
+            .and_then(|last| orphans.iter().copied().find(|&id| id > last))
+            .unwrap_or(first);
+
+        let (x, y) = self.notes_state.notes()[&next].center();
+        self.viewport.center_on(x, y);
+        self.ui_state.last_orphan = Some(next);
+    }
+
+    /// Moves the viewport to the next (or previous) search match, wrapping around, and
+    /// makes it the current one. Returns false if nothing matches.
+    pub fn jump_to_search_match(&mut self, forward: bool) -> bool {
+        let Some(search) = &self.ui_state.search else {
+            return false;
+        };
+        let mut matches = search.all_matches(self.notes_state.notes(), |id| {
+            self.note_is_locked(id) || self.note_is_hidden(id)
+        });
+
+        let position = search
+            .current
+            .as_ref()
+            .and_then(|current| matches.iter().position(|m| m == current));
+        let next = match (position, forward) {
+            _ if matches.is_empty() => return false,
+            (Some(i), true) => (i + 1) % matches.len(),
+            (Some(i), false) => (i + matches.len() - 1) % matches.len(),
+            (None, true) => 0,
+            (None, false) => matches.len() - 1,
+        };
+        let next = matches.swap_remove(next);
+
+        let (x, y) = self.notes_state.notes()[&next.note_id].center();
+        self.viewport.center_on(x, y);
+        if let Some(search) = &mut self.ui_state.search {
+            search.current = Some(next);
+        }
+        true
+    }
+
+    /// `n`/`N` in Normal mode: like [`MapState::jump_to_search_match`], reporting in the
+    /// bar when nothing matches anymore.
+    pub fn cycle_search_matches(&mut self, forward: bool) {
+        if !self.jump_to_search_match(forward) {
+            self.ui_state
+                .set_notification(Notification::PatternNotFound);
+        }
+    }
+
+    /// Moves the cursor to the first match of the in-note search starting at or after
+    /// `from` in the note being edited, or with `forward` false to the last one starting
+    /// before it, wrapping around the note. Returns false if nothing matches.
+    pub fn jump_to_note_match(&mut self, from: usize, forward: bool) -> bool {
+        let (Some(id), Some(note_search)) = (
+            self.notes_state.selected_note_id(),
+            self.ui_state.note_search.as_mut(),
+        ) else {
+            return false;
+        };
+        let matches = note_search
+            .search
+            .matches_in(&self.notes_state.notes()[&id].content);
+
+        let next = if forward {
+            (matches.iter().find(|range| range.start >= from)).or(matches.first())
+        } else {
+            (matches.iter().rev().find(|range| range.start < from)).or(matches.last())
+        };
+        let Some(next) = next else {
+            return false;
+        };
+
+        self.notes_state.set_cursor_pos(next.start);
+        note_search.search.current = Some(SearchMatch {
+            note_id: id,
+            range: next.clone(),
+            in_title: false,
+        });
+        true
+    }
+
+    /// `n`/`N` in Edit Normal mode and `Ctrl+N`/`Ctrl+P` in the other Edit modes: the
+    /// next or previous match of the in-note search from the cursor.
+    pub fn cycle_note_matches(&mut self, forward: bool) {
+        if self.ui_state.note_search.is_none() {
+            return;
+        }
+        let cursor = self.notes_state.cursor_pos();
+        let from = if forward { cursor + 1 } else { cursor };
+        if !self.jump_to_note_match(from, forward) {
+            self.ui_state
+                .set_notification(Notification::PatternNotFound);
+        }
+    }
+
+    /// Creates a region at the viewport center and starts typing its label in Region mode.
+    pub fn add_region(&mut self) {
+        self.persistence.mark_dirty();
+
+        let (x, y) = self.viewport.center();
+        self.regions_state
+            .add(Region::new(x, y, String::new(), Color::White));
+        self.regions_state.editing_label = true;
+        self.mode = Mode::Region;
+    }
+
+    /// Selects the region under (or closest to) the viewport center and switches to Region mode.
+    pub fn select_region(&mut self) {
+        let (x, y) = self.viewport.center();
+
+        if let Some(index) = self.regions_state.find_closest(x, y) {
+            self.regions_state.select(index);
+            self.mode = Mode::Region;
+        }
+    }
+
+    /// Ids of the notes whose center lies inside the region, or of a group's members,
+    /// ordered top to bottom, left to right.
+    pub fn notes_in_region(&self, index: usize) -> Vec<usize> {
+        let Some(region) = self.regions_state.regions().get(index) else {
+            return vec![];
+        };
+
+        let mut notes: Vec<(usize, &Note)> = self
+            .notes_state
+            .notes()
+            .iter()
+            .filter(|&(id, note)| {
+                if region.is_group() {
+                    return region.members.contains(id);
+                }
+                let (x, y) = note.center();
+                region.contains(x, y)
+            })
+            .map(|(&id, note)| (id, note))
+            .collect();
+        notes.sort_by_key(|(id, note)| (note.y, note.x, *id));
+
+        notes.into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Puts the selected notes into a new group drawn around them and starts typing its
+    /// label in Region mode (`g` in Visual mode).
+    pub fn group_selection(&mut self) {
+        let notes = self.notes_state.notes();
+        let members = self.notes_state.selection();
+        let bounds: Vec<(isize, isize, isize, isize)> = members
+            .iter()
+            .filter_map(|id| notes.get(id))
+            .map(|note| {
+                let (width, height) = note.get_dimensions();
+                let right = note.x + width as isize - 1;
+                (note.x, note.y, right, note.y + height as isize - 1)
+            })
+            .collect();
+        if bounds.is_empty() {
+            return;
+        }
+
+        let left = bounds.iter().map(|b| b.0).min().unwrap_or(0);
+        let top = bounds.iter().map(|b| b.1).min().unwrap_or(0);
+        let right = bounds.iter().map(|b| b.2).max().unwrap_or(0);
+        let bottom = bounds.iter().map(|b| b.3).max().unwrap_or(0);
 
-        if let Some(id) = self
+        self.regions_state.add(Region::group(
+            members,
+            (left, top),
+            (right, bottom),
+            Color::White,
+        ));
+        self.regions_state.editing_label = true;
+        self.notes_state.deselect();
+        self.mode = Mode::Region;
+        self.persistence.mark_dirty();
+    }
+
+    /// Connects the focused note to the other selected notes with one fan-out connection
+    /// on the active layer. With no other notes selected, removes the fan-outs starting
+    /// at the focused note instead.
+    pub fn fan_out_selection(&mut self) {
+        let Some(from_id) = self.notes_state.selected_note_id() else {
+            return;
+        };
+        let mut to_ids: Vec<usize> = self
             .notes_state
-            .find_closest_note(screen_center_x, screen_center_y)
+            .selection()
+            .into_iter()
+            .filter(|&id| id != from_id)
+            .collect();
+
+        if to_ids.is_empty() {
+            let removed = self.hyperedges_state.remove_from(from_id);
+            if removed == 0 {
+                self.ui_state
+                    .set_notification(Notification::NoFanOutTargets);
+            } else {
+                self.ui_state
+                    .set_notification(Notification::FanOutsRemoved(removed));
+                self.persistence.mark_dirty();
+            }
+            return;
+        }
+        if self.layers_state.is_frozen(self.layers_state.active) {
+            self.ui_state.set_notification(Notification::LayerLocked);
+            return;
+        }
+
+        to_ids.sort_unstable();
+        let count = to_ids.len();
+        let active = self.layers_state.active;
+        self.hyperedges_state.add(Hyperedge {
+            from_id,
+            from_side: self.settings.default_start_side,
+            to_ids,
+            color: Color::White,
+            layer: (active != 0).then_some(active),
+        });
+        self.ui_state
+            .set_notification(Notification::FannedOut(count));
+        self.persistence.mark_dirty();
+    }
+
+    /// Moves the selected region; a group takes its member notes along, so it stays
+    /// put if any of them is locked.
+    pub fn move_selected_region(&mut self, dx: isize, dy: isize) {
+        let notes = self.notes_state.notes();
+        let Some(region) = self.regions_state.selected_region_mut() else {
+            return;
+        };
+        if region
+            .members
+            .iter()
+            .any(|id| notes.get(id).is_some_and(|note| note.locked))
         {
-            self.notes_state.select(id);
-            self.mode = Mode::Visual;
+            self.ui_state.set_notification(Notification::NoteLocked);
+            return;
+        }
+        region.move_by(dx, dy);
+
+        for id in region.members.clone() {
+            if let Some(note) = self.notes_state.get_mut(id) {
+                note.x += dx;
+                note.y += dy;
+            }
         }
+        self.persistence.mark_dirty();
     }
 
-    /// Handles periodic auto-save operations based on configured intervals.
-    pub fn auto_save_if_needed(&mut self) {
-        if let Some(interval) = self.settings.save_interval {
-            if self.persistence.should_save(interval) {
-                let map_file_path = self.persistence.file_write_path.clone();
-                let _ = save_map_file(self, &map_file_path); // No notification for auto-save
-                self.persistence.reset_save_timer();
+    /// Deletes the selected region and returns to Normal mode. For a group, the member
+    /// notes are deleted too if `with_members` is set (except locked ones), and kept
+    /// otherwise.
+    pub fn delete_selected_region(&mut self, with_members: bool) {
+        let Some(region) = self.regions_state.remove_selected() else {
+            return;
+        };
+
+        if with_members {
+            let now = Local::now();
+            for id in region.members {
+                if self
+                    .notes_state
+                    .notes()
+                    .get(&id)
+                    .is_some_and(|note| note.locked)
+                {
+                    continue;
+                }
+                self.trash_note(id, now);
             }
         }
+        self.persistence.mark_dirty();
+        self.mode = Mode::Normal;
     }
 
-    /// Handles periodic backup operations based on configured intervals.
-    pub fn auto_backup_if_needed(&mut self) {
-        if let Some(interval) = &self.settings.runtime_backups_interval {
-            if self.persistence.should_backup(interval) {
-                // Runtime backups interval implies backups path exists in settings
-                handle_runtime_backup(self);
-                self.persistence.reset_backup_timer();
+    /// Selects every note inside the selected region and switches to Visual mode.
+    ///
+    /// Does nothing if the region is empty.
+    pub fn select_notes_in_region(&mut self) {
+        let Some(index) = self.regions_state.selected_index() else {
+            return;
+        };
+        let mut ids = self.notes_in_region(index);
+        ids.retain(|&id| self.note_is_selectable(id));
+        if ids.is_empty() {
+            return;
+        }
+
+        self.regions_state.deselect();
+        self.notes_state.select_many(&ids);
+        self.mode = Mode::Visual;
+    }
+
+    /// Starts a selection rectangle on the selected note (`b` in Visual mode).
+    ///
+    /// # Panics
+    /// If no note is selected.
+    pub fn start_box_select(&mut self) {
+        let id = self.notes_state.expect_selected_note_id();
+        self.ui_state.box_select =
+            Some(BoxSelect::new(id, self.notes_state.expect_selected_note()));
+        self.mode = Mode::VisualBox;
+    }
+
+    /// Moves the free corner of the selection rectangle and selects every note it touches.
+    pub fn move_box_corner(&mut self, dx: isize, dy: isize) {
+        let Some(box_select) = &mut self.ui_state.box_select else {
+            return;
+        };
+        box_select.move_corner(dx, dy);
+
+        // The box always covers the original note, so it stays the selected one
+        let mut ids: Vec<usize> = self
+            .notes_state
+            .notes()
+            .iter()
+            .filter(|&(&id, note)| {
+                id != box_select.original
+                    && box_select.intersects(note)
+                    && !self.layers_state.is_frozen(note.layer())
+            })
+            .map(|(&id, _)| id)
+            .collect();
+        ids.sort_unstable();
+        ids.insert(0, box_select.original);
+
+        self.notes_state.select_many(&ids);
+    }
+
+    /// Keeps the notes the rectangle touches selected and returns to Visual mode.
+    pub fn finish_box_select(&mut self) {
+        self.ui_state.box_select = None;
+        self.mode = Mode::Visual;
+    }
+
+    /// Drops the rectangle's selection, going back to the note selected before it.
+    pub fn cancel_box_select(&mut self) {
+        if let Some(box_select) = self.ui_state.box_select.take() {
+            self.notes_state.select_many(&[box_select.original]);
+        }
+        self.mode = Mode::Visual;
+    }
+
+    /// Tidies the notes inside the selected region into a compact grid, ordered by
+    /// the region's current arrange order. Locked notes stay where they are. Returns the
+    /// number of notes arranged.
+    pub fn arrange_region(&mut self) -> usize {
+        let Some(index) = self.regions_state.selected_index() else {
+            return 0;
+        };
+
+        let mut ids = self.notes_in_region(index);
+        let notes = self.notes_state.notes();
+        ids.retain(|id| !notes[id].locked);
+        let title = |id: &usize| {
+            notes[id]
+                .content
+                .lines()
+                .next()
+                .unwrap_or("")
+                .to_lowercase()
+        };
+        match self.regions_state.arrange_order {
+            ArrangeOrder::Title => ids.sort_by_cached_key(|id| (title(id), *id)),
+            ArrangeOrder::Priority => ids.sort_by_cached_key(|id| {
+                (
+                    note_priority(&notes[id].content).unwrap_or(usize::MAX),
+                    title(id),
+                    *id,
+                )
+            }),
+            // Note ids only ever increase, so higher ids were created later
+            ArrangeOrder::Recency => ids.sort_by(|a, b| b.cmp(a)),
+            ArrangeOrder::Weight => {
+                let connections = self.connections_state.connections();
+                let total_weight = |id: &usize| -> u64 {
+                    connections
+                        .iter()
+                        .filter(|c| c.touches(*id))
+                        .map(|c| u64::from(c.weight()))
+                        .sum()
+                };
+                ids.sort_by_cached_key(|id| (Reverse(total_weight(id)), title(id), *id))
+            }
+        }
+
+        let sizes: Vec<(u16, u16)> = ids.iter().map(|id| notes[id].get_dimensions()).collect();
+        let Some(region) = self.regions_state.selected_region_mut() else {
+            return 0;
+        };
+        let positions = region.grid_positions(&sizes);
+
+        for (&id, (x, y)) in ids.iter().zip(positions) {
+            if let Some(note) = self.notes_state.get_mut(id) {
+                note.x = x;
+                note.y = y;
+            }
+        }
+        if !ids.is_empty() {
+            self.persistence.mark_dirty();
+        }
+
+        ids.len()
+    }
+
+    /// Lines up the edges of the selected notes, or spaces them evenly.
+    ///
+    /// Edges align with the outermost note in that direction. Needs at least two selected
+    /// notes (three to distribute); returns the number of notes that moved.
+    pub fn align_selection(&mut self, align: Align) -> usize {
+        if self.selection_is_locked() {
+            return 0;
+        }
+        let notes = self.notes_state.notes();
+        // (id, x, y, width, height)
+        let mut boxes: Vec<(usize, isize, isize, usize, usize)> = self
+            .notes_state
+            .selection()
+            .into_iter()
+            .filter_map(|id| {
+                let note = notes.get(&id)?;
+                let (width, height) = note.get_dimensions();
+                Some((id, note.x, note.y, width as usize, height as usize))
+            })
+            .collect();
+        if boxes.len() < 2 {
+            return 0;
+        }
+
+        let positions: Vec<(usize, isize, isize)> = match align {
+            Align::Left => {
+                let left = boxes.iter().map(|b| b.1).min().unwrap_or(0);
+                boxes.iter().map(|b| (b.0, left, b.2)).collect()
+            }
+            Align::Right => {
+                let right = boxes.iter().map(|b| b.1 + b.3 as isize).max().unwrap_or(0);
+                boxes
+                    .iter()
+                    .map(|b| (b.0, right - b.3 as isize, b.2))
+                    .collect()
+            }
+            Align::Top => {
+                let top = boxes.iter().map(|b| b.2).min().unwrap_or(0);
+                boxes.iter().map(|b| (b.0, b.1, top)).collect()
+            }
+            Align::Bottom => {
+                let bottom = boxes.iter().map(|b| b.2 + b.4 as isize).max().unwrap_or(0);
+                boxes
+                    .iter()
+                    .map(|b| (b.0, b.1, bottom - b.4 as isize))
+                    .collect()
+            }
+            Align::DistributeHorizontally => {
+                boxes.sort_by_key(|b| (b.1, b.0));
+                let spans: Vec<(isize, usize)> = boxes.iter().map(|b| (b.1, b.3)).collect();
+                distribute(&spans)
+                    .into_iter()
+                    .zip(&boxes)
+                    .map(|(x, b)| (b.0, x, b.2))
+                    .collect()
+            }
+            Align::DistributeVertically => {
+                boxes.sort_by_key(|b| (b.2, b.0));
+                let spans: Vec<(isize, usize)> = boxes.iter().map(|b| (b.2, b.4)).collect();
+                distribute(&spans)
+                    .into_iter()
+                    .zip(&boxes)
+                    .map(|(y, b)| (b.0, b.1, y))
+                    .collect()
+            }
+        };
+
+        let mut moved = 0;
+        for (id, x, y) in positions {
+            if let Some(note) = self.notes_state.get_mut(id)
+                && (note.x, note.y) != (x, y)
+            {
+                note.x = x;
+                note.y = y;
+                moved += 1;
+            }
+        }
+        if moved > 0 {
+            self.persistence.mark_dirty();
+        }
+
+        moved
+    }
+
+    /// Tags notes that have just moved into an auto-tagging region.
+    ///
+    /// `previous_centers` holds each moved note's id and its center before the move;
+    /// regions that already contained that center are skipped, as are tags the note
+    /// already has.
+    pub fn tag_notes_entering_regions(&mut self, previous_centers: &[(usize, (isize, isize))]) {
+        for &(id, (previous_x, previous_y)) in previous_centers {
+            if self.note_is_locked(id) {
+                continue;
+            }
+            let Some(note) = self.notes_state.notes().get(&id) else {
+                continue;
+            };
+            let (x, y) = note.center();
+
+            let tags: Vec<String> = self
+                .regions_state
+                .regions()
+                .iter()
+                .filter(|region| {
+                    region.auto_tag
+                        && region.contains(x, y)
+                        && !region.contains(previous_x, previous_y)
+                })
+                .filter_map(Region::tag)
+                .collect();
+
+            let Some(note) = self.notes_state.get_mut(id) else {
+                continue;
+            };
+            for tag in tags {
+                if note.content.split_whitespace().any(|word| word == tag) {
+                    continue;
+                }
+                if !note.content.is_empty() && !note.content.ends_with(char::is_whitespace) {
+                    note.content.push(' ');
+                }
+                note.content.push_str(&tag);
+                self.persistence.mark_dirty();
+            }
+        }
+    }
+
+    /// Adds a waypoint to the focused connection halfway between the selected waypoint
+    /// (or the start of the connection) and the point after it.
+    pub fn add_waypoint(&mut self) {
+        let Some(connection) = &self.connections_state.focused_connection else {
+            return;
+        };
+        let notes = self.notes_state.notes();
+        let (Some(start_note), Some((to_id, to_side))) =
+            (notes.get(&connection.from_id), connection.to)
+        else {
+            return;
+        };
+        let Some(end_note) = notes.get(&to_id) else {
+            return;
+        };
+
+        let to_point = |(x, y): (isize, isize)| Point { x, y };
+        let start = to_point(start_note.get_connection_point(connection.from_side));
+        let end = to_point(end_note.get_connection_point(to_side));
+
+        let (previous, next) = match self.connections_state.selected_waypoint {
+            Some(selected) => (
+                connection.waypoints[selected],
+                connection
+                    .waypoints
+                    .get(selected + 1)
+                    .copied()
+                    .unwrap_or(end),
+            ),
+            None => (connection.waypoints.last().copied().unwrap_or(start), end),
+        };
+
+        self.connections_state.insert_waypoint(Point {
+            x: (previous.x + next.x) / 2,
+            y: (previous.y + next.y) / 2,
+        });
+        self.persistence.mark_dirty();
+    }
+
+    /// Asks whether to leave for another screen without saving the map's changes.
+    pub fn show_discard_menu(&mut self, menu_type: DiscardMenuType) {
+        let locale = self.settings.locale();
+        let (question, confirm) = match menu_type {
+            DiscardMenuType::Start => ("discard.start.question", "discard.start.confirm"),
+            DiscardMenuType::Settings => ("discard.settings.question", "discard.settings.confirm"),
+        };
+
+        let mut dialog = Dialog::new(tr(locale, question), MapDialogAction::Cancel);
+        if menu_type == DiscardMenuType::Settings {
+            dialog = dialog.line(tr(locale, "discard.settings.reason"));
+        }
+        self.ui_state.dialog = Some(
+            dialog
+                .button(
+                    tr(locale, "discard.cancel"),
+                    KeyCode::Esc,
+                    MapDialogAction::Cancel,
+                    Color::Green,
+                )
+                .button(
+                    tr(locale, confirm),
+                    KeyCode::Char('q'),
+                    MapDialogAction::Discard(menu_type),
+                    Color::Red,
+                ),
+        );
+        self.clear_and_redraw();
+    }
+
+    /// Tells what was left out of a map opened from a damaged file, and where the damaged
+    /// file was kept (None if it couldn't be copied).
+    pub fn show_recovery_report(&mut self, report: &RecoveryReport, damaged_copy: Option<&Path>) {
+        let locale = self.settings.locale();
+        let mut dialog = Dialog::new(tr(locale, "recovery.title"), MapDialogAction::Cancel);
+        for skipped in report.skipped.iter().take(RECOVERY_PREVIEW_LINES) {
+            dialog = dialog.line(skipped.clone());
+        }
+        if report.skipped.len() > RECOVERY_PREVIEW_LINES {
+            dialog = dialog.line(tr_args(
+                locale,
+                "recovery.more",
+                &[(
+                    "count",
+                    &(report.skipped.len() - RECOVERY_PREVIEW_LINES).to_string(),
+                )],
+            ));
+        }
+        dialog = dialog.line(match damaged_copy {
+            Some(path) => tr_args(
+                locale,
+                "recovery.copy",
+                &[("path", &path.display().to_string())],
+            ),
+            None => String::from(tr(locale, "recovery.no_copy")),
+        });
+
+        self.ui_state.dialog = Some(dialog.button(
+            tr(locale, "recovery.close"),
+            KeyCode::Esc,
+            MapDialogAction::Cancel,
+            Color::Green,
+        ));
+        self.clear_and_redraw();
+    }
+
+    /// Switches to Delete mode and asks whether to delete the selected notes, listing
+    /// the connections that go with them and the notes those lead to.
+    pub fn confirm_delete(&mut self) {
+        if self.selection_is_locked() {
+            return;
+        }
+        let locale = self.settings.locale();
+        let selected_count = self.notes_state.selection().len();
+        let question = if selected_count > 1 {
+            tr_args(
+                locale,
+                "delete.question.many",
+                &[("count", &selected_count.to_string())],
+            )
+        } else {
+            String::from(tr(locale, "delete.question.one"))
+        };
+
+        let (connection_count, leads_to) = self.connections_of_selection();
+        let mut dialog = Dialog::new(question, MapDialogAction::Cancel);
+        if connection_count > 0 {
+            dialog = dialog.line(if connection_count > 1 {
+                tr_args(
+                    locale,
+                    "delete.connections.many",
+                    &[("count", &connection_count.to_string())],
+                )
+            } else {
+                String::from(tr(locale, "delete.connections.one"))
+            });
+        }
+        if !leads_to.is_empty() {
+            let mut titles: Vec<String> = leads_to
+                .iter()
+                .take(DELETE_PREVIEW_TITLES)
+                .map(|id| match note_title(&self.notes_state.notes()[id]) {
+                    title if title.trim().is_empty() => format!("#{id}"),
+                    title => title,
+                })
+                .collect();
+            if leads_to.len() > DELETE_PREVIEW_TITLES {
+                titles.push(tr_args(
+                    locale,
+                    "delete.connections.more",
+                    &[(
+                        "count",
+                        &(leads_to.len() - DELETE_PREVIEW_TITLES).to_string(),
+                    )],
+                ));
+            }
+            dialog = dialog.line(tr_args(
+                locale,
+                "delete.connections.to",
+                &[("notes", &titles.join(", "))],
+            ));
+        }
+
+        dialog = dialog
+            .button(
+                tr(locale, "delete.cancel"),
+                KeyCode::Esc,
+                MapDialogAction::Cancel,
+                Color::Green,
+            )
+            .button(
+                tr(locale, "delete.confirm"),
+                KeyCode::Char('d'),
+                MapDialogAction::DeleteNotes,
+                Color::Red,
+            );
+        // Only connections to notes that stay can be kept
+        if !leads_to.is_empty() {
+            dialog = dialog.button(
+                tr(locale, "delete.keep_connections"),
+                KeyCode::Char('p'),
+                MapDialogAction::DeleteKeepingConnections,
+                Color::Yellow,
+            );
+        }
+
+        self.mode = Mode::Delete;
+        self.ui_state.dialog = Some(dialog);
+        self.clear_and_redraw();
+    }
+
+    /// Number of connections deleting the selected notes would remove, and the ids of
+    /// the notes outside the selection they lead to, in ascending order.
+    pub fn connections_of_selection(&self) -> (usize, Vec<usize>) {
+        let selection = self.notes_state.selection();
+        let mut count = 0;
+        let mut leads_to = Vec::new();
+        for connection in self.connections_state.connections() {
+            let to_id = connection.to_id;
+            let from_selected = selection.contains(&connection.from_id);
+            let to_selected = selection.contains(&to_id);
+            if from_selected || to_selected {
+                count += 1;
+            }
+            if from_selected && !to_selected {
+                leads_to.push(to_id);
+            } else if to_selected && !from_selected {
+                leads_to.push(connection.from_id);
             }
         }
+        leads_to.sort_unstable();
+        leads_to.dedup();
+        (count, leads_to)
+    }
+
+    /// Deletes the selected note (or every multi-selected note) along with their
+    /// connections and switches to Normal mode.
+    ///
+    /// # Panics
+    /// If no note is selected.
+    pub fn delete_selected_note(&mut self) {
+        self.notes_state.expect_selected_note_id();
+
+        self.persistence.mark_dirty();
+        let now = Local::now();
+        for id in self.notes_state.selection() {
+            self.trash_note(id, now);
+        }
+        self.repeat.record(RepeatableAction::Delete);
+        self.mode = Mode::Normal;
+    }
+
+    /// Removes a note along with its connections, putting them in the trash.
+    fn trash_note(&mut self, id: usize, now: DateTime<Local>) {
+        let Some(mut note) = self.notes_state.notes().get(&id).cloned() else {
+            return;
+        };
+        // Unlocked notes go to the trash encrypted, like they're saved
+        if note.encrypted.is_some() {
+            if self.vault.is_unlocked(id)
+                && let Some(encrypted) = self.vault.encrypt(&note.content)
+            {
+                note.encrypted = Some(encrypted);
+            }
+            note.content = String::new();
+        }
+        let connections = self
+            .connections_state
+            .get_connections_for_note(id)
+            .into_iter()
+            .cloned()
+            .collect();
+        self.trash.push(TrashedNote {
+            id,
+            note,
+            connections,
+            deleted_at: now,
+        });
+
+        self.notes_state.remove(id);
+        self.connections_state.remove_note(id);
+        self.regions_state.remove_note(id);
+        self.hyperedges_state.remove_note(id);
+        self.vault.forget(id);
+    }
+
+    /// Takes the note highlighted in the trash browser out of the trash, along with its
+    /// connections to the notes that are on the map. Connections to notes still in the
+    /// trash are kept with those.
+    pub fn restore_trashed_note(&mut self) {
+        let Some(trashed) = self
+            .trash
+            .selected()
+            .and_then(|index| self.trash.take(index))
+        else {
+            return;
+        };
+
+        let id = self.notes_state.restore(trashed.id, trashed.note);
+        let mut restored = 0;
+        for mut connection in trashed.connections {
+            if connection.from_id == trashed.id {
+                connection.from_id = id;
+            }
+            if connection.to_id == trashed.id {
+                connection.to_id = id;
+            }
+            let notes = self.notes_state.notes();
+            let ends_exist =
+                notes.contains_key(&connection.from_id) && notes.contains_key(&connection.to_id);
+            if ends_exist {
+                if !self.connections_state.connections().contains(&connection) {
+                    self.connections_state.add(connection);
+                    restored += 1;
+                }
+            } else {
+                // The other note is in the trash too, so it comes back with that one
+                self.trash.add_connection(connection);
+            }
+        }
+
+        self.persistence.mark_dirty();
+        self.ui_state
+            .set_notification(Notification::NoteRestored(restored));
+    }
+
+    /// Deletes the note highlighted in the trash browser for good.
+    pub fn purge_trashed_note(&mut self) {
+        if let Some(index) = self.trash.selected() {
+            self.trash.take(index);
+            self.persistence.mark_dirty();
+        }
+    }
+
+    /// Asks whether to delete every note in the trash for good.
+    pub fn confirm_empty_trash(&mut self) {
+        if self.trash.is_empty() {
+            return;
+        }
+
+        let locale = self.settings.locale();
+        let question = tr_args(
+            locale,
+            "trash.empty.question",
+            &[("count", &self.trash.notes().len().to_string())],
+        );
+        self.ui_state.dialog = Some(
+            Dialog::new(question, MapDialogAction::Cancel)
+                .button(
+                    tr(locale, "trash.empty.cancel"),
+                    KeyCode::Esc,
+                    MapDialogAction::Cancel,
+                    Color::Green,
+                )
+                .button(
+                    tr(locale, "trash.empty.confirm"),
+                    KeyCode::Char('X'),
+                    MapDialogAction::EmptyTrash,
+                    Color::Red,
+                ),
+        );
+        self.clear_and_redraw();
+    }
+
+    pub fn empty_trash(&mut self) {
+        self.trash.clear();
+        self.persistence.mark_dirty();
+        self.ui_state.set_notification(Notification::TrashEmptied);
+    }
+
+    /// Shows the table of every connection (`:connections`), sorted by the note they
+    /// start at.
+    pub fn open_connection_table(&mut self) {
+        self.ui_state.connection_table = Some(ConnectionTable::new());
+        self.refresh_connection_table();
+    }
+
+    /// Lists the connections in the table again in its sort order, e.g. after some were
+    /// deleted or the order changed. Marks of connections that went are dropped.
+    pub fn refresh_connection_table(&mut self) {
+        let Some(table) = self.ui_state.connection_table.as_mut() else {
+            return;
+        };
+        let notes = self.notes_state.notes();
+        let connections = self.connections_state.connections();
+        let title = |id: usize| {
+            notes
+                .get(&id)
+                .map(|note| note_title(note).to_lowercase())
+                .unwrap_or_default()
+        };
+
+        let mut rows: Vec<usize> = (0..connections.len()).collect();
+        match table.sort {
+            ConnectionSort::From => {
+                rows.sort_by_cached_key(|&index| title(connections[index].from_id))
+            }
+            ConnectionSort::To => rows.sort_by_cached_key(|&index| title(connections[index].to_id)),
+            ConnectionSort::Color => {
+                rows.sort_by_cached_key(|&index| get_color_name_in_string(connections[index].color))
+            }
+            ConnectionSort::Weight => rows.sort_by_key(|&index| connections[index].weight()),
+        }
+        if table.descending {
+            rows.reverse();
+        }
+
+        table.marked.retain(|&index| index < connections.len());
+        table.selected = table.selected.min(rows.len().saturating_sub(1));
+        table.rows = rows;
+    }
+
+    /// Sorts the connection table by its next column, or the other way round.
+    pub fn sort_connection_table(&mut self, reverse: bool) {
+        if let Some(table) = self.ui_state.connection_table.as_mut() {
+            if reverse {
+                table.descending = !table.descending;
+            } else {
+                table.sort = table.sort.next();
+                table.descending = false;
+            }
+        }
+        self.refresh_connection_table();
+    }
+
+    /// Deletes the connections marked in the table, or the highlighted one if none
+    /// are. Connections on locked or hidden layers are kept.
+    pub fn delete_table_connections(&mut self) {
+        let Some(table) = self.ui_state.connection_table.as_mut() else {
+            return;
+        };
+        let targets = table.targets();
+        table.marked.clear();
+
+        let mut deleted = 0;
+        // From the last, so the indices of the ones still to delete stay put
+        for &index in targets.iter().rev() {
+            if self
+                .layers_state
+                .is_frozen(self.connections_state.connections()[index].layer())
+            {
+                continue;
+            }
+            self.connections_state.remove(index);
+            deleted += 1;
+        }
+
+        self.refresh_connection_table();
+        self.finish_table_change(targets.len(), deleted);
+        if deleted > 0 {
+            self.ui_state
+                .set_notification(Notification::ConnectionsDeleted(deleted));
+        }
+    }
+
+    /// Gives the connections marked in the table, or the highlighted one if none are,
+    /// the color. Connections on locked or hidden layers keep theirs.
+    pub fn recolor_table_connections(&mut self, color: Color) {
+        let Some(table) = self.ui_state.connection_table.as_ref() else {
+            return;
+        };
+        let targets = table.targets();
+
+        let mut recolored = 0;
+        for &index in &targets {
+            if self
+                .layers_state
+                .is_frozen(self.connections_state.connections()[index].layer())
+            {
+                continue;
+            }
+            self.connections_state.set_color(index, color);
+            recolored += 1;
+        }
+
+        self.refresh_connection_table();
+        self.finish_table_change(targets.len(), recolored);
+    }
+
+    /// Marks the map as changed if any of the connections a table action went for were
+    /// changed, and tells why if only some or none of them were.
+    fn finish_table_change(&mut self, targets: usize, changed: usize) {
+        if changed > 0 {
+            self.persistence.mark_dirty();
+        }
+        if changed < targets {
+            self.ui_state.set_notification(Notification::LayerLocked);
+        }
+    }
+
+    /// Deletes the selected notes like [`Self::delete_selected_note`], but puts a
+    /// placeholder note in place of each one connected to a note that stays, and
+    /// re-attaches those connections to it. Connections between deleted notes go.
+    ///
+    /// # Panics
+    /// If no note is selected.
+    pub fn delete_selected_note_keeping_connections(&mut self) {
+        let selection = self.notes_state.selection();
+        let placeholder_text = tr(self.settings.locale(), "delete.placeholder");
+
+        let mut kept = Vec::new();
+        for &id in &selection {
+            let outside: Vec<Connection> = self
+                .connections_state
+                .get_connections_for_note(id)
+                .into_iter()
+                .filter(|connection| {
+                    !selection.contains(&connection.from_id)
+                        || !selection.contains(&connection.to_id)
+                })
+                .cloned()
+                .collect();
+            if outside.is_empty() {
+                continue;
+            }
+
+            let note = &self.notes_state.notes()[&id];
+            let (x, y, layer) = (note.x, note.y, note.layer());
+            let placeholder =
+                self.notes_state
+                    .add(x, y, placeholder_text.to_string(), Color::DarkGray);
+            if let Some(note) = self.notes_state.get_mut(placeholder) {
+                note.set_layer(layer);
+            }
+            for mut connection in outside {
+                if connection.from_id == id {
+                    connection.from_id = placeholder;
+                } else {
+                    connection.to_id = placeholder;
+                }
+                kept.push(connection);
+            }
+        }
+
+        self.delete_selected_note();
+        for connection in kept {
+            self.connections_state.add(connection);
+        }
+    }
+
+    /// Creates the notes and connections described by quick-entry DSL text.
+    ///
+    /// The pasted block's top-left corner is placed at the viewport center.
+    /// Returns the number of notes created.
+    pub fn paste_dsl(&mut self, text: &str) -> Result<usize, DslError> {
+        let graph = parse_dsl(text)?;
+        Ok(self.add_graph(&graph))
+    }
+
+    /// Pastes the clipboard's text as quick-entry DSL (`p` in Normal mode) and notifies
+    /// how it went. `clipboard` is None when there's no clipboard to read.
+    pub fn paste_clipboard(&mut self, clipboard: Option<String>) {
+        let notification = match clipboard.map(|text| self.paste_dsl(&text)) {
+            None => Notification::ClipboardUnavailable,
+            Some(Ok(_)) => Notification::PasteSuccess,
+            Some(Err(DslError::MissingLabel(line))) => Notification::PasteInvalidLine(line),
+            Some(Err(DslError::Empty)) => Notification::PasteFail,
+        };
+        self.ui_state.set_notification(notification);
+    }
+
+    /// Adds the graph's notes and connections laid out around the viewport center.
+    /// Returns the number of notes added.
+    fn add_graph(&mut self, graph: &DslGraph) -> usize {
+        let (origin_x, origin_y) = self.viewport.center();
+
+        let ids: Vec<usize> = graph
+            .labels
+            .iter()
+            .zip(graph.layout())
+            .map(|(label, (x, y))| {
+                self.notes_state.add(
+                    origin_x + x as isize,
+                    origin_y + y as isize,
+                    label.clone(),
+                    Color::White,
+                )
+            })
+            .collect();
+
+        for &(from, to) in &graph.edges {
+            self.connections_state.add(Connection {
+                from_id: ids[from],
+                from_side: Side::Right,
+                to_id: ids[to],
+                to_side: Side::Left,
+                color: Color::White,
+                waypoints: vec![],
+                weight: None,
+                layer: None,
+            });
+        }
+
+        self.persistence.mark_dirty();
+
+        ids.len()
+    }
+
+    /// Builds a traversable graph of the current notes and connections.
+    pub fn graph(&self) -> Graph<String> {
+        build_graph(
+            self.notes_state.notes(),
+            self.connections_state.connections(),
+        )
+    }
+
+    /// Moves the selected note to its next status (see [`Note::cycle_status`]) and gives
+    /// the rest of the selection the same status.
+    ///
+    /// Panics if no note is selected.
+    pub fn cycle_selection_status(&mut self) {
+        let mut selected = self.notes_state.expect_selected_note().clone();
+        selected.cycle_status();
+
+        for id in self.notes_state.selection() {
+            if let Some(note) = self.notes_state.get_mut(id) {
+                note.status = selected.status;
+            }
+        }
+        self.persistence.mark_dirty();
+    }
+
+    /// Locks the selected notes against moving, editing and deleting, or unlocks them if
+    /// they all are already.
+    pub fn toggle_selection_lock(&mut self) {
+        let selection = self.notes_state.selection();
+        let lock = !selection.iter().all(|id| {
+            self.notes_state
+                .notes()
+                .get(id)
+                .is_some_and(|note| note.locked)
+        });
+
+        for id in selection {
+            if let Some(note) = self.notes_state.get_mut(id) {
+                note.locked = lock;
+            }
+        }
+        self.persistence.mark_dirty();
+    }
+
+    /// Returns true, and says so in a notification, if any of the selected notes is
+    /// locked against moving, editing and deleting.
+    pub fn selection_is_locked(&mut self) -> bool {
+        let locked = self.notes_state.selection().iter().any(|id| {
+            self.notes_state
+                .notes()
+                .get(id)
+                .is_some_and(|note| note.locked)
+        });
+        if locked {
+            self.ui_state.set_notification(Notification::NoteLocked);
+        }
+        locked
+    }
+
+    /// Moves the selected notes to the front or back of the overlapping notes, or one
+    /// step up or down.
+    pub fn restack_selection(&mut self, restack: Restack) {
+        if self.notes_state.restack(restack) {
+            self.persistence.mark_dirty();
+        }
+    }
+
+    /// Dims the notes without the given status, or shows all of them again for None.
+    /// Returns the number of notes with the status.
+    pub fn filter_by_status(&mut self, status: Option<NoteStatus>) -> usize {
+        self.ui_state.status_filter = status;
+        self.notes_state
+            .notes()
+            .values()
+            .filter(|note| status.is_some() && note.status == status)
+            .count()
+    }
+
+    /// Shows the prompt to edit the selected note's title, starting from its current one.
+    ///
+    /// Panics if no note is selected.
+    pub fn open_title_prompt(&mut self) {
+        if self.selection_is_locked() {
+            return;
+        }
+        let title = self.notes_state.expect_selected_note().title.clone();
+        self.ui_state.title_prompt = Some(title.unwrap_or_default());
+    }
+
+    /// Sets the selected note's title; a blank one removes it.
+    ///
+    /// Panics if no note is selected.
+    pub fn set_selected_note_title(&mut self, title: &str) {
+        let note = self.notes_state.expect_selected_note_mut();
+        let previous = note.title.clone();
+        note.set_title(title);
+        if note.title != previous {
+            self.persistence.mark_dirty();
+        }
+    }
+
+    /// Switches to Edit mode, using modal editing if enabled in settings.
+    ///
+    /// Block cursor provides visual feedback that modal editing is active (vim-style).
+    /// Locked (still encrypted) notes can't be edited until they are unlocked, and
+    /// neither can notes locked with `L`.
+    pub fn switch_to_edit_mode(&mut self) {
+        if let Some(id) = self.notes_state.selected_note_id()
+            && self.note_is_locked(id)
+        {
+            return;
+        }
+        if self.selection_is_locked() {
+            return;
+        }
+
+        self.load_dictionary_with_fs(&RealFileSystem);
+
+        if self.settings.edit_modal {
+            let _ = execute!(stdout(), SetCursorStyle::SteadyBlock);
+            self.mode = Mode::EditNormal;
+        } else {
+            self.mode = Mode::Edit;
+        }
+    }
+
+    /// Loads the spell checking dictionary chosen in the settings, unless it already is.
+    pub fn load_dictionary_with_fs(&mut self, fs: &dyn FileSystem) {
+        let Some(name) = self.settings.spell_dictionary.clone() else {
+            return;
+        };
+        if self.spelling.dictionary(&name).is_some() {
+            return;
+        }
+
+        let dirs = dictionary_dirs(fs.get_home_dir().as_deref());
+        match Dictionary::load(&dirs, &name) {
+            Ok(dictionary) => self.spelling.set_dictionary(&name, dictionary),
+            Err(_) => self
+                .ui_state
+                .set_notification(Notification::DictionaryMissing),
+        }
+    }
+
+    /// The dictionary notes are spell checked with, None if spell checking is off or
+    /// the dictionary couldn't be loaded.
+    pub fn spell_dictionary(&self) -> Option<&Dictionary> {
+        self.spelling
+            .dictionary(self.settings.spell_dictionary.as_ref()?)
+    }
+
+    /// Replaces the misspelled word at the cursor with a suggested spelling (`F7` in
+    /// Edit mode). Pressed again, puts the next suggestion in its place, and after the
+    /// last one the word as it was typed.
+    pub fn cycle_spelling_suggestion(&mut self) {
+        let Some(id) = self.notes_state.selected_note_id() else {
+            return;
+        };
+        let dictionary = (self.settings.spell_dictionary.as_ref())
+            .and_then(|name| self.spelling.dictionary(name));
+        let Some(dictionary) = dictionary else {
+            let notification = match self.settings.spell_dictionary {
+                Some(_) => Notification::DictionaryMissing,
+                None => Notification::SpellCheckOff,
+            };
+            self.ui_state.set_notification(notification);
+            return;
+        };
+        let cursor = self.notes_state.cursor_pos();
+        let content = &self.notes_state.expect_selected_note().content;
+
+        // Carry on with the word replaced last, unless it was edited since
+        let continued = self.spelling.cycle.as_ref().is_some_and(|cycle| {
+            let range = cycle.range();
+            cycle.note_id == id
+                && content.get(range.clone()) == Some(cycle.current())
+                && range.start <= cursor
+                && cursor <= range.end
+        });
+        let replaced = if continued {
+            let cycle = self.spelling.cycle.as_mut().expect("checked above");
+            let range = cycle.range();
+            cycle.index = (cycle.index + 1) % (cycle.suggestions.len() + 1);
+            range
+        } else {
+            let suggestions = word_at(content, cursor)
+                .filter(|range| !dictionary.check(&content[range.clone()]))
+                .map(|range| (dictionary.suggest(&content[range.clone()]), range));
+            let Some((suggestions, range)) = suggestions.filter(|(s, _)| !s.is_empty()) else {
+                self.ui_state
+                    .set_notification(Notification::NoSpellingSuggestions);
+                return;
+            };
+            self.spelling.cycle = Some(SuggestionCycle {
+                note_id: id,
+                start: range.start,
+                original: content[range.clone()].to_string(),
+                suggestions,
+                index: 0,
+            });
+            range
+        };
+
+        let cycle = self.spelling.cycle.as_ref().expect("set above");
+        let word = cycle.current().to_string();
+        let notification = match cycle.suggestions.get(cycle.index) {
+            Some(_) => Notification::SpellingSuggestion(cycle.index + 1),
+            None => Notification::SpellingRestored,
+        };
+        self.notes_state
+            .expect_selected_note_mut()
+            .content
+            .replace_range(replaced.clone(), &word);
+        // The cursor stays on the word in Edit Normal mode, after it otherwise
+        let cursor = match self.mode {
+            Mode::EditNormal => replaced.start,
+            _ => replaced.start + word.len(),
+        };
+        self.notes_state.set_cursor_pos(cursor);
+        self.persistence.mark_dirty();
+        self.ui_state.set_notification(notification);
+    }
+
+    /// Selects the note closest to the viewport center and enters Visual mode.
+    ///
+    /// Uses Manhattan distance from viewport center to note top-left corner.
+    pub fn select_note(&mut self) {
+        let (screen_center_x, screen_center_y) = self.viewport.center();
+
+        if let Some(id) =
+            self.notes_state
+                .find_closest_note(screen_center_x, screen_center_y, |note| {
+                    !self.layers_state.is_frozen(note.layer())
+                })
+        {
+            self.notes_state.select(id);
+            self.mode = Mode::Visual;
+        }
+    }
+
+    /// Returns true for encrypted notes that haven't been unlocked this session.
+    pub fn note_is_locked(&self, id: usize) -> bool {
+        self.notes_state
+            .notes()
+            .get(&id)
+            .is_some_and(|note| note.encrypted.is_some() && !self.vault.is_unlocked(id))
+    }
+
+    /// Loads the plugin scripts from the plugins directory, reporting the ones that
+    /// don't compile.
+    pub fn load_plugins_with_fs(&mut self, fs: &dyn FileSystem) {
+        let Some(home) = fs.get_home_dir() else {
+            return;
+        };
+        let (plugins, errors) = Plugins::load(&plugins_dir(&home));
+        self.plugins = plugins;
+        self.report_plugin_errors(&errors);
+    }
+
+    fn report_plugin_errors(&mut self, errors: &[PluginError]) {
+        if errors.is_empty() {
+            return;
+        }
+        self.plugins.log_errors(errors, Local::now());
+        self.ui_state.set_notification(Notification::PluginFailed);
+    }
+
+    /// The map as plugin scripts see it, without the encrypted notes that weren't
+    /// unlocked.
+    pub fn plugin_map(&self) -> PluginMap {
+        let notes = self
+            .notes_state
+            .notes()
+            .iter()
+            .filter(|(id, _)| !self.note_is_locked(**id))
+            .map(|(&id, note)| {
+                let note = PluginNote {
+                    x: note.x,
+                    y: note.y,
+                    content: note.content.clone(),
+                };
+                (id, note)
+            })
+            .collect();
+        let connections = self
+            .connections_state
+            .connections()
+            .iter()
+            .map(|connection| (connection.from_id, connection.to_id))
+            .collect();
+
+        PluginMap {
+            path: self.persistence.file_write_path.clone(),
+            notes,
+            connections,
+            next_id: self.notes_state.next_note_id_counter(),
+            edits: vec![],
+        }
+    }
+
+    /// Calls a function of the plugin scripts with note ids as arguments and applies
+    /// the changes they made. Returns whether every script ran it without failing, None
+    /// if no script defines the function.
+    pub fn run_plugin_function(&mut self, function: &str, args: &[usize]) -> Option<bool> {
+        if !self.plugins.defines(function, args.len()) {
+            return None;
+        }
+
+        let (plugin_map, errors) = self.plugins.call(function, args, self.plugin_map());
+        self.apply_plugin_edits(plugin_map.edits);
+        self.report_plugin_errors(&errors);
+        Some(errors.is_empty())
+    }
+
+    /// Applies the changes of plugin scripts. Changes to locked notes are left out, as
+    /// are connections repeating another one unless parallel connections are allowed.
+    fn apply_plugin_edits(&mut self, edits: Vec<PluginEdit>) {
+        if edits.is_empty() {
+            return;
+        }
+
+        // Ids of the notes the scripts added, as given by the scripts
+        let mut added: HashMap<usize, usize> = HashMap::new();
+        let note_id = |added: &HashMap<usize, usize>, id: usize| *added.get(&id).unwrap_or(&id);
+
+        for edit in edits {
+            match edit {
+                PluginEdit::AddNote(plugin_id, note) => {
+                    let id = self
+                        .notes_state
+                        .add(note.x, note.y, note.content, Color::White);
+                    if let Some(note) = self.notes_state.get_mut(id) {
+                        note.set_layer(self.layers_state.active);
+                    }
+                    added.insert(plugin_id, id);
+                }
+                PluginEdit::SetContent(id, content) => {
+                    let id = note_id(&added, id);
+                    if self.note_is_locked(id) {
+                        continue;
+                    }
+                    if let Some(note) = self.notes_state.get_mut(id)
+                        && !note.locked
+                    {
+                        note.content = content;
+                    }
+                }
+                PluginEdit::MoveNote(id, x, y) => {
+                    if let Some(note) = self.notes_state.get_mut(note_id(&added, id))
+                        && !note.locked
+                    {
+                        (note.x, note.y) = (x, y);
+                    }
+                }
+                PluginEdit::Connect(from, to) => {
+                    let connection = Connection {
+                        from_id: note_id(&added, from),
+                        from_side: Side::Right,
+                        to_id: note_id(&added, to),
+                        to_side: Side::Left,
+                        color: Color::White,
+                        waypoints: vec![],
+                        weight: None,
+                        layer: None,
+                    };
+                    let repeated = (self.connections_state.connections().iter())
+                        .any(|other| other.has_same_ends(&connection));
+                    if repeated && !self.settings.parallel_connections {
+                        continue;
+                    }
+                    self.connections_state.add(connection);
+                }
+            }
+        }
+
+        self.persistence.mark_dirty();
+        self.clear_and_redraw();
+    }
+
+    /// Returns true for notes on a hidden layer.
+    pub fn note_is_hidden(&self, id: usize) -> bool {
+        self.notes_state
+            .notes()
+            .get(&id)
+            .is_some_and(|note| self.layers_state.is_hidden(note.layer()))
+    }
+
+    /// Returns true for notes that can be selected: ones not on a hidden or locked layer.
+    pub fn note_is_selectable(&self, id: usize) -> bool {
+        self.notes_state
+            .notes()
+            .get(&id)
+            .is_some_and(|note| !self.layers_state.is_frozen(note.layer()))
+    }
+
+    /// Returns true for connections that aren't drawn: ones on a hidden layer or
+    /// attached to a note on one.
+    pub fn connection_is_hidden(&self, connection: &Connection) -> bool {
+        self.layers_state.is_hidden(connection.layer())
+            || self.note_is_hidden(connection.from_id)
+            || self.note_is_hidden(connection.to_id)
+    }
+
+    /// Returns true for fan-outs that aren't drawn: ones on a hidden layer or from a
+    /// note on one. Their branches to notes on hidden layers aren't drawn either.
+    pub fn hyperedge_is_hidden(&self, hyperedge: &Hyperedge) -> bool {
+        self.layers_state.is_hidden(hyperedge.layer()) || self.note_is_hidden(hyperedge.from_id)
+    }
+
+    /// Puts the selected notes on the active layer (`Y` in Visual mode).
+    ///
+    /// Refused with a notification if the active layer is hidden or locked, as the
+    /// notes couldn't stay selected there.
+    ///
+    /// # Panics
+    /// If no note is selected.
+    pub fn move_selection_to_active_layer(&mut self) {
+        self.notes_state.expect_selected_note_id();
+
+        let active = self.layers_state.active;
+        if self.layers_state.is_frozen(active) {
+            self.ui_state.set_notification(Notification::LayerLocked);
+            return;
+        }
+
+        for id in self.notes_state.selection() {
+            if let Some(note) = self.notes_state.get_mut(id) {
+                note.set_layer(active);
+            }
+        }
+        self.persistence.mark_dirty();
+    }
+
+    /// Toggles per-note encryption on the selected note.
+    ///
+    /// - Plain note: becomes encrypted (stays readable for the rest of the session)
+    /// - Locked note: asks for the passphrase to unlock it
+    /// - Unlocked encrypted note: encryption is removed and it is stored as plain text again
+    ///
+    /// Asks for the session passphrase first if it hasn't been entered yet.
+    ///
+    /// # Panics
+    /// If no note is selected.
+    pub fn toggle_note_encryption(&mut self) {
+        let id = self.notes_state.expect_selected_note_id();
+
+        if self.note_is_locked(id) {
+            // The session passphrase may not be the one this note was encrypted with
+            let unlocked = self.vault.has_passphrase() && self.unlock_selected_note().is_ok();
+            if !unlocked {
+                self.ui_state.passphrase_prompt =
+                    Some(PassphrasePrompt::new(PassphrasePurpose::UnlockNote));
+            }
+            return;
+        }
+
+        let note = self.notes_state.expect_selected_note_mut();
+        if note.encrypted.is_some() {
+            note.encrypted = None;
+            self.vault.forget(id);
+            self.persistence.mark_dirty();
+        } else if self.vault.has_passphrase() {
+            self.encrypt_selected_note();
+        } else {
+            self.ui_state.passphrase_prompt =
+                Some(PassphrasePrompt::new(PassphrasePurpose::EncryptNote));
+        }
+    }
+
+    /// Stores the passphrase typed into the prompt and carries out the pending action.
+    ///
+    /// If the passphrase can't decrypt the note, the prompt stays open with an error.
+    /// A new map file passphrase is asked for twice and doesn't touch the session
+    /// passphrase of the notes.
+    pub fn submit_passphrase(&mut self) {
+        let Some(mut prompt) = self.ui_state.passphrase_prompt.take() else {
+            return;
+        };
+
+        match prompt.purpose {
+            PassphrasePurpose::EncryptMap => {
+                let mut confirm_prompt =
+                    PassphrasePrompt::new(PassphrasePurpose::ConfirmMapPassphrase);
+                confirm_prompt.first_entry = Some(prompt.input);
+                self.ui_state.passphrase_prompt = Some(confirm_prompt);
+                return;
+            }
+            PassphrasePurpose::ConfirmMapPassphrase => {
+                if prompt.first_entry.take() == Some(prompt.input.clone()) {
+                    self.encrypt_map_file(&prompt.input);
+                } else {
+                    self.ui_state
+                        .set_notification(Notification::PassphraseMismatch);
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        self.vault.set_passphrase(prompt.input);
+
+        match prompt.purpose {
+            PassphrasePurpose::EncryptNote => self.encrypt_selected_note(),
+            PassphrasePurpose::UnlockNote => {
+                if let Err(err) = self.unlock_selected_note() {
+                    self.vault.clear_passphrase();
+                    let mut prompt = PassphrasePrompt::new(PassphrasePurpose::UnlockNote);
+                    prompt.error = Some(err);
+                    self.ui_state.passphrase_prompt = Some(prompt);
+                }
+            }
+            PassphrasePurpose::EncryptMap | PassphrasePurpose::ConfirmMapPassphrase => {}
+        }
+    }
+
+    /// Encrypts the map file at rest with the passphrase from now on, and saves it
+    /// right away so no plain copy is left behind.
+    fn encrypt_map_file(&mut self, passphrase: &str) {
+        self.persistence.file_key = Some(FileKey::new(passphrase));
+        self.save_file_key_change(Notification::MapEncrypted);
+    }
+
+    /// Stores the map file unencrypted again, saving it right away.
+    fn decrypt_map_file(&mut self) {
+        self.persistence.file_key = None;
+        self.save_file_key_change(Notification::MapDecrypted);
+    }
+
+    fn save_file_key_change(&mut self, success_notif: Notification) {
+        let path = self.persistence.file_write_path.clone();
+        let notification = match save_map_file(self, &path) {
+            Ok(_) => success_notif,
+            Err(_) => {
+                // Saved with the new key later, e.g. by auto-save
+                self.persistence.mark_dirty();
+                Notification::SaveFail
+            }
+        };
+        self.ui_state.set_notification(notification);
+    }
+
+    fn encrypt_selected_note(&mut self) {
+        let id = self.notes_state.expect_selected_note_id();
+        let content = self.notes_state.expect_selected_note().content.clone();
+
+        if let Some(encrypted) = self.vault.encrypt(&content) {
+            self.notes_state.expect_selected_note_mut().encrypted = Some(encrypted);
+            self.vault.mark_unlocked(id);
+            self.persistence.mark_dirty();
+        }
+    }
+
+    fn unlock_selected_note(&mut self) -> Result<(), crate::utils::CryptoError> {
+        let id = self.notes_state.expect_selected_note_id();
+        let Some(encrypted) = self.notes_state.expect_selected_note().encrypted.clone() else {
+            return Ok(());
+        };
+
+        let plaintext = self.vault.decrypt(&encrypted)?;
+        self.notes_state.expect_selected_note_mut().content = plaintext;
+        self.vault.mark_unlocked(id);
+        Ok(())
+    }
+
+    /// Runs a command typed on the `:` command line and reports the outcome in the bar.
+    pub fn run_command(&mut self, input: &str) {
+        let notification = match Command::parse(input) {
+            Ok(Command::Export(scope, format, path)) => {
+                let path = path.unwrap_or_else(|| {
+                    let file_write_path = &self.persistence.file_write_path;
+                    let name = map_name(file_write_path);
+                    file_write_path.with_file_name(format!("{name}.{}", format.extension()))
+                });
+                match self.export_to(format, scope, &path) {
+                    Ok(true) => Notification::ExportSuccess,
+                    Ok(false) => Notification::ExportNothingSelected,
+                    Err(_) => Notification::ExportFail,
+                }
+            }
+            Ok(Command::Import(path)) => match self.import_outline_from(&path) {
+                Ok(_) => Notification::ImportSuccess,
+                Err(_) => Notification::ImportFail,
+            },
+            Ok(Command::ImportNotes(format, path)) => {
+                match self.import_note_list_from(&path, format) {
+                    Ok(count) => Notification::NotesImported(count),
+                    Err(NoteListError::Csv(line)) => Notification::NoteListInvalidRow(line),
+                    Err(_) => Notification::NoteListFail,
+                }
+            }
+            // Both report their outcome themselves
+            Ok(Command::Encrypt) => {
+                self.ui_state.passphrase_prompt =
+                    Some(PassphrasePrompt::new(PassphrasePurpose::EncryptMap));
+                return;
+            }
+            Ok(Command::Decrypt) => {
+                self.decrypt_map_file();
+                return;
+            }
+            Ok(Command::Snapshot(name)) => match self.create_snapshot(&name, Local::now()) {
+                Ok(_) => Notification::SnapshotSuccess,
+                Err(_) => Notification::SnapshotFail,
+            },
+            Ok(Command::Snapshots) => match self.open_snapshot_picker() {
+                Ok(_) => return,
+                Err(_) => Notification::SnapshotFail,
+            },
+            // Reports itself when the map doesn't fit
+            Ok(Command::Fit) => {
+                self.fit_to_content();
+                return;
+            }
+            Ok(Command::Filter(status)) => match self.filter_by_status(status) {
+                _ if status.is_none() => Notification::FilterCleared,
+                count => Notification::Filtered(count),
+            },
+            Ok(Command::Trash) => {
+                self.trash.open_browser();
+                return;
+            }
+            Ok(Command::Connections) => {
+                self.open_connection_table();
+                return;
+            }
+            Ok(Command::Diff(path)) => match self.read_map_copy(&path) {
+                Some(map_data) => {
+                    self.open_diff_view(map_name(&path), map_data);
+                    return;
+                }
+                None => Notification::DiffFail,
+            },
+            Ok(Command::Substitute(substitution)) => self.substitute(&substitution),
+            Ok(Command::Grep(pattern)) => match self.grep_workspace(&pattern) {
+                Some(notification) => notification,
+                None => return,
+            },
+            Ok(Command::Plugin(function)) => match self.run_plugin_function(&function, &[]) {
+                Some(true) => Notification::PluginRan,
+                // Reported with the errors
+                Some(false) => return,
+                None => Notification::PluginFunctionMissing,
+            },
+            Ok(Command::Run(name)) => self.run_user_command(&name),
+            Err(_) => Notification::UnknownCommand,
+        };
+        self.ui_state.set_notification(notification);
+    }
+
+    /// Runs a search-and-replace over the content of every note, skipping locked ones.
+    ///
+    /// All replacements form a single undo step. Returns the notification reporting
+    /// the number of replacements.
+    pub fn substitute(&mut self, substitution: &Substitution) -> Notification {
+        let Ok(matcher) = substitution.matcher() else {
+            return Notification::InvalidPattern;
+        };
+
+        let mut ids: Vec<usize> = self.notes_state.notes().keys().copied().collect();
+        ids.sort_unstable();
+
+        let mut previous_contents = vec![];
+        let mut replacements = 0;
+        for id in ids {
+            if self.note_is_locked(id) {
+                continue;
+            }
+            let Some(note) = self.notes_state.get_mut(id) else {
+                continue;
+            };
+            if note.locked {
+                continue;
+            }
+            if let Some((content, count)) = substitution.apply(&matcher, &note.content) {
+                previous_contents.push((id, std::mem::replace(&mut note.content, content)));
+                replacements += count;
+            }
+        }
+
+        if previous_contents.is_empty() {
+            return Notification::PatternNotFound;
+        }
+        self.history.push(UndoStep::NoteContents(previous_contents));
+        self.persistence.mark_dirty();
+        Notification::Replaced(replacements)
+    }
+
+    /// Runs the external program of the user command with that name (`:run <name>`),
+    /// giving it the selected note's content or the whole map. A command replacing the
+    /// note's content with the program's output can be undone.
+    ///
+    /// Returns the notification reporting the outcome.
+    pub fn run_user_command(&mut self, name: &str) -> Notification {
+        let Some(user_command) = (self.settings.user_commands.iter())
+            .find(|user_command| user_command.name == name)
+            .cloned()
+        else {
+            return Notification::UnknownUserCommand;
+        };
+        let map_path = self.persistence.file_write_path.clone();
+
+        let note_id = match user_command.input {
+            CommandInput::Note => {
+                let Some(id) = self.notes_state.selected_note_id() else {
+                    return Notification::UserCommandNoNote;
+                };
+                let locked = self.notes_state.notes()[&id].locked && user_command.replace;
+                if self.note_is_locked(id) || locked {
+                    return Notification::NoteLocked;
+                }
+                Some(id)
+            }
+            CommandInput::Map => None,
+        };
+        let input = match note_id {
+            Some(id) => self.notes_state.notes()[&id].content.clone(),
+            None => match serde_json::to_string_pretty(&MapData::from_map_state(self)) {
+                Ok(json) => json,
+                Err(_) => return Notification::UserCommandFailed,
+            },
+        };
+
+        let Ok(output) = run_program(&user_command.command, &input, &map_path) else {
+            return Notification::UserCommandFailed;
+        };
+        if let Some(id) = note_id
+            && user_command.replace
+        {
+            // Programs end their output with a line break the note didn't have
+            let output = output.strip_suffix('\n').unwrap_or(&output).to_string();
+            if let Some(note) = self.notes_state.get_mut(id)
+                && note.content != output
+            {
+                let previous = std::mem::replace(&mut note.content, output);
+                self.history
+                    .push(UndoStep::NoteContents(vec![(id, previous)]));
+                self.persistence.mark_dirty();
+            }
+        }
+        Notification::UserCommandDone
+    }
+
+    /// Takes back the most recent undo step (`u` in Normal mode).
+    pub fn undo(&mut self) {
+        let Some(step) = self.history.pop() else {
+            self.ui_state.set_notification(Notification::NothingToUndo);
+            return;
+        };
+
+        match step {
+            UndoStep::NoteContents(contents) => {
+                for (id, content) in contents {
+                    if let Some(note) = self.notes_state.get_mut(id) {
+                        note.content = content;
+                    }
+                }
+            }
+        }
+
+        self.persistence.mark_dirty();
+        self.ui_state.set_notification(Notification::Undone);
+    }
+
+    /// Writes the notes in `scope` to `path` in the given format. Returns false, writing
+    /// nothing, if the scope needs a selection and no note is selected.
+    pub fn export_to(
+        &self,
+        format: ExportFormat,
+        scope: ExportScope,
+        path: &Path,
+    ) -> std::io::Result<bool> {
+        let Some((notes, connections)) = self.notes_in_scope(scope) else {
+            return Ok(false);
+        };
+        let title = map_name(&self.persistence.file_write_path);
+
+        let document = match format {
+            ExportFormat::Mermaid => export_mermaid(&notes, &connections),
+            ExportFormat::Svg => export_svg(&notes, &connections),
+            ExportFormat::Opml => export_opml(&title, &notes, &connections),
+            ExportFormat::FreeMind => export_freemind(&title, &notes, &connections),
+            ExportFormat::Html => export_html(&title, &notes, &connections),
+            ExportFormat::Dot => export_dot(&title, &notes, &connections),
+        };
+        std::fs::write(path, document)?;
+        Ok(true)
+    }
+
+    /// The notes `:export` writes for `scope`, with the connections between them.
+    /// Connections leading out of the scope are left out, so nothing outside of it
+    /// shows up in the export. None if the scope needs a selection and there is none.
+    pub fn notes_in_scope(
+        &self,
+        scope: ExportScope,
+    ) -> Option<(HashMap<usize, Note>, Vec<Connection>)> {
+        let notes = self.notes_state.notes();
+        let ids: HashSet<usize> = match scope {
+            ExportScope::Map => notes.keys().copied().collect(),
+            ExportScope::Selection => {
+                self.notes_state.selected_note_id()?;
+                self.notes_state.selection().into_iter().collect()
+            }
+            ExportScope::Branch => {
+                let id = self.notes_state.selected_note_id()?;
+                reachable_from(&self.graph(), id).into_iter().collect()
+            }
+        };
+
+        let notes = notes
+            .iter()
+            .filter(|(id, _)| ids.contains(id))
+            .map(|(&id, note)| (id, note.clone()))
+            .collect();
+        let connections = self
+            .connections_state
+            .connections()
+            .iter()
+            .filter(|connection| {
+                ids.contains(&connection.from_id) && ids.contains(&connection.to_id)
+            })
+            .cloned()
+            .collect();
+        Some((notes, connections))
+    }
+
+    /// Adds the notes of an OPML or FreeMind outline file at the viewport center, laid
+    /// out as a tree, see [`parse_outline`]. Returns the number of notes added.
+    pub fn import_outline_from(&mut self, path: &Path) -> Result<usize, OutlineError> {
+        let text = std::fs::read_to_string(path).map_err(|_| OutlineError::Read)?;
+        let graph = parse_outline(&text)?;
+        Ok(self.add_graph(&graph))
+    }
+
+    /// Adds the notes listed in a plain text or CSV file, see [`parse_note_list`]. Notes
+    /// without a position are laid out in a grid from the viewport center. Returns the
+    /// number of notes added.
+    pub fn import_note_list_from(
+        &mut self,
+        path: &Path,
+        format: NoteListFormat,
+    ) -> Result<usize, NoteListError> {
+        let text = std::fs::read_to_string(path).map_err(|_| NoteListError::Read)?;
+        let listed = parse_note_list(&text, format)?;
+
+        let (origin_x, origin_y) = self.viewport.center();
+        let sizes: Vec<(u16, u16)> = listed
+            .iter()
+            .filter(|listed| listed.position.is_none())
+            .map(|listed| Note::new(0, 0, listed.content.clone(), listed.color).get_dimensions())
+            .collect();
+        let mut grid = grid_layout(&sizes).into_iter();
+
+        for listed in &listed {
+            let (x, y) = match listed.position {
+                Some(position) => position,
+                None => {
+                    let (x, y) = grid.next().unwrap_or_default();
+                    (origin_x + x as isize, origin_y + y as isize)
+                }
+            };
+            let id = self
+                .notes_state
+                .add(x, y, listed.content.clone(), listed.color);
+            if let Some(note) = self.notes_state.get_mut(id) {
+                note.set_layer(self.layers_state.active);
+            }
+        }
+        self.persistence.mark_dirty();
+
+        Ok(listed.len())
+    }
+
+    /// Snapshots the whole map into a named milestone stored next to the map file.
+    ///
+    /// An empty name is replaced by the current date and time.
+    pub fn create_milestone(&mut self, name: &str, now: DateTime<Local>) -> Result<(), TmmprError> {
+        let name = match name.trim() {
+            "" => now.format("%Y-%m-%d %H:%M").to_string(),
+            name => name.to_string(),
+        };
+        let milestone = Milestone {
+            name,
+            created_at: now,
+            map: MapData::from_map_state(self),
+        };
+
+        add_milestone(&self.persistence.file_write_path, milestone)
+    }
+
+    /// Shows the list of this map's milestones, newest first.
+    pub fn open_milestone_picker(&mut self) -> Result<(), TmmprError> {
+        let mut milestones = read_milestones(&self.persistence.file_write_path)?;
+        milestones.reverse();
+
+        self.milestones.picker = Some(MilestonePicker {
+            milestones,
+            selected: 0,
+        });
+        Ok(())
+    }
+
+    /// Opens the milestone highlighted in the picker read-only in the milestone tab,
+    /// replacing any milestone that was open.
+    pub fn open_selected_milestone(&mut self) {
+        let Some(picker) = self.milestones.picker.take() else {
+            return;
+        };
+        let Some(milestone) = picker.milestones.into_iter().nth(picker.selected) else {
+            return;
+        };
+
+        let mut map_state =
+            MapState::new_with_settings(self.persistence.file_write_path.clone(), Settings::new());
+        map_state.load_map_data(milestone.map);
+
+        self.milestones.open = Some(OpenMilestone {
+            name: milestone.name,
+            map_state: Box::new(map_state),
+        });
+        self.milestones.showing_milestone = true;
+    }
+
+    /// Saves the map as a named snapshot in its snapshots directory, see
+    /// [`snapshot_path`]. The map file and its unsaved changes are left alone.
+    pub fn create_snapshot(&mut self, name: &str, now: DateTime<Local>) -> Result<(), TmmprError> {
+        let map_path = self.persistence.file_write_path.clone();
+        let path = snapshot_path(
+            &map_path,
+            name,
+            now.naive_local(),
+            is_compressed_map_path(&map_path),
+        );
+
+        std::fs::create_dir_all(snapshots_dir(&map_path))?;
+        write_map_copy(self, &path)?;
+        Ok(())
+    }
+
+    /// Shows the list of this map's snapshots, newest first.
+    pub fn open_snapshot_picker(&mut self) -> Result<(), TmmprError> {
+        let snapshots = list_snapshots(&self.persistence.file_write_path)?;
+        self.ui_state.snapshot_picker = Some(SnapshotPicker::new(snapshots));
+        Ok(())
+    }
+
+    /// Shows the list of the workspace's maps with this one highlighted.
+    pub fn open_workspace_picker(&mut self) {
+        let Some(workspace) = &self.workspace else {
+            self.ui_state.set_notification(Notification::NoWorkspace);
+            return;
+        };
+
+        match workspace.list_maps() {
+            Ok(maps) => {
+                let picker = WorkspacePicker::new(maps, &self.persistence.file_write_path);
+                self.ui_state.workspace_picker = Some(picker);
+            }
+            Err(_) => self.ui_state.set_notification(Notification::MapSwitchFail),
+        }
+    }
+
+    /// Searches the notes of every map in the workspace and lists the matching ones.
+    /// Returns the notification to show instead if there's nothing to list.
+    pub fn grep_workspace(&mut self, pattern: &str) -> Option<Notification> {
+        let Some(workspace) = &self.workspace else {
+            return Some(Notification::NoWorkspace);
+        };
+        let search = SearchState::with_query(pattern);
+        if pattern.is_empty() || search.is_invalid() {
+            return Some(Notification::InvalidPattern);
+        }
+
+        let results = search_workspace(
+            workspace,
+            &search,
+            &self.persistence.file_write_path,
+            self.notes_state.notes(),
+            &self.layers_state,
+        );
+        if results.is_empty() {
+            return Some(Notification::PatternNotFound);
+        }
+        self.ui_state.workspace_search = Some(WorkspaceSearch::new(pattern.to_string(), results));
+        None
+    }
+
+    /// Centers the view on a note found by a search and highlights the query's matches
+    /// like `/` does, with `n`/`N` continuing from the note.
+    pub fn show_search_match(&mut self, query: &str, note_id: usize) {
+        let Some(note) = self.notes_state.notes().get(&note_id) else {
+            return;
+        };
+        let (x, y) = note.center();
+        let mut search = SearchState::with_query(query);
+        search.current = search.matches_in_note(note_id, note).into_iter().next();
+
+        self.viewport.center_on(x, y);
+        self.ui_state.search = Some(search);
+        self.clear_and_redraw();
+    }
+
+    /// Replaces the open map's contents with the snapshot highlighted in the picker and
+    /// closes the picker. The map file keeps its contents until the next save.
+    pub fn load_selected_snapshot(&mut self) {
+        let Some(picker) = self.ui_state.snapshot_picker.take() else {
+            return;
+        };
+        let map_data = picker
+            .selected_snapshot()
+            .and_then(|snapshot| self.read_map_copy(&snapshot.path));
+
+        let Some(map_data) = map_data else {
+            self.ui_state.set_notification(Notification::SnapshotFail);
+            return;
+        };
+        self.replace_map(map_data);
+        self.persistence.mark_dirty();
+        self.ui_state.set_notification(Notification::SnapshotLoaded);
+    }
+
+    /// Compares the snapshot highlighted in the picker with the open map and shows the
+    /// differences in the picker.
+    pub fn diff_selected_snapshot(&mut self) {
+        let map_data = self
+            .ui_state
+            .snapshot_picker
+            .as_ref()
+            .and_then(|picker| picker.selected_snapshot())
+            .and_then(|snapshot| self.read_map_copy(&snapshot.path));
+
+        let Some(map_data) = map_data else {
+            self.ui_state.snapshot_picker = None;
+            self.ui_state.set_notification(Notification::SnapshotFail);
+            return;
+        };
+        let diff = diff_maps(
+            self.notes_state.notes(),
+            self.connections_state.connections(),
+            &map_data.notes,
+            &map_data.connections,
+        );
+        if let Some(picker) = self.ui_state.snapshot_picker.as_mut() {
+            picker.diff = Some(diff);
+        }
+    }
+
+    /// Opens the snapshot highlighted in the picker in a diff view, see
+    /// [`MapState::open_diff_view`], and closes the picker.
+    pub fn view_selected_snapshot_diff(&mut self) {
+        let Some(picker) = self.ui_state.snapshot_picker.take() else {
+            return;
+        };
+        let Some(snapshot) = picker.selected_snapshot() else {
+            return;
+        };
+
+        match self.read_map_copy(&snapshot.path) {
+            Some(map_data) => self.open_diff_view(snapshot.name.clone(), map_data),
+            None => self.ui_state.set_notification(Notification::SnapshotFail),
+        }
+    }
+
+    /// Shows how the open map differs from an older version of it on a read-only
+    /// canvas: the open map with added and changed notes highlighted, and the notes and
+    /// connections it no longer has drawn back in.
+    pub fn open_diff_view(&mut self, name: String, old_map: MapData) {
+        let mut shown_map = self.open_map_data();
+        let changes = map_changes(
+            &old_map.notes,
+            &old_map.connections,
+            &shown_map.notes,
+            &shown_map.connections,
+        );
+        if changes.is_empty() {
+            self.ui_state.set_notification(Notification::NoDifferences);
+            return;
+        }
+
+        for (&id, change) in &changes.notes {
+            if *change == NoteChange::Removed {
+                shown_map.notes.insert(id, old_map.notes[&id].clone());
+                shown_map.render_order.push(id);
+            }
+        }
+        shown_map
+            .connections
+            .extend(changes.connections_removed.iter().cloned());
+
+        let mut map_state = MapState::new_with_settings(
+            self.persistence.file_write_path.clone(),
+            self.settings.clone(),
+        );
+        map_state.load_map_data(shown_map);
+        map_state.ui_state.map_changes = Some(changes);
+
+        self.diff_view = Some(DiffView {
+            name,
+            map_state: Box::new(map_state),
+        });
+    }
+
+    /// Reads a copy of this map, such as a backup or snapshot. An encrypted copy is
+    /// readable as long as it's encrypted with the key the map was opened with.
+    pub fn read_map_copy(&self, path: &Path) -> Option<MapData> {
+        match read_map_file(path).ok()? {
+            MapFileContents::Plain(map_data) => Some(*map_data),
+            MapFileContents::Encrypted(encrypted) => {
+                decrypt_map_data(&encrypted, self.persistence.file_key.as_ref()?).ok()
+            }
+        }
+    }
+
+    /// Notes as they should be written to disk.
+    ///
+    /// Unlocked encrypted notes are re-encrypted with their current content and their
+    /// plaintext is stripped. Locked notes are already stored as ciphertext only.
+    pub fn notes_for_saving(&mut self) -> HashMap<usize, Note> {
+        let mut notes = self.notes_state.notes().clone();
+
+        for (id, note) in notes.iter_mut() {
+            if note.encrypted.is_some() && self.vault.is_unlocked(*id) {
+                if let Some(encrypted) = self.vault.encrypt(&note.content) {
+                    // The open note keeps the stored ciphertext, so it compares equal to
+                    // the file, see [`diff_maps`]
+                    if let Some(open_note) = self.notes_state.get_mut(*id) {
+                        open_note.encrypted = Some(encrypted.clone());
+                    }
+                    note.encrypted = Some(encrypted);
+                }
+                note.content = String::new();
+            }
+        }
+
+        notes
+    }
+
+    /// Periodically checks whether the map file was changed by another program, and if
+    /// it differs from the open map, asks the user what to do with it.
+    ///
+    /// A file that can't be read (e.g. while it's still being written) is checked again
+    /// next time.
+    pub fn check_external_change(&mut self) {
+        if !self.persistence.should_check_disk() {
+            return;
+        }
+        self.persistence.reset_disk_check_timer();
+
+        let path = self.persistence.file_write_path.clone();
+        let modified = file_modified(&path);
+        if modified.is_none() || modified == self.persistence.disk_modified {
+            return;
+        }
+        let disk_map = match read_map_file(&path) {
+            Ok(MapFileContents::Plain(map_data)) => *map_data,
+            // Readable as long as it's still encrypted with the key it was opened with
+            Ok(MapFileContents::Encrypted(encrypted)) => {
+                match self
+                    .persistence
+                    .file_key
+                    .as_ref()
+                    .map(|file_key| decrypt_map_data(&encrypted, file_key))
+                {
+                    Some(Ok(map_data)) => map_data,
+                    _ => return,
+                }
+            }
+            Err(_) => return,
+        };
+
+        let diff = diff_maps(
+            self.notes_state.notes(),
+            self.connections_state.connections(),
+            &disk_map.notes,
+            &disk_map.connections,
+        );
+        if diff.is_empty() {
+            // Same map, e.g. only the file's timestamp changed
+            self.persistence.disk_modified = modified;
+            return;
+        }
+
+        self.persistence.external_change = Some(ExternalChange {
+            disk_map,
+            modified,
+            diff,
+            show_diff: false,
+            merge: None,
+            selected_conflict: 0,
+        });
+        self.clear_and_redraw();
+    }
+
+    /// Replaces the open map with the changed map file, discarding unsaved changes.
+    pub fn reload_external_change(&mut self) {
+        let Some(change) = self.persistence.external_change.take() else {
+            return;
+        };
+
+        self.replace_map(change.disk_map.clone());
+
+        self.persistence.base_map = Some(change.disk_map);
+        self.persistence.disk_modified = change.modified;
+        self.persistence.mark_clean();
+        self.ui_state.set_notification(Notification::MapReloaded);
+        self.clear_and_redraw();
+    }
+
+    /// Keeps the open map, which is then saved over the changed map file.
+    pub fn keep_local_over_external_change(&mut self) {
+        let Some(change) = self.persistence.external_change.take() else {
+            return;
+        };
+
+        self.persistence.base_map = Some(change.disk_map);
+        self.persistence.disk_modified = change.modified;
+        self.persistence.mark_dirty();
+        self.clear_and_redraw();
+    }
+
+    /// Three-way merges the changed map file into the open map, using the file as it was
+    /// last loaded or saved as the common base, see [`MapMerge`].
+    ///
+    /// Without conflicts the merge is applied right away, otherwise the conflicts are
+    /// listed for the user to choose a version for each. Does nothing if there is no
+    /// base to merge from.
+    pub fn merge_external_change(&mut self) {
+        let Some(base) = &self.persistence.base_map else {
+            return;
+        };
+        let Some(change) = &self.persistence.external_change else {
+            return;
+        };
+
+        // Unlocked notes whose plaintext no longer matches the base's ciphertext. One
+        // that can't be decrypted counts as edited, so the edit isn't lost.
+        let vault = &mut self.vault;
+        let edited_unlocked: HashSet<usize> = self
+            .notes_state
+            .notes()
+            .iter()
+            .filter(|(id, note)| {
+                vault.is_unlocked(**id)
+                    && base.notes.get(id).is_some_and(|base_note| {
+                        let base_text = match &base_note.encrypted {
+                            Some(_) if !vault.has_passphrase() => None,
+                            Some(encrypted) => vault.decrypt(encrypted).ok(),
+                            None => Some(base_note.content.clone()),
+                        };
+                        base_text.as_ref() != Some(&note.content)
+                    })
+            })
+            .map(|(id, _)| *id)
+            .collect();
+
+        let merge = MapMerge::new(
+            base,
+            &self.open_map_data(),
+            &change.disk_map,
+            &edited_unlocked,
+        );
+        let has_conflicts = !merge.conflicts.is_empty();
+        if let Some(change) = self.persistence.external_change.as_mut() {
+            change.merge = Some(merge);
+            change.selected_conflict = 0;
+        }
+
+        if !has_conflicts {
+            self.finish_merge();
+        }
+    }
+
+    /// Sets which version of the highlighted merge conflict to keep.
+    pub fn choose_merge_version(&mut self, choice: MergeChoice) {
+        if let Some(change) = self.persistence.external_change.as_mut()
+            && let Some(merge) = change.merge.as_mut()
+            && let Some(conflict) = merge.conflicts.get_mut(change.selected_conflict)
+        {
+            conflict.choice = choice;
+        }
+    }
+
+    /// Replaces the open map with the merge result. The result still has to be saved.
+    pub fn finish_merge(&mut self) {
+        let Some(mut change) = self.persistence.external_change.take() else {
+            return;
+        };
+        let Some(merge) = change.merge.take() else {
+            self.persistence.external_change = Some(change);
+            return;
+        };
+
+        self.replace_map(merge.into_map_data());
+
+        // The file is the base for the next merge, and is overwritten on the next save
+        self.persistence.base_map = Some(change.disk_map);
+        self.persistence.disk_modified = change.modified;
+        self.persistence.mark_dirty();
+        self.ui_state.set_notification(Notification::MergeSuccess);
+        self.clear_and_redraw();
+    }
+
+    /// Replaces the open map's contents with a backup's. The map file keeps its contents
+    /// until the next save, like after any other change.
+    pub fn restore_backup(&mut self, map_data: MapData) {
+        self.replace_map(map_data);
+        self.persistence.mark_dirty();
+        self.ui_state.set_notification(Notification::BackupRestored);
+        self.clear_and_redraw();
+    }
+
+    /// Replaces the open map's contents with the unsaved changes saved when tmmpr
+    /// crashed (see [`crate::utils::emergency_save`]), to be saved like any other change.
+    pub fn restore_crash_changes(&mut self, map_data: MapData) {
+        self.replace_map(map_data);
+        self.persistence.mark_dirty();
+        self.ui_state
+            .set_notification(Notification::CrashChangesRestored);
+        self.clear_and_redraw();
+    }
+
+    /// The open map as map data, with unlocked notes' plaintext still in place.
+    fn open_map_data(&self) -> MapData {
+        MapData {
+            view_pos: self.viewport.view_pos.clone(),
+            next_note_id_counter: self.notes_state.next_note_id_counter(),
+            notes: self.notes_state.notes().clone(),
+            render_order: self.notes_state.render_order().clone(),
+            connections: self.connections_state.connections().to_vec(),
+            regions: self.regions_state.regions().to_vec(),
+            hyperedges: self.hyperedges_state.hyperedges().to_vec(),
+            layers: self.layers_state.layers_for_saving(),
+            view_state: None,
+            trash: self.trash.notes().to_vec(),
+        }
+    }
+
+    /// Replaces the open map's contents, e.g. with a reloaded or merged map file, and
+    /// returns to Normal mode.
+    ///
+    /// Unlocked notes stay unlocked only if their text is unchanged. Text from the file
+    /// is the stored ciphertext, so those notes are locked again.
+    fn replace_map(&mut self, map_data: MapData) {
+        let previous_notes = self.notes_state.notes().clone();
+        self.load_map_data(map_data);
+
+        for (id, previous) in previous_notes {
+            let same_text = self.notes_state.notes().get(&id).is_some_and(|note| {
+                note.content == previous.content && note.encrypted == previous.encrypted
+            });
+            if self.vault.is_unlocked(id) && !same_text {
+                self.vault.forget(id);
+            }
+        }
+
+        self.ui_state.clear_path();
+        self.ui_state.last_orphan = None;
+        self.history.clear();
+        let _ = execute!(stdout(), SetCursorStyle::SteadyBar);
+        self.mode = Mode::Normal;
+    }
+
+    /// Handles periodic auto-save operations based on configured intervals, saving in
+    /// the background. Also applies the result of a finished background save.
+    ///
+    /// Waits while an external change of the map file is unresolved, so it isn't
+    /// overwritten before the user decides, and while the previous save is still being
+    /// written.
+    pub fn auto_save_if_needed(&mut self) {
+        finish_background_save(self, false);
+
+        // The tutorial map is only saved when asked to
+        if self.tutorial.is_some() {
+            return;
+        }
+
+        if let Some(interval) = self.settings.save_interval {
+            if self.persistence.external_change.is_none()
+                && self.persistence.background_save.is_none()
+                && self.persistence.should_save(interval)
+            {
+                let map_file_path = self.persistence.file_write_path.clone();
+                // No notification for auto-save
+                save_map_file_in_background(self, &map_file_path, false);
+                self.persistence.reset_save_timer();
+            }
+        }
+    }
+
+    /// Redraws the bar when the status bar format shows the clock and its minute has
+    /// changed.
+    pub fn refresh_bar_clock(&mut self, now: DateTime<Local>) {
+        let shows_clock = self
+            .settings
+            .status_bar_format
+            .as_ref()
+            .is_some_and(|format| StatusBarFormat::parse(format).contains(BarField::Clock));
+        if !shows_clock {
+            return;
+        }
+
+        let clock = now.format("%H:%M").to_string();
+        if self.ui_state.bar_clock != clock {
+            self.ui_state.bar_clock = clock;
+            self.ui_state.request_redraw();
+        }
+    }
+
+    /// Handles periodic backup operations based on configured intervals.
+    pub fn auto_backup_if_needed(&mut self) {
+        if self.tutorial.is_some() {
+            return;
+        }
+
+        if let Some(interval) = &self.settings.runtime_backups_interval {
+            if self.persistence.should_backup(interval) {
+                // Runtime backups interval implies backups path exists in settings
+                handle_runtime_backup(self);
+                self.persistence.reset_backup_timer();
+            }
+        }
+    }
+}
+
+/// New starts of `spans` (start, length), sorted by start, so the gaps between them are
+/// equal while the first and last span keep their place. Leftover cells go to the first
+/// gaps; spans that don't fit end up touching, moving the last one further out.
+fn distribute(spans: &[(isize, usize)]) -> Vec<isize> {
+    let (Some(first), Some(last)) = (spans.first(), spans.last()) else {
+        return vec![];
+    };
+    if spans.len() < 3 {
+        return spans.iter().map(|span| span.0).collect();
+    }
+
+    let total = (last.0 + last.1 as isize).abs_diff(first.0);
+    let lengths: usize = spans.iter().map(|span| span.1).sum();
+    let free = total.saturating_sub(lengths);
+    let gaps = spans.len() - 1;
+
+    let mut starts = vec![];
+    let mut position = first.0;
+    for (i, span) in spans.iter().enumerate() {
+        starts.push(position);
+        position += (span.1 + free / gaps + usize::from(i < free % gaps)) as isize;
     }
+    starts
 }
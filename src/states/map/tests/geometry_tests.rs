@@ -1,6 +1,11 @@
 #[cfg(test)]
 mod tests {
-    use crate::states::map::geometry::SignedRect;
+    use ratatui::layout::Rect;
+
+    use crate::{
+        states::map::{CanvasPos, ScreenPos, ViewPos, ViewportState, geometry::SignedRect},
+        utils::Point,
+    };
 
     #[test]
     fn test_signed_rect_no_intersection() {
@@ -233,4 +238,32 @@ mod tests {
         assert_eq!(intersection.width, 1);
         assert_eq!(intersection.height, 1);
     }
+
+    #[test]
+    fn test_screen_pos_cell_in_area() {
+        let area = Rect::new(0, 0, 80, 24);
+
+        assert_eq!(ScreenPos::new(0, 0).cell_in(area), Some((0, 0)));
+        assert_eq!(ScreenPos::new(79, 23).cell_in(area), Some((79, 23)));
+        assert_eq!(ScreenPos::new(-1, 5).cell_in(area), None);
+        assert_eq!(ScreenPos::new(5, -1).cell_in(area), None);
+        assert_eq!(ScreenPos::new(80, 5).cell_in(area), None);
+        assert_eq!(ScreenPos::new(5, 24).cell_in(area), None);
+    }
+
+    #[test]
+    fn test_viewport_converts_between_canvas_and_screen() {
+        let mut viewport = ViewportState::new();
+        viewport.view_pos = ViewPos { x: 100, y: 40 };
+
+        let canvas = CanvasPos::from((110_isize, 45_isize));
+        let screen = viewport.to_screen(canvas);
+        assert_eq!(screen, ScreenPos::new(10, 5));
+        assert_eq!(viewport.to_canvas(screen), canvas);
+
+        // Positions left of and above the viewport are off-screen
+        let screen = viewport.to_screen(CanvasPos::from(Point { x: 90, y: 40 }));
+        assert_eq!(screen, ScreenPos::new(-10, 0));
+        assert_eq!(screen.cell_in(Rect::new(0, 0, 80, 24)), None);
+    }
 }
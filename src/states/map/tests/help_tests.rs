@@ -0,0 +1,57 @@
+use crate::{
+    states::map::{HELP_PAGES, Mode, help_catalog_keys, help_page_for_mode, search_help},
+    utils::{Locale, catalog},
+};
+
+#[test]
+fn test_every_locale_has_the_help_texts() {
+    for locale in Locale::ALL {
+        for key in help_catalog_keys() {
+            assert!(
+                catalog(locale).contains_key(key),
+                "{} is missing {key}",
+                locale.name()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_help_page_for_mode() {
+    let modes = [
+        (Mode::Normal, 2),
+        (Mode::Region, 2),
+        (Mode::Visual, 3),
+        (Mode::VisualBox, 3),
+        (Mode::Delete, 3),
+        (Mode::VisualMove, 4),
+        (Mode::VisualConnect, 4),
+        (Mode::VisualWaypoint, 4),
+        (Mode::Edit, 5),
+        (Mode::EditNormal, 5),
+        (Mode::EditInsert, 5),
+    ];
+    for (mode, page) in modes {
+        assert_eq!(help_page_for_mode(mode), page, "{mode:?}");
+        assert!(HELP_PAGES[page - 1].modes.contains(&mode));
+    }
+}
+
+#[test]
+fn test_search_help() {
+    let matches = search_help(Locale::English, "WAYPOINT");
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].page, 4);
+    assert_eq!(matches[0].keys, "w");
+    assert_eq!(matches[0].section, "Visual (Connection)");
+
+    // Keys are searched too, in the words of the locale
+    let matches = search_help(Locale::German, "umschalt");
+    assert!(!matches.is_empty());
+    assert!(matches.iter().all(|found| found.keys.contains("Umschalt")));
+    assert_eq!(matches[0].page, 2);
+
+    assert!(search_help(Locale::German, "Wegpunkte").len() == 1);
+    assert!(search_help(Locale::English, "  ").is_empty());
+    assert!(search_help(Locale::English, "no such binding").is_empty());
+}
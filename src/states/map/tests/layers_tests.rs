@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use ratatui::style::Color;
+
+use crate::{
+    states::{
+        MapState,
+        map::{Layer, LayersState, Note},
+        settings::Settings,
+    },
+    utils::MapData,
+};
+
+#[test]
+fn test_default_layer_is_not_saved() {
+    let mut layers_state = LayersState::new();
+    assert_eq!(layers_state.layers().len(), 1);
+    assert!(layers_state.layers_for_saving().is_empty());
+
+    layers_state.toggle_locked(0);
+    assert_eq!(layers_state.layers_for_saving().len(), 1);
+    assert!(layers_state.is_frozen(0));
+    assert!(!layers_state.is_hidden(0));
+    // Unknown layers are treated as plain visible ones
+    assert!(!layers_state.is_frozen(7));
+}
+
+#[test]
+fn test_layers_round_trip_through_map_data() {
+    let mut map_state = MapState::new_with_settings(PathBuf::new(), Settings::new());
+    let id = map_state
+        .notes_state
+        .add(0, 0, String::from("why?"), Color::White);
+    let questions = map_state.layers_state.add(String::from("questions"));
+    map_state.layers_state.toggle_hidden(questions);
+    map_state
+        .notes_state
+        .get_mut(id)
+        .unwrap()
+        .set_layer(questions);
+
+    let map_data = MapData::from_map_state(&mut map_state);
+    assert_eq!(map_data.layers[1].name, "questions");
+    assert!(map_data.layers[1].hidden);
+
+    let mut loaded = MapState::new_with_settings(PathBuf::new(), Settings::new());
+    loaded.load_map_data(map_data);
+    assert!(loaded.note_is_hidden(id));
+    assert_eq!(loaded.layers_state.layers().len(), 2);
+}
+
+#[test]
+fn test_first_layer_is_stored_as_none() {
+    let mut note = Note::new(0, 0, String::new(), Color::White);
+    note.set_layer(2);
+    assert_eq!(note.layer, Some(2));
+    note.set_layer(0);
+    assert_eq!(note.layer, None);
+
+    let layer: Layer = serde_json::from_str(r#"{"name": "facts"}"#).unwrap();
+    assert_eq!(layer, Layer::new(String::from("facts")));
+}
@@ -1,4 +1,13 @@
 // This file organizes all the test submodules
 mod geometry_tests;
+mod help_tests;
+mod layers_tests;
 mod note_tests;
+mod notifications_tests;
+mod path_cache_tests;
+mod regions_tests;
+mod search_tests;
 mod state_tests;
+mod trash_tests;
+mod tutorial_tests;
+mod vault_tests;
@@ -1,5 +1,5 @@
 use super::super::enums::Side;
-use super::super::note::Note;
+use super::super::note::{Note, NoteStatus};
 use ratatui::style::Color;
 
 #[cfg(test)]
@@ -76,8 +76,8 @@ mod tests {
 
         let (width, height) = note.get_dimensions();
 
-        let expected_x = note.x + width as usize - 1;
-        let expected_y = note.y + (height / 2) as usize;
+        let expected_x = note.x + width as isize - 1;
+        let expected_y = note.y + (height / 2) as isize;
 
         assert_eq!(x, expected_x);
         assert_eq!(y, expected_y);
@@ -90,7 +90,7 @@ mod tests {
 
         let (_, height) = note.get_dimensions();
         let expected_x = note.x;
-        let expected_y = note.y + (height / 2) as usize;
+        let expected_y = note.y + (height / 2) as isize;
 
         assert_eq!(x, expected_x);
         assert_eq!(y, expected_y);
@@ -103,7 +103,7 @@ mod tests {
 
         let (width, _) = note.get_dimensions();
 
-        let expected_x = note.x + (width / 2) as usize;
+        let expected_x = note.x + (width / 2) as isize;
         let expected_y = note.y;
 
         assert_eq!(x, expected_x);
@@ -117,8 +117,8 @@ mod tests {
 
         let (width, height) = note.get_dimensions();
 
-        let expected_x = note.x + (width / 2) as usize;
-        let expected_y = note.y + height as usize - 1;
+        let expected_x = note.x + (width / 2) as isize;
+        let expected_y = note.y + height as isize - 1;
 
         assert_eq!(x, expected_x);
         assert_eq!(y, expected_y);
@@ -177,4 +177,77 @@ mod tests {
         // Top and bottom should have same x-coordinate (middle of note)
         assert_eq!(top.0, bottom.0);
     }
+
+    #[test]
+    fn test_note_uuid_is_only_saved_when_set() {
+        let mut note = Note::new(1, 2, "Text".to_string(), Color::White);
+        let json = serde_json::to_string(&note).unwrap();
+        assert!(!json.contains("uuid"));
+        // Notes saved before UUIDs existed load without one
+        assert_eq!(serde_json::from_str::<Note>(&json).unwrap().uuid, None);
+
+        note.uuid = Some(uuid::Uuid::new_v4());
+        let json = serde_json::to_string(&note).unwrap();
+        assert_eq!(serde_json::from_str::<Note>(&json).unwrap(), note);
+    }
+
+    #[test]
+    fn test_note_title() {
+        let mut note = Note::new(0, 0, "Hi".to_string(), Color::White);
+        let json = serde_json::to_string(&note).unwrap();
+        assert!(!json.contains("title"));
+
+        note.set_title("  A title that is wider than the note  ");
+        assert_eq!(
+            note.title.as_deref(),
+            Some("A title that is wider than the note")
+        );
+        // 35 chars + a space on each side + 2 (border) + 1 (cursor)
+        assert_eq!(note.get_dimensions(), (40, 4));
+        let json = serde_json::to_string(&note).unwrap();
+        assert_eq!(serde_json::from_str::<Note>(&json).unwrap(), note);
+
+        note.set_title(" ");
+        assert_eq!(note.title, None);
+    }
+
+    #[test]
+    fn test_note_status() {
+        let mut note = Note::new(0, 0, "Task".to_string(), Color::White);
+        assert!(!serde_json::to_string(&note).unwrap().contains("status"));
+
+        let mut statuses = vec![];
+        for _ in 0..5 {
+            note.cycle_status();
+            statuses.push(note.status);
+        }
+        assert_eq!(
+            statuses,
+            vec![
+                Some(NoteStatus::Todo),
+                Some(NoteStatus::Doing),
+                Some(NoteStatus::Done),
+                Some(NoteStatus::Blocked),
+                None,
+            ]
+        );
+
+        note.status = Some(NoteStatus::Blocked);
+        let json = serde_json::to_string(&note).unwrap();
+        assert!(json.contains("\"status\":\"blocked\""));
+        assert_eq!(serde_json::from_str::<Note>(&json).unwrap(), note);
+        assert_eq!(NoteStatus::from_name("done"), Some(NoteStatus::Done));
+        assert_eq!(NoteStatus::from_name("later"), None);
+    }
+
+    #[test]
+    fn test_note_lock_is_only_saved_when_set() {
+        let mut note = Note::new(0, 0, "Reference".to_string(), Color::White);
+        assert!(!serde_json::to_string(&note).unwrap().contains("locked"));
+
+        note.locked = true;
+        let json = serde_json::to_string(&note).unwrap();
+        assert!(json.contains("\"locked\":true"));
+        assert_eq!(serde_json::from_str::<Note>(&json).unwrap(), note);
+    }
 }
@@ -0,0 +1,84 @@
+use std::time::{Duration, Instant};
+
+use crate::states::map::{
+    MAX_NOTIFICATIONS, NOTIFICATION_DURATION, Notification, NotificationQueue, UIState,
+};
+
+#[test]
+fn test_notifications_stack_instead_of_overwriting() {
+    let now = Instant::now();
+    let mut queue = NotificationQueue::new();
+
+    queue.push_at(Notification::SaveSuccess, now);
+    queue.push_at(Notification::BackupSuccess, now);
+
+    let shown: Vec<&Notification> = queue.iter().map(|toast| &toast.notification).collect();
+    assert_eq!(
+        shown,
+        [&Notification::SaveSuccess, &Notification::BackupSuccess]
+    );
+    assert_eq!(queue.latest(), Some(&Notification::BackupSuccess));
+}
+
+#[test]
+fn test_notifications_expire() {
+    let now = Instant::now();
+    let mut queue = NotificationQueue::new();
+    queue.push_at(Notification::SaveSuccess, now);
+    queue.push_at(Notification::BackupFail, now + Duration::from_secs(2));
+
+    assert!(!queue.expire(now + NOTIFICATION_DURATION - Duration::from_millis(1)));
+    assert_eq!(queue.len(), 2);
+
+    assert!(queue.expire(now + NOTIFICATION_DURATION));
+    assert_eq!(queue.latest(), Some(&Notification::BackupFail));
+    assert_eq!(queue.len(), 1);
+
+    assert!(queue.expire(now + NOTIFICATION_DURATION + Duration::from_secs(2)));
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn test_repeated_notification_starts_over() {
+    let now = Instant::now();
+    let later = now + Duration::from_secs(3);
+    let mut queue = NotificationQueue::new();
+    queue.push_at(Notification::SaveSuccess, now);
+    queue.push_at(Notification::BackupSuccess, now);
+
+    queue.push_at(Notification::SaveSuccess, later);
+
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue.latest(), Some(&Notification::SaveSuccess));
+    queue.expire(now + NOTIFICATION_DURATION);
+    assert_eq!(queue.len(), 1);
+}
+
+#[test]
+fn test_oldest_notification_goes_when_full() {
+    let now = Instant::now();
+    let mut queue = NotificationQueue::new();
+    for count in 0..=MAX_NOTIFICATIONS {
+        queue.push_at(Notification::Replaced(count), now);
+    }
+
+    assert_eq!(queue.len(), MAX_NOTIFICATIONS);
+    assert_eq!(
+        queue.iter().next().map(|toast| &toast.notification),
+        Some(&Notification::Replaced(1))
+    );
+}
+
+#[test]
+fn test_expired_notifications_are_redrawn_away() {
+    let mut ui_state = UIState::new();
+    ui_state.set_notification(Notification::SaveSuccess);
+    ui_state.mark_redrawn();
+
+    ui_state.expire_notifications(Instant::now());
+    assert!(!ui_state.needs_clear_and_redraw);
+
+    ui_state.expire_notifications(Instant::now() + NOTIFICATION_DURATION);
+    assert!(ui_state.needs_clear_and_redraw);
+    assert!(ui_state.notifications.is_empty());
+}
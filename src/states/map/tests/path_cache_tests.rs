@@ -0,0 +1,135 @@
+use ratatui::style::Color;
+use std::rc::Rc;
+
+use crate::{
+    states::map::{Connection, ConnectionsState, Note, PathCache, Side},
+    utils::{Point, calculate_path_with_waypoints},
+};
+
+fn connection(from_id: usize, to_id: usize) -> Connection {
+    Connection {
+        from_id,
+        from_side: Side::Right,
+        to_id,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    }
+}
+
+fn note_at(x: isize, y: isize) -> Note {
+    Note::new(x, y, String::from("Note"), Color::White)
+}
+
+#[test]
+fn test_path_is_reused_while_notes_stay_put() {
+    let mut cache = PathCache::new();
+    let connection = connection(0, 1);
+    let (start, end) = (note_at(10, 10), note_at(50, 20));
+
+    let first = cache.path(&connection, &start, &end, 0);
+    let second = cache.path(&connection, &start, &end, 0);
+
+    assert!(Rc::ptr_eq(&first, &second));
+    assert_eq!(
+        *first,
+        *calculate_path_with_waypoints(&start, Side::Right, &end, Side::Left, &[])
+    );
+}
+
+#[test]
+fn test_path_is_computed_again_when_an_endpoint_moves() {
+    let mut cache = PathCache::new();
+    let connection = connection(0, 1);
+    let (mut start, mut end) = (note_at(10, 10), note_at(80, 20));
+    let first = cache.path(&connection, &start, &end, 0);
+
+    end.x = 90;
+    let moved = cache.path(&connection, &start, &end, 0);
+
+    assert!(!Rc::ptr_eq(&first, &moved));
+    assert_eq!(
+        *moved,
+        *calculate_path_with_waypoints(&start, Side::Right, &end, Side::Left, &[])
+    );
+    assert_eq!(cache.len(), 1);
+
+    // Widening the start note moves its right connection point too
+    start
+        .content
+        .push_str("\na line much wider than the note was before");
+    let resized = cache.path(&connection, &start, &end, 0);
+    assert!(!Rc::ptr_eq(&moved, &resized));
+}
+
+#[test]
+fn test_path_is_computed_again_when_waypoints_change() {
+    let mut cache = PathCache::new();
+    let mut connection = connection(0, 1);
+    let (start, end) = (note_at(10, 10), note_at(50, 20));
+    let first = cache.path(&connection, &start, &end, 0);
+
+    connection.waypoints.push(Point { x: 30, y: 2 });
+    let routed = cache.path(&connection, &start, &end, 0);
+
+    assert!(!Rc::ptr_eq(&first, &routed));
+    assert!(routed.contains(&Point { x: 30, y: 2 }));
+}
+
+#[test]
+fn test_prune_forgets_removed_connections() {
+    let mut cache = PathCache::new();
+    let (kept, removed) = (connection(0, 1), connection(1, 2));
+    let (a, b, c) = (note_at(10, 10), note_at(50, 20), note_at(90, 30));
+    cache.path(&kept, &a, &b, 0);
+    cache.path(&removed, &b, &c, 0);
+
+    // Nothing to do while there are as many connections as paths
+    cache.prune(&ConnectionsState::from_connections(vec![
+        kept.clone(),
+        removed,
+    ]));
+    assert_eq!(cache.len(), 2);
+
+    cache.prune(&ConnectionsState::from_connections(vec![kept]));
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_parallel_connections_get_their_own_lanes() {
+    let mut back = connection(1, 0);
+    back.from_side = Side::Left;
+    back.to_side = Side::Right;
+    let connections_state = ConnectionsState::from_connections(vec![
+        connection(0, 1),
+        connection(1, 2),
+        connection(0, 1),
+        back.clone(),
+    ]);
+    let stored = connections_state.connections();
+
+    assert_eq!(connections_state.parallel_offset(&stored[0]), 0);
+    assert_eq!(connections_state.parallel_offset(&stored[1]), 0);
+    assert_eq!(connections_state.parallel_offset(&stored[2]), 1);
+    // The third one between notes 0 and 1, going the other way
+    assert_eq!(connections_state.parallel_offset(&stored[3]), 1);
+
+    // A connection being made goes after the stored ones
+    assert_eq!(connections_state.parallel_offset(&back), -2);
+}
+
+#[test]
+fn test_lanes_are_cached_apart() {
+    let mut cache = PathCache::new();
+    let connection = connection(0, 1);
+    let (start, end) = (note_at(10, 10), note_at(50, 20));
+
+    let first = cache.path(&connection, &start, &end, 0);
+    let aside = cache.path(&connection, &start, &end, -1);
+
+    assert_eq!(cache.len(), 2);
+    assert_ne!(first, aside);
+    assert_eq!(aside[0], Point { x: 30, y: 11 });
+}
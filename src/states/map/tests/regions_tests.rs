@@ -0,0 +1,156 @@
+use ratatui::style::Color;
+
+use crate::states::map::{
+    ArrangeOrder, MIN_REGION_HEIGHT, MIN_REGION_WIDTH, Region, RegionsState, note_priority,
+};
+
+fn region_at(x: isize, y: isize) -> Region {
+    Region::new(x, y, String::from("Region"), Color::White)
+}
+
+#[test]
+fn test_region_contains() {
+    let region = region_at(10, 10);
+
+    assert!(region.contains(10, 10));
+    assert!(region.contains(49, 24));
+    assert!(!region.contains(50, 10));
+    assert!(!region.contains(10, 25));
+    assert!(!region.contains(9, 10));
+}
+
+#[test]
+fn test_region_resize_respects_minimum() {
+    let mut region = region_at(0, 0);
+
+    region.resize(-100, -100);
+    assert_eq!(
+        (region.width, region.height),
+        (MIN_REGION_WIDTH, MIN_REGION_HEIGHT)
+    );
+
+    region.resize(6, 2);
+    assert_eq!(
+        (region.width, region.height),
+        (MIN_REGION_WIDTH + 6, MIN_REGION_HEIGHT + 2)
+    );
+}
+
+#[test]
+fn test_region_move_past_origin() {
+    let mut region = region_at(2, 3);
+
+    region.move_by(-5, -5);
+
+    assert_eq!((region.x, region.y), (-3, -2));
+}
+
+#[test]
+fn test_add_selects_new_region() {
+    let mut regions_state = RegionsState::new();
+
+    regions_state.add(region_at(0, 0));
+    let index = regions_state.add(region_at(100, 0));
+
+    assert_eq!(index, 1);
+    assert_eq!(regions_state.selected_index(), Some(1));
+}
+
+#[test]
+fn test_select_next_wraps_around() {
+    let mut regions_state = RegionsState::from_regions(vec![region_at(0, 0), region_at(100, 0)]);
+
+    regions_state.select_next();
+    assert_eq!(regions_state.selected_index(), Some(0));
+    regions_state.select_next();
+    assert_eq!(regions_state.selected_index(), Some(1));
+    regions_state.select_next();
+    assert_eq!(regions_state.selected_index(), Some(0));
+}
+
+#[test]
+fn test_find_closest_prefers_containing_region() {
+    let regions_state = RegionsState::from_regions(vec![
+        region_at(0, 0),
+        region_at(100, 0),
+        // Overlaps the first one and is drawn on top of it
+        region_at(20, 5),
+    ]);
+
+    assert_eq!(regions_state.find_closest(25, 10), Some(2));
+    assert_eq!(regions_state.find_closest(5, 2), Some(0));
+    // Outside every region: nearest center
+    assert_eq!(regions_state.find_closest(200, 5), Some(1));
+    assert_eq!(RegionsState::new().find_closest(0, 0), None);
+}
+
+#[test]
+fn test_remove_selected() {
+    let mut regions_state = RegionsState::from_regions(vec![region_at(0, 0), region_at(100, 0)]);
+    regions_state.select(0);
+    regions_state.editing_label = true;
+
+    regions_state.remove_selected();
+
+    assert_eq!(regions_state.regions(), &[region_at(100, 0)]);
+    assert_eq!(regions_state.selected_index(), None);
+    assert!(!regions_state.editing_label);
+}
+
+#[test]
+fn test_region_tag() {
+    let mut region = Region::new(0, 0, String::from("  In  Review "), Color::White);
+    assert_eq!(region.tag().as_deref(), Some("#in-review"));
+
+    region.label = String::from("Done");
+    assert_eq!(region.tag().as_deref(), Some("#done"));
+
+    // Unlabeled regions have no tag
+    region.label = String::from("   ");
+    assert_eq!(region.tag(), None);
+}
+
+#[test]
+fn test_grid_positions_fill_rows_then_grow_region() {
+    let mut region = region_at(10, 10);
+
+    // 40 wide region: 36 inner cells fit one column of 21 + 1
+    let positions = region.grid_positions(&[(21, 4), (21, 5), (21, 4)]);
+
+    assert_eq!(positions, vec![(12, 12), (12, 18), (12, 24)]);
+    // 3 rows of 6 + border and gap
+    assert_eq!(region.height, 21);
+
+    let mut wide = region_at(0, 0);
+    wide.width = 60;
+    let positions = wide.grid_positions(&[(21, 4), (21, 4), (21, 4)]);
+
+    assert_eq!(positions, vec![(2, 2), (24, 2), (2, 7)]);
+    assert_eq!(wide.height, 15);
+}
+
+#[test]
+fn test_grid_positions_empty() {
+    let mut region = region_at(0, 0);
+
+    assert!(region.grid_positions(&[]).is_empty());
+    assert_eq!(region, region_at(0, 0));
+}
+
+#[test]
+fn test_note_priority() {
+    assert_eq!(note_priority("Fix bug #p1"), Some(1));
+    assert_eq!(note_priority("#p12 later\n#p3"), Some(12));
+    assert_eq!(note_priority("#pending #p"), None);
+    assert_eq!(note_priority("no tag"), None);
+}
+
+#[test]
+fn test_arrange_order_cycles() {
+    let order = ArrangeOrder::default();
+    assert_eq!(order, ArrangeOrder::Title);
+    assert_eq!(order.next(), ArrangeOrder::Priority);
+    assert_eq!(order.next().next(), ArrangeOrder::Recency);
+    assert_eq!(order.next().next().next(), ArrangeOrder::Weight);
+    assert_eq!(order.next().next().next().next(), ArrangeOrder::Title);
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use ratatui::style::Color;
+
+use crate::states::map::{Note, SearchMatch, SearchState};
+
+fn search_for(query: &str) -> SearchState {
+    let mut search = SearchState::new();
+    for c in query.chars() {
+        search.push(c);
+    }
+    search
+}
+
+#[test]
+fn test_smart_case_and_invalid_regex() {
+    assert_eq!(
+        search_for("api").matches_in("API and api"),
+        vec![0..3, 8..11]
+    );
+    assert_eq!(search_for("Api").matches_in("API and Api"), vec![8..11]);
+    assert_eq!(search_for(r"v\d+").matches_in("v1, v22"), vec![0..2, 4..7]);
+
+    let mut search = search_for("v(");
+    assert!(search.is_invalid());
+    assert!(search.matches_in("v(").is_empty());
+    search.pop();
+    assert!(!search.is_invalid());
+}
+
+#[test]
+fn test_empty_matches_are_skipped() {
+    assert_eq!(search_for("x*").matches_in("axxb"), vec![1..3]);
+}
+
+#[test]
+fn test_all_matches_ordered_by_note_position() {
+    let notes = HashMap::from([
+        (0, Note::new(50, 10, String::from("todo"), Color::White)),
+        (
+            1,
+            Note::new(0, 10, String::from("todo, todo"), Color::White),
+        ),
+        (
+            2,
+            Note::new(90, 0, String::from("later todo"), Color::White),
+        ),
+        (3, Note::new(0, 0, String::from("todo"), Color::White)),
+    ]);
+
+    let matches = search_for("todo").all_matches(&notes, |id| id == 3);
+
+    let expected =
+        [(2, 6..10), (1, 0..4), (1, 6..10), (0, 0..4)].map(|(note_id, range)| SearchMatch {
+            note_id,
+            range,
+            in_title: false,
+        });
+    assert_eq!(matches, expected);
+}
+
+#[test]
+fn test_all_matches_include_titles() {
+    let mut titled = Note::new(0, 0, String::from("buy tickets"), Color::White);
+    titled.set_title("Trip tickets");
+    let notes = HashMap::from([(0, titled)]);
+
+    let matches = search_for("tickets").all_matches(&notes, |_| false);
+
+    assert_eq!(
+        matches,
+        vec![
+            SearchMatch {
+                note_id: 0,
+                range: 5..12,
+                in_title: true,
+            },
+            SearchMatch {
+                note_id: 0,
+                range: 4..11,
+                in_title: false,
+            },
+        ]
+    );
+}
@@ -1,16 +1,25 @@
+use chrono::{Local, TimeZone};
 use ratatui::style::Color;
 use std::{
     collections::HashMap,
-    path::PathBuf,
-    time::{Duration, Instant},
+    fs::File,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
 };
+use tempfile::TempDir;
 
 use crate::{
     states::{
         MapState,
-        map::{Connection, Mode, Note, Side},
+        map::{
+            Connection, ConnectionsState, DraftConnection, Hyperedge, Mode, Note, Notification,
+            Side, SignedRect, ViewPos,
+        },
+    },
+    utils::{
+        ConflictKind, IoErrorKind, MapData, MergeChoice, encrypt, file_modified,
+        finish_background_save, save_map_file, test_utils::MockFileSystem, write_json_data,
     },
-    utils::{IoErrorKind, test_utils::MockFileSystem},
 };
 
 fn create_map_state_using_mock_filesystem(path: PathBuf) -> MapState {
@@ -19,8 +28,8 @@ fn create_map_state_using_mock_filesystem(path: PathBuf) -> MapState {
 }
 
 fn create_test_map_state(
-    view_pos_x: usize,
-    view_pos_y: usize,
+    view_pos_x: isize,
+    view_pos_y: isize,
     width: usize,
     height: usize,
 ) -> MapState {
@@ -65,10 +74,11 @@ fn test_add_several_notes() {
     assert_eq!(map_state.persistence.has_unsaved_changes, true);
     assert_eq!(
         *map_state.notes_state.notes(),
+        // Nudged above and below the first one rather than on top of it
         HashMap::from([
             (0, Note::new(50, 25, String::from(""), Color::White)),
-            (1, Note::new(50, 25, String::from(""), Color::White)),
-            (2, Note::new(50, 25, String::from(""), Color::White)),
+            (1, Note::new(50, 20, String::from(""), Color::White)),
+            (2, Note::new(50, 30, String::from(""), Color::White)),
         ])
     );
     assert_eq!(*map_state.notes_state.render_order(), vec![0, 1, 2]);
@@ -77,6 +87,27 @@ fn test_add_several_notes() {
     assert_eq!(map_state.notes_state.next_note_id_counter(), 3);
 }
 
+#[test]
+fn test_add_note_next_to_covering_notes() {
+    let mut map_state = create_test_map_state(0, 0, 100, 50);
+    // A wide note across the center, and one on a hidden layer that doesn't count
+    map_state
+        .notes_state
+        .add(30, 24, "x".repeat(60), Color::White);
+    map_state.layers_state.add(String::from("Hidden"));
+    map_state.layers_state.toggle_hidden(1);
+    let hidden = map_state
+        .notes_state
+        .add(50, 29, String::from("Hidden"), Color::White);
+    map_state.notes_state.get_mut(hidden).unwrap().set_layer(1);
+
+    map_state.add_note();
+
+    // Below is closer than above, since the wide note is in the lower half
+    let note = &map_state.notes_state.notes()[&2];
+    assert_eq!((note.x, note.y), (50, 29));
+}
+
 #[test]
 fn test_add_note_diff_viewpos() {
     let mut map_state = create_test_map_state(20, 70, 250, 100);
@@ -116,9 +147,9 @@ fn test_new() {
     assert_eq!(map_state.connections_state.focused_connection, None);
     assert_eq!(map_state.connections_state.editing_connection_index, None);
     assert_eq!(map_state.persistence.file_write_path, path);
-    assert_eq!(map_state.ui_state.show_notification, None);
+    assert!(map_state.ui_state.notifications.is_empty());
     assert_eq!(map_state.persistence.has_unsaved_changes, false);
-    assert_eq!(map_state.ui_state.confirm_discard_menu, None);
+    assert_eq!(map_state.ui_state.dialog, None);
     assert_eq!(map_state.ui_state.help_screen, None);
     // Since get_setting_with_fs from map_state creation will
     // fail writing to mock directory - assume write error
@@ -178,7 +209,6 @@ fn test_select_note_single_note() {
     map_state.select_note();
 
     assert_eq!(map_state.notes_state.selected_note_id(), Some(0));
-    // Check that render order was updated (note moved to back)
     assert_eq!(*map_state.notes_state.render_order(), vec![0]);
 }
 
@@ -202,8 +232,8 @@ fn test_select_note_multiple_notes() {
 
     // Should select note 1 (closest to center)
     assert_eq!(map_state.notes_state.selected_note_id(), Some(1));
-    // Check that render order was updated (note 1 moved to back)
-    assert_eq!(*map_state.notes_state.render_order(), vec![0, 2, 1]);
+    // Selecting doesn't change the stacking order
+    assert_eq!(*map_state.notes_state.render_order(), vec![0, 1, 2]);
 }
 
 #[test]
@@ -214,11 +244,14 @@ fn test_stash_connection_with_target() {
     let connection = Connection {
         from_id: 1,
         from_side: Side::Right,
-        to_id: Some(2),
-        to_side: Some(Side::Left),
+        to_id: 2,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection);
+    map_state.connections_state.focused_connection = Some(connection.clone().into());
 
     map_state.connections_state.stash_connection();
 
@@ -247,17 +280,120 @@ fn test_stash_connection_with_target() {
 }
 
 #[test]
-fn test_stash_connection_without_target() {
+fn test_deleted_notes_leave_fan_outs() {
     let mut map_state = create_test_map_state(0, 0, 100, 50);
+    for x in [0, 30, 30, 30] {
+        map_state
+            .notes_state
+            .add(x, 10, String::from("note"), Color::White);
+    }
+    let fan_out = |from_id, to_ids| Hyperedge {
+        from_id,
+        from_side: Side::Right,
+        to_ids,
+        color: Color::White,
+        layer: None,
+    };
+    map_state.hyperedges_state.add(fan_out(0, vec![1, 2]));
+    map_state.hyperedges_state.add(fan_out(3, vec![0]));
 
-    // Create a connection without a target
+    // A deleted target loses its branch, the fan-out to only it goes
+    map_state.notes_state.select(1);
+    map_state.delete_selected_note();
+    assert_eq!(
+        map_state.hyperedges_state.hyperedges(),
+        [fan_out(0, vec![2]), fan_out(3, vec![0])]
+    );
+
+    map_state.notes_state.select(0);
+    map_state.delete_selected_note();
+    assert!(map_state.hyperedges_state.hyperedges().is_empty());
+}
+
+#[test]
+fn test_stash_self_loop_connection() {
+    let mut map_state = create_test_map_state(0, 0, 100, 50);
     let connection = Connection {
         from_id: 1,
         from_side: Side::Right,
-        to_id: None,
-        to_side: None,
+        to_id: 1,
+        to_side: Side::Right,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
+    map_state.connections_state.focused_connection = Some(connection.clone().into());
+
+    map_state.connections_state.stash_connection();
+
+    assert_eq!(map_state.connections_state.connections(), [connection]);
+    // Listed for its note once
+    assert_eq!(map_state.connections_state.connection_index()[&1], [0]);
+    assert_eq!(
+        map_state
+            .connections_state
+            .get_connections_for_note(1)
+            .len(),
+        1
+    );
+
+    map_state.connections_state.remove_note(1);
+    assert!(map_state.connections_state.connections().is_empty());
+    assert!(map_state.connections_state.connection_index().is_empty());
+}
+
+#[test]
+fn test_draft_connection_completed() {
+    let mut draft = DraftConnection::new(1, Side::Right, Color::Red);
+    draft.adjust_weight(2);
+    assert_eq!(draft.completed(), None);
+
+    draft.to = Some((2, Side::Left));
+    let connection = draft.completed().unwrap();
+    assert_eq!((connection.to_id, connection.to_side), (2, Side::Left));
+    assert_eq!(connection.weight(), 3);
+    assert_eq!(DraftConnection::from(connection), draft);
+}
+
+#[test]
+fn test_rebuild_connection_index() {
+    let connection = |from_id, to_id| Connection {
+        from_id,
+        from_side: Side::Right,
+        to_id,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    };
+    let mut connections_state = ConnectionsState::from_connections(vec![
+        connection(1, 2),
+        connection(2, 3),
+        connection(3, 3),
+    ]);
+    let index = connections_state.connection_index().clone();
+    assert_eq!(index[&2], [0, 1]);
+    assert_eq!(index[&3], [1, 2]);
+
+    connections_state.rebuild_index();
+    assert_eq!(connections_state.connection_index(), &index);
+
+    // Notes left without connections are dropped from the index
+    connections_state.remove(0);
+    connections_state.rebuild_index();
+    assert!(!connections_state.connection_index().contains_key(&1));
+    assert_eq!(connections_state.connection_index()[&2], [0]);
+    assert_eq!(connections_state.connection_index()[&3], [0, 1]);
+}
+
+#[test]
+fn test_stash_connection_without_target() {
+    let mut map_state = create_test_map_state(0, 0, 100, 50);
+
+    // Create a connection without a target
+    let connection = DraftConnection::new(1, Side::Right, Color::White);
     map_state.connections_state.focused_connection = Some(connection);
 
     map_state.connections_state.stash_connection();
@@ -278,11 +414,14 @@ fn test_take_out_connection() {
     let connection = Connection {
         from_id: 1,
         from_side: Side::Right,
-        to_id: Some(2),
-        to_side: Some(Side::Left),
+        to_id: 2,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection);
+    map_state.connections_state.focused_connection = Some(connection.clone().into());
     map_state.connections_state.stash_connection();
 
     map_state.connections_state.take_out_connection(0);
@@ -297,7 +436,7 @@ fn test_take_out_connection() {
     // focused_connection should now contain the removed connection
     assert_eq!(
         map_state.connections_state.focused_connection,
-        Some(connection)
+        Some(connection.into())
     );
 }
 
@@ -347,3 +486,451 @@ fn test_on_tick_save_changes_no_unsaved_changes() {
     // Should not trigger save since there are no unsaved changes
     assert_eq!(map_state.persistence.last_save, old_last_save);
 }
+
+#[test]
+fn test_paste_dsl_creates_notes_and_connections() {
+    let mut map_state = create_test_map_state(0, 0, 100, 50);
+
+    let created = map_state.paste_dsl("A -> B\nA -> C").unwrap();
+
+    assert_eq!(created, 3);
+    assert_eq!(map_state.notes_state.notes().len(), 3);
+    assert_eq!(map_state.connections_state.connections().len(), 2);
+    assert!(map_state.persistence.has_unsaved_changes);
+
+    // Top-left of the pasted block is at the viewport center
+    let (center_x, center_y) = map_state.viewport.center();
+    let root = &map_state.notes_state.notes()[&0];
+    assert_eq!((root.x, root.y), (center_x, center_y));
+
+    let connection = &map_state.connections_state.connections()[0];
+    assert_eq!(connection.from_id, 0);
+    assert_eq!(connection.to_id, 1);
+    assert_eq!(connection.from_side, Side::Right);
+    assert_eq!(connection.to_side, Side::Left);
+}
+
+#[test]
+fn test_paste_invalid_dsl_changes_nothing() {
+    let mut map_state = create_test_map_state(0, 0, 100, 50);
+
+    assert!(map_state.paste_dsl("A ->").is_err());
+    assert!(map_state.notes_state.notes().is_empty());
+    assert!(!map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_paste_clipboard_notifies_outcome() {
+    let mut map_state = create_test_map_state(0, 0, 100, 50);
+
+    map_state.paste_clipboard(None);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::ClipboardUnavailable)
+    );
+
+    map_state.paste_clipboard(Some(String::from("A -> B\n -> C")));
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::PasteInvalidLine(2))
+    );
+
+    map_state.paste_clipboard(Some(String::from("# nothing here")));
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::PasteFail)
+    );
+    assert!(map_state.notes_state.notes().is_empty());
+
+    map_state.paste_clipboard(Some(String::from("A -> B")));
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::PasteSuccess)
+    );
+    assert_eq!(map_state.notes_state.notes().len(), 2);
+}
+
+#[test]
+fn test_graph_follows_connections() {
+    let mut map_state = create_test_map_state(0, 0, 100, 50);
+    map_state.paste_dsl("A -> B -> C\nD").unwrap();
+
+    let graph = map_state.graph();
+
+    assert_eq!(graph.node_count(), 4);
+    assert_eq!(graph.neighbors(0), &[1]);
+    assert_eq!(graph.bfs(0).collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert_eq!(graph.node(3).unwrap().data, "D");
+    assert_eq!(graph.edge_weight(0, 1), Some(1));
+}
+
+#[test]
+fn test_graph_uses_connection_weights() {
+    let mut map_state = create_test_map_state(0, 0, 100, 50);
+    map_state.paste_dsl("A -> B").unwrap();
+    let mut connection = map_state.connections_state.connections()[0].clone();
+    connection.adjust_weight(2);
+    map_state.connections_state = ConnectionsState::from_connections(vec![connection]);
+
+    assert_eq!(map_state.graph().edge_weight(0, 1), Some(3));
+}
+
+#[test]
+fn test_content_bounds() {
+    let mut map_state = create_test_map_state(0, 0, 100, 50);
+    assert_eq!(map_state.content_bounds(), None);
+
+    map_state
+        .notes_state
+        .add(10, 20, String::from("A"), Color::White);
+    map_state
+        .notes_state
+        .add(50, 5, String::from("B"), Color::White);
+    let (width, height) = map_state.notes_state.notes()[&1].get_dimensions();
+
+    assert_eq!(
+        map_state.content_bounds(),
+        Some(SignedRect {
+            x: 10,
+            y: 5,
+            width: 40 + width as isize,
+            height: 15 + height as isize,
+        })
+    );
+}
+
+#[test]
+fn test_fit_to_content_larger_than_screen() {
+    let mut map_state = create_test_map_state(0, 0, 100, 50);
+    map_state
+        .notes_state
+        .add(30, 40, String::from("A"), Color::White);
+    map_state
+        .notes_state
+        .add(500, 400, String::from("B"), Color::White);
+
+    map_state.fit_to_content();
+
+    // Shows the top left of the map
+    assert_eq!(map_state.viewport.view_pos.x, 30);
+    assert_eq!(map_state.viewport.view_pos.y, 40);
+}
+
+/// Map state with one note, saved to a map file in `temp_dir`.
+fn create_saved_map_state(temp_dir: &TempDir) -> MapState {
+    let path = temp_dir.path().join("map.json");
+    let mut map_state = create_map_state_using_mock_filesystem(path.clone());
+    map_state
+        .notes_state
+        .add(0, 0, String::from("Mine"), Color::White);
+    save_map_file(&mut map_state, &path).unwrap();
+    map_state
+}
+
+/// Writes the map file the way another program would, with a later modification time.
+fn change_map_file(path: &Path, content: &str) {
+    let mut other = create_map_state_using_mock_filesystem(path.to_path_buf());
+    other
+        .notes_state
+        .add(0, 0, String::from(content), Color::White);
+    write_json_data(path, &MapData::from_map_state(&mut other)).unwrap();
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(5))
+        .unwrap();
+}
+
+/// Lets the next check run without waiting for the check interval.
+fn check_now(map_state: &mut MapState) {
+    map_state.persistence.last_disk_check = Instant::now() - Duration::from_secs(2);
+    map_state.check_external_change();
+}
+
+#[test]
+fn test_save_records_map_file_modified_time() {
+    let temp_dir = TempDir::new().unwrap();
+    let map_state = create_saved_map_state(&temp_dir);
+
+    assert!(map_state.persistence.disk_modified.is_some());
+    assert_eq!(
+        map_state.persistence.disk_modified,
+        file_modified(&temp_dir.path().join("map.json"))
+    );
+}
+
+#[test]
+fn test_check_external_change_detects_other_writes() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut map_state = create_saved_map_state(&temp_dir);
+
+    // Nothing changed
+    check_now(&mut map_state);
+    assert!(map_state.persistence.external_change.is_none());
+
+    change_map_file(&temp_dir.path().join("map.json"), "Theirs");
+
+    // Not checked again before the interval has passed
+    map_state.check_external_change();
+    assert!(map_state.persistence.external_change.is_none());
+
+    check_now(&mut map_state);
+    let change = map_state.persistence.external_change.as_ref().unwrap();
+    assert_eq!(change.diff.changed, vec!["Theirs"]);
+    assert!(!change.show_diff);
+}
+
+#[test]
+fn test_check_external_change_ignores_same_contents() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut map_state = create_saved_map_state(&temp_dir);
+    let path = temp_dir.path().join("map.json");
+
+    change_map_file(&path, "Mine");
+    check_now(&mut map_state);
+
+    assert!(map_state.persistence.external_change.is_none());
+    assert_eq!(map_state.persistence.disk_modified, file_modified(&path));
+}
+
+#[test]
+fn test_check_external_change_retries_unreadable_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut map_state = create_saved_map_state(&temp_dir);
+    let path = temp_dir.path().join("map.json");
+
+    // E.g. caught in the middle of being written
+    std::fs::write(&path, "{ \"notes\": ").unwrap();
+    check_now(&mut map_state);
+    assert!(map_state.persistence.external_change.is_none());
+
+    change_map_file(&path, "Theirs");
+    check_now(&mut map_state);
+    assert!(map_state.persistence.external_change.is_some());
+}
+
+#[test]
+fn test_reload_external_change() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut map_state = create_saved_map_state(&temp_dir);
+    let path = temp_dir.path().join("map.json");
+    map_state
+        .notes_state
+        .add(5, 5, String::from("Unsaved"), Color::White);
+    map_state.persistence.mark_dirty();
+    map_state.notes_state.select(0);
+    map_state.mode = Mode::Visual;
+
+    change_map_file(&path, "Theirs");
+    check_now(&mut map_state);
+    map_state.reload_external_change();
+
+    assert!(map_state.persistence.external_change.is_none());
+    assert!(!map_state.persistence.has_unsaved_changes);
+    assert_eq!(map_state.persistence.disk_modified, file_modified(&path));
+    assert_eq!(map_state.mode, Mode::Normal);
+    assert_eq!(map_state.notes_state.selected_note_id(), None);
+    assert_eq!(map_state.notes_state.notes().len(), 1);
+    assert_eq!(map_state.notes_state.notes()[&0].content, "Theirs");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::MapReloaded)
+    );
+}
+
+#[test]
+fn test_keep_local_over_external_change() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut map_state = create_saved_map_state(&temp_dir);
+    let path = temp_dir.path().join("map.json");
+    map_state.settings.save_interval = Some(0);
+
+    change_map_file(&path, "Theirs");
+    check_now(&mut map_state);
+    map_state.persistence.mark_dirty();
+
+    // Auto-save waits for the user's decision
+    map_state.persistence.last_save = Instant::now() - Duration::from_secs(1);
+    map_state.auto_save_if_needed();
+    assert!(map_state.persistence.has_unsaved_changes);
+
+    map_state.keep_local_over_external_change();
+    assert!(map_state.persistence.external_change.is_none());
+    assert!(map_state.persistence.has_unsaved_changes);
+
+    // The same change isn't reported again
+    check_now(&mut map_state);
+    assert!(map_state.persistence.external_change.is_none());
+
+    // And the next save writes the open map over it
+    map_state.auto_save_if_needed();
+    finish_background_save(&mut map_state, true);
+    let saved: MapData = crate::utils::read_json_data(&path).unwrap();
+    assert_eq!(saved.notes[&0].content, "Mine");
+}
+
+#[test]
+fn test_merge_external_change_without_conflicts() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut map_state = create_saved_map_state(&temp_dir);
+    let path = temp_dir.path().join("map.json");
+    map_state
+        .notes_state
+        .add(5, 5, String::from("Local"), Color::White);
+    map_state.persistence.mark_dirty();
+
+    change_map_file(&path, "Theirs");
+    check_now(&mut map_state);
+    map_state.merge_external_change();
+
+    // Applied right away
+    assert!(map_state.persistence.external_change.is_none());
+    let notes = map_state.notes_state.notes();
+    assert_eq!(notes[&0].content, "Theirs");
+    assert_eq!(notes[&1].content, "Local");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::MergeSuccess)
+    );
+    // Not saved yet, and the file is the new merge base
+    assert!(map_state.persistence.has_unsaved_changes);
+    assert_eq!(
+        map_state.persistence.base_map.as_ref().unwrap().notes[&0].content,
+        "Theirs"
+    );
+    assert_eq!(map_state.persistence.disk_modified, file_modified(&path));
+}
+
+#[test]
+fn test_merge_external_change_with_conflict() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut map_state = create_saved_map_state(&temp_dir);
+    let path = temp_dir.path().join("map.json");
+    map_state.notes_state.get_mut(0).unwrap().content = String::from("Edited");
+
+    change_map_file(&path, "Theirs");
+    check_now(&mut map_state);
+    map_state.merge_external_change();
+
+    let change = map_state.persistence.external_change.as_ref().unwrap();
+    assert_eq!(change.merge.as_ref().unwrap().conflicts.len(), 1);
+
+    map_state.choose_merge_version(MergeChoice::Theirs);
+    map_state.finish_merge();
+
+    assert!(map_state.persistence.external_change.is_none());
+    assert_eq!(map_state.notes_state.notes()[&0].content, "Theirs");
+}
+
+#[test]
+fn test_merge_external_change_keeps_edit_of_unlocked_note() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut map_state = create_saved_map_state(&temp_dir);
+    let path = temp_dir.path().join("map.json");
+    // Note 0 was saved encrypted and has been unlocked and edited since
+    let encrypted = encrypt("Mine", "pw");
+    let base_note = map_state.persistence.base_map.as_mut().unwrap();
+    let base_note = base_note.notes.get_mut(&0).unwrap();
+    base_note.content = String::new();
+    base_note.encrypted = Some(encrypted.clone());
+    let note = map_state.notes_state.get_mut(0).unwrap();
+    note.content = String::from("Edited");
+    note.encrypted = Some(encrypted);
+    map_state.vault.set_passphrase(String::from("pw"));
+    map_state.vault.mark_unlocked(0);
+
+    change_map_file(&path, "Theirs");
+    check_now(&mut map_state);
+    map_state.merge_external_change();
+
+    let change = map_state.persistence.external_change.as_ref().unwrap();
+    let conflicts = &change.merge.as_ref().unwrap().conflicts;
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].kind, ConflictKind::Content);
+
+    map_state.finish_merge();
+    assert_eq!(map_state.notes_state.notes()[&0].content, "Edited");
+    assert!(map_state.vault.is_unlocked(0));
+}
+
+#[test]
+fn test_merge_external_change_needs_base() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut map_state = create_saved_map_state(&temp_dir);
+    map_state.persistence.base_map = None;
+
+    change_map_file(&temp_dir.path().join("map.json"), "Theirs");
+    check_now(&mut map_state);
+    map_state.merge_external_change();
+
+    let change = map_state.persistence.external_change.as_ref().unwrap();
+    assert!(change.merge.is_none());
+    assert_eq!(map_state.notes_state.notes()[&0].content, "Mine");
+}
+
+#[test]
+fn test_refresh_bar_clock() {
+    let mut map_state = create_map_state_using_mock_filesystem(PathBuf::from("/test/path"));
+    let nine = Local.with_ymd_and_hms(2026, 3, 2, 9, 5, 0).unwrap();
+
+    // Nothing to refresh without a clock in the status bar
+    map_state.ui_state.mark_redrawn();
+    map_state.refresh_bar_clock(nine);
+    assert_eq!(map_state.ui_state.bar_clock, "");
+    assert!(!map_state.ui_state.needs_clear_and_redraw);
+
+    map_state.settings.status_bar_format = Some(String::from("{mode}|{clock}"));
+    map_state.refresh_bar_clock(nine);
+    assert_eq!(map_state.ui_state.bar_clock, "09:05");
+    assert!(map_state.ui_state.needs_clear_and_redraw);
+
+    // Redrawn only once the minute changes
+    map_state.ui_state.mark_redrawn();
+    map_state.refresh_bar_clock(nine + chrono::Duration::seconds(30));
+    assert!(!map_state.ui_state.needs_clear_and_redraw);
+    map_state.refresh_bar_clock(nine + chrono::Duration::seconds(60));
+    assert_eq!(map_state.ui_state.bar_clock, "09:06");
+    assert!(map_state.ui_state.needs_clear_and_redraw);
+}
+
+#[test]
+fn test_note_uuids_setting() {
+    let mut existing = Note::new(0, 0, String::from("Has one"), Color::White);
+    let uuid = uuid::Uuid::new_v4();
+    existing.uuid = Some(uuid);
+    let map_data = MapData {
+        notes: HashMap::from([
+            (0, existing),
+            (1, Note::new(0, 0, String::from("Old"), Color::White)),
+        ]),
+        next_note_id_counter: 2,
+        render_order: vec![0, 1],
+        view_pos: ViewPos::new(),
+        connections: vec![],
+        regions: vec![],
+        hyperedges: vec![],
+        layers: vec![],
+        view_state: None,
+        trash: vec![],
+    };
+
+    // Off: maps are left as they are
+    let mut map_state = create_map_state_using_mock_filesystem(PathBuf::from("/test/path"));
+    map_state.load_map_data(map_data.clone());
+    assert!(map_state.notes_state.notes()[&1].uuid.is_none());
+    assert!(!map_state.persistence.has_unsaved_changes);
+
+    map_state.settings.note_uuids = true;
+    map_state.load_map_data(map_data);
+    let notes = map_state.notes_state.notes();
+    assert_eq!(notes[&0].uuid, Some(uuid));
+    assert!(notes[&1].uuid.is_some_and(|new| new != uuid));
+    // Saved with the map like any other change
+    assert!(map_state.persistence.has_unsaved_changes);
+
+    map_state.add_note();
+    let added = map_state.notes_state.expect_selected_note().uuid;
+    assert!(added.is_some());
+    assert_ne!(added, map_state.notes_state.notes()[&1].uuid);
+}
@@ -0,0 +1,208 @@
+use chrono::Local;
+use ratatui::style::Color;
+use std::path::PathBuf;
+
+use crate::{
+    states::{
+        MapState,
+        map::{Connection, MAX_TRASHED_NOTES, Note, Notification, Side, TrashState, TrashedNote},
+        settings::Settings,
+    },
+    utils::MapData,
+};
+
+fn create_test_map_state() -> MapState {
+    MapState::new_with_settings(PathBuf::from("/test/path"), Settings::new())
+}
+
+fn connect(map_state: &mut MapState, from_id: usize, to_id: usize) {
+    map_state.connections_state.add(Connection {
+        from_id,
+        from_side: Side::Right,
+        to_id,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    });
+}
+
+fn trashed(id: usize) -> TrashedNote {
+    TrashedNote {
+        id,
+        note: Note::new(0, 0, format!("Note {id}"), Color::White),
+        connections: vec![],
+        deleted_at: Local::now(),
+    }
+}
+
+/// Notes 0-2 with 0 connected to 1 and 2, and note 0 deleted
+fn create_map_state_with_deleted_hub() -> MapState {
+    let mut map_state = create_test_map_state();
+    for title in ["Hub", "Left", "Right"] {
+        map_state
+            .notes_state
+            .add(10, 10, String::from(title), Color::White);
+    }
+    connect(&mut map_state, 0, 1);
+    connect(&mut map_state, 2, 0);
+    map_state.notes_state.select(0);
+    map_state.delete_selected_note();
+    map_state
+}
+
+#[test]
+fn test_trash_keeps_newest_first_up_to_limit() {
+    let mut trash = TrashState::new();
+    for id in 0..MAX_TRASHED_NOTES + 5 {
+        trash.push(trashed(id));
+    }
+
+    assert_eq!(trash.notes().len(), MAX_TRASHED_NOTES);
+    assert_eq!(trash.notes()[0].id, MAX_TRASHED_NOTES + 4);
+    assert_eq!(trash.notes().last().unwrap().id, 5);
+}
+
+#[test]
+fn test_trash_browser_selection() {
+    let mut trash = TrashState::new();
+    assert_eq!(trash.selected(), None);
+
+    trash.push(trashed(0));
+    trash.push(trashed(1));
+    trash.open_browser();
+    assert_eq!(trash.selected(), Some(0));
+
+    trash.select_next();
+    trash.select_next();
+    assert_eq!(trash.selected(), Some(1));
+
+    // The highlight stays on the list when its last note is taken
+    assert_eq!(trash.take(1).map(|trashed| trashed.id), Some(0));
+    assert_eq!(trash.selected(), Some(0));
+    trash.select_previous();
+    assert_eq!(trash.selected(), Some(0));
+
+    trash.clear();
+    assert_eq!(trash.selected(), None);
+}
+
+#[test]
+fn test_deleted_note_goes_to_trash_with_connections() {
+    let map_state = create_map_state_with_deleted_hub();
+
+    assert!(!map_state.notes_state.notes().contains_key(&0));
+    assert!(map_state.connections_state.connections().is_empty());
+    let trashed = &map_state.trash.notes()[0];
+    assert_eq!(trashed.id, 0);
+    assert_eq!(trashed.note.content, "Hub");
+    assert_eq!(trashed.connections.len(), 2);
+}
+
+#[test]
+fn test_restore_trashed_note_with_connections() {
+    let mut map_state = create_map_state_with_deleted_hub();
+    map_state.trash.open_browser();
+
+    map_state.restore_trashed_note();
+
+    assert!(map_state.trash.is_empty());
+    assert_eq!(map_state.notes_state.notes()[&0].content, "Hub");
+    assert!(map_state.notes_state.render_order().contains(&0));
+    assert_eq!(map_state.connections_state.connections().len(), 2);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::NoteRestored(2))
+    );
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_restore_skips_connections_to_deleted_notes() {
+    let mut map_state = create_map_state_with_deleted_hub();
+    map_state.notes_state.select(1);
+    map_state.delete_selected_note();
+    map_state.trash.open_browser();
+
+    // Restore the hub, which was deleted first
+    map_state.trash.select_next();
+    map_state.restore_trashed_note();
+
+    assert_eq!(map_state.connections_state.connections().len(), 1);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::NoteRestored(1))
+    );
+
+    // Restoring the other one brings back the connection between them
+    map_state.restore_trashed_note();
+    assert_eq!(map_state.connections_state.connections().len(), 2);
+}
+
+#[test]
+fn test_restore_gives_new_id_when_old_one_is_taken() {
+    let mut map_state = create_map_state_with_deleted_hub();
+    map_state.trash.open_browser();
+    // As if another program reused the id
+    let taken = map_state
+        .notes_state
+        .restore(0, Note::new(0, 0, String::from("New"), Color::White));
+    assert_eq!(taken, 0);
+
+    map_state.restore_trashed_note();
+
+    let id = map_state.notes_state.next_note_id_counter() - 1;
+    assert_eq!(map_state.notes_state.notes()[&id].content, "Hub");
+    assert!(
+        map_state
+            .connections_state
+            .connections()
+            .iter()
+            .all(|connection| connection.from_id == id || connection.touches(id))
+    );
+}
+
+#[test]
+fn test_purge_and_empty_trash() {
+    let mut map_state = create_map_state_with_deleted_hub();
+    map_state.notes_state.select(1);
+    map_state.delete_selected_note();
+    map_state.trash.open_browser();
+
+    map_state.purge_trashed_note();
+    assert_eq!(map_state.trash.notes().len(), 1);
+    assert_eq!(map_state.trash.notes()[0].id, 0);
+
+    map_state.confirm_empty_trash();
+    assert!(map_state.ui_state.dialog.is_some());
+    map_state.empty_trash();
+    assert!(map_state.trash.is_empty());
+}
+
+#[test]
+fn test_trash_round_trips_through_map_data() {
+    let mut map_state = create_map_state_with_deleted_hub();
+
+    let map_data = MapData::from_map_state(&mut map_state);
+    assert_eq!(map_data.trash.len(), 1);
+    let json = serde_json::to_string(&map_data).unwrap();
+    let map_data: MapData = serde_json::from_str(&json).unwrap();
+
+    let mut loaded = create_test_map_state();
+    loaded.load_map_data(map_data);
+    assert_eq!(loaded.trash.notes(), map_state.trash.notes());
+}
+
+#[test]
+fn test_maps_without_trash_load() {
+    let map_data: MapData = serde_json::from_str(
+        r#"{"view_pos": {"x": 0, "y": 0}, "next_note_id_counter": 0, "notes": {},
+            "render_order": [], "connections": []}"#,
+    )
+    .unwrap();
+    assert!(map_data.trash.is_empty());
+
+    // And maps with an empty trash are saved without it
+    assert!(!serde_json::to_string(&map_data).unwrap().contains("trash"));
+}
@@ -0,0 +1,119 @@
+use ratatui::style::Color;
+
+use crate::{
+    states::map::{Connection, MapState, Mode, Side, TUTORIAL_STEPS},
+    utils::{Locale, catalog, test_utils::MockFileSystem},
+};
+
+fn tutorial_step(map_state: &MapState) -> usize {
+    map_state.tutorial.as_ref().unwrap().step
+}
+
+#[test]
+fn test_tutorial_map() {
+    let map_state = MapState::new_tutorial_with_fs(&MockFileSystem::new());
+
+    assert_eq!(map_state.notes_state.notes().len(), 4);
+    assert_eq!(map_state.connections_state.connections().len(), 3);
+    assert!(map_state.notes_state.notes()[&0].content.contains("tmmpr"));
+    assert!(!map_state.persistence.has_unsaved_changes);
+    assert!(
+        map_state
+            .persistence
+            .file_write_path
+            .ends_with(".config/tmmpr/tutorial.json")
+    );
+    assert_eq!(tutorial_step(&map_state), 0);
+}
+
+#[test]
+fn test_tutorial_steps_advance_when_done() {
+    let mut map_state = MapState::new_tutorial_with_fs(&MockFileSystem::new());
+
+    // Nothing done yet
+    map_state.advance_tutorial();
+    assert_eq!(tutorial_step(&map_state), 0);
+
+    map_state.viewport.view_pos.x += 1;
+    map_state.advance_tutorial();
+    assert_eq!(tutorial_step(&map_state), 1);
+
+    map_state.notes_state.select(0);
+    map_state.mode = Mode::Visual;
+    map_state.advance_tutorial();
+    assert_eq!(tutorial_step(&map_state), 2);
+
+    // Moving the note only counts once back in Visual mode
+    map_state.mode = Mode::VisualMove;
+    map_state.notes_state.get_mut(0).unwrap().x += 3;
+    map_state.advance_tutorial();
+    assert_eq!(tutorial_step(&map_state), 2);
+    map_state.mode = Mode::Visual;
+    map_state.advance_tutorial();
+    assert_eq!(tutorial_step(&map_state), 3);
+
+    map_state.mode = Mode::Normal;
+    map_state.advance_tutorial();
+    assert_eq!(tutorial_step(&map_state), 4);
+
+    map_state
+        .notes_state
+        .add(80, 20, String::from("mine"), Color::White);
+    map_state.advance_tutorial();
+    assert_eq!(tutorial_step(&map_state), 5);
+
+    map_state.connections_state.add(Connection {
+        from_id: 4,
+        from_side: Side::Right,
+        to_id: 0,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    });
+    map_state.advance_tutorial();
+    assert_eq!(tutorial_step(&map_state), 6);
+
+    map_state.ui_state.show_help(2);
+    map_state.advance_tutorial();
+    assert_eq!(tutorial_step(&map_state), TUTORIAL_STEPS.len() - 1);
+    assert!(map_state.tutorial.as_ref().unwrap().is_complete());
+
+    // The last step stays
+    map_state.advance_tutorial();
+    assert_eq!(tutorial_step(&map_state), TUTORIAL_STEPS.len() - 1);
+}
+
+#[test]
+fn test_tutorial_isnt_auto_saved() {
+    let mut map_state = MapState::new_tutorial_with_fs(&MockFileSystem::new());
+    map_state.settings.save_interval = Some(0);
+    map_state.persistence.mark_dirty();
+
+    map_state.auto_save_if_needed();
+
+    assert!(map_state.persistence.background_save.is_none());
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_every_locale_has_the_tutorial_texts() {
+    let keys = [
+        "tutorial.bar",
+        "tutorial.note.welcome",
+        "tutorial.note.notes",
+    ]
+    .into_iter()
+    .chain(["tutorial.note.modes", "tutorial.note.help"])
+    .chain(TUTORIAL_STEPS.iter().map(|step| step.text));
+    for key in keys {
+        for locale in Locale::ALL {
+            assert!(
+                catalog(locale).contains_key(key),
+                "{} is missing {key}",
+                locale.name()
+            );
+        }
+    }
+}
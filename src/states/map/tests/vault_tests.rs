@@ -0,0 +1,169 @@
+use ratatui::style::Color;
+use std::path::PathBuf;
+
+use crate::{
+    states::{
+        MapState,
+        map::{Mode, PassphrasePurpose},
+    },
+    utils::{CryptoError, encrypt, test_utils::MockFileSystem},
+};
+
+fn create_map_state_with_selected_note(content: &str) -> MapState {
+    let mock_fs = MockFileSystem::new();
+    let mut map_state = MapState::new_with_fs(PathBuf::from("/test/path"), &mock_fs);
+    let id = map_state
+        .notes_state
+        .add(0, 0, String::from(content), Color::White);
+    map_state.notes_state.select(id);
+    map_state.mode = Mode::Visual;
+    map_state
+}
+
+fn type_passphrase(map_state: &mut MapState, passphrase: &str) {
+    map_state
+        .ui_state
+        .passphrase_prompt
+        .as_mut()
+        .unwrap()
+        .input
+        .push_str(passphrase);
+    map_state.submit_passphrase();
+}
+
+#[test]
+fn test_encrypting_note_asks_for_passphrase_first() {
+    let mut map_state = create_map_state_with_selected_note("secret");
+
+    map_state.toggle_note_encryption();
+
+    let prompt = map_state.ui_state.passphrase_prompt.as_ref().unwrap();
+    assert_eq!(prompt.purpose, PassphrasePurpose::EncryptNote);
+    assert!(
+        map_state
+            .notes_state
+            .expect_selected_note()
+            .encrypted
+            .is_none()
+    );
+}
+
+#[test]
+fn test_encrypt_note_keeps_it_readable_in_session() {
+    let mut map_state = create_map_state_with_selected_note("secret");
+
+    map_state.toggle_note_encryption();
+    type_passphrase(&mut map_state, "pw");
+
+    let note = map_state.notes_state.expect_selected_note();
+    assert!(note.encrypted.is_some());
+    assert_eq!(note.content, "secret");
+    assert!(!map_state.note_is_locked(0));
+    assert!(map_state.ui_state.passphrase_prompt.is_none());
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_second_note_is_encrypted_without_asking_again() {
+    let mut map_state = create_map_state_with_selected_note("first");
+    map_state.toggle_note_encryption();
+    type_passphrase(&mut map_state, "pw");
+
+    let id = map_state
+        .notes_state
+        .add(30, 0, String::from("second"), Color::White);
+    map_state.notes_state.select(id);
+    map_state.toggle_note_encryption();
+
+    assert!(map_state.ui_state.passphrase_prompt.is_none());
+    assert!(
+        map_state
+            .notes_state
+            .expect_selected_note()
+            .encrypted
+            .is_some()
+    );
+}
+
+#[test]
+fn test_notes_for_saving_strips_plaintext() {
+    let mut map_state = create_map_state_with_selected_note("secret");
+    map_state.toggle_note_encryption();
+    type_passphrase(&mut map_state, "pw");
+
+    let saved_notes = map_state.notes_for_saving();
+    let saved_note = &saved_notes[&0];
+
+    assert_eq!(saved_note.content, "");
+    let stored = saved_note.encrypted.as_ref().unwrap();
+    assert_eq!(crate::utils::decrypt(stored, "pw").unwrap(), "secret");
+    // In-memory note is untouched
+    assert_eq!(
+        map_state.notes_state.expect_selected_note().content,
+        "secret"
+    );
+}
+
+#[test]
+fn test_unlock_locked_note() {
+    let mut map_state = create_map_state_with_selected_note("");
+    map_state.notes_state.expect_selected_note_mut().encrypted = Some(encrypt("hidden", "pw"));
+    assert!(map_state.note_is_locked(0));
+
+    map_state.toggle_note_encryption();
+    assert_eq!(
+        map_state
+            .ui_state
+            .passphrase_prompt
+            .as_ref()
+            .unwrap()
+            .purpose,
+        PassphrasePurpose::UnlockNote
+    );
+    type_passphrase(&mut map_state, "pw");
+
+    assert!(!map_state.note_is_locked(0));
+    assert_eq!(
+        map_state.notes_state.expect_selected_note().content,
+        "hidden"
+    );
+}
+
+#[test]
+fn test_unlock_with_wrong_passphrase_keeps_prompt_open() {
+    let mut map_state = create_map_state_with_selected_note("");
+    map_state.notes_state.expect_selected_note_mut().encrypted = Some(encrypt("hidden", "pw"));
+
+    map_state.toggle_note_encryption();
+    type_passphrase(&mut map_state, "nope");
+
+    let prompt = map_state.ui_state.passphrase_prompt.as_ref().unwrap();
+    assert_eq!(prompt.error, Some(CryptoError::WrongPassphrase));
+    assert_eq!(prompt.input, "");
+    assert!(map_state.note_is_locked(0));
+    assert!(!map_state.vault.has_passphrase());
+}
+
+#[test]
+fn test_locked_note_cannot_be_edited() {
+    let mut map_state = create_map_state_with_selected_note("");
+    map_state.notes_state.expect_selected_note_mut().encrypted = Some(encrypt("hidden", "pw"));
+
+    map_state.switch_to_edit_mode();
+
+    assert_eq!(map_state.mode, Mode::Visual);
+}
+
+#[test]
+fn test_toggling_unlocked_note_removes_encryption() {
+    let mut map_state = create_map_state_with_selected_note("secret");
+    map_state.toggle_note_encryption();
+    type_passphrase(&mut map_state, "pw");
+
+    map_state.toggle_note_encryption();
+
+    let note = map_state.notes_state.expect_selected_note();
+    assert!(note.encrypted.is_none());
+    assert_eq!(note.content, "secret");
+    assert_eq!(map_state.notes_for_saving()[&0].content, "secret");
+}
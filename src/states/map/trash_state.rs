@@ -0,0 +1,127 @@
+use chrono::{DateTime, Local};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{Connection, Note};
+
+/// Most notes kept in the trash; the oldest go for good when more are deleted
+pub const MAX_TRASHED_NOTES: usize = 100;
+
+/// A deleted note, saved with the map so it can be restored after reopening it.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
+pub struct TrashedNote {
+    /// Id the note had; it gets it back on restoring unless another note has it by then
+    pub id: usize,
+    pub note: Note,
+    /// Connections the note had, restored along with it to the notes still there
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connections: Vec<Connection>,
+    pub deleted_at: DateTime<Local>,
+}
+
+/// The trash list shown over the map (`:trash`).
+#[derive(PartialEq, Debug, Default)]
+pub struct TrashBrowser {
+    /// Index of the highlighted note
+    pub selected: usize,
+}
+
+/// Notes deleted from the map, newest first.
+#[derive(PartialEq, Debug, Default)]
+pub struct TrashState {
+    notes: Vec<TrashedNote>,
+    /// Intercepts all input while shown
+    pub browser: Option<TrashBrowser>,
+}
+
+impl TrashState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The trash as loaded from a map file.
+    pub fn from_notes(notes: Vec<TrashedNote>) -> Self {
+        Self {
+            notes,
+            browser: None,
+        }
+    }
+
+    pub fn notes(&self) -> &[TrashedNote] {
+        &self.notes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+    }
+
+    /// Puts a deleted note at the top of the trash, dropping the oldest ones past
+    /// [`MAX_TRASHED_NOTES`].
+    pub fn push(&mut self, trashed: TrashedNote) {
+        self.notes.insert(0, trashed);
+        self.notes.truncate(MAX_TRASHED_NOTES);
+    }
+
+    /// Keeps a connection with the trashed notes it leads to, to be restored with them.
+    pub fn add_connection(&mut self, connection: Connection) {
+        for trashed in self.notes.iter_mut() {
+            if connection.touches(trashed.id) && !trashed.connections.contains(&connection) {
+                trashed.connections.push(connection.clone());
+            }
+        }
+    }
+
+    /// Takes a note out of the trash, keeping the browser's highlight on the list.
+    pub fn take(&mut self, index: usize) -> Option<TrashedNote> {
+        if index >= self.notes.len() {
+            return None;
+        }
+
+        let trashed = self.notes.remove(index);
+        if let Some(browser) = self.browser.as_mut() {
+            browser.selected = browser.selected.min(self.notes.len().saturating_sub(1));
+        }
+        Some(trashed)
+    }
+
+    /// Deletes every note in the trash for good.
+    pub fn clear(&mut self) {
+        self.notes.clear();
+        if let Some(browser) = self.browser.as_mut() {
+            browser.selected = 0;
+        }
+    }
+
+    /// Shows the trash browser with the most recently deleted note highlighted.
+    pub fn open_browser(&mut self) {
+        self.browser = Some(TrashBrowser::default());
+    }
+
+    pub fn close_browser(&mut self) {
+        self.browser = None;
+    }
+
+    /// Index of the note highlighted in the browser, if it's shown and not empty.
+    pub fn selected(&self) -> Option<usize> {
+        self.browser
+            .as_ref()
+            .map(|browser| browser.selected)
+            .filter(|&selected| selected < self.notes.len())
+    }
+
+    /// Moves the browser's highlight down, stopping at the last note.
+    pub fn select_next(&mut self) {
+        let len = self.notes.len();
+        if let Some(browser) = self.browser.as_mut()
+            && browser.selected + 1 < len
+        {
+            browser.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if let Some(browser) = self.browser.as_mut() {
+            browser.selected = browser.selected.saturating_sub(1);
+        }
+    }
+}
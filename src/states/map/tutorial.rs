@@ -0,0 +1,131 @@
+use super::{MapState, Mode, ViewPos};
+use crate::{
+    graph::MapBuilder,
+    utils::{Locale, MapData, tr_lines},
+};
+
+/// A step of the tutorial, shown in the bar until it's done.
+pub struct TutorialStep {
+    /// Catalog key of what to do
+    pub text: &'static str,
+    /// True once the user did it, compared to the map as it was when the step started
+    done: fn(&MapState, &StepStart) -> bool,
+}
+
+const fn step(text: &'static str, done: fn(&MapState, &StepStart) -> bool) -> TutorialStep {
+    TutorialStep { text, done }
+}
+
+/// The steps of the tutorial, each a few keys of one mode. The last one only says
+/// that the tutorial is complete.
+pub const TUTORIAL_STEPS: &[TutorialStep] = &[
+    step("tutorial.step.pan", |map_state, start| {
+        map_state.viewport.view_pos != start.view_pos
+    }),
+    step("tutorial.step.select", |map_state, _| {
+        map_state.mode == Mode::Visual
+    }),
+    step("tutorial.step.move", |map_state, start| {
+        map_state.mode == Mode::Visual
+            && start.note_positions.iter().any(|(id, position)| {
+                map_state
+                    .notes_state
+                    .notes()
+                    .get(id)
+                    .is_some_and(|note| (note.x, note.y) != *position)
+            })
+    }),
+    step("tutorial.step.normal", |map_state, _| {
+        map_state.mode == Mode::Normal
+    }),
+    step("tutorial.step.add", |map_state, start| {
+        map_state.mode == Mode::Normal && map_state.notes_state.notes().len() > start.note_count
+    }),
+    step("tutorial.step.connect", |map_state, start| {
+        map_state.connections_state.connections().len() > start.connection_count
+    }),
+    step("tutorial.step.help", |map_state, _| {
+        map_state.ui_state.is_help_visible()
+    }),
+    step("tutorial.step.done", |_, _| false),
+];
+
+/// The map as it was when a tutorial step started.
+#[derive(PartialEq, Debug)]
+struct StepStart {
+    view_pos: ViewPos,
+    /// Note ids with their positions
+    note_positions: Vec<(usize, (isize, isize))>,
+    note_count: usize,
+    connection_count: usize,
+}
+
+impl StepStart {
+    fn capture(map_state: &MapState) -> Self {
+        let notes = map_state.notes_state.notes();
+        Self {
+            view_pos: map_state.viewport.view_pos.clone(),
+            note_positions: notes
+                .iter()
+                .map(|(id, note)| (*id, (note.x, note.y)))
+                .collect(),
+            note_count: notes.len(),
+            connection_count: map_state.connections_state.connections().len(),
+        }
+    }
+}
+
+/// Progress through the tutorial, opened from the start screen on a generated map.
+#[derive(PartialEq, Debug)]
+pub struct Tutorial {
+    /// Index into [`TUTORIAL_STEPS`]
+    pub step: usize,
+    start: StepStart,
+}
+
+impl Tutorial {
+    pub fn new(map_state: &MapState) -> Self {
+        Self {
+            step: 0,
+            start: StepStart::capture(map_state),
+        }
+    }
+
+    pub fn current(&self) -> &'static TutorialStep {
+        &TUTORIAL_STEPS[self.step]
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.step + 1 == TUTORIAL_STEPS.len()
+    }
+
+    /// Moves on to the next step if the user did the current one. Returns true if so.
+    pub fn advance(&mut self, map_state: &MapState) -> bool {
+        if self.is_complete() || !(self.current().done)(map_state, &self.start) {
+            return false;
+        }
+
+        self.step += 1;
+        self.start = StepStart::capture(map_state);
+        true
+    }
+}
+
+/// The sample map of the tutorial, with a few connected notes explaining the basics.
+pub fn tutorial_map(locale: Locale) -> MapData {
+    let note = |key| tr_lines(locale, key).join("\n");
+
+    MapBuilder::new()
+        .note(note("tutorial.note.welcome"))
+        .at(4, 2)
+        .note(note("tutorial.note.notes"))
+        .at(52, 2)
+        .note(note("tutorial.note.modes"))
+        .at(4, 14)
+        .note(note("tutorial.note.help"))
+        .at(52, 14)
+        .connect(0, 1)
+        .connect(0, 2)
+        .connect(1, 3)
+        .build()
+}
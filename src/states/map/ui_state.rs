@@ -1,21 +1,123 @@
-use crate::states::map::{DiscardMenuType, Notification};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use crate::{
+    states::{
+        Dialog,
+        map::{
+            BoxSelect, ConnectionTable, HELP_PAGES, MapDialogAction, NoteSearch, NoteStatus,
+            Notification, NotificationQueue, PassphrasePrompt, PathCache, SearchState,
+            SnapshotPicker, WorkspacePicker, WorkspaceSearch,
+        },
+    },
+    utils::MapChanges,
+};
+
+/// Where the user left off in a map, saved with it so reopening the map resumes there.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug, Default)]
+pub struct ViewState {
+    /// Notes selected in Visual mode, the selected note first; selected again on reopening
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub selection: Vec<usize>,
+    /// Help page that was shown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help_page: Option<usize>,
+    /// Query of the last search; its matches are highlighted again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    /// Layer new notes were being put on
+    #[serde(default)]
+    pub active_layer: usize,
+}
+
+impl ViewState {
+    /// True if there's nothing to restore beyond the view position.
+    pub fn is_empty(&self) -> bool {
+        *self == ViewState::default()
+    }
+}
 
 #[derive(PartialEq, Debug)]
 pub struct UIState {
     pub needs_clear_and_redraw: bool,
-    pub show_notification: Option<Notification>,
-    pub confirm_discard_menu: Option<DiscardMenuType>,
+    pub notifications: NotificationQueue,
+    /// Confirmation shown above the map, e.g. before discarding changes; intercepts all
+    /// input while shown
+    pub dialog: Option<Dialog<MapDialogAction>>,
     /// Page number of the currently visible help screen
     pub help_screen: Option<usize>,
+    /// Query typed after `/` on the help screen, which then lists the matching bindings
+    pub help_search: Option<String>,
+    /// Passphrase entry for note encryption; intercepts all input while shown
+    pub passphrase_prompt: Option<PassphrasePrompt>,
+    /// Note a path search starts from, set while the user picks the target note
+    pub path_start: Option<usize>,
+    /// Note ids of the last found path, highlighted until cleared
+    pub highlighted_path: Vec<usize>,
+    /// Graph statistics overlay toggled in Normal mode
+    pub show_stats: bool,
+    /// Orphaned note the viewport was last moved to, so the next one can be found
+    pub last_orphan: Option<usize>,
+    /// First key of a two-key command (the `z` of `zf`, or `a` of the Visual mode `ah`)
+    pub pending_key: Option<char>,
+    /// Text typed on the `:` command line; intercepts all input while shown
+    pub command_line: Option<String>,
+    /// Title being typed for the selected note (`t` in Visual mode); intercepts all
+    /// input while shown
+    pub title_prompt: Option<String>,
+    /// Notes without this status are dimmed (`:filter <status>`)
+    pub status_filter: Option<NoteStatus>,
+    /// Intercepts all input while shown
+    pub snapshot_picker: Option<SnapshotPicker>,
+    /// Intercepts all input while shown
+    pub workspace_picker: Option<WorkspacePicker>,
+    /// Every connection listed by `:connections`; intercepts all input while shown
+    pub connection_table: Option<ConnectionTable>,
+    /// Results of a `:grep` over the workspace; intercepts all input while shown
+    pub workspace_search: Option<WorkspaceSearch>,
+    /// Differences highlighted on the canvas, set on the map shown by a diff view
+    pub map_changes: Option<MapChanges>,
+    /// Matches stay highlighted until Esc in Normal mode
+    pub search: Option<SearchState>,
+    /// Search within the note being edited; intercepts all input while its query is
+    /// typed, and its matches stay highlighted until Edit mode is left
+    pub note_search: Option<NoteSearch>,
+    /// Selection rectangle, set in Visual (Box) mode
+    pub box_select: Option<BoxSelect>,
+    /// Time of day shown by a status bar format's `{clock}`
+    pub bar_clock: String,
+    /// Connection paths from earlier frames, reused while their notes stay put
+    pub path_cache: PathCache,
 }
 
 impl UIState {
     pub fn new() -> Self {
         Self {
             needs_clear_and_redraw: true,
-            show_notification: None,
-            confirm_discard_menu: None,
+            notifications: NotificationQueue::new(),
+            dialog: None,
             help_screen: None,
+            help_search: None,
+            passphrase_prompt: None,
+            path_start: None,
+            highlighted_path: Vec::new(),
+            show_stats: false,
+            last_orphan: None,
+            pending_key: None,
+            command_line: None,
+            title_prompt: None,
+            status_filter: None,
+            snapshot_picker: None,
+            workspace_picker: None,
+            connection_table: None,
+            workspace_search: None,
+            map_changes: None,
+            search: None,
+            note_search: None,
+            box_select: None,
+            bar_clock: String::new(),
+            path_cache: PathCache::new(),
         }
     }
 
@@ -27,31 +129,46 @@ impl UIState {
         self.needs_clear_and_redraw = false;
     }
 
+    /// Shows a notification above the bar until it expires, see [`NotificationQueue`]
     pub fn set_notification(&mut self, notification: Notification) {
-        self.show_notification = Some(notification);
+        self.notifications.push(notification);
+        self.request_redraw();
     }
 
-    pub fn clear_notification(&mut self) {
-        self.show_notification = None;
-    }
-
-    pub fn show_discard_menu(&mut self, menu_type: DiscardMenuType) {
-        self.confirm_discard_menu = Some(menu_type);
+    /// Removes the expired notifications, redrawing the screen without them.
+    pub fn expire_notifications(&mut self, now: Instant) {
+        if self.notifications.expire(now) {
+            self.request_redraw();
+        }
     }
 
-    pub fn hide_discard_menu(&mut self) {
-        self.confirm_discard_menu = None;
+    pub fn close_dialog(&mut self) {
+        self.dialog = None;
     }
 
+    /// Shows a help page, the last one if there aren't that many
     pub fn show_help(&mut self, page: usize) {
-        self.help_screen = Some(page);
+        self.help_screen = Some(page.clamp(1, HELP_PAGES.len()));
     }
 
     pub fn hide_help(&mut self) {
         self.help_screen = None;
+        self.help_search = None;
     }
 
     pub fn is_help_visible(&self) -> bool {
         self.help_screen.is_some()
     }
+
+    pub fn clear_path(&mut self) {
+        self.path_start = None;
+        self.highlighted_path.clear();
+    }
+
+    /// Returns true if the notes are next to each other on the highlighted path.
+    pub fn path_links(&self, a: usize, b: usize) -> bool {
+        self.highlighted_path
+            .windows(2)
+            .any(|pair| (pair[0], pair[1]) == (a, b) || (pair[0], pair[1]) == (b, a))
+    }
 }
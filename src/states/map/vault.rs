@@ -0,0 +1,131 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::utils::{
+    CryptoError, EncryptedText, KDF_ROUNDS, Key, decrypt_with_key, derive_key, encrypt_with_key,
+    new_salt,
+};
+
+/// What to do once the user has entered the session passphrase.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum PassphrasePurpose {
+    /// Mark the selected note as encrypted
+    EncryptNote,
+    /// Decrypt the selected (locked) note for this session
+    UnlockNote,
+    /// Encrypt the map file at rest with a new passphrase
+    EncryptMap,
+    /// Repeat the new map file passphrase
+    ConfirmMapPassphrase,
+}
+
+/// State of the passphrase prompt shown over the map.
+#[derive(PartialEq, Debug)]
+pub struct PassphrasePrompt {
+    pub purpose: PassphrasePurpose,
+    pub input: String,
+    /// Set when the previously entered passphrase could not decrypt the note
+    pub error: Option<CryptoError>,
+    /// The passphrase as typed the first time, while it's being repeated
+    pub first_entry: Option<String>,
+}
+
+impl PassphrasePrompt {
+    pub fn new(purpose: PassphrasePurpose) -> Self {
+        Self {
+            purpose,
+            input: String::new(),
+            error: None,
+            first_entry: None,
+        }
+    }
+}
+
+/// Session-only secrets for per-note encryption.
+///
+/// Nothing in here is ever persisted. Encrypted notes are stored as ciphertext in the
+/// map file and only decrypted in memory once the user unlocks them.
+#[derive(PartialEq, Debug)]
+pub struct VaultState {
+    passphrase: Option<String>,
+    /// Salt used for everything encrypted during this session
+    session_salt: Vec<u8>,
+    /// Keys derived from the passphrase, per salt, to avoid re-running the KDF
+    keys: HashMap<(Vec<u8>, u32), Key>,
+    /// Encrypted notes whose plaintext is currently held in `Note::content`
+    unlocked: HashSet<usize>,
+}
+
+impl Default for VaultState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VaultState {
+    pub fn new() -> Self {
+        Self {
+            passphrase: None,
+            session_salt: new_salt(),
+            keys: HashMap::new(),
+            unlocked: HashSet::new(),
+        }
+    }
+
+    pub fn has_passphrase(&self) -> bool {
+        self.passphrase.is_some()
+    }
+
+    pub fn set_passphrase(&mut self, passphrase: String) {
+        self.passphrase = Some(passphrase);
+        self.keys.clear();
+    }
+
+    pub fn clear_passphrase(&mut self) {
+        self.passphrase = None;
+        self.keys.clear();
+    }
+
+    pub fn is_unlocked(&self, note_id: usize) -> bool {
+        self.unlocked.contains(&note_id)
+    }
+
+    pub fn mark_unlocked(&mut self, note_id: usize) {
+        self.unlocked.insert(note_id);
+    }
+
+    pub fn forget(&mut self, note_id: usize) {
+        self.unlocked.remove(&note_id);
+    }
+
+    /// Returns the key for the given salt, deriving and caching it on first use.
+    /// `None` if no passphrase has been entered yet.
+    fn key_for(&mut self, salt: &[u8], rounds: u32) -> Option<Key> {
+        let passphrase = self.passphrase.as_ref()?;
+
+        let key = *self
+            .keys
+            .entry((salt.to_vec(), rounds))
+            .or_insert_with(|| derive_key(passphrase, salt, rounds));
+
+        Some(key)
+    }
+
+    /// Encrypts with the session passphrase. `None` if no passphrase has been entered.
+    pub fn encrypt(&mut self, plaintext: &str) -> Option<EncryptedText> {
+        let salt = self.session_salt.clone();
+        let key = self.key_for(&salt, KDF_ROUNDS)?;
+        Some(encrypt_with_key(plaintext, &key, &salt, KDF_ROUNDS))
+    }
+
+    /// Decrypts with the session passphrase.
+    ///
+    /// # Panics
+    /// If no passphrase has been entered.
+    pub fn decrypt(&mut self, encrypted: &EncryptedText) -> Result<String, CryptoError> {
+        let salt = encrypted.salt_bytes()?;
+        let key = self
+            .key_for(&salt, encrypted.rounds)
+            .expect("Bug: decrypt() called before a passphrase was entered");
+        decrypt_with_key(encrypted, &key)
+    }
+}
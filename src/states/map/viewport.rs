@@ -1,10 +1,16 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use super::{CanvasPos, ScreenPos, SignedRect};
+
+/// Rows at the bottom of the screen taken by the info bar
+const BAR_HEIGHT: usize = 3;
+
 /// Represents the top-left corner of the viewport on the infinite canvas.
-#[derive(PartialEq, Serialize, Deserialize, Clone, Debug)]
+#[derive(PartialEq, Serialize, Deserialize, JsonSchema, Clone, Debug)]
 pub struct ViewPos {
-    pub x: usize,
-    pub y: usize,
+    pub x: isize,
+    pub y: isize,
 }
 
 impl ViewPos {
@@ -15,8 +21,8 @@ impl ViewPos {
 
 #[derive(PartialEq, Debug)]
 pub struct ViewportState {
-    /// The position of the viewport (camera) on the infinite canvas.
-    /// Position can only be positive.
+    /// The position of the viewport (camera) on the infinite canvas, which extends in
+    /// all directions.
     pub view_pos: ViewPos,
     /// The current width of the terminal screen in cells. Updated on every frame.
     pub screen_width: usize,
@@ -33,18 +39,96 @@ impl ViewportState {
         }
     }
 
-    pub fn center(&self) -> (usize, usize) {
+    pub fn center(&self) -> (isize, isize) {
         (
-            self.view_pos.x + self.screen_width / 2,
-            self.view_pos.y + self.screen_height / 2,
+            self.view_pos.x + self.screen_width as isize / 2,
+            self.view_pos.y + self.screen_height as isize / 2,
         )
     }
 
-    /// Transforms world coordinates to screen-relative coordinates by subtracting viewport offset.
-    /// Returns negative values if the point is off-screen to the left or top.
-    pub fn to_screen_coords(&self, p_x: isize, p_y: isize) -> (isize, isize) {
-        let p_x = p_x - self.view_pos.x as isize;
-        let p_y = p_y - self.view_pos.y as isize;
-        (p_x, p_y)
+    /// Moves the viewport so the point is in the middle of the screen.
+    pub fn center_on(&mut self, x: isize, y: isize) {
+        self.view_pos.x = x - self.screen_width as isize / 2;
+        self.view_pos.y = y - self.screen_height as isize / 2;
+    }
+
+    /// Pans the viewport along with a note that moved `amount` cells sideways, just far
+    /// enough to keep `margin` cells between the note and the screen edge it moved
+    /// towards, and never further than the note moved, so a note that was already cut
+    /// off doesn't make the view jump.
+    pub fn follow_horizontally(&mut self, x: isize, width: usize, amount: isize, margin: usize) {
+        self.view_pos.x = follow(self.view_pos.x, self.screen_width, x, width, amount, margin);
+    }
+
+    /// Like [`ViewportState::follow_horizontally`], for a note that moved up or down,
+    /// keeping it clear of the bar at the bottom.
+    pub fn follow_vertically(&mut self, y: isize, height: usize, amount: isize, margin: usize) {
+        let visible_height = self.screen_height.saturating_sub(BAR_HEIGHT);
+        self.view_pos.y = follow(self.view_pos.y, visible_height, y, height, amount, margin);
+    }
+
+    /// Moves the viewport so the canvas rectangle is centered in the area above the bar.
+    ///
+    /// Along an axis where the rectangle doesn't fit, its top or left edge is shown instead.
+    pub fn fit(&mut self, bounds: &SignedRect) {
+        let visible_height = self.screen_height.saturating_sub(BAR_HEIGHT);
+        let start = |position: isize, size: isize, screen: usize| -> isize {
+            let screen = screen as isize;
+            if size <= screen {
+                position + size / 2 - screen / 2
+            } else {
+                position
+            }
+        };
+
+        self.view_pos.x = start(bounds.x, bounds.width, self.screen_width);
+        self.view_pos.y = start(bounds.y, bounds.height, visible_height);
+    }
+
+    /// Whether the canvas rectangle fits in the area above the bar.
+    pub fn fits(&self, bounds: &SignedRect) -> bool {
+        let visible_height = self.screen_height.saturating_sub(BAR_HEIGHT);
+        bounds.width <= self.screen_width as isize && bounds.height <= visible_height as isize
+    }
+
+    /// Moves the viewport so the bottom right corner of the canvas rectangle is at the
+    /// bottom right of the area above the bar.
+    pub fn show_end_of(&mut self, bounds: &SignedRect) {
+        let visible_height = self.screen_height.saturating_sub(BAR_HEIGHT);
+
+        self.view_pos.x = bounds.x + bounds.width - self.screen_width as isize;
+        self.view_pos.y = bounds.y + bounds.height - visible_height as isize;
+    }
+
+    /// Where a canvas position appears on screen. The result is off-screen (negative or
+    /// past the screen size) for positions outside the viewport.
+    pub fn to_screen(&self, pos: CanvasPos) -> ScreenPos {
+        ScreenPos::new(pos.x - self.view_pos.x, pos.y - self.view_pos.y)
+    }
+
+    /// The canvas position shown at a screen position.
+    pub fn to_canvas(&self, pos: ScreenPos) -> CanvasPos {
+        CanvasPos::new(pos.x + self.view_pos.x, pos.y + self.view_pos.y)
+    }
+}
+
+/// New start of the view along one axis, see [`ViewportState::follow_horizontally`].
+/// The margin shrinks for notes too large to have it on both sides.
+fn follow(
+    view: isize,
+    screen: usize,
+    start: isize,
+    size: usize,
+    amount: isize,
+    margin: usize,
+) -> isize {
+    let margin = margin.min(screen.saturating_sub(size) / 2) as isize;
+    let (screen, size) = (screen as isize, size as isize);
+    if amount > 0 {
+        let overshoot = (start + size + margin - (view + screen)).max(0);
+        view + overshoot.min(amount)
+    } else {
+        let overshoot = (view - (start - margin)).max(0);
+        view - overshoot.min(-amount)
     }
 }
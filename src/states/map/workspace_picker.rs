@@ -0,0 +1,67 @@
+use std::path::{Path, PathBuf};
+
+use crate::utils::WorkspaceMatch;
+
+/// List of the workspace's maps shown for switching to another one (`W` in Normal mode).
+#[derive(PartialEq, Debug)]
+pub struct WorkspacePicker {
+    /// Sorted by name
+    pub maps: Vec<PathBuf>,
+    /// Index of the highlighted map
+    pub selected: usize,
+}
+
+impl WorkspacePicker {
+    /// A picker with the open map highlighted.
+    pub fn new(maps: Vec<PathBuf>, open_map: &Path) -> Self {
+        let selected = maps.iter().position(|path| path == open_map).unwrap_or(0);
+        Self { maps, selected }
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.maps.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn selected_map(&self) -> Option<&PathBuf> {
+        self.maps.get(self.selected)
+    }
+}
+
+/// Notes of the workspace's maps matching a `:grep` pattern, listed to open one.
+#[derive(PartialEq, Debug)]
+pub struct WorkspaceSearch {
+    pub query: String,
+    pub results: Vec<WorkspaceMatch>,
+    /// Index of the highlighted result
+    pub selected: usize,
+}
+
+impl WorkspaceSearch {
+    pub fn new(query: String, results: Vec<WorkspaceMatch>) -> Self {
+        Self {
+            query,
+            results,
+            selected: 0,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.results.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn selected_result(&self) -> Option<&WorkspaceMatch> {
+        self.results.get(self.selected)
+    }
+}
@@ -1,7 +1,11 @@
+pub mod backups;
+pub mod dialog;
 pub mod map;
 pub mod settings;
 pub mod start;
 
+pub use backups::BackupsState;
+pub use dialog::{Dialog, DialogButton};
 pub use map::MapState;
 pub use settings::SettingsState;
 pub use start::StartState;
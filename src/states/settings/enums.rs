@@ -54,6 +54,28 @@ pub enum SelectedToggle {
     Toggle5,
     /// Modal Editing for Edit Mode
     Toggle6,
+    /// UI language
+    Toggle7,
+    /// Compression of new map files
+    Toggle8,
+    /// Compact JSON in map files
+    Toggle9,
+    /// Backups retention policy
+    Toggle10,
+    /// Number of recent files kept on the start screen
+    Toggle11,
+    /// Format of the map screen's status bar
+    Toggle12,
+    /// UUIDs for notes
+    Toggle13,
+    /// Parallel connections between the same note sides
+    Toggle14,
+    /// Dictionary for spell checking in Edit mode
+    Toggle15,
+    /// Undo history kept in a file next to the map
+    Toggle16,
+    /// Scroll margin kept around the selected note and the cursor
+    Toggle17,
 }
 
 impl SelectedToggle {
@@ -67,7 +89,7 @@ impl SelectedToggle {
     }
 }
 
-#[derive(PartialEq, Serialize, Deserialize, Debug)]
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
 pub enum BackupsInterval {
     Daily,
     Every3Days,
@@ -75,6 +97,19 @@ pub enum BackupsInterval {
     Every2Weeks,
 }
 
+/// Which backups of a map are kept; older ones are deleted after each new backup.
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub enum BackupRetention {
+    /// Never delete backups
+    #[default]
+    KeepAll,
+    /// The given number of most recent backups
+    KeepLast(usize),
+    /// The most recent backup of each of the last 7 days, 4 weeks and 12 months that
+    /// have backups
+    Tiered,
+}
+
 #[derive(PartialEq, Debug)]
 pub enum BackupsErr {
     DirFind,
@@ -82,7 +117,7 @@ pub enum BackupsErr {
     FileWrite,
 }
 
-#[derive(PartialEq, Serialize, Deserialize, Debug)]
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
 pub enum RuntimeBackupsInterval {
     Hourly,
     Every2Hours,
@@ -1,13 +1,23 @@
 use std::collections::HashMap;
 
-use crate::states::{
-    map::Side,
-    settings::{BackupsInterval, RuntimeBackupsInterval, cycle_side},
+use crate::{
+    states::{
+        map::Side,
+        settings::{BackupRetention, BackupsInterval, RuntimeBackupsInterval, cycle_side},
+    },
+    utils::{Locale, UserCommand},
 };
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq, Serialize, Deserialize, Debug)]
+/// Number of unpinned recent files kept on the start screen unless set otherwise
+pub const DEFAULT_RECENT_FILES_LIMIT: usize = 10;
+
+fn default_recent_files_limit() -> usize {
+    DEFAULT_RECENT_FILES_LIMIT
+}
+
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
 pub struct Settings {
     /// Interval (in seconds) at which to auto-save changes
     pub save_interval: Option<usize>,
@@ -21,6 +31,47 @@ pub struct Settings {
     pub default_start_side: Side,
     pub default_end_side: Side,
     pub edit_modal: bool,
+    /// Language of the UI; None detects it from the environment (`LANG`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<Locale>,
+    /// Create new map files gzip-compressed (`.json.gz`)
+    #[serde(default)]
+    pub compress_maps: bool,
+    /// Write uncompressed map files as compact instead of pretty-printed JSON
+    #[serde(default)]
+    pub compact_json: bool,
+    /// Which backups are kept when older ones are pruned
+    #[serde(default)]
+    pub backup_retention: BackupRetention,
+    /// How many recently opened files (besides pinned ones) the start screen keeps
+    #[serde(default = "default_recent_files_limit")]
+    pub recent_files_limit: usize,
+    /// Format of the map screen's bottom bar (see `utils::StatusBarFormat`); None shows
+    /// the built-in mode and view position
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_bar_format: Option<String>,
+    /// Give notes UUIDs (see `Note::uuid`), including the notes of maps opened from now on
+    #[serde(default)]
+    pub note_uuids: bool,
+    /// Allow several connections between the same sides of the same two notes
+    #[serde(default)]
+    pub parallel_connections: bool,
+    /// Name of the hunspell dictionary notes are spell checked with in Edit mode (see
+    /// `utils::dictionary_dirs`); None turns spell checking off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spell_dictionary: Option<String>,
+    /// Keep the undo history in a file next to the map (see `utils::undo_file_path`), so
+    /// it's still there when the map is opened again
+    #[serde(default)]
+    pub persist_undo: bool,
+    /// Cells kept between the selected note and the screen edge when moving it pans the
+    /// view, and lines kept above and below the cursor in a scrolled note, like vim's
+    /// `scrolloff`
+    #[serde(default)]
+    pub scroll_margin: usize,
+    /// External programs run with `:run <name>`; only set in the settings file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub user_commands: Vec<UserCommand>,
 }
 
 impl Settings {
@@ -34,9 +85,38 @@ impl Settings {
             default_start_side: Side::Right,
             default_end_side: Side::Right,
             edit_modal: false,
+            locale: None,
+            compress_maps: false,
+            compact_json: false,
+            backup_retention: BackupRetention::KeepAll,
+            recent_files_limit: DEFAULT_RECENT_FILES_LIMIT,
+            status_bar_format: None,
+            note_uuids: false,
+            parallel_connections: false,
+            spell_dictionary: None,
+            persist_undo: false,
+            scroll_margin: 0,
+            user_commands: vec![],
         }
     }
 
+    /// The UI language: the chosen one, or the one detected from the environment.
+    pub fn locale(&self) -> Locale {
+        self.locale.unwrap_or_else(Locale::from_env)
+    }
+
+    /// Cycles the UI language: automatic -> each locale in turn -> automatic
+    pub fn cycle_locale(&mut self) {
+        self.locale = match self.locale {
+            None => Some(Locale::ALL[0]),
+            Some(locale) => Locale::ALL
+                .iter()
+                .position(|&other| other == locale)
+                .and_then(|index| Locale::ALL.get(index + 1))
+                .copied(),
+        };
+    }
+
     /// Cycles through available save intervals: 10s -> 20s -> 30s -> 60s -> off
     pub fn cycle_save_intervals(&mut self) {
         self.save_interval = match self.save_interval {
@@ -72,6 +152,49 @@ impl Settings {
         }
     }
 
+    /// Cycles backup retention: keep all -> last 5 -> last 10 -> last 20 -> tiered
+    pub fn cycle_backup_retention(&mut self) {
+        self.backup_retention = match self.backup_retention {
+            BackupRetention::KeepAll => BackupRetention::KeepLast(5),
+            BackupRetention::KeepLast(5) => BackupRetention::KeepLast(10),
+            BackupRetention::KeepLast(10) => BackupRetention::KeepLast(20),
+            BackupRetention::KeepLast(_) => BackupRetention::Tiered,
+            BackupRetention::Tiered => BackupRetention::KeepAll,
+        };
+    }
+
+    /// Cycles the number of recent files kept: 3 -> 5 -> 10 -> 20 -> 3
+    pub fn cycle_recent_files_limit(&mut self) {
+        self.recent_files_limit = match self.recent_files_limit {
+            3 => 5,
+            5 => 10,
+            10 => 20,
+            _ => 3,
+        };
+    }
+
+    /// Cycles the scroll margin: 0 -> 2 -> 5 -> 10 -> 0
+    pub fn cycle_scroll_margin(&mut self) {
+        self.scroll_margin = match self.scroll_margin {
+            0 => 2,
+            2 => 5,
+            5 => 10,
+            _ => 0,
+        };
+    }
+
+    /// Cycles the spell checking dictionary: off -> each available one in turn -> off
+    pub fn cycle_spell_dictionary(&mut self, available: &[String]) {
+        self.spell_dictionary = match &self.spell_dictionary {
+            None => available.first().cloned(),
+            Some(name) => available
+                .iter()
+                .position(|other| other == name)
+                .and_then(|index| available.get(index + 1))
+                .cloned(),
+        };
+    }
+
     /// Cycles default connection side. If `start_side` is true, cycles start side; otherwise cycles end side.
     pub fn cycle_default_sides(&mut self, start_side: bool) {
         if start_side {
@@ -5,7 +5,7 @@ use crate::{
         BackupsErr, BackupsInterval, DiscardExitTo, RuntimeBackupsInterval, SelectedToggle,
         SettingsNotification, SettingsType, get_settings_with_fs,
     },
-    utils::{FileSystem, RealFileSystem},
+    utils::{DEFAULT_STATUS_BAR_FORMAT, FileSystem, RealFileSystem},
 };
 
 /// Resolves backup path to absolute path.
@@ -45,6 +45,8 @@ pub struct SettingsState {
     pub context_page: bool,
     pub input_prompt: bool,
     pub input_prompt_err: Option<BackupsErr>,
+    /// The status bar format being edited; Some while its prompt is shown
+    pub format_prompt: Option<String>,
 }
 
 impl SettingsState {
@@ -61,6 +63,7 @@ impl SettingsState {
             context_page: false,
             input_prompt: false,
             input_prompt_err: None,
+            format_prompt: None,
         }
     }
 
@@ -78,13 +81,24 @@ impl SettingsState {
             SelectedToggle::Toggle3 => SelectedToggle::Toggle4,
             SelectedToggle::Toggle4 => SelectedToggle::Toggle5,
             SelectedToggle::Toggle5 => SelectedToggle::Toggle6,
-            SelectedToggle::Toggle6 => SelectedToggle::Toggle1,
+            SelectedToggle::Toggle6 => SelectedToggle::Toggle7,
+            SelectedToggle::Toggle7 => SelectedToggle::Toggle8,
+            SelectedToggle::Toggle8 => SelectedToggle::Toggle9,
+            SelectedToggle::Toggle9 => SelectedToggle::Toggle10,
+            SelectedToggle::Toggle10 => SelectedToggle::Toggle11,
+            SelectedToggle::Toggle11 => SelectedToggle::Toggle12,
+            SelectedToggle::Toggle12 => SelectedToggle::Toggle13,
+            SelectedToggle::Toggle13 => SelectedToggle::Toggle14,
+            SelectedToggle::Toggle14 => SelectedToggle::Toggle15,
+            SelectedToggle::Toggle15 => SelectedToggle::Toggle16,
+            SelectedToggle::Toggle16 => SelectedToggle::Toggle17,
+            SelectedToggle::Toggle17 => SelectedToggle::Toggle1,
         }
     }
 
     pub fn toggle_go_up(&mut self) {
         self.selected_toggle = match self.selected_toggle {
-            SelectedToggle::Toggle1 => SelectedToggle::Toggle6,
+            SelectedToggle::Toggle1 => SelectedToggle::Toggle17,
             SelectedToggle::Toggle2 => SelectedToggle::Toggle1,
             SelectedToggle::Toggle3 => SelectedToggle::Toggle2,
             SelectedToggle::Toggle4 => {
@@ -97,9 +111,42 @@ impl SettingsState {
             }
             SelectedToggle::Toggle5 => SelectedToggle::Toggle4,
             SelectedToggle::Toggle6 => SelectedToggle::Toggle5,
+            SelectedToggle::Toggle7 => SelectedToggle::Toggle6,
+            SelectedToggle::Toggle8 => SelectedToggle::Toggle7,
+            SelectedToggle::Toggle9 => SelectedToggle::Toggle8,
+            SelectedToggle::Toggle10 => SelectedToggle::Toggle9,
+            SelectedToggle::Toggle11 => SelectedToggle::Toggle10,
+            SelectedToggle::Toggle12 => SelectedToggle::Toggle11,
+            SelectedToggle::Toggle13 => SelectedToggle::Toggle12,
+            SelectedToggle::Toggle14 => SelectedToggle::Toggle13,
+            SelectedToggle::Toggle15 => SelectedToggle::Toggle14,
+            SelectedToggle::Toggle16 => SelectedToggle::Toggle15,
+            SelectedToggle::Toggle17 => SelectedToggle::Toggle16,
         }
     }
 
+    /// Opens the status bar format prompt with the current format, or the default one
+    /// if the built-in bar is used.
+    pub fn open_format_prompt(&mut self) {
+        let format = self
+            .settings
+            .settings()
+            .status_bar_format
+            .clone()
+            .unwrap_or_else(|| String::from(DEFAULT_STATUS_BAR_FORMAT));
+        self.format_prompt = Some(format);
+    }
+
+    /// Uses the format entered in the prompt and closes it. An empty format brings
+    /// back the built-in bar.
+    pub fn submit_format(&mut self) {
+        let Some(format) = self.format_prompt.take() else {
+            return;
+        };
+        self.settings.settings_mut().status_bar_format =
+            (!format.trim().is_empty()).then_some(format);
+    }
+
     pub fn submit_path(&mut self) {
         self.submit_path_with_fs(&RealFileSystem)
     }
@@ -2,15 +2,16 @@ use ratatui::{
     style::{Color, Style},
     widgets::{Block, BorderType},
 };
+use std::path::PathBuf;
 
 /// Buttons available in the start screen.
-/// Limited to 3 recent files to keep the UI compact and focused.
 #[derive(PartialEq, Debug)]
 pub enum SelectedStartButton {
     CreateSelect,
-    Recent1,
-    Recent2,
-    Recent3,
+    /// Opens the tutorial map
+    Tutorial,
+    /// Index into the recent files list, pinned entries first
+    Recent(usize),
 }
 
 impl SelectedStartButton {
@@ -44,3 +45,16 @@ impl FocusedInputBox {
         }
     }
 }
+
+/// What the buttons of a dialog on the start screen do.
+#[derive(PartialEq, Clone, Debug)]
+pub enum StartDialogAction {
+    /// Closes the dialog
+    Cancel,
+    /// Opens the map file without checking its checksum again
+    OpenAnyway(PathBuf),
+    /// Opens the map and puts back its unsaved changes from a crash
+    RestoreCrashChanges(PathBuf),
+    /// Deletes the map's unsaved changes from a crash
+    DiscardCrashChanges(PathBuf),
+}
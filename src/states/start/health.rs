@@ -0,0 +1,136 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    states::{settings::Settings, start::RecentPaths},
+    utils::{FileSystem, TmmprError, read_json_data, write_json_data},
+};
+
+/// A problem with the files and directories tmmpr relies on, found on launch so it
+/// can be fixed before a save fails because of it.
+#[derive(PartialEq, Clone, Debug)]
+pub enum HealthIssue {
+    HomeDirMissing,
+    ConfigDirCreate(PathBuf),
+    ConfigDirNotWritable(PathBuf),
+    /// The settings file exists but isn't valid settings
+    SettingsUnreadable(PathBuf),
+    /// The recent files list exists but isn't valid
+    RecentsUnreadable(PathBuf),
+    BackupsDirMissing(PathBuf),
+    BackupsDirNotWritable(PathBuf),
+}
+
+impl HealthIssue {
+    /// Describes the issue in one line for the warnings panel.
+    pub fn message(&self) -> String {
+        match self {
+            HealthIssue::HomeDirMissing => String::from("Could not find the home directory"),
+            HealthIssue::ConfigDirCreate(path) => {
+                format!("Config directory can't be created: {}", path.display())
+            }
+            HealthIssue::ConfigDirNotWritable(path) => {
+                format!("Config directory isn't writable: {}", path.display())
+            }
+            HealthIssue::SettingsUnreadable(path) => {
+                format!("Settings file can't be read: {}", path.display())
+            }
+            HealthIssue::RecentsUnreadable(path) => {
+                format!("Recent files list can't be read: {}", path.display())
+            }
+            HealthIssue::BackupsDirMissing(path) => {
+                format!("Backups directory doesn't exist: {}", path.display())
+            }
+            HealthIssue::BackupsDirNotWritable(path) => {
+                format!("Backups directory isn't writable: {}", path.display())
+            }
+        }
+    }
+
+    /// Describes what [`HealthIssue::fix`] does, or `None` if it can't be fixed from here.
+    pub fn fix_description(&self) -> Option<&'static str> {
+        match self {
+            HealthIssue::ConfigDirCreate(_) | HealthIssue::BackupsDirMissing(_) => {
+                Some("create the directory")
+            }
+            HealthIssue::SettingsUnreadable(_) | HealthIssue::RecentsUnreadable(_) => {
+                Some("keep it as .broken and start a new one")
+            }
+            HealthIssue::HomeDirMissing
+            | HealthIssue::ConfigDirNotWritable(_)
+            | HealthIssue::BackupsDirNotWritable(_) => None,
+        }
+    }
+
+    /// Attempts to fix the issue. Issues without a fix are left as they are.
+    pub fn fix(&self, fs: &dyn FileSystem) -> Result<(), TmmprError> {
+        match self {
+            HealthIssue::ConfigDirCreate(path) | HealthIssue::BackupsDirMissing(path) => {
+                fs.create_dir_all(path)?
+            }
+            HealthIssue::SettingsUnreadable(path) => {
+                set_aside(path)?;
+                write_json_data(path, &Settings::new())?
+            }
+            HealthIssue::RecentsUnreadable(path) => {
+                set_aside(path)?;
+                write_json_data(path, &RecentPaths::new())?
+            }
+            HealthIssue::HomeDirMissing
+            | HealthIssue::ConfigDirNotWritable(_)
+            | HealthIssue::BackupsDirNotWritable(_) => {}
+        }
+        Ok(())
+    }
+}
+
+/// Renames a broken file to `<name>.broken`, so a fix never throws its contents away.
+fn set_aside(path: &Path) -> std::io::Result<()> {
+    let mut broken = path.as_os_str().to_owned();
+    broken.push(".broken");
+    std::fs::rename(path, broken)
+}
+
+/// Checks that the config directory, the settings and recent files and the backups
+/// directory (if backups are enabled) can be read and written.
+///
+/// Files that don't exist yet aren't issues, they're created on first use.
+pub fn check_health_with_fs(fs: &dyn FileSystem) -> Vec<HealthIssue> {
+    let Some(home_path) = fs.get_home_dir() else {
+        return vec![HealthIssue::HomeDirMissing];
+    };
+
+    let config_dir_path = home_path.join(".config/tmmpr/");
+    if fs.create_dir_all(&config_dir_path).is_err() {
+        return vec![HealthIssue::ConfigDirCreate(config_dir_path)];
+    }
+
+    let mut issues = vec![];
+    if fs.test_write_to_dir(&config_dir_path).is_err() {
+        issues.push(HealthIssue::ConfigDirNotWritable(config_dir_path.clone()));
+    }
+
+    let recents_path = config_dir_path.join("recent_paths").with_extension("json");
+    if fs.path_exists(&recents_path) && read_json_data::<RecentPaths>(&recents_path).is_err() {
+        issues.push(HealthIssue::RecentsUnreadable(recents_path));
+    }
+
+    let settings_path = config_dir_path.join("settings").with_extension("json");
+    if fs.path_exists(&settings_path) {
+        match read_json_data::<Settings>(&settings_path) {
+            Ok(settings) => {
+                if let Some(backups_path) = &settings.backups_path {
+                    // Relative backup paths are relative to the home directory
+                    let backups_path = home_path.join(backups_path);
+                    if !fs.path_exists(&backups_path) {
+                        issues.push(HealthIssue::BackupsDirMissing(backups_path));
+                    } else if fs.test_write_to_dir(&backups_path).is_err() {
+                        issues.push(HealthIssue::BackupsDirNotWritable(backups_path));
+                    }
+                }
+            }
+            Err(_) => issues.push(HealthIssue::SettingsUnreadable(settings_path)),
+        }
+    }
+
+    issues
+}
@@ -1,7 +1,11 @@
 mod enums;
+mod health;
+mod preview;
 mod recent_paths;
 mod state;
 
 pub use enums::*;
+pub use health::*;
+pub use preview::*;
 pub use recent_paths::*;
 pub use state::*;
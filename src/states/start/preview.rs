@@ -0,0 +1,145 @@
+use chrono::{DateTime, Local};
+use serde::Deserialize;
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    states::map::Note,
+    utils::{EncryptedMapFile, IoErrorKind, file_modified, map_name, read_json_data},
+};
+
+/// Minimal view of a map file used for previews.
+///
+/// Only the fields needed for the preview are deserialized; everything else in the
+/// file (view position, render order, connection details) is skipped by serde.
+#[derive(Deserialize)]
+struct PreviewData {
+    notes: HashMap<usize, Note>,
+    connections: Vec<serde::de::IgnoredAny>,
+}
+
+/// Read-only summary of a map file shown next to the recent files list.
+#[derive(PartialEq, Debug, Clone)]
+pub struct MapPreview {
+    /// File name without extension
+    pub title: String,
+    /// Directory the file is in, to tell maps with the same name apart
+    pub directory: String,
+    /// When the file was last written, if the filesystem reports it
+    pub modified: Option<DateTime<Local>>,
+    pub note_count: usize,
+    pub connection_count: usize,
+    /// First line of the note nearest to the top-left of the map, if any
+    pub first_note_line: Option<String>,
+    /// Bounding rectangles (x, y, width, height) of every note, used to draw the thumbnail
+    pub note_rects: Vec<(isize, isize, usize, usize)>,
+    /// The map file is encrypted at rest, so nothing but its title is known
+    pub encrypted: bool,
+}
+
+impl MapPreview {
+    /// Reads the map file at `path` and builds a preview from it.
+    pub fn load(path: &Path) -> Result<MapPreview, IoErrorKind> {
+        let title = map_name(path);
+        let directory = path
+            .parent()
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let modified = file_modified(path).map(DateTime::<Local>::from);
+
+        let data: PreviewData = match read_json_data(path) {
+            Ok(data) => data,
+            Err(_) => {
+                read_json_data::<EncryptedMapFile>(path).map_err(|_| IoErrorKind::FileRead)?;
+                return Ok(MapPreview {
+                    title,
+                    directory,
+                    modified,
+                    note_count: 0,
+                    connection_count: 0,
+                    first_note_line: None,
+                    note_rects: vec![],
+                    encrypted: true,
+                });
+            }
+        };
+
+        let first_note_line = data
+            .notes
+            .values()
+            .min_by_key(|note| (note.y, note.x))
+            .and_then(|note| note.content.lines().next())
+            .map(|line| line.to_string());
+
+        let note_rects = data
+            .notes
+            .values()
+            .map(|note| {
+                let (width, height) = note.get_dimensions();
+                (note.x, note.y, width as usize, height as usize)
+            })
+            .collect();
+
+        Ok(MapPreview {
+            title,
+            directory,
+            modified,
+            note_count: data.notes.len(),
+            connection_count: data.connections.len(),
+            first_note_line,
+            note_rects,
+            encrypted: false,
+        })
+    }
+
+    /// Scales the note layout down into a `width` x `height` character grid.
+    ///
+    /// Cells covered by at least one note are filled, everything else is blank.
+    /// The whole layout is always fit into the grid, so wide maps get squashed horizontally
+    /// and tall maps vertically.
+    pub fn thumbnail(&self, width: usize, height: usize) -> Vec<String> {
+        if width == 0 || height == 0 {
+            return vec![];
+        }
+
+        let mut grid = vec![vec![' '; width]; height];
+
+        if !self.note_rects.is_empty() {
+            let min_x = self.note_rects.iter().map(|r| r.0).min().unwrap_or(0);
+            let min_y = self.note_rects.iter().map(|r| r.1).min().unwrap_or(0);
+            let max_x = self
+                .note_rects
+                .iter()
+                .map(|r| r.0 + r.2 as isize)
+                .max()
+                .unwrap_or(1);
+            let max_y = self
+                .note_rects
+                .iter()
+                .map(|r| r.1 + r.3 as isize)
+                .max()
+                .unwrap_or(1);
+
+            let span_x = max_x.abs_diff(min_x).max(1);
+            let span_y = max_y.abs_diff(min_y).max(1);
+
+            // Offsets from the top-left note, so notes left of or above the origin fit too
+            for &(x, y, w, h) in &self.note_rects {
+                let (x, y) = (x.abs_diff(min_x), y.abs_diff(min_y));
+                let start_col = x * width / span_x;
+                let end_col = ((x + w) * width).div_ceil(span_x).min(width);
+                let start_row = y * height / span_y;
+                let end_row = ((y + h) * height).div_ceil(span_y).min(height);
+
+                for row in grid.iter_mut().take(end_row).skip(start_row) {
+                    for cell in row.iter_mut().take(end_col).skip(start_col) {
+                        *cell = '█';
+                    }
+                }
+            }
+        }
+
+        grid.into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect()
+    }
+}
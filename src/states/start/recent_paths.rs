@@ -1,59 +1,182 @@
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
-use crate::utils::{IoErrorKind, filesystem::FileSystem, read_json_data, write_json_data};
+use crate::utils::{
+    IoErrorKind, filesystem::FileSystem, read_json_data, update_json_data, write_json_data,
+};
 
-/// Stores up to 3 most recently opened map files.
+/// Recently opened map files listed on the start screen.
+///
+/// Pinned favorites are listed first and kept until unpinned; the other paths are
+/// dropped once more than the limit from the settings have been opened since.
 /// Uses PathBuf for owned data that persists across the application lifecycle.
-#[derive(PartialEq, Serialize, Deserialize, Debug)]
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(from = "RecentPathsFile")]
 pub struct RecentPaths {
-    pub recent_path_1: Option<PathBuf>,
-    pub recent_path_2: Option<PathBuf>,
-    pub recent_path_3: Option<PathBuf>,
+    /// In the order they were pinned
+    pub pinned: Vec<PathBuf>,
+    /// Most recently opened first
+    pub recent: Vec<PathBuf>,
+}
+
+/// Forms of `recent_paths.json`: the current one, and the three fixed slots written
+/// by earlier versions.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RecentPathsFile {
+    Current {
+        #[serde(default)]
+        pinned: Vec<PathBuf>,
+        recent: Vec<PathBuf>,
+    },
+    Slots {
+        recent_path_1: Option<PathBuf>,
+        recent_path_2: Option<PathBuf>,
+        recent_path_3: Option<PathBuf>,
+    },
+}
+
+impl From<RecentPathsFile> for RecentPaths {
+    fn from(file: RecentPathsFile) -> Self {
+        match file {
+            RecentPathsFile::Current { pinned, recent } => RecentPaths { pinned, recent },
+            RecentPathsFile::Slots {
+                recent_path_1,
+                recent_path_2,
+                recent_path_3,
+            } => RecentPaths {
+                pinned: vec![],
+                recent: [recent_path_1, recent_path_2, recent_path_3]
+                    .into_iter()
+                    .flatten()
+                    .collect(),
+            },
+        }
+    }
 }
 
 impl RecentPaths {
     pub fn new() -> RecentPaths {
-        RecentPaths {
-            recent_path_1: None,
-            recent_path_2: None,
-            recent_path_3: None,
-        }
+        RecentPaths::default()
     }
 
-    /// Adds a path to the top of the recent list (position 1).
-    /// Shifts existing paths down, discarding the oldest if list is full.
-    pub fn add(&mut self, path: PathBuf) {
-        self.recent_path_3 = self.recent_path_2.clone();
-        self.recent_path_2 = self.recent_path_1.clone();
-        self.recent_path_1 = Some(path);
+    /// Number of listed paths, pinned ones included.
+    pub fn len(&self) -> usize {
+        self.pinned.len() + self.recent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The path at `index` in the listed order: pinned paths, then the others.
+    pub fn get(&self, index: usize) -> Option<&PathBuf> {
+        self.pinned
+            .get(index)
+            .or_else(|| self.recent.get(index.checked_sub(self.pinned.len())?))
+    }
+
+    /// Every listed path in order, with true for pinned ones.
+    pub fn entries(&self) -> impl Iterator<Item = (&PathBuf, bool)> {
+        let pinned = self.pinned.iter().map(|path| (path, true));
+        pinned.chain(self.recent.iter().map(|path| (path, false)))
+    }
+
+    /// Adds a path to the top of the unpinned paths, keeping at most `limit` of them.
+    /// A path that is already listed stays where it is.
+    pub fn add(&mut self, path: PathBuf, limit: usize) {
+        if self.contains_path(&path) {
+            return;
+        }
+        self.recent.insert(0, path);
+        self.recent.truncate(limit);
     }
 
     pub fn contains_path(&self, path: &Path) -> bool {
-        self.recent_path_1.as_deref() == Some(path)
-            || self.recent_path_2.as_deref() == Some(path)
-            || self.recent_path_3.as_deref() == Some(path)
+        self.entries().any(|(listed, _)| listed == path)
     }
 
-    /// Persists recent paths to `~/.config/tmmpr/recent_paths.json`.
+    /// Pins the path at `index` below the other pinned paths, or unpins it back to the
+    /// top of the others if it was pinned.
+    pub fn toggle_pin(&mut self, index: usize) {
+        if index < self.pinned.len() {
+            let path = self.pinned.remove(index);
+            self.recent.insert(0, path);
+        } else if let Some(offset) = index.checked_sub(self.pinned.len())
+            && offset < self.recent.len()
+        {
+            let path = self.recent.remove(offset);
+            self.pinned.push(path);
+        }
+    }
+
+    /// Removes the path at `index` from the list.
+    pub fn remove(&mut self, index: usize) {
+        if index < self.pinned.len() {
+            self.pinned.remove(index);
+        } else if let Some(offset) = index.checked_sub(self.pinned.len())
+            && offset < self.recent.len()
+        {
+            self.recent.remove(offset);
+        }
+    }
+
+    /// Removes every path whose file no longer exists. Returns the number removed.
+    pub fn remove_missing(&mut self, fs: &dyn FileSystem) -> usize {
+        let count = self.len();
+        self.pinned.retain(|path| fs.path_exists(path));
+        self.recent.retain(|path| fs.path_exists(path));
+        count - self.len()
+    }
+
+    /// Persists recent paths to `~/.config/tmmpr/recent_paths.json` after a map was
+    /// opened.
+    ///
+    /// Merges with paths another running instance may have saved since these were
+    /// loaded: the most recently added path goes on top of the file's current list
+    /// (keeping at most `limit` unpinned paths), and the merged list replaces this one.
     ///
     /// Errors are silently ignored: this function is only called after successful
     /// initialization by `get_recent_paths_with_fs`, which ensures the config directory
     /// exists and is writable. If saving fails, recent paths simply won't persist.
-    pub fn save_with_fs(&self, fs: &impl FileSystem) {
-        let home_path = match fs.get_home_dir() {
-            Some(path) => path,
-            None => return,
+    pub fn save_with_fs(&mut self, fs: &impl FileSystem, limit: usize) {
+        let Some(recent_paths_file_path) = recent_paths_file_path(fs) else {
+            return;
         };
 
-        let recent_paths_file_path = home_path
-            .join(".config/tmmpr/recent_paths")
-            .with_extension("json");
+        let merged = update_json_data(&recent_paths_file_path, |on_disk: Option<RecentPaths>| {
+            let Some(mut merged) = on_disk else {
+                return self.clone();
+            };
+            if let Some(path) = self.recent.first() {
+                merged.add(path.clone(), limit);
+            }
+            merged
+        });
+
+        if let Ok(merged) = merged {
+            *self = merged;
+        }
+    }
 
-        let _ = write_json_data(&recent_paths_file_path, &self);
+    /// Writes the list as it is, e.g. after pinning or removing paths on the start
+    /// screen. Errors are ignored like in [`RecentPaths::save_with_fs`].
+    pub fn overwrite_with_fs(&self, fs: &dyn FileSystem) {
+        if let Some(recent_paths_file_path) = recent_paths_file_path(fs) {
+            let _ = write_json_data(&recent_paths_file_path, self);
+        }
     }
 }
 
+fn recent_paths_file_path(fs: &dyn FileSystem) -> Option<PathBuf> {
+    let home_path = fs.get_home_dir()?;
+    Some(
+        home_path
+            .join(".config/tmmpr/recent_paths")
+            .with_extension("json"),
+    )
+}
+
 /// Loads recent paths from `~/.config/tmmpr/recent_paths.json`, creating an empty file
 /// if it doesn't exist.
 ///
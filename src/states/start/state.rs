@@ -1,11 +1,35 @@
 use crate::{
     input::AppAction,
-    states::start::{FocusedInputBox, RecentPaths, SelectedStartButton, get_recent_paths_with_fs},
+    states::{
+        Dialog,
+        settings::get_settings_with_fs,
+        start::{
+            FocusedInputBox, HealthIssue, MapPreview, RecentPaths, SelectedStartButton,
+            StartDialogAction, check_health_with_fs, get_recent_paths_with_fs,
+        },
+    },
     utils::IoErrorKind,
-    utils::{FileSystem, RealFileSystem},
+    utils::{
+        CryptoError, FileSystem, RealFileSystem, TmmprError, emergency_save_path,
+        find_emergency_saves_with_fs, map_file_extension,
+    },
 };
+use crossterm::event::KeyCode;
+use ratatui::style::Color;
 use std::path::PathBuf;
 
+/// Most characters of a load error kept for the Start screen; the rest is cut off
+pub const MAX_LOAD_ERROR_CHARS: usize = 160;
+
+/// Passphrase prompt for opening a map file that is encrypted at rest.
+#[derive(PartialEq, Debug)]
+pub struct MapPassphrasePrompt {
+    pub path: PathBuf,
+    pub input: String,
+    /// Set when the previously entered passphrase could not decrypt the file
+    pub error: Option<CryptoError>,
+}
+
 #[derive(PartialEq, Debug)]
 pub struct StartState {
     pub needs_clear_and_redraw: bool,
@@ -15,7 +39,22 @@ pub struct StartState {
     pub input_path_string: Option<String>,
     pub input_path_name: Option<String>,
     pub display_err_msg: Option<IoErrorKind>,
+    /// Why the last map couldn't be opened (the underlying file or parse error), shown
+    /// along with `display_err_msg`
+    pub load_error: Option<String>,
     pub recent_paths: Result<RecentPaths, IoErrorKind>,
+    /// Preview of the highlighted recent file, keyed by its path so it is only
+    /// loaded again when the highlighted entry changes.
+    pub preview: Option<(PathBuf, Result<MapPreview, IoErrorKind>)>,
+    /// Problems found by the launch health check, shown in a warnings panel until
+    /// fixed or dismissed.
+    pub health_issues: Vec<HealthIssue>,
+    pub passphrase_prompt: Option<MapPassphrasePrompt>,
+    /// Question shown over the screen; intercepts all input while shown
+    pub dialog: Option<Dialog<StartDialogAction>>,
+    /// Map whose unsaved changes from a crash are put back once it's opened, which for
+    /// an encrypted map is after its passphrase was entered
+    pub pending_crash_restore: Option<PathBuf>,
 }
 
 impl StartState {
@@ -26,7 +65,10 @@ impl StartState {
 
     /// Creates a new start screen state with a custom filesystem implementation (for testing)
     pub fn new_with_fs(fs: &dyn FileSystem) -> StartState {
-        StartState {
+        // Checked before loading the recent paths, which would create missing files
+        let health_issues = check_health_with_fs(fs);
+
+        let mut start_state = StartState {
             needs_clear_and_redraw: true,
             selected_button: SelectedStartButton::CreateSelect,
             input_path: false,
@@ -34,8 +76,35 @@ impl StartState {
             input_path_string: None,
             input_path_name: None,
             display_err_msg: None,
+            load_error: None,
             recent_paths: get_recent_paths_with_fs(fs),
+            preview: None,
+            health_issues,
+            passphrase_prompt: None,
+            dialog: None,
+            pending_crash_restore: None,
+        };
+        start_state.offer_crash_changes_with_fs(fs);
+        start_state
+    }
+
+    /// Applies every available fix for the health issues, then checks again so only
+    /// the issues that remain are shown.
+    pub fn fix_health_issues_with_fs(&mut self, fs: &dyn FileSystem) {
+        for issue in &self.health_issues {
+            // A failed fix shows up again in the new check
+            let _ = issue.fix(fs);
         }
+
+        self.health_issues = check_health_with_fs(fs);
+        self.recent_paths = get_recent_paths_with_fs(fs);
+        self.clear_and_redraw();
+    }
+
+    /// Closes the warnings panel, leaving the issues as they are.
+    pub fn dismiss_health_issues(&mut self) {
+        self.health_issues.clear();
+        self.clear_and_redraw();
     }
 
     pub fn clear_and_redraw(&mut self) {
@@ -48,31 +117,123 @@ impl StartState {
             "j" | "Down" => self.button_list_go_down(),
             _ => {}
         }
+
+        self.update_preview();
+    }
+
+    /// Returns the path behind the highlighted recent entry, if any.
+    pub fn selected_recent_path(&self) -> Option<PathBuf> {
+        let recent_paths = self.recent_paths.as_ref().ok()?;
+
+        match self.selected_button {
+            SelectedStartButton::CreateSelect | SelectedStartButton::Tutorial => None,
+            SelectedStartButton::Recent(index) => recent_paths.get(index).cloned(),
+        }
+    }
+
+    /// Lazily loads the preview for the highlighted recent entry.
+    ///
+    /// The file is only read when the highlighted path differs from the cached one.
+    pub fn update_preview(&mut self) {
+        match self.selected_recent_path() {
+            Some(path) => {
+                if self.preview.as_ref().map(|(p, _)| p) != Some(&path) {
+                    let preview = MapPreview::load(&path);
+                    self.preview = Some((path, preview));
+                }
+            }
+            None => self.preview = None,
+        }
     }
 
     fn button_list_go_up(&mut self) {
         self.selected_button = match self.selected_button {
-            SelectedStartButton::CreateSelect => SelectedStartButton::CreateSelect,
-            SelectedStartButton::Recent1 => SelectedStartButton::CreateSelect,
-            SelectedStartButton::Recent2 => SelectedStartButton::Recent1,
-            SelectedStartButton::Recent3 => SelectedStartButton::Recent2,
+            SelectedStartButton::CreateSelect | SelectedStartButton::Tutorial => {
+                SelectedStartButton::CreateSelect
+            }
+            SelectedStartButton::Recent(0) => SelectedStartButton::Tutorial,
+            SelectedStartButton::Recent(index) => SelectedStartButton::Recent(index - 1),
         }
     }
 
     fn button_list_go_down(&mut self) {
+        let count = self.recent_paths.as_ref().map_or(0, RecentPaths::len);
+
         self.selected_button = match self.selected_button {
-            SelectedStartButton::CreateSelect => SelectedStartButton::Recent1,
-            SelectedStartButton::Recent1 => SelectedStartButton::Recent2,
-            SelectedStartButton::Recent2 => SelectedStartButton::Recent3,
-            SelectedStartButton::Recent3 => SelectedStartButton::Recent3,
+            SelectedStartButton::CreateSelect => SelectedStartButton::Tutorial,
+            SelectedStartButton::Tutorial if count > 0 => SelectedStartButton::Recent(0),
+            SelectedStartButton::Recent(index) if index + 1 < count => {
+                SelectedStartButton::Recent(index + 1)
+            }
+            SelectedStartButton::Tutorial => SelectedStartButton::Tutorial,
+            SelectedStartButton::Recent(index) => SelectedStartButton::Recent(index),
         }
     }
 
+    /// Pins or unpins the highlighted recent entry, keeping it highlighted.
+    pub fn toggle_pin_selected_with_fs(&mut self, fs: &dyn FileSystem) {
+        let SelectedStartButton::Recent(index) = self.selected_button else {
+            return;
+        };
+        let Ok(recent_paths) = &mut self.recent_paths else {
+            return;
+        };
+        let Some(path) = recent_paths.get(index).cloned() else {
+            return;
+        };
+
+        recent_paths.toggle_pin(index);
+        recent_paths.overwrite_with_fs(fs);
+        if let Some(new_index) = recent_paths.entries().position(|(p, _)| *p == path) {
+            self.selected_button = SelectedStartButton::Recent(new_index);
+        }
+        self.update_preview();
+        self.clear_and_redraw();
+    }
+
+    /// Removes the highlighted entry from the recent files list.
+    pub fn remove_selected_with_fs(&mut self, fs: &dyn FileSystem) {
+        let SelectedStartButton::Recent(index) = self.selected_button else {
+            return;
+        };
+        if let Ok(recent_paths) = &mut self.recent_paths {
+            recent_paths.remove(index);
+            recent_paths.overwrite_with_fs(fs);
+        }
+        self.clamp_selected_recent();
+    }
+
+    /// Removes the entries whose map file no longer exists from the recent files list.
+    pub fn remove_missing_with_fs(&mut self, fs: &dyn FileSystem) {
+        if let Ok(recent_paths) = &mut self.recent_paths
+            && recent_paths.remove_missing(fs) > 0
+        {
+            recent_paths.overwrite_with_fs(fs);
+        }
+        self.clamp_selected_recent();
+    }
+
+    /// Keeps the highlight on an existing entry after entries were removed.
+    fn clamp_selected_recent(&mut self) {
+        let count = self.recent_paths.as_ref().map_or(0, RecentPaths::len);
+        if let SelectedStartButton::Recent(index) = self.selected_button
+            && index >= count
+        {
+            self.selected_button = match count {
+                0 => SelectedStartButton::CreateSelect,
+                _ => SelectedStartButton::Recent(count - 1),
+            };
+        }
+        self.update_preview();
+        self.clear_and_redraw();
+    }
+
     /// Handles path submission from either recent files or manual input.
     ///
     /// For recent paths: validates existence before loading.
     /// For manual input: constructs full path from home dir + user input (e.g. maps/map_0),
-    /// creates necessary directories, then loads or creates the map file.
+    /// creates necessary directories, then loads or creates the map file. An empty map
+    /// name opens the directory as a workspace instead.
     pub fn submit_path_with_fs(
         &mut self,
         recent_path: Option<PathBuf>,
@@ -84,6 +245,7 @@ impl StartState {
                     AppAction::LoadMapFile(path)
                 } else {
                     self.display_err_msg = Some(IoErrorKind::FileRead);
+                    self.load_error = Some(format!("{} doesn't exist", path.display()));
                     self.clear_and_redraw();
                     AppAction::Continue
                 }
@@ -103,27 +265,149 @@ impl StartState {
 
                 let map_path = home_path.join(path);
 
+                // Without a map name the directory is opened as a workspace
+                if name.is_empty() {
+                    if fs.path_exists(&map_path) {
+                        return AppAction::OpenWorkspace(map_path);
+                    }
+                    self.handle_submit_error(IoErrorKind::FileRead);
+                    return AppAction::Continue;
+                }
+
                 if let Err(_) = fs.create_dir_all(&map_path) {
                     self.handle_submit_error(IoErrorKind::DirCreate);
                     return AppAction::Continue;
                 };
 
-                let map_file_path = map_path.join(name).with_extension("json");
+                // An existing map is opened whether it's compressed or not, a new one
+                // is compressed if the settings say so
+                let existing = [false, true]
+                    .map(|compressed| {
+                        map_path
+                            .join(name)
+                            .with_extension(map_file_extension(compressed))
+                    })
+                    .into_iter()
+                    .find(|path| fs.path_exists(path));
 
-                if fs.path_exists(&map_file_path) {
-                    AppAction::LoadMapFile(map_file_path)
-                } else {
-                    AppAction::CreateMapFile(map_file_path)
+                match existing {
+                    Some(map_file_path) => AppAction::LoadMapFile(map_file_path),
+                    None => {
+                        let compressed = get_settings_with_fs(fs).settings().compress_maps;
+                        AppAction::CreateMapFile(
+                            map_path
+                                .join(name)
+                                .with_extension(map_file_extension(compressed)),
+                        )
+                    }
                 }
             }
         }
     }
 
+    /// Asks for the passphrase of an encrypted map file, showing why if a passphrase
+    /// was already tried.
+    pub fn ask_map_passphrase(&mut self, path: PathBuf, error: Option<CryptoError>) {
+        self.passphrase_prompt = Some(MapPassphrasePrompt {
+            path,
+            input: String::new(),
+            error,
+        });
+        self.clear_and_redraw();
+    }
+
+    /// Asks whether to open a map file whose contents don't match the checksum saved
+    /// with them.
+    pub fn warn_checksum_mismatch(&mut self, path: PathBuf) {
+        self.dialog = Some(
+            Dialog::new(
+                "This map file may be incomplete or changed by another program",
+                StartDialogAction::Cancel,
+            )
+            .line(format!(
+                "{} doesn't match the checksum saved with it.",
+                path.display()
+            ))
+            .line("Notes written last may be missing, e.g. after a sync tool cut the file short.")
+            .button(
+                "[ ESC ] - Cancel",
+                KeyCode::Esc,
+                StartDialogAction::Cancel,
+                Color::Green,
+            )
+            .button(
+                "[ o ] - Open anyway",
+                KeyCode::Char('o'),
+                StartDialogAction::OpenAnyway(path),
+                Color::Red,
+            ),
+        );
+        self.clear_and_redraw();
+    }
+
+    /// Asks whether to restore the unsaved changes a crash left of a recent map (see
+    /// [`crate::utils::emergency_save`]), one map at a time.
+    pub fn offer_crash_changes_with_fs(&mut self, fs: &dyn FileSystem) {
+        let Ok(recent_paths) = &self.recent_paths else {
+            return;
+        };
+        let paths = recent_paths.entries().map(|(path, _)| path);
+        let Some(path) = find_emergency_saves_with_fs(paths, fs).into_iter().next() else {
+            return;
+        };
+
+        self.dialog = Some(
+            Dialog::new(
+                "Unsaved changes from a crash were found",
+                StartDialogAction::Cancel,
+            )
+            .line(format!(
+                "tmmpr closed unexpectedly with unsaved changes to {}.",
+                path.display()
+            ))
+            .line(format!(
+                "They were saved to {}.",
+                emergency_save_path(&path).display()
+            ))
+            .button(
+                "[ r ] - Restore",
+                KeyCode::Char('r'),
+                StartDialogAction::RestoreCrashChanges(path.clone()),
+                Color::Green,
+            )
+            .button(
+                "[ d ] - Discard",
+                KeyCode::Char('d'),
+                StartDialogAction::DiscardCrashChanges(path),
+                Color::Red,
+            )
+            .button(
+                "[ ESC ] - Later",
+                KeyCode::Esc,
+                StartDialogAction::Cancel,
+                Color::White,
+            ),
+        );
+        self.clear_and_redraw();
+    }
+
     /// Resets input fields and displays an error message when path submission fails
     pub fn handle_submit_error(&mut self, err_msg: IoErrorKind) {
         self.input_path_string = Some(String::new());
         self.input_path_name = Some(String::new());
         self.focused_input_box = FocusedInputBox::InputBox1;
         self.display_err_msg = Some(err_msg);
+        self.load_error = None;
+    }
+
+    /// Shows why a map file couldn't be opened, truncated to [`MAX_LOAD_ERROR_CHARS`].
+    pub fn handle_load_error(&mut self, err: &TmmprError) {
+        self.handle_submit_error(IoErrorKind::FileRead);
+
+        let message = err.to_string();
+        self.load_error = Some(match message.char_indices().nth(MAX_LOAD_ERROR_CHARS) {
+            Some((end, _)) => format!("{}...", &message[..end]),
+            None => message,
+        });
     }
 }
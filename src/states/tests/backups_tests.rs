@@ -0,0 +1,120 @@
+use ratatui::style::Color;
+use std::{fs, path::Path};
+
+use crate::{
+    states::{BackupsState, MapState, map::Notification, settings::Settings},
+    utils::{MapData, write_json_data},
+};
+
+/// A map state for `plans.json` with backups in the given directory and one note.
+fn map_state_with_backups(backups_dir: &Path) -> MapState {
+    let settings = Settings {
+        backups_path: Some(backups_dir.to_string_lossy().into_owned()),
+        ..Settings::new()
+    };
+    let mut map_state = MapState::new_with_settings(backups_dir.join("plans.json"), settings);
+    map_state
+        .notes_state
+        .add(0, 0, String::from("Open note"), Color::White);
+    map_state
+}
+
+/// Writes a backup of `plans` with the given number of notes.
+fn write_backup(backups_dir: &Path, file_name: &str, note_count: usize) {
+    let mut map_state =
+        MapState::new_with_settings(backups_dir.join("plans.json"), Settings::new());
+    for i in 0..note_count {
+        map_state
+            .notes_state
+            .add(i as isize * 30, 0, format!("Backup note {i}"), Color::White);
+    }
+    write_json_data(
+        &backups_dir.join(file_name),
+        &MapData::from_map_state(&mut map_state),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_new_lists_backups_newest_first_with_note_counts() {
+    let backups_dir = tempfile::tempdir().unwrap();
+    write_backup(backups_dir.path(), "plans-load-backup-26-03-01.json", 2);
+    write_backup(
+        backups_dir.path(),
+        "plans-session-backup-26-03-02-1800.json",
+        3,
+    );
+    write_backup(backups_dir.path(), "other-load-backup-26-03-03.json", 1);
+    fs::write(
+        backups_dir.path().join("plans-load-backup-26-02-01.json"),
+        "not a map",
+    )
+    .unwrap();
+
+    let backups_state = BackupsState::new(map_state_with_backups(backups_dir.path()));
+
+    let summary: Vec<(bool, Option<usize>)> = backups_state
+        .backups
+        .iter()
+        .map(|backup| (backup.on_load, backup.note_count()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![(false, Some(3)), (true, Some(2)), (true, None)]
+    );
+    assert!(!backups_state.backups_disabled);
+}
+
+#[test]
+fn test_new_without_backups_directory() {
+    let map_state = MapState::new_with_settings("plans.json".into(), Settings::new());
+
+    let backups_state = BackupsState::new(map_state);
+
+    assert!(backups_state.backups.is_empty());
+    assert!(backups_state.backups_disabled);
+}
+
+#[test]
+fn test_restore_selected_replaces_open_map() {
+    let backups_dir = tempfile::tempdir().unwrap();
+    write_backup(backups_dir.path(), "plans-load-backup-26-03-01.json", 1);
+    write_backup(backups_dir.path(), "plans-load-backup-26-03-02.json", 2);
+    let mut backups_state = BackupsState::new(map_state_with_backups(backups_dir.path()));
+
+    backups_state.select_next();
+    backups_state.select_next();
+    assert_eq!(backups_state.selected, 1);
+
+    assert!(backups_state.restore_selected());
+    let map_state = backups_state.take_map_state();
+    assert_eq!(map_state.notes_state.notes().len(), 1);
+    assert_eq!(map_state.notes_state.notes()[&0].content, "Backup note 0");
+    assert!(map_state.persistence.has_unsaved_changes);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::BackupRestored)
+    );
+    assert_eq!(
+        map_state.persistence.file_write_path,
+        backups_dir.path().join("plans.json")
+    );
+}
+
+#[test]
+fn test_restore_selected_unreadable_backup_keeps_open_map() {
+    let backups_dir = tempfile::tempdir().unwrap();
+    fs::write(
+        backups_dir.path().join("plans-load-backup-26-03-01.json"),
+        "not a map",
+    )
+    .unwrap();
+    let mut backups_state = BackupsState::new(map_state_with_backups(backups_dir.path()));
+
+    assert!(!backups_state.restore_selected());
+    assert_eq!(
+        backups_state.map_state.notes_state.notes()[&0].content,
+        "Open note"
+    );
+    assert!(!backups_state.map_state.persistence.has_unsaved_changes);
+}
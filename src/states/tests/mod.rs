@@ -1,2 +1,3 @@
+mod backups_tests;
 mod settings_tests;
 mod start_tests;
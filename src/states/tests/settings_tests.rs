@@ -14,7 +14,7 @@ use crate::{
         },
     },
     utils::{
-        IoErrorKind, read_json_data,
+        DEFAULT_STATUS_BAR_FORMAT, IoErrorKind, Locale, read_json_data,
         test_utils::{MockFileSystem, TempFileSystem},
     },
 };
@@ -60,6 +60,50 @@ fn test_toggle_go_down_without_runtime_backups() {
 
     state.selected_toggle = SelectedToggle::Toggle6;
     state.toggle_go_down();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle7);
+
+    state.selected_toggle = SelectedToggle::Toggle7;
+    state.toggle_go_down();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle8);
+
+    state.selected_toggle = SelectedToggle::Toggle8;
+    state.toggle_go_down();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle9);
+
+    state.selected_toggle = SelectedToggle::Toggle9;
+    state.toggle_go_down();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle10);
+
+    state.selected_toggle = SelectedToggle::Toggle10;
+    state.toggle_go_down();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle11);
+
+    state.selected_toggle = SelectedToggle::Toggle11;
+    state.toggle_go_down();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle12);
+
+    state.selected_toggle = SelectedToggle::Toggle12;
+    state.toggle_go_down();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle13);
+
+    state.selected_toggle = SelectedToggle::Toggle13;
+    state.toggle_go_down();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle14);
+
+    state.selected_toggle = SelectedToggle::Toggle14;
+    state.toggle_go_down();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle15);
+
+    state.selected_toggle = SelectedToggle::Toggle15;
+    state.toggle_go_down();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle16);
+
+    state.selected_toggle = SelectedToggle::Toggle16;
+    state.toggle_go_down();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle17);
+
+    state.selected_toggle = SelectedToggle::Toggle17;
+    state.toggle_go_down();
     assert_eq!(state.selected_toggle, SelectedToggle::Toggle1);
 }
 
@@ -91,7 +135,47 @@ fn test_toggle_go_up_without_runtime_backups() {
 
     state.selected_toggle = SelectedToggle::Toggle1;
     state.toggle_go_up();
-    assert_eq!(state.selected_toggle, SelectedToggle::Toggle6);
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle17);
+
+    state.selected_toggle = SelectedToggle::Toggle17;
+    state.toggle_go_up();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle16);
+
+    state.selected_toggle = SelectedToggle::Toggle16;
+    state.toggle_go_up();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle15);
+
+    state.selected_toggle = SelectedToggle::Toggle15;
+    state.toggle_go_up();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle14);
+
+    state.selected_toggle = SelectedToggle::Toggle14;
+    state.toggle_go_up();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle13);
+
+    state.selected_toggle = SelectedToggle::Toggle13;
+    state.toggle_go_up();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle12);
+
+    state.selected_toggle = SelectedToggle::Toggle12;
+    state.toggle_go_up();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle11);
+
+    state.selected_toggle = SelectedToggle::Toggle11;
+    state.toggle_go_up();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle10);
+
+    state.selected_toggle = SelectedToggle::Toggle10;
+    state.toggle_go_up();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle9);
+
+    state.selected_toggle = SelectedToggle::Toggle9;
+    state.toggle_go_up();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle8);
+
+    state.selected_toggle = SelectedToggle::Toggle8;
+    state.toggle_go_up();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle7);
 
     state.selected_toggle = SelectedToggle::Toggle2;
     state.toggle_go_up();
@@ -109,6 +193,9 @@ fn test_toggle_go_up_without_runtime_backups() {
     state.selected_toggle = SelectedToggle::Toggle6;
     state.toggle_go_up();
     assert_eq!(state.selected_toggle, SelectedToggle::Toggle5);
+    state.selected_toggle = SelectedToggle::Toggle7;
+    state.toggle_go_up();
+    assert_eq!(state.selected_toggle, SelectedToggle::Toggle6);
 }
 
 #[test]
@@ -342,6 +429,101 @@ fn test_cycle_save_intervals() {
     assert_eq!(settings.save_interval, Some(20));
 }
 
+#[test]
+fn test_cycle_recent_files_limit() {
+    let mut settings = Settings::new();
+    assert_eq!(settings.recent_files_limit, 10);
+
+    settings.cycle_recent_files_limit();
+    assert_eq!(settings.recent_files_limit, 20);
+
+    settings.cycle_recent_files_limit();
+    assert_eq!(settings.recent_files_limit, 3);
+
+    settings.cycle_recent_files_limit();
+    assert_eq!(settings.recent_files_limit, 5);
+}
+
+#[test]
+fn test_cycle_scroll_margin() {
+    let mut settings = Settings::new();
+    assert_eq!(settings.scroll_margin, 0);
+
+    settings.cycle_scroll_margin();
+    assert_eq!(settings.scroll_margin, 2);
+
+    settings.cycle_scroll_margin();
+    assert_eq!(settings.scroll_margin, 5);
+
+    settings.cycle_scroll_margin();
+    assert_eq!(settings.scroll_margin, 10);
+
+    settings.cycle_scroll_margin();
+    assert_eq!(settings.scroll_margin, 0);
+}
+
+#[test]
+fn test_cycle_spell_dictionary() {
+    let mut settings = Settings::new();
+    let available = vec![String::from("de_DE"), String::from("en_US")];
+    assert_eq!(settings.spell_dictionary, None);
+
+    settings.cycle_spell_dictionary(&available);
+    assert_eq!(settings.spell_dictionary.as_deref(), Some("de_DE"));
+
+    settings.cycle_spell_dictionary(&available);
+    assert_eq!(settings.spell_dictionary.as_deref(), Some("en_US"));
+
+    settings.cycle_spell_dictionary(&available);
+    assert_eq!(settings.spell_dictionary, None);
+
+    // A dictionary that was removed since it was chosen turns spell checking off
+    settings.spell_dictionary = Some(String::from("fr_FR"));
+    settings.cycle_spell_dictionary(&available);
+    assert_eq!(settings.spell_dictionary, None);
+
+    // With no dictionaries installed it stays off
+    settings.cycle_spell_dictionary(&[]);
+    assert_eq!(settings.spell_dictionary, None);
+}
+
+#[test]
+fn test_format_prompt_starts_from_default_format() {
+    let mut state = create_settings_state(PathBuf::from("/test/path/map.json"));
+
+    state.open_format_prompt();
+    assert_eq!(
+        state.format_prompt.as_deref(),
+        Some(DEFAULT_STATUS_BAR_FORMAT)
+    );
+
+    state.format_prompt = Some(String::from("{mode} {unsaved}|{clock}"));
+    state.submit_format();
+    assert_eq!(state.format_prompt, None);
+    assert_eq!(
+        state.settings.settings().status_bar_format.as_deref(),
+        Some("{mode} {unsaved}|{clock}")
+    );
+
+    // The prompt reopens with the custom format
+    state.open_format_prompt();
+    assert_eq!(
+        state.format_prompt.as_deref(),
+        Some("{mode} {unsaved}|{clock}")
+    );
+}
+
+#[test]
+fn test_empty_format_restores_builtin_bar() {
+    let mut state = create_settings_state(PathBuf::from("/test/path/map.json"));
+    state.settings.settings_mut().status_bar_format = Some(String::from("{notes}"));
+
+    state.format_prompt = Some(String::from("  "));
+    state.submit_format();
+
+    assert_eq!(state.settings.settings().status_bar_format, None);
+}
+
 #[test]
 fn test_cycle_backup_interval() {
     let mut settings = Settings::new();
@@ -432,6 +614,34 @@ fn test_cycle_default_sides() {
     assert_eq!(settings.default_end_side, Side::Right);
 }
 
+#[test]
+fn test_cycle_locale() {
+    let mut settings = Settings::new();
+    assert_eq!(settings.locale, None);
+
+    settings.cycle_locale();
+    assert_eq!(settings.locale, Some(Locale::English));
+    assert_eq!(settings.locale(), Locale::English);
+
+    settings.cycle_locale();
+    assert_eq!(settings.locale, Some(Locale::German));
+    assert_eq!(settings.locale(), Locale::German);
+
+    settings.cycle_locale();
+    assert_eq!(settings.locale, None);
+}
+
+#[test]
+fn test_settings_without_locale_still_load() {
+    let mut json = serde_json::to_value(Settings::new()).unwrap();
+    // Automatic language isn't written to the settings file
+    assert!(json.get("locale").is_none());
+
+    json["locale"] = serde_json::json!("German");
+    let settings: Settings = serde_json::from_value(json).unwrap();
+    assert_eq!(settings.locale, Some(Locale::German));
+}
+
 // ============================================================================
 // Tests for SettingsType
 // ============================================================================
@@ -1,27 +1,53 @@
+use crossterm::event::KeyCode;
 use ratatui::style::{Color, Style};
 use std::path::PathBuf;
 
 use crate::{
     input::AppAction,
-    states::start::{
-        FocusedInputBox, RecentPaths, SelectedStartButton, StartState, get_recent_paths_with_fs,
+    states::{
+        settings::Settings,
+        start::{
+            FocusedInputBox, HealthIssue, MAX_LOAD_ERROR_CHARS, MapPreview, RecentPaths,
+            SelectedStartButton, StartDialogAction, StartState, check_health_with_fs,
+            get_recent_paths_with_fs,
+        },
+    },
+    utils::{
+        IoErrorKind, TmmprError, emergency_save_path,
+        test_utils::{MockFileSystem, TempFileSystem},
     },
-    utils::{IoErrorKind, test_utils::MockFileSystem},
 };
 
-#[test]
-fn test_navigate_start_buttons_up() {
+/// A start state listing three recent files.
+fn start_state_with_recent_paths() -> StartState {
     let mock_fs = MockFileSystem::new();
     let mut start_state = StartState::new_with_fs(&mock_fs);
+    start_state.recent_paths = Ok(RecentPaths {
+        pinned: vec![],
+        recent: vec![
+            PathBuf::from("/test/path1.json"),
+            PathBuf::from("/test/path2.json"),
+            PathBuf::from("/test/path3.json"),
+        ],
+    });
+    start_state
+}
+
+#[test]
+fn test_navigate_start_buttons_up() {
+    let mut start_state = start_state_with_recent_paths();
 
     // Test k key
-    start_state.selected_button = SelectedStartButton::Recent2;
+    start_state.selected_button = SelectedStartButton::Recent(1);
     start_state.navigate_start_buttons("k");
-    assert_eq!(start_state.selected_button, SelectedStartButton::Recent1);
+    assert_eq!(start_state.selected_button, SelectedStartButton::Recent(0));
 
     // Test Up key
-    start_state.selected_button = SelectedStartButton::Recent1;
+    start_state.selected_button = SelectedStartButton::Recent(0);
     start_state.navigate_start_buttons("Up");
+    assert_eq!(start_state.selected_button, SelectedStartButton::Tutorial);
+
+    start_state.navigate_start_buttons("k");
     assert_eq!(
         start_state.selected_button,
         SelectedStartButton::CreateSelect
@@ -38,36 +64,37 @@ fn test_navigate_start_buttons_up() {
 
 #[test]
 fn test_navigate_start_buttons_down() {
-    let mock_fs = MockFileSystem::new();
-    let mut start_state = StartState::new_with_fs(&mock_fs);
+    let mut start_state = start_state_with_recent_paths();
 
     // Test j key
     start_state.selected_button = SelectedStartButton::CreateSelect;
     start_state.navigate_start_buttons("j");
-    assert_eq!(start_state.selected_button, SelectedStartButton::Recent1);
+    assert_eq!(start_state.selected_button, SelectedStartButton::Tutorial);
+    start_state.navigate_start_buttons("j");
+    assert_eq!(start_state.selected_button, SelectedStartButton::Recent(0));
 
     // Test Down key
-    start_state.selected_button = SelectedStartButton::Recent1;
+    start_state.selected_button = SelectedStartButton::Recent(0);
     start_state.navigate_start_buttons("Down");
-    assert_eq!(start_state.selected_button, SelectedStartButton::Recent2);
+    assert_eq!(start_state.selected_button, SelectedStartButton::Recent(1));
 
-    // Test that Recent3 stays at Recent3
-    start_state.selected_button = SelectedStartButton::Recent3;
+    // Test that the last recent entry stays selected
+    start_state.selected_button = SelectedStartButton::Recent(2);
     start_state.navigate_start_buttons("j");
-    assert_eq!(start_state.selected_button, SelectedStartButton::Recent3);
+    assert_eq!(start_state.selected_button, SelectedStartButton::Recent(2));
 }
 
 #[test]
 fn test_navigate_start_buttons_other_keys() {
     let mock_fs = MockFileSystem::new();
     let mut start_state = StartState::new_with_fs(&mock_fs);
-    start_state.selected_button = SelectedStartButton::Recent1;
+    start_state.selected_button = SelectedStartButton::Recent(0);
 
     start_state.navigate_start_buttons("a");
-    assert_eq!(start_state.selected_button, SelectedStartButton::Recent1); // No change
+    assert_eq!(start_state.selected_button, SelectedStartButton::Recent(0)); // No change
 
     start_state.navigate_start_buttons("Enter");
-    assert_eq!(start_state.selected_button, SelectedStartButton::Recent1); // No change
+    assert_eq!(start_state.selected_button, SelectedStartButton::Recent(0)); // No change
 }
 
 // Note: button_list_go_up and button_list_go_down are private methods
@@ -95,6 +122,10 @@ fn test_submit_path_with_nonexistent_recent_path() {
 
     assert_eq!(result, AppAction::Continue);
     assert_eq!(start_state.display_err_msg, Some(IoErrorKind::FileRead));
+    assert_eq!(
+        start_state.load_error.as_deref(),
+        Some("/nonexistent/path/file.json doesn't exist")
+    );
     assert_eq!(start_state.needs_clear_and_redraw, true);
 }
 
@@ -142,6 +173,47 @@ fn test_submit_path_load_existing_file() {
     }
 }
 
+#[test]
+fn test_submit_path_load_existing_compressed_file() {
+    let expected_path = PathBuf::from("/mock/home/maps/existing_map.json.gz");
+    let mock_fs = MockFileSystem::new().with_existing_path(expected_path.clone());
+    let mut start_state = StartState::new_with_fs(&mock_fs);
+
+    start_state.input_path_string = Some("maps/".to_string());
+    start_state.input_path_name = Some("existing_map".to_string());
+
+    let result = start_state.submit_path_with_fs(None, &mock_fs);
+
+    // Should find the map even though it's compressed
+    match result {
+        AppAction::LoadMapFile(path) => {
+            assert_eq!(path, expected_path);
+        }
+        _ => panic!("Expected LoadMapFile action, got {:?}", result),
+    }
+}
+
+#[test]
+fn test_submit_path_without_name_opens_workspace() {
+    let dir = PathBuf::from("/mock/home/projects/garden");
+    let mock_fs = MockFileSystem::new().with_existing_path(dir.clone());
+    let mut start_state = StartState::new_with_fs(&mock_fs);
+
+    start_state.input_path_string = Some("projects/garden/".to_string());
+    start_state.input_path_name = Some(String::new());
+
+    let result = start_state.submit_path_with_fs(None, &mock_fs);
+    assert_eq!(result, AppAction::OpenWorkspace(dir));
+
+    // A directory that doesn't exist isn't created
+    start_state.input_path_string = Some("projects/missing/".to_string());
+    start_state.input_path_name = Some(String::new());
+
+    let result = start_state.submit_path_with_fs(None, &mock_fs);
+    assert_eq!(result, AppAction::Continue);
+    assert_eq!(start_state.display_err_msg, Some(IoErrorKind::FileRead));
+}
+
 #[test]
 fn test_submit_path_no_home_dir() {
     let mock_fs = MockFileSystem::new().with_home_dir(None);
@@ -194,6 +266,26 @@ fn test_handle_submit_error() {
     assert_eq!(start_state.display_err_msg, Some(IoErrorKind::DirCreate));
 }
 
+#[test]
+fn test_handle_load_error_keeps_the_reason() {
+    let mock_fs = MockFileSystem::new();
+    let mut start_state = StartState::new_with_fs(&mock_fs);
+
+    start_state.handle_load_error(&TmmprError::Validation(String::from("bad note")));
+    assert_eq!(start_state.display_err_msg, Some(IoErrorKind::FileRead));
+    assert_eq!(start_state.load_error.as_deref(), Some("bad note"));
+
+    // Long errors are cut off
+    start_state.handle_load_error(&TmmprError::Validation("é".repeat(500)));
+    let load_error = start_state.load_error.clone().unwrap();
+    assert_eq!(load_error.chars().count(), MAX_LOAD_ERROR_CHARS + 3);
+    assert!(load_error.ends_with("é..."));
+
+    // Other errors have no reason to show
+    start_state.handle_submit_error(IoErrorKind::DirCreate);
+    assert_eq!(start_state.load_error, None);
+}
+
 #[test]
 fn test_selected_start_button_get_style_selected() {
     let button = SelectedStartButton::CreateSelect;
@@ -207,7 +299,7 @@ fn test_selected_start_button_get_style_selected() {
 #[test]
 fn test_selected_start_button_get_style_not_selected() {
     let button = SelectedStartButton::CreateSelect;
-    let selected_button = SelectedStartButton::Recent1;
+    let selected_button = SelectedStartButton::Recent(0);
 
     let style = button.get_style(&selected_button);
 
@@ -236,29 +328,93 @@ fn test_recent_paths_add() {
     let path3 = PathBuf::from("/path/3");
     let path4 = PathBuf::from("/path/4");
 
-    // Add first path
-    recent_paths.add(path1.clone());
-    assert_eq!(recent_paths.recent_path_1, Some(path1.clone()));
-    assert_eq!(recent_paths.recent_path_2, None);
-    assert_eq!(recent_paths.recent_path_3, None);
+    // Newest paths go on top
+    recent_paths.add(path1.clone(), 3);
+    recent_paths.add(path2.clone(), 3);
+    assert_eq!(recent_paths.recent, vec![path2.clone(), path1.clone()]);
 
-    // Add second path
-    recent_paths.add(path2.clone());
-    assert_eq!(recent_paths.recent_path_1, Some(path2.clone()));
-    assert_eq!(recent_paths.recent_path_2, Some(path1.clone()));
-    assert_eq!(recent_paths.recent_path_3, None);
+    // Adding a listed path changes nothing
+    recent_paths.add(path1.clone(), 3);
+    assert_eq!(recent_paths.recent, vec![path2.clone(), path1.clone()]);
 
-    // Add third path
-    recent_paths.add(path3.clone());
-    assert_eq!(recent_paths.recent_path_1, Some(path3.clone()));
-    assert_eq!(recent_paths.recent_path_2, Some(path2.clone()));
-    assert_eq!(recent_paths.recent_path_3, Some(path1.clone()));
+    // Paths beyond the limit are discarded, oldest first
+    recent_paths.add(path3.clone(), 3);
+    recent_paths.add(path4.clone(), 3);
+    assert_eq!(recent_paths.recent, vec![path4, path3, path2]);
+}
 
-    // Add fourth path (should discard the oldest)
-    recent_paths.add(path4.clone());
-    assert_eq!(recent_paths.recent_path_1, Some(path4));
-    assert_eq!(recent_paths.recent_path_2, Some(path3));
-    assert_eq!(recent_paths.recent_path_3, Some(path2));
+#[test]
+fn test_recent_paths_pinned_are_listed_first_and_kept() {
+    let mut recent_paths = RecentPaths::new();
+    for name in ["a", "b", "c"] {
+        recent_paths.add(PathBuf::from(format!("/maps/{name}.json")), 3);
+    }
+
+    // Pin "a", the oldest entry
+    recent_paths.toggle_pin(2);
+    assert_eq!(recent_paths.get(0), Some(&PathBuf::from("/maps/a.json")));
+    assert_eq!(recent_paths.len(), 3);
+
+    // Pinned paths don't count towards the limit
+    for name in ["d", "e", "f"] {
+        recent_paths.add(PathBuf::from(format!("/maps/{name}.json")), 3);
+    }
+    let listed: Vec<_> = recent_paths.entries().collect();
+    assert_eq!(listed[0], (&PathBuf::from("/maps/a.json"), true));
+    assert_eq!(listed[1], (&PathBuf::from("/maps/f.json"), false));
+    assert_eq!(listed.len(), 4);
+
+    // Unpinning puts it back on top of the others
+    recent_paths.toggle_pin(0);
+    assert!(recent_paths.pinned.is_empty());
+    assert_eq!(recent_paths.recent[0], PathBuf::from("/maps/a.json"));
+}
+
+#[test]
+fn test_recent_paths_remove() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let existing = temp_dir.path().join("existing.json");
+    std::fs::write(&existing, "{}").unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+
+    let mut recent_paths = RecentPaths {
+        pinned: vec![temp_dir.path().join("pinned_gone.json")],
+        recent: vec![
+            existing.clone(),
+            temp_dir.path().join("gone.json"),
+            PathBuf::from("/maps/removed.json"),
+        ],
+    };
+
+    recent_paths.remove(3);
+    assert!(!recent_paths.contains_path(&PathBuf::from("/maps/removed.json")));
+
+    assert_eq!(recent_paths.remove_missing(&fs), 2);
+    assert_eq!(
+        recent_paths,
+        RecentPaths {
+            pinned: vec![],
+            recent: vec![existing],
+        }
+    );
+}
+
+#[test]
+fn test_recent_paths_loads_three_slot_file() {
+    let recent_paths: RecentPaths = serde_json::from_str(
+        r#"{ "recent_path_1": "/maps/b.json", "recent_path_2": "/maps/a.json", "recent_path_3": null }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        recent_paths,
+        RecentPaths {
+            pinned: vec![],
+            recent: vec![PathBuf::from("/maps/b.json"), PathBuf::from("/maps/a.json")],
+        }
+    );
 }
 
 #[test]
@@ -270,9 +426,9 @@ fn test_recent_paths_contains_path() {
     let path3 = PathBuf::from("/path/3");
     let path4 = PathBuf::from("/path/4");
 
-    recent_paths.add(path1.clone());
-    recent_paths.add(path2.clone());
-    recent_paths.add(path3.clone());
+    recent_paths.add(path1.clone(), 10);
+    recent_paths.add(path2.clone(), 10);
+    recent_paths.add(path3.clone(), 10);
 
     assert!(recent_paths.contains_path(&path1));
     assert!(recent_paths.contains_path(&path2));
@@ -311,12 +467,12 @@ fn test_get_recent_paths_dir_create_failure() {
 #[test]
 fn test_recent_paths_save_with_mock() {
     let mut recent_paths = RecentPaths::new();
-    recent_paths.add(PathBuf::from("/test/path.json"));
+    recent_paths.add(PathBuf::from("/test/path.json"), 10);
 
     // Test that save_with_fs can be called with MockFileSystem
     // This verifies the method signature works correctly with the trait
     let mock_fs = MockFileSystem::new();
-    recent_paths.save_with_fs(&mock_fs);
+    recent_paths.save_with_fs(&mock_fs, 10);
 
     // The save returns () so we just verify it doesn't panic
     // In production, this would write to ~/.config/tmmpr/recent_paths.json
@@ -326,11 +482,295 @@ fn test_recent_paths_save_with_mock() {
 #[test]
 fn test_recent_paths_save_no_home_dir() {
     let mut recent_paths = RecentPaths::new();
-    recent_paths.add(PathBuf::from("/test/path.json"));
+    recent_paths.add(PathBuf::from("/test/path.json"), 10);
 
     // Test that save_with_fs handles missing home directory gracefully
     let mock_fs = MockFileSystem::new().with_home_dir(None);
-    recent_paths.save_with_fs(&mock_fs);
+    recent_paths.save_with_fs(&mock_fs, 10);
 
     // Should return early without panicking when home dir is None
 }
+
+// ==================== MAP PREVIEW TESTS ====================
+
+fn write_preview_test_map(dir: &std::path::Path) -> PathBuf {
+    let path = dir.join("preview_map.json");
+    let json = r#"{
+        "view_pos": { "x": 0, "y": 0 },
+        "next_note_id_counter": 2,
+        "notes": {
+            "0": { "x": 40, "y": 10, "content": "Second\nline", "color": "White" },
+            "1": { "x": 0, "y": 0, "content": "Top left", "color": "Red" }
+        },
+        "render_order": [0, 1],
+        "connections": [
+            { "from_id": 0, "from_side": "Right", "to_id": 1, "to_side": "Left", "color": "White" }
+        ]
+    }"#;
+    std::fs::write(&path, json).unwrap();
+    path
+}
+
+#[test]
+fn test_map_preview_load() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = write_preview_test_map(temp_dir.path());
+
+    let preview = MapPreview::load(&path).unwrap();
+
+    assert_eq!(preview.title, "preview_map");
+    assert_eq!(
+        preview.directory,
+        temp_dir.path().to_string_lossy().into_owned()
+    );
+    assert!(preview.modified.is_some());
+    assert_eq!(preview.note_count, 2);
+    assert_eq!(preview.connection_count, 1);
+    assert_eq!(preview.first_note_line, Some(String::from("Top left")));
+    assert_eq!(preview.note_rects.len(), 2);
+}
+
+#[test]
+fn test_map_preview_load_missing_file() {
+    let result = MapPreview::load(&PathBuf::from("/nonexistent/map.json"));
+    assert_eq!(result, Err(IoErrorKind::FileRead));
+}
+
+#[test]
+fn test_map_preview_thumbnail_dimensions_and_fill() {
+    let preview = MapPreview {
+        title: String::from("map"),
+        directory: String::from("/maps"),
+        modified: None,
+        note_count: 2,
+        connection_count: 0,
+        first_note_line: None,
+        note_rects: vec![(0, 0, 10, 5), (90, 45, 10, 5)],
+        encrypted: false,
+    };
+
+    let thumbnail = preview.thumbnail(10, 10);
+
+    assert_eq!(thumbnail.len(), 10);
+    assert!(thumbnail.iter().all(|row| row.chars().count() == 10));
+    // Notes sit in opposite corners of the layout
+    assert!(thumbnail[0].starts_with('█'));
+    assert!(thumbnail[9].ends_with('█'));
+    assert!(thumbnail[0].ends_with(' '));
+    assert!(thumbnail[9].starts_with(' '));
+}
+
+#[test]
+fn test_map_preview_thumbnail_with_negative_positions() {
+    let preview = MapPreview {
+        title: String::from("map"),
+        directory: String::from("/maps"),
+        modified: None,
+        note_count: 2,
+        connection_count: 0,
+        first_note_line: None,
+        note_rects: vec![(-50, -25, 10, 5), (40, 20, 10, 5)],
+        encrypted: false,
+    };
+
+    let thumbnail = preview.thumbnail(10, 10);
+
+    // Laid out from the top-left note, like a map starting at the origin
+    assert!(thumbnail[0].starts_with('█'));
+    assert!(thumbnail[9].ends_with('█'));
+    assert!(thumbnail[0].ends_with(' '));
+    assert!(thumbnail[9].starts_with(' '));
+}
+
+#[test]
+fn test_map_preview_thumbnail_empty_map() {
+    let preview = MapPreview {
+        title: String::from("map"),
+        directory: String::from("/maps"),
+        modified: None,
+        note_count: 0,
+        connection_count: 0,
+        first_note_line: None,
+        note_rects: vec![],
+        encrypted: false,
+    };
+
+    let thumbnail = preview.thumbnail(4, 2);
+    assert_eq!(thumbnail, vec![String::from("    "), String::from("    ")]);
+    assert!(preview.thumbnail(0, 5).is_empty());
+}
+
+#[test]
+fn test_navigating_to_recent_entry_loads_preview() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = write_preview_test_map(temp_dir.path());
+
+    let mock_fs = MockFileSystem::new();
+    let mut start_state = StartState::new_with_fs(&mock_fs);
+    let mut recent_paths = RecentPaths::new();
+    recent_paths.add(path.clone(), 10);
+    start_state.recent_paths = Ok(recent_paths);
+
+    start_state.navigate_start_buttons("j");
+    assert_eq!(start_state.preview, None);
+    start_state.navigate_start_buttons("j");
+
+    let (preview_path, preview) = start_state.preview.as_ref().unwrap();
+    assert_eq!(*preview_path, path);
+    assert_eq!(preview.as_ref().unwrap().note_count, 2);
+
+    // Moving back to the tutorial button drops the preview
+    start_state.navigate_start_buttons("k");
+    assert_eq!(start_state.preview, None);
+}
+
+#[test]
+fn test_navigating_to_empty_recent_slot_has_no_preview() {
+    let mock_fs = MockFileSystem::new();
+    let mut start_state = StartState::new_with_fs(&mock_fs);
+    start_state.recent_paths = Ok(RecentPaths::new());
+
+    start_state.navigate_start_buttons("j");
+
+    assert_eq!(start_state.preview, None);
+}
+
+#[test]
+fn test_recent_paths_save_merges_with_other_instance() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+
+    // Both instances start from the same file
+    let mut first_instance = get_recent_paths_with_fs(&fs).unwrap();
+    let mut second_instance = get_recent_paths_with_fs(&fs).unwrap();
+
+    first_instance.add(PathBuf::from("/maps/a.json"), 10);
+    first_instance.save_with_fs(&fs, 10);
+    second_instance.add(PathBuf::from("/maps/b.json"), 10);
+    second_instance.save_with_fs(&fs, 10);
+
+    let expected = RecentPaths {
+        pinned: vec![],
+        recent: vec![PathBuf::from("/maps/b.json"), PathBuf::from("/maps/a.json")],
+    };
+    assert_eq!(second_instance, expected);
+    assert_eq!(get_recent_paths_with_fs(&fs).unwrap(), expected);
+}
+
+#[test]
+fn test_health_check_passes_on_fresh_install() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+
+    assert_eq!(check_health_with_fs(&fs), vec![]);
+    assert!(StartState::new_with_fs(&fs).health_issues.is_empty());
+}
+
+#[test]
+fn test_health_check_reports_directory_problems() {
+    assert_eq!(
+        check_health_with_fs(&MockFileSystem::new().with_home_dir(None)),
+        vec![HealthIssue::HomeDirMissing]
+    );
+    assert_eq!(
+        check_health_with_fs(&MockFileSystem::new().with_dir_create_failure()),
+        vec![HealthIssue::ConfigDirCreate(PathBuf::from(
+            "/mock/home/.config/tmmpr/"
+        ))]
+    );
+    assert_eq!(
+        check_health_with_fs(&MockFileSystem::new().with_write_failure()),
+        vec![HealthIssue::ConfigDirNotWritable(PathBuf::from(
+            "/mock/home/.config/tmmpr/"
+        ))]
+    );
+}
+
+#[test]
+fn test_health_check_fixes_broken_files_and_missing_backups_dir() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let config_dir = temp_dir.path().join(".config/tmmpr");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("recent_paths.json"), "{ not json").unwrap();
+    let settings = Settings {
+        backups_path: Some(String::from("tmmpr_backups")),
+        ..Settings::new()
+    };
+    std::fs::write(
+        config_dir.join("settings.json"),
+        serde_json::to_string(&settings).unwrap(),
+    )
+    .unwrap();
+
+    let mut start_state = StartState::new_with_fs(&fs);
+    assert_eq!(
+        start_state.health_issues,
+        vec![
+            HealthIssue::RecentsUnreadable(config_dir.join("recent_paths.json")),
+            HealthIssue::BackupsDirMissing(temp_dir.path().join("tmmpr_backups")),
+        ]
+    );
+    assert!(start_state.recent_paths.is_err());
+
+    start_state.fix_health_issues_with_fs(&fs);
+
+    assert!(start_state.health_issues.is_empty());
+    assert!(start_state.recent_paths.is_ok());
+    assert!(temp_dir.path().join("tmmpr_backups").is_dir());
+    // The broken file is kept next to the new one
+    assert_eq!(
+        std::fs::read_to_string(config_dir.join("recent_paths.json.broken")).unwrap(),
+        "{ not json"
+    );
+}
+
+#[test]
+fn test_health_check_reports_unreadable_settings() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let settings_path = temp_dir.path().join(".config/tmmpr/settings.json");
+    std::fs::create_dir_all(settings_path.parent().unwrap()).unwrap();
+    std::fs::write(&settings_path, "[]").unwrap();
+
+    let issues = check_health_with_fs(&fs);
+    assert_eq!(
+        issues,
+        vec![HealthIssue::SettingsUnreadable(settings_path.clone())]
+    );
+
+    issues[0].fix(&fs).unwrap();
+    assert!(check_health_with_fs(&fs).is_empty());
+}
+
+#[test]
+fn test_start_offers_crash_changes_of_recent_maps() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let map_path = temp_dir.path().join("plan.json");
+    let mut start_state = StartState::new_with_fs(&fs);
+    let recent_paths = start_state.recent_paths.as_mut().unwrap();
+    recent_paths.add(map_path.clone(), 10);
+    recent_paths.save_with_fs(&fs, 10);
+
+    // Nothing to offer without a recovery file
+    assert_eq!(StartState::new_with_fs(&fs).dialog, None);
+
+    std::fs::write(emergency_save_path(&map_path), "{}").unwrap();
+    start_state = StartState::new_with_fs(&fs);
+    let dialog = start_state.dialog.unwrap();
+    assert_eq!(
+        dialog.action_for_key(KeyCode::Char('r')),
+        Some(StartDialogAction::RestoreCrashChanges(map_path))
+    );
+}
@@ -0,0 +1,166 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Margin},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::{
+    states::{BackupsState, backups::BackupEntry},
+    ui::render_size_guard,
+    utils::{diff_maps, map_name, note_title},
+};
+
+/// Renders the backups screen: the open map's backups next to a preview of the
+/// highlighted one.
+pub fn render_backups(frame: &mut Frame, backups_state: &BackupsState) {
+    if render_size_guard(frame) {
+        return;
+    }
+    frame.render_widget(Clear, frame.area());
+
+    let screen_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .split(frame.area());
+
+    let title = format!(
+        "Backups of {}",
+        map_name(&backups_state.map_state.persistence.file_write_path)
+    );
+    frame.render_widget(
+        Line::from(title).alignment(Alignment::Center),
+        screen_layout[0],
+    );
+    frame.render_widget(
+        Line::from("j / k - select      Enter / r - restore into the open map      Esc / q - back to the map")
+            .alignment(Alignment::Center),
+        screen_layout[2],
+    );
+
+    let body_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(screen_layout[1]);
+
+    frame.render_widget(Block::bordered().title(" Backups "), body_layout[0]);
+    frame.render_widget(Block::bordered().title(" Preview "), body_layout[1]);
+    let list_area = body_layout[0].inner(Margin::new(2, 1));
+    let preview_area = body_layout[1].inner(Margin::new(2, 1));
+
+    if backups_state.backups.is_empty() {
+        let message = if backups_state.backups_disabled {
+            "Backups are off - choose a backups directory in the settings"
+        } else {
+            "No backups of this map yet"
+        };
+        frame.render_widget(Line::from(message).fg(Color::DarkGray), list_area);
+        return;
+    }
+
+    // Keep the highlighted backup in view
+    let visible_rows = list_area.height as usize;
+    let first = (backups_state.selected + 1).saturating_sub(visible_rows);
+    let lines: Vec<Line> = backups_state
+        .backups
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(visible_rows)
+        .map(|(index, backup)| {
+            let line = backup_line(backup);
+            if index == backups_state.selected {
+                line.style(Style::new().add_modifier(Modifier::REVERSED))
+            } else {
+                line
+            }
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), list_area);
+
+    if let Some(backup) = backups_state.selected_backup() {
+        frame.render_widget(
+            Paragraph::new(preview_lines(backup, backups_state)),
+            preview_area,
+        );
+    }
+}
+
+/// A backup in the list: when and how it was made, and its note count.
+fn backup_line(backup: &BackupEntry) -> Line<'static> {
+    let (time, kind) = if backup.on_load {
+        (backup.created_at.format("%Y-%m-%d      "), "on load")
+    } else {
+        (backup.created_at.format("%Y-%m-%d %H:%M"), "session")
+    };
+    let note_count = match backup.note_count() {
+        Some(1) => String::from("1 note"),
+        Some(count) => format!("{count} notes"),
+        None => String::from("unreadable"),
+    };
+
+    Line::from(vec![
+        Span::from(format!("{time}  ")),
+        Span::from(format!("{kind:<9}")).fg(Color::DarkGray),
+        Span::from(note_count),
+    ])
+}
+
+/// The highlighted backup's size, how it differs from the open map, and its notes'
+/// titles from the top left of the map.
+fn preview_lines<'a>(backup: &BackupEntry, backups_state: &BackupsState) -> Vec<Line<'a>> {
+    let Some(map_data) = &backup.map_data else {
+        return vec![
+            Line::from("This backup can't be read.").fg(Color::Red),
+            Line::from(""),
+            Line::from(backup.path.to_string_lossy().into_owned()).fg(Color::DarkGray),
+        ];
+    };
+
+    let map_state = &backups_state.map_state;
+    let diff = diff_maps(
+        map_state.notes_state.notes(),
+        map_state.connections_state.connections(),
+        &map_data.notes,
+        &map_data.connections,
+    );
+
+    let mut lines = vec![
+        Line::from(format!(
+            "{} notes, {} connections",
+            map_data.notes.len(),
+            map_data.connections.len()
+        )),
+        Line::from(""),
+        Line::from("Compared to the open map:"),
+    ];
+    if diff.is_empty() {
+        lines.push(Line::from("  the same").fg(Color::DarkGray));
+    } else {
+        lines.push(Line::from(vec![
+            Span::from(format!("  +{} notes ", diff.added.len())).fg(Color::Green),
+            Span::from(format!("-{} notes ", diff.removed.len())).fg(Color::Red),
+            Span::from(format!("~{} changed", diff.changed.len())).fg(Color::Yellow),
+        ]));
+        lines.push(Line::from(format!(
+            "  +{} / -{} connections",
+            diff.connections_added, diff.connections_removed
+        )));
+    }
+    lines.push(Line::from(""));
+
+    let mut notes: Vec<_> = map_data.notes.values().collect();
+    notes.sort_by_key(|note| (note.y, note.x));
+    lines.extend(
+        notes
+            .into_iter()
+            .map(|note| Line::from(format!("- {}", note_title(note)))),
+    );
+
+    lines
+}
@@ -3,13 +3,20 @@ pub const NORMAL_CHARSET: [&str; 6] = ["─", "│", "┌", "┐", "└", "┘"]
 
 pub const PLAIN_JUNCTIONS: [&str; 4] = ["┴", "┬", "┤", "├"];
 pub const THICK_JUNCTIONS: [&str; 4] = ["┻", "┳", "┫", "┣"];
-pub const DOUBLE_JUNCTIONS: [&str; 4] = ["╩", "╦", "╣", "╠"];
-
-/// Used to determine which corner character to draw when rendering connections
-#[derive(Copy, Clone)]
-pub enum SegDir {
-    Right,
-    Left,
-    Up,
-    Down,
-}
+
+/// Where a plain line meets the border of a note drawn thick or double, and where a
+/// thick line (being created/edited) meets a plain border
+pub const THICK_BORDER_JUNCTIONS: [&str; 4] = ["┷", "┯", "┨", "┠"];
+pub const DOUBLE_BORDER_JUNCTIONS: [&str; 4] = ["╧", "╤", "╢", "╟"];
+pub const THICK_LINE_JUNCTIONS: [&str; 4] = ["┸", "┰", "┥", "┝"];
+
+/// Where two connections cross
+pub const PLAIN_CROSSING: &str = "┼";
+pub const THICK_CROSSING: &str = "╋";
+
+/// Rows at the bottom of the screen taken by the bar, which notifications and the note
+/// being edited stay above
+pub const BAR_HEIGHT: u16 = 3;
+
+/// Marks a connection bend point while waypoints are being edited
+pub const WAYPOINT_CHARACTER: &str = "●";
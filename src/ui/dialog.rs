@@ -0,0 +1,93 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph, Wrap},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::states::Dialog;
+
+/// Space between two buttons on the same line
+const BUTTON_GAP: &str = "      ";
+
+/// Renders a dialog across the bottom of the screen: its title and message, then its
+/// buttons side by side with the focused one highlighted.
+///
+/// On a narrow terminal the message wraps and the buttons go on as many lines as they
+/// need; on a low one the blank lines around them are left out first.
+pub fn render_dialog<A>(frame: &mut Frame, dialog: &Dialog<A>) {
+    let area = frame.area();
+    let inner_width = area.width.saturating_sub(2).max(1) as usize;
+
+    let mut text = vec![Line::from(dialog.title.as_str())];
+    text.extend(dialog.message.iter().map(|line| Line::from(line.as_str())));
+    let text_height: usize = text
+        .iter()
+        .map(|line| line.width().div_ceil(inner_width).max(1))
+        .sum();
+
+    let mut button_lines = vec![Vec::new()];
+    let mut line_width = 0;
+    for (index, button) in dialog.buttons.iter().enumerate() {
+        let width = button.label.width();
+        let buttons = button_lines.last_mut().unwrap();
+        if !buttons.is_empty() {
+            if line_width + BUTTON_GAP.len() + width > inner_width {
+                button_lines.push(Vec::new());
+                line_width = 0;
+            } else {
+                buttons.push(Span::raw(BUTTON_GAP));
+                line_width += BUTTON_GAP.len();
+            }
+        }
+        let style = if index == dialog.focused {
+            Style::new().bg(button.color).fg(Color::Black)
+        } else {
+            Style::new().fg(button.color)
+        };
+        button_lines
+            .last_mut()
+            .unwrap()
+            .push(Span::styled(button.label.as_str(), style));
+        line_width += width;
+    }
+
+    // Borders, and blank lines above the title, below the message and below the buttons
+    let compact_height = text_height + button_lines.len() + 2;
+    let padded = compact_height + 3 <= area.height as usize;
+    let mut lines = Vec::new();
+    if padded {
+        lines.push(Line::default());
+    }
+    lines.extend(text);
+    if padded {
+        lines.push(Line::default());
+    }
+    lines.extend(button_lines.into_iter().map(Line::from));
+    let height = if padded {
+        compact_height + 3
+    } else {
+        compact_height
+    };
+
+    let dialog_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(if padded { 1 } else { 0 }),
+            Constraint::Length(height as u16),
+        ])
+        .split(area);
+
+    frame.render_widget(Clear, dialog_area[1]);
+    frame.render_widget(Clear, dialog_area[2]);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered()),
+        dialog_area[2],
+    );
+}
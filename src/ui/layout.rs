@@ -0,0 +1,78 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Clear, Paragraph, Wrap},
+};
+
+/// Smallest terminal the screens are drawn on; a smaller one shows
+/// [`render_size_guard`]'s request to enlarge it instead
+pub const MIN_TERMINAL_WIDTH: u16 = 40;
+pub const MIN_TERMINAL_HEIGHT: u16 = 10;
+
+/// Panels narrower than this keep one column between their border and their content
+const NARROW_PANEL_WIDTH: u16 = 40;
+/// Panels lower than this keep one row between their border and their content
+const LOW_PANEL_HEIGHT: u16 = 16;
+
+/// Margin between a bordered panel and its content, shrunk to a single cell on small
+/// panels so the content keeps most of the room on a small terminal.
+pub fn panel_margin(area: Rect, horizontal: u16, vertical: u16) -> Margin {
+    Margin::new(
+        if area.width < NARROW_PANEL_WIDTH {
+            horizontal.min(1)
+        } else {
+            horizontal
+        },
+        if area.height < LOW_PANEL_HEIGHT {
+            vertical.min(1)
+        } else {
+            vertical
+        },
+    )
+}
+
+/// Whether the area is smaller than [`MIN_TERMINAL_WIDTH`] × [`MIN_TERMINAL_HEIGHT`].
+pub fn is_too_small(area: Rect) -> bool {
+    area.width < MIN_TERMINAL_WIDTH || area.height < MIN_TERMINAL_HEIGHT
+}
+
+/// Asks for a larger terminal in place of a screen that doesn't fit, see
+/// [`is_too_small`]. Returns whether it did, in which case the screen isn't drawn; it's
+/// drawn again as soon as the terminal is resized to fit.
+pub fn render_size_guard(frame: &mut Frame) -> bool {
+    let area = frame.area();
+    if !is_too_small(area) {
+        return false;
+    }
+
+    let lines = vec![
+        Line::from("Terminal too small"),
+        Line::from(format!(
+            "Please enlarge it to {MIN_TERMINAL_WIDTH}×{MIN_TERMINAL_HEIGHT}"
+        )),
+        Line::styled(
+            format!("(now {}×{})", area.width, area.height),
+            Style::new().fg(Color::DarkGray),
+        ),
+    ];
+    let text_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(1),
+            // A row to spare for a line wrapped on a narrow terminal
+            Constraint::Length(lines.len() as u16 + 1),
+            Constraint::Fill(1),
+        ])
+        .split(area)[1];
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        text_area,
+    );
+    true
+}
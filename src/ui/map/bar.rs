@@ -10,53 +10,52 @@ use ratatui::{
 use crate::{
     states::{
         MapState,
-        map::{DiscardMenuType, Mode, Notification},
+        map::{Mode, TUTORIAL_STEPS},
+    },
+    utils::{
+        BarField, BarItem, IoErrorKind, Locale, StatusBarFormat, get_color_name_in_string, tr,
+        tr_args,
     },
-    utils::{IoErrorKind, get_color_name_in_string},
 };
 
-/// Renders the bottom information bar showing mode, viewport position, and transient notifications.
+/// Renders the bottom information bar showing mode and viewport position.
 ///
 /// Note: This function clears one-time notifications/errors from the state after rendering them.
-pub fn render_bar(frame: &mut Frame, map_state: &mut MapState) {
+pub fn render_bar(frame: &mut Frame, map_state: &mut MapState, locale: Locale) {
     let size = frame.area();
 
-    let (mode_text, mode_text_color) = match &map_state.mode {
-        Mode::Normal => (String::from("[ NORMAL ]"), Style::new().fg(Color::White)),
-        Mode::Visual => (String::from("[ VISUAL ]"), Style::new().fg(Color::Yellow)),
-        Mode::VisualMove => (
-            String::from("[ VISUAL (MOVE) ]"),
-            Style::new().fg(Color::Yellow),
-        ),
-        Mode::VisualConnect => (
-            String::from("[ VISUAL (CONNECT) ]"),
-            Style::new().fg(Color::Yellow),
-        ),
-        Mode::Edit => (String::from("[ EDIT ]"), Style::new().fg(Color::Blue)),
-        Mode::EditNormal => (
-            String::from("[ EDIT (NORMAL) ]"),
-            Style::new().fg(Color::Blue),
-        ),
-        Mode::EditInsert => (
-            String::from("[ EDIT (INSERT) ]"),
-            Style::new().fg(Color::Blue),
+    let (mode_key, mode_text_color) = mode_label(&map_state.mode);
+
+    // A status bar format from the settings replaces the mode and the view position
+    let (mode_display, view_position_display) = match &map_state.settings.status_bar_format {
+        Some(format) => {
+            let format = StatusBarFormat::parse(format);
+            (
+                Paragraph::new(format_bar_items(&format.left, map_state, locale)),
+                Paragraph::new(format_bar_items(&format.right, map_state, locale)),
+            )
+        }
+        None => (
+            Paragraph::new(tr(locale, mode_key)).style(mode_text_color),
+            Paragraph::new(tr_args(
+                locale,
+                "bar.view",
+                &[
+                    ("x", &map_state.viewport.view_pos.x.to_string()),
+                    ("y", &map_state.viewport.view_pos.y.to_string()),
+                ],
+            )),
         ),
-        Mode::Delete => (String::from("[ DELETE ]"), Style::new().fg(Color::Red)),
     };
-
-    let mode_display = Paragraph::new(format!("{}", mode_text))
-        .style(mode_text_color)
+    let mode_display = mode_display
         .alignment(Alignment::Left)
         .block(Block::default().padding(Padding::new(2, 0, 0, 0)));
+    let view_position_display = view_position_display
+        .alignment(Alignment::Right)
+        .block(Block::default().padding(Padding::new(0, 2, 0, 0)));
 
-    let view_position_display = Paragraph::new(format!(
-        "View: {},{}",
-        map_state.viewport.view_pos.x, map_state.viewport.view_pos.y,
-    ))
-    .alignment(Alignment::Right)
-    .block(Block::default().padding(Padding::new(0, 2, 0, 0)));
-
-    // Bar occupies last 3 rows: one empty spacer, two content rows
+    // Bar occupies last 3 rows: a spacer (the tutorial step on the tutorial map), two
+    // content rows
     let bar_area = Rect {
         x: size.x,
         y: size.height - 3,
@@ -95,32 +94,85 @@ pub fn render_bar(frame: &mut Frame, map_state: &mut MapState) {
     frame.render_widget(mode_display, row_2_areas[0]);
     frame.render_widget(view_position_display, row_2_areas[2]);
 
-    if let Mode::Delete = &map_state.mode {
-        let delete_note_prompt = Line::from(Span::styled(
-            String::from("d - Delete the selected note          Esc - Go back to Visual Mode"),
-            Style::new().fg(Color::Red),
-        ));
+    if let Mode::Region = &map_state.mode {
+        let region_prompt = if map_state.regions_state.editing_label {
+            "bar.region.label_prompt"
+        } else {
+            "bar.region.keys"
+        };
+        let region_prompt = Line::from(tr(locale, region_prompt))
+            .fg(Color::Magenta)
+            .alignment(Alignment::Center);
+
+        frame.render_widget(region_prompt, row_2_areas[1]);
 
-        frame.render_widget(delete_note_prompt, row_2_areas[1]);
+        // Number of notes inside the region, and whether notes moved in get tagged
+        if let Some(index) = map_state.regions_state.selected_index()
+            && let Some(region) = map_state.regions_state.selected_region()
+        {
+            let note_count = map_state.notes_in_region(index).len();
+            let label = if region.label.is_empty() {
+                tr(locale, "bar.region.unnamed")
+            } else {
+                &region.label
+            };
+            let mut region_info = tr_args(
+                locale,
+                if note_count == 1 {
+                    "bar.region.notes.one"
+                } else {
+                    "bar.region.notes.many"
+                },
+                &[("label", label), ("count", &note_count.to_string())],
+            );
+            if region.auto_tag
+                && let Some(tag) = region.tag()
+            {
+                region_info.push_str(&tr_args(locale, "bar.region.tags", &[("tag", &tag)]));
+            }
+            region_info.push_str(&tr_args(
+                locale,
+                "bar.region.arrange",
+                &[("order", map_state.regions_state.arrange_order.name())],
+            ));
+
+            let region_info = Paragraph::new(region_info)
+                .style(Style::new().fg(Color::Magenta))
+                .alignment(Alignment::Left)
+                .block(Block::default().padding(Padding::new(2, 0, 0, 0)));
+            frame.render_widget(region_info, row_1_areas[0]);
+        }
     }
 
     // Show color of focused connection if one exists, otherwise show color of selected note
     if matches!(
         map_state.mode,
-        Mode::Visual | Mode::VisualMove | Mode::VisualConnect
+        Mode::Visual
+            | Mode::VisualMove
+            | Mode::VisualConnect
+            | Mode::VisualWaypoint
+            | Mode::VisualBox
     ) {
-        let mut current_color_text = String::from("");
+        let mut current_color_text = "";
         let mut current_color_name = String::from("");
         let mut current_color = Color::White;
+        let mut weight_text = String::from("");
 
         if let Some(selected_note_id) = &map_state.notes_state.selected_note_id() {
             if let Some(focused_connection) = &map_state.connections_state.focused_connection {
-                current_color_text = String::from("Selected connection color: ");
+                current_color_text = tr(locale, "bar.color.connection");
                 current_color_name = get_color_name_in_string(focused_connection.color);
                 current_color = focused_connection.color;
+                if focused_connection.weight.is_some() {
+                    weight_text = tr_args(
+                        locale,
+                        "bar.weight",
+                        &[("weight", &focused_connection.weight().to_string())],
+                    );
+                }
             } else {
                 if let Some(note) = map_state.notes_state.notes().get(selected_note_id) {
-                    current_color_text = String::from("Selected note color: ");
+                    current_color_text = tr(locale, "bar.color.note");
                     current_color_name = get_color_name_in_string(note.color);
                     current_color = note.color;
                 }
@@ -130,144 +182,150 @@ pub fn render_bar(frame: &mut Frame, map_state: &mut MapState) {
         let current_color_widget = Line::from(vec![
             Span::from(current_color_text),
             Span::styled(current_color_name, Style::new().fg(current_color)),
+            Span::from(weight_text),
         ])
         .alignment(Alignment::Center);
 
         frame.render_widget(current_color_widget, row_2_areas[1]);
     }
 
+    // Path search progress, or the length of the highlighted path
+    let path_hint = if map_state.ui_state.path_start.is_some() {
+        Some(String::from(tr(locale, "bar.path.search")))
+    } else if !map_state.ui_state.highlighted_path.is_empty() {
+        let steps = map_state.ui_state.highlighted_path.len() - 1;
+        Some(tr_args(
+            locale,
+            if steps == 1 {
+                "bar.path.one"
+            } else {
+                "bar.path.many"
+            },
+            &[("count", &steps.to_string())],
+        ))
+    } else if let Some(search) = &map_state.ui_state.search
+        && !search.typing
+    {
+        let matches = search.all_matches(map_state.notes_state.notes(), |id| {
+            map_state.note_is_locked(id)
+        });
+        let current = search
+            .current
+            .as_ref()
+            .and_then(|current| matches.iter().position(|m| m == current))
+            .map_or(0, |i| i + 1);
+        Some(tr_args(
+            locale,
+            "bar.search",
+            &[
+                ("current", &current.to_string()),
+                ("count", &matches.len().to_string()),
+            ],
+        ))
+    } else {
+        None
+    };
+    if let Some(path_hint) = path_hint {
+        frame.render_widget(
+            Line::from(path_hint)
+                .fg(Color::Cyan)
+                .alignment(Alignment::Center),
+            row_1_areas[1],
+        );
+    } else if map_state.persistence.recovered {
+        frame.render_widget(
+            Line::from(tr(locale, "bar.recovered"))
+                .fg(Color::Yellow)
+                .alignment(Alignment::Center),
+            row_1_areas[1],
+        );
+    }
+
+    // The current step of the tutorial, on the row above the bar
+    if let Some(tutorial) = &map_state.tutorial {
+        let step_text = tr_args(
+            locale,
+            "tutorial.bar",
+            &[
+                ("step", &(tutorial.step + 1).to_string()),
+                ("steps", &TUTORIAL_STEPS.len().to_string()),
+                ("text", tr(locale, tutorial.current().text)),
+            ],
+        );
+        frame.render_widget(
+            Line::from(step_text)
+                .fg(Color::Green)
+                .alignment(Alignment::Center),
+            bar_rows[0],
+        );
+    }
+
     // One-time error notification: rendered once then immediately cleared from state
     if let Some(err_msg) = &map_state.settings_err_msg {
         let settings_err_msg = match err_msg {
-            IoErrorKind::DirFind => Line::from(Span::styled(
-                "Settings error: no home directory - using defaults.",
-                Style::new().fg(Color::Red),
-            ))
-            .alignment(Alignment::Center),
-            IoErrorKind::DirCreate => Line::from(Span::styled(
-                "Settings error: can't create config directory - using defaults.",
-                Style::new().fg(Color::Red),
-            ))
-            .alignment(Alignment::Center),
-            IoErrorKind::FileWrite => Line::from(Span::styled(
-                "Settings error: can't create settings file - using defaults.",
-                Style::new().fg(Color::Red),
-            ))
-            .alignment(Alignment::Center),
-            IoErrorKind::FileRead => Line::from(Span::styled(
-                "Settings error: can't read settings file - using defaults.",
-                Style::new().fg(Color::Red),
-            ))
-            .alignment(Alignment::Center),
+            IoErrorKind::DirFind => "bar.settings_error.dir_find",
+            IoErrorKind::DirCreate => "bar.settings_error.dir_create",
+            IoErrorKind::FileWrite => "bar.settings_error.file_write",
+            IoErrorKind::FileRead => "bar.settings_error.file_read",
         };
+        let settings_err_msg = Line::from(Span::styled(
+            tr(locale, settings_err_msg),
+            Style::new().fg(Color::Red),
+        ))
+        .alignment(Alignment::Center);
 
         frame.render_widget(settings_err_msg, row_1_areas[1]);
         map_state.settings_err_msg = None;
     }
+}
 
-    // One-time success/failure notification: rendered once then immediately cleared from state
-    if let Some(notification) = &map_state.ui_state.show_notification {
-        match notification {
-            Notification::SaveSuccess => {
-                let notification_message = Line::from("Map file saved successfully")
-                    .fg(Color::Green)
-                    .alignment(Alignment::Center);
-                frame.render_widget(notification_message, row_2_areas[1]);
-            }
-            Notification::SaveFail => {
-                let notification_message = Line::from("Error saving the map file")
-                    .fg(Color::Red)
-                    .alignment(Alignment::Center);
-                frame.render_widget(notification_message, row_2_areas[1]);
-            }
-            Notification::BackupSuccess => {
-                let notification_message = Line::from("Backup file made successfully")
-                    .fg(Color::Green)
-                    .alignment(Alignment::Center);
-                frame.render_widget(notification_message, row_2_areas[1]);
-            }
-            Notification::BackupFail => {
-                let notification_message = Line::from("Error saving backup file")
-                    .fg(Color::Red)
-                    .alignment(Alignment::Center);
-                frame.render_widget(notification_message, row_2_areas[1]);
-            }
-            Notification::BackupRecordFail => {
-                let notification_message =
-                    Line::from("Backup created successfully, but failed to update backup records")
-                        .fg(Color::Red)
-                        .alignment(Alignment::Center);
-                frame.render_widget(notification_message, row_2_areas[1]);
-            }
-        };
-
-        map_state.ui_state.clear_notification();
+/// The translation key of the mode's name and the style it's shown in.
+fn mode_label(mode: &Mode) -> (&'static str, Style) {
+    match mode {
+        Mode::Normal => ("bar.mode.normal", Style::new().fg(Color::White)),
+        Mode::Visual => ("bar.mode.visual", Style::new().fg(Color::Yellow)),
+        Mode::VisualMove => ("bar.mode.visual_move", Style::new().fg(Color::Yellow)),
+        Mode::VisualConnect => ("bar.mode.visual_connect", Style::new().fg(Color::Yellow)),
+        Mode::VisualWaypoint => ("bar.mode.visual_waypoint", Style::new().fg(Color::Yellow)),
+        Mode::VisualBox => ("bar.mode.visual_box", Style::new().fg(Color::Yellow)),
+        Mode::Edit => ("bar.mode.edit", Style::new().fg(Color::Blue)),
+        Mode::EditNormal => ("bar.mode.edit_normal", Style::new().fg(Color::Blue)),
+        Mode::EditInsert => ("bar.mode.edit_insert", Style::new().fg(Color::Blue)),
+        Mode::Delete => ("bar.mode.delete", Style::new().fg(Color::Red)),
+        Mode::Region => ("bar.mode.region", Style::new().fg(Color::Magenta)),
     }
+}
 
-    if let Some(discard_menu_type) = &map_state.ui_state.confirm_discard_menu {
-        let confirm_discard_menu_area = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Fill(1),
-                Constraint::Length(1),
-                Constraint::Length(8),
-            ])
-            .split(frame.area());
-
-        frame.render_widget(Clear, confirm_discard_menu_area[1]);
-        frame.render_widget(Clear, confirm_discard_menu_area[2]);
-        frame.render_widget(Block::bordered(), confirm_discard_menu_area[2]);
-
-        let confirm_discard_menu_text_areas = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(2),
-                Constraint::Length(1),
-                Constraint::Length(1),
-                Constraint::Length(1),
-                Constraint::Length(1),
-                Constraint::Length(2),
-            ])
-            .split(confirm_discard_menu_area[2]);
-
-        match discard_menu_type {
-            DiscardMenuType::Start => {
-                let line_1 =
-                    Line::from("Discard unsaved changes to this map?").alignment(Alignment::Center);
-                let line_2 = Line::from(vec![
-                    Span::styled("[ ESC ] - Cancel", Style::new().fg(Color::Green)),
-                    Span::raw("      "),
-                    Span::styled(
-                        "[ q ] - Confirm discard and exit",
-                        Style::new().fg(Color::Red),
-                    ),
-                ])
-                .alignment(Alignment::Center);
-
-                frame.render_widget(line_1, confirm_discard_menu_text_areas[1]);
-                frame.render_widget(line_2, confirm_discard_menu_text_areas[4]);
+/// Fills in one side of a status bar format. The mode keeps its color.
+fn format_bar_items(items: &[BarItem], map_state: &MapState, locale: Locale) -> Line<'static> {
+    let spans: Vec<Span> = items
+        .iter()
+        .map(|item| match item {
+            BarItem::Text(text) => Span::from(text.clone()),
+            BarItem::Field(BarField::Mode) => {
+                let (mode_key, mode_style) = mode_label(&map_state.mode);
+                Span::styled(tr(locale, mode_key).to_string(), mode_style)
             }
-            DiscardMenuType::Settings => {
-                let line_1 = Line::from("Discard unsaved changes to this map and go to settings?")
-                    .alignment(Alignment::Center);
-                let line_2 = Line::from(
-                    "(You must save changes or discard them before you can open the settings menu)",
-                )
-                .alignment(Alignment::Center);
-                let line_3 = Line::from(vec![
-                    Span::styled("[ ESC ] - Cancel", Style::new().fg(Color::Green)),
-                    Span::raw("      "),
-                    Span::styled(
-                        "[ q ] - Confirm discard and go to settings",
-                        Style::new().fg(Color::Red),
-                    ),
-                ])
-                .alignment(Alignment::Center);
-
-                frame.render_widget(line_1, confirm_discard_menu_text_areas[1]);
-                frame.render_widget(line_2, confirm_discard_menu_text_areas[2]);
-                frame.render_widget(line_3, confirm_discard_menu_text_areas[4]);
+            BarItem::Field(BarField::X) => Span::from(map_state.viewport.view_pos.x.to_string()),
+            BarItem::Field(BarField::Y) => Span::from(map_state.viewport.view_pos.y.to_string()),
+            BarItem::Field(BarField::SelectedNote) => Span::from(
+                map_state
+                    .notes_state
+                    .selected_note_id()
+                    .map_or(String::from("-"), |id| id.to_string()),
+            ),
+            BarItem::Field(BarField::Unsaved) => {
+                if map_state.persistence.has_unsaved_changes {
+                    Span::from("[+]").fg(Color::Yellow)
+                } else {
+                    Span::from("")
+                }
             }
-        }
-    }
+            BarItem::Field(BarField::Clock) => Span::from(map_state.ui_state.bar_clock.clone()),
+            BarItem::Field(BarField::NoteCount) => {
+                Span::from(map_state.notes_state.notes().len().to_string())
+            }
+        })
+        .collect();
+    Line::from(spans)
 }
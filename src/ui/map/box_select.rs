@@ -0,0 +1,63 @@
+use ratatui::{
+    Frame,
+    prelude::Rect,
+    style::Color,
+    widgets::{Block, BorderType, Borders},
+};
+
+use crate::states::{
+    MapState,
+    map::{BoxSelect, SignedRect},
+};
+
+/// Draws the outline of the Visual (Box) mode selection rectangle over the notes.
+///
+/// Only the sides that are on screen are drawn, like the borders of clipped notes.
+pub fn render_box_select(frame: &mut Frame, map_state: &MapState, box_select: &BoxSelect) {
+    let ((left, top), (right, bottom)) = box_select.bounds();
+    let origin = map_state.viewport.to_screen((left, top).into());
+    let box_rect = SignedRect {
+        x: origin.x,
+        y: origin.y,
+        width: right - left + 1,
+        height: bottom - top + 1,
+    };
+    let frame_rect = SignedRect {
+        x: 0,
+        y: 0,
+        width: frame.area().width as isize,
+        height: frame.area().height as isize,
+    };
+
+    let Some(visible_part) = box_rect.intersection(&frame_rect) else {
+        return;
+    };
+
+    let mut borders = Borders::NONE;
+    if box_rect.x == visible_part.x {
+        borders |= Borders::LEFT;
+    }
+    if box_rect.x + box_rect.width == visible_part.x + visible_part.width {
+        borders |= Borders::RIGHT;
+    }
+    if box_rect.y == visible_part.y {
+        borders |= Borders::TOP;
+    }
+    if box_rect.y + box_rect.height == visible_part.y + visible_part.height {
+        borders |= Borders::BOTTOM;
+    }
+
+    // No Clear, so the notes inside stay visible
+    frame.render_widget(
+        Block::default()
+            .borders(borders)
+            .border_type(BorderType::Rounded)
+            .border_style(Color::Yellow),
+        Rect::new(
+            visible_part.x as u16,
+            visible_part.y as u16,
+            visible_part.width as u16,
+            visible_part.height as u16,
+        ),
+    );
+}
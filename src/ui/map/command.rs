@@ -0,0 +1,26 @@
+use ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    style::{Color, Stylize},
+    text::Line,
+    widgets::Clear,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Renders the `:` command line over the bottom row of the screen.
+pub fn render_command_line(frame: &mut Frame, command: &str) {
+    let area = frame.area();
+    if area.height == 0 {
+        return;
+    }
+    let line_area = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+
+    frame.render_widget(Clear, line_area);
+    frame.render_widget(
+        Line::from(format!(":{command}")).fg(Color::Yellow),
+        line_area,
+    );
+
+    let cursor_x = (1 + command.width()).min(area.width.saturating_sub(1) as usize);
+    frame.set_cursor_position(Position::new(line_area.x + cursor_x as u16, line_area.y));
+}
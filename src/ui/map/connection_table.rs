@@ -0,0 +1,130 @@
+use ratatui::{
+    Frame,
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+use unicode_width::UnicodeWidthChar;
+
+use super::milestones::popup_area;
+use crate::{
+    states::{MapState, map::ConnectionSort},
+    utils::{Locale, get_color_name_in_string, note_title, tr, tr_args},
+};
+
+/// Rows of the table, not counting its header
+const VISIBLE_ROWS: usize = 14;
+/// Width of the columns with note titles
+const TITLE_WIDTH: usize = 40;
+/// Width of the color column
+const COLOR_WIDTH: usize = 16;
+
+/// Renders the table of every connection in its sort order, with the notes each one
+/// joins, its color and weight, and which are marked.
+pub fn render_connection_table(frame: &mut Frame, map_state: &MapState, locale: Locale) {
+    let Some(table) = &map_state.ui_state.connection_table else {
+        return;
+    };
+    let table_area = popup_area(frame, 120, VISIBLE_ROWS as u16 + 6);
+    let notes = map_state.notes_state.notes();
+    let connections = map_state.connections_state.connections();
+
+    let header = |column: ConnectionSort, key: &'static str, width: usize| {
+        let mut title = tr(locale, key).to_string();
+        if table.sort == column {
+            title.push_str(if table.descending { " ▼" } else { " ▲" });
+        }
+        cell(&title, width)
+    };
+    let mut lines = vec![
+        Line::from(format!(
+            "  {}{}{}{}",
+            header(ConnectionSort::From, "prompt.connections.from", TITLE_WIDTH),
+            header(ConnectionSort::To, "prompt.connections.to", TITLE_WIDTH),
+            header(
+                ConnectionSort::Color,
+                "prompt.connections.color",
+                COLOR_WIDTH
+            ),
+            header(ConnectionSort::Weight, "prompt.connections.weight", 8),
+        ))
+        .add_modifier(Modifier::BOLD),
+    ];
+
+    if table.rows.is_empty() {
+        lines.push(Line::from(tr(locale, "prompt.connections.empty")).fg(Color::DarkGray));
+    } else {
+        // Keep the highlighted connection in view
+        let first = table.selected.saturating_sub(VISIBLE_ROWS - 1);
+        lines.extend(
+            table
+                .rows
+                .iter()
+                .enumerate()
+                .skip(first)
+                .take(VISIBLE_ROWS)
+                .map(|(row, &index)| {
+                    let connection = &connections[index];
+                    let title = |id: usize| notes.get(&id).map(note_title).unwrap_or_default();
+                    let mark = if table.marked.contains(&index) {
+                        "● "
+                    } else {
+                        "  "
+                    };
+                    let line = Line::from(vec![
+                        Span::from(mark).fg(Color::Yellow),
+                        Span::from(cell(&title(connection.from_id), TITLE_WIDTH)),
+                        Span::from(cell(&title(connection.to_id), TITLE_WIDTH)),
+                        Span::from(cell(
+                            &get_color_name_in_string(connection.color),
+                            COLOR_WIDTH,
+                        ))
+                        .fg(connection.color),
+                        Span::from(connection.weight().to_string()),
+                    ]);
+                    if row == table.selected {
+                        line.style(Style::new().add_modifier(Modifier::REVERSED))
+                    } else {
+                        line
+                    }
+                }),
+        );
+    }
+    lines.resize(VISIBLE_ROWS + 1, Line::from(""));
+    lines.push(Line::from(""));
+    if !table.marked.is_empty() {
+        let marked = tr_args(
+            locale,
+            "prompt.connections.marked",
+            &[("count", &table.marked.len().to_string())],
+        );
+        lines.push(Line::from(marked).fg(Color::Yellow));
+    } else {
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(tr(locale, "prompt.connections.keys")).fg(Color::DarkGray));
+
+    frame.render_widget(Clear, table_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(Block::bordered().title(tr(locale, "prompt.connections.title"))),
+        table_area,
+    );
+}
+
+/// The text cut or padded with spaces to fill `width` columns, leaving at least one
+/// blank column at the end.
+fn cell(text: &str, width: usize) -> String {
+    let mut cell = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let c_width = c.width().unwrap_or(0);
+        if used + c_width >= width {
+            break;
+        }
+        cell.push(c);
+        used += c_width;
+    }
+    cell.push_str(&" ".repeat(width - used));
+    cell
+}
@@ -1,216 +1,358 @@
-use ratatui::{Frame, style::Color};
+use ratatui::{Frame, layout::Rect, style::Color, widgets::BorderType};
 
 use crate::{
     states::{
         MapState,
-        map::{Mode, Note, Side},
+        map::{
+            CanvasPos, Connection, DraftConnection, Hyperedge, Mode, ScreenPos, Side, SignedRect,
+        },
     },
     ui::{
-        DOUBLE_JUNCTIONS, IN_PROGRESS_CHARSET, NORMAL_CHARSET, PLAIN_JUNCTIONS, SegDir,
-        THICK_JUNCTIONS,
+        DOUBLE_BORDER_JUNCTIONS, IN_PROGRESS_CHARSET, NORMAL_CHARSET, PLAIN_CROSSING,
+        PLAIN_JUNCTIONS, THICK_BORDER_JUNCTIONS, THICK_CROSSING, THICK_JUNCTIONS,
+        THICK_LINE_JUNCTIONS, WAYPOINT_CHARACTER, note_border_type,
     },
-    utils::{Point, calculate_path},
+    utils::{Point, connection_ends, connection_path, hyperedge_paths, path_bounds},
 };
 
 pub fn render_connections(frame: &mut Frame, map_state: &mut MapState) {
+    let frame_rect = SignedRect {
+        x: 0,
+        y: 0,
+        width: frame.area().width as isize,
+        height: frame.area().height as isize,
+    };
+    let mut lines = DrawnLines::new(frame.area());
+
     for connection in map_state.connections_state.connections() {
-        if let Some(start_note) = map_state.notes_state.notes().get(&connection.from_id) {
-            if let Some(end_note_id) = connection.to_id {
-                if let Some(end_note) = map_state.notes_state.notes().get(&end_note_id) {
-                    let path = calculate_path(
-                        start_note,
-                        connection.from_side,
-                        end_note,
-                        connection.to_side.unwrap(), // Safe: to_side guaranteed present when to_id is Some
-                    );
-
-                    // Optimization: skip off-screen connections to avoid expensive per-cell iteration.
-                    // `.any()` short-circuits on first visible point.
-                    let is_visible = path.iter().any(|point| {
-                        let (p_x, p_y) = map_state.viewport.to_screen_coords(point.x, point.y);
-                        p_x >= 0
-                            && p_x < frame.area().width as isize
-                            && p_y >= 0
-                            && p_y < frame.area().height as isize
-                    });
-
-                    if !is_visible {
-                        continue;
-                    }
-
-                    draw_connection(path, false, connection.color, frame, map_state);
-                }
-            }
+        if map_state.connection_is_hidden(connection) {
+            continue;
+        }
+        let notes = map_state.notes_state.notes();
+        let (Some(start_note), Some(end_note)) =
+            (notes.get(&connection.from_id), notes.get(&connection.to_id))
+        else {
+            continue;
+        };
+        let (end_note_id, end_side) = (connection.to_id, connection.to_side);
+
+        // Connections between the same notes are drawn side by side
+        let offset = map_state.connections_state.parallel_offset(connection);
+
+        // Optimization: skip connections that can't reach the screen before
+        // calculating their path, so only the visible part of a large map costs anything.
+        let bounds = path_bounds(
+            start_note,
+            connection.from_side,
+            end_note,
+            end_side,
+            &connection.waypoints,
+        );
+        // Drawn aside, or looping back to its note, the path can be that far
+        // off its bounds
+        let margin = 2 * offset.abs() + 2;
+        let origin = map_state
+            .viewport
+            .to_screen(CanvasPos::new(bounds.x - margin, bounds.y - margin));
+        let screen_bounds = SignedRect {
+            x: origin.x,
+            y: origin.y,
+            width: bounds.width + 2 * margin,
+            height: bounds.height + 2 * margin,
+        };
+        if screen_bounds.intersection(&frame_rect).is_none() {
+            continue;
+        }
+
+        let path = map_state
+            .ui_state
+            .path_cache
+            .path(connection, start_note, end_note, offset);
+
+        let changes = map_state.ui_state.map_changes.as_ref();
+        let color = if map_state
+            .ui_state
+            .path_links(connection.from_id, end_note_id)
+        {
+            Color::Cyan
+        } else if changes.is_some_and(|changes| changes.connections_added.contains(connection)) {
+            Color::Green
+        } else if changes.is_some_and(|changes| changes.connections_removed.contains(connection)) {
+            Color::Red
+        } else {
+            connection.color
+        };
+        draw_connection(&path, false, color, frame, map_state, &mut lines);
+        if let Some(weight) = connection.weight {
+            draw_weight_label(&path, weight, color, frame, map_state);
+        }
+    }
+
+    map_state
+        .ui_state
+        .path_cache
+        .prune(&map_state.connections_state);
+
+    for hyperedge in map_state.hyperedges_state.hyperedges() {
+        if let Some((trunk, branches)) = shown_hyperedge_paths(hyperedge, map_state) {
+            draw_hyperedge(
+                &trunk,
+                &branches,
+                hyperedge.color,
+                frame,
+                map_state,
+                &mut lines,
+            );
         }
     }
 
     // Render focused connection being created/edited
-    if let Some(focused_connection) = &map_state.connections_state.focused_connection {
-        if let Some(start_note) = map_state
-            .notes_state
-            .notes()
-            .get(&focused_connection.from_id)
-        {
-            if let Some(end_note_id) = focused_connection.to_id {
-                if let Some(end_note) = map_state.notes_state.notes().get(&end_note_id) {
-                    let path = calculate_path(
-                        start_note,
-                        focused_connection.from_side,
-                        end_note,
-                        focused_connection.to_side.unwrap(), // Safe: to_side guaranteed present when to_id is Some
-                    );
-
-                    draw_connection(path, true, Color::Yellow, frame, map_state);
-                }
+    // (nothing to draw until it has an end)
+    if let Some(focused_connection) = map_state
+        .connections_state
+        .focused_connection
+        .as_ref()
+        .and_then(DraftConnection::completed)
+    {
+        let notes = map_state.notes_state.notes();
+        if let (Some(start_note), Some(end_note)) = (
+            notes.get(&focused_connection.from_id),
+            notes.get(&focused_connection.to_id),
+        ) {
+            let path = connection_path(
+                &focused_connection,
+                start_note,
+                end_note,
+                map_state
+                    .connections_state
+                    .parallel_offset(&focused_connection),
+            );
+
+            draw_connection(&path, true, Color::Yellow, frame, map_state, &mut lines);
+            if let Some(weight) = focused_connection.weight {
+                draw_weight_label(&path, weight, Color::Yellow, frame, map_state);
+            }
+
+            if map_state.mode == Mode::VisualWaypoint {
+                draw_waypoints(&focused_connection.waypoints, frame, map_state);
             }
         }
     }
 }
 
-/// Draws a connection path on the screen.
+/// Line directions a cell can have drawn in it, combined as bits
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+/// Directions the connection lines drawn so far this frame leave each screen cell in,
+/// so a line meeting another one is joined to it (a crossing or a junction) instead of
+/// covering it and looking like it ends there.
+struct DrawnLines {
+    area: Rect,
+    /// Row by row, see [`UP`] and friends
+    arms: Vec<u8>,
+}
+
+impl DrawnLines {
+    fn new(area: Rect) -> Self {
+        Self {
+            area,
+            arms: vec![0; area.width as usize * area.height as usize],
+        }
+    }
+
+    /// Adds the line directions of a cell to the ones already drawn in it, returns them all.
+    fn join(&mut self, (x, y): (u16, u16), arms: u8) -> u8 {
+        let row = (y - self.area.y) as usize;
+        let drawn = &mut self.arms[row * self.area.width as usize + (x - self.area.x) as usize];
+        *drawn |= arms;
+        *drawn
+    }
+}
+
+/// The line character leaving its cell in the directions `arms` (see [`UP`] and
+/// friends). A single direction draws a straight line.
+/// `in_progress`: if true, uses the charsets of a connection being created/edited
+fn line_symbol(arms: u8, in_progress: bool) -> &'static str {
+    let (charset, junctions, crossing) = if in_progress {
+        (&IN_PROGRESS_CHARSET, &THICK_JUNCTIONS, THICK_CROSSING)
+    } else {
+        (&NORMAL_CHARSET, &PLAIN_JUNCTIONS, PLAIN_CROSSING)
+    };
+
+    let has = |arm| arms & arm != 0;
+    match (has(UP), has(DOWN), has(LEFT), has(RIGHT)) {
+        (true, true, true, true) => crossing,
+        // ┴ ┬ ┤ ├
+        (true, false, true, true) => junctions[0],
+        (false, true, true, true) => junctions[1],
+        (true, true, true, false) => junctions[2],
+        (true, true, false, true) => junctions[3],
+        // ┌ ┐ └ ┘
+        (false, true, false, true) => charset[2],
+        (false, true, true, false) => charset[3],
+        (true, false, false, true) => charset[4],
+        (true, false, true, false) => charset[5],
+        // │ ─
+        (_, _, false, false) => charset[1],
+        _ => charset[0],
+    }
+}
+
+/// Draws a connection path on the screen, joined to the lines drawn before it.
 /// `in_progress`: if true, uses special charset to indicate connection being created/edited
-pub fn draw_connection(
-    path: Vec<Point>,
+fn draw_connection(
+    path: &[Point],
     in_progress: bool,
     color: Color,
     frame: &mut Frame,
     map_state: &MapState,
+    lines: &mut DrawnLines,
 ) {
-    let connection_charset = if in_progress {
-        &IN_PROGRESS_CHARSET
-    } else {
-        &NORMAL_CHARSET
-    };
+    let area = frame.area();
+    // Direction of the previous segment's line into the point the next one starts at
+    let mut arriving = 0;
 
-    // Draw horizontal and vertical line segments (path split into pairs of points)
+    // Each segment is drawn from its start up to the next one. The last point is on
+    // the end note's border and isn't drawn.
     for points in path.windows(2) {
-        let (p1_x, p1_y) = map_state
-            .viewport
-            .to_screen_coords(points[0].x, points[0].y);
-
-        if points[0].x != points[1].x {
-            let x_diff = (points[1].x - points[0].x).abs();
-            let mut x_coor: isize;
-
-            for offset in 0..x_diff {
-                if points[1].x > points[0].x {
-                    x_coor = p1_x + offset;
-                } else {
-                    x_coor = p1_x - offset;
-                }
-
-                if x_coor >= 0
-                    && x_coor < frame.area().width as isize
-                    && p1_y >= 0
-                    && p1_y < frame.area().height as isize
-                {
-                    if let Some(cell) = frame.buffer_mut().cell_mut((x_coor as u16, p1_y as u16)) {
-                        cell.set_symbol(connection_charset[0]).set_fg(color);
-                    }
-                }
-            }
-        } else {
-            let y_diff = (points[1].y - points[0].y).abs();
-            let mut y_coor: isize;
-
-            for offset in 0..y_diff {
-                if points[1].y > points[0].y {
-                    y_coor = p1_y + offset;
-                } else {
-                    y_coor = p1_y - offset;
-                }
+        let (from, to) = (points[0], points[1]);
+        let (step_x, step_y) = ((to.x - from.x).signum(), (to.y - from.y).signum());
+        let (forward, backward) = match (step_x, step_y) {
+            (0, 1) => (DOWN, UP),
+            (0, -1) => (UP, DOWN),
+            (1, 0) => (RIGHT, LEFT),
+            (-1, 0) => (LEFT, RIGHT),
+            // Points are identical; the line carries on from the previous segment
+            _ => continue,
+        };
 
-                if y_coor >= 0
-                    && y_coor < frame.area().height as isize
-                    && p1_x >= 0
-                    && p1_x < frame.area().width as isize
-                {
-                    if let Some(cell) = frame.buffer_mut().cell_mut((p1_x as u16, y_coor as u16)) {
-                        cell.set_symbol(connection_charset[1]).set_fg(color);
-                    }
+        let start = map_state.viewport.to_screen(from.into());
+        let length = (to.x - from.x).abs() + (to.y - from.y).abs();
+        for offset in 0..length {
+            let arms = forward | if offset == 0 { arriving } else { backward };
+            let point = ScreenPos::new(start.x + offset * step_x, start.y + offset * step_y);
+            if let Some(position) = point.cell_in(area) {
+                let arms = lines.join(position, arms);
+                if let Some(cell) = frame.buffer_mut().cell_mut(position) {
+                    cell.set_symbol(line_symbol(arms, in_progress))
+                        .set_fg(color);
                 }
             }
         }
+        arriving = backward;
     }
+}
 
-    // Calculate segment directions for each pair of points.
-    // Used to determine which corner character (┌ ┐ └ ┘) to draw at path bends.
-    let mut segment_directions: Vec<SegDir> = vec![];
+/// Paths of a fan-out connection as drawn: its trunk and the branches to the targets
+/// that aren't hidden. None if the fan-out isn't drawn at all.
+fn shown_hyperedge_paths(
+    hyperedge: &Hyperedge,
+    map_state: &MapState,
+) -> Option<(Vec<Point>, Vec<Vec<Point>>)> {
+    if map_state.hyperedge_is_hidden(hyperedge) {
+        return None;
+    }
+    let notes = map_state.notes_state.notes();
+    let source = notes.get(&hyperedge.from_id)?;
+    let targets: Vec<_> = hyperedge
+        .to_ids
+        .iter()
+        .filter(|&&id| !map_state.note_is_hidden(id))
+        .filter_map(|id| notes.get(id))
+        .collect();
+    if targets.is_empty() {
+        return None;
+    }
+    Some(hyperedge_paths(source, hyperedge.from_side, &targets))
+}
 
-    for points in path.windows(2) {
-        if points[0].x != points[1].x {
-            if points[1].x > points[0].x {
-                segment_directions.push(SegDir::Right);
-            } else {
-                segment_directions.push(SegDir::Left);
-            }
-        } else if points[0].y != points[1].y {
-            if points[1].y > points[0].y {
-                segment_directions.push(SegDir::Down);
-            } else {
-                segment_directions.push(SegDir::Up);
-            }
-        } else {
-            // Points are identical; continue in same direction to avoid corner artifacts
-            if let Some(last_direction) = segment_directions.last() {
-                segment_directions.push(*last_direction);
-            }
+/// Draws a fan-out connection: the trunk on to each branch, so the branches are
+/// joined where they split off.
+fn draw_hyperedge(
+    trunk: &[Point],
+    branches: &[Vec<Point>],
+    color: Color,
+    frame: &mut Frame,
+    map_state: &MapState,
+    lines: &mut DrawnLines,
+) {
+    for branch in branches {
+        let path: Vec<Point> = trunk.iter().chain(branch.iter().skip(1)).copied().collect();
+        draw_connection(&path, false, color, frame, map_state, lines);
+    }
+}
+
+/// Writes the connection's weight as a number centered on the longest segment of its path.
+///
+/// Drawn after the line itself so the digits replace the line characters beneath them.
+fn draw_weight_label(
+    path: &[Point],
+    weight: u32,
+    color: Color,
+    frame: &mut Frame,
+    map_state: &MapState,
+) {
+    let Some(segment) = path
+        .windows(2)
+        .max_by_key(|points| (points[1].x - points[0].x).abs() + (points[1].y - points[0].y).abs())
+    else {
+        return;
+    };
+
+    let label = weight.to_string();
+    let middle = map_state.viewport.to_screen(CanvasPos::new(
+        (segment[0].x + segment[1].x) / 2,
+        (segment[0].y + segment[1].y) / 2,
+    ));
+
+    for (offset, character) in label.chars().enumerate() {
+        let x = middle.x - label.len() as isize / 2 + offset as isize;
+        if let Some(position) = ScreenPos::new(x, middle.y).cell_in(frame.area())
+            && let Some(cell) = frame.buffer_mut().cell_mut(position)
+        {
+            cell.set_char(character).set_fg(color);
         }
     }
+}
 
-    // Draw corner characters at path bends (skip first and last points)
-    for (i, points) in path.windows(3).enumerate() {
-        let (p_x, p_y) = map_state
-            .viewport
-            .to_screen_coords(points[1].x, points[1].y);
-
-        let incoming = segment_directions[i];
-        let outgoing = segment_directions[i + 1];
-
-        let corner_character = match (incoming, outgoing) {
-            // ┌
-            (SegDir::Left, SegDir::Down) => connection_charset[2],
-            (SegDir::Up, SegDir::Right) => connection_charset[2],
-            // ┐
-            (SegDir::Right, SegDir::Down) => connection_charset[3],
-            (SegDir::Up, SegDir::Left) => connection_charset[3],
-            // └
-            (SegDir::Down, SegDir::Right) => connection_charset[4],
-            (SegDir::Left, SegDir::Up) => connection_charset[4],
-            // ┘
-            (SegDir::Down, SegDir::Left) => connection_charset[5],
-            (SegDir::Right, SegDir::Up) => connection_charset[5],
-            // ─
-            (SegDir::Left, SegDir::Left) => connection_charset[0],
-            (SegDir::Left, SegDir::Right) => connection_charset[0],
-            (SegDir::Right, SegDir::Right) => connection_charset[0],
-            (SegDir::Right, SegDir::Left) => connection_charset[0],
-            // │
-            (SegDir::Up, SegDir::Up) => connection_charset[1],
-            (SegDir::Up, SegDir::Down) => connection_charset[1],
-            (SegDir::Down, SegDir::Down) => connection_charset[1],
-            (SegDir::Down, SegDir::Up) => connection_charset[1],
+/// Marks the waypoints of the connection being edited, highlighting the selected one.
+fn draw_waypoints(waypoints: &[Point], frame: &mut Frame, map_state: &MapState) {
+    for (index, waypoint) in waypoints.iter().enumerate() {
+        let marker = map_state.viewport.to_screen((*waypoint).into());
+
+        let color = if map_state.connections_state.selected_waypoint == Some(index) {
+            Color::Magenta
+        } else {
+            Color::Yellow
         };
 
-        if p_x >= 0
-            && p_x < frame.area().width as isize
-            && p_y >= 0
-            && p_y < frame.area().height as isize
+        if let Some(position) = marker.cell_in(frame.area())
+            && let Some(cell) = frame.buffer_mut().cell_mut(position)
         {
-            if let Some(cell) = frame.buffer_mut().cell_mut((p_x as u16, p_y as u16)) {
-                cell.set_symbol(corner_character).set_fg(color);
-            }
+            cell.set_symbol(WAYPOINT_CHARACTER).set_fg(color);
         }
     }
 }
 
-/// Draws the connection point character at the specified side of a note.
-/// `is_editing`: true when drawing connection being created/edited
+/// Where a connection leaves its start note and enters its end note, moved along the
+/// note edges when it's drawn aside of others between the same notes. None if a note
+/// it joins is missing.
+pub fn junction_points(connection: &Connection, map_state: &MapState) -> Option<(Point, Point)> {
+    let notes = map_state.notes_state.notes();
+    let start_note = notes.get(&connection.from_id)?;
+    let end_note = notes.get(&connection.to_id)?;
 
-// use connection from and to id's for the argument
+    let offset = map_state.connections_state.parallel_offset(connection);
+    Some(connection_ends(connection, start_note, end_note, offset))
+}
 
+/// Draws the connection point character at `point`, on the specified side of a note.
+/// `is_editing`: true when drawing connection being created/edited
 pub fn draw_connecting_character(
-    note: &Note,
+    point: Point,
     note_id: usize,
     side: Side,
     is_editing: bool,
@@ -218,16 +360,13 @@ pub fn draw_connecting_character(
     frame: &mut Frame,
     map_state: &MapState,
 ) {
-    // Visual style varies based on mode: thick for Visual, double for Edit, plain otherwise
-    let connection_charset = match map_state.notes_state.selected_note_id() {
-        Some(selected_note_id) if selected_note_id == note_id || is_editing => {
-            match map_state.mode {
-                Mode::Normal => unreachable!("Bug: cannot be in Normal Mode with a selected note"),
-                Mode::Visual | Mode::VisualMove | Mode::VisualConnect => &THICK_JUNCTIONS,
-                Mode::Edit | Mode::EditNormal | Mode::EditInsert => &DOUBLE_JUNCTIONS,
-                Mode::Delete => &PLAIN_JUNCTIONS,
-            }
-        }
+    // Merged into the note's border: its line style along the edge, the connection's
+    // line style out of it
+    let connection_charset = match (note_border_type(map_state, note_id), is_editing) {
+        (BorderType::Thick, true) => &THICK_JUNCTIONS,
+        (BorderType::Thick, false) => &THICK_BORDER_JUNCTIONS,
+        (BorderType::Double, _) => &DOUBLE_BORDER_JUNCTIONS,
+        (_, true) => &THICK_LINE_JUNCTIONS,
         _ => &PLAIN_JUNCTIONS,
     };
 
@@ -238,18 +377,11 @@ pub fn draw_connecting_character(
         Side::Right => connection_charset[3],
     };
 
-    let p = note.get_connection_point(side);
-    let (p_x, p_y) = map_state
-        .viewport
-        .to_screen_coords(p.0 as isize, p.1 as isize);
+    let p = map_state.viewport.to_screen(point.into());
 
-    if p_x >= 0
-        && p_x < frame.area().width as isize
-        && p_y >= 0
-        && p_y < frame.area().height as isize
+    if let Some(position) = p.cell_in(frame.area())
+        && let Some(cell) = frame.buffer_mut().cell_mut(position)
     {
-        if let Some(cell) = frame.buffer_mut().cell_mut((p_x as u16, p_y as u16)) {
-            cell.set_symbol(connection_point_character).set_fg(color);
-        }
+        cell.set_symbol(connection_point_character).set_fg(color);
     }
 }
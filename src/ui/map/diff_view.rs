@@ -0,0 +1,67 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::Clear,
+};
+
+use crate::{
+    states::map::DiffView,
+    utils::{Locale, NoteChange, tr, tr_args},
+};
+
+/// Renders the top row of a diff view: what the highlighting colors stand for and how
+/// many notes and connections changed.
+pub fn render_diff_view_header(frame: &mut Frame, diff_view: &DiffView, locale: Locale) {
+    let Some(changes) = &diff_view.map_state.ui_state.map_changes else {
+        return;
+    };
+
+    let count = |key: &'static str, matches: fn(&NoteChange) -> bool| {
+        tr_args(
+            locale,
+            key,
+            &[("count", &changes.count(matches).to_string())],
+        )
+    };
+    let spans = vec![
+        Span::styled(
+            tr_args(locale, "diff_view.title", &[("name", &diff_view.name)]),
+            Style::new().fg(Color::Black).bg(Color::Cyan),
+        ),
+        Span::from(" "),
+        Span::from(count("diff_view.added", |change| {
+            *change == NoteChange::Added
+        }))
+        .fg(Color::Green),
+        Span::from(count("diff_view.removed", |change| {
+            *change == NoteChange::Removed
+        }))
+        .fg(Color::Red),
+        Span::from(count("diff_view.edited", |change| {
+            matches!(change, NoteChange::Modified { content: true, .. })
+        }))
+        .fg(Color::Yellow),
+        Span::from(count("diff_view.moved", |change| {
+            matches!(change, NoteChange::Modified { content: false, .. })
+        }))
+        .fg(Color::Magenta),
+        Span::from(tr_args(
+            locale,
+            "diff_view.connections",
+            &[
+                ("added", &changes.connections_added.len().to_string()),
+                ("removed", &changes.connections_removed.len().to_string()),
+            ],
+        )),
+        Span::from(tr(locale, "diff_view.keys")).fg(Color::DarkGray),
+    ];
+
+    let header_area = Rect {
+        height: 1.min(frame.area().height),
+        ..frame.area()
+    };
+    frame.render_widget(Clear, header_area);
+    frame.render_widget(Line::from(spans), header_area);
+}
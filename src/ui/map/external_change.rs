@@ -0,0 +1,194 @@
+use ratatui::{
+    Frame,
+    layout::Alignment,
+    style::{Color, Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+};
+
+use super::milestones::popup_area;
+use crate::{
+    states::map::{ExternalChange, Note},
+    utils::{
+        ConflictKind, Locale, MapMerge, MergeChoice, get_color_name_in_string, note_title, tr,
+        tr_args,
+    },
+};
+
+/// Most differences listed at once; the rest are counted
+const MAX_DIFF_LINES: usize = 12;
+
+/// Renders the prompt shown when the map file was changed by another program, with the
+/// differences to the open map when asked for.
+pub fn render_external_change(
+    frame: &mut Frame,
+    change: &ExternalChange,
+    has_unsaved_changes: bool,
+    locale: Locale,
+) {
+    if let Some(merge) = &change.merge {
+        render_merge_conflicts(frame, merge, change.selected_conflict, locale);
+        return;
+    }
+
+    let diff = &change.diff;
+
+    let mut lines = vec![Line::from(tr(locale, "prompt.external_change.message"))];
+    if has_unsaved_changes {
+        lines.push(Line::from(tr(locale, "prompt.external_change.unsaved")).fg(Color::Yellow));
+    }
+    lines.push(Line::from(""));
+
+    if change.show_diff {
+        let entries: Vec<Line> = diff
+            .added
+            .iter()
+            .map(|title| Line::from(format!("+ {title}")).fg(Color::Green))
+            .chain(
+                diff.removed
+                    .iter()
+                    .map(|title| Line::from(format!("- {title}")).fg(Color::Red)),
+            )
+            .chain(
+                diff.changed
+                    .iter()
+                    .map(|title| Line::from(format!("~ {title}")).fg(Color::Yellow)),
+            )
+            .collect();
+        let hidden = entries.len().saturating_sub(MAX_DIFF_LINES);
+
+        lines.extend(entries.into_iter().take(MAX_DIFF_LINES));
+        if hidden > 0 {
+            lines.push(
+                Line::from(tr_args(
+                    locale,
+                    "prompt.external_change.more",
+                    &[("count", &hidden.to_string())],
+                ))
+                .fg(Color::DarkGray),
+            );
+        }
+        if diff.connections_added > 0 || diff.connections_removed > 0 {
+            lines.push(Line::from(tr_args(
+                locale,
+                "prompt.external_change.connections",
+                &[
+                    ("added", &diff.connections_added.to_string()),
+                    ("removed", &diff.connections_removed.to_string()),
+                ],
+            )));
+        }
+    } else {
+        lines.push(Line::from(tr_args(
+            locale,
+            "prompt.external_change.summary",
+            &[
+                ("added", &diff.added.len().to_string()),
+                ("removed", &diff.removed.len().to_string()),
+                ("changed", &diff.changed.len().to_string()),
+            ],
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(tr(locale, "prompt.external_change.keys")).fg(Color::DarkGray));
+
+    let prompt_area = popup_area(frame, 70, lines.len() as u16 + 2);
+
+    frame.render_widget(Clear, prompt_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .block(Block::bordered().title(tr(locale, "prompt.external_change.title"))),
+        prompt_area,
+    );
+}
+
+/// Renders the conflicts of a merge with the version kept for each, and both versions
+/// of the highlighted one.
+fn render_merge_conflicts(frame: &mut Frame, merge: &MapMerge, selected: usize, locale: Locale) {
+    let mut lines = vec![
+        Line::from(tr_args(
+            locale,
+            "prompt.merge.message",
+            &[("count", &merge.conflicts.len().to_string())],
+        )),
+        Line::from(""),
+    ];
+
+    // Scrolls so the highlighted conflict stays visible
+    let first = selected.saturating_sub(MAX_DIFF_LINES - 1);
+    for (index, conflict) in merge
+        .conflicts
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(MAX_DIFF_LINES)
+    {
+        let title = conflict
+            .mine
+            .as_ref()
+            .or(conflict.theirs.as_ref())
+            .map(note_title)
+            .unwrap_or_default();
+        let kind = match conflict.kind {
+            ConflictKind::Content => tr(locale, "prompt.merge.kind.content"),
+            ConflictKind::Position => tr(locale, "prompt.merge.kind.position"),
+            ConflictKind::Color => tr(locale, "prompt.merge.kind.color"),
+            ConflictKind::DeletedMine => tr(locale, "prompt.merge.kind.deleted_mine"),
+            ConflictKind::DeletedTheirs => tr(locale, "prompt.merge.kind.deleted_theirs"),
+        };
+        let choice = match conflict.choice {
+            MergeChoice::Mine => tr(locale, "prompt.merge.choice.mine"),
+            MergeChoice::Theirs => tr(locale, "prompt.merge.choice.theirs"),
+        };
+
+        let style = if index == selected {
+            Style::new()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::REVERSED)
+        } else {
+            Style::new()
+        };
+        lines.push(Line::styled(
+            format!(" {title}: {kind}  [{choice}] "),
+            style,
+        ));
+    }
+
+    if let Some(conflict) = merge.conflicts.get(selected) {
+        let value = |note: &Option<Note>| match note {
+            None => tr(locale, "prompt.merge.deleted").to_string(),
+            Some(note) => match conflict.kind {
+                ConflictKind::Position => format!("{}, {}", note.x, note.y),
+                ConflictKind::Color => get_color_name_in_string(note.color),
+                _ => note_title(note),
+            },
+        };
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(tr_args(
+            locale,
+            "prompt.merge.mine",
+            &[("value", &value(&conflict.mine))],
+        )));
+        lines.push(Line::from(tr_args(
+            locale,
+            "prompt.merge.theirs",
+            &[("value", &value(&conflict.theirs))],
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(tr(locale, "prompt.merge.keys")).fg(Color::DarkGray));
+
+    let prompt_area = popup_area(frame, 80, lines.len() as u16 + 2);
+
+    frame.render_widget(Clear, prompt_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .block(Block::bordered().title(tr(locale, "prompt.merge.title"))),
+        prompt_area,
+    );
+}
@@ -0,0 +1,83 @@
+use ratatui::{
+    Frame,
+    layout::Alignment,
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::{
+    states::map::{LayerPanel, LayersState},
+    utils::{Locale, tr},
+};
+
+use super::milestones::popup_area;
+
+/// Renders the layer list with each layer's state; the active layer is marked with `>`.
+pub fn render_layer_panel(
+    frame: &mut Frame,
+    layers_state: &LayersState,
+    panel: &LayerPanel,
+    locale: Locale,
+) {
+    let visible_rows = 10;
+    let panel_area = popup_area(frame, 80, visible_rows as u16 + 4);
+
+    // Keep the highlighted layer in view
+    let first = panel.selected.saturating_sub(visible_rows - 1);
+    let mut lines: Vec<Line> = layers_state
+        .layers()
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(visible_rows)
+        .map(|(index, layer)| {
+            let marker = if index == layers_state.active {
+                "> "
+            } else {
+                "  "
+            };
+            let name = if layer.name.is_empty() && !panel.editing_name {
+                tr(locale, "prompt.layers.unnamed").to_string()
+            } else {
+                layer.name.clone()
+            };
+
+            let mut spans = vec![Span::from(marker), Span::from(name)];
+            if layer.hidden {
+                spans.push(
+                    Span::from(format!("  {}", tr(locale, "prompt.layers.hidden")))
+                        .fg(Color::DarkGray),
+                );
+            }
+            if layer.locked {
+                spans.push(
+                    Span::from(format!("  {}", tr(locale, "prompt.layers.locked"))).fg(Color::Red),
+                );
+            }
+
+            let line = Line::from(spans);
+            if index == panel.selected {
+                line.style(Style::new().add_modifier(Modifier::REVERSED))
+            } else {
+                line
+            }
+        })
+        .collect();
+    lines.resize(visible_rows, Line::from(""));
+    lines.push(Line::from(""));
+    let keys = if panel.editing_name {
+        "prompt.layers.name_keys"
+    } else {
+        "prompt.layers.keys"
+    };
+    lines.push(Line::from(tr(locale, keys)).fg(Color::DarkGray));
+
+    frame.render_widget(Clear, panel_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .block(Block::bordered().title(tr(locale, "prompt.layers.title"))),
+        panel_area,
+    );
+}
@@ -0,0 +1,133 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::{
+    states::map::{MilestonePicker, MilestonesState},
+    utils::{Locale, tr, tr_args},
+};
+
+/// Centered popup area of the given size, clamped to the frame.
+pub(super) fn popup_area(frame: &Frame, width: u16, height: u16) -> Rect {
+    let area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .split(frame.area());
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(width),
+            Constraint::Fill(1),
+        ])
+        .split(area[1])[1]
+}
+
+/// Renders the prompt for a new milestone's name.
+pub fn render_milestone_prompt(frame: &mut Frame, name: &str, locale: Locale) {
+    let prompt_area = popup_area(frame, 60, 7);
+
+    let lines = vec![
+        Line::from(tr(locale, "prompt.milestone.label")),
+        Line::from(name.to_string()).fg(Color::Yellow),
+        Line::from(""),
+        Line::from(""),
+        Line::from(tr(locale, "prompt.milestone.keys")).fg(Color::DarkGray),
+    ];
+
+    frame.render_widget(Clear, prompt_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .block(Block::bordered().title(tr(locale, "prompt.milestone.title"))),
+        prompt_area,
+    );
+}
+
+/// Renders the list of milestones to open, newest first.
+pub fn render_milestone_picker(frame: &mut Frame, picker: &MilestonePicker, locale: Locale) {
+    let visible_rows = 10;
+    let picker_area = popup_area(frame, 60, visible_rows as u16 + 4);
+
+    let mut lines: Vec<Line> = if picker.milestones.is_empty() {
+        vec![Line::from(tr(locale, "prompt.milestone_picker.empty")).fg(Color::DarkGray)]
+    } else {
+        // Keep the highlighted milestone in view
+        let first = picker.selected.saturating_sub(visible_rows - 1);
+        picker
+            .milestones
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(visible_rows)
+            .map(|(index, milestone)| {
+                let line = Line::from(vec![
+                    Span::from(milestone.name.clone()),
+                    Span::from(format!(
+                        "  {}",
+                        milestone.created_at.format("%Y-%m-%d %H:%M")
+                    ))
+                    .fg(Color::DarkGray),
+                ]);
+                if index == picker.selected {
+                    line.style(Style::new().add_modifier(Modifier::REVERSED))
+                } else {
+                    line
+                }
+            })
+            .collect()
+    };
+    lines.resize(visible_rows, Line::from(""));
+    lines.push(Line::from(""));
+    lines.push(Line::from(tr(locale, "prompt.milestone_picker.keys")).fg(Color::DarkGray));
+
+    frame.render_widget(Clear, picker_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .block(Block::bordered().title(tr(locale, "prompt.milestone_picker.title"))),
+        picker_area,
+    );
+}
+
+/// Renders the tab line shown on the top row while a milestone is open.
+pub fn render_milestone_tabs(frame: &mut Frame, milestones: &MilestonesState, locale: Locale) {
+    let Some(open) = &milestones.open else {
+        return;
+    };
+
+    let active = Style::new().fg(Color::Black).bg(Color::Cyan);
+    let inactive = Style::new().fg(Color::DarkGray);
+    let (map_style, milestone_style) = if milestones.showing_milestone {
+        (inactive, active)
+    } else {
+        (active, inactive)
+    };
+
+    let mut spans = vec![
+        Span::styled(tr(locale, "tabs.current_map"), map_style),
+        Span::from(" "),
+        Span::styled(
+            tr_args(locale, "tabs.milestone", &[("name", &open.name)]),
+            milestone_style,
+        ),
+    ];
+    if milestones.showing_milestone {
+        spans.push(Span::from(tr(locale, "tabs.keys")).fg(Color::DarkGray));
+    }
+
+    let tabs_area = Rect {
+        height: 1.min(frame.area().height),
+        ..frame.area()
+    };
+    frame.render_widget(Clear, tabs_area);
+    frame.render_widget(Line::from(spans), tabs_area);
+}
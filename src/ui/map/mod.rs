@@ -1,11 +1,43 @@
 pub mod bar;
+pub mod box_select;
+pub mod command;
+pub mod connection_table;
 pub mod connections;
+pub mod diff_view;
+pub mod external_change;
 pub mod help;
+pub mod layers;
+pub mod milestones;
 pub mod notes;
+pub mod notifications;
+pub mod passphrase;
+pub mod regions;
 pub mod screen;
+pub mod search;
+pub mod snapshots;
+pub mod stats;
+pub mod title;
+pub mod trash;
+pub mod workspace;
 
 pub use bar::*;
+pub use box_select::*;
+pub use command::*;
+pub use connection_table::*;
 pub use connections::*;
+pub use diff_view::*;
+pub use external_change::*;
 pub use help::*;
+pub use layers::*;
+pub use milestones::*;
 pub use notes::*;
+pub use notifications::*;
+pub use passphrase::*;
+pub use regions::*;
 pub use screen::*;
+pub use search::*;
+pub use snapshots::*;
+pub use stats::*;
+pub use title::*;
+pub use trash::*;
+pub use workspace::*;
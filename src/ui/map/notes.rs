@@ -1,8 +1,11 @@
+use std::ops::Range;
+
 use ratatui::{
     Frame,
     layout::Position,
     prelude::Rect,
-    style::Color,
+    style::{Color, Style, Stylize},
+    text::{Line, Span, Text},
     widgets::{Block, BorderType, Borders, Clear, Paragraph},
 };
 use unicode_width::UnicodeWidthStr;
@@ -12,7 +15,11 @@ use crate::{
         MapState,
         map::{Mode, SignedRect},
     },
-    ui::draw_connecting_character,
+    ui::{BAR_HEIGHT, draw_connecting_character, junction_points},
+    utils::{
+        ENCRYPTED_LABEL, MarkdownStyle, NoteChange, checklist_progress, connection_point,
+        hyperedge_target_side, markdown_spans, tr,
+    },
 };
 
 /// Renders notes with proper clipping, scrolling, and z-ordering.
@@ -21,17 +28,37 @@ use crate::{
 /// visibility handling. Connection points are drawn after each note to prevent
 /// visual layering issues.
 pub fn render_notes(frame: &mut Frame, map_state: &mut MapState) {
-    for &note_id in map_state.notes_state.render_order() {
+    // The note being edited is drawn on top so its text and cursor stay visible
+    let editing = map_state.notes_state.selected_note_id().filter(|_| {
+        matches!(
+            map_state.mode,
+            Mode::Edit | Mode::EditNormal | Mode::EditInsert
+        )
+    });
+    let render_order = map_state
+        .notes_state
+        .render_order()
+        .iter()
+        .copied()
+        .filter(|&id| Some(id) != editing)
+        .chain(editing);
+
+    // Kept for the next frame, so the text only scrolls once the cursor leaves the box
+    let mut edit_scroll = None;
+
+    for note_id in render_order {
+        // Notes on hidden layers aren't drawn
+        if map_state.note_is_hidden(note_id) {
+            continue;
+        }
         if let Some(note) = map_state.notes_state.notes().get(&note_id) {
             let (note_width, note_height) = note.get_dimensions();
 
             // Convert canvas coordinates to screen space (can be negative if off-screen)
-            let (p_x, p_y) = map_state
-                .viewport
-                .to_screen_coords(note.x as isize, note.y as isize);
+            let origin = map_state.viewport.to_screen((note.x, note.y).into());
             let note_rect = SignedRect {
-                x: p_x,
-                y: p_y,
+                x: origin.x,
+                y: origin.y,
                 width: note_width as isize,
                 height: note_height as isize,
             };
@@ -46,7 +73,7 @@ pub fn render_notes(frame: &mut Frame, map_state: &mut MapState) {
             // Skip notes completely outside the viewport
             if let Some(visible_part) = note_rect.intersection(&frame_rect) {
                 // Coordinates are guaranteed non-negative after clipping to frame
-                let note_area = Rect::new(
+                let mut note_area = Rect::new(
                     visible_part.x as u16,
                     visible_part.y as u16,
                     visible_part.width as u16,
@@ -55,7 +82,7 @@ pub fn render_notes(frame: &mut Frame, map_state: &mut MapState) {
 
                 // Scroll text content to match clipped portion
                 let horizontal_scroll = (visible_part.x - note_rect.x) as u16;
-                let vertical_scroll = (visible_part.y - note_rect.y) as u16;
+                let mut vertical_scroll = (visible_part.y - note_rect.y) as u16;
 
                 // Show borders only for sides that are fully visible (not clipped)
                 let mut borders = Borders::NONE;
@@ -72,36 +99,170 @@ pub fn render_notes(frame: &mut Frame, map_state: &mut MapState) {
                     borders |= Borders::BOTTOM;
                 }
 
+                // The note being edited keeps its box above the bar with both borders
+                // drawn, its text scrolling in there to keep the cursor's line shown
+                let map_bottom = frame.area().height.saturating_sub(BAR_HEIGHT);
+                let box_height = (note_area.y + note_area.height)
+                    .min(map_bottom)
+                    .saturating_sub(note_area.y);
+                // Lines of the text above and below the box, while it's scrolled
+                let mut hidden_lines = None;
+                if editing == Some(note_id) && box_height >= 3 {
+                    note_area.height = box_height;
+                    borders |= Borders::TOP | Borders::BOTTOM;
+
+                    let rows = box_height as usize - 2;
+                    let margin = map_state.settings.scroll_margin;
+                    let first = map_state.notes_state.scroll_to_cursor(rows, margin);
+                    let lines = 1 + note.content.matches('\n').count();
+                    vertical_scroll = first as u16;
+                    edit_scroll = Some(first);
+                    hidden_lines = Some((first, lines.saturating_sub(first + rows)));
+                }
+
                 let border_color = match map_state.notes_state.selected_note_id() {
                     Some(selected_note_id) if selected_note_id == note_id => match map_state.mode {
-                        Mode::Normal => {
-                            unreachable!("Bug: cannot be in Normal Mode with a selected note")
+                        Mode::Normal | Mode::Region => {
+                            unreachable!(
+                                "Bug: cannot be in Normal/Region Mode with a selected note"
+                            )
                         }
-                        Mode::Visual | Mode::VisualMove | Mode::VisualConnect => Color::Yellow,
+                        Mode::Visual
+                        | Mode::VisualMove
+                        | Mode::VisualConnect
+                        | Mode::VisualWaypoint
+                        | Mode::VisualBox => Color::Yellow,
                         Mode::Edit | Mode::EditNormal | Mode::EditInsert => Color::Blue,
                         Mode::Delete => Color::Red,
                     },
-                    _ => note.color,
-                };
-
-                let border_type = match map_state.notes_state.selected_note_id() {
-                    Some(selected_note_id) if selected_note_id == note_id => match map_state.mode {
-                        Mode::Normal => {
-                            unreachable!("Bug: cannot be in Normal Mode with a selected note")
+                    // Rest of a multi-selection
+                    _ if map_state.notes_state.is_in_multi_selection(note_id) => {
+                        match map_state.mode {
+                            Mode::Delete => Color::Red,
+                            _ => Color::Yellow,
                         }
-                        Mode::Visual | Mode::VisualMove | Mode::VisualConnect => BorderType::Thick,
-                        Mode::Edit | Mode::EditNormal | Mode::EditInsert => BorderType::Double,
-                        Mode::Delete => BorderType::Rounded,
+                    }
+                    _ if map_state.ui_state.highlighted_path.contains(&note_id) => Color::Cyan,
+                    // Differences shown by a diff view
+                    _ => match map_state
+                        .ui_state
+                        .map_changes
+                        .as_ref()
+                        .and_then(|changes| changes.notes.get(&note_id))
+                    {
+                        Some(change) => note_change_color(change),
+                        None => note.color,
                     },
-                    _ => BorderType::Plain,
                 };
 
-                let block = Block::default()
+                // Dimmed when filtered out, unless selected
+                let filtered_out = map_state
+                    .ui_state
+                    .status_filter
+                    .is_some_and(|status| note.status != Some(status))
+                    && map_state.notes_state.selected_note_id() != Some(note_id)
+                    && !map_state.notes_state.is_in_multi_selection(note_id);
+                let border_color = if filtered_out {
+                    Color::DarkGray
+                } else {
+                    border_color
+                };
+
+                let mut block = Block::default()
                     .borders(borders)
                     .border_style(border_color)
-                    .border_type(border_type);
+                    .border_type(note_border_type(map_state, note_id));
+
+                // Locked notes only have ciphertext; show a placeholder instead
+                let is_locked = map_state.note_is_locked(note_id);
+                // The note being edited shows the matches of the search within it instead
+                let search = match &map_state.ui_state.note_search {
+                    Some(note_search) if editing == Some(note_id) => Some(&note_search.search),
+                    _ => map_state.ui_state.search.as_ref(),
+                };
+                let search_matches = match search {
+                    Some(search) if !is_locked => search.matches_in(&note.content),
+                    _ => vec![],
+                };
+                let current = search
+                    .and_then(|search| search.current.as_ref())
+                    .filter(|current| current.note_id == note_id);
+                let text = if is_locked {
+                    Text::from(ENCRYPTED_LABEL).fg(Color::DarkGray)
+                } else if !search_matches.is_empty() {
+                    let current = current
+                        .filter(|current| !current.in_title)
+                        .map(|current| &current.range);
+                    highlight_matches(&note.content, &search_matches, current)
+                } else if filtered_out {
+                    Text::from(note.content.as_str()).fg(Color::DarkGray)
+                } else if editing == Some(note_id) {
+                    // The raw text while editing, so the cursor lines up with what's typed
+                    spell_checked_text(map_state, &note.content)
+                } else {
+                    markdown_text(&note.content)
+                };
+                // Titles aren't encrypted, so they show on locked notes too
+                if let Some(title) = &note.title {
+                    let title_matches = match &map_state.ui_state.search {
+                        Some(search) => search.matches_in(title),
+                        None => vec![],
+                    };
+                    let current = current
+                        .filter(|current| current.in_title)
+                        .map(|current| &current.range);
+                    let mut spans = vec![Span::raw(" ")];
+                    spans.extend(
+                        highlight_matches(title, &title_matches, current)
+                            .lines
+                            .into_iter()
+                            .flat_map(|line| line.spans),
+                    );
+                    spans.push(Span::raw(" "));
+                    block = block.title(Line::from(spans).bold());
+                }
+                if note.encrypted.is_some() && !is_locked {
+                    block = block.title(Line::from(" encrypted ").fg(Color::DarkGray));
+                }
+                if let Some(status) = note.status {
+                    let label = tr(map_state.settings.locale(), status.label_key());
+                    let color = if filtered_out {
+                        Color::DarkGray
+                    } else {
+                        status.color()
+                    };
+                    block = block.title_bottom(
+                        Line::from(format!(" {label} "))
+                            .fg(color)
+                            .bold()
+                            .right_aligned(),
+                    );
+                }
+                if note.locked {
+                    block = block.title_bottom(Line::from(" 🔒 ").fg(border_color));
+                }
+                if let Some((above, below)) = hidden_lines {
+                    if above > 0 {
+                        block = block.title(Line::from(format!(" ▲ {above} ")).centered());
+                    }
+                    if below > 0 {
+                        block = block.title_bottom(Line::from(format!(" ▼ {below} ")).centered());
+                    }
+                }
+                if !is_locked && let Some((done, total)) = checklist_progress(&note.content) {
+                    let color = if done == total {
+                        Color::Green
+                    } else {
+                        Color::DarkGray
+                    };
+                    block = block.title(
+                        Line::from(format!(" {done}/{total} "))
+                            .fg(color)
+                            .right_aligned(),
+                    );
+                }
 
-                let text_widget = Paragraph::new(note.content.as_str())
+                let text_widget = Paragraph::new(text)
                     .scroll((vertical_scroll, horizontal_scroll))
                     .block(block);
 
@@ -125,11 +286,9 @@ pub fn render_notes(frame: &mut Frame, map_state: &mut MapState) {
                             None => text_before_cursor.width(),
                         };
 
-                        // Only show cursor if it's within the scrolled visible area
-                        // (note_area.height - 2) accounts for top and bottom borders
-                        if cursor_y_relative >= vertical_scroll as usize
-                            && cursor_y_relative <= (note_area.height - 2) as usize
-                        {
+                        // Only shown in a box with room for the text, which is then
+                        // scrolled to the cursor's line
+                        if hidden_lines.is_some() {
                             let final_cursor_x = note_area.x as usize + 1 + cursor_x_relative
                                 - horizontal_scroll as usize;
 
@@ -152,21 +311,61 @@ pub fn render_notes(frame: &mut Frame, map_state: &mut MapState) {
                     .connections_state
                     .get_connections_for_note(note_id);
                 for connection in connection_vec {
+                    if map_state.connection_is_hidden(connection) {
+                        continue;
+                    }
+                    let Some((start, end)) = junction_points(connection, map_state) else {
+                        continue;
+                    };
+                    // Both ends of a connection from the note back to itself
                     if note_id == connection.from_id {
                         draw_connecting_character(
-                            note,
-                            connection.from_id,
+                            start,
+                            note_id,
                             connection.from_side,
                             false,
                             border_color,
                             frame,
                             map_state,
                         );
-                    } else {
+                    }
+                    if connection.to_id == note_id {
                         draw_connecting_character(
-                            note,
-                            connection.to_id.unwrap(),
-                            connection.to_side.unwrap(),
+                            end,
+                            note_id,
+                            connection.to_side,
+                            false,
+                            border_color,
+                            frame,
+                            map_state,
+                        );
+                    }
+                }
+
+                for hyperedge in map_state.hyperedges_state.hyperedges() {
+                    if !hyperedge.touches(note_id) || map_state.hyperedge_is_hidden(hyperedge) {
+                        continue;
+                    }
+                    let Some(source) = map_state.notes_state.notes().get(&hyperedge.from_id) else {
+                        continue;
+                    };
+                    if hyperedge.from_id == note_id {
+                        draw_connecting_character(
+                            connection_point(note, hyperedge.from_side),
+                            note_id,
+                            hyperedge.from_side,
+                            false,
+                            border_color,
+                            frame,
+                            map_state,
+                        );
+                    }
+                    if hyperedge.to_ids.contains(&note_id) {
+                        let side = hyperedge_target_side(source, hyperedge.from_side, note);
+                        draw_connecting_character(
+                            connection_point(note, side),
+                            note_id,
+                            side,
                             false,
                             border_color,
                             frame,
@@ -178,32 +377,187 @@ pub fn render_notes(frame: &mut Frame, map_state: &mut MapState) {
         }
     }
 
+    if let Some(edit_scroll) = edit_scroll {
+        map_state.notes_state.set_edit_scroll(edit_scroll);
+    }
+
     // Highlight connection endpoints while user is creating a new connection
-    if let Some(connection) = &map_state.connections_state.focused_connection {
-        if let Some(start_note) = map_state.notes_state.notes().get(&connection.from_id) {
+    if let Some(focused) = &map_state.connections_state.focused_connection {
+        let ends = match focused.completed() {
+            Some(connection) => {
+                junction_points(&connection, map_state).map(|(start, end)| (start, Some(end)))
+            }
+            // Only its start until it has an end
+            None => map_state
+                .notes_state
+                .notes()
+                .get(&focused.from_id)
+                .map(|note| (connection_point(note, focused.from_side), None)),
+        };
+        if let Some((start, end)) = ends {
             draw_connecting_character(
-                start_note,
-                connection.from_id,
-                connection.from_side,
+                start,
+                focused.from_id,
+                focused.from_side,
                 true,
                 Color::Yellow,
                 frame,
                 map_state,
             );
 
-            if let Some(end_note_id) = connection.to_id {
-                if let Some(end_note) = map_state.notes_state.notes().get(&end_note_id) {
-                    draw_connecting_character(
-                        end_note,
-                        end_note_id,
-                        connection.to_side.unwrap(),
-                        true,
-                        Color::Yellow,
-                        frame,
-                        map_state,
-                    );
-                }
+            if let (Some(end), Some((end_note_id, to_side))) = (end, focused.to) {
+                draw_connecting_character(
+                    end,
+                    end_note_id,
+                    to_side,
+                    true,
+                    Color::Yellow,
+                    frame,
+                    map_state,
+                );
             }
         }
     }
 }
+
+/// Border the note is drawn with: thick while selected in Visual mode, double while
+/// edited, rounded while its deletion is confirmed and plain otherwise.
+pub fn note_border_type(map_state: &MapState, note_id: usize) -> BorderType {
+    match map_state.notes_state.selected_note_id() {
+        Some(selected_note_id) if selected_note_id == note_id => match map_state.mode {
+            Mode::Normal | Mode::Region => {
+                unreachable!("Bug: cannot be in Normal/Region Mode with a selected note")
+            }
+            Mode::Visual
+            | Mode::VisualMove
+            | Mode::VisualConnect
+            | Mode::VisualWaypoint
+            | Mode::VisualBox => BorderType::Thick,
+            Mode::Edit | Mode::EditNormal | Mode::EditInsert => BorderType::Double,
+            Mode::Delete => BorderType::Rounded,
+        },
+        _ => BorderType::Plain,
+    }
+}
+
+/// Note text with the search matches highlighted, the current match in a different color.
+fn highlight_matches<'a>(
+    content: &'a str,
+    matches: &[Range<usize>],
+    current: Option<&Range<usize>>,
+) -> Text<'a> {
+    let mut lines = vec![];
+    let mut line_start = 0;
+
+    for line in content.split('\n') {
+        let line_end = line_start + line.len();
+        let mut spans = vec![];
+        let mut pos = line_start;
+
+        // A match spanning lines is highlighted on each of them
+        for range in matches
+            .iter()
+            .filter(|range| range.start < line_end && range.end > line_start)
+        {
+            let start = range.start.max(line_start);
+            let end = range.end.min(line_end);
+            if start > pos {
+                spans.push(Span::raw(&content[pos..start]));
+            }
+            let background = if current == Some(range) {
+                Color::LightRed
+            } else {
+                Color::Yellow
+            };
+            spans.push(Span::styled(
+                &content[start..end],
+                Style::new().fg(Color::Black).bg(background),
+            ));
+            pos = end;
+        }
+        if pos < line_end {
+            spans.push(Span::raw(&content[pos..line_end]));
+        }
+
+        lines.push(Line::from(spans));
+        line_start = line_end + 1;
+    }
+
+    Text::from(lines)
+}
+
+/// Note text with its Markdown styled: headings bold, code cyan, list markers dimmed
+/// and links underlined. The syntax itself stays in the text.
+fn markdown_text(content: &str) -> Text<'_> {
+    let styles: Vec<(Range<usize>, Style)> = markdown_spans(content)
+        .into_iter()
+        .map(|(range, style)| {
+            let style = match style {
+                MarkdownStyle::Heading => Style::new().bold(),
+                MarkdownStyle::Code => Style::new().fg(Color::Cyan),
+                MarkdownStyle::ListMarker => Style::new().fg(Color::DarkGray),
+                MarkdownStyle::Link => Style::new().underlined(),
+            };
+            (range, style)
+        })
+        .collect();
+    styled_text(content, &styles)
+}
+
+/// Text of the note being edited with its misspelled words underlined in red, except
+/// the word being typed at the cursor.
+fn spell_checked_text<'a>(map_state: &MapState, content: &'a str) -> Text<'a> {
+    let Some(dictionary) = map_state.spell_dictionary() else {
+        return Text::from(content);
+    };
+    let cursor = map_state.notes_state.cursor_pos();
+    let typing = matches!(map_state.mode, Mode::Edit | Mode::EditInsert);
+    let styles: Vec<(Range<usize>, Style)> = dictionary
+        .misspelled(content)
+        .into_iter()
+        .filter(|range| !(typing && range.end == cursor))
+        .map(|range| (range, Style::new().underlined().underline_color(Color::Red)))
+        .collect();
+    styled_text(content, &styles)
+}
+
+/// Text with the byte ranges in their styles; the ranges are in order, don't overlap and
+/// don't span lines.
+fn styled_text<'a>(content: &'a str, styles: &[(Range<usize>, Style)]) -> Text<'a> {
+    let mut styles = styles.iter().peekable();
+    let mut lines = vec![];
+    let mut line_start = 0;
+
+    for line in content.split('\n') {
+        let line_end = line_start + line.len();
+        let mut spans = vec![];
+        let mut pos = line_start;
+
+        while let Some((range, style)) = styles.next_if(|(range, _)| range.start < line_end) {
+            if range.start > pos {
+                spans.push(Span::raw(&content[pos..range.start]));
+            }
+            spans.push(Span::styled(&content[range.clone()], *style));
+            pos = range.end;
+        }
+        if pos < line_end {
+            spans.push(Span::raw(&content[pos..line_end]));
+        }
+
+        lines.push(Line::from(spans));
+        line_start = line_end + 1;
+    }
+
+    Text::from(lines)
+}
+
+/// Border color of a note in a diff view: added green, removed red, edited yellow, and
+/// only moved or recolored magenta.
+pub fn note_change_color(change: &NoteChange) -> Color {
+    match change {
+        NoteChange::Added => Color::Green,
+        NoteChange::Removed => Color::Red,
+        NoteChange::Modified { content: true, .. } => Color::Yellow,
+        NoteChange::Modified { .. } => Color::Magenta,
+    }
+}
@@ -0,0 +1,135 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::{
+    states::map::{Notification, NotificationQueue},
+    ui::BAR_HEIGHT,
+    utils::{Locale, tr_args},
+};
+
+/// Renders the shown notifications as toasts stacked up from above the bar's right end,
+/// the newest at the bottom. Toasts that don't fit on the screen are left out.
+pub fn render_notifications(frame: &mut Frame, notifications: &NotificationQueue, locale: Locale) {
+    let area = frame.area();
+    let mut bottom = area.height.saturating_sub(BAR_HEIGHT);
+
+    for toast in notifications.iter().rev() {
+        let (message, color) = notification_message(&toast.notification, locale);
+        if bottom < 3 {
+            break;
+        }
+        let width = (message.chars().count() as u16 + 4).min(area.width);
+
+        let toast_area = Rect {
+            x: area.width.saturating_sub(width + 2),
+            y: bottom - 3,
+            width,
+            height: 3,
+        };
+        frame.render_widget(Clear, toast_area);
+        frame.render_widget(
+            Paragraph::new(Line::from(message))
+                .style(Style::new().fg(color))
+                .block(Block::bordered().border_style(color)),
+            toast_area,
+        );
+        bottom -= 3;
+    }
+}
+
+/// The text of a notification and the color it's shown in.
+fn notification_message(notification: &Notification, locale: Locale) -> (String, Color) {
+    let (message, color) = match notification {
+        Notification::SaveSuccess => ("notification.save_success", Color::Green),
+        Notification::SaveFail => ("notification.save_fail", Color::Red),
+        Notification::BackupSuccess => ("notification.backup_success", Color::Green),
+        Notification::BackupFail => ("notification.backup_fail", Color::Red),
+        Notification::BackupRecordFail => ("notification.backup_record_fail", Color::Red),
+        Notification::PasteSuccess => ("notification.paste_success", Color::Green),
+        Notification::PasteFail => ("notification.paste_fail", Color::Red),
+        Notification::PasteInvalidLine(_) => ("notification.paste_invalid_line", Color::Red),
+        Notification::ClipboardUnavailable => ("notification.clipboard_unavailable", Color::Red),
+        Notification::MilestoneSuccess => ("notification.milestone_success", Color::Green),
+        Notification::MilestoneFail => ("notification.milestone_fail", Color::Red),
+        Notification::NoPath => ("notification.no_path", Color::Red),
+        Notification::NoOrphans => ("notification.no_orphans", Color::Green),
+        Notification::MapLargerThanScreen => ("notification.map_larger_than_screen", Color::Yellow),
+        Notification::ExportSuccess => ("notification.export_success", Color::Green),
+        Notification::ExportFail => ("notification.export_fail", Color::Red),
+        Notification::ExportNothingSelected => ("notification.nothing_selected", Color::Red),
+        Notification::ImportSuccess => ("notification.import_success", Color::Green),
+        Notification::ImportFail => ("notification.import_fail", Color::Red),
+        Notification::NotesImported(_) => ("notification.notes_imported", Color::Green),
+        Notification::NoteListFail => ("notification.note_list_fail", Color::Red),
+        Notification::NoteListInvalidRow(_) => ("notification.note_list_invalid_row", Color::Red),
+        Notification::Filtered(_) => ("notification.filtered", Color::Green),
+        Notification::FilterCleared => ("notification.filter_cleared", Color::Green),
+        Notification::NoteLocked => ("notification.note_locked", Color::Red),
+        Notification::UnknownCommand => ("notification.unknown_command", Color::Red),
+        Notification::MapReloaded => ("notification.map_reloaded", Color::Green),
+        Notification::MergeSuccess => ("notification.merge_success", Color::Green),
+        Notification::MapEncrypted => ("notification.map_encrypted", Color::Green),
+        Notification::MapDecrypted => ("notification.map_decrypted", Color::Green),
+        Notification::PassphraseMismatch => ("notification.passphrase_mismatch", Color::Red),
+        Notification::MilestoneEncrypted => ("notification.milestone_encrypted", Color::Red),
+        Notification::BackupRestored => ("notification.backup_restored", Color::Green),
+        Notification::SnapshotSuccess => ("notification.snapshot_success", Color::Green),
+        Notification::SnapshotFail => ("notification.snapshot_fail", Color::Red),
+        Notification::SnapshotLoaded => ("notification.snapshot_loaded", Color::Green),
+        Notification::DiffFail => ("notification.diff_fail", Color::Red),
+        Notification::NoDifferences => ("notification.no_differences", Color::Green),
+        Notification::Replaced(_) => ("notification.replaced", Color::Green),
+        Notification::PatternNotFound => ("notification.pattern_not_found", Color::Red),
+        Notification::InvalidPattern => ("notification.invalid_pattern", Color::Red),
+        Notification::Undone => ("notification.undone", Color::Green),
+        Notification::NothingToUndo => ("notification.nothing_to_undo", Color::Red),
+        Notification::LayerLocked => ("notification.layer_locked", Color::Red),
+        Notification::DuplicateConnection => ("notification.duplicate_connection", Color::Red),
+        Notification::NoWorkspace => ("notification.no_workspace", Color::Red),
+        Notification::MapSwitchFail => ("notification.map_switch_fail", Color::Red),
+        Notification::ChecksumMismatch => ("notification.checksum_mismatch", Color::Yellow),
+        Notification::MapRepaired(_) => ("notification.map_repaired", Color::Yellow),
+        Notification::NoteRestored(_) => ("notification.note_restored", Color::Green),
+        Notification::TrashEmptied => ("notification.trash_emptied", Color::Green),
+        Notification::ConnectionsDeleted(_) => ("notification.connections_deleted", Color::Green),
+        Notification::FannedOut(_) => ("notification.fanned_out", Color::Green),
+        Notification::FanOutsRemoved(_) => ("notification.fan_outs_removed", Color::Green),
+        Notification::NoFanOutTargets => ("notification.no_fan_out_targets", Color::Red),
+        Notification::SpellCheckOff => ("notification.spell_check_off", Color::Red),
+        Notification::DictionaryMissing => ("notification.dictionary_missing", Color::Red),
+        Notification::SpellingSuggestion(_) => ("notification.spelling_suggestion", Color::Green),
+        Notification::SpellingRestored => ("notification.spelling_restored", Color::Green),
+        Notification::NoSpellingSuggestions => ("notification.no_spelling_suggestions", Color::Red),
+        Notification::PluginRan => ("notification.plugin_ran", Color::Green),
+        Notification::PluginFunctionMissing => ("notification.plugin_function_missing", Color::Red),
+        Notification::PluginFailed => ("notification.plugin_failed", Color::Red),
+        Notification::UnknownUserCommand => ("notification.unknown_user_command", Color::Red),
+        Notification::UserCommandNoNote => ("notification.user_command_no_note", Color::Red),
+        Notification::UserCommandDone => ("notification.user_command_done", Color::Green),
+        Notification::UserCommandFailed => ("notification.user_command_failed", Color::Red),
+        Notification::CrashChangesRestored => ("notification.crash_changes_restored", Color::Green),
+        Notification::CrashChangesRestoreFail => {
+            ("notification.crash_changes_restore_fail", Color::Red)
+        }
+    };
+    let count = match notification {
+        Notification::Replaced(count)
+        | Notification::NoteRestored(count)
+        | Notification::ConnectionsDeleted(count)
+        | Notification::FannedOut(count)
+        | Notification::FanOutsRemoved(count)
+        | Notification::MapRepaired(count)
+        | Notification::SpellingSuggestion(count)
+        | Notification::NotesImported(count)
+        | Notification::NoteListInvalidRow(count)
+        | Notification::PasteInvalidLine(count)
+        | Notification::Filtered(count) => count.to_string(),
+        _ => String::new(),
+    };
+    (tr_args(locale, message, &[("count", &count)]), color)
+}
@@ -0,0 +1,76 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::{
+    states::map::{PassphrasePrompt, PassphrasePurpose},
+    utils::{CryptoError, Locale, tr},
+};
+
+/// Renders the passphrase prompt used for per-note and map file encryption.
+/// The typed passphrase is masked.
+pub fn render_passphrase_prompt(frame: &mut Frame, prompt: &PassphrasePrompt, locale: Locale) {
+    let prompt_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(7),
+            Constraint::Fill(1),
+        ])
+        .split(frame.area());
+    let prompt_area = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(60),
+            Constraint::Fill(1),
+        ])
+        .split(prompt_area[1])[1];
+
+    let title = match prompt.purpose {
+        PassphrasePurpose::EncryptNote => tr(locale, "prompt.passphrase.encrypt_title"),
+        PassphrasePurpose::UnlockNote => tr(locale, "prompt.passphrase.unlock_title"),
+        PassphrasePurpose::EncryptMap => tr(locale, "prompt.passphrase.encrypt_map_title"),
+        PassphrasePurpose::ConfirmMapPassphrase => {
+            tr(locale, "prompt.passphrase.confirm_map_title")
+        }
+    };
+    let label = match prompt.purpose {
+        PassphrasePurpose::EncryptNote | PassphrasePurpose::UnlockNote => {
+            tr(locale, "prompt.passphrase.label")
+        }
+        PassphrasePurpose::EncryptMap | PassphrasePurpose::ConfirmMapPassphrase => {
+            tr(locale, "prompt.passphrase.map_label")
+        }
+    };
+
+    let error_line = match &prompt.error {
+        Some(CryptoError::WrongPassphrase) => {
+            Line::from(tr(locale, "prompt.passphrase.wrong")).fg(Color::Red)
+        }
+        Some(CryptoError::Decode) => {
+            Line::from(tr(locale, "prompt.passphrase.damaged")).fg(Color::Red)
+        }
+        None => Line::from(""),
+    };
+
+    let lines = vec![
+        Line::from(label),
+        Line::from("*".repeat(prompt.input.chars().count())).fg(Color::Yellow),
+        error_line,
+        Line::from(""),
+        Line::from(tr(locale, "prompt.passphrase.keys")).fg(Color::DarkGray),
+    ];
+
+    frame.render_widget(Clear, prompt_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .block(Block::bordered().title(title)),
+        prompt_area,
+    );
+}
@@ -0,0 +1,115 @@
+use ratatui::{
+    Frame,
+    layout::Position,
+    prelude::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Borders},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::states::{
+    MapState,
+    map::{Mode, ScreenPos, SignedRect},
+};
+
+/// Character used for the faint dotted fill of a region's interior
+const REGION_PATTERN: &str = "·";
+
+/// Renders background regions beneath connections and notes.
+///
+/// Regions are drawn dimmed with a sparse dot pattern so they read as background.
+/// The region being edited in Region mode gets a highlighted border.
+pub fn render_regions(frame: &mut Frame, map_state: &MapState) {
+    let frame_rect = SignedRect {
+        x: 0,
+        y: 0,
+        width: frame.area().width as isize,
+        height: frame.area().height as isize,
+    };
+
+    for (index, region) in map_state.regions_state.regions().iter().enumerate() {
+        let origin = map_state.viewport.to_screen((region.x, region.y).into());
+        let region_rect = SignedRect {
+            x: origin.x,
+            y: origin.y,
+            width: region.width as isize,
+            height: region.height as isize,
+        };
+
+        let Some(visible_part) = region_rect.intersection(&frame_rect) else {
+            continue;
+        };
+        let region_area = Rect::new(
+            visible_part.x as u16,
+            visible_part.y as u16,
+            visible_part.width as u16,
+            visible_part.height as u16,
+        );
+
+        let is_selected = map_state.mode == Mode::Region
+            && map_state.regions_state.selected_index() == Some(index);
+
+        // Pattern is anchored to canvas coordinates so it doesn't shimmer while scrolling
+        let pattern_style = Style::new().fg(region.color).add_modifier(Modifier::DIM);
+        for screen_y in region_area.top()..region_area.bottom() {
+            for screen_x in region_area.left()..region_area.right() {
+                let canvas = map_state
+                    .viewport
+                    .to_canvas(ScreenPos::new(screen_x as isize, screen_y as isize));
+                if canvas.y.rem_euclid(2) == 0
+                    && canvas.x.rem_euclid(4) == 0
+                    && let Some(cell) = frame.buffer_mut().cell_mut((screen_x, screen_y))
+                {
+                    cell.set_symbol(REGION_PATTERN).set_style(pattern_style);
+                }
+            }
+        }
+
+        // Show borders only for sides that are fully visible (not clipped)
+        let mut borders = Borders::NONE;
+        if region_rect.x == visible_part.x {
+            borders |= Borders::LEFT;
+        }
+        if region_rect.x + region_rect.width == visible_part.x + visible_part.width {
+            borders |= Borders::RIGHT;
+        }
+        if region_rect.y == visible_part.y {
+            borders |= Borders::TOP;
+        }
+        if region_rect.y + region_rect.height == visible_part.y + visible_part.height {
+            borders |= Borders::BOTTOM;
+        }
+
+        // Groups get a double border to set them apart from plain regions
+        let (border_style, border_type) = match (is_selected, region.is_group()) {
+            (true, _) => (Style::new().fg(Color::Magenta), BorderType::Thick),
+            (false, true) => (pattern_style, BorderType::Double),
+            (false, false) => (pattern_style, BorderType::Rounded),
+        };
+
+        let mut block = Block::default()
+            .borders(borders)
+            .border_style(border_style)
+            .border_type(border_type);
+        if borders.contains(Borders::TOP) {
+            block = block.title(Line::styled(
+                format!(" {} ", region.label),
+                Style::new().fg(region.color).add_modifier(Modifier::BOLD),
+            ));
+        }
+
+        frame.render_widget(block, region_area);
+
+        // Cursor at the end of the label while it's being typed
+        if is_selected
+            && map_state.regions_state.editing_label
+            && borders.contains(Borders::TOP | Borders::LEFT)
+        {
+            let cursor_x = region_area.x as usize + 2 + region.label.width();
+            if cursor_x < region_area.right() as usize {
+                frame.set_cursor_position(Position::new(cursor_x as u16, region_area.y));
+            }
+        }
+    }
+}
@@ -1,23 +1,132 @@
 use ratatui::{Frame, widgets::Clear};
 
 use crate::{
+    graph::algorithms::graph_stats,
     states::MapState,
-    ui::{render_bar, render_connections, render_map_help_page, render_notes},
+    ui::{
+        render_bar, render_box_select, render_command_line, render_connection_table,
+        render_connections, render_dialog, render_diff_view_header, render_external_change,
+        render_graph_stats, render_layer_panel, render_map_help_page, render_milestone_picker,
+        render_milestone_prompt, render_milestone_tabs, render_notes, render_notifications,
+        render_passphrase_prompt, render_regions, render_search_line, render_size_guard,
+        render_snapshot_picker, render_title_prompt, render_trash_browser, render_workspace_picker,
+        render_workspace_search,
+    },
 };
 
 pub fn render_map(frame: &mut Frame, map_state: &mut MapState) {
+    if render_size_guard(frame) {
+        return;
+    }
     frame.render_widget(Clear, frame.area());
 
+    let locale = map_state.settings.locale();
+
     if let Some(page_number) = map_state.ui_state.help_screen {
-        render_map_help_page(frame, page_number);
+        let help_search = map_state.ui_state.help_search.as_deref();
+        render_map_help_page(frame, page_number, help_search, locale);
         return;
     }
 
+    let show_stats = map_state.ui_state.show_stats;
+    let last_orphan = map_state.ui_state.last_orphan;
+
+    // The milestone tab and the diff view show a read-only map in place of the live one
+    let shown_state = match (&mut map_state.diff_view, &mut map_state.milestones.open) {
+        (Some(diff_view), _) => diff_view.map_state.as_mut(),
+        (None, Some(open)) if map_state.milestones.showing_milestone => open.map_state.as_mut(),
+        _ => &mut *map_state,
+    };
+
     // Viewport needs current dimensions for calculations like centering new notes
-    map_state.viewport.screen_width = frame.area().width as usize;
-    map_state.viewport.screen_height = frame.area().height as usize;
+    shown_state.viewport.screen_width = frame.area().width as usize;
+    shown_state.viewport.screen_height = frame.area().height as usize;
+
+    render_regions(frame, shown_state); // Regions are the background layer
+    render_connections(frame, shown_state);
+    render_notes(frame, shown_state); // Notes drawn over connections
+    if let Some(box_select) = &shown_state.ui_state.box_select {
+        render_box_select(frame, shown_state, box_select);
+    }
+    render_bar(frame, shown_state, locale); // Bar drawn over everything
+
+    if show_stats {
+        render_graph_stats(
+            frame,
+            &graph_stats(&shown_state.graph()),
+            last_orphan,
+            locale,
+        );
+    }
 
-    render_connections(frame, map_state);
-    render_notes(frame, map_state); // Notes drawn over connections
-    render_bar(frame, map_state); // Bar drawn over everything
+    render_notifications(frame, &map_state.ui_state.notifications, locale);
+    render_milestone_tabs(frame, &map_state.milestones, locale);
+    if let Some(diff_view) = &map_state.diff_view {
+        render_diff_view_header(frame, diff_view, locale);
+    }
+
+    if let Some(command) = &map_state.ui_state.command_line {
+        render_command_line(frame, command);
+    }
+    if let Some(search) = &map_state.ui_state.search
+        && search.typing
+    {
+        render_search_line(frame, search);
+    }
+    if let Some(note_search) = &map_state.ui_state.note_search
+        && note_search.search.typing
+    {
+        render_search_line(frame, &note_search.search);
+    }
+    if let Some(prompt) = &map_state.ui_state.passphrase_prompt {
+        render_passphrase_prompt(frame, prompt, locale);
+    }
+    if let Some(name) = &map_state.milestones.name_prompt {
+        render_milestone_prompt(frame, name, locale);
+    }
+    if let Some(title) = &map_state.ui_state.title_prompt {
+        render_title_prompt(frame, title, locale);
+    }
+    if let Some(picker) = &map_state.milestones.picker {
+        render_milestone_picker(frame, picker, locale);
+    }
+    if let Some(panel) = &map_state.layers_state.panel {
+        render_layer_panel(frame, &map_state.layers_state, panel, locale);
+    }
+    if let Some(picker) = &map_state.ui_state.snapshot_picker {
+        render_snapshot_picker(frame, picker, locale);
+    }
+    render_trash_browser(frame, &map_state.trash, locale);
+    render_connection_table(frame, map_state, locale);
+    if let Some(picker) = &map_state.ui_state.workspace_picker
+        && let Some(workspace) = &map_state.workspace
+    {
+        let dir_name = workspace
+            .dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        render_workspace_picker(
+            frame,
+            picker,
+            &map_state.persistence.file_write_path,
+            &dir_name,
+            locale,
+        );
+    }
+    if let Some(search) = &map_state.ui_state.workspace_search {
+        render_workspace_search(frame, search, locale);
+    }
+    if let Some(change) = &map_state.persistence.external_change {
+        render_external_change(
+            frame,
+            change,
+            map_state.persistence.has_unsaved_changes,
+            locale,
+        );
+    }
+    // Dialogs take input before the prompts above, so they're drawn over them
+    if let Some(dialog) = &map_state.ui_state.dialog {
+        render_dialog(frame, dialog);
+    }
 }
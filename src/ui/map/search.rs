@@ -0,0 +1,34 @@
+use ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    style::{Color, Stylize},
+    text::Line,
+    widgets::Clear,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::states::map::SearchState;
+
+/// Renders the `/` search query over the bottom row of the screen, red while it isn't a
+/// valid regular expression.
+pub fn render_search_line(frame: &mut Frame, search: &SearchState) {
+    let area = frame.area();
+    if area.height == 0 {
+        return;
+    }
+    let line_area = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+    let color = if search.is_invalid() {
+        Color::Red
+    } else {
+        Color::Yellow
+    };
+
+    frame.render_widget(Clear, line_area);
+    frame.render_widget(
+        Line::from(format!("/{}", search.query)).fg(color),
+        line_area,
+    );
+
+    let cursor_x = (1 + search.query.width()).min(area.width.saturating_sub(1) as usize);
+    frame.set_cursor_position(Position::new(line_area.x + cursor_x as u16, line_area.y));
+}
@@ -0,0 +1,134 @@
+use ratatui::{
+    Frame,
+    layout::Alignment,
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+
+use super::milestones::popup_area;
+use crate::{
+    states::map::SnapshotPicker,
+    utils::{Locale, MapDiff, tr, tr_args},
+};
+
+/// Rows of the list, and most differences listed at once
+const VISIBLE_ROWS: usize = 12;
+
+/// Renders the list of snapshots to load or compare, newest first, or the differences
+/// to the highlighted one when asked for.
+pub fn render_snapshot_picker(frame: &mut Frame, picker: &SnapshotPicker, locale: Locale) {
+    if let Some(diff) = &picker.diff {
+        let name = picker
+            .selected_snapshot()
+            .map(|snapshot| snapshot.name.as_str())
+            .unwrap_or_default();
+        render_snapshot_diff(frame, diff, name, locale);
+        return;
+    }
+
+    let picker_area = popup_area(frame, 70, VISIBLE_ROWS as u16 + 4);
+
+    let mut lines: Vec<Line> = if picker.snapshots.is_empty() {
+        vec![Line::from(tr(locale, "prompt.snapshot_picker.empty")).fg(Color::DarkGray)]
+    } else {
+        // Keep the highlighted snapshot in view
+        let first = picker.selected.saturating_sub(VISIBLE_ROWS - 1);
+        picker
+            .snapshots
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(VISIBLE_ROWS)
+            .map(|(index, snapshot)| {
+                let line = Line::from(vec![
+                    Span::from(snapshot.name.clone()),
+                    Span::from(format!(
+                        "  {}",
+                        snapshot.created_at.format("%Y-%m-%d %H:%M")
+                    ))
+                    .fg(Color::DarkGray),
+                ]);
+                if index == picker.selected {
+                    line.style(Style::new().add_modifier(Modifier::REVERSED))
+                } else {
+                    line
+                }
+            })
+            .collect()
+    };
+    lines.resize(VISIBLE_ROWS, Line::from(""));
+    lines.push(Line::from(""));
+    lines.push(Line::from(tr(locale, "prompt.snapshot_picker.keys")).fg(Color::DarkGray));
+
+    frame.render_widget(Clear, picker_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .block(Block::bordered().title(tr(locale, "prompt.snapshot_picker.title"))),
+        picker_area,
+    );
+}
+
+/// Renders what loading the snapshot would change in the open map.
+fn render_snapshot_diff(frame: &mut Frame, diff: &MapDiff, name: &str, locale: Locale) {
+    let mut lines = vec![];
+
+    if diff.is_empty() {
+        lines.push(Line::from(tr(locale, "prompt.snapshot_diff.same")).fg(Color::DarkGray));
+    } else {
+        let entries: Vec<Line> = diff
+            .added
+            .iter()
+            .map(|title| Line::from(format!("+ {title}")).fg(Color::Green))
+            .chain(
+                diff.removed
+                    .iter()
+                    .map(|title| Line::from(format!("- {title}")).fg(Color::Red)),
+            )
+            .chain(
+                diff.changed
+                    .iter()
+                    .map(|title| Line::from(format!("~ {title}")).fg(Color::Yellow)),
+            )
+            .collect();
+        let hidden = entries.len().saturating_sub(VISIBLE_ROWS);
+
+        lines.extend(entries.into_iter().take(VISIBLE_ROWS));
+        if hidden > 0 {
+            lines.push(
+                Line::from(tr_args(
+                    locale,
+                    "prompt.external_change.more",
+                    &[("count", &hidden.to_string())],
+                ))
+                .fg(Color::DarkGray),
+            );
+        }
+        lines.push(Line::from(tr_args(
+            locale,
+            "prompt.external_change.connections",
+            &[
+                ("added", &diff.connections_added.to_string()),
+                ("removed", &diff.connections_removed.to_string()),
+            ],
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(tr(locale, "prompt.snapshot_diff.keys")).fg(Color::DarkGray));
+
+    let diff_area = popup_area(frame, 70, lines.len() as u16 + 2);
+
+    frame.render_widget(Clear, diff_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .block(Block::bordered().title(tr_args(
+                locale,
+                "prompt.snapshot_diff.title",
+                &[("name", name)],
+            ))),
+        diff_area,
+    );
+}
@@ -0,0 +1,68 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::{
+    graph::algorithms::GraphStats,
+    utils::{Locale, tr, tr_args},
+};
+
+/// Renders the graph statistics overlay in the top right corner of the map.
+///
+/// `last_orphan` is the orphaned note the viewport was last moved to, shown as its
+/// position among all orphans.
+pub fn render_graph_stats(
+    frame: &mut Frame,
+    stats: &GraphStats,
+    last_orphan: Option<usize>,
+    locale: Locale,
+) {
+    let width = 34.min(frame.area().width);
+    let height = 11.min(frame.area().height);
+    let area = Rect::new(
+        frame.area().width.saturating_sub(width + 1),
+        1.min(frame.area().height),
+        width,
+        height,
+    );
+
+    let orphan_position = last_orphan
+        .and_then(|id| stats.orphans.iter().position(|&orphan| orphan == id))
+        .map(|index| format!("  ({}/{})", index + 1, stats.orphans.len()))
+        .unwrap_or_default();
+
+    let count_line =
+        |key, count: usize| Line::from(tr_args(locale, key, &[("count", &count.to_string())]));
+    let lines = vec![
+        count_line("stats.notes", stats.node_count),
+        count_line("stats.connections", stats.edge_count),
+        Line::from(tr_args(
+            locale,
+            "stats.orphans",
+            &[
+                ("count", &stats.orphans.len().to_string()),
+                ("position", &orphan_position),
+            ],
+        ))
+        .fg(if stats.orphans.is_empty() {
+            Color::White
+        } else {
+            Color::Yellow
+        }),
+        count_line("stats.leaves", stats.leaves.len()),
+        count_line("stats.max_depth", stats.max_depth),
+        count_line("stats.groups", stats.component_count),
+        Line::from(""),
+        Line::from(tr(locale, "stats.keys")).fg(Color::DarkGray),
+    ];
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(tr(locale, "stats.title"))),
+        area,
+    );
+}
@@ -0,0 +1,31 @@
+use ratatui::{
+    Frame,
+    layout::Alignment,
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+};
+
+use super::milestones::popup_area;
+use crate::utils::{Locale, tr};
+
+/// Renders the prompt for the selected note's title.
+pub fn render_title_prompt(frame: &mut Frame, title: &str, locale: Locale) {
+    let prompt_area = popup_area(frame, 60, 7);
+
+    let lines = vec![
+        Line::from(tr(locale, "prompt.title.label")),
+        Line::from(title.to_string()).fg(Color::Yellow).bold(),
+        Line::from(""),
+        Line::from(""),
+        Line::from(tr(locale, "prompt.title.keys")).fg(Color::DarkGray),
+    ];
+
+    frame.render_widget(Clear, prompt_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .block(Block::bordered().title(tr(locale, "prompt.title.title"))),
+        prompt_area,
+    );
+}
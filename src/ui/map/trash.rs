@@ -0,0 +1,73 @@
+use ratatui::{
+    Frame,
+    layout::Alignment,
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+
+use super::milestones::popup_area;
+use crate::{
+    states::map::TrashState,
+    utils::{Locale, note_title, tr, tr_args},
+};
+
+/// Rows of the list
+const VISIBLE_ROWS: usize = 12;
+
+/// Renders the notes in the trash, most recently deleted first, with when they were
+/// deleted and how many connections go with them.
+pub fn render_trash_browser(frame: &mut Frame, trash: &TrashState, locale: Locale) {
+    let Some(browser) = &trash.browser else {
+        return;
+    };
+    let browser_area = popup_area(frame, 100, VISIBLE_ROWS as u16 + 4);
+
+    let mut lines: Vec<Line> = if trash.is_empty() {
+        vec![Line::from(tr(locale, "prompt.trash.empty")).fg(Color::DarkGray)]
+    } else {
+        // Keep the highlighted note in view
+        let first = browser.selected.saturating_sub(VISIBLE_ROWS - 1);
+        trash
+            .notes()
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(VISIBLE_ROWS)
+            .map(|(index, trashed)| {
+                let mut details = format!("  {}", trashed.deleted_at.format("%Y-%m-%d %H:%M"));
+                if !trashed.connections.is_empty() {
+                    let connections = tr_args(
+                        locale,
+                        "prompt.trash.connections",
+                        &[("count", &trashed.connections.len().to_string())],
+                    );
+                    details.push_str(&format!("  {connections}"));
+                }
+                let line = Line::from(vec![
+                    Span::styled(
+                        note_title(&trashed.note),
+                        Style::new().fg(trashed.note.color),
+                    ),
+                    Span::from(details).fg(Color::DarkGray),
+                ]);
+                if index == browser.selected {
+                    line.style(Style::new().add_modifier(Modifier::REVERSED))
+                } else {
+                    line
+                }
+            })
+            .collect()
+    };
+    lines.resize(VISIBLE_ROWS, Line::from(""));
+    lines.push(Line::from(""));
+    lines.push(Line::from(tr(locale, "prompt.trash.keys")).fg(Color::DarkGray));
+
+    frame.render_widget(Clear, browser_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .block(Block::bordered().title(tr(locale, "prompt.trash.title"))),
+        browser_area,
+    );
+}
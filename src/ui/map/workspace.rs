@@ -0,0 +1,105 @@
+use ratatui::{
+    Frame,
+    layout::Alignment,
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+
+use super::milestones::popup_area;
+use crate::{
+    states::map::{WorkspacePicker, WorkspaceSearch},
+    utils::{Locale, map_name, tr, tr_args},
+};
+
+/// Rows of the list
+const VISIBLE_ROWS: usize = 12;
+
+/// Renders the workspace's maps to switch to; the open map is marked with `>`.
+pub fn render_workspace_picker(
+    frame: &mut Frame,
+    picker: &WorkspacePicker,
+    open_map: &std::path::Path,
+    dir_name: &str,
+    locale: Locale,
+) {
+    let picker_area = popup_area(frame, 70, VISIBLE_ROWS as u16 + 4);
+
+    // Keep the highlighted map in view
+    let first = picker.selected.saturating_sub(VISIBLE_ROWS - 1);
+    let mut lines: Vec<Line> = picker
+        .maps
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(VISIBLE_ROWS)
+        .map(|(index, path)| {
+            let marker = if path == open_map { "> " } else { "  " };
+            let line = Line::from(vec![Span::from(marker), Span::from(map_name(path))]);
+            if index == picker.selected {
+                line.style(Style::new().add_modifier(Modifier::REVERSED))
+            } else {
+                line
+            }
+        })
+        .collect();
+    lines.resize(VISIBLE_ROWS, Line::from(""));
+    lines.push(Line::from(""));
+    lines.push(Line::from(tr(locale, "prompt.workspace.keys")).fg(Color::DarkGray));
+
+    frame.render_widget(Clear, picker_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .block(Block::bordered().title(tr_args(
+                locale,
+                "prompt.workspace.title",
+                &[("name", dir_name)],
+            ))),
+        picker_area,
+    );
+}
+
+/// Renders the notes found by `:grep`, each with its map's name and matching line.
+pub fn render_workspace_search(frame: &mut Frame, search: &WorkspaceSearch, locale: Locale) {
+    let results_area = popup_area(frame, 90, VISIBLE_ROWS as u16 + 4);
+
+    // Keep the highlighted result in view
+    let first = search.selected.saturating_sub(VISIBLE_ROWS - 1);
+    let mut lines: Vec<Line> = search
+        .results
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(VISIBLE_ROWS)
+        .map(|(index, found)| {
+            let line = Line::from(vec![
+                Span::from(format!("{}: ", map_name(&found.path))).fg(Color::Yellow),
+                Span::from(found.line.clone()),
+            ]);
+            if index == search.selected {
+                line.style(Style::new().add_modifier(Modifier::REVERSED))
+            } else {
+                line
+            }
+        })
+        .collect();
+    lines.resize(VISIBLE_ROWS, Line::from(""));
+    lines.push(Line::from(""));
+    lines.push(Line::from(tr(locale, "prompt.workspace_search.keys")).fg(Color::DarkGray));
+
+    frame.render_widget(Clear, results_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Left)
+            .block(Block::bordered().title(tr_args(
+                locale,
+                "prompt.workspace_search.title",
+                &[
+                    ("query", &search.query),
+                    ("count", &search.results.len().to_string()),
+                ],
+            ))),
+        results_area,
+    );
+}
@@ -1,9 +1,15 @@
+pub mod backups;
 pub mod constants;
+pub mod dialog;
+pub mod layout;
 pub mod map;
 pub mod settings;
 pub mod start;
 
+pub use backups::*;
 pub use constants::*;
+pub use dialog::*;
+pub use layout::*;
 pub use map::*;
 pub use settings::*;
 pub use start::*;
@@ -1,28 +1,37 @@
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Margin, Position},
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
     style::{Color, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, Clear, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
 
 use crate::{
     states::{
         SettingsState,
         settings::{
-            BackupsErr, BackupsInterval, RuntimeBackupsInterval, SelectedToggle,
+            BackupRetention, BackupsErr, BackupsInterval, RuntimeBackupsInterval, SelectedToggle,
             SettingsNotification, SettingsType, side_to_string,
         },
     },
-    utils::IoErrorKind,
+    ui::{panel_margin, render_size_guard},
+    utils::{IoErrorKind, Locale},
 };
 
+/// Terminals lower than this leave out the notification and hint lines of the settings
+/// menu, so the toggles keep the room
+const COMPACT_SETTINGS_HEIGHT: u16 = 24;
+
 /// Renders the settings screen with toggleable options for map behavior and backups.
 ///
 /// Displays different views based on state: error message, context help page, input prompt
 /// for backups path, or the main settings menu. Consumes one-time notifications by clearing
 /// them after rendering.
 pub fn render_settings(frame: &mut Frame, settings_state: &mut SettingsState) {
+    if render_size_guard(frame) {
+        return;
+    }
+
     // Error case - settings functionality unavailable
     if let SettingsType::Default(_, error_message) = &settings_state.settings {
         if let Some(err_msg) = error_message {
@@ -83,7 +92,7 @@ pub fn render_settings(frame: &mut Frame, settings_state: &mut SettingsState) {
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Fill(1),
-                Constraint::Length(40),
+                Constraint::Length(75),
                 Constraint::Length(1),
                 Constraint::Length(1),
                 Constraint::Length(3),
@@ -136,6 +145,46 @@ pub fn render_settings(frame: &mut Frame, settings_state: &mut SettingsState) {
             Line::from("Enables basic modal editing with limited vim-inspired"),
             Line::from("keybindings. See help screen for available commands."),
             Line::from("(? / F1  in Map Screen to open Help Screen)"),
+            Line::from(""),
+            Line::from("7. Language"),
+            Line::from("Language of the map screen. Auto follows LANG."),
+            Line::from(""),
+            Line::from("8. Compress New Maps"),
+            Line::from("Creates new map files gzip-compressed (.json.gz)."),
+            Line::from(""),
+            Line::from("9. Compact Map Files"),
+            Line::from("Writes maps without indentation: smaller, faster saves."),
+            Line::from(""),
+            Line::from("10. Backups Retention"),
+            Line::from("Deletes a map's older backups after each new one. Tiered"),
+            Line::from("keeps one per day (7), week (4) and month (12)."),
+            Line::from(""),
+            Line::from("11. Recent Files Kept"),
+            Line::from("Length of the start screen's list, not counting pinned."),
+            Line::from(""),
+            Line::from("12. Status Bar"),
+            Line::from("Items: {mode} {x} {y} {note} {unsaved} {clock} {notes}"),
+            Line::from("Text after | is right-aligned. Empty: built-in bar."),
+            Line::from(""),
+            Line::from("13. Note UUIDs"),
+            Line::from("Gives notes ids that stay unique across maps, for merging"),
+            Line::from("and other tools. Opened maps get them on the next save."),
+            Line::from(""),
+            Line::from("14. Parallel Connections"),
+            Line::from("Allows more than one connection between the same sides"),
+            Line::from("of two notes. Disabled, duplicates are refused."),
+            Line::from(""),
+            Line::from("15. Spell Checking"),
+            Line::from("Hunspell dictionary for underlining typos while editing;"),
+            Line::from("F7 cycles suggestions. Off without a dictionary."),
+            Line::from(""),
+            Line::from("16. Undo History"),
+            Line::from("Kept in a file next to the map (name.undo.gz) to undo"),
+            Line::from("after reopening. Encrypted notes are left out."),
+            Line::from(""),
+            Line::from("17. Scroll Margin"),
+            Line::from("Cells kept between a moved note and the screen edge, and"),
+            Line::from("lines around the cursor in a long note, like scrolloff."),
         ];
 
         let context_page_content: Vec<ListItem> =
@@ -144,18 +193,32 @@ pub fn render_settings(frame: &mut Frame, settings_state: &mut SettingsState) {
 
         frame.render_widget(
             context_page_content,
-            context_page_area[1].inner(Margin::new(3, 3)),
+            context_page_area[1].inner(panel_margin(context_page_area[1], 3, 3)),
         );
 
         return;
     }
 
     // Main settings menu
-    let settings_menu_area = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
+    let settings_menu_constraints = if frame.area().height < COMPACT_SETTINGS_HEIGHT {
+        // Only the toggles and the controls
+        [
+            Constraint::Length(0),
+            Constraint::Fill(1),
+            Constraint::Length(0),
+            Constraint::Length(0),
+            Constraint::Length(0),
+            Constraint::Length(0),
+            Constraint::Length(0),
+            Constraint::Length(1),
+            Constraint::Length(0),
+            Constraint::Length(1),
+            Constraint::Length(0),
+        ]
+    } else {
+        [
             Constraint::Fill(1),
-            Constraint::Length(40),
+            Constraint::Max(40),
             Constraint::Length(1),
             Constraint::Length(1),
             Constraint::Length(1),
@@ -165,7 +228,11 @@ pub fn render_settings(frame: &mut Frame, settings_state: &mut SettingsState) {
             Constraint::Length(1),
             Constraint::Length(1),
             Constraint::Fill(1),
-        ])
+        ]
+    };
+    let settings_menu_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(settings_menu_constraints)
         .split(frame.area());
 
     let settings_screen_controls_text1 = Line::from(
@@ -222,6 +289,14 @@ pub fn render_settings(frame: &mut Frame, settings_state: &mut SettingsState) {
         frame.render_widget(runtime_backups_toggle_hint, settings_menu_area[5]);
     }
 
+    if matches!(settings_state.selected_toggle, SelectedToggle::Toggle15) {
+        let dictionaries_hint = Line::from(String::from(
+            "Hunspell dictionaries (.dic/.aff) from ~/.config/tmmpr/dictionaries or /usr/share/hunspell",
+        ))
+        .alignment(Alignment::Center);
+        frame.render_widget(dictionaries_hint, settings_menu_area[5]);
+    }
+
     let settings_menu_area = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
@@ -293,6 +368,90 @@ pub fn render_settings(frame: &mut Frame, settings_state: &mut SettingsState) {
     };
     let toggle6_style = SelectedToggle::Toggle6.get_style(&settings_state.selected_toggle);
 
+    // Toggle 7 - UI language
+    let toggle7_content_text = match settings_state.settings.settings().locale {
+        Some(locale) => String::from(locale.name()),
+        None => format!("Auto ({})", Locale::from_env().name()),
+    };
+    let toggle7_style = SelectedToggle::Toggle7.get_style(&settings_state.selected_toggle);
+
+    // Toggle 8 - compression of new map files
+    let toggle8_content_text = if settings_state.settings.settings().compress_maps {
+        String::from("Enabled")
+    } else {
+        String::from("Disabled")
+    };
+    let toggle8_style = SelectedToggle::Toggle8.get_style(&settings_state.selected_toggle);
+
+    // Toggle 9 - compact JSON in map files
+    let toggle9_content_text = if settings_state.settings.settings().compact_json {
+        String::from("Enabled")
+    } else {
+        String::from("Disabled")
+    };
+    let toggle9_style = SelectedToggle::Toggle9.get_style(&settings_state.selected_toggle);
+
+    // Toggle 10 - backups retention
+    let toggle10_content_text = match settings_state.settings.settings().backup_retention {
+        BackupRetention::KeepAll => String::from("Keep all"),
+        BackupRetention::KeepLast(count) => format!("Keep last {count}"),
+        BackupRetention::Tiered => String::from("Daily / weekly / monthly"),
+    };
+    let toggle10_style = SelectedToggle::Toggle10.get_style(&settings_state.selected_toggle);
+
+    // Toggle 11 - number of recent files kept
+    let toggle11_content_text = settings_state
+        .settings
+        .settings()
+        .recent_files_limit
+        .to_string();
+    let toggle11_style = SelectedToggle::Toggle11.get_style(&settings_state.selected_toggle);
+
+    // Toggle 12 - status bar format
+    let toggle12_content_text = match &settings_state.settings.settings().status_bar_format {
+        Some(format) => format.clone(),
+        None => String::from("Built-in"),
+    };
+    let toggle12_style = SelectedToggle::Toggle12.get_style(&settings_state.selected_toggle);
+
+    // Toggle 13 - UUIDs for notes
+    let toggle13_content_text = if settings_state.settings.settings().note_uuids {
+        String::from("Enabled")
+    } else {
+        String::from("Disabled")
+    };
+    let toggle13_style = SelectedToggle::Toggle13.get_style(&settings_state.selected_toggle);
+
+    // Toggle 14 - Parallel connections
+    let toggle14_content_text = if settings_state.settings.settings().parallel_connections {
+        String::from("Allowed")
+    } else {
+        String::from("Refused")
+    };
+    let toggle14_style = SelectedToggle::Toggle14.get_style(&settings_state.selected_toggle);
+
+    // Toggle 15 - Spell checking dictionary
+    let toggle15_content_text = match &settings_state.settings.settings().spell_dictionary {
+        Some(name) => name.clone(),
+        None => String::from("Off"),
+    };
+    let toggle15_style = SelectedToggle::Toggle15.get_style(&settings_state.selected_toggle);
+
+    // Toggle 16 - Undo history kept between sessions
+    let toggle16_content_text = if settings_state.settings.settings().persist_undo {
+        String::from("Kept in a file")
+    } else {
+        String::from("This session only")
+    };
+    let toggle16_style = SelectedToggle::Toggle16.get_style(&settings_state.selected_toggle);
+
+    // Toggle 17 - Scroll margin
+    let toggle17_content_text = match settings_state.settings.settings().scroll_margin {
+        0 => String::from("Off"),
+        margin => format!("{margin} cells"),
+    };
+    let toggle17_style = SelectedToggle::Toggle17.get_style(&settings_state.selected_toggle);
+
     let settings_menu_content_lines = vec![
         Line::from(vec![
             Span::raw("Map changes auto save interval:  "),
@@ -320,6 +479,61 @@ pub fn render_settings(frame: &mut Frame, settings_state: &mut SettingsState) {
             Span::raw("Modal Editing for Edit Mode:  "),
             Span::styled(format!("{}", toggle6_content_text), toggle6_style),
         ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Language:  "),
+            Span::styled(toggle7_content_text, toggle7_style),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Compress new maps:  "),
+            Span::styled(toggle8_content_text, toggle8_style),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Compact map files:  "),
+            Span::styled(toggle9_content_text, toggle9_style),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Backups retention:  "),
+            Span::styled(toggle10_content_text, toggle10_style),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Recent files kept:  "),
+            Span::styled(toggle11_content_text, toggle11_style),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Status bar:  "),
+            Span::styled(toggle12_content_text, toggle12_style),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Note UUIDs:  "),
+            Span::styled(toggle13_content_text, toggle13_style),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Parallel connections:  "),
+            Span::styled(toggle14_content_text, toggle14_style),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Spell checking:  "),
+            Span::styled(toggle15_content_text, toggle15_style),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Undo history:  "),
+            Span::styled(toggle16_content_text, toggle16_style),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Scroll margin:  "),
+            Span::styled(toggle17_content_text, toggle17_style),
+        ]),
     ];
 
     let settings_menu_content: Vec<ListItem> = settings_menu_content_lines
@@ -328,9 +542,13 @@ pub fn render_settings(frame: &mut Frame, settings_state: &mut SettingsState) {
         .collect();
     let settings_menu_content = List::new(settings_menu_content);
 
-    frame.render_widget(
+    // Scrolls to the selected toggle when the terminal is too low for all of them
+    let mut settings_menu_list_state =
+        ListState::default().with_selected(Some(toggle_line(&settings_state.selected_toggle)));
+    frame.render_stateful_widget(
         settings_menu_content,
-        settings_menu_area[1].inner(Margin::new(3, 3)),
+        settings_menu_area[1].inner(panel_margin(settings_menu_area[1], 3, 3)),
+        &mut settings_menu_list_state,
     );
 
     // Modal prompt for entering backups directory path
@@ -408,8 +626,6 @@ pub fn render_settings(frame: &mut Frame, settings_state: &mut SettingsState) {
         .wrap(Wrap { trim: false });
         frame.render_widget(user_input_path, input_prompt_input_area[1]);
 
-        // Account for wrapping: inner width is 58 (60 - 2 borders)
-        let inner_width = 58usize;
         let path_len = settings_state
             .settings
             .settings()
@@ -417,10 +633,7 @@ pub fn render_settings(frame: &mut Frame, settings_state: &mut SettingsState) {
             .as_ref()
             .unwrap()
             .len();
-        // +1 offset accounts for border width
-        let cursor_x = input_prompt_input_area[1].x + (path_len % inner_width) as u16 + 1;
-        let cursor_y = input_prompt_input_area[1].y + (path_len / inner_width) as u16 + 1;
-        frame.set_cursor_position(Position::new(cursor_x, cursor_y));
+        frame.set_cursor_position(input_cursor_position(input_prompt_input_area[1], path_len));
 
         if let Some(err) = &settings_state.input_prompt_err {
             match err {
@@ -446,46 +659,149 @@ pub fn render_settings(frame: &mut Frame, settings_state: &mut SettingsState) {
         }
     }
 
-    // Confirmation menu when attempting to exit with unsaved changes
-    if let Some(_) = &settings_state.confirm_discard_menu {
-        let confirm_discard_menu_area = Layout::default()
+    // Modal prompt for editing the status bar format
+    if let Some(format) = &settings_state.format_prompt {
+        let format_prompt_area = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Fill(1),
-                Constraint::Length(1),
-                Constraint::Length(8),
+                Constraint::Length(16),
+                Constraint::Fill(1),
             ])
             .split(frame.area());
+        let format_prompt_area = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(100),
+                Constraint::Fill(1),
+            ])
+            .split(format_prompt_area[1]);
 
-        frame.render_widget(Clear, confirm_discard_menu_area[1]);
-        frame.render_widget(Clear, confirm_discard_menu_area[2]);
-        frame.render_widget(Block::bordered(), confirm_discard_menu_area[2]);
+        frame.render_widget(Clear, frame.area());
+        frame.render_widget(Block::bordered(), format_prompt_area[1]);
 
-        let confirm_discard_menu_text_areas = Layout::default()
+        let format_prompt_lines_area = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
+                Constraint::Min(2),
+                Constraint::Length(5),
                 Constraint::Length(2),
+                Constraint::Length(4),
                 Constraint::Length(1),
                 Constraint::Length(1),
+                Constraint::Min(2),
+            ])
+            .split(format_prompt_area[1]);
+        let format_prompt_input_area = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Min(2),
+                Constraint::Length(60),
+                Constraint::Min(2),
+            ])
+            .split(format_prompt_lines_area[3]);
+
+        let format_prompt_text = vec![
+            Line::from("Enter the status bar format").alignment(Alignment::Center),
+            Line::from(""),
+            Line::from("Items: {mode} {x} {y} {note} {unsaved} {clock} {notes}")
+                .alignment(Alignment::Center),
+            Line::from("Text after | is right-aligned").alignment(Alignment::Center),
+            Line::from("Empty field - uses the built-in bar").alignment(Alignment::Center),
+        ];
+        let format_prompt_text: Vec<ListItem> =
+            format_prompt_text.into_iter().map(ListItem::new).collect();
+        let format_prompt_text = List::new(format_prompt_text);
+
+        let keybinds_text =
+            Line::from("Esc - cancel          Enter - confirm format").alignment(Alignment::Center);
+
+        frame.render_widget(format_prompt_text, format_prompt_lines_area[1]);
+        frame.render_widget(keybinds_text, format_prompt_lines_area[5]);
+
+        let user_input_format = Paragraph::new(Line::from(format.as_str()))
+            .block(Block::bordered())
+            .wrap(Wrap { trim: false });
+        frame.render_widget(user_input_format, format_prompt_input_area[1]);
+
+        let format_len = format.chars().count();
+        frame.set_cursor_position(input_cursor_position(
+            format_prompt_input_area[1],
+            format_len,
+        ));
+    }
+
+    // Confirmation menu when attempting to exit with unsaved changes
+    if let Some(_) = &settings_state.confirm_discard_menu {
+        let confirm_discard_menu_area = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Fill(1),
                 Constraint::Length(1),
-                Constraint::Length(1),
-                Constraint::Length(2),
+                Constraint::Length(8),
             ])
-            .split(confirm_discard_menu_area[2]);
+            .split(frame.area());
+
+        frame.render_widget(Clear, confirm_discard_menu_area[1]);
+        frame.render_widget(Clear, confirm_discard_menu_area[2]);
 
-        let line_1 =
-            Line::from("Exit without saving changes to settings?").alignment(Alignment::Center);
-        let line_2 = Line::from(vec![
+        // One blank line less between the question and the keys when they have to wrap
+        let gap = if frame.area().width < 60 { 1 } else { 2 };
+        let mut confirm_discard_menu_lines = vec![
+            Line::default(),
+            Line::from("Exit without saving changes to settings?"),
+        ];
+        confirm_discard_menu_lines.extend(vec![Line::default(); gap]);
+        confirm_discard_menu_lines.push(Line::from(vec![
             Span::styled("[ ESC ] - Cancel", Style::new().fg(Color::Green)),
             Span::raw("      "),
             Span::styled(
                 "[ q ] - Confirm discard and exit",
                 Style::new().fg(Color::Red),
             ),
-        ])
-        .alignment(Alignment::Center);
-
-        frame.render_widget(line_1, confirm_discard_menu_text_areas[1]);
-        frame.render_widget(line_2, confirm_discard_menu_text_areas[4]);
+        ]));
+        // Wraps on a narrow terminal instead of cutting off the keys
+        let confirm_discard_menu = Paragraph::new(confirm_discard_menu_lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered());
+        frame.render_widget(confirm_discard_menu, confirm_discard_menu_area[2]);
     }
 }
+
+/// Line of the settings menu the toggle is on; every toggle is followed by a blank line.
+fn toggle_line(toggle: &SelectedToggle) -> usize {
+    let index = match toggle {
+        SelectedToggle::Toggle1 => 0,
+        SelectedToggle::Toggle2 => 1,
+        SelectedToggle::Toggle3 => 2,
+        SelectedToggle::Toggle4 => 3,
+        SelectedToggle::Toggle5 => 4,
+        SelectedToggle::Toggle6 => 5,
+        SelectedToggle::Toggle7 => 6,
+        SelectedToggle::Toggle8 => 7,
+        SelectedToggle::Toggle9 => 8,
+        SelectedToggle::Toggle10 => 9,
+        SelectedToggle::Toggle11 => 10,
+        SelectedToggle::Toggle12 => 11,
+        SelectedToggle::Toggle13 => 12,
+        SelectedToggle::Toggle14 => 13,
+        SelectedToggle::Toggle15 => 14,
+        SelectedToggle::Toggle16 => 15,
+        SelectedToggle::Toggle17 => 16,
+    };
+    index * 2
+}
+
+/// Cursor position after `len` characters typed into a bordered input box, which wraps
+/// them at its inner width. Kept inside the box when the terminal is too small for all
+/// of the text.
+fn input_cursor_position(input_area: Rect, len: usize) -> Position {
+    let inner_width = input_area.width.saturating_sub(2).max(1) as usize;
+    let inner_height = input_area.height.saturating_sub(2).max(1) as usize;
+    // +1 offset accounts for border width
+    let column = (len % inner_width) as u16 + 1;
+    let row = (len / inner_width).min(inner_height - 1) as u16 + 1;
+    Position::new(input_area.x + column, input_area.y + row)
+}
@@ -1,7 +1,7 @@
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Position},
-    style::{Color, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
+    style::{Color, Style, Stylize},
     text::{Line, Span},
     widgets::{Block, Clear, List, ListItem, Paragraph, Wrap},
 };
@@ -9,20 +9,26 @@ use ratatui::{
 use crate::{
     states::{
         StartState,
-        start::{FocusedInputBox, SelectedStartButton},
+        start::{
+            FocusedInputBox, HealthIssue, MapPassphrasePrompt, MapPreview, SelectedStartButton,
+        },
     },
-    utils::IoErrorKind,
+    ui::{render_dialog, render_size_guard},
+    utils::{CryptoError, IoErrorKind},
 };
 
 /// Renders the start screen with menu options and optional path input dialog.
 pub fn render_start(frame: &mut Frame, start_state: &mut StartState) {
+    if render_size_guard(frame) {
+        return;
+    }
     frame.render_widget(Clear, frame.area());
 
     let start_text_area = Layout::default()
         .direction(Direction::Vertical)
         .constraints(vec![
             Constraint::Percentage(35),
-            Constraint::Min(12),
+            Constraint::Min(14),
             Constraint::Percentage(45),
             Constraint::Min(1),
         ])
@@ -30,14 +36,17 @@ pub fn render_start(frame: &mut Frame, start_state: &mut StartState) {
 
     let create_select_style =
         SelectedStartButton::CreateSelect.get_style(&start_state.selected_button);
-    let recent1_style = SelectedStartButton::Recent1.get_style(&start_state.selected_button);
-    let recent2_style = SelectedStartButton::Recent2.get_style(&start_state.selected_button);
-    let recent3_style = SelectedStartButton::Recent3.get_style(&start_state.selected_button);
 
     // Display error if getting recent paths failed, otherwise show recents header
     let recents_text = match &start_state.display_err_msg {
         Some(_) => Line::from(Span::styled(
-            "File doesn't exist or there was an error reading it",
+            match &start_state.load_error {
+                Some(load_error) => fit_to_width(
+                    format!("Couldn't open the map: {load_error}"),
+                    start_text_area[1].width,
+                ),
+                None => String::from("File doesn't exist or there was an error reading it"),
+            },
             Style::new().fg(Color::Red),
         ))
         .alignment(Alignment::Center),
@@ -66,29 +75,33 @@ pub fn render_start(frame: &mut Frame, start_state: &mut StartState) {
         },
     };
 
-    let (recent1_text, recent2_text, recent3_text) =
-        if let Ok(recent_paths) = &start_state.recent_paths {
-            (
-                recent_paths
-                    .recent_path_1
-                    .as_ref()
-                    .map_or(String::new(), |p| p.to_string_lossy().into_owned()),
-                recent_paths
-                    .recent_path_2
-                    .as_ref()
-                    .map_or(String::new(), |p| p.to_string_lossy().into_owned()),
-                recent_paths
-                    .recent_path_3
-                    .as_ref()
-                    .map_or(String::new(), |p| p.to_string_lossy().into_owned()),
-            )
-        } else {
-            (String::new(), String::new(), String::new())
-        };
-    // Wrap paths in brackets to visually distinguish as selectable buttons
-    let recent1_text = format!("[ {} ]", recent1_text);
-    let recent2_text = format!("[ {} ]", recent2_text);
-    let recent3_text = format!("[ {} ]", recent3_text);
+    // Rows left for recent files below the menu's 11 header lines; the list scrolls to
+    // keep the highlighted entry in view
+    let visible_rows = (start_text_area[1].height as usize)
+        .saturating_sub(11)
+        .max(1);
+    let first = match start_state.selected_button {
+        SelectedStartButton::Recent(index) => index.saturating_sub(visible_rows - 1),
+        SelectedStartButton::CreateSelect | SelectedStartButton::Tutorial => 0,
+    };
+    let recent_lines: Vec<Line> = match &start_state.recent_paths {
+        Ok(recent_paths) => recent_paths
+            .entries()
+            .enumerate()
+            .skip(first)
+            .take(visible_rows)
+            .map(|(index, (path, pinned))| {
+                // Wrap paths in brackets to visually distinguish as selectable buttons,
+                // pinned favorites are marked with a star
+                let marker = if pinned { "* " } else { "" };
+                let text = format!("[ {}{} ]", marker, path.to_string_lossy());
+                let style =
+                    SelectedStartButton::Recent(index).get_style(&start_state.selected_button);
+                Line::from(Span::styled(text, style)).alignment(Alignment::Center)
+            })
+            .collect(),
+        Err(_) => vec![],
+    };
 
     let start_menu = vec![
         Line::from("tmmpr  v0.1.1").alignment(Alignment::Center),
@@ -101,24 +114,44 @@ pub fn render_start(frame: &mut Frame, start_state: &mut StartState) {
         ))
         .alignment(Alignment::Center),
         Line::from(""),
+        Line::from(Span::styled(
+            "[ Tutorial - learn the basics on a sample map ]",
+            SelectedStartButton::Tutorial.get_style(&start_state.selected_button),
+        ))
+        .alignment(Alignment::Center),
+        Line::from(""),
         Line::from(""),
         recents_text,
         Line::from(""),
-        Line::from(Span::styled(recent1_text, recent1_style)).alignment(Alignment::Center),
-        Line::from(Span::styled(recent2_text, recent2_style)).alignment(Alignment::Center),
-        Line::from(Span::styled(recent3_text, recent3_style)).alignment(Alignment::Center),
     ];
 
-    let start_menu: Vec<ListItem> = start_menu.into_iter().map(ListItem::new).collect();
+    let start_menu: Vec<ListItem> = start_menu
+        .into_iter()
+        .chain(recent_lines)
+        .map(ListItem::new)
+        .collect();
 
     let start_menu = List::new(start_menu);
 
     let info_text = Line::from(
-        "q - quit      Enter - choose option      k / Up - go up       j / Down - go down",
+        "q - quit    Enter - choose    k / j - up / down    p - pin    d - remove    D - remove missing",
     )
     .alignment(Alignment::Center);
 
-    frame.render_widget(start_menu, start_text_area[1]);
+    // Preview of the highlighted recent file is shown to the right of the menu
+    // when there is enough horizontal room for both
+    match &start_state.preview {
+        Some((_, preview)) if frame.area().width >= 100 => {
+            let menu_and_preview_areas = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![Constraint::Fill(1), Constraint::Length(36)])
+                .split(start_text_area[1]);
+
+            frame.render_widget(start_menu, menu_and_preview_areas[0]);
+            render_map_preview(frame, preview, menu_and_preview_areas[1]);
+        }
+        _ => frame.render_widget(start_menu, start_text_area[1]),
+    }
     frame.render_widget(info_text, start_text_area[3]);
 
     // Overlay input dialog when user chooses to create/select a map
@@ -181,8 +214,10 @@ pub fn render_start(frame: &mut Frame, start_state: &mut StartState) {
             Line::from("(Relative to your home directory, e.g. maps/):")
                 .alignment(Alignment::Center),
         );
-        let text_line_3 =
-            Paragraph::new(Line::from("Enter the map name:").alignment(Alignment::Center));
+        let text_line_3 = Paragraph::new(
+            Line::from("Enter the map name (empty: open the directory as a workspace):")
+                .alignment(Alignment::Center),
+        );
 
         frame.render_widget(Clear, input_menu_area[1]);
         frame.render_widget(Block::bordered(), input_menu_area[1]);
@@ -266,7 +301,13 @@ pub fn render_start(frame: &mut Frame, start_state: &mut StartState) {
                 }
                 IoErrorKind::FileRead => {
                     let error_text = Line::from(Span::styled(
-                        "Error reading the map file",
+                        match &start_state.load_error {
+                            Some(load_error) => fit_to_width(
+                                format!("Error reading the map file: {load_error}"),
+                                input_menu_areas[8].width,
+                            ),
+                            None => String::from("Error reading the map file"),
+                        },
                         Style::new().fg(Color::Red),
                     ))
                     .alignment(Alignment::Center);
@@ -275,4 +316,188 @@ pub fn render_start(frame: &mut Frame, start_state: &mut StartState) {
             }
         }
     }
+
+    if let Some(prompt) = &start_state.passphrase_prompt {
+        render_map_passphrase_prompt(frame, prompt);
+    }
+
+    if let Some(dialog) = &start_state.dialog {
+        render_dialog(frame, dialog);
+    }
+
+    if !start_state.health_issues.is_empty() {
+        render_health_issues(frame, &start_state.health_issues);
+    }
+}
+
+/// Renders the passphrase prompt of an encrypted map file. The typed passphrase is
+/// masked.
+fn render_map_passphrase_prompt(frame: &mut Frame, prompt: &MapPassphrasePrompt) {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(8),
+            Constraint::Fill(1),
+        ])
+        .split(frame.area());
+    let area = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(60),
+            Constraint::Fill(1),
+        ])
+        .split(vertical[1])[1];
+
+    let error_line = match &prompt.error {
+        Some(CryptoError::WrongPassphrase) => Line::from("Wrong passphrase").fg(Color::Red),
+        Some(CryptoError::Decode) => Line::from("Encrypted map file is damaged").fg(Color::Red),
+        None => Line::from(""),
+    };
+    let file_name = prompt
+        .path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+
+    let lines = vec![
+        Line::from(file_name).fg(Color::DarkGray),
+        Line::from("Passphrase:"),
+        Line::from("*".repeat(prompt.input.chars().count())).fg(Color::Yellow),
+        error_line,
+        Line::from(""),
+        Line::from("Enter - open      Esc - cancel").fg(Color::DarkGray),
+    ];
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .block(Block::bordered().title(" Encrypted map ")),
+        area,
+    );
+}
+
+/// Renders the launch warnings panel listing every health check issue and its fix.
+fn render_health_issues(frame: &mut Frame, issues: &[HealthIssue]) {
+    let mut lines = vec![Line::from("")];
+    for issue in issues {
+        lines.push(Line::from(format!(" ! {}", issue.message())).fg(Color::Red));
+        let fix = match issue.fix_description() {
+            Some(fix) => format!("   f: {fix}"),
+            None => String::from("   Fix the permissions or path, then restart"),
+        };
+        lines.push(Line::from(fix).fg(Color::DarkGray));
+    }
+    lines.push(Line::from(""));
+
+    let can_fix = issues.iter().any(|issue| issue.fix_description().is_some());
+    let keys = if can_fix {
+        "f - fix      Esc - continue anyway      q - quit"
+    } else {
+        "Esc - continue anyway      q - quit"
+    };
+    lines.push(Line::from(keys).alignment(Alignment::Center));
+
+    let height = (lines.len() + 2) as u16;
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .split(frame.area());
+    let area = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(90),
+            Constraint::Fill(1),
+        ])
+        .split(vertical[1])[1];
+
+    let panel = Paragraph::new(lines)
+        .block(
+            Block::bordered()
+                .title(" Setup warnings ")
+                .border_style(Color::Yellow),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(panel, area);
+}
+
+/// Renders the read-only summary and layout thumbnail of the highlighted recent file.
+fn render_map_preview(frame: &mut Frame, preview: &Result<MapPreview, IoErrorKind>, area: Rect) {
+    let block = Block::bordered().title(" Preview ");
+    let inner_area = block.inner(area);
+    frame.render_widget(block, area);
+
+    let preview = match preview {
+        Ok(preview) => preview,
+        Err(_) => {
+            let error_text = Paragraph::new(Line::from(Span::styled(
+                "Can't read this map file",
+                Style::new().fg(Color::Red),
+            )))
+            .wrap(Wrap { trim: false });
+            frame.render_widget(error_text, inner_area);
+            return;
+        }
+    };
+
+    let modified = match preview.modified {
+        Some(modified) => format!("Modified {}", modified.format("%Y-%m-%d %H:%M")),
+        None => String::from("Modified: unknown"),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(
+            preview.title.clone(),
+            Style::new().fg(Color::Yellow),
+        )),
+        Line::from(preview.directory.clone()).fg(Color::DarkGray),
+        Line::from(modified),
+    ];
+
+    if preview.encrypted {
+        lines.push(Line::from("Encrypted map file").fg(Color::DarkGray));
+        frame.render_widget(Paragraph::new(lines), inner_area);
+        return;
+    }
+
+    lines.extend([
+        Line::from(format!(
+            "{} notes, {} connections",
+            preview.note_count, preview.connection_count
+        )),
+        Line::from(
+            preview
+                .first_note_line
+                .clone()
+                .unwrap_or_else(|| String::from("(empty map)")),
+        )
+        .fg(Color::DarkGray),
+        Line::from(""),
+    ]);
+
+    let thumbnail_height = (inner_area.height as usize).saturating_sub(lines.len());
+    for row in preview.thumbnail(inner_area.width as usize, thumbnail_height) {
+        lines.push(Line::from(row).fg(Color::Cyan));
+    }
+
+    frame.render_widget(Paragraph::new(lines), inner_area);
+}
+
+/// Cuts text that's wider than `width` columns, ending it with "..." instead.
+fn fit_to_width(text: String, width: u16) -> String {
+    let width = width as usize;
+    if text.chars().count() <= width {
+        return text;
+    }
+    let kept: String = text.chars().take(width.saturating_sub(3)).collect();
+    format!("{kept}...")
 }
@@ -1,13 +1,22 @@
-use chrono::{Duration as ChronoDuration, Local};
-use std::{path::PathBuf, time::Duration as StdDuration};
+use chrono::{Datelike, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime};
+use std::{
+    cmp::Reverse,
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration as StdDuration,
+};
 
 use crate::{
     states::{
         MapState,
         map::Notification,
-        settings::{BackupsInterval, RuntimeBackupsInterval},
+        settings::{BackupRetention, BackupsInterval, RuntimeBackupsInterval},
+    },
+    utils::{
+        filesystem::FileSystem, is_compressed_map_path, map_file_extension, map_name,
+        record_backup_date_with_fs, save_with_notification,
     },
-    utils::{filesystem::FileSystem, save_settings_to_file_with_fs, save_with_notification},
 };
 
 /// Creates a backup snapshot when a map file is loaded, respecting the configured backup interval.
@@ -18,6 +27,11 @@ pub fn handle_on_load_backup_with_fs(
     fs: &impl FileSystem,
     current_date: chrono::DateTime<Local>,
 ) {
+    // Backups are compressed like the map file
+    let extension = map_file_extension(is_compressed_map_path(
+        &map_state.persistence.file_write_path,
+    ));
+
     // Extract configuration upfront to avoid multiple mutable borrows of map_state
     // throughout the function. All data needed for backup decision is pulled into
     // backup_config, or None if backups are disabled.
@@ -25,19 +39,14 @@ pub fn handle_on_load_backup_with_fs(
         &map_state.settings.backups_path,
         &map_state.settings.backups_interval,
     ) {
-        let filename = map_state
-            .persistence
-            .file_write_path
-            .file_stem()
-            .and_then(|name| name.to_str())
-            .unwrap_or("unknown");
+        let filename = map_name(&map_state.persistence.file_write_path);
 
         Some((
             PathBuf::from(backups_path.clone()),
             backups_interval,
-            filename.to_string(),
+            filename.clone(),
             current_date,
-            map_state.settings.backup_dates.get(filename).copied(),
+            map_state.settings.backup_dates.get(&filename).copied(),
         ))
     } else {
         None
@@ -60,7 +69,7 @@ pub fn handle_on_load_backup_with_fs(
                     filename,
                     date.format("%y-%m-%d")
                 ))
-                .with_extension("json");
+                .with_extension(extension);
 
             if save_with_notification(
                 map_state,
@@ -70,12 +79,18 @@ pub fn handle_on_load_backup_with_fs(
             )
             .is_ok()
             {
-                map_state.settings.backup_dates.insert(filename, date);
-                if save_settings_to_file_with_fs(&map_state.settings, fs).is_err() {
+                prune_backups(
+                    &backups_path,
+                    &filename,
+                    map_state.settings.backup_retention,
+                );
+
+                if record_backup_date_with_fs(&map_state.settings, &filename, date, fs).is_err() {
                     map_state
                         .ui_state
                         .set_notification(Notification::BackupRecordFail);
                 }
+                map_state.settings.backup_dates.insert(filename, date);
             }
         }
     }
@@ -89,24 +104,19 @@ pub fn handle_on_load_backup_with_fs(
 /// - Are triggered by elapsed time since the last runtime backup
 /// - Always makes a backup when called, interval handled outside
 pub fn handle_runtime_backup(map_state: &mut MapState) {
+    let extension = map_file_extension(is_compressed_map_path(
+        &map_state.persistence.file_write_path,
+    ));
+
     // Extract configuration upfront to avoid multiple mutable borrows of map_state
     let backup_config = if let (Some(backups_path), Some(_)) = (
         &map_state.settings.backups_path,
         &map_state.settings.runtime_backups_interval,
     ) {
-        let filename = map_state
-            .persistence
-            .file_write_path
-            .file_stem()
-            .and_then(|name| name.to_str())
-            .unwrap_or("unknown");
+        let filename = map_name(&map_state.persistence.file_write_path);
         let date = Local::now();
 
-        Some((
-            PathBuf::from(backups_path.clone()),
-            filename.to_string(),
-            date,
-        ))
+        Some((PathBuf::from(backups_path.clone()), filename, date))
     } else {
         None
     };
@@ -118,17 +128,128 @@ pub fn handle_runtime_backup(map_state: &mut MapState) {
                 filename,
                 date.format("%y-%m-%d-%H%M")
             ))
-            .with_extension("json");
+            .with_extension(extension);
 
-        let _ = save_with_notification(
+        if save_with_notification(
             map_state,
             &backups_file_path,
             Notification::BackupSuccess,
             Notification::BackupFail,
-        );
+        )
+        .is_ok()
+        {
+            prune_backups(
+                &backups_path,
+                &filename,
+                map_state.settings.backup_retention,
+            );
+        }
+    }
+}
+
+/// When the backup with the given file name was made, if it's a backup of the named map.
+///
+/// Read from the name rather than the file's timestamps, which copying or syncing the
+/// backups directory may change.
+pub fn backup_timestamp(file_name: &str, map_name: &str) -> Option<NaiveDateTime> {
+    let rest = file_name.strip_prefix(map_name)?.strip_prefix('-')?;
+    // Backups are compressed like their map, so there may be two extensions
+    let stamp = rest.split('.').next()?;
+
+    if let Some(date) = stamp.strip_prefix("load-backup-") {
+        NaiveDate::parse_from_str(date, "%y-%m-%d")
+            .ok()?
+            .and_hms_opt(0, 0, 0)
+    } else if let Some(date_time) = stamp.strip_prefix("session-backup-") {
+        NaiveDateTime::parse_from_str(date_time, "%y-%m-%d-%H%M").ok()
+    } else {
+        None
     }
 }
 
+/// Backups the retention policy doesn't keep, from a map's backups and when each was made.
+pub fn backups_to_prune(
+    mut backups: Vec<(PathBuf, NaiveDateTime)>,
+    retention: BackupRetention,
+) -> Vec<PathBuf> {
+    // Newest first, so each period keeps its most recent backup
+    backups.sort_by_key(|(_, time)| Reverse(*time));
+
+    let keep: Vec<bool> = match retention {
+        BackupRetention::KeepAll => return vec![],
+        BackupRetention::KeepLast(count) => (0..backups.len()).map(|i| i < count).collect(),
+        BackupRetention::Tiered => {
+            let mut keep = vec![false; backups.len()];
+            keep_newest_per_period(&backups, &mut keep, 7, |time| (time.year(), time.ordinal()));
+            keep_newest_per_period(&backups, &mut keep, 4, |time| {
+                (time.iso_week().year(), time.iso_week().week())
+            });
+            keep_newest_per_period(&backups, &mut keep, 12, |time| (time.year(), time.month()));
+            keep
+        }
+    };
+
+    backups
+        .into_iter()
+        .zip(keep)
+        .filter(|(_, keep)| !keep)
+        .map(|((path, _), _)| path)
+        .collect()
+}
+
+/// Marks the newest backup of each of the `periods` newest periods (days, weeks, ...)
+/// that have backups as kept. `backups` is sorted newest first.
+fn keep_newest_per_period(
+    backups: &[(PathBuf, NaiveDateTime)],
+    keep: &mut [bool],
+    periods: usize,
+    period_of: impl Fn(&NaiveDateTime) -> (i32, u32),
+) {
+    let mut seen = HashSet::new();
+    for (index, (_, time)) in backups.iter().enumerate() {
+        if seen.len() == periods {
+            break;
+        }
+        if seen.insert(period_of(time)) {
+            keep[index] = true;
+        }
+    }
+}
+
+/// The named map's backups in the backups directory and when each was made, newest
+/// first. Empty if the directory can't be listed.
+pub fn list_backups(backups_path: &Path, map_name: &str) -> Vec<(PathBuf, NaiveDateTime)> {
+    let Ok(entries) = fs::read_dir(backups_path) else {
+        return vec![];
+    };
+
+    let mut backups: Vec<(PathBuf, NaiveDateTime)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let time = backup_timestamp(&entry.file_name().to_string_lossy(), map_name)?;
+            Some((entry.path(), time))
+        })
+        .collect();
+    backups.sort_by_key(|(_, time)| Reverse(*time));
+    backups
+}
+
+/// Deletes the named map's backups in the backups directory that the retention policy
+/// doesn't keep. Returns how many were deleted.
+///
+/// Best effort: backups that can't be listed or deleted are left alone, as they don't
+/// affect the backup just made.
+pub fn prune_backups(backups_path: &Path, map_name: &str, retention: BackupRetention) -> usize {
+    if retention == BackupRetention::KeepAll {
+        return 0;
+    }
+
+    backups_to_prune(list_backups(backups_path, map_name), retention)
+        .iter()
+        .filter(|path| fs::remove_file(path).is_ok())
+        .count()
+}
+
 /// Converts a BackupsInterval enum to its equivalent chrono::Duration for date arithmetic.
 pub fn get_duration(interval: &BackupsInterval) -> ChronoDuration {
     match interval {
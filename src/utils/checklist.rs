@@ -0,0 +1,55 @@
+/// Bullets a checklist item may start with before its checkbox
+const ITEM_BULLETS: [&str; 2] = ["- ", "* "];
+
+/// Byte offset in `line` of the mark between the brackets of a checklist item's
+/// checkbox, and whether it's checked.
+///
+/// A checklist item is a line starting with `[ ]` (open) or `[x]` / `[X]` (done),
+/// optionally indented and after a `- ` or `* ` bullet.
+fn checkbox_mark(line: &str) -> Option<(usize, bool)> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    let bullet = ITEM_BULLETS
+        .iter()
+        .find(|bullet| rest.starts_with(**bullet))
+        .map_or(0, |bullet| bullet.len());
+
+    let checkbox = rest[bullet..].get(..3)?;
+    let checked = match checkbox {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    Some((indent + bullet + 1, checked))
+}
+
+/// Number of done and of all checklist items in a note, None if it has none.
+pub fn checklist_progress(content: &str) -> Option<(usize, usize)> {
+    let (done, total) = content
+        .lines()
+        .filter_map(checkbox_mark)
+        .fold((0, 0), |(done, total), (_, checked)| {
+            (done + usize::from(checked), total + 1)
+        });
+    (total > 0).then_some((done, total))
+}
+
+/// Checks or unchecks the checklist item on the line with the byte offset `cursor_pos`.
+/// Returns false if that line isn't a checklist item.
+///
+/// The content keeps its length, so the cursor stays where it was.
+pub fn toggle_checkbox(content: &mut String, cursor_pos: usize) -> bool {
+    let line_start = content[..cursor_pos]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let line_end = content[line_start..]
+        .find('\n')
+        .map_or(content.len(), |index| line_start + index);
+
+    let Some((mark, checked)) = checkbox_mark(&content[line_start..line_end]) else {
+        return false;
+    };
+    let mark = line_start + mark;
+    content.replace_range(mark..mark + 1, if checked { " " } else { "x" });
+    true
+}
@@ -0,0 +1,54 @@
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+use crate::utils::{MapData, TmmprError, read_json_data};
+
+/// Key of the checksum in map files, see [`ChecksummedMapData`]
+pub const CHECKSUM_KEY: &str = "checksum";
+
+/// Map data as written to a plain map file: with a checksum of the rest of the file
+/// added, to notice files that were cut short or changed by another program.
+#[derive(Serialize)]
+pub struct ChecksummedMapData<'a> {
+    #[serde(flatten)]
+    map_data: &'a MapData,
+    checksum: String,
+}
+
+impl<'a> ChecksummedMapData<'a> {
+    pub fn new(map_data: &'a MapData) -> Result<Self, TmmprError> {
+        Ok(Self {
+            map_data,
+            checksum: map_checksum(serde_json::to_value(map_data)?),
+        })
+    }
+}
+
+/// SHA-256 of a map file's JSON without its checksum, as hex.
+///
+/// Hashed in a canonical form (keys sorted, no whitespace), so it doesn't depend on
+/// the order the notes were written in, or on whether the file is pretty-printed,
+/// compact or compressed.
+pub fn map_checksum(mut map_json: Value) -> String {
+    if let Value::Object(fields) = &mut map_json {
+        fields.remove(CHECKSUM_KEY);
+    }
+    Sha256::digest(map_json.to_string().as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Whether a plain map file's contents still match the checksum saved with them; false
+/// for files cut short or changed by another program since. Files without a checksum
+/// (written by older versions or other tools) always match.
+pub fn verify_map_checksum(path: &Path) -> Result<bool, TmmprError> {
+    let map_json: Value = read_json_data(path)?;
+    let Some(saved) = map_json.get(CHECKSUM_KEY).and_then(Value::as_str) else {
+        return Ok(true);
+    };
+    let saved = saved.to_string();
+    Ok(map_checksum(map_json) == saved)
+}
@@ -0,0 +1,164 @@
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use chacha20poly1305::{
+    AeadCore, ChaCha20Poly1305, KeyInit,
+    aead::{Aead, OsRng, rand_core::RngCore},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// PBKDF2 rounds used for newly encrypted data.
+///
+/// The round count is stored alongside every ciphertext, so it can be raised later
+/// without breaking existing files. Tests use a low count to stay fast in debug builds.
+pub const KDF_ROUNDS: u32 = if cfg!(test) { 1_000 } else { 210_000 };
+
+pub const SALT_LEN: usize = 16;
+
+/// Shown in place of an encrypted note's text wherever it would appear in plain form.
+pub const ENCRYPTED_LABEL: &str = "[encrypted]";
+
+/// Derived 256-bit symmetric key.
+pub type Key = [u8; 32];
+
+#[derive(PartialEq, Debug)]
+pub enum CryptoError {
+    /// Stored data is not valid base64 or has the wrong length
+    Decode,
+    /// Authentication failed: wrong passphrase or tampered data
+    WrongPassphrase,
+}
+
+/// Ciphertext together with everything needed to decrypt it given the passphrase.
+///
+/// All binary fields are base64 encoded so the value can live inside the JSON map file.
+#[derive(PartialEq, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+pub struct EncryptedText {
+    pub salt: String,
+    pub rounds: u32,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+impl EncryptedText {
+    /// Decoded salt bytes, used to look up or derive the matching key.
+    pub fn salt_bytes(&self) -> Result<Vec<u8>, CryptoError> {
+        BASE64.decode(&self.salt).map_err(|_| CryptoError::Decode)
+    }
+}
+
+/// Generates a random salt for key derivation.
+pub fn new_salt() -> Vec<u8> {
+    let mut salt = vec![0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+/// Derives a key from a passphrase with PBKDF2-HMAC-SHA256.
+pub fn derive_key(passphrase: &str, salt: &[u8], rounds: u32) -> Key {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, rounds, &mut key);
+    key
+}
+
+/// Encrypts `plaintext` with an already derived key.
+///
+/// `salt` and `rounds` must be the values the key was derived with; they are stored
+/// in the result so the key can be derived again from the passphrase later.
+pub fn encrypt_with_key(plaintext: &str, key: &Key, salt: &[u8], rounds: u32) -> EncryptedText {
+    let cipher = ChaCha20Poly1305::new(key.into());
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .expect("Bug: encrypting an in-memory buffer cannot fail");
+
+    EncryptedText {
+        salt: BASE64.encode(salt),
+        rounds,
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    }
+}
+
+/// Decrypts data produced by [`encrypt_with_key`].
+pub fn decrypt_with_key(encrypted: &EncryptedText, key: &Key) -> Result<String, CryptoError> {
+    let nonce = BASE64
+        .decode(&encrypted.nonce)
+        .map_err(|_| CryptoError::Decode)?;
+    let ciphertext = BASE64
+        .decode(&encrypted.ciphertext)
+        .map_err(|_| CryptoError::Decode)?;
+
+    if nonce.len() != 12 {
+        return Err(CryptoError::Decode);
+    }
+
+    let cipher = ChaCha20Poly1305::new(key.into());
+    let plaintext = cipher
+        .decrypt(nonce.as_slice().into(), ciphertext.as_slice())
+        .map_err(|_| CryptoError::WrongPassphrase)?;
+
+    String::from_utf8(plaintext).map_err(|_| CryptoError::Decode)
+}
+
+/// Convenience wrapper: derives a fresh key and encrypts in one go.
+pub fn encrypt(plaintext: &str, passphrase: &str) -> EncryptedText {
+    let salt = new_salt();
+    let key = derive_key(passphrase, &salt, KDF_ROUNDS);
+    encrypt_with_key(plaintext, &key, &salt, KDF_ROUNDS)
+}
+
+/// Convenience wrapper: derives the key from the passphrase and decrypts.
+pub fn decrypt(encrypted: &EncryptedText, passphrase: &str) -> Result<String, CryptoError> {
+    let salt = encrypted.salt_bytes()?;
+    let key = derive_key(passphrase, &salt, encrypted.rounds);
+    decrypt_with_key(encrypted, &key)
+}
+
+/// A key derived from a passphrase, kept for the session to encrypt a whole file on
+/// every save without running the KDF again.
+#[derive(PartialEq, Clone, Debug)]
+pub struct FileKey {
+    key: Key,
+    salt: Vec<u8>,
+    rounds: u32,
+}
+
+impl FileKey {
+    /// Derives a key with a fresh salt, for a file that isn't encrypted yet.
+    pub fn new(passphrase: &str) -> FileKey {
+        let salt = new_salt();
+        FileKey {
+            key: derive_key(passphrase, &salt, KDF_ROUNDS),
+            salt,
+            rounds: KDF_ROUNDS,
+        }
+    }
+
+    /// Derives the key `encrypted` was encrypted with and decrypts it. Fails if the
+    /// passphrase is wrong.
+    pub fn unlock(
+        passphrase: &str,
+        encrypted: &EncryptedText,
+    ) -> Result<(FileKey, String), CryptoError> {
+        let salt = encrypted.salt_bytes()?;
+        let file_key = FileKey {
+            key: derive_key(passphrase, &salt, encrypted.rounds),
+            salt,
+            rounds: encrypted.rounds,
+        };
+        let plaintext = file_key.decrypt(encrypted)?;
+        Ok((file_key, plaintext))
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> EncryptedText {
+        encrypt_with_key(plaintext, &self.key, &self.salt, self.rounds)
+    }
+
+    /// Decrypts data encrypted with this key. Data encrypted under another salt (e.g.
+    /// by another instance that encrypted the file anew) fails like a wrong passphrase.
+    pub fn decrypt(&self, encrypted: &EncryptedText) -> Result<String, CryptoError> {
+        decrypt_with_key(encrypted, &self.key)
+    }
+}
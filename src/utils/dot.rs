@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use crate::{
+    states::map::{Connection, Note},
+    utils::ENCRYPTED_LABEL,
+};
+
+/// Writes the map as a Graphviz `digraph` named `title`, for `dot` and the other
+/// Graphviz tools.
+///
+/// Every note becomes a node `n<id>` labeled with its title, or its content if it has
+/// none, in id order. Every complete connection becomes an edge in its direction,
+/// labeled with its weight when one is set. Encrypted notes are labeled `[encrypted]`.
+pub fn export_dot(title: &str, notes: &HashMap<usize, Note>, connections: &[Connection]) -> String {
+    let mut ids: Vec<usize> = notes.keys().copied().collect();
+    ids.sort_unstable();
+
+    let mut lines = vec![
+        format!("digraph \"{}\" {{", dot_string(title)),
+        String::from("    node [shape=box];"),
+    ];
+    for id in ids {
+        let note = &notes[&id];
+        let label = match &note.title {
+            Some(title) => title.as_str(),
+            None if note.encrypted.is_some() => ENCRYPTED_LABEL,
+            None => note.content.as_str(),
+        };
+        lines.push(format!("    n{id} [label=\"{}\"];", dot_string(label)));
+    }
+
+    for connection in connections {
+        let to_id = connection.to_id;
+        if !notes.contains_key(&connection.from_id) || !notes.contains_key(&to_id) {
+            continue;
+        }
+
+        let edge = format!("    n{} -> n{to_id}", connection.from_id);
+        lines.push(match connection.weight {
+            Some(weight) => format!("{edge} [label=\"{weight}\"];"),
+            None => format!("{edge};"),
+        });
+    }
+
+    lines.push(String::from("}"));
+    lines.join("\n") + "\n"
+}
+
+/// Escapes text for a quoted DOT string: quotes and backslashes are escaped and line
+/// breaks become `\n`, which Graphviz draws as centered lines.
+pub fn dot_string(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
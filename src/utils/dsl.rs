@@ -0,0 +1,158 @@
+use ratatui::style::Color;
+use std::collections::{HashMap, VecDeque};
+
+use crate::states::map::Note;
+
+/// Horizontal gap between layers of laid out notes
+const LAYER_GAP: usize = 6;
+/// Vertical gap between notes in the same layer
+const ROW_GAP: usize = 2;
+
+/// A small graph described in the quick-entry DSL.
+///
+/// The DSL is line based:
+/// - `A -> B -> C` creates notes A, B and C connected in a chain
+/// - `A` on its own creates a single note
+/// - `\n` inside a label becomes a line break
+/// - blank lines and lines starting with `#` are ignored
+///
+/// Notes are identified by their label, so the same label always refers to the same note.
+#[derive(PartialEq, Debug)]
+pub struct DslGraph {
+    /// Note labels in order of first appearance
+    pub labels: Vec<String>,
+    /// Connections as (from, to) indices into `labels`
+    pub edges: Vec<(usize, usize)>,
+}
+
+/// The DSL text couldn't be parsed.
+#[derive(PartialEq, Debug)]
+pub enum DslError {
+    /// Nothing to create (no notes in the text)
+    Empty,
+    /// An arrow with a missing label on either side, 1-based line number
+    MissingLabel(usize),
+}
+
+/// Parses quick-entry DSL text into a graph.
+pub fn parse_dsl(text: &str) -> Result<DslGraph, DslError> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut label_ids: HashMap<String, usize> = HashMap::new();
+    let mut edges = Vec::new();
+
+    for (line_number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut previous: Option<usize> = None;
+        for label in line.split("->") {
+            let label = label.trim().replace("\\n", "\n");
+            if label.is_empty() {
+                return Err(DslError::MissingLabel(line_number + 1));
+            }
+
+            let id = *label_ids.entry(label.clone()).or_insert_with(|| {
+                labels.push(label);
+                labels.len() - 1
+            });
+
+            if let Some(from) = previous
+                && !edges.contains(&(from, id))
+            {
+                edges.push((from, id));
+            }
+            previous = Some(id);
+        }
+    }
+
+    if labels.is_empty() {
+        return Err(DslError::Empty);
+    }
+
+    Ok(DslGraph { labels, edges })
+}
+
+impl DslGraph {
+    /// Computes positions for every note, relative to the top-left corner of the pasted block.
+    ///
+    /// Notes are arranged left to right in layers by their distance from a root
+    /// (a note with no incoming connections). Notes only reachable through a cycle
+    /// start a layer of their own at the left edge.
+    pub fn layout(&self) -> Vec<(usize, usize)> {
+        let count = self.labels.len();
+
+        let mut has_incoming = vec![false; count];
+        let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); count];
+        for &(from, to) in &self.edges {
+            has_incoming[to] = true;
+            outgoing[from].push(to);
+        }
+
+        // Breadth-first from every root assigns each note the shortest distance to one
+        let mut layer_of: Vec<Option<usize>> = vec![None; count];
+        let mut queue = VecDeque::new();
+        let roots = (0..count).filter(|&id| !has_incoming[id]);
+        // Notes that are part of cycles with no root get visited in label order afterwards
+        for start in roots.chain(0..count) {
+            if layer_of[start].is_some() {
+                continue;
+            }
+            layer_of[start] = Some(0);
+            queue.push_back(start);
+
+            while let Some(id) = queue.pop_front() {
+                let next_layer = layer_of[id].unwrap_or(0) + 1;
+                for &next in &outgoing[id] {
+                    if layer_of[next].is_none() {
+                        layer_of[next] = Some(next_layer);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        let dimensions: Vec<(usize, usize)> = self
+            .labels
+            .iter()
+            .map(|label| {
+                let (width, height) = Note::new(0, 0, label.clone(), Color::White).get_dimensions();
+                (width as usize, height as usize)
+            })
+            .collect();
+
+        let layer_count = layer_of.iter().flatten().max().map_or(0, |max| max + 1);
+
+        // Each layer is as wide as its widest note
+        let mut layer_x = vec![0; layer_count];
+        let mut next_x = 0;
+        for (layer, x) in layer_x.iter_mut().enumerate() {
+            *x = next_x;
+            let layer_width = (0..count)
+                .filter(|&id| layer_of[id] == Some(layer))
+                .map(|id| dimensions[id].0)
+                .max()
+                .unwrap_or(0);
+            next_x += layer_width + LAYER_GAP;
+        }
+
+        let mut next_y = vec![0; layer_count];
+        (0..count)
+            .map(|id| {
+                let layer = layer_of[id].unwrap_or(0);
+                let y = next_y[layer];
+                next_y[layer] += dimensions[id].1 + ROW_GAP;
+                (layer_x[layer], y)
+            })
+            .collect()
+    }
+}
+
+/// Reads text from the system clipboard.
+///
+/// Returns None when there's no clipboard available (e.g. no display server)
+/// or it doesn't contain text.
+pub fn read_clipboard_text() -> Option<String> {
+    arboard::Clipboard::new().ok()?.get_text().ok()
+}
@@ -0,0 +1,122 @@
+use crossterm::{cursor::SetCursorStyle, execute};
+use std::{
+    fs,
+    io::stdout,
+    panic::{self, PanicHookInfo},
+    path::{Path, PathBuf},
+    thread,
+};
+
+use crate::{
+    input::{disable_bracketed_paste, disable_keyboard_enhancement},
+    states::MapState,
+    utils::{
+        FileKey, FileSystem, MapData, MapFileContents, TmmprError, decrypt_map_data, read_map_file,
+        write_map_copy,
+    },
+};
+
+/// Where issues are reported, printed with the crash report
+const ISSUES_URL: &str = "https://github.com/tanciaku/tmmpr/issues";
+
+/// Path the open map is saved to when tmmpr crashes: the map file's name with
+/// `.recovery` appended, in the same directory.
+pub fn emergency_save_path(map_path: &Path) -> PathBuf {
+    let mut file_name = map_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".recovery");
+    map_path.with_file_name(file_name)
+}
+
+/// Saves the map's unsaved changes next to its file (see [`emergency_save_path`]),
+/// leaving the map file itself as it was. Maps encrypted at rest are written encrypted
+/// with the same passphrase.
+///
+/// None if there was nothing unsaved.
+pub fn emergency_save(map_state: &mut MapState) -> Option<Result<PathBuf, TmmprError>> {
+    if !map_state.persistence.has_unsaved_changes {
+        return None;
+    }
+    let path = emergency_save_path(&map_state.persistence.file_write_path);
+    Some(write_map_copy(map_state, &path).map(|_| path))
+}
+
+/// Maps in the list that were left with an emergency save by a crash, in list order.
+pub fn find_emergency_saves_with_fs<'a>(
+    map_paths: impl IntoIterator<Item = &'a PathBuf>,
+    fs: &dyn FileSystem,
+) -> Vec<PathBuf> {
+    map_paths
+        .into_iter()
+        .filter(|path| fs.path_exists(&emergency_save_path(path)))
+        .cloned()
+        .collect()
+}
+
+/// Reads the map's emergency save. One of a map encrypted at rest is decrypted with the
+/// key the map was opened with.
+pub fn read_emergency_save(
+    map_path: &Path,
+    file_key: Option<&FileKey>,
+) -> Result<MapData, TmmprError> {
+    match read_map_file(&emergency_save_path(map_path))? {
+        MapFileContents::Plain(map_data) => Ok(*map_data),
+        MapFileContents::Encrypted(encrypted) => {
+            let file_key = file_key.ok_or_else(|| {
+                TmmprError::Validation(String::from(
+                    "the recovery file is encrypted, but the map isn't",
+                ))
+            })?;
+            decrypt_map_data(&encrypted, file_key).map_err(|_| {
+                TmmprError::Validation(String::from(
+                    "the recovery file can't be decrypted with the map's passphrase",
+                ))
+            })
+        }
+    }
+}
+
+/// Deletes the map's emergency save, once it's been restored or isn't wanted.
+pub fn discard_emergency_save(map_path: &Path) -> Result<(), TmmprError> {
+    fs::remove_file(emergency_save_path(map_path))?;
+    Ok(())
+}
+
+/// Restores the terminal before the panic message is printed, so it isn't garbled by
+/// raw mode or the alternate screen, then runs the hook that was installed before
+/// (`color_eyre`'s report).
+///
+/// Only panics on the main thread are treated as a crash; a background save that
+/// panics is reported as a failed save instead.
+pub fn install_panic_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info: &PanicHookInfo| {
+        if thread::current().name() == Some("main") {
+            disable_keyboard_enhancement();
+            disable_bracketed_paste();
+            ratatui::restore();
+            let _ = execute!(stdout(), SetCursorStyle::DefaultUserShape);
+            previous(info);
+        }
+    }));
+}
+
+/// What's printed after the panic message once the terminal has been restored, with
+/// what happened to the unsaved changes (see [`emergency_save`]).
+pub fn crash_report(saved: Option<&Result<PathBuf, TmmprError>>) -> String {
+    let mut report = String::from("tmmpr ran into a bug and had to close.\n");
+    match saved {
+        Some(Ok(path)) => report.push_str(&format!(
+            "Your unsaved changes were saved to {}; the map file itself wasn't changed.\n\
+             tmmpr offers to restore them the next time it starts.\n",
+            path.display()
+        )),
+        Some(Err(err)) => {
+            report.push_str(&format!("Your unsaved changes couldn't be saved: {err}\n"))
+        }
+        None => report.push_str("There were no unsaved changes.\n"),
+    }
+    report.push_str(&format!(
+        "Please report it at {ISSUES_URL}, along with the message above.\n"
+    ));
+    report
+}
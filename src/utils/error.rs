@@ -0,0 +1,62 @@
+use std::fmt;
+
+/// Error of the functions that read and write tmmpr's files (maps, backups,
+/// snapshots, milestones, settings), so callers can tell the causes apart.
+#[derive(Debug)]
+pub enum TmmprError {
+    /// Reading or writing a file or directory failed
+    Io(std::io::Error),
+    /// A file isn't JSON (after decompressing), or not of the expected structure
+    Parse(serde_json::Error),
+    /// A value in a file isn't of the expected structure; `field` is where it is, e.g.
+    /// `notes.3.color` (see the schema in `schema/map.schema.json` for maps)
+    Field {
+        field: String,
+        error: serde_json::Error,
+    },
+    /// Something the operation depends on is missing or unusable, such as the home
+    /// directory the config directory is kept in
+    Validation(String),
+}
+
+impl fmt::Display for TmmprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TmmprError::Io(err) => write!(f, "file error: {err}"),
+            TmmprError::Parse(err) => write!(f, "invalid file contents: {err}"),
+            TmmprError::Field { field, error } => {
+                write!(f, "invalid file contents at `{field}`: {error}")
+            }
+            TmmprError::Validation(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for TmmprError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TmmprError::Io(err) => Some(err),
+            TmmprError::Parse(err) => Some(err),
+            TmmprError::Field { error, .. } => Some(error),
+            TmmprError::Validation(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for TmmprError {
+    fn from(err: std::io::Error) -> Self {
+        TmmprError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for TmmprError {
+    fn from(err: serde_json::Error) -> Self {
+        TmmprError::Parse(err)
+    }
+}
+
+impl From<tempfile::PersistError> for TmmprError {
+    fn from(err: tempfile::PersistError) -> Self {
+        TmmprError::Io(err.error)
+    }
+}
@@ -1,4 +1,16 @@
-use std::{fs, path::Path};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use std::{
+    fs::{self, File},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use tempfile::NamedTempFile;
+
+use crate::utils::TmmprError;
+
+/// First bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
 
 #[derive(PartialEq, Debug)]
 pub enum IoErrorKind {
@@ -9,7 +21,7 @@ pub enum IoErrorKind {
 }
 
 /// Writes data as pretty-printed JSON, overwriting if file exists.
-pub fn write_json_data<T>(path: &Path, data: &T) -> Result<(), Box<dyn std::error::Error>>
+pub fn write_json_data<T>(path: &Path, data: &T) -> Result<(), TmmprError>
 where
     T: serde::Serialize,
 {
@@ -18,12 +30,112 @@ where
     Ok(())
 }
 
+/// How [`write_json_data_buffered`] lays out the JSON it writes.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum JsonFormat {
+    /// Indented, one value per line
+    Pretty,
+    /// Without any whitespace
+    Compact,
+    /// Compact and gzip-compressed
+    Compressed,
+}
+
+/// Writes data as JSON in the given format, overwriting if file exists.
+///
+/// The data is serialized into `buffer` first, which is cleared but keeps its
+/// capacity, so repeated writes of large data don't reallocate it every time.
+pub fn write_json_data_buffered<T>(
+    path: &Path,
+    data: &T,
+    format: JsonFormat,
+    buffer: &mut Vec<u8>,
+) -> Result<(), TmmprError>
+where
+    T: serde::Serialize,
+{
+    buffer.clear();
+    match format {
+        JsonFormat::Pretty => serde_json::to_writer_pretty(&mut *buffer, data)?,
+        JsonFormat::Compact => serde_json::to_writer(&mut *buffer, data)?,
+        JsonFormat::Compressed => {
+            let mut encoder = GzEncoder::new(&mut *buffer, Compression::default());
+            serde_json::to_writer(&mut encoder, data)?;
+            encoder.flush()?;
+            encoder.finish()?;
+        }
+    }
+    fs::write(path, &buffer)?;
+    Ok(())
+}
+
 /// Reads and deserializes JSON data from file.
-pub fn read_json_data<T>(path: &Path) -> Result<T, Box<dyn std::error::Error>>
+///
+/// Gzip-compressed files (see [`JsonFormat::Compressed`]) are decompressed
+/// transparently, whatever their name.
+pub fn read_json_data<T>(path: &Path) -> Result<T, TmmprError>
 where
     T: serde::de::DeserializeOwned,
 {
-    let json_string = fs::read_to_string(path)?;
-    let data: T = serde_json::from_str(&json_string)?;
+    let mut bytes = fs::read(path)?;
+
+    if bytes.starts_with(&GZIP_MAGIC) {
+        let mut json_bytes = Vec::new();
+        GzDecoder::new(bytes.as_slice()).read_to_end(&mut json_bytes)?;
+        bytes = json_bytes;
+    }
+
+    // Text that isn't UTF-8 is a parse error like any other invalid JSON. The path to
+    // the value that failed is kept, so a hand-edited file can be fixed
+    let mut deserializer = serde_json::Deserializer::from_slice(&bytes);
+    let data: T = serde_path_to_error::deserialize(&mut deserializer).map_err(|err| {
+        let field = err.path().to_string();
+        let error = err.into_inner();
+        // Syntax errors and missing fields of the file itself have no field to point at
+        if field == "." || !error.is_data() {
+            TmmprError::Parse(error)
+        } else {
+            TmmprError::Field { field, error }
+        }
+    })?;
+    deserializer.end()?;
+    Ok(data)
+}
+
+/// Last modification time of a file, or None if it doesn't exist or can't be read.
+pub fn file_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Read-modify-write of a JSON file that several running instances share.
+///
+/// An exclusive lock on `<path>.lock` is held while the file is read, passed to
+/// `update` and written back, so concurrent instances apply their changes one after
+/// another instead of overwriting each other's. `update` gets None if the file is
+/// missing or unreadable. The new contents replace the file atomically and are returned.
+pub fn update_json_data<T, F>(path: &Path, update: F) -> Result<T, TmmprError>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+    F: FnOnce(Option<T>) -> T,
+{
+    let mut lock_path = PathBuf::from(path);
+    lock_path.as_mut_os_string().push(".lock");
+    let lock_file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path)?;
+    lock_file.lock()?;
+
+    let data = update(read_json_data(path).ok());
+
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+    let mut temp_file = NamedTempFile::new_in(dir.unwrap_or(Path::new(".")))?;
+    serde_json::to_writer_pretty(&mut temp_file, &data)?;
+    temp_file.persist(path)?;
+
+    // Lock is released when `lock_file` is dropped
     Ok(data)
 }
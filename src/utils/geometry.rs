@@ -1,4 +1,10 @@
-use crate::states::map::{Note, Side};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    graph::routing::{Obstacle, Orthogonal, RoutingStrategy},
+    states::map::{Connection, Note, Side, SignedRect},
+};
 
 /// A 2D point in the coordinate space.
 ///
@@ -19,7 +25,7 @@ use crate::states::map::{Note, Side};
 /// let origin = Point { x: 0, y: 0 };
 /// let bottom_right = Point { x: 100, y: 50 };
 /// ```
-#[derive(Clone, Copy)]
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, PartialEq, Debug)]
 pub struct Point {
     pub x: isize,
     pub y: isize,
@@ -143,18 +149,38 @@ pub fn calculate_path(
     end_note: &Note,
     end_side: Side,
 ) -> Vec<Point> {
-    let start_tuple = start_note.get_connection_point(start_side);
-    let end_tuple = end_note.get_connection_point(end_side);
+    calculate_path_with(&Orthogonal, start_note, start_side, end_note, end_side, &[])
+}
 
-    let start = Point {
-        x: start_tuple.0 as isize,
-        y: start_tuple.1 as isize,
-    };
-    let end = Point {
-        x: end_tuple.0 as isize,
-        y: end_tuple.1 as isize,
-    };
+/// Calculates a connection path between two notes using the given routing strategy.
+///
+/// [`calculate_path`] is this with [`Orthogonal`] routing and no obstacles.
+/// `obstacles` are only looked at by strategies that route around things.
+pub fn calculate_path_with(
+    strategy: &dyn RoutingStrategy,
+    start_note: &Note,
+    start_side: Side,
+    end_note: &Note,
+    end_side: Side,
+    obstacles: &[Obstacle],
+) -> Vec<Point> {
+    let start = connection_point(start_note, start_side);
+    let end = connection_point(end_note, end_side);
 
+    strategy.route(start, start_side, end, end_side, obstacles)
+}
+
+/// The middle of a note's edge on that side, where its connections meet it.
+pub fn connection_point(note: &Note, side: Side) -> Point {
+    let (x, y) = note.get_connection_point(side);
+    Point { x, y }
+}
+
+/// The shape-based path between two connection points behind [`calculate_path`].
+///
+/// `start` and `end` are points on the edges of the notes, leaving through `start_side`
+/// and entering through `end_side`.
+pub fn orthogonal_path(start: Point, start_side: Side, end: Point, end_side: Side) -> Vec<Point> {
     // Offset points extend 2 units away from note edges for visual clearance
     let start_off = get_offset_point(start, start_side);
     let end_off = get_offset_point(end, end_side);
@@ -539,6 +565,467 @@ pub fn calculate_path(
     points
 }
 
+/// Calculates a connection path that passes through user-placed waypoints.
+///
+/// Without waypoints this is the same as [`calculate_path`]. Otherwise the path leaves
+/// the start note through its offset point, visits every waypoint in order and enters
+/// the end note through its offset point. Consecutive points that aren't aligned are
+/// joined with an elbow (horizontal first), so the path stays made of horizontal and
+/// vertical segments.
+pub fn calculate_path_with_waypoints(
+    start_note: &Note,
+    start_side: Side,
+    end_note: &Note,
+    end_side: Side,
+    waypoints: &[Point],
+) -> Vec<Point> {
+    if waypoints.is_empty() {
+        return calculate_path(start_note, start_side, end_note, end_side);
+    }
+
+    let start = connection_point(start_note, start_side);
+    let end = connection_point(end_note, end_side);
+
+    let start_off = get_offset_point(start, start_side);
+    let end_off = get_offset_point(end, end_side);
+
+    let mut points = vec![start, start_off];
+    for &target in waypoints.iter().chain([&end_off]) {
+        let last = *points.last().expect("path starts with two points");
+        if last.x != target.x && last.y != target.y {
+            points.push(Point {
+                x: target.x,
+                y: last.y,
+            });
+        }
+        points.push(target);
+    }
+    points.push(end);
+
+    points
+}
+
+/// Calculates the path of a connection between its notes, drawn `offset` cells aside
+/// like [`calculate_parallel_path`]. A connection from a note back to itself without
+/// waypoints is drawn as a [`self_loop_path`].
+pub fn connection_path(
+    connection: &Connection,
+    start_note: &Note,
+    end_note: &Note,
+    offset: isize,
+) -> Vec<Point> {
+    if connection.is_self_loop() && connection.waypoints.is_empty() {
+        return self_loop_path(start_note, connection.from_side, offset);
+    }
+
+    let (path, _) = calculate_parallel_path(
+        start_note,
+        connection.from_side,
+        end_note,
+        connection.to_side,
+        &connection.waypoints,
+        offset,
+    );
+    path
+}
+
+/// Where a connection leaves its start note and enters its end note, as
+/// drawn by [`connection_path`].
+pub fn connection_ends(
+    connection: &Connection,
+    start_note: &Note,
+    end_note: &Note,
+    offset: isize,
+) -> (Point, Point) {
+    let end_side = connection.to_side;
+    if connection.is_self_loop() && connection.waypoints.is_empty() {
+        let path = self_loop_path(start_note, connection.from_side, offset);
+        return (path[0], path[path.len() - 1]);
+    }
+
+    let offset = fitting_offset(start_note, connection.from_side, end_note, end_side, offset);
+    offset_ends(start_note, connection.from_side, end_note, end_side, offset)
+}
+
+/// The path of a connection from a note back to itself: a small loop out of the
+/// note's edge on `side`, leaving just before its middle and coming back in at the
+/// middle. Loops with a larger `offset` (either way) reach further out, around the
+/// smaller ones.
+pub fn self_loop_path(note: &Note, side: Side, offset: isize) -> Vec<Point> {
+    let end = connection_point(note, side);
+    let reach = 2 + 2 * offset.abs();
+    let (start, out) = match side {
+        Side::Right => (
+            Point {
+                y: end.y - 1,
+                ..end
+            },
+            Point { x: reach, y: 0 },
+        ),
+        Side::Left => (
+            Point {
+                y: end.y - 1,
+                ..end
+            },
+            Point { x: -reach, y: 0 },
+        ),
+        Side::Top => (
+            Point {
+                x: end.x - 2,
+                ..end
+            },
+            Point { x: 0, y: -reach },
+        ),
+        Side::Bottom => (
+            Point {
+                x: end.x - 2,
+                ..end
+            },
+            Point { x: 0, y: reach },
+        ),
+    };
+
+    vec![
+        start,
+        Point {
+            x: start.x + out.x,
+            y: start.y + out.y,
+        },
+        Point {
+            x: end.x + out.x,
+            y: end.y + out.y,
+        },
+        end,
+    ]
+}
+
+/// Calculates the path of a connection drawn `offset` cells aside (see [`offset_path`]),
+/// so it doesn't cover other connections between the same two notes.
+///
+/// The offset is brought closer to 0 until both ends of the path stay on their note
+/// edges, between the corners. Returns the path and the offset it was drawn with.
+pub fn calculate_parallel_path(
+    start_note: &Note,
+    start_side: Side,
+    end_note: &Note,
+    end_side: Side,
+    waypoints: &[Point],
+    offset: isize,
+) -> (Vec<Point>, isize) {
+    let path = calculate_path_with_waypoints(start_note, start_side, end_note, end_side, waypoints);
+    let offset = fitting_offset(start_note, start_side, end_note, end_side, offset);
+
+    (offset_path(&path, offset), offset)
+}
+
+/// The offset closest to `offset` (and not further from 0) that a connection between the
+/// two notes can be drawn aside with, keeping both of its ends on their note edges.
+pub fn fitting_offset(
+    start_note: &Note,
+    start_side: Side,
+    end_note: &Note,
+    end_side: Side,
+    offset: isize,
+) -> isize {
+    let mut offset = offset;
+    while offset != 0 {
+        let (start, end) = offset_ends(start_note, start_side, end_note, end_side, offset);
+        if on_edge(start_note, start_side, start) && on_edge(end_note, end_side, end) {
+            break;
+        }
+        offset -= offset.signum();
+    }
+    offset
+}
+
+/// Where a connection drawn `offset` cells aside leaves its start note and enters its
+/// end note: the connection points moved along the note edges.
+pub fn offset_ends(
+    start_note: &Note,
+    start_side: Side,
+    end_note: &Note,
+    end_side: Side,
+    offset: isize,
+) -> (Point, Point) {
+    let start = connection_point(start_note, start_side);
+    let end = connection_point(end_note, end_side);
+    let outward = |side| match side {
+        Side::Right => Point { x: 1, y: 0 },
+        Side::Left => Point { x: -1, y: 0 },
+        Side::Top => Point { x: 0, y: -1 },
+        Side::Bottom => Point { x: 0, y: 1 },
+    };
+    let leaving = outward(start_side);
+    let out_of_end = outward(end_side);
+    let entering = Point {
+        x: -out_of_end.x,
+        y: -out_of_end.y,
+    };
+
+    let start_shift = aside(leaving, offset);
+    let end_shift = aside(entering, offset);
+    (
+        Point {
+            x: start.x + start_shift.x,
+            y: start.y + start_shift.y,
+        },
+        Point {
+            x: end.x + end_shift.x,
+            y: end.y + end_shift.y,
+        },
+    )
+}
+
+/// Moves every segment of an orthogonal path `offset` cells to the side of its direction
+/// (right as drawn for positive offsets), so parallel paths drawn with different offsets
+/// don't overlap.
+///
+/// Segments stay horizontal or vertical: corners move diagonally to where the moved
+/// segments meet, and a path that turns back on itself gets a short step where it turns.
+/// The ends move along the note edges the path leaves and enters through.
+pub fn offset_path(path: &[Point], offset: isize) -> Vec<Point> {
+    let mut points: Vec<Point> = path.to_vec();
+    points.dedup();
+    if offset == 0 || points.len() < 2 {
+        return points;
+    }
+
+    let shifts: Vec<Point> = points
+        .windows(2)
+        .map(|pair| {
+            let direction = Point {
+                x: (pair[1].x - pair[0].x).signum(),
+                y: (pair[1].y - pair[0].y).signum(),
+            };
+            aside(direction, offset)
+        })
+        .collect();
+    let moved = |point: Point, shift: Point| Point {
+        x: point.x + shift.x,
+        y: point.y + shift.y,
+    };
+
+    let mut offset_points = vec![moved(points[0], shifts[0])];
+    for (index, &point) in points.iter().enumerate().skip(1).take(points.len() - 2) {
+        let (before, after) = (shifts[index - 1], shifts[index]);
+        let turns = (before.x == 0) != (after.x == 0);
+        if turns {
+            offset_points.push(moved(moved(point, before), after));
+        } else {
+            offset_points.push(moved(point, before));
+            if after != before {
+                offset_points.push(moved(point, after));
+            }
+        }
+    }
+    offset_points.push(moved(points[points.len() - 1], shifts[shifts.len() - 1]));
+
+    offset_points
+}
+
+/// `offset` cells to the right (as drawn, with y growing downward) of a unit `direction`.
+fn aside(direction: Point, offset: isize) -> Point {
+    Point {
+        x: -direction.y * offset,
+        y: direction.x * offset,
+    }
+}
+
+/// Whether the point is on the note's edge on that side, not counting its corners.
+fn on_edge(note: &Note, side: Side, point: Point) -> bool {
+    let (width, height) = note.get_dimensions();
+    let (left, top) = (note.x, note.y);
+    let (right, bottom) = (left + width as isize - 1, top + height as isize - 1);
+    let within_rows = top < point.y && point.y < bottom;
+    let within_columns = left < point.x && point.x < right;
+
+    match side {
+        Side::Left => point.x == left && within_rows,
+        Side::Right => point.x == right && within_rows,
+        Side::Top => point.y == top && within_columns,
+        Side::Bottom => point.y == bottom && within_columns,
+    }
+}
+
+/// Cells the trunk of a fan-out connection runs out of its source note before it splits
+const TRUNK_LENGTH: isize = 4;
+
+/// Paths of a connection fanning out from one note to several others (see
+/// [`Hyperedge`](crate::states::map::Hyperedge)): the trunk out of the source note's
+/// `source_side`, then a branch from the trunk's end to each target, in their order.
+///
+/// Branches leave the trunk's end along one shared line across it, then turn towards
+/// their targets; ones to targets too close to that line are routed like connections.
+pub fn hyperedge_paths(
+    source: &Note,
+    source_side: Side,
+    targets: &[&Note],
+) -> (Vec<Point>, Vec<Vec<Point>>) {
+    let start = connection_point(source, source_side);
+    let split = trunk_end(start, source_side);
+
+    let branches = targets
+        .iter()
+        .map(|target| {
+            let side = hyperedge_target_side(source, source_side, target);
+            let end = connection_point(target, side);
+            if side == opposite_side(source_side) {
+                let bend = match source_side {
+                    Side::Right | Side::Left => Point { x: split.x, ..end },
+                    Side::Top | Side::Bottom => Point { y: split.y, ..end },
+                };
+                let mut branch = vec![split, bend, end];
+                branch.dedup();
+                branch
+            } else {
+                // Along the same line to beside the target, unless it's too close to it
+                let beside = get_offset_point(end, side);
+                let (bend, clear) = match side {
+                    Side::Top => (
+                        Point {
+                            y: beside.y,
+                            ..split
+                        },
+                        beside.y > split.y,
+                    ),
+                    Side::Bottom => (
+                        Point {
+                            y: beside.y,
+                            ..split
+                        },
+                        beside.y < split.y,
+                    ),
+                    Side::Left => (
+                        Point {
+                            x: beside.x,
+                            ..split
+                        },
+                        beside.x > split.x,
+                    ),
+                    Side::Right => (
+                        Point {
+                            x: beside.x,
+                            ..split
+                        },
+                        beside.x < split.x,
+                    ),
+                };
+                if clear {
+                    let mut branch = vec![split, bend, beside, end];
+                    branch.dedup();
+                    branch
+                } else {
+                    orthogonal_path(split, source_side, end, side)
+                }
+            }
+        })
+        .collect();
+
+    (vec![start, split], branches)
+}
+
+/// The side a branch of a fan-out connection enters a target through: the one facing
+/// the source note's side when the target is ahead of the trunk, otherwise the side
+/// facing the trunk.
+pub fn hyperedge_target_side(source: &Note, source_side: Side, target: &Note) -> Side {
+    let split = trunk_end(connection_point(source, source_side), source_side);
+    let facing = opposite_side(source_side);
+    let entry = connection_point(target, facing);
+
+    let ahead = match source_side {
+        Side::Right => entry.x > split.x,
+        Side::Left => entry.x < split.x,
+        Side::Bottom => entry.y > split.y,
+        Side::Top => entry.y < split.y,
+    };
+    if ahead {
+        return facing;
+    }
+
+    let (center_x, center_y) = target.center();
+    match source_side {
+        Side::Right | Side::Left if center_y < split.y => Side::Bottom,
+        Side::Right | Side::Left => Side::Top,
+        Side::Top | Side::Bottom if center_x < split.x => Side::Right,
+        Side::Top | Side::Bottom => Side::Left,
+    }
+}
+
+/// Where the trunk of a fan-out connection leaving through `side` at `start` splits.
+fn trunk_end(start: Point, side: Side) -> Point {
+    match side {
+        Side::Right => Point {
+            x: start.x + TRUNK_LENGTH,
+            ..start
+        },
+        Side::Left => Point {
+            x: start.x - TRUNK_LENGTH,
+            ..start
+        },
+        Side::Top => Point {
+            y: start.y - TRUNK_LENGTH,
+            ..start
+        },
+        Side::Bottom => Point {
+            y: start.y + TRUNK_LENGTH,
+            ..start
+        },
+    }
+}
+
+fn opposite_side(side: Side) -> Side {
+    match side {
+        Side::Right => Side::Left,
+        Side::Left => Side::Right,
+        Side::Top => Side::Bottom,
+        Side::Bottom => Side::Top,
+    }
+}
+
+/// The canvas rectangle a connection path lies within, without calculating the path.
+///
+/// Every shape is made of the connection points, their offset points, the waypoints
+/// and points between them, so the rectangle around those holds all of
+/// [`calculate_path_with_waypoints`]. Used to skip connections that can't be on screen.
+pub fn path_bounds(
+    start_note: &Note,
+    start_side: Side,
+    end_note: &Note,
+    end_side: Side,
+    waypoints: &[Point],
+) -> SignedRect {
+    let start = connection_point(start_note, start_side);
+    let end = connection_point(end_note, end_side);
+    let corners = [
+        start,
+        end,
+        get_offset_point(start, start_side),
+        get_offset_point(end, end_side),
+    ];
+
+    let mut points = corners.iter().chain(waypoints);
+    let first = *points.next().expect("a path has a start point");
+    let (min, max) = points.fold((first, first), |(min, max), point| {
+        (
+            Point {
+                x: min.x.min(point.x),
+                y: min.y.min(point.y),
+            },
+            Point {
+                x: max.x.max(point.x),
+                y: max.y.max(point.y),
+            },
+        )
+    });
+
+    SignedRect {
+        x: min.x,
+        y: min.y,
+        width: max.x - min.x + 1,
+        height: max.y - min.y + 1,
+    }
+}
+
 pub fn get_offset_point(p: Point, side: Side) -> Point {
     let offset = 2;
     let p_off = match side {
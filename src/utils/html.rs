@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use serde_json::{Value, json};
+
+use crate::{
+    states::map::{Connection, Note},
+    utils::{export_svg, get_color_name_in_string, svg_escape},
+};
+
+/// Id of the `<script>` element holding the map data in an HTML export
+pub const HTML_DATA_ID: &str = "map-data";
+
+/// Styles of the viewer page: the map fills the window, the toolbar floats on top
+const VIEWER_STYLE: &str = r#"html, body { margin: 0; height: 100%; overflow: hidden; background: #1e1e1e; color: #e5e5e5; font-family: sans-serif; }
+#map { width: 100%; height: 100%; cursor: grab; touch-action: none; }
+#map.dragging { cursor: grabbing; }
+#map svg { width: 100%; height: 100%; }
+#toolbar { position: fixed; top: 8px; left: 8px; display: flex; gap: 6px; align-items: center; background: rgba(30, 30, 30, 0.9); padding: 6px; border: 1px solid #808080; }
+#toolbar input, #toolbar button { background: #1e1e1e; color: #e5e5e5; border: 1px solid #808080; padding: 2px 6px; }
+rect.match { stroke-width: 6; }"#;
+
+/// The viewer: drag to pan, wheel or +/- to zoom, 0 to fit the map, and a search
+/// field that moves the view from one matching note to the next on Enter.
+const VIEWER_SCRIPT: &str = r#"(function () {
+  const data = JSON.parse(document.getElementById("map-data").textContent);
+  const container = document.getElementById("map");
+  const svg = container.querySelector("svg");
+  svg.removeAttribute("width");
+  svg.removeAttribute("height");
+  const initial = svg.getAttribute("viewBox").split(" ").map(Number);
+  let view = initial.slice();
+  const show = () => svg.setAttribute("viewBox", view.join(" "));
+
+  const zoom = (factor, cx, cy) => {
+    const [x, y, w, h] = view;
+    cx = cx === undefined ? x + w / 2 : cx;
+    cy = cy === undefined ? y + h / 2 : cy;
+    view = [cx - (cx - x) * factor, cy - (cy - y) * factor, w * factor, h * factor];
+    show();
+  };
+  const toMap = (event) => {
+    const rect = svg.getBoundingClientRect();
+    const scale = Math.max(view[2] / rect.width, view[3] / rect.height);
+    return [event.clientX - rect.left, event.clientY - rect.top, scale];
+  };
+
+  container.addEventListener("wheel", (event) => {
+    event.preventDefault();
+    const point = svg.createSVGPoint();
+    point.x = event.clientX;
+    point.y = event.clientY;
+    const at = point.matrixTransform(svg.getScreenCTM().inverse());
+    zoom(event.deltaY > 0 ? 1.2 : 1 / 1.2, at.x, at.y);
+  }, { passive: false });
+
+  let drag = null;
+  container.addEventListener("pointerdown", (event) => {
+    drag = toMap(event);
+    container.classList.add("dragging");
+    container.setPointerCapture(event.pointerId);
+  });
+  container.addEventListener("pointermove", (event) => {
+    if (!drag) return;
+    const [x, y] = toMap(event);
+    view[0] -= (x - drag[0]) * drag[2];
+    view[1] -= (y - drag[1]) * drag[2];
+    drag = [x, y, drag[2]];
+    show();
+  });
+  const stop = () => { drag = null; container.classList.remove("dragging"); };
+  container.addEventListener("pointerup", stop);
+  container.addEventListener("pointercancel", stop);
+
+  const fit = () => { view = initial.slice(); show(); };
+  document.getElementById("zoom-in").onclick = () => zoom(1 / 1.2);
+  document.getElementById("zoom-out").onclick = () => zoom(1.2);
+  document.getElementById("fit").onclick = fit;
+
+  const search = document.getElementById("search");
+  const count = document.getElementById("count");
+  let matches = [];
+  let current = -1;
+  search.addEventListener("input", () => {
+    const text = search.value.toLowerCase();
+    svg.querySelectorAll("rect.match").forEach((rect) => rect.classList.remove("match"));
+    const found = (value) => value !== null && value.toLowerCase().includes(text);
+    matches = text ? data.notes.filter((note) => found(note.title) || found(note.content)) : [];
+    matches.forEach((note) => document.getElementById("note-" + note.id).classList.add("match"));
+    current = -1;
+    count.textContent = text ? matches.length + " found" : "";
+  });
+  search.addEventListener("keydown", (event) => {
+    if (event.key !== "Enter" || matches.length === 0) return;
+    current = (current + 1) % matches.length;
+    const box = document.getElementById("note-" + matches[current].id).getBBox();
+    view = [box.x + box.width / 2 - view[2] / 2, box.y + box.height / 2 - view[3] / 2, view[2], view[3]];
+    show();
+  });
+
+  document.addEventListener("keydown", (event) => {
+    if (event.target === search) return;
+    if (event.key === "+" || event.key === "=") zoom(1 / 1.2);
+    else if (event.key === "-") zoom(1.2);
+    else if (event.key === "0") fit();
+    else if (event.key === "/") { event.preventDefault(); search.focus(); }
+  });
+})();"#;
+
+/// Writes the map as a single HTML page to explore it read-only in a browser, for
+/// people without the TUI.
+///
+/// The page shows the map as drawn by [`export_svg`], which can be panned by dragging
+/// and zoomed with the mouse wheel, and searched for notes by their text. The notes and
+/// connections are embedded as JSON too (see [`html_map_data`]) in a `<script>` with
+/// the id [`HTML_DATA_ID`]. Nothing is loaded from elsewhere, so the file works offline.
+pub fn export_html(
+    title: &str,
+    notes: &HashMap<usize, Note>,
+    connections: &[Connection],
+) -> String {
+    // "</" would end the script element early; "<\/" reads the same as JSON
+    let data = html_map_data(notes, connections)
+        .to_string()
+        .replace("</", "<\\/");
+    let title = svg_escape(title);
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+{VIEWER_STYLE}
+</style>
+</head>
+<body>
+<div id="toolbar">
+  <strong>{title}</strong>
+  <input id="search" type="search" placeholder="Search notes (/)">
+  <span id="count"></span>
+  <button id="zoom-out" title="Zoom out (-)">-</button>
+  <button id="zoom-in" title="Zoom in (+)">+</button>
+  <button id="fit" title="Fit the map (0)">Fit</button>
+</div>
+<div id="map">
+{svg}</div>
+<script type="application/json" id="{HTML_DATA_ID}">{data}</script>
+<script>
+{VIEWER_SCRIPT}
+</script>
+</body>
+</html>
+"#,
+        svg = export_svg(notes, connections),
+    )
+}
+
+/// The notes and connections embedded in an HTML export, in id order.
+///
+/// Notes have their `id`, `x`, `y`, `color` name, `title` and `status` (null if not
+/// set) and `content`, which is null for encrypted notes. Connections have their `from` and `to`
+/// note ids and their `weight` (null if not set); connections to missing notes are left
+/// out.
+pub fn html_map_data(notes: &HashMap<usize, Note>, connections: &[Connection]) -> Value {
+    let mut ids: Vec<usize> = notes.keys().copied().collect();
+    ids.sort_unstable();
+
+    let notes_data: Vec<Value> = ids
+        .iter()
+        .map(|id| {
+            let note = &notes[id];
+            let content = (note.encrypted.is_none()).then_some(note.content.as_str());
+            json!({
+                "id": id,
+                "x": note.x,
+                "y": note.y,
+                "color": get_color_name_in_string(note.color),
+                "title": note.title,
+                "status": note.status,
+                "content": content,
+            })
+        })
+        .collect();
+    let connections_data: Vec<Value> = connections
+        .iter()
+        .filter_map(|connection| {
+            let to_id = connection.to_id;
+            (notes.contains_key(&connection.from_id) && notes.contains_key(&to_id)).then(|| {
+                json!({
+                    "from": connection.from_id,
+                    "to": to_id,
+                    "weight": connection.weight,
+                })
+            })
+        })
+        .collect();
+
+    json!({ "notes": notes_data, "connections": connections_data })
+}
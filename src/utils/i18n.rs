@@ -0,0 +1,125 @@
+//! Message catalogs for the UI text.
+//!
+//! Each locale's catalog is a JSON file in `locales/`, compiled into the binary. Entries
+//! are looked up by dotted key (`bar.mode.normal`) and are either a single text or, for
+//! help pages, a list of lines. Text can contain `{name}` placeholders, filled in by
+//! [`tr_args`]. Entries missing from a translation fall back to English.
+
+use std::{collections::HashMap, sync::OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+/// A language the UI can be shown in.
+#[derive(PartialEq, Eq, Hash, Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum Locale {
+    English,
+    German,
+}
+
+impl Locale {
+    /// Every available locale, in the order the settings cycle through them.
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::German];
+
+    /// The locale's name in its own language.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::German => "Deutsch",
+        }
+    }
+
+    /// Parses a POSIX locale value such as `de_DE.UTF-8` by its language code.
+    ///
+    /// Returns None for `C`, `POSIX` and languages without a catalog.
+    pub fn from_lang(value: &str) -> Option<Locale> {
+        let language = value
+            .split(['_', '.', '@', '-'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match language.as_str() {
+            "en" => Some(Locale::English),
+            "de" => Some(Locale::German),
+            _ => None,
+        }
+    }
+
+    /// Detects the locale from `LC_ALL`, `LC_MESSAGES` and `LANG`, in that order, like
+    /// other programs do. Defaults to English.
+    pub fn from_env() -> Locale {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Locale::from_lang(&value))
+            .unwrap_or(Locale::English)
+    }
+
+    fn catalog_source(&self) -> &'static str {
+        match self {
+            Locale::English => include_str!("../../locales/en.json"),
+            Locale::German => include_str!("../../locales/de.json"),
+        }
+    }
+}
+
+/// A catalog entry: a single text, or the lines of a help page.
+#[derive(PartialEq, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum CatalogEntry {
+    Text(String),
+    Lines(Vec<String>),
+}
+
+pub type Catalog = HashMap<String, CatalogEntry>;
+
+/// The parsed catalog of a locale. Parsed once, on first use.
+pub fn catalog(locale: Locale) -> &'static Catalog {
+    static CATALOGS: OnceLock<HashMap<Locale, Catalog>> = OnceLock::new();
+
+    let catalogs = CATALOGS.get_or_init(|| {
+        Locale::ALL
+            .iter()
+            .map(|&locale| {
+                let catalog = serde_json::from_str(locale.catalog_source())
+                    .expect("Bug: built-in message catalog is not valid JSON");
+                (locale, catalog)
+            })
+            .collect()
+    });
+    &catalogs[&locale]
+}
+
+/// Looks up an entry, falling back to English if the locale doesn't have it.
+fn lookup(locale: Locale, key: &str) -> Option<&'static CatalogEntry> {
+    catalog(locale)
+        .get(key)
+        .or_else(|| catalog(Locale::English).get(key))
+}
+
+/// Text of a catalog entry. Returns the key itself if no catalog has it, so a missing
+/// entry shows up in the UI instead of crashing it.
+pub fn tr(locale: Locale, key: &'static str) -> &'static str {
+    match lookup(locale, key) {
+        Some(CatalogEntry::Text(text)) => text,
+        _ => key,
+    }
+}
+
+/// Text of a catalog entry with each `{name}` placeholder replaced by its value.
+pub fn tr_args(locale: Locale, key: &'static str, args: &[(&str, &str)]) -> String {
+    let mut text = tr(locale, key).to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{name}}}"), value);
+    }
+    text
+}
+
+/// Lines of a multi-line catalog entry, such as a help page.
+pub fn tr_lines(locale: Locale, key: &'static str) -> &'static [String] {
+    match lookup(locale, key) {
+        Some(CatalogEntry::Lines(lines)) => lines,
+        _ => &[],
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+use crate::utils::MapData;
+
+/// Makes a loaded map hold together, so a file edited by hand or by another program
+/// can't leave ids behind that nothing else expects:
+///
+/// - connections, fan-outs and region members may only lead to notes that exist
+/// - `render_order` lists every note exactly once
+/// - `next_note_id_counter` is above every note id
+///
+/// Returns what was repaired, e.g. `connections.2: leads to note 7, which doesn't exist`;
+/// nothing for a map that was fine.
+pub fn repair_map_data(map_data: &mut MapData) -> Vec<String> {
+    let mut repairs = vec![];
+    let notes = &map_data.notes;
+    let missing = |id: &usize| !notes.contains_key(id);
+
+    let mut index = 0;
+    map_data.connections.retain(|connection| {
+        let field = format!("connections.{index}");
+        index += 1;
+        if missing(&connection.from_id) {
+            repairs.push(format!(
+                "{field}: starts at note {}, which doesn't exist",
+                connection.from_id
+            ));
+        } else if missing(&connection.to_id) {
+            repairs.push(format!(
+                "{field}: leads to note {}, which doesn't exist",
+                connection.to_id
+            ));
+        } else {
+            return true;
+        }
+        false
+    });
+
+    let mut index = 0;
+    map_data.hyperedges.retain_mut(|hyperedge| {
+        let field = format!("hyperedges.{index}");
+        index += 1;
+        if missing(&hyperedge.from_id) {
+            repairs.push(format!(
+                "{field}: starts at note {}, which doesn't exist",
+                hyperedge.from_id
+            ));
+            return false;
+        }
+        hyperedge.to_ids.retain(|id| {
+            let exists = !missing(id);
+            if !exists {
+                repairs.push(format!("{field}: leads to note {id}, which doesn't exist"));
+            }
+            exists
+        });
+        !hyperedge.to_ids.is_empty()
+    });
+
+    for (index, region) in map_data.regions.iter_mut().enumerate() {
+        region.members.retain(|id| {
+            let exists = !missing(id);
+            if !exists {
+                repairs.push(format!(
+                    "regions.{index}: has note {id} as a member, which doesn't exist"
+                ));
+            }
+            exists
+        });
+    }
+
+    // Notes left out of the stacking order are put on top, in id order
+    let mut listed = HashSet::new();
+    map_data.render_order.retain(|id| {
+        if missing(id) {
+            repairs.push(format!(
+                "render_order: lists note {id}, which doesn't exist"
+            ));
+            false
+        } else if !listed.insert(*id) {
+            repairs.push(format!("render_order: lists note {id} more than once"));
+            false
+        } else {
+            true
+        }
+    });
+    let mut unlisted: Vec<usize> = notes
+        .keys()
+        .filter(|id| !listed.contains(id))
+        .copied()
+        .collect();
+    unlisted.sort_unstable();
+    for id in &unlisted {
+        repairs.push(format!("render_order: doesn't list note {id}"));
+    }
+    map_data.render_order.extend(unlisted);
+
+    // Never hands out the id of a note that's already there
+    if let Some(max_id) = notes.keys().max()
+        && map_data.next_note_id_counter <= *max_id
+    {
+        repairs.push(format!(
+            "next_note_id_counter: {} isn't above note id {max_id}",
+            map_data.next_note_id_counter
+        ));
+        map_data.next_note_id_counter = max_id + 1;
+    }
+
+    repairs
+}
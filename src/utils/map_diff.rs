@@ -0,0 +1,174 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::{
+    states::map::{Connection, Note},
+    utils::ENCRYPTED_LABEL,
+};
+
+/// How another copy of a map differs from this one, by note id.
+#[derive(PartialEq, Debug, Default)]
+pub struct MapDiff {
+    /// Titles of notes only in the other copy
+    pub added: Vec<String>,
+    /// Titles of notes only in this copy
+    pub removed: Vec<String>,
+    /// Titles (in the other copy) of notes with different text, position or color
+    pub changed: Vec<String>,
+    pub connections_added: usize,
+    pub connections_removed: usize,
+}
+
+impl MapDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.connections_added == 0
+            && self.connections_removed == 0
+    }
+}
+
+/// How a note differs between an older and a newer version of a map.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum NoteChange {
+    /// Only in the newer version
+    Added,
+    /// Only in the older version
+    Removed,
+    /// In both versions, with the parts that differ
+    Modified {
+        moved: bool,
+        content: bool,
+        color: bool,
+    },
+}
+
+/// Every difference between an older and a newer version of a map, with notes matched
+/// by id.
+#[derive(PartialEq, Debug, Default)]
+pub struct MapChanges {
+    pub notes: BTreeMap<usize, NoteChange>,
+    /// Connections only in the newer version
+    pub connections_added: Vec<Connection>,
+    /// Connections only in the older version
+    pub connections_removed: Vec<Connection>,
+}
+
+impl MapChanges {
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+            && self.connections_added.is_empty()
+            && self.connections_removed.is_empty()
+    }
+
+    /// How many notes changed in the given way.
+    pub fn count(&self, matches: impl Fn(&NoteChange) -> bool) -> usize {
+        self.notes.values().filter(|change| matches(change)).count()
+    }
+}
+
+/// Compares an older version of a map with a newer one.
+///
+/// Encrypted notes are compared by their ciphertext, as only one side may be unlocked.
+/// A connection that changed in any way counts as removed and added.
+pub fn map_changes(
+    old_notes: &HashMap<usize, Note>,
+    old_connections: &[Connection],
+    new_notes: &HashMap<usize, Note>,
+    new_connections: &[Connection],
+) -> MapChanges {
+    let ids: BTreeSet<usize> = old_notes.keys().chain(new_notes.keys()).copied().collect();
+
+    let notes = ids
+        .into_iter()
+        .filter_map(|id| {
+            let change = match (old_notes.get(&id), new_notes.get(&id)) {
+                (None, Some(_)) => NoteChange::Added,
+                (Some(_), None) => NoteChange::Removed,
+                (Some(old), Some(new)) => {
+                    let (moved, content, color) = note_differences(old, new);
+                    if !(moved || content || color) {
+                        return None;
+                    }
+                    NoteChange::Modified {
+                        moved,
+                        content,
+                        color,
+                    }
+                }
+                (None, None) => return None,
+            };
+            Some((id, change))
+        })
+        .collect();
+
+    MapChanges {
+        notes,
+        connections_added: new_connections
+            .iter()
+            .filter(|connection| !old_connections.contains(connection))
+            .cloned()
+            .collect(),
+        connections_removed: old_connections
+            .iter()
+            .filter(|connection| !new_connections.contains(connection))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Compares this copy of a map (`my_*`) with another one (`their_*`), such as the map
+/// file after it was changed by another program. Titles are listed in note id order.
+///
+/// Encrypted notes are compared by their ciphertext, as only one side may be unlocked.
+pub fn diff_maps(
+    my_notes: &HashMap<usize, Note>,
+    my_connections: &[Connection],
+    their_notes: &HashMap<usize, Note>,
+    their_connections: &[Connection],
+) -> MapDiff {
+    let changes = map_changes(my_notes, my_connections, their_notes, their_connections);
+
+    let mut diff = MapDiff {
+        connections_added: changes.connections_added.len(),
+        connections_removed: changes.connections_removed.len(),
+        ..MapDiff::default()
+    };
+    for (id, change) in changes.notes {
+        match change {
+            NoteChange::Added => diff.added.push(note_title(&their_notes[&id])),
+            NoteChange::Removed => diff.removed.push(note_title(&my_notes[&id])),
+            NoteChange::Modified { .. } => diff.changed.push(note_title(&their_notes[&id])),
+        }
+    }
+
+    diff
+}
+
+/// Whether the note was moved, its text (or title or status) changed and its color
+/// changed.
+fn note_differences(old: &Note, new: &Note) -> (bool, bool, bool) {
+    let content = if old.encrypted.is_some() || new.encrypted.is_some() {
+        old.encrypted != new.encrypted
+    } else {
+        old.content != new.content
+    };
+
+    (
+        (old.x, old.y) != (new.x, new.y),
+        content || old.title != new.title || old.status != new.status,
+        old.color != new.color,
+    )
+}
+
+/// The note's title, or else the first line of its text, or a placeholder for
+/// encrypted notes.
+pub fn note_title(note: &Note) -> String {
+    if let Some(title) = &note.title {
+        return title.clone();
+    }
+    if note.encrypted.is_some() {
+        return String::from(ENCRYPTED_LABEL);
+    }
+    note.content.lines().next().unwrap_or_default().to_string()
+}
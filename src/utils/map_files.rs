@@ -1,19 +1,26 @@
 use chrono::Local;
 use ratatui::style::Color;
+use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::{collections::HashMap, path::Path};
+use std::{collections::HashMap, path::Path, thread};
 
 use crate::{
     app::{App, Screen},
+    graph::{Graph, Node},
     states::{
-        MapState,
-        map::{Connection, ConnectionsState, Note, NotesState, Notification, ViewPos},
+        MapState, StartState,
+        map::{
+            BackgroundSave, Connection, Hyperedge, Layer, Note, Notification, Region, TrashedNote,
+            UndoHistory, UndoStep, ViewPos, ViewState,
+        },
     },
     utils::{
-        IoErrorKind,
+        ChecksummedMapData, CryptoError, EncryptedText, FileKey, IoErrorKind, JsonFormat,
+        TmmprError, discard_emergency_save, file_modified,
         filesystem::{FileSystem, RealFileSystem},
         get_color_from_string, get_color_name_in_string, handle_on_load_backup_with_fs,
-        read_json_data, write_json_data,
+        keep_damaged_copy, read_emergency_save, read_json_data, read_undo_file, recover_map_data,
+        verify_map_checksum, write_json_data_buffered, write_undo_file,
     },
 };
 
@@ -21,7 +28,8 @@ use crate::{
 ///
 /// Separated from `MapState` to include only the data that needs to be persisted,
 /// excluding runtime-only fields.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq, Debug)]
+#[schemars(title = "tmmpr map", description = "A mind map file written by tmmpr")]
 pub struct MapData {
     pub view_pos: ViewPos,
     #[serde(alias = "next_note_id")]
@@ -29,6 +37,77 @@ pub struct MapData {
     pub notes: HashMap<usize, Note>,
     pub render_order: Vec<usize>,
     pub connections: Vec<Connection>,
+    /// Added after the initial file format, so older files load without it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<Region>,
+    /// Connections fanning out to several notes, empty for most maps
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hyperedges: Vec<Hyperedge>,
+    /// Empty for maps that only use the default layer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layers: Vec<Layer>,
+    /// Selection, search and help page to resume with, see [`MapState::view_state`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view_state: Option<ViewState>,
+    /// Deleted notes, newest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trash: Vec<TrashedNote>,
+}
+
+/// On-disk form of a map file encrypted at rest: the whole map data as one ciphertext,
+/// so not even the number of notes can be read without the passphrase.
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Debug)]
+pub struct EncryptedMapFile {
+    pub encrypted_map: EncryptedText,
+}
+
+/// Contents of a map file as read from disk.
+#[derive(PartialEq, Debug)]
+pub enum MapFileContents {
+    Plain(Box<MapData>),
+    /// Needs the passphrase to be read, see [`unlock_map_data`]
+    Encrypted(EncryptedText),
+}
+
+impl MapData {
+    /// Captures the map as it would be written to disk (encrypted notes stay encrypted).
+    pub fn from_map_state(map_state: &mut MapState) -> Self {
+        MapData {
+            view_pos: map_state.viewport.view_pos.clone(),
+            next_note_id_counter: map_state.notes_state.next_note_id_counter(),
+            notes: map_state.notes_for_saving(),
+            render_order: map_state.notes_state.render_order().clone(),
+            connections: map_state.connections_state.connections().to_vec(),
+            regions: map_state.regions_state.regions().to_vec(),
+            hyperedges: map_state.hyperedges_state.hyperedges().to_vec(),
+            layers: map_state.layers_state.layers_for_saving(),
+            view_state: map_state.view_state(),
+            trash: map_state.trash.notes().to_vec(),
+        }
+    }
+
+    /// Builds a traversable graph of the map, see [`build_graph`].
+    pub fn graph(&self) -> Graph<String> {
+        build_graph(&self.notes, &self.connections)
+    }
+}
+
+/// Builds a directed graph from notes and connections.
+///
+/// Node ids are note ids and node data is the note content. Each complete connection
+/// becomes an edge from its start note to its end note, weighted by the connection's
+/// weight; connections to missing notes are skipped.
+pub fn build_graph(notes: &HashMap<usize, Note>, connections: &[Connection]) -> Graph<String> {
+    let mut graph = Graph::new();
+
+    for (&id, note) in notes {
+        graph.add_node(id, Node::new(note.x, note.y, note.content.clone()));
+    }
+    for connection in connections {
+        graph.add_weighted_edge(connection.from_id, connection.to_id, connection.weight());
+    }
+
+    graph
 }
 
 /// Serializes `ratatui::style::Color` as a human-readable color name string.
@@ -50,6 +129,96 @@ where
     Ok(get_color_from_string(&s))
 }
 
+/// Extension of map files: `json.gz` for compressed ones.
+pub fn map_file_extension(compressed: bool) -> &'static str {
+    if compressed { "json.gz" } else { "json" }
+}
+
+/// True for map files that are written compressed, i.e. named `*.json.gz`.
+pub fn is_compressed_map_path(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "gz")
+}
+
+/// The map's name: its file name without the `.json` or `.json.gz` extension.
+pub fn map_name(path: &Path) -> String {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let name = file_name
+        .strip_suffix(".json.gz")
+        .or_else(|| file_name.strip_suffix(".json"))
+        .unwrap_or(&file_name);
+    name.to_string()
+}
+
+/// Writes map data to a map file, compressed if the path says so (see
+/// [`is_compressed_map_path`]), otherwise compact or pretty-printed.
+///
+/// `buffer` is reused between saves, see [`write_json_data_buffered`].
+pub fn write_map_data(
+    path: &Path,
+    map_data: &MapData,
+    compact: bool,
+    buffer: &mut Vec<u8>,
+) -> Result<(), TmmprError> {
+    let format = if is_compressed_map_path(path) {
+        JsonFormat::Compressed
+    } else if compact {
+        JsonFormat::Compact
+    } else {
+        JsonFormat::Pretty
+    };
+    write_json_data_buffered(path, &ChecksummedMapData::new(map_data)?, format, buffer)
+}
+
+/// Writes map data encrypted with the key, see [`EncryptedMapFile`]. The ciphertext is
+/// compressed if the path says so.
+pub fn write_encrypted_map_data(
+    path: &Path,
+    map_data: &MapData,
+    file_key: &FileKey,
+    buffer: &mut Vec<u8>,
+) -> Result<(), TmmprError> {
+    let encrypted_map = file_key.encrypt(&serde_json::to_string(map_data)?);
+    let format = if is_compressed_map_path(path) {
+        JsonFormat::Compressed
+    } else {
+        JsonFormat::Pretty
+    };
+    write_json_data_buffered(path, &EncryptedMapFile { encrypted_map }, format, buffer)
+}
+
+/// Reads a map file, which may be encrypted.
+pub fn read_map_file(path: &Path) -> Result<MapFileContents, TmmprError> {
+    // Plain maps are by far the most common, and an encrypted one fails to parse as a
+    // plain map right away
+    match read_json_data::<MapData>(path) {
+        Ok(map_data) => Ok(MapFileContents::Plain(Box::new(map_data))),
+        Err(err) => match read_json_data::<EncryptedMapFile>(path) {
+            Ok(file) => Ok(MapFileContents::Encrypted(file.encrypted_map)),
+            Err(_) => Err(err),
+        },
+    }
+}
+
+/// Decrypts an encrypted map file's data with the passphrase. Returns the key too, to
+/// save the map encrypted again.
+pub fn unlock_map_data(
+    encrypted: &EncryptedText,
+    passphrase: &str,
+) -> Result<(MapData, FileKey), CryptoError> {
+    let (file_key, json_string) = FileKey::unlock(passphrase, encrypted)?;
+    let map_data = serde_json::from_str(&json_string).map_err(|_| CryptoError::Decode)?;
+    Ok((map_data, file_key))
+}
+
+/// Decrypts an encrypted map file's data with the key it was opened with.
+pub fn decrypt_map_data(
+    encrypted: &EncryptedText,
+    file_key: &FileKey,
+) -> Result<MapData, CryptoError> {
+    let json_string = file_key.decrypt(encrypted)?;
+    serde_json::from_str(&json_string).map_err(|_| CryptoError::Decode)
+}
+
 /// Creates a new map file at the given path and transitions to the Map screen.
 pub fn create_map_file(app: &mut App, path: &Path) {
     create_map_file_with_fs(app, path, &RealFileSystem);
@@ -65,9 +234,15 @@ pub fn create_map_file_with_fs(app: &mut App, path: &Path, fs: &impl FileSystem)
         notes: map_state.notes_state.notes().clone(),
         render_order: map_state.notes_state.render_order().clone(),
         connections: map_state.connections_state.connections().to_vec(),
+        regions: map_state.regions_state.regions().to_vec(),
+        hyperedges: map_state.hyperedges_state.hyperedges().to_vec(),
+        layers: map_state.layers_state.layers_for_saving(),
+        view_state: None,
+        trash: vec![],
     };
 
-    if let Err(_) = write_json_data(path, &map_data) {
+    let compact = map_state.settings.compact_json;
+    if let Err(_) = write_map_data(path, &map_data, compact, &mut Vec::new()) {
         if let Screen::Start(start_state) = &mut app.screen {
             start_state.handle_submit_error(IoErrorKind::FileWrite);
         }
@@ -78,13 +253,18 @@ pub fn create_map_file_with_fs(app: &mut App, path: &Path, fs: &impl FileSystem)
     if let Screen::Start(start_state) = &mut app.screen {
         if let Ok(recent_paths) = &mut start_state.recent_paths {
             if !recent_paths.contains_path(path) {
-                recent_paths.add(path.to_path_buf());
-                recent_paths.save_with_fs(fs);
+                let limit = map_state.settings.recent_files_limit;
+                recent_paths.add(path.to_path_buf(), limit);
+                recent_paths.save_with_fs(fs, limit);
             }
         }
     }
 
-    app.screen = Screen::Map(MapState::new_with_fs(path.to_path_buf(), fs));
+    let mut map_state = MapState::new_with_fs(path.to_path_buf(), fs);
+    map_state.persistence.disk_modified = file_modified(path);
+    map_state.persistence.base_map = Some(map_data);
+    attach_workspace(app, &mut map_state, path);
+    app.screen = Screen::Map(map_state);
 }
 
 pub fn save_with_notification(
@@ -92,7 +272,7 @@ pub fn save_with_notification(
     path: &Path,
     success_notif: Notification,
     fail_notif: Notification,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), TmmprError> {
     match save_map_file(map_state, path) {
         Ok(_) => {
             map_state.ui_state.set_notification(success_notif);
@@ -107,24 +287,147 @@ pub fn save_with_notification(
     }
 }
 
+/// Writes the open map to a file the way it's saved (encrypted with the map's key, if
+/// it has one), without touching the persistence state. Returns the data written.
+pub fn write_map_copy(map_state: &mut MapState, path: &Path) -> Result<MapData, TmmprError> {
+    let map_data = MapData::from_map_state(map_state);
+
+    let compact = map_state.settings.compact_json;
+    let persistence = &mut map_state.persistence;
+    match &persistence.file_key {
+        Some(file_key) => {
+            write_encrypted_map_data(path, &map_data, file_key, &mut persistence.save_buffer)?
+        }
+        None => write_map_data(path, &map_data, compact, &mut persistence.save_buffer)?,
+    }
+    Ok(map_data)
+}
+
 /// Saves map data to a file.
 ///
-/// Updates persistence state to allow exit after successful save.
-pub fn save_map_file(
-    map_state: &mut MapState,
-    path: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let map_data = MapData {
-        view_pos: map_state.viewport.view_pos.clone(),
-        next_note_id_counter: map_state.notes_state.next_note_id_counter(),
-        notes: map_state.notes_state.notes().clone(),
-        render_order: map_state.notes_state.render_order().clone(),
-        connections: map_state.connections_state.connections().to_vec(),
+/// Updates persistence state to allow exit after successful save. A save still running
+/// in the background is finished first, and the plugins' `on_save` hook is run before
+/// the map's own file is written.
+pub fn save_map_file(map_state: &mut MapState, path: &Path) -> Result<(), TmmprError> {
+    finish_background_save(map_state, true);
+    run_save_hook(map_state, path);
+    let map_data = write_map_copy(map_state, path)?;
+
+    map_state.persistence.mark_clean();
+    // Our own writes aren't external changes
+    if path == map_state.persistence.file_write_path {
+        map_state.persistence.recovered = false;
+        if let Some(steps) = undo_steps_to_keep(map_state) {
+            // Losing the undo history isn't worth failing the save over
+            let _ = write_undo_file(path, &map_data, &steps);
+        }
+        map_state.persistence.disk_modified = file_modified(path);
+        map_state.persistence.base_map = Some(map_data);
+    }
+    Ok(())
+}
+
+/// Runs the plugins' `on_save` hook when the map is saved to its own file, so the
+/// changes the scripts make are saved with it.
+fn run_save_hook(map_state: &mut MapState, path: &Path) {
+    if path == map_state.persistence.file_write_path {
+        map_state.run_plugin_function("on_save", &[]);
+    }
+}
+
+/// Undo steps kept next to the map file when it's saved, None if the undo history
+/// isn't kept (see `Settings::persist_undo`). Maps encrypted at rest keep none, so
+/// their text isn't written in the clear.
+fn undo_steps_to_keep(map_state: &MapState) -> Option<Vec<UndoStep>> {
+    map_state
+        .settings
+        .persist_undo
+        .then(|| match map_state.persistence.file_key {
+            Some(_) => vec![],
+            None => map_state.history.steps().to_vec(),
+        })
+}
+
+/// Saves the map on a background thread, so serializing and writing a large map doesn't
+/// block input.
+///
+/// The map counts as saved right away; edits made meanwhile mark it unsaved again as
+/// usual, and so does a failed save. The result is applied by
+/// [`finish_background_save`], with a notification if `notify` is set.
+pub fn save_map_file_in_background(map_state: &mut MapState, path: &Path, notify: bool) {
+    // One save at a time, so the file isn't written by two threads
+    finish_background_save(map_state, true);
+    run_save_hook(map_state, path);
+
+    let map_data = MapData::from_map_state(map_state);
+    let compact = map_state.settings.compact_json;
+    let file_key = map_state.persistence.file_key.clone();
+    let mut buffer = std::mem::take(&mut map_state.persistence.save_buffer);
+    let thread_path = path.to_path_buf();
+    let undo_steps = (path == map_state.persistence.file_write_path)
+        .then(|| undo_steps_to_keep(map_state))
+        .flatten();
+
+    let handle = thread::spawn(move || {
+        let result = match &file_key {
+            Some(file_key) => {
+                write_encrypted_map_data(&thread_path, &map_data, file_key, &mut buffer)
+            }
+            None => write_map_data(&thread_path, &map_data, compact, &mut buffer),
+        };
+        if let (Ok(_), Some(steps)) = (&result, &undo_steps) {
+            let _ = write_undo_file(&thread_path, &map_data, steps);
+        }
+        (result, map_data, buffer)
+    });
+
+    map_state.persistence.mark_clean();
+    map_state.persistence.background_save =
+        Some(BackgroundSave::new(path.to_path_buf(), notify, handle));
+}
+
+/// Applies the result of a background save once its thread is done, or waits for it
+/// if `wait` is set. Does nothing without a background save.
+pub fn finish_background_save(map_state: &mut MapState, wait: bool) {
+    let Some(save) = map_state
+        .persistence
+        .background_save
+        .take_if(|save| wait || save.is_finished())
+    else {
+        return;
+    };
+    let path = save.path.clone();
+    let notify = save.notify;
+
+    let saved = match save.join() {
+        Some((result, map_data, buffer)) => {
+            map_state.persistence.save_buffer = buffer;
+            result.map(|_| map_data)
+        }
+        None => Err(TmmprError::Validation(String::from(
+            "the save thread stopped unexpectedly",
+        ))),
     };
 
-    write_json_data(path, &map_data).inspect(|_| {
-        map_state.persistence.mark_clean();
-    })
+    let notification = match saved {
+        Ok(map_data) => {
+            // Our own writes aren't external changes
+            if path == map_state.persistence.file_write_path {
+                map_state.persistence.recovered = false;
+                map_state.persistence.disk_modified = file_modified(&path);
+                map_state.persistence.base_map = Some(map_data);
+            }
+            Notification::SaveSuccess
+        }
+        Err(_) => {
+            map_state.persistence.mark_dirty();
+            Notification::SaveFail
+        }
+    };
+    if notify {
+        map_state.ui_state.set_notification(notification);
+        map_state.clear_and_redraw();
+    }
 }
 
 /// Loads a map file and transitions to the Map screen.
@@ -135,43 +438,177 @@ pub fn load_map_file(app: &mut App, path: &Path) {
 /// Loads a map file with a custom filesystem (testable version).
 ///
 /// Only called from the Start screen. On error, shows error message and remains
-/// on Start screen to allow retry.
+/// on Start screen to allow retry. An encrypted map file asks for its passphrase,
+/// see [`unlock_map_file_with_fs`].
+///
+/// A map file that doesn't match the checksum saved with it (see [`verify_map_checksum`])
+/// asks whether to open it anyway, or is opened with a warning when switching maps of a
+/// workspace.
 pub fn load_map_file_with_fs(app: &mut App, path: &Path, fs: &impl FileSystem) {
+    open_map_file_with_fs(app, path, None, true, fs);
+}
+
+/// Loads a map file without checking its checksum.
+pub fn load_map_file_unchecked(app: &mut App, path: &Path) {
+    load_map_file_unchecked_with_fs(app, path, &RealFileSystem);
+}
+
+/// Loads a map file without checking its checksum, with a custom filesystem (testable
+/// version).
+pub fn load_map_file_unchecked_with_fs(app: &mut App, path: &Path, fs: &impl FileSystem) {
+    open_map_file_with_fs(app, path, None, false, fs);
+}
+
+/// Loads a map file that is encrypted at rest, with the passphrase entered on the
+/// Start screen.
+pub fn unlock_map_file(app: &mut App, path: &Path, passphrase: &str) {
+    unlock_map_file_with_fs(app, path, passphrase, &RealFileSystem);
+}
+
+/// Loads an encrypted map file with a custom filesystem (testable version).
+///
+/// A wrong passphrase asks for it again.
+pub fn unlock_map_file_with_fs(app: &mut App, path: &Path, passphrase: &str, fs: &impl FileSystem) {
+    open_map_file_with_fs(app, path, Some(passphrase), false, fs);
+}
+
+/// `verify` checks the checksum of plain map files; encrypted ones are authenticated
+/// when they're decrypted.
+fn open_map_file_with_fs(
+    app: &mut App,
+    path: &Path,
+    passphrase: Option<&str>,
+    verify: bool,
+    fs: &impl FileSystem,
+) {
     let mut map_state = MapState::new_with_fs(path.to_path_buf(), fs);
+    let mut recovery = None;
+    let mut mismatch = false;
 
-    match read_json_data::<MapData>(path) {
-        Ok(map_data) => {
-            map_state.viewport.view_pos = map_data.view_pos;
-            map_state.notes_state = NotesState::from_map_data(
-                map_data.notes,
-                map_data.next_note_id_counter,
-                map_data.render_order,
-            );
-            map_state.connections_state = ConnectionsState::from_connections(map_data.connections);
+    let unlocked = match read_map_file(path) {
+        Ok(MapFileContents::Plain(map_data)) => {
+            mismatch = verify && !verify_map_checksum(path).unwrap_or(true);
+            if mismatch && let Screen::Start(start_state) = &mut app.screen {
+                start_state.warn_checksum_mismatch(path.to_path_buf());
+                return;
+            }
+            Ok((*map_data, None))
         }
-        Err(_) => {
-            // Note: handle_submit_error resets input fields even when called from recent paths entry,
-            // but this is harmless since the fields aren't visible in that context.
+        Ok(MapFileContents::Encrypted(encrypted)) => match passphrase {
+            Some(passphrase) => unlock_map_data(&encrypted, passphrase)
+                .map(|(map_data, file_key)| (map_data, Some(file_key)))
+                .map_err(Some),
+            None => Err(None),
+        },
+        // A damaged map is opened with whatever can still be read of it
+        Err(err) => match recover_map_data(path) {
+            Ok((map_data, report)) => {
+                recovery = Some(report);
+                Ok((map_data, None))
+            }
+            Err(_) => {
+                // Note: handle_load_error resets input fields even when called from recent paths entry,
+                // but this is harmless since the fields aren't visible in that context.
+                if let Screen::Start(start_state) = &mut app.screen {
+                    start_state.handle_load_error(&err);
+                }
+                return;
+            }
+        },
+    };
+
+    match unlocked {
+        Ok((map_data, file_key)) => {
+            map_state.persistence.base_map = Some(map_data.clone());
+            let undo_steps = (map_state.settings.persist_undo && file_key.is_none())
+                .then(|| read_undo_file(path, &map_data).unwrap_or_default());
+            map_state.persistence.file_key = file_key;
+            let view_state = map_data.view_state.clone();
+            map_state.load_map_data(map_data);
+            if let Some(steps) = undo_steps {
+                map_state.history = UndoHistory::from_steps(steps);
+            }
+            if let Some(view_state) = view_state {
+                map_state.restore_view_state(view_state);
+            }
+            map_state.persistence.disk_modified = file_modified(path);
+            if mismatch {
+                map_state
+                    .ui_state
+                    .set_notification(Notification::ChecksumMismatch);
+            }
+            if let Some(report) = &recovery {
+                // Left unsaved, so it's only written over the damaged file when asked to
+                map_state.persistence.recovered = true;
+                map_state.persistence.mark_dirty();
+                let damaged_copy = keep_damaged_copy(path).ok();
+                map_state.show_recovery_report(report, damaged_copy.as_deref());
+            }
+        }
+        // Asks for the passphrase, again with the error if it couldn't decrypt the file
+        Err(error) => {
+            // Also reached when going back to the map from the settings, which don't
+            // keep the key
+            if !matches!(app.screen, Screen::Start(_)) {
+                app.screen = Screen::Start(StartState::new_with_fs(fs));
+            }
             if let Screen::Start(start_state) = &mut app.screen {
-                start_state.handle_submit_error(IoErrorKind::FileRead);
+                start_state.ask_map_passphrase(path.to_path_buf(), error);
             }
             return;
         }
     }
 
     // Always called from Start screen
+    let mut restore_crash_changes = false;
     if let Screen::Start(start_state) = &mut app.screen {
+        restore_crash_changes = start_state
+            .pending_crash_restore
+            .take_if(|pending| pending == path)
+            .is_some();
         if let Ok(recent_paths) = &mut start_state.recent_paths {
             if !recent_paths.contains_path(path) {
-                recent_paths.add(path.to_path_buf());
-                recent_paths.save_with_fs(fs);
+                let limit = map_state.settings.recent_files_limit;
+                recent_paths.add(path.to_path_buf(), limit);
+                recent_paths.save_with_fs(fs, limit);
             }
         }
     }
 
+    attach_workspace(app, &mut map_state, path);
     app.screen = Screen::Map(map_state);
 
     if let Screen::Map(map_state) = &mut app.screen {
         handle_on_load_backup_with_fs(map_state, fs, Local::now());
+        if restore_crash_changes {
+            restore_emergency_save(map_state, path);
+        }
+    }
+}
+
+/// Puts the unsaved changes a crash left of the map back into it, deleting the recovery
+/// file once they're in. One that can't be read is kept, with a notification.
+fn restore_emergency_save(map_state: &mut MapState, path: &Path) {
+    match read_emergency_save(path, map_state.persistence.file_key.as_ref()) {
+        Ok(map_data) => {
+            map_state.restore_crash_changes(map_data);
+            let _ = discard_emergency_save(path);
+        }
+        Err(_) => map_state
+            .ui_state
+            .set_notification(Notification::CrashChangesRestoreFail),
+    }
+}
+
+/// Gives the map being opened the open workspace, if the map is in it. Opening a map
+/// from elsewhere closes the workspace.
+fn attach_workspace(app: &mut App, map_state: &mut MapState, path: &Path) {
+    if !app
+        .workspace
+        .as_ref()
+        .is_some_and(|workspace| workspace.contains(path))
+    {
+        app.workspace = None;
     }
+    map_state.workspace = app.workspace.clone();
 }
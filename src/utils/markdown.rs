@@ -0,0 +1,106 @@
+use std::ops::Range;
+
+/// Kind of Markdown syntax picked out of a note's text to style it.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MarkdownStyle {
+    /// A whole `#` heading line
+    Heading,
+    /// An inline `` `code` `` span with its backticks, or a line of a fenced block
+    Code,
+    /// The `-`, `*`, `+` or `1.` starting a list item
+    ListMarker,
+    /// A `[text](url)` link
+    Link,
+}
+
+/// Byte ranges of the Markdown syntax in a note's text, in order and not overlapping.
+///
+/// Only the basics are recognized and the text itself is left as typed, so styling it
+/// doesn't change how much room a note takes up.
+pub fn markdown_spans(content: &str) -> Vec<(Range<usize>, MarkdownStyle)> {
+    let mut spans = vec![];
+    let mut line_start = 0;
+    let mut in_fence = false;
+
+    for line in content.split('\n') {
+        let line_end = line_start + line.len();
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        if trimmed.starts_with("```") || in_fence {
+            if trimmed.starts_with("```") {
+                in_fence = !in_fence;
+            }
+            if !line.is_empty() {
+                spans.push((line_start..line_end, MarkdownStyle::Code));
+            }
+        } else if is_heading(trimmed) {
+            spans.push((line_start..line_end, MarkdownStyle::Heading));
+        } else {
+            let mut inline_start = line_start;
+            if let Some(len) = list_marker_len(trimmed) {
+                let marker_start = line_start + indent;
+                spans.push((marker_start..marker_start + len, MarkdownStyle::ListMarker));
+                inline_start = marker_start + len;
+            }
+            inline_spans(content, inline_start..line_end, &mut spans);
+        }
+
+        line_start = line_end + 1;
+    }
+
+    spans
+}
+
+/// One to six `#` followed by a space or nothing else.
+fn is_heading(line: &str) -> bool {
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    (1..=6).contains(&hashes) && (line.len() == hashes || line[hashes..].starts_with(' '))
+}
+
+/// Length of the list marker a line starts with, None if it isn't a list item.
+fn list_marker_len(line: &str) -> Option<usize> {
+    if ["- ", "* ", "+ "]
+        .iter()
+        .any(|marker| line.starts_with(marker))
+    {
+        return Some(1);
+    }
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let rest = &line[digits..];
+    (digits > 0 && (rest.starts_with(". ") || rest.starts_with(") "))).then_some(digits + 1)
+}
+
+/// Code spans and links within part of a line; an unclosed one is left as plain text.
+fn inline_spans(
+    content: &str,
+    range: Range<usize>,
+    spans: &mut Vec<(Range<usize>, MarkdownStyle)>,
+) {
+    let line = &content[range.clone()];
+    let mut pos = 0;
+
+    while pos < line.len() {
+        let rest = &line[pos..];
+        let found = if let Some(code) = rest.strip_prefix('`') {
+            code.find('`').map(|end| (end + 2, MarkdownStyle::Code))
+        } else if rest.starts_with('[') {
+            rest.find("](").and_then(|text_end| {
+                rest[text_end..]
+                    .find(')')
+                    .map(|end| (text_end + end + 1, MarkdownStyle::Link))
+            })
+        } else {
+            None
+        };
+
+        match found {
+            Some((len, style)) => {
+                let start = range.start + pos;
+                spans.push((start..start + len, style));
+                pos += len;
+            }
+            None => pos += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+}
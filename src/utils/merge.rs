@@ -0,0 +1,387 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    states::map::{Connection, Hyperedge, Layer, Note, Region, TrashedNote, ViewPos},
+    utils::MapData,
+};
+
+/// What two versions of a note both changed differently since their common base.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ConflictKind {
+    /// Text (or ciphertext of an encrypted note)
+    Content,
+    Position,
+    Color,
+    /// Deleted in this version, changed in the other
+    DeletedMine,
+    /// Changed in this version, deleted in the other
+    DeletedTheirs,
+}
+
+/// Which version of a conflicting note a merge keeps.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum MergeChoice {
+    Mine,
+    Theirs,
+}
+
+/// A note both versions changed in a way that can't be merged automatically.
+#[derive(PartialEq, Clone, Debug)]
+pub struct MergeConflict {
+    pub note_id: usize,
+    pub kind: ConflictKind,
+    /// The note in this version, None if deleted
+    pub mine: Option<Note>,
+    /// The note in the other version, None if deleted
+    pub theirs: Option<Note>,
+    pub choice: MergeChoice,
+}
+
+/// A three-way merge of two versions of a map that share a common base version.
+///
+/// Notes are matched by id. A change made in only one version is taken over, the same
+/// change made in both is taken once, and different changes to the same part of a note
+/// (its text, position or color) are conflicts, which keep this version until a
+/// conflict's [`MergeChoice`] says otherwise. Notes added in both versions under the
+/// same id are both kept, the other version's under a new id. Connections and regions
+/// are merged as sets: anything either version added is kept, anything either version
+/// removed is dropped. Layers the other version added are appended, and so are the
+/// notes it put in the trash.
+#[derive(PartialEq, Debug)]
+pub struct MapMerge {
+    view_pos: ViewPos,
+    /// Past every id either version used, so deleted ids aren't reused
+    next_note_id_counter: usize,
+    notes: HashMap<usize, Note>,
+    render_order: Vec<usize>,
+    connections: Vec<Connection>,
+    regions: Vec<Region>,
+    hyperedges: Vec<Hyperedge>,
+    layers: Vec<Layer>,
+    trash: Vec<TrashedNote>,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MapMerge {
+    /// Merges `theirs` into `mine`. The result keeps `mine`'s view position.
+    ///
+    /// `edited_unlocked` are the encrypted notes whose text this version changed while
+    /// they were unlocked. Their plaintext can't be compared to the base's ciphertext
+    /// here, so the caller, holding the passphrase, tells them apart.
+    pub fn new(
+        base: &MapData,
+        mine: &MapData,
+        theirs: &MapData,
+        edited_unlocked: &HashSet<usize>,
+    ) -> MapMerge {
+        let mut ids: Vec<usize> = [base, mine, theirs]
+            .iter()
+            .flat_map(|map| map.notes.keys())
+            .copied()
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let mut notes = HashMap::new();
+        let mut conflicts = vec![];
+        // Notes added in both versions under the same id, to move to a free id
+        let mut clashing = vec![];
+
+        for id in ids {
+            let base_note = base.notes.get(&id);
+            let my_note = mine.notes.get(&id);
+            let their_note = theirs.notes.get(&id);
+
+            match (base_note, my_note, their_note) {
+                (Some(base_note), Some(my_note), Some(their_note)) => {
+                    let (note, note_conflicts) = merge_note(
+                        base_note,
+                        my_note,
+                        their_note,
+                        edited_unlocked.contains(&id),
+                    );
+                    notes.insert(id, note);
+                    conflicts.extend(note_conflicts.into_iter().map(|kind| MergeConflict {
+                        note_id: id,
+                        kind,
+                        mine: Some(my_note.clone()),
+                        theirs: Some(their_note.clone()),
+                        choice: MergeChoice::Mine,
+                    }));
+                }
+                (Some(base_note), Some(my_note), None) => {
+                    if changed_since(base_note, my_note, edited_unlocked.contains(&id)) {
+                        notes.insert(id, my_note.clone());
+                        conflicts.push(MergeConflict {
+                            note_id: id,
+                            kind: ConflictKind::DeletedTheirs,
+                            mine: Some(my_note.clone()),
+                            theirs: None,
+                            choice: MergeChoice::Mine,
+                        });
+                    }
+                }
+                (Some(base_note), None, Some(their_note)) => {
+                    if their_note != base_note {
+                        conflicts.push(MergeConflict {
+                            note_id: id,
+                            kind: ConflictKind::DeletedMine,
+                            mine: None,
+                            theirs: Some(their_note.clone()),
+                            choice: MergeChoice::Mine,
+                        });
+                    }
+                }
+                (None, Some(my_note), Some(their_note)) => {
+                    notes.insert(id, my_note.clone());
+                    if my_note != their_note {
+                        clashing.push(id);
+                    }
+                }
+                (_, Some(note), None) | (_, None, Some(note)) => {
+                    notes.insert(id, note.clone());
+                }
+                (_, None, None) => {}
+            }
+        }
+
+        // Their additions that clashed with ours move to ids no version uses
+        let mut next_id = [base, mine, theirs]
+            .iter()
+            .map(|map| map.next_note_id_counter)
+            .chain(notes.keys().map(|id| id + 1))
+            .max()
+            .unwrap_or_default();
+        let mut moved: HashMap<usize, usize> = HashMap::new();
+        for id in clashing {
+            notes.insert(next_id, theirs.notes[&id].clone());
+            moved.insert(id, next_id);
+            next_id += 1;
+        }
+        let their_id = |id: usize| moved.get(&id).copied().unwrap_or(id);
+
+        let their_connections: Vec<Connection> = theirs
+            .connections
+            .iter()
+            .map(|connection| Connection {
+                from_id: their_id(connection.from_id),
+                to_id: their_id(connection.to_id),
+                ..connection.clone()
+            })
+            .collect();
+        let their_hyperedges: Vec<Hyperedge> = theirs
+            .hyperedges
+            .iter()
+            .map(|hyperedge| Hyperedge {
+                from_id: their_id(hyperedge.from_id),
+                to_ids: hyperedge.to_ids.iter().map(|&id| their_id(id)).collect(),
+                ..hyperedge.clone()
+            })
+            .collect();
+
+        // Layers are only ever added, so the other version's extra ones are new
+        let mut layers = mine.layers.clone();
+        layers.extend(theirs.layers.iter().skip(mine.layers.len()).cloned());
+
+        // Notes either version deleted, this version's first
+        let mut trash = mine.trash.clone();
+        for trashed in &theirs.trash {
+            if !trash.contains(trashed) {
+                trash.push(trashed.clone());
+            }
+        }
+
+        let mut render_order = mine.render_order.clone();
+        render_order.extend(theirs.render_order.iter().map(|&id| their_id(id)));
+
+        MapMerge {
+            view_pos: mine.view_pos.clone(),
+            next_note_id_counter: next_id,
+            notes,
+            render_order,
+            connections: merge_sets(&base.connections, &mine.connections, &their_connections),
+            regions: merge_sets(&base.regions, &mine.regions, &theirs.regions),
+            hyperedges: merge_sets(&base.hyperedges, &mine.hyperedges, &their_hyperedges),
+            layers,
+            trash,
+            conflicts,
+        }
+    }
+
+    /// Applies the conflicts' choices and returns the merged map.
+    ///
+    /// Connections to notes that didn't make it into the result are dropped, as are
+    /// fan-outs from them; fan-outs lose their branches to them.
+    pub fn into_map_data(self) -> MapData {
+        let mut notes = self.notes;
+
+        for conflict in self.conflicts {
+            if conflict.choice == MergeChoice::Mine {
+                continue;
+            }
+            let id = conflict.note_id;
+            match (conflict.kind, notes.get_mut(&id), conflict.theirs) {
+                (ConflictKind::Content, Some(note), Some(theirs)) => {
+                    note.content = theirs.content;
+                    note.encrypted = theirs.encrypted;
+                    note.title = theirs.title;
+                }
+                (ConflictKind::Position, Some(note), Some(theirs)) => {
+                    (note.x, note.y) = (theirs.x, theirs.y);
+                }
+                (ConflictKind::Color, Some(note), Some(theirs)) => note.color = theirs.color,
+                (ConflictKind::DeletedMine, _, Some(theirs)) => {
+                    notes.insert(id, theirs);
+                }
+                (ConflictKind::DeletedTheirs, _, None) => {
+                    notes.remove(&id);
+                }
+                _ => {}
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let mut render_order: Vec<usize> = self
+            .render_order
+            .into_iter()
+            .filter(|id| notes.contains_key(id) && seen.insert(*id))
+            .collect();
+        let mut unordered: Vec<usize> = notes
+            .keys()
+            .filter(|id| !seen.contains(id))
+            .copied()
+            .collect();
+        unordered.sort_unstable();
+        render_order.extend(unordered);
+
+        let connections = self
+            .connections
+            .into_iter()
+            .filter(|connection| {
+                notes.contains_key(&connection.from_id) && notes.contains_key(&connection.to_id)
+            })
+            .collect();
+        let hyperedges = self
+            .hyperedges
+            .into_iter()
+            .filter_map(|mut hyperedge| {
+                hyperedge.to_ids.retain(|id| notes.contains_key(id));
+                (notes.contains_key(&hyperedge.from_id) && !hyperedge.to_ids.is_empty())
+                    .then_some(hyperedge)
+            })
+            .collect();
+
+        MapData {
+            view_pos: self.view_pos,
+            next_note_id_counter: self.next_note_id_counter,
+            notes,
+            render_order,
+            connections,
+            regions: self.regions,
+            hyperedges,
+            layers: self.layers,
+            view_state: None,
+            trash: self.trash,
+        }
+    }
+}
+
+/// Whether this version of a note differs from the base. The text of a note encrypted
+/// in this version only counts as changed if it was `edited` while unlocked.
+fn changed_since(base: &Note, mine: &Note, edited: bool) -> bool {
+    if mine.encrypted.is_none() {
+        return mine != base;
+    }
+    // `content` holds the plaintext only while the note is unlocked
+    let rest_of_mine = Note {
+        content: base.content.clone(),
+        ..mine.clone()
+    };
+    edited || rest_of_mine != *base
+}
+
+/// Merges the text, position and color of a note separately. Returns the merged note,
+/// with this version's value for each conflicting part, and the conflicts.
+///
+/// `mine_edited` is set if this version changed the note's text while it was unlocked.
+fn merge_note(
+    base: &Note,
+    mine: &Note,
+    theirs: &Note,
+    mine_edited: bool,
+) -> (Note, Vec<ConflictKind>) {
+    let mut note = mine.clone();
+    let mut conflicts = vec![];
+
+    // Unlocked notes hold their plaintext in `content`, so as soon as one version is
+    // encrypted, only the ciphertexts are compared
+    let encrypted = [base, mine, theirs]
+        .iter()
+        .any(|note| note.encrypted.is_some());
+    // The title goes with the text, so a conflict offers both of one version together
+    let text = |note: &Note| {
+        if encrypted {
+            (note.encrypted.clone(), None, note.title.clone())
+        } else {
+            (None, Some(note.content.clone()), note.title.clone())
+        }
+    };
+    let mut my_text = text(mine);
+    // An edit of an unlocked note is only in its plaintext until it's saved
+    if encrypted && mine_edited {
+        my_text.1 = Some(mine.content.clone());
+    }
+    match merge_value(text(base), my_text.clone(), text(theirs)) {
+        Some(merged) if merged == my_text => {}
+        Some(_) => {
+            note.content = theirs.content.clone();
+            note.encrypted = theirs.encrypted.clone();
+            note.title = theirs.title.clone();
+        }
+        None => conflicts.push(ConflictKind::Content),
+    }
+
+    match merge_value((base.x, base.y), (mine.x, mine.y), (theirs.x, theirs.y)) {
+        Some(position) => (note.x, note.y) = position,
+        None => conflicts.push(ConflictKind::Position),
+    }
+    match merge_value(base.color, mine.color, theirs.color) {
+        Some(color) => note.color = color,
+        None => conflicts.push(ConflictKind::Color),
+    }
+    // Changing a status or lock is quick to redo, so this version's wins without asking
+    note.status = merge_value(base.status, mine.status, theirs.status).unwrap_or(mine.status);
+    note.locked = merge_value(base.locked, mine.locked, theirs.locked).unwrap_or(mine.locked);
+    // A UUID one version gave the note is kept; if both did, this version's is
+    note.uuid = mine.uuid.or(theirs.uuid);
+
+    (note, conflicts)
+}
+
+/// The merged value if only one version changed it (or both the same way), None if
+/// they changed it differently.
+fn merge_value<T: PartialEq>(base: T, mine: T, theirs: T) -> Option<T> {
+    if mine == theirs || theirs == base {
+        Some(mine)
+    } else if mine == base {
+        Some(theirs)
+    } else {
+        None
+    }
+}
+
+/// Items in both versions, and items only one version added since the base.
+fn merge_sets<T: PartialEq + Clone>(base: &[T], mine: &[T], theirs: &[T]) -> Vec<T> {
+    let mut merged: Vec<T> = mine
+        .iter()
+        .filter(|item| theirs.contains(item) || !base.contains(item))
+        .cloned()
+        .collect();
+    merged.extend(
+        theirs
+            .iter()
+            .filter(|item| !mine.contains(item) && !base.contains(item))
+            .cloned(),
+    );
+    merged
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use crate::{
+    states::map::{Connection, Note},
+    utils::ENCRYPTED_LABEL,
+};
+
+/// Writes the map as a Mermaid `flowchart TD` document.
+///
+/// Every note becomes a node `n<id>` labeled with its (sanitized) title, or its content
+/// if it has none, in id order.
+/// Every complete connection becomes an arrow in its direction, labeled with its weight
+/// when one is set. Encrypted notes are labeled `[encrypted]`.
+pub fn export_mermaid(notes: &HashMap<usize, Note>, connections: &[Connection]) -> String {
+    let mut ids: Vec<usize> = notes.keys().copied().collect();
+    ids.sort_unstable();
+
+    let mut lines = vec![String::from("flowchart TD")];
+    for id in ids {
+        let note = &notes[&id];
+        let label = if let Some(title) = &note.title {
+            mermaid_label(title)
+        } else if note.encrypted.is_some() {
+            String::from(ENCRYPTED_LABEL)
+        } else {
+            mermaid_label(&note.content)
+        };
+        lines.push(format!("    n{id}[\"{label}\"]"));
+    }
+
+    for connection in connections {
+        let to_id = connection.to_id;
+        if !notes.contains_key(&connection.from_id) || !notes.contains_key(&to_id) {
+            continue;
+        }
+
+        let arrow = match connection.weight {
+            Some(weight) => format!("-->|{weight}|"),
+            None => String::from("-->"),
+        };
+        lines.push(format!("    n{} {arrow} n{to_id}", connection.from_id));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Makes note text safe to put inside a quoted Mermaid label.
+///
+/// Characters Mermaid would read as syntax are written as entity codes and line breaks
+/// become `<br>`. Empty notes get a single space so the node still renders.
+pub fn mermaid_label(content: &str) -> String {
+    let mut label = String::new();
+    for (index, line) in content.lines().enumerate() {
+        if index > 0 {
+            label.push_str("<br>");
+        }
+        for c in line.chars() {
+            match c {
+                '"' => label.push_str("#quot;"),
+                '#' => label.push_str("#35;"),
+                '<' => label.push_str("#lt;"),
+                '>' => label.push_str("#gt;"),
+                '`' => label.push_str("#96;"),
+                c if c.is_control() => {}
+                c => label.push(c),
+            }
+        }
+    }
+
+    if label.trim().is_empty() {
+        String::from(" ")
+    } else {
+        label
+    }
+}
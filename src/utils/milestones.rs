@@ -0,0 +1,56 @@
+use chrono::{DateTime, Local};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::utils::{MapData, TmmprError};
+
+/// A named snapshot of the whole map, taken at a point in time.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Milestone {
+    pub name: String,
+    pub created_at: DateTime<Local>,
+    pub map: MapData,
+}
+
+/// Path of the file holding a map's milestones: the map file's name with
+/// `.milestones.gz` appended, in the same directory.
+pub fn milestones_path(map_path: &Path) -> PathBuf {
+    let mut file_name = map_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".milestones.gz");
+    map_path.with_file_name(file_name)
+}
+
+/// Reads all milestones of a map, oldest first. A map without a milestones file has none.
+pub fn read_milestones(map_path: &Path) -> Result<Vec<Milestone>, TmmprError> {
+    let path = milestones_path(map_path);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut json_string = String::new();
+    GzDecoder::new(fs::File::open(path)?).read_to_string(&mut json_string)?;
+    let milestones: Vec<Milestone> = serde_json::from_str(&json_string)?;
+    Ok(milestones)
+}
+
+/// Writes all milestones of a map as gzip-compressed JSON, replacing the previous file.
+pub fn write_milestones(map_path: &Path, milestones: &[Milestone]) -> Result<(), TmmprError> {
+    let json_string = serde_json::to_string(milestones)?;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(json_string.as_bytes())?;
+    fs::write(milestones_path(map_path), encoder.finish()?)?;
+    Ok(())
+}
+
+/// Appends a milestone to the map's milestones file.
+pub fn add_milestone(map_path: &Path, milestone: Milestone) -> Result<(), TmmprError> {
+    let mut milestones = read_milestones(map_path)?;
+    milestones.push(milestone);
+    write_milestones(map_path, &milestones)
+}
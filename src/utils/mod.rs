@@ -1,17 +1,73 @@
 pub mod backups;
+pub mod checklist;
+pub mod checksum;
 pub mod colors;
+pub mod crypto;
+pub mod dot;
+pub mod dsl;
+pub mod emergency;
+pub mod error;
 pub mod file_io;
 pub mod filesystem;
 pub mod geometry;
+pub mod html;
+pub mod i18n;
+pub mod invariants;
+pub mod map_diff;
 pub mod map_files;
+pub mod markdown;
+pub mod merge;
+pub mod mermaid;
+pub mod milestones;
+pub mod note_list;
+pub mod outline;
+pub mod plugins;
+pub mod recovery;
+pub mod schema;
 pub mod settings;
+pub mod snapshots;
+pub mod spelling;
+pub mod status_bar;
+pub mod substitute;
+pub mod svg;
 #[cfg(test)]
 mod tests;
+pub mod undo_file;
+pub mod user_commands;
+pub mod workspace;
 
 pub use backups::*;
+pub use checklist::*;
+pub use checksum::*;
 pub use colors::*;
+pub use crypto::*;
+pub use dot::*;
+pub use dsl::*;
+pub use emergency::*;
+pub use error::*;
 pub use file_io::*;
 pub use filesystem::*;
 pub use geometry::*;
+pub use html::*;
+pub use i18n::*;
+pub use invariants::*;
+pub use map_diff::*;
 pub use map_files::*;
+pub use markdown::*;
+pub use merge::*;
+pub use mermaid::*;
+pub use milestones::*;
+pub use note_list::*;
+pub use outline::*;
+pub use plugins::*;
+pub use recovery::*;
+pub use schema::*;
 pub use settings::*;
+pub use snapshots::*;
+pub use spelling::*;
+pub use status_bar::*;
+pub use substitute::*;
+pub use svg::*;
+pub use undo_file::*;
+pub use user_commands::*;
+pub use workspace::*;
@@ -0,0 +1,236 @@
+use ratatui::style::Color;
+
+use crate::utils::get_color_from_string;
+
+/// Columns between the notes of a grid laid out by [`grid_layout`]
+const GRID_GAP_X: usize = 4;
+/// Rows between the notes of a grid laid out by [`grid_layout`]
+const GRID_GAP_Y: usize = 2;
+
+/// How a file lists the notes to import, see [`parse_note_list`].
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum NoteListFormat {
+    /// Plain text, one note per line
+    Lines,
+    /// Plain text, one note per paragraph (lines separated by blank lines)
+    Paragraphs,
+    /// `content,x,y,color` rows, of which all but the content may be left out
+    Csv,
+}
+
+impl NoteListFormat {
+    /// Parses the format name used on the command line.
+    pub fn from_name(name: &str) -> Option<NoteListFormat> {
+        match name {
+            "lines" => Some(NoteListFormat::Lines),
+            "paragraphs" => Some(NoteListFormat::Paragraphs),
+            "csv" => Some(NoteListFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// A note read from a note list.
+#[derive(PartialEq, Debug)]
+pub struct ListedNote {
+    pub content: String,
+    /// Canvas position given in a CSV row; notes without one are laid out in a grid
+    pub position: Option<(isize, isize)>,
+    pub color: Color,
+}
+
+impl ListedNote {
+    fn new(content: String) -> Self {
+        Self {
+            content,
+            position: None,
+            color: Color::White,
+        }
+    }
+}
+
+/// The note list couldn't be imported.
+#[derive(PartialEq, Debug)]
+pub enum NoteListError {
+    /// The file couldn't be read
+    Read,
+    /// The file lists no notes
+    Empty,
+    /// A CSV row isn't `content,x,y,color`, with the line it starts on (from 1)
+    Csv(usize),
+}
+
+/// Reads the notes listed in a plain text or CSV file.
+///
+/// Blank lines and paragraphs are skipped, and so is a CSV header row starting with
+/// `content`. A CSV row gives a note's content, optionally followed by its position
+/// (both `x` and `y`, or neither) and its color by name (`red`, `Green`, ...); fields
+/// with commas, quotes or line breaks are quoted with `"`, quotes doubled inside them.
+pub fn parse_note_list(
+    text: &str,
+    format: NoteListFormat,
+) -> Result<Vec<ListedNote>, NoteListError> {
+    let notes: Vec<ListedNote> = match format {
+        NoteListFormat::Lines => text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| ListedNote::new(line.to_string()))
+            .collect(),
+        NoteListFormat::Paragraphs => paragraphs(text).into_iter().map(ListedNote::new).collect(),
+        NoteListFormat::Csv => {
+            let mut notes = vec![];
+            for (line, fields) in csv_records(text)? {
+                if notes.is_empty() && fields[0].trim().eq_ignore_ascii_case("content") {
+                    continue;
+                }
+                if let Some(note) = csv_note(&fields).ok_or(NoteListError::Csv(line))? {
+                    notes.push(note);
+                }
+            }
+            notes
+        }
+    };
+
+    if notes.is_empty() {
+        return Err(NoteListError::Empty);
+    }
+    Ok(notes)
+}
+
+/// Groups of consecutive non-blank lines.
+fn paragraphs(text: &str) -> Vec<String> {
+    let mut paragraphs = vec![];
+    let mut current: Vec<&str> = vec![];
+    for line in text.lines().chain(std::iter::once("")) {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(current.join("\n"));
+                current.clear();
+            }
+        } else {
+            current.push(line.trim_end());
+        }
+    }
+    paragraphs
+}
+
+/// The note of a CSV row, None for a row without content, or Err for an invalid row.
+fn csv_note(fields: &[String]) -> Option<Option<ListedNote>> {
+    if fields.len() > 4 {
+        return None;
+    }
+    let field = |index: usize| fields.get(index).map(|field| field.trim()).unwrap_or("");
+
+    let content = fields[0].trim_end();
+    if content.trim().is_empty() {
+        return Some(None);
+    }
+
+    let position = match (field(1), field(2)) {
+        ("", "") => None,
+        (x, y) => Some((x.parse().ok()?, y.parse().ok()?)),
+    };
+    let color = match field(3) {
+        "" => Color::White,
+        name => {
+            let mut chars = name.chars();
+            let first = chars.next()?.to_ascii_uppercase();
+            let name = format!("{first}{}", chars.as_str().to_ascii_lowercase());
+            get_color_from_string(&name)
+        }
+    };
+
+    Some(Some(ListedNote {
+        content: content.to_string(),
+        position,
+        color,
+    }))
+}
+
+/// Splits CSV text into rows of fields, each with the line it starts on (from 1).
+/// Blank lines are skipped.
+fn csv_records(text: &str) -> Result<Vec<(usize, Vec<String>)>, NoteListError> {
+    let mut records = vec![];
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            '\n' if in_quotes => {
+                field.push('\n');
+                line += 1;
+            }
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.iter().any(|field| !field.trim().is_empty()) {
+                    records.push((record_line, std::mem::take(&mut fields)));
+                }
+                fields.clear();
+                line += 1;
+                record_line = line;
+            }
+            c => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(NoteListError::Csv(record_line));
+    }
+    fields.push(field);
+    if fields.iter().any(|field| !field.trim().is_empty()) {
+        records.push((record_line, fields));
+    }
+    Ok(records)
+}
+
+/// Positions of notes of the given sizes in a grid about as wide as it is tall, in
+/// rows from the top left. Each column is as wide as its widest note and each row as
+/// tall as its tallest one.
+pub fn grid_layout(sizes: &[(u16, u16)]) -> Vec<(usize, usize)> {
+    if sizes.is_empty() {
+        return vec![];
+    }
+    let columns = (sizes.len() as f64).sqrt().ceil() as usize;
+
+    let mut column_x = vec![0; columns];
+    for column in 1..columns {
+        let widest = sizes
+            .iter()
+            .skip(column - 1)
+            .step_by(columns)
+            .map(|&(width, _)| width as usize)
+            .max()
+            .unwrap_or(0);
+        column_x[column] = column_x[column - 1] + widest + GRID_GAP_X;
+    }
+
+    let mut positions = Vec::with_capacity(sizes.len());
+    let mut row_y = 0;
+    for row in sizes.chunks(columns) {
+        for (column, _) in row.iter().enumerate() {
+            positions.push((column_x[column], row_y));
+        }
+        let tallest = row.iter().map(|&(_, height)| height as usize).max();
+        row_y += tallest.unwrap_or(0) + GRID_GAP_Y;
+    }
+    positions
+}
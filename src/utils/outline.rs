@@ -0,0 +1,275 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    states::map::{Connection, Note},
+    utils::{DslGraph, ENCRYPTED_LABEL},
+};
+
+/// Element and label attribute of an OPML outline entry
+const OPML_ENTRY: (&str, &str) = ("outline", "text");
+/// Element and label attribute of a FreeMind node
+const FREEMIND_ENTRY: (&str, &str) = ("node", "TEXT");
+
+/// Where an outline format keeps the text of a note with a title, which labels the entry
+#[derive(Clone, Copy)]
+enum NoteBody {
+    /// An attribute of the entry, like OPML's `_note`
+    Attribute(&'static str),
+    /// A FreeMind `richcontent` note inside the entry
+    RichContent,
+}
+
+/// The outline file couldn't be imported.
+#[derive(PartialEq, Debug)]
+pub enum OutlineError {
+    /// The file couldn't be read
+    Read,
+    /// Not well-formed XML, with the parser's message
+    Xml(String),
+    /// XML, but neither an OPML nor a FreeMind document
+    UnknownFormat,
+    /// The outline has no entries
+    Empty,
+}
+
+/// Parses an OPML (`<opml>`) or FreeMind (`<map>`, `.mm`) document into a graph.
+///
+/// Every outline entry becomes a note labeled with its text (`text` in OPML, `TEXT` in
+/// FreeMind) and is connected to each of its children. Entries with the same text stay
+/// separate notes. Use [`DslGraph::layout`] to lay the result out as a tree.
+pub fn parse_outline(text: &str) -> Result<DslGraph, OutlineError> {
+    let document =
+        roxmltree::Document::parse(text).map_err(|err| OutlineError::Xml(err.to_string()))?;
+    let root = document.root_element();
+
+    // Tag of the entry elements, their label attribute, and the element they're under
+    let (entry_tag, label_attribute, container) = match root.tag_name().name() {
+        "opml" => (
+            OPML_ENTRY.0,
+            OPML_ENTRY.1,
+            root.children()
+                .find(|child| child.has_tag_name("body"))
+                .ok_or(OutlineError::Empty)?,
+        ),
+        "map" => (FREEMIND_ENTRY.0, FREEMIND_ENTRY.1, root),
+        _ => return Err(OutlineError::UnknownFormat),
+    };
+
+    let mut graph = DslGraph {
+        labels: vec![],
+        edges: vec![],
+    };
+    // Depth-first, so the labels are in document order
+    let mut stack: Vec<(roxmltree::Node, Option<usize>)> = container
+        .children()
+        .filter(|child| child.has_tag_name(entry_tag))
+        .rev()
+        .map(|child| (child, None))
+        .collect();
+    while let Some((node, parent)) = stack.pop() {
+        let label = node
+            .attribute(label_attribute)
+            .or_else(|| node.attribute("title"))
+            .unwrap_or("")
+            .to_string();
+        graph.labels.push(label);
+        let id = graph.labels.len() - 1;
+        if let Some(parent) = parent {
+            graph.edges.push((parent, id));
+        }
+
+        stack.extend(
+            node.children()
+                .filter(|child| child.has_tag_name(entry_tag))
+                .rev()
+                .map(|child| (child, Some(id))),
+        );
+    }
+
+    if graph.labels.is_empty() {
+        return Err(OutlineError::Empty);
+    }
+
+    Ok(graph)
+}
+
+/// A note in the outline tree built from a map, see [`outline_tree`].
+#[derive(PartialEq, Debug)]
+pub struct OutlineEntry {
+    pub note_id: usize,
+    pub children: Vec<OutlineEntry>,
+}
+
+/// Turns the map into a forest for outline formats, which can only nest.
+///
+/// Notes with no incoming connections are roots, in id order, and each note's children
+/// are the targets of its outgoing connections. A note reachable in several ways only
+/// appears the first time it's reached, so links across branches and cycles are lost.
+/// Notes only reachable through a cycle become roots of their own.
+pub fn outline_tree(notes: &HashMap<usize, Note>, connections: &[Connection]) -> Vec<OutlineEntry> {
+    let mut ids: Vec<usize> = notes.keys().copied().collect();
+    ids.sort_unstable();
+
+    let mut children: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut has_incoming = HashSet::new();
+    for connection in connections {
+        let to_id = connection.to_id;
+        if notes.contains_key(&connection.from_id) && notes.contains_key(&to_id) {
+            children.entry(connection.from_id).or_default().push(to_id);
+            has_incoming.insert(to_id);
+        }
+    }
+
+    fn build(
+        id: usize,
+        children: &HashMap<usize, Vec<usize>>,
+        visited: &mut HashSet<usize>,
+    ) -> OutlineEntry {
+        visited.insert(id);
+        let mut entry = OutlineEntry {
+            note_id: id,
+            children: vec![],
+        };
+        for &child in children.get(&id).into_iter().flatten() {
+            if !visited.contains(&child) {
+                entry.children.push(build(child, children, visited));
+            }
+        }
+        entry
+    }
+
+    let mut visited = HashSet::new();
+    let roots = ids.iter().filter(|id| !has_incoming.contains(id));
+    let mut forest = vec![];
+    for &id in roots.chain(ids.iter()) {
+        if !visited.contains(&id) {
+            forest.push(build(id, &children, &mut visited));
+        }
+    }
+    forest
+}
+
+/// Writes the map as an OPML 2.0 outline, see [`outline_tree`] for how it's nested.
+///
+/// Entries are labeled with the note's title if it has one, with the text as the
+/// entry's `_note`, and with the text otherwise.
+pub fn export_opml(
+    title: &str,
+    notes: &HashMap<usize, Note>,
+    connections: &[Connection],
+) -> String {
+    let mut lines = vec![
+        String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"),
+        String::from("<opml version=\"2.0\">"),
+        format!("  <head><title>{}</title></head>", xml_attribute(title)),
+        String::from("  <body>"),
+    ];
+    write_entries(
+        &outline_tree(notes, connections),
+        notes,
+        (OPML_ENTRY, NoteBody::Attribute("_note")),
+        2,
+        &mut lines,
+    );
+    lines.push(String::from("  </body>"));
+    lines.push(String::from("</opml>"));
+    lines.join("\n") + "\n"
+}
+
+/// Writes the map as a FreeMind (`.mm`) mind map, see [`outline_tree`] for how it's
+/// nested.
+///
+/// FreeMind maps have a single root, so a map with several roots is placed under a
+/// root node named `title`. Notes with a title are labeled with it and keep their
+/// text as the node's note.
+pub fn export_freemind(
+    title: &str,
+    notes: &HashMap<usize, Note>,
+    connections: &[Connection],
+) -> String {
+    let forest = outline_tree(notes, connections);
+    let mut lines = vec![String::from("<map version=\"1.0.1\">")];
+    if forest.len() == 1 {
+        write_entries(&forest, notes, FREEMIND, 1, &mut lines);
+    } else {
+        lines.push(format!("  <node TEXT=\"{}\">", xml_attribute(title)));
+        write_entries(&forest, notes, FREEMIND, 2, &mut lines);
+        lines.push(String::from("  </node>"));
+    }
+    lines.push(String::from("</map>"));
+    lines.join("\n") + "\n"
+}
+
+/// FreeMind's entries and where they keep the text of notes with a title
+const FREEMIND: ((&str, &str), NoteBody) = (FREEMIND_ENTRY, NoteBody::RichContent);
+
+/// Writes the entries as nested elements, one per line, indented by `depth`.
+fn write_entries(
+    entries: &[OutlineEntry],
+    notes: &HashMap<usize, Note>,
+    format @ ((tag, attribute), body): ((&str, &str), NoteBody),
+    depth: usize,
+    lines: &mut Vec<String>,
+) {
+    for entry in entries {
+        let indent = "  ".repeat(depth);
+        let note = &notes[&entry.note_id];
+        let mut attributes = format!("{attribute}=\"{}\"", xml_attribute(&outline_label(note)));
+        let text = outline_body(note);
+        if let (Some(text), NoteBody::Attribute(name)) = (text, body) {
+            attributes.push_str(&format!(" {name}=\"{}\"", xml_attribute(text)));
+        }
+        let rich_content = text.filter(|_| matches!(body, NoteBody::RichContent));
+
+        if entry.children.is_empty() && rich_content.is_none() {
+            lines.push(format!("{indent}<{tag} {attributes}/>"));
+            continue;
+        }
+        lines.push(format!("{indent}<{tag} {attributes}>"));
+        if let Some(text) = rich_content {
+            let paragraphs: String = text
+                .lines()
+                .map(|line| format!("<p>{}</p>", xml_attribute(line)))
+                .collect();
+            lines.push(format!(
+                "{indent}  <richcontent TYPE=\"NOTE\"><html><body>{paragraphs}</body></html></richcontent>"
+            ));
+        }
+        write_entries(&entry.children, notes, format, depth + 1, lines);
+        lines.push(format!("{indent}</{tag}>"));
+    }
+}
+
+fn outline_label(note: &Note) -> String {
+    if let Some(title) = &note.title {
+        title.clone()
+    } else if note.encrypted.is_some() {
+        String::from(ENCRYPTED_LABEL)
+    } else {
+        note.content.clone()
+    }
+}
+
+/// The text written along with a note's title, if it has one and the text isn't
+/// encrypted or empty.
+fn outline_body(note: &Note) -> Option<&str> {
+    (note.title.is_some() && note.encrypted.is_none() && !note.content.trim().is_empty())
+        .then_some(note.content.as_str())
+}
+
+/// Escapes text for a double-quoted XML attribute, keeping line breaks as `&#10;`.
+pub fn xml_attribute(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#10;"),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,361 @@
+use chrono::{DateTime, Local};
+use rhai::{AST, Array, CallFnOptions, Dynamic, Engine, EvalAltResult, INT, Map, Scope};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// Most operations a plugin function may take, so a script stuck in a loop can't
+/// freeze the map
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Directory plugin scripts (`*.rhai`) are loaded from.
+pub fn plugins_dir(home: &Path) -> PathBuf {
+    home.join(".config/tmmpr/plugins")
+}
+
+/// A note as plugin scripts see it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PluginNote {
+    pub x: isize,
+    pub y: isize,
+    pub content: String,
+}
+
+/// A change a plugin script made to the map, applied in order once the script returns.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PluginEdit {
+    /// A note the script added, by the id it was given in [`PluginMap`]
+    AddNote(usize, PluginNote),
+    SetContent(usize, String),
+    MoveNote(usize, isize, isize),
+    /// A connection from the first note's right side to the second's left side
+    Connect(usize, usize),
+}
+
+/// The map as plugin scripts see it, along with the edits they made to it.
+///
+/// Scripts only get to see the notes they may read and change, so encrypted notes that
+/// weren't unlocked are left out.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct PluginMap {
+    /// Path of the map file; files written by scripts are relative to its directory
+    pub path: PathBuf,
+    pub notes: BTreeMap<usize, PluginNote>,
+    /// (from, to) note ids
+    pub connections: Vec<(usize, usize)>,
+    /// Id the next note added by a script is given
+    pub next_id: usize,
+    pub edits: Vec<PluginEdit>,
+}
+
+impl PluginMap {
+    fn note(&self, id: INT) -> Result<(usize, &PluginNote), Box<EvalAltResult>> {
+        usize::try_from(id)
+            .ok()
+            .and_then(|id| Some((id, self.notes.get(&id)?)))
+            .ok_or_else(|| format!("no note with id {id}").into())
+    }
+
+    fn add_note(&mut self, x: INT, y: INT, content: String) -> Result<INT, Box<EvalAltResult>> {
+        let note = PluginNote {
+            x: position(x)?,
+            y: position(y)?,
+            content,
+        };
+        let id = self.next_id;
+        self.next_id += 1;
+        self.notes.insert(id, note.clone());
+        self.edits.push(PluginEdit::AddNote(id, note));
+        Ok(id as INT)
+    }
+
+    fn set_content(&mut self, id: INT, content: String) -> Result<(), Box<EvalAltResult>> {
+        let (id, _) = self.note(id)?;
+        self.notes.get_mut(&id).unwrap().content = content.clone();
+        self.edits.push(PluginEdit::SetContent(id, content));
+        Ok(())
+    }
+
+    fn move_note(&mut self, id: INT, x: INT, y: INT) -> Result<(), Box<EvalAltResult>> {
+        let (id, _) = self.note(id)?;
+        let (x, y) = (position(x)?, position(y)?);
+        let note = self.notes.get_mut(&id).unwrap();
+        (note.x, note.y) = (x, y);
+        self.edits.push(PluginEdit::MoveNote(id, x, y));
+        Ok(())
+    }
+
+    fn connect(&mut self, from: INT, to: INT) -> Result<(), Box<EvalAltResult>> {
+        let (from, _) = self.note(from)?;
+        let (to, _) = self.note(to)?;
+        self.connections.push((from, to));
+        self.edits.push(PluginEdit::Connect(from, to));
+        Ok(())
+    }
+
+    fn write_file(&self, path: &str, text: &str) -> Result<(), Box<EvalAltResult>> {
+        let path = self
+            .path
+            .parent()
+            .map_or_else(|| PathBuf::from(path), |dir| dir.join(path));
+        fs::write(&path, text).map_err(|err| format!("writing {}: {err}", path.display()).into())
+    }
+}
+
+fn position(value: INT) -> Result<isize, Box<EvalAltResult>> {
+    isize::try_from(value).map_err(|_| format!("{value} isn't a valid position").into())
+}
+
+fn note_to_map(id: usize, note: &PluginNote) -> Map {
+    let mut map = Map::new();
+    map.insert("id".into(), (id as INT).into());
+    map.insert("x".into(), (note.x as INT).into());
+    map.insert("y".into(), (note.y as INT).into());
+    map.insert("content".into(), note.content.clone().into());
+    map
+}
+
+/// The engine scripts run in, with the functions they use to read and change the map:
+///
+/// - `notes()` - every note as `#{id, x, y, content}`
+/// - `note(id)` - a single note
+/// - `connections()` - every connection as `#{from, to}`
+/// - `add_note(x, y, content)` - adds a note, returning its id
+/// - `set_content(id, content)`, `move_note(id, x, y)`, `connect(from, to)`
+/// - `map_path()` - path of the map file
+/// - `write_file(path, text)` - writes a file, relative to the map file's directory
+///
+/// `print` output is dropped, as it would garble the screen.
+fn plugin_engine(map: &Rc<RefCell<PluginMap>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.on_print(|_| {});
+    engine.on_debug(|_, _, _| {});
+
+    let shared = map.clone();
+    engine.register_fn("notes", move || -> Array {
+        let map = shared.borrow();
+        map.notes
+            .iter()
+            .map(|(&id, note)| note_to_map(id, note).into())
+            .collect()
+    });
+    let shared = map.clone();
+    engine.register_fn("note", move |id: INT| -> Result<Map, Box<EvalAltResult>> {
+        let map = shared.borrow();
+        let (id, note) = map.note(id)?;
+        Ok(note_to_map(id, note))
+    });
+    let shared = map.clone();
+    engine.register_fn("connections", move || -> Array {
+        let map = shared.borrow();
+        map.connections
+            .iter()
+            .map(|&(from, to)| {
+                let mut connection = Map::new();
+                connection.insert("from".into(), (from as INT).into());
+                connection.insert("to".into(), (to as INT).into());
+                connection.into()
+            })
+            .collect()
+    });
+    let shared = map.clone();
+    engine.register_fn("add_note", move |x: INT, y: INT, content: &str| {
+        shared.borrow_mut().add_note(x, y, content.to_string())
+    });
+    let shared = map.clone();
+    engine.register_fn("set_content", move |id: INT, content: &str| {
+        shared.borrow_mut().set_content(id, content.to_string())
+    });
+    let shared = map.clone();
+    engine.register_fn("move_note", move |id: INT, x: INT, y: INT| {
+        shared.borrow_mut().move_note(id, x, y)
+    });
+    let shared = map.clone();
+    engine.register_fn("connect", move |from: INT, to: INT| {
+        shared.borrow_mut().connect(from, to)
+    });
+    let shared = map.clone();
+    engine.register_fn("map_path", move || -> String {
+        shared.borrow().path.display().to_string()
+    });
+    let shared = map.clone();
+    engine.register_fn("write_file", move |path: &str, text: &str| {
+        shared.borrow().write_file(path, text)
+    });
+
+    engine
+}
+
+/// A plugin script that failed to load or run.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PluginError {
+    /// File name of the script
+    pub plugin: String,
+    pub message: String,
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.plugin, self.message)
+    }
+}
+
+struct Plugin {
+    name: String,
+    ast: AST,
+}
+
+/// Plugin scripts loaded from the plugins directory (see [`plugins_dir`]), written in
+/// [Rhai](https://rhai.rs).
+///
+/// Scripts define functions that are called with [`Plugins::call`]: the hooks
+/// `on_save()` and `on_note_created(id)`, and any function without parameters run with
+/// `:plugin <function>`.
+#[derive(Default)]
+pub struct Plugins {
+    /// Where the scripts were loaded from, and errors are logged to
+    dir: Option<PathBuf>,
+    plugins: Vec<Plugin>,
+}
+
+impl fmt::Debug for Plugins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Plugins")
+            .field("dir", &self.dir)
+            .field("plugins", &self.names())
+            .finish()
+    }
+}
+
+impl PartialEq for Plugins {
+    fn eq(&self, other: &Self) -> bool {
+        self.dir == other.dir && self.names() == other.names()
+    }
+}
+
+impl Plugins {
+    /// Compiles every `*.rhai` script in the directory, in file name order. Scripts that
+    /// don't compile are left out and reported; a missing directory has no plugins.
+    pub fn load(dir: &Path) -> (Plugins, Vec<PluginError>) {
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+            .collect();
+        paths.sort();
+
+        let engine = Engine::new();
+        let mut plugins = vec![];
+        let mut errors = vec![];
+        for path in paths {
+            let name = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            let compiled = fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|script| engine.compile(script).map_err(|err| err.to_string()));
+            match compiled {
+                Ok(ast) => plugins.push(Plugin { name, ast }),
+                Err(message) => errors.push(PluginError {
+                    plugin: name,
+                    message,
+                }),
+            }
+        }
+
+        let plugins = Plugins {
+            dir: Some(dir.to_path_buf()),
+            plugins,
+        };
+        (plugins, errors)
+    }
+
+    /// File names of the loaded scripts.
+    pub fn names(&self) -> Vec<&str> {
+        self.plugins
+            .iter()
+            .map(|plugin| plugin.name.as_str())
+            .collect()
+    }
+
+    /// Whether any script defines the function with that many parameters.
+    pub fn defines(&self, function: &str, params: usize) -> bool {
+        self.plugins
+            .iter()
+            .any(|plugin| defines(&plugin.ast, function, params))
+    }
+
+    /// Calls the function in every script that defines it, in order, each seeing the
+    /// changes of the ones before. A script that fails has none of its changes kept.
+    pub fn call(
+        &self,
+        function: &str,
+        args: &[usize],
+        map: PluginMap,
+    ) -> (PluginMap, Vec<PluginError>) {
+        let args: Vec<Dynamic> = args.iter().map(|&arg| (arg as INT).into()).collect();
+        let shared = Rc::new(RefCell::new(map));
+        let engine = plugin_engine(&shared);
+        let mut errors = vec![];
+
+        for plugin in &self.plugins {
+            if !defines(&plugin.ast, function, args.len()) {
+                continue;
+            }
+            let before = shared.borrow().clone();
+            let result = engine.call_fn_with_options::<Dynamic>(
+                CallFnOptions::new().eval_ast(false),
+                &mut Scope::new(),
+                &plugin.ast,
+                function,
+                args.clone(),
+            );
+            if let Err(err) = result {
+                *shared.borrow_mut() = before;
+                errors.push(PluginError {
+                    plugin: plugin.name.clone(),
+                    message: err.to_string(),
+                });
+            }
+        }
+
+        drop(engine);
+        let map = Rc::try_unwrap(shared)
+            .map(RefCell::into_inner)
+            .unwrap_or_else(|shared| shared.borrow().clone());
+        (map, errors)
+    }
+
+    /// Appends the errors to `errors.log` in the plugins directory, as the message bar
+    /// has no room for them.
+    pub fn log_errors(&self, errors: &[PluginError], now: DateTime<Local>) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let Ok(mut log) = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join("errors.log"))
+        else {
+            return;
+        };
+        for error in errors {
+            let _ = writeln!(log, "{} {error}", now.format("%Y-%m-%d %H:%M:%S"));
+        }
+    }
+}
+
+fn defines(ast: &AST, function: &str, params: usize) -> bool {
+    ast.iter_functions()
+        .any(|f| f.name == function && f.params.len() == params)
+}
@@ -0,0 +1,170 @@
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    states::map::{Connection, Hyperedge, Note, Region, ViewPos},
+    utils::{MapData, TmmprError, read_json_data},
+};
+
+/// What the lenient loader left out of a damaged map file.
+#[derive(PartialEq, Debug, Default, Clone)]
+pub struct RecoveryReport {
+    /// Each value that couldn't be read, with where it was and why, e.g.
+    /// ``notes.3: invalid type: integer `7`, expected a string``
+    pub skipped: Vec<String>,
+}
+
+impl RecoveryReport {
+    fn skip(&mut self, field: &str, reason: impl std::fmt::Display) {
+        self.skipped.push(format!("{field}: {reason}"));
+    }
+}
+
+/// Path the damaged file is copied to before a recovered map is opened: the map file's
+/// name with `.damaged` appended, in the same directory.
+pub fn damaged_copy_path(map_path: &Path) -> PathBuf {
+    let mut file_name = map_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".damaged");
+    map_path.with_file_name(file_name)
+}
+
+/// Reads a map file that doesn't load as a whole, keeping every note, connection,
+/// region, fan-out, layer and trashed note that can be read on its own and leaving out
+/// the rest, along with connections to notes that were left out.
+///
+/// The file still has to be JSON with a `notes` object; anything else isn't a map that
+/// can be recovered.
+pub fn recover_map_data(path: &Path) -> Result<(MapData, RecoveryReport), TmmprError> {
+    let Value::Object(mut fields) = read_json_data::<Value>(path)? else {
+        return Err(TmmprError::Validation(String::from("not a map file")));
+    };
+    let Some(Value::Object(note_values)) = fields.remove("notes") else {
+        return Err(TmmprError::Validation(String::from(
+            "not a map file: it has no notes",
+        )));
+    };
+    let mut report = RecoveryReport::default();
+
+    let mut notes: HashMap<usize, Note> = HashMap::new();
+    for (key, value) in note_values {
+        let field = format!("notes.{key}");
+        match key.parse::<usize>() {
+            Ok(id) => match serde_json::from_value(value) {
+                Ok(note) => {
+                    notes.insert(id, note);
+                }
+                Err(err) => report.skip(&field, err),
+            },
+            Err(_) => report.skip(&field, "not a note id"),
+        }
+    }
+
+    // Notes left out of the stacking order are put on top, in id order
+    let mut render_order: Vec<usize> =
+        recover_field(&mut fields, "render_order", &mut report).unwrap_or_default();
+    render_order.retain(|id| notes.contains_key(id));
+    render_order.dedup();
+    let mut unordered: Vec<usize> = notes
+        .keys()
+        .filter(|id| !render_order.contains(id))
+        .copied()
+        .collect();
+    unordered.sort_unstable();
+    render_order.extend(unordered);
+
+    // Never hands out the id of a note that was read
+    let next_id = notes.keys().max().map_or(0, |id| id + 1);
+    let counter_field = if fields.contains_key("next_note_id_counter") {
+        "next_note_id_counter"
+    } else {
+        "next_note_id"
+    };
+    let counter: Option<usize> = recover_field(&mut fields, counter_field, &mut report);
+
+    // Connections and fan-outs can't be drawn without their notes
+    let mut connections: Vec<Connection> = recover_list(&mut fields, "connections", &mut report);
+    let count = connections.len();
+    connections.retain(|connection| {
+        notes.contains_key(&connection.from_id) && notes.contains_key(&connection.to_id)
+    });
+    if connections.len() < count {
+        report.skip(
+            "connections",
+            format!("{} to notes that were left out", count - connections.len()),
+        );
+    }
+    let mut hyperedges: Vec<Hyperedge> = recover_list(&mut fields, "hyperedges", &mut report);
+    for hyperedge in &mut hyperedges {
+        hyperedge.to_ids.retain(|id| notes.contains_key(id));
+    }
+    hyperedges
+        .retain(|hyperedge| notes.contains_key(&hyperedge.from_id) && !hyperedge.to_ids.is_empty());
+    let mut regions: Vec<Region> = recover_list(&mut fields, "regions", &mut report);
+    for region in &mut regions {
+        region.members.retain(|id| notes.contains_key(id));
+    }
+
+    let map_data = MapData {
+        view_pos: recover_field(&mut fields, "view_pos", &mut report).unwrap_or(ViewPos::new()),
+        next_note_id_counter: counter.unwrap_or(0).max(next_id),
+        render_order,
+        connections,
+        regions,
+        hyperedges,
+        layers: recover_list(&mut fields, "layers", &mut report),
+        view_state: recover_field(&mut fields, "view_state", &mut report),
+        trash: recover_list(&mut fields, "trash", &mut report),
+        notes,
+    };
+    Ok((map_data, report))
+}
+
+/// Copies the damaged map file next to it, see [`damaged_copy_path`], so saving the
+/// recovered map doesn't lose what couldn't be read.
+pub fn keep_damaged_copy(map_path: &Path) -> Result<PathBuf, TmmprError> {
+    let copy_path = damaged_copy_path(map_path);
+    fs::copy(map_path, &copy_path)?;
+    Ok(copy_path)
+}
+
+/// Reads a field, None if it's missing or can't be read (which is reported).
+fn recover_field<T: DeserializeOwned>(
+    fields: &mut Map<String, Value>,
+    field: &str,
+    report: &mut RecoveryReport,
+) -> Option<T> {
+    let value = fields.remove(field)?;
+    serde_json::from_value(value)
+        .map_err(|err| report.skip(field, err))
+        .ok()
+}
+
+/// Reads a list field item by item, leaving out (and reporting) the items that can't be
+/// read. A missing field is an empty list.
+fn recover_list<T: DeserializeOwned>(
+    fields: &mut Map<String, Value>,
+    field: &str,
+    report: &mut RecoveryReport,
+) -> Vec<T> {
+    match fields.remove(field) {
+        None | Some(Value::Null) => vec![],
+        Some(Value::Array(items)) => items
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, item)| {
+                serde_json::from_value(item)
+                    .map_err(|err| report.skip(&format!("{field}.{index}"), err))
+                    .ok()
+            })
+            .collect(),
+        Some(_) => {
+            report.skip(field, "not a list");
+            vec![]
+        }
+    }
+}
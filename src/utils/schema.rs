@@ -0,0 +1,25 @@
+use schemars::schema_for;
+use serde_json::json;
+
+use crate::utils::{CHECKSUM_KEY, MapData};
+
+/// Generates the JSON Schema of plain (not encrypted) map files from [`MapData`], so it
+/// describes exactly what the map loader accepts. Shipped as `schema/map.schema.json`
+/// for editors and other tools working with maps, and printed by `tmmpr schema`.
+pub fn map_schema() -> String {
+    let mut schema = schema_for!(MapData);
+    // Added when the map is written, see `ChecksummedMapData`
+    if let Some(properties) = schema
+        .get_mut("properties")
+        .and_then(|properties| properties.as_object_mut())
+    {
+        properties.insert(
+            CHECKSUM_KEY.to_string(),
+            json!({
+                "description": "SHA-256 (hex) of the file without this field, with keys sorted and no whitespace",
+                "type": "string",
+            }),
+        );
+    }
+    serde_json::to_string_pretty(&schema).expect("a schema is plain JSON") + "\n"
+}
@@ -1,8 +1,9 @@
-use std::path::Path;
+use chrono::{DateTime, Local};
+use std::path::{Path, PathBuf};
 
 use crate::{
     states::settings::Settings,
-    utils::{filesystem::FileSystem, write_json_data},
+    utils::{TmmprError, filesystem::FileSystem, update_json_data},
 };
 
 /// Saves the application settings to the settings file using a custom FileSystem.
@@ -18,26 +19,74 @@ use crate::{
 pub fn save_settings_to_file_with_fs(
     settings: &Settings,
     fs: &dyn FileSystem,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let home_path = fs.get_home_dir().ok_or("Could not find home directory")?;
+) -> Result<(), TmmprError> {
+    save_settings_to_path(settings, &settings_file_path_with_fs(fs)?)
+}
+
+/// Records when a map file was last backed up on load, changing nothing else in the
+/// settings file, so settings changed by another running instance are kept.
+pub fn record_backup_date_with_fs(
+    settings: &Settings,
+    filename: &str,
+    date: DateTime<Local>,
+    fs: &dyn FileSystem,
+) -> Result<(), TmmprError> {
+    update_json_data(
+        &settings_file_path_with_fs(fs)?,
+        |on_disk: Option<Settings>| {
+            let mut merged = on_disk.unwrap_or_else(|| settings.clone());
+            merged.backup_dates.insert(filename.to_string(), date);
+            merged
+        },
+    )?;
+    Ok(())
+}
+
+/// Path of the settings file, creating its directory if needed.
+fn settings_file_path_with_fs(fs: &dyn FileSystem) -> Result<PathBuf, TmmprError> {
+    let home_path = fs
+        .get_home_dir()
+        .ok_or_else(|| TmmprError::Validation(String::from("could not find the home directory")))?;
 
     // Using XDG Base Directory specification for config files
     let config_dir_path = home_path.join(".config/tmmpr/");
 
     fs.create_dir_all(&config_dir_path)?;
 
-    let settings_file_path = config_dir_path.join("settings").with_extension("json");
-
-    save_settings_to_path(settings, &settings_file_path)
+    Ok(config_dir_path.join("settings").with_extension("json"))
 }
 
 /// Lower-level function for saving settings to an arbitrary path.
 ///
 /// Separated from `save_settings_to_file_with_fs` to allow testing without
 /// filesystem abstraction and to enable custom save locations if needed.
-pub fn save_settings_to_path(
-    settings: &Settings,
-    path: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
-    write_json_data(path, settings)
+///
+/// Merges with the file's current contents (see [`merge_settings`]) so another running
+/// instance's backup records aren't lost.
+pub fn save_settings_to_path(settings: &Settings, path: &Path) -> Result<(), TmmprError> {
+    update_json_data(path, |on_disk: Option<Settings>| {
+        merge_settings(settings, on_disk)
+    })?;
+    Ok(())
+}
+
+/// Combines settings being saved with the settings currently on disk.
+///
+/// The saved settings' options win, since the user just chose them. Backup records
+/// from both are kept, with the later date for maps backed up by both.
+pub fn merge_settings(settings: &Settings, on_disk: Option<Settings>) -> Settings {
+    let mut merged = settings.clone();
+
+    for (filename, date) in on_disk
+        .map(|on_disk| on_disk.backup_dates)
+        .unwrap_or_default()
+    {
+        merged
+            .backup_dates
+            .entry(filename)
+            .and_modify(|merged_date| *merged_date = (*merged_date).max(date))
+            .or_insert(date);
+    }
+
+    merged
 }
@@ -0,0 +1,80 @@
+use chrono::NaiveDateTime;
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::utils::{TmmprError, map_file_extension, map_name};
+
+/// Format of the time a snapshot's file name starts with
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// A named checkpoint of a map, stored as a map file of its own in the map's
+/// snapshots directory, so it can also be opened like any other map.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Snapshot {
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub path: PathBuf,
+}
+
+/// Directory holding a map's snapshots: the map file's name with `.snapshots`
+/// appended, in the same directory.
+pub fn snapshots_dir(map_path: &Path) -> PathBuf {
+    let mut dir_name = map_path.file_name().unwrap_or_default().to_os_string();
+    dir_name.push(".snapshots");
+    map_path.with_file_name(dir_name)
+}
+
+/// Path of a new snapshot of the map: `<time>_<name>.json`, compressed like the map
+/// file. Characters that can't be in a file name are replaced in the name.
+pub fn snapshot_path(
+    map_path: &Path,
+    name: &str,
+    created_at: NaiveDateTime,
+    compressed: bool,
+) -> PathBuf {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+
+    snapshots_dir(map_path).join(format!(
+        "{}_{name}.{}",
+        created_at.format(TIMESTAMP_FORMAT),
+        map_file_extension(compressed)
+    ))
+}
+
+/// Reads the name and time of a snapshot back from its file name.
+fn parse_snapshot_path(path: &Path) -> Option<Snapshot> {
+    let file_stem = map_name(path);
+    let (stamp, name) = file_stem.split_once('_')?;
+
+    Some(Snapshot {
+        name: name.to_string(),
+        created_at: NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT).ok()?,
+        path: path.to_path_buf(),
+    })
+}
+
+/// All snapshots of a map, newest first. A map without a snapshots directory has none.
+pub fn list_snapshots(map_path: &Path) -> Result<Vec<Snapshot>, TmmprError> {
+    let dir = snapshots_dir(map_path);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut snapshots: Vec<Snapshot> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| parse_snapshot_path(&entry.path()))
+        .collect();
+    snapshots.sort_by_key(|snapshot| Reverse(snapshot.created_at));
+    Ok(snapshots)
+}
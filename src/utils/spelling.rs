@@ -0,0 +1,409 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::utils::TmmprError;
+
+/// Most suggestions offered for a misspelled word
+const MAX_SUGGESTIONS: usize = 10;
+
+/// Letters tried when suggesting words, if the dictionary's `.aff` file has no `TRY` line
+const DEFAULT_TRY: &str = "esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'";
+
+/// Directories searched for hunspell dictionaries (`<name>.dic` with its `<name>.aff`),
+/// the tmmpr config directory first.
+pub fn dictionary_dirs(home: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    if let Some(home) = home {
+        dirs.push(home.join(".config/tmmpr/dictionaries"));
+    }
+    dirs.push(PathBuf::from("/usr/share/hunspell"));
+    dirs.push(PathBuf::from("/usr/share/myspell"));
+    dirs
+}
+
+/// Names of the dictionaries found in the directories, e.g. `en_US`, sorted.
+pub fn available_dictionaries(dirs: &[PathBuf]) -> Vec<String> {
+    let mut names: Vec<String> = dirs
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "dic").then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// How the `.aff` file writes the flags of a word.
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum FlagType {
+    /// One character per flag, the default
+    Char,
+    /// Two characters per flag (`FLAG long`)
+    Long,
+    /// Comma-separated numbers (`FLAG num`)
+    Num,
+}
+
+impl FlagType {
+    fn split(self, flags: &str) -> Vec<String> {
+        match self {
+            FlagType::Char => flags.chars().map(String::from).collect(),
+            FlagType::Long => flags
+                .chars()
+                .collect::<Vec<char>>()
+                .chunks(2)
+                .map(|pair| pair.iter().collect())
+                .collect(),
+            FlagType::Num => flags.split(',').map(String::from).collect(),
+        }
+    }
+}
+
+/// One character of an affix condition: any character, or one in or out of a set.
+#[derive(Debug)]
+enum CharClass {
+    Any,
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl CharClass {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            CharClass::Any => true,
+            CharClass::OneOf(chars) => chars.contains(&c),
+            CharClass::NoneOf(chars) => !chars.contains(&c),
+        }
+    }
+
+    /// Parses a condition like `[^aeiou]y`; `.` matches anything.
+    fn parse_condition(condition: &str) -> Vec<CharClass> {
+        let mut classes = vec![];
+        let mut chars = condition.chars();
+
+        while let Some(c) = chars.next() {
+            classes.push(match c {
+                '.' => CharClass::Any,
+                '[' => {
+                    let set: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                    match set.strip_prefix('^') {
+                        Some(set) => CharClass::NoneOf(set.chars().collect()),
+                        None => CharClass::OneOf(set.chars().collect()),
+                    }
+                }
+                c => CharClass::OneOf(vec![c]),
+            });
+        }
+
+        classes
+    }
+}
+
+/// A prefix or suffix rule: strip some characters off a stem and add others, if the
+/// stem's start (prefix) or end (suffix) meets the condition.
+#[derive(Debug)]
+struct AffixRule {
+    strip: String,
+    add: String,
+    condition: Vec<CharClass>,
+}
+
+impl AffixRule {
+    fn apply(&self, stem: &str, prefix: bool) -> Option<String> {
+        let chars: Vec<char> = stem.chars().collect();
+        if chars.len() < self.condition.len() {
+            return None;
+        }
+        let checked = if prefix {
+            &chars[..self.condition.len()]
+        } else {
+            &chars[chars.len() - self.condition.len()..]
+        };
+        if !checked
+            .iter()
+            .zip(&self.condition)
+            .all(|(&c, class)| class.matches(c))
+        {
+            return None;
+        }
+
+        if prefix {
+            let rest = stem.strip_prefix(self.strip.as_str())?;
+            Some(format!("{}{rest}", self.add))
+        } else {
+            let rest = stem.strip_suffix(self.strip.as_str())?;
+            Some(format!("{rest}{}", self.add))
+        }
+    }
+}
+
+/// The rules of one affix flag.
+#[derive(Debug)]
+struct AffixClass {
+    prefix: bool,
+    /// Whether the rules combine with the other kind (prefix with suffix)
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+/// Words to spell check against, read from a hunspell dictionary.
+///
+/// The `.dic` file's stems are expanded with the prefix and suffix rules of the `.aff`
+/// file up front, so checking a word is a lookup. Other hunspell features, such as
+/// compounding and replacement tables, aren't supported.
+#[derive(PartialEq, Debug, Default)]
+pub struct Dictionary {
+    words: HashSet<String>,
+    /// Characters tried when suggesting corrections, most likely first
+    try_chars: Vec<char>,
+}
+
+impl Dictionary {
+    /// Loads the dictionary with the name (`<name>.dic` and `<name>.aff`) from the
+    /// first of the directories that has it.
+    pub fn load(dirs: &[PathBuf], name: &str) -> Result<Dictionary, TmmprError> {
+        let dic_path = dirs
+            .iter()
+            .map(|dir| dir.join(format!("{name}.dic")))
+            .find(|path| path.exists())
+            .ok_or_else(|| TmmprError::Validation(format!("no dictionary named {name}")))?;
+        let aff = match fs::read(dic_path.with_extension("aff")) {
+            Ok(bytes) => decode(bytes),
+            Err(_) => String::new(),
+        };
+        let dic = decode(fs::read(&dic_path)?);
+
+        Ok(Dictionary::from_hunspell(&dic, &aff))
+    }
+
+    /// Builds a dictionary from the contents of a `.dic` and `.aff` file; the `.aff`
+    /// contents may be empty for a plain word list.
+    pub fn from_hunspell(dic: &str, aff: &str) -> Dictionary {
+        let mut flag_type = FlagType::Char;
+        let mut try_chars: Vec<char> = DEFAULT_TRY.chars().collect();
+        let mut need_affix = None;
+        let mut classes: HashMap<String, AffixClass> = HashMap::new();
+
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", "long", ..] => flag_type = FlagType::Long,
+                ["FLAG", "num", ..] => flag_type = FlagType::Num,
+                ["TRY", chars, ..] => try_chars = chars.chars().collect(),
+                ["NEEDAFFIX", flag, ..] => need_affix = Some(flag.to_string()),
+                // Header line of an affix class: `SFX A Y 2`
+                [kind @ ("PFX" | "SFX"), flag, cross, count]
+                    if count.parse::<usize>().is_ok() && !classes.contains_key(*flag) =>
+                {
+                    classes.insert(
+                        flag.to_string(),
+                        AffixClass {
+                            prefix: *kind == "PFX",
+                            cross_product: *cross == "Y",
+                            rules: vec![],
+                        },
+                    );
+                }
+                // A rule of the class: `SFX A y ied [^aeiou]y`
+                [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+                    if let Some(class) = classes.get_mut(*flag)
+                        && class.prefix == (*kind == "PFX")
+                    {
+                        let unset = |affix: &str| if affix == "0" { "" } else { affix }.to_string();
+                        // Flags after a slash continue with more affixes, not supported
+                        let add = add.split('/').next().unwrap_or_default();
+                        class.rules.push(AffixRule {
+                            strip: unset(strip),
+                            add: unset(add),
+                            condition: CharClass::parse_condition(rest.first().unwrap_or(&".")),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut words = HashSet::new();
+        let mut lines = dic.lines();
+        // The first line is the number of words
+        if let Some(first) = lines.next()
+            && first.trim().parse::<usize>().is_err()
+        {
+            words.insert(first.trim().to_string());
+        }
+
+        for line in lines {
+            // Anything after whitespace is morphological data
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (stem, flags) = match entry.split_once('/') {
+                Some((stem, flags)) => (stem, flag_type.split(flags)),
+                None => (entry, vec![]),
+            };
+            if !need_affix.as_ref().is_some_and(|flag| flags.contains(flag)) {
+                words.insert(stem.to_string());
+            }
+
+            let affixes: Vec<&AffixClass> =
+                flags.iter().filter_map(|flag| classes.get(flag)).collect();
+            let mut suffixed = vec![];
+            for class in affixes.iter().filter(|class| !class.prefix) {
+                for rule in &class.rules {
+                    if let Some(word) = rule.apply(stem, false) {
+                        if class.cross_product {
+                            suffixed.push(word.clone());
+                        }
+                        words.insert(word);
+                    }
+                }
+            }
+            for class in affixes.iter().filter(|class| class.prefix) {
+                for rule in &class.rules {
+                    let crossed = suffixed.iter().filter(|_| class.cross_product);
+                    for base in std::iter::once(stem).chain(crossed.map(String::as_str)) {
+                        if let Some(word) = rule.apply(base, true) {
+                            words.insert(word);
+                        }
+                    }
+                }
+            }
+        }
+
+        Dictionary { words, try_chars }
+    }
+
+    /// Whether the word is spelled right. Capitalized or all-caps versions of a word in
+    /// the dictionary count too, and so do words with digits or of a single letter.
+    pub fn check(&self, word: &str) -> bool {
+        let word = word.replace('’', "'");
+        if word.chars().count() < 2 || word.chars().any(|c| c.is_numeric()) {
+            return true;
+        }
+        if self.words.contains(&word) {
+            return true;
+        }
+
+        let lower = word.to_lowercase();
+        let mut chars = word.chars();
+        let first_upper = chars.next().is_some_and(char::is_uppercase);
+        let all_upper = word == word.to_uppercase();
+        let rest_lower = chars.as_str() == chars.as_str().to_lowercase();
+        (first_upper && rest_lower || all_upper)
+            && (self.words.contains(&lower) || self.words.contains(&capitalize(&lower)))
+    }
+
+    /// Byte ranges of the misspelled words in the text.
+    pub fn misspelled(&self, text: &str) -> Vec<Range<usize>> {
+        text.split_word_bound_indices()
+            .filter(|(_, word)| word.chars().any(char::is_alphabetic) && !self.check(word))
+            .map(|(start, word)| start..start + word.len())
+            .collect()
+    }
+
+    /// Words in the dictionary one or two edits (a letter removed, added, changed or
+    /// swapped with the next) away from the word, closest first, in its capitalization.
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let lower = word.to_lowercase();
+        let known = |candidate: &String| {
+            self.words.contains(candidate) || self.words.contains(&capitalize(candidate))
+        };
+
+        let one_edit = self.edits(&lower);
+        let mut found: Vec<String> = one_edit.iter().filter(|c| known(c)).cloned().collect();
+        if found.is_empty() {
+            found = one_edit
+                .iter()
+                .flat_map(|candidate| self.edits(candidate))
+                .filter(known)
+                .collect();
+        }
+
+        let mut seen = HashSet::new();
+        found.retain(|candidate| *candidate != lower && seen.insert(candidate.clone()));
+        found.truncate(MAX_SUGGESTIONS);
+
+        let all_upper = word.chars().count() > 1 && word == word.to_uppercase();
+        let first_upper = word.chars().next().is_some_and(char::is_uppercase);
+        found
+            .into_iter()
+            .map(|candidate| {
+                if all_upper {
+                    candidate.to_uppercase()
+                } else if first_upper {
+                    capitalize(&candidate)
+                } else if !self.words.contains(&candidate) {
+                    // Names are only in the dictionary capitalized
+                    capitalize(&candidate)
+                } else {
+                    candidate
+                }
+            })
+            .collect()
+    }
+
+    /// Every string one edit away from the word, swaps and changed letters first.
+    fn edits(&self, word: &str) -> Vec<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let letters = self.try_chars.iter().filter(|c| !c.is_uppercase()).copied();
+        let with = |prefix: &[char], middle: Option<char>, suffix: &[char]| -> String {
+            prefix.iter().chain(middle.iter()).chain(suffix).collect()
+        };
+        let mut edits = vec![];
+
+        for i in 0..chars.len().saturating_sub(1) {
+            let mut swapped = chars.clone();
+            swapped.swap(i, i + 1);
+            edits.push(swapped.into_iter().collect());
+        }
+        for i in 0..chars.len() {
+            for letter in letters.clone().filter(|&letter| letter != chars[i]) {
+                edits.push(with(&chars[..i], Some(letter), &chars[i + 1..]));
+            }
+        }
+        for i in 0..chars.len() {
+            edits.push(with(&chars[..i], None, &chars[i + 1..]));
+        }
+        for i in 0..=chars.len() {
+            for letter in letters.clone() {
+                edits.push(with(&chars[..i], Some(letter), &chars[i..]));
+            }
+        }
+
+        edits
+    }
+}
+
+/// Byte range of the word the position is in or just after, if any.
+pub fn word_at(text: &str, pos: usize) -> Option<Range<usize>> {
+    text.split_word_bound_indices()
+        .filter(|(_, word)| word.chars().any(char::is_alphabetic))
+        .map(|(start, word)| start..start + word.len())
+        .find(|range| range.start <= pos && pos <= range.end)
+}
+
+/// The word with its first letter in upper case.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Dictionary files are often in a legacy encoding (`SET ISO8859-1`); anything that
+/// isn't UTF-8 is read as Latin-1.
+fn decode(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes)
+        .unwrap_or_else(|err| err.into_bytes().into_iter().map(char::from).collect())
+}
@@ -0,0 +1,103 @@
+/// Template used when the status bar format is first edited in the settings; it
+/// shows the same items as the built-in bar.
+pub const DEFAULT_STATUS_BAR_FORMAT: &str = "{mode}|{x}, {y}";
+
+/// A value the status bar can show, written as `{name}` in the format.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum BarField {
+    /// `{mode}`: the current mode
+    Mode,
+    /// `{x}`: the view position's x coordinate
+    X,
+    /// `{y}`: the view position's y coordinate
+    Y,
+    /// `{note}`: the selected note's id, or `-` without a selection
+    SelectedNote,
+    /// `{unsaved}`: `[+]` while the map has unsaved changes
+    Unsaved,
+    /// `{clock}`: the time of day (hours and minutes)
+    Clock,
+    /// `{notes}`: the number of notes on the map
+    NoteCount,
+}
+
+impl BarField {
+    fn from_name(name: &str) -> Option<BarField> {
+        match name {
+            "mode" => Some(BarField::Mode),
+            "x" => Some(BarField::X),
+            "y" => Some(BarField::Y),
+            "note" => Some(BarField::SelectedNote),
+            "unsaved" => Some(BarField::Unsaved),
+            "clock" => Some(BarField::Clock),
+            "notes" => Some(BarField::NoteCount),
+            _ => None,
+        }
+    }
+}
+
+/// A piece of a status bar format: literal text or a field to fill in.
+#[derive(PartialEq, Clone, Debug)]
+pub enum BarItem {
+    Text(String),
+    Field(BarField),
+}
+
+/// A parsed status bar format, e.g. `{mode} {unsaved}|{x}, {y}  {clock}`.
+///
+/// The first `|` splits the format into the left-aligned and the right-aligned part of
+/// the bar. Braces around anything but a known field name are shown as they are.
+#[derive(PartialEq, Clone, Debug)]
+pub struct StatusBarFormat {
+    pub left: Vec<BarItem>,
+    pub right: Vec<BarItem>,
+}
+
+impl StatusBarFormat {
+    pub fn parse(format: &str) -> StatusBarFormat {
+        let (left, right) = format.split_once('|').unwrap_or((format, ""));
+        StatusBarFormat {
+            left: parse_items(left),
+            right: parse_items(right),
+        }
+    }
+
+    /// True if the format shows the given field on either side.
+    pub fn contains(&self, field: BarField) -> bool {
+        self.left
+            .iter()
+            .chain(&self.right)
+            .any(|item| *item == BarItem::Field(field))
+    }
+}
+
+fn parse_items(format: &str) -> Vec<BarItem> {
+    let mut items = vec![];
+    let mut text = String::new();
+    let mut rest = format;
+
+    while let Some(start) = rest.find('{') {
+        let field = rest[start + 1..].find('}').and_then(|end| {
+            BarField::from_name(&rest[start + 1..start + 1 + end]).map(|field| (field, end))
+        });
+        match field {
+            Some((field, end)) => {
+                text.push_str(&rest[..start]);
+                if !text.is_empty() {
+                    items.push(BarItem::Text(std::mem::take(&mut text)));
+                }
+                items.push(BarItem::Field(field));
+                rest = &rest[start + end + 2..];
+            }
+            None => {
+                text.push_str(&rest[..=start]);
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        items.push(BarItem::Text(text));
+    }
+    items
+}
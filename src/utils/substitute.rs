@@ -0,0 +1,94 @@
+use regex::{NoExpand, Regex, RegexBuilder};
+
+/// A vim-style `s/pattern/replacement/flags` search-and-replace over note text.
+///
+/// The pattern is matched literally unless the `r` flag is given. Flags:
+/// * `g` - replace every match in a note instead of only the first
+/// * `r` - treat the pattern as a regular expression; `$1` etc. in the replacement
+///   refer to its capture groups
+/// * `i` - ignore case
+#[derive(PartialEq, Clone, Debug)]
+pub struct Substitution {
+    pub pattern: String,
+    pub replacement: String,
+    pub global: bool,
+    pub regex: bool,
+    pub ignore_case: bool,
+}
+
+impl Substitution {
+    /// Parses the text after `s`, e.g. `/old/new/g`.
+    ///
+    /// The first character is the delimiter; a backslash before it makes it part of
+    /// the pattern or replacement. Returns None for an empty pattern or unknown flags.
+    pub fn parse(input: &str) -> Option<Substitution> {
+        let mut chars = input.chars();
+        let delimiter = chars
+            .next()
+            .filter(|c| !c.is_alphanumeric() && *c != '\\')?;
+
+        let mut parts = vec![String::new()];
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(next) if next == delimiter => parts.last_mut()?.push(next),
+                    Some(next) => {
+                        parts.last_mut()?.push('\\');
+                        parts.last_mut()?.push(next);
+                    }
+                    None => parts.last_mut()?.push('\\'),
+                },
+                c if c == delimiter => parts.push(String::new()),
+                c => parts.last_mut()?.push(c),
+            }
+        }
+
+        let (pattern, replacement, flags) = match parts.as_slice() {
+            [pattern, replacement] => (pattern, replacement, ""),
+            [pattern, replacement, flags] => (pattern, replacement, flags.as_str()),
+            _ => return None,
+        };
+        if pattern.is_empty() || !flags.chars().all(|flag| "gri".contains(flag)) {
+            return None;
+        }
+
+        Some(Substitution {
+            pattern: pattern.clone(),
+            replacement: replacement.clone(),
+            global: flags.contains('g'),
+            regex: flags.contains('r'),
+            ignore_case: flags.contains('i'),
+        })
+    }
+
+    /// Compiles the pattern, failing only for an invalid regular expression.
+    pub fn matcher(&self) -> Result<Regex, regex::Error> {
+        let pattern = if self.regex {
+            self.pattern.clone()
+        } else {
+            regex::escape(&self.pattern)
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(self.ignore_case)
+            .build()
+    }
+
+    /// Replaces the matches of `matcher` (from [`Substitution::matcher`]) in `text`.
+    ///
+    /// Returns the new text and the number of replacements, None if nothing matched.
+    pub fn apply(&self, matcher: &Regex, text: &str) -> Option<(String, usize)> {
+        let matches = matcher.find_iter(text).count();
+        if matches == 0 {
+            return None;
+        }
+
+        let (limit, count) = if self.global { (0, matches) } else { (1, 1) };
+        let replaced = if self.regex {
+            matcher.replacen(text, limit, self.replacement.as_str())
+        } else {
+            matcher.replacen(text, limit, NoExpand(&self.replacement))
+        };
+
+        Some((replaced.into_owned(), count))
+    }
+}
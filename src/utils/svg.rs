@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use ratatui::style::Color;
+
+use crate::{
+    states::map::{Connection, Note},
+    utils::{ENCRYPTED_LABEL, Point, connection_path},
+};
+
+/// Width of one terminal cell in SVG units
+pub const SVG_CELL_WIDTH: isize = 10;
+/// Height of one terminal cell in SVG units
+pub const SVG_CELL_HEIGHT: isize = 20;
+/// Empty cells kept around the map's content
+const SVG_MARGIN: isize = 2;
+
+const BACKGROUND: &str = "#1e1e1e";
+/// Used for the placeholder text of encrypted notes, like the TUI's dark gray
+const MUTED: &str = "#808080";
+
+/// Writes the map as an SVG image laid out like the terminal: one cell is
+/// [`SVG_CELL_WIDTH`] by [`SVG_CELL_HEIGHT`] units.
+///
+/// Notes are bordered rectangles (with the id `note-<id>`) and their text, connections are polylines along
+/// the same paths the TUI draws, with their weight as a label when one is set. Colors
+/// match the terminal's. Titles are drawn in bold on the top border. Encrypted notes
+/// show `[encrypted]` instead of their text.
+pub fn export_svg(notes: &HashMap<usize, Note>, connections: &[Connection]) -> String {
+    let mut ids: Vec<usize> = notes.keys().copied().collect();
+    ids.sort_unstable();
+
+    // Content bounds in cells, so the image starts at the top-left note
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (0, 0, 0, 0);
+    for (index, note) in ids.iter().map(|id| &notes[id]).enumerate() {
+        let (width, height) = note.get_dimensions();
+        let (left, top) = (note.x, note.y);
+        let (right, bottom) = (left + width as isize, top + height as isize);
+        if index == 0 {
+            (min_x, min_y, max_x, max_y) = (left, top, right, bottom);
+        } else {
+            (min_x, min_y) = (min_x.min(left), min_y.min(top));
+            (max_x, max_y) = (max_x.max(right), max_y.max(bottom));
+        }
+    }
+    let (origin_x, origin_y) = (min_x - SVG_MARGIN, min_y - SVG_MARGIN);
+    let image_width = (max_x - min_x + 2 * SVG_MARGIN) * SVG_CELL_WIDTH;
+    let image_height = (max_y - min_y + 2 * SVG_MARGIN) * SVG_CELL_HEIGHT;
+
+    // Center of a cell in SVG units
+    let cell_x = |x: isize| (x - origin_x) * SVG_CELL_WIDTH + SVG_CELL_WIDTH / 2;
+    let cell_y = |y: isize| (y - origin_y) * SVG_CELL_HEIGHT + SVG_CELL_HEIGHT / 2;
+
+    let mut lines = vec![
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{image_width}\" height=\"{image_height}\" viewBox=\"0 0 {image_width} {image_height}\" font-family=\"monospace\" font-size=\"{}\">",
+            SVG_CELL_HEIGHT * 3 / 4
+        ),
+        format!("  <rect width=\"100%\" height=\"100%\" fill=\"{BACKGROUND}\"/>"),
+    ];
+
+    // Connections first so notes are drawn over them, as in the TUI
+    for connection in connections {
+        let (Some(start_note), Some(end_note)) =
+            (notes.get(&connection.from_id), notes.get(&connection.to_id))
+        else {
+            continue;
+        };
+
+        let path = connection_path(connection, start_note, end_note, 0);
+        let points: Vec<String> = path
+            .iter()
+            .map(|point| format!("{},{}", cell_x(point.x), cell_y(point.y)))
+            .collect();
+        let color = svg_color(connection.color);
+        lines.push(format!(
+            "  <polyline points=\"{}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"2\"/>",
+            points.join(" ")
+        ));
+
+        if let Some(weight) = connection.weight
+            && let Some(middle) = path_middle(&path)
+        {
+            lines.push(format!(
+                "  <text x=\"{}\" y=\"{}\" fill=\"{color}\" text-anchor=\"middle\" dominant-baseline=\"central\">{weight}</text>",
+                cell_x(middle.x),
+                cell_y(middle.y)
+            ));
+        }
+    }
+
+    for id in ids {
+        let note = &notes[&id];
+        let (width, height) = note.get_dimensions();
+        let (x, y) = (note.x, note.y);
+        let color = svg_color(note.color);
+
+        // The border runs through the centers of the outer cells
+        lines.push(format!(
+            "  <rect id=\"note-{id}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{BACKGROUND}\" stroke=\"{color}\" stroke-width=\"2\"/>",
+            cell_x(x),
+            cell_y(y),
+            (width as isize - 1) * SVG_CELL_WIDTH,
+            (height as isize - 1) * SVG_CELL_HEIGHT
+        ));
+
+        // On the top border like the TUI draws it, over a gap in the line
+        if let Some(title) = &note.title {
+            let title_x = (x + 1 - origin_x) * SVG_CELL_WIDTH;
+            lines.push(format!(
+                "  <text x=\"{title_x}\" y=\"{}\" fill=\"{color}\" font-weight=\"bold\" xml:space=\"preserve\" dominant-baseline=\"central\" stroke=\"{BACKGROUND}\" stroke-width=\"6\" paint-order=\"stroke\"> {} </text>",
+                cell_y(y),
+                svg_escape(title)
+            ));
+        }
+
+        let (text, text_color) = if note.encrypted.is_some() {
+            (String::from(ENCRYPTED_LABEL), MUTED)
+        } else {
+            (note.content.clone(), svg_color(Color::White))
+        };
+        let text_x = (x + 1 - origin_x) * SVG_CELL_WIDTH;
+        lines.push(format!(
+            "  <text fill=\"{text_color}\" xml:space=\"preserve\" dominant-baseline=\"central\">"
+        ));
+        for (row, line) in text.split('\n').enumerate() {
+            lines.push(format!(
+                "    <tspan x=\"{text_x}\" y=\"{}\">{}</tspan>",
+                cell_y(y + 1 + row as isize),
+                svg_escape(line)
+            ));
+        }
+        lines.push(String::from("  </text>"));
+    }
+
+    lines.push(String::from("</svg>"));
+    lines.join("\n") + "\n"
+}
+
+/// Hex code of a terminal color, using the common xterm palette.
+pub fn svg_color(color: Color) -> &'static str {
+    match color {
+        Color::Black => "#000000",
+        Color::Red => "#cd3131",
+        Color::Green => "#0dbc79",
+        Color::Yellow => "#e5e510",
+        Color::Blue => "#2472c8",
+        Color::Magenta => "#bc3fbc",
+        Color::Cyan => "#11a8cd",
+        Color::Gray => "#e5e5e5",
+        Color::DarkGray => MUTED,
+        _ => "#ffffff",
+    }
+}
+
+/// Escapes text for use as SVG character data, dropping control characters.
+pub fn svg_escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The point halfway along a path, measured in cells.
+fn path_middle(path: &[Point]) -> Option<Point> {
+    let length: isize = path
+        .windows(2)
+        .map(|segment| (segment[1].x - segment[0].x).abs() + (segment[1].y - segment[0].y).abs())
+        .sum();
+    let mut remaining = length / 2;
+
+    for segment in path.windows(2) {
+        let (from, to) = (segment[0], segment[1]);
+        let segment_length = (to.x - from.x).abs() + (to.y - from.y).abs();
+        if remaining <= segment_length {
+            return Some(Point {
+                x: from.x + (to.x - from.x).signum() * remaining,
+                y: from.y + (to.y - from.y).signum() * remaining,
+            });
+        }
+        remaining -= segment_length;
+    }
+
+    path.first().copied()
+}
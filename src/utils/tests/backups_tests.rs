@@ -1,4 +1,4 @@
-use chrono::{Duration as ChronoDuration, Local};
+use chrono::{Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime};
 use std::collections::HashMap;
 use std::fs;
 use std::{path::PathBuf, time::Duration as StdDuration};
@@ -8,11 +8,12 @@ use crate::{
     states::{
         MapState,
         map::Notification,
-        settings::{BackupsInterval, RuntimeBackupsInterval},
+        settings::{BackupRetention, BackupsInterval, RuntimeBackupsInterval},
     },
     utils::{
         backups::{
-            get_duration, get_duration_rt, handle_on_load_backup_with_fs, handle_runtime_backup,
+            backup_timestamp, backups_to_prune, get_duration, get_duration_rt,
+            handle_on_load_backup_with_fs, handle_runtime_backup, list_backups, prune_backups,
         },
         filesystem::test_utils::TempFileSystem,
     },
@@ -126,7 +127,7 @@ fn test_handle_on_load_backup_disabled_backups() {
     handle_on_load_backup_with_fs(&mut map_state, &fs, Local::now());
 
     // Verify no notification was set
-    assert_eq!(map_state.ui_state.show_notification, None);
+    assert!(map_state.ui_state.notifications.is_empty());
 }
 
 #[test]
@@ -153,8 +154,8 @@ fn test_handle_on_load_backup_first_backup() {
 
     // Verify notification was set to BackupSuccess
     assert_eq!(
-        map_state.ui_state.show_notification,
-        Some(Notification::BackupSuccess)
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::BackupSuccess)
     );
 
     // Verify a backup file was created in the backup directory
@@ -206,7 +207,7 @@ fn test_handle_on_load_backup_skip_recent_backup() {
     handle_on_load_backup_with_fs(&mut map_state, &fs, Local::now());
 
     // Verify no new backup was created
-    assert_eq!(map_state.ui_state.show_notification, None);
+    assert!(map_state.ui_state.notifications.is_empty());
 
     // Verify no backup files were created
     let backup_files: Vec<_> = fs::read_dir(backup_dir.path())
@@ -245,8 +246,8 @@ fn test_handle_on_load_backup_old_backup_triggers_new() {
 
     // Verify backup was created successfully
     assert_eq!(
-        map_state.ui_state.show_notification,
-        Some(Notification::BackupSuccess)
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::BackupSuccess)
     );
 
     // Verify a backup file was created
@@ -337,8 +338,8 @@ fn test_handle_on_load_backup_invalid_backup_directory() {
 
     // Verify backup failed
     assert_eq!(
-        map_state.ui_state.show_notification,
-        Some(Notification::BackupFail)
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::BackupFail)
     );
 }
 
@@ -364,8 +365,8 @@ fn test_handle_on_load_backup_multiple_calls_with_time_passing() {
     // First call - should create backup (no previous backup)
     handle_on_load_backup_with_fs(&mut map_state, &fs, day_0);
     assert_eq!(
-        map_state.ui_state.show_notification,
-        Some(Notification::BackupSuccess)
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::BackupSuccess)
     );
     assert_eq!(
         map_state
@@ -385,10 +386,10 @@ fn test_handle_on_load_backup_multiple_calls_with_time_passing() {
     assert_eq!(backup_count, 1);
 
     // Second call (2 days later) - should NOT create backup
-    map_state.ui_state.show_notification = None;
+    map_state.ui_state.notifications.clear();
     let day_2 = day_0 + ChronoDuration::days(2);
     handle_on_load_backup_with_fs(&mut map_state, &fs, day_2);
-    assert_eq!(map_state.ui_state.show_notification, None);
+    assert!(map_state.ui_state.notifications.is_empty());
 
     let backup_count = fs::read_dir(backup_dir.path())
         .unwrap()
@@ -401,8 +402,8 @@ fn test_handle_on_load_backup_multiple_calls_with_time_passing() {
     let day_3 = day_0 + ChronoDuration::days(3);
     handle_on_load_backup_with_fs(&mut map_state, &fs, day_3);
     assert_eq!(
-        map_state.ui_state.show_notification,
-        Some(Notification::BackupSuccess)
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::BackupSuccess)
     );
     assert_eq!(
         map_state
@@ -447,7 +448,7 @@ fn test_handle_runtime_backup_disabled_backups() {
     handle_runtime_backup(&mut map_state);
 
     // Verify no notification was set
-    assert_eq!(map_state.ui_state.show_notification, None);
+    assert!(map_state.ui_state.notifications.is_empty());
 }
 
 #[test]
@@ -468,8 +469,8 @@ fn test_handle_runtime_backup_creates_backup() {
 
     // Verify notification was set to BackupSuccess
     assert_eq!(
-        map_state.ui_state.show_notification,
-        Some(Notification::BackupSuccess)
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::BackupSuccess)
     );
 
     // Verify a backup file was created in the backup directory
@@ -578,8 +579,8 @@ fn test_handle_runtime_backup_invalid_backup_directory() {
 
     // Verify backup failed
     assert_eq!(
-        map_state.ui_state.show_notification,
-        Some(Notification::BackupFail)
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::BackupFail)
     );
 }
 
@@ -602,7 +603,7 @@ fn test_handle_runtime_backup_backups_enabled_but_no_interval() {
     handle_runtime_backup(&mut map_state);
 
     // Verify no backup was created (both backups_path and runtime_interval required)
-    assert_eq!(map_state.ui_state.show_notification, None);
+    assert!(map_state.ui_state.notifications.is_empty());
 
     // Verify no files in backup directory
     let backup_files: Vec<_> = fs::read_dir(backup_dir.path())
@@ -613,3 +614,192 @@ fn test_handle_runtime_backup_backups_enabled_but_no_interval() {
 
     assert_eq!(backup_files.len(), 0);
 }
+
+// ============================================================================
+// Tests for backup retention
+// ============================================================================
+
+fn date_time(year: i32, month: u32, day: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(year, month, day)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap()
+}
+
+/// One backup per day, from the newest (`start`) going back `days` days
+fn daily_backups(start: NaiveDateTime, days: i64) -> Vec<(PathBuf, NaiveDateTime)> {
+    (0..days)
+        .map(|day| {
+            let time = start - ChronoDuration::days(day);
+            (PathBuf::from(format!("backup-{day}")), time)
+        })
+        .collect()
+}
+
+#[test]
+fn test_backup_timestamp_from_file_name() {
+    assert_eq!(
+        backup_timestamp("plans-load-backup-26-03-09.json", "plans"),
+        Some(
+            NaiveDate::from_ymd_opt(2026, 3, 9)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        )
+    );
+    assert_eq!(
+        backup_timestamp("plans-session-backup-26-03-09-1745.json.gz", "plans"),
+        Some(
+            NaiveDate::from_ymd_opt(2026, 3, 9)
+                .unwrap()
+                .and_hms_opt(17, 45, 0)
+                .unwrap()
+        )
+    );
+    // Another map's backups, or not a backup at all
+    assert_eq!(
+        backup_timestamp("plans-old-load-backup-26-03-09.json", "plans"),
+        None
+    );
+    assert_eq!(backup_timestamp("plans.json", "plans"), None);
+    assert_eq!(
+        backup_timestamp("plans-load-backup-yesterday.json", "plans"),
+        None
+    );
+}
+
+#[test]
+fn test_backups_to_prune_keep_all() {
+    let backups = daily_backups(date_time(2026, 3, 9), 30);
+
+    assert!(backups_to_prune(backups, BackupRetention::KeepAll).is_empty());
+}
+
+#[test]
+fn test_backups_to_prune_keep_last() {
+    let mut backups = daily_backups(date_time(2026, 3, 9), 8);
+    // Order on disk doesn't matter
+    backups.reverse();
+
+    let mut pruned = backups_to_prune(backups, BackupRetention::KeepLast(5));
+    pruned.sort();
+
+    assert_eq!(
+        pruned,
+        vec![
+            PathBuf::from("backup-5"),
+            PathBuf::from("backup-6"),
+            PathBuf::from("backup-7")
+        ]
+    );
+}
+
+#[test]
+fn test_backups_to_prune_tiered() {
+    // A backup every day for two years
+    let backups = daily_backups(date_time(2026, 3, 9), 730);
+    let kept: Vec<NaiveDateTime> = {
+        let pruned = backups_to_prune(backups.clone(), BackupRetention::Tiered);
+        backups
+            .into_iter()
+            .filter(|(path, _)| !pruned.contains(path))
+            .map(|(_, time)| time)
+            .collect()
+    };
+
+    // The last 7 days, Monday 2026-03-09 back to Tuesday
+    assert!(kept.contains(&date_time(2026, 3, 9)));
+    assert!(kept.contains(&date_time(2026, 3, 3)));
+    // Sundays (the newest backup of their ISO week) of the 4 newest weeks
+    assert!(kept.contains(&date_time(2026, 3, 1)));
+    assert!(kept.contains(&date_time(2026, 2, 22)));
+    assert!(!kept.contains(&date_time(2026, 2, 15)));
+    // Month ends, back to 12 months that have backups
+    assert!(kept.contains(&date_time(2026, 2, 28)));
+    assert!(kept.contains(&date_time(2025, 4, 30)));
+    assert!(!kept.contains(&date_time(2025, 3, 31)));
+    // 7 days, 2 more weeks (the newest two weeks end within the days), and 11 more
+    // months (March's newest is one of the days)
+    assert_eq!(kept.len(), 7 + 2 + 11);
+}
+
+#[test]
+fn test_list_backups_newest_first() {
+    let backup_dir = tempfile::tempdir().unwrap();
+    for name in [
+        "plans-load-backup-26-03-02.json",
+        "plans-session-backup-26-03-02-1800.json",
+        "plans-load-backup-26-03-01.json.gz",
+        "other-load-backup-26-03-05.json",
+    ] {
+        fs::write(backup_dir.path().join(name), "{}").unwrap();
+    }
+
+    let backups = list_backups(backup_dir.path(), "plans");
+
+    let names: Vec<String> = backups
+        .iter()
+        .map(|(path, _)| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "plans-session-backup-26-03-02-1800.json",
+            "plans-load-backup-26-03-02.json",
+            "plans-load-backup-26-03-01.json.gz"
+        ]
+    );
+}
+
+#[test]
+fn test_prune_backups_deletes_only_this_maps_old_backups() {
+    let backup_dir = tempfile::tempdir().unwrap();
+    for name in [
+        "plans-load-backup-26-03-01.json",
+        "plans-load-backup-26-03-02.json",
+        "plans-session-backup-26-03-02-1800.json",
+        "other-load-backup-26-01-01.json",
+        "notes.txt",
+    ] {
+        fs::write(backup_dir.path().join(name), "{}").unwrap();
+    }
+
+    let deleted = prune_backups(backup_dir.path(), "plans", BackupRetention::KeepLast(1));
+
+    assert_eq!(deleted, 2);
+    let mut remaining: Vec<String> = fs::read_dir(backup_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    remaining.sort();
+    assert_eq!(
+        remaining,
+        vec![
+            "notes.txt",
+            "other-load-backup-26-01-01.json",
+            "plans-session-backup-26-03-02-1800.json"
+        ]
+    );
+}
+
+#[test]
+fn test_handle_runtime_backup_prunes_old_backups() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let backup_dir = tempfile::tempdir().unwrap();
+    let map_file_path = temp_dir.path().join("test_map.json");
+    let old_backup = backup_dir
+        .path()
+        .join("test_map-session-backup-20-01-01-0900.json");
+    fs::write(&old_backup, "{}").unwrap();
+
+    let mut map_state = create_map_state_using_mock_filesystem(map_file_path);
+    map_state.settings.backups_path = Some(backup_dir.path().to_string_lossy().to_string());
+    map_state.settings.runtime_backups_interval = Some(RuntimeBackupsInterval::Hourly);
+    map_state.settings.backup_retention = BackupRetention::KeepLast(1);
+
+    handle_runtime_backup(&mut map_state);
+
+    // Only the new backup is left
+    assert!(!old_backup.exists());
+    assert_eq!(fs::read_dir(backup_dir.path()).unwrap().count(), 1);
+}
@@ -0,0 +1,43 @@
+use crate::utils::{checklist_progress, toggle_checkbox};
+
+#[test]
+fn test_checklist_progress() {
+    assert_eq!(
+        checklist_progress("Groceries\n[ ] milk\n[x] eggs"),
+        Some((1, 2))
+    );
+    assert_eq!(
+        checklist_progress("- [X] one\n  * [x] two\n- [ ] three"),
+        Some((2, 3))
+    );
+    // Checkboxes only count at the start of a line
+    assert_eq!(checklist_progress("see [x] below\n[]\n[ x]"), None);
+    assert_eq!(checklist_progress(""), None);
+}
+
+#[test]
+fn test_toggle_checkbox() {
+    let mut content = String::from("Todo\n  - [ ] write\n[x] read");
+
+    // Anywhere on the item's line
+    assert!(toggle_checkbox(&mut content, 14));
+    assert_eq!(content, "Todo\n  - [x] write\n[x] read");
+    assert!(toggle_checkbox(&mut content, 5));
+    assert_eq!(content, "Todo\n  - [ ] write\n[x] read");
+    // At the very end of the note
+    let end = content.len();
+    assert!(toggle_checkbox(&mut content, end));
+    assert_eq!(content, "Todo\n  - [ ] write\n[ ] read");
+
+    assert!(!toggle_checkbox(&mut content, 2));
+    assert_eq!(content, "Todo\n  - [ ] write\n[ ] read");
+}
+
+#[test]
+fn test_toggle_checkbox_multibyte_content() {
+    let mut content = String::from("Émile\n[ ] café");
+
+    let end = content.len();
+    assert!(toggle_checkbox(&mut content, end));
+    assert_eq!(content, "Émile\n[x] café");
+}
@@ -0,0 +1,57 @@
+use std::fs;
+use tempfile::tempdir;
+
+use crate::{
+    graph::MapBuilder,
+    utils::{CHECKSUM_KEY, MapData, verify_map_checksum, write_map_data},
+};
+
+fn test_map() -> MapData {
+    MapBuilder::new()
+        .note("Plan")
+        .note("Build")
+        .note("Ship")
+        .connect(0, 1)
+        .build()
+}
+
+#[test]
+fn test_saved_maps_match_their_checksum() {
+    let dir = tempdir().unwrap();
+
+    for (name, compact) in [
+        ("pretty.json", false),
+        ("compact.json", true),
+        ("map.json.gz", false),
+    ] {
+        let path = dir.path().join(name);
+        write_map_data(&path, &test_map(), compact, &mut vec![]).unwrap();
+        assert!(verify_map_checksum(&path).unwrap(), "{name}");
+    }
+
+    // The same map written again has the same checksum, whatever order its notes are in
+    let checksum = |name: &str| {
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.path().join(name)).unwrap()).unwrap();
+        json[CHECKSUM_KEY].as_str().unwrap().to_string()
+    };
+    assert_eq!(checksum("pretty.json"), checksum("compact.json"));
+}
+
+#[test]
+fn test_changed_maps_dont_match_their_checksum() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("map.json");
+    write_map_data(&path, &test_map(), false, &mut vec![]).unwrap();
+
+    let mut json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    json["notes"].as_object_mut().unwrap().remove("2");
+    fs::write(&path, json.to_string()).unwrap();
+    assert!(!verify_map_checksum(&path).unwrap());
+
+    // Files without a checksum aren't checked
+    json.as_object_mut().unwrap().remove(CHECKSUM_KEY);
+    fs::write(&path, json.to_string()).unwrap();
+    assert!(verify_map_checksum(&path).unwrap());
+}
@@ -0,0 +1,79 @@
+use crate::utils::{CryptoError, FileKey, decrypt, encrypt};
+
+#[test]
+fn test_encrypt_decrypt_roundtrip() {
+    let encrypted = encrypt("secret note ✓", "passphrase");
+
+    assert_ne!(encrypted.ciphertext, "secret note ✓");
+    assert_eq!(decrypt(&encrypted, "passphrase").unwrap(), "secret note ✓");
+}
+
+#[test]
+fn test_decrypt_with_wrong_passphrase() {
+    let encrypted = encrypt("secret", "right");
+
+    assert_eq!(
+        decrypt(&encrypted, "wrong"),
+        Err(CryptoError::WrongPassphrase)
+    );
+}
+
+#[test]
+fn test_encrypting_twice_uses_fresh_nonce_and_salt() {
+    let first = encrypt("same", "passphrase");
+    let second = encrypt("same", "passphrase");
+
+    assert_ne!(first.nonce, second.nonce);
+    assert_ne!(first.salt, second.salt);
+    assert_ne!(first.ciphertext, second.ciphertext);
+}
+
+#[test]
+fn test_decrypt_damaged_data() {
+    let mut encrypted = encrypt("secret", "passphrase");
+    encrypted.nonce = String::from("not base64!");
+
+    assert_eq!(decrypt(&encrypted, "passphrase"), Err(CryptoError::Decode));
+}
+
+#[test]
+fn test_decrypt_tampered_ciphertext() {
+    let mut encrypted = encrypt("secret", "passphrase");
+    encrypted.ciphertext = encrypt("other", "passphrase").ciphertext;
+
+    assert_eq!(
+        decrypt(&encrypted, "passphrase"),
+        Err(CryptoError::WrongPassphrase)
+    );
+}
+
+#[test]
+fn test_file_key_unlocks_what_it_encrypted() {
+    let file_key = FileKey::new("journal passphrase");
+    let encrypted = file_key.encrypt("dear diary");
+
+    let (unlocked_key, plaintext) = FileKey::unlock("journal passphrase", &encrypted).unwrap();
+
+    assert_eq!(plaintext, "dear diary");
+    // The unlocked key encrypts under the same salt, so either key reads the result
+    assert_eq!(unlocked_key, file_key);
+    assert_eq!(
+        file_key.decrypt(&unlocked_key.encrypt("more")).unwrap(),
+        "more"
+    );
+}
+
+#[test]
+fn test_file_key_wrong_passphrase() {
+    let encrypted = FileKey::new("right").encrypt("secret");
+
+    assert_eq!(
+        FileKey::unlock("wrong", &encrypted),
+        Err(CryptoError::WrongPassphrase)
+    );
+    // A key derived under another salt can't decrypt it either
+    assert_eq!(
+        FileKey::new("right").decrypt(&encrypted),
+        Err(CryptoError::WrongPassphrase)
+    );
+}
@@ -0,0 +1,66 @@
+use ratatui::style::Color;
+use std::collections::HashMap;
+
+use crate::{
+    states::map::{Connection, Note, Side},
+    utils::{dot_string, encrypt, export_dot},
+};
+
+fn create_connection(from_id: usize, to_id: usize, weight: Option<u32>) -> Connection {
+    Connection {
+        from_id,
+        from_side: Side::Right,
+        to_id,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight,
+        layer: None,
+    }
+}
+
+#[test]
+fn test_export_dot_digraph() {
+    let mut titled = Note::new(0, 0, String::from("Ship it\nsoon"), Color::White);
+    titled.title = Some(String::from("Release"));
+    let mut secret = Note::new(0, 0, String::from("Password"), Color::White);
+    secret.encrypted = Some(encrypt("Password", "passphrase"));
+    let notes = HashMap::from([
+        (
+            1,
+            Note::new(0, 0, String::from("Build \"fast\""), Color::White),
+        ),
+        (
+            0,
+            Note::new(0, 0, String::from("Plan\nfirst"), Color::White),
+        ),
+        (2, titled),
+        (3, secret),
+    ]);
+    let connections = vec![
+        create_connection(0, 1, None),
+        create_connection(1, 2, Some(3)),
+        // Dangling connections are left out
+        create_connection(2, 7, None),
+    ];
+
+    assert_eq!(
+        export_dot("plan", &notes, &connections),
+        "digraph \"plan\" {
+    node [shape=box];
+    n0 [label=\"Plan\\nfirst\"];
+    n1 [label=\"Build \\\"fast\\\"\"];
+    n2 [label=\"Release\"];
+    n3 [label=\"[encrypted]\"];
+    n0 -> n1;
+    n1 -> n2 [label=\"3\"];
+}
+"
+    );
+}
+
+#[test]
+fn test_dot_string() {
+    assert_eq!(dot_string("C:\\maps"), "C:\\\\maps");
+    assert_eq!(dot_string("a\tb\nc"), "ab\\nc");
+}
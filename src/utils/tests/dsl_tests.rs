@@ -0,0 +1,65 @@
+use crate::utils::{DslError, DslGraph, parse_dsl};
+
+#[test]
+fn test_parse_chain_and_single_note() {
+    let graph = parse_dsl("A -> B -> C\nD").unwrap();
+
+    assert_eq!(graph.labels, vec!["A", "B", "C", "D"]);
+    assert_eq!(graph.edges, vec![(0, 1), (1, 2)]);
+}
+
+#[test]
+fn test_parse_reuses_labels_and_skips_duplicate_edges() {
+    let graph = parse_dsl("A -> B\nA -> C\nA -> B").unwrap();
+
+    assert_eq!(graph.labels, vec!["A", "B", "C"]);
+    assert_eq!(graph.edges, vec![(0, 1), (0, 2)]);
+}
+
+#[test]
+fn test_parse_ignores_comments_and_blank_lines() {
+    let graph = parse_dsl("# heading\n\n   A   \n").unwrap();
+
+    assert_eq!(graph.labels, vec!["A"]);
+    assert!(graph.edges.is_empty());
+}
+
+#[test]
+fn test_parse_line_break_escape() {
+    let graph = parse_dsl("first\\nsecond").unwrap();
+
+    assert_eq!(graph.labels, vec!["first\nsecond"]);
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(parse_dsl(""), Err(DslError::Empty));
+    assert_eq!(parse_dsl("# only a comment"), Err(DslError::Empty));
+    assert_eq!(parse_dsl("A\nB ->"), Err(DslError::MissingLabel(2)));
+    assert_eq!(parse_dsl("-> B"), Err(DslError::MissingLabel(1)));
+}
+
+#[test]
+fn test_layout_places_layers_left_to_right() {
+    let graph = parse_dsl("A -> B\nA -> C").unwrap();
+    let positions = graph.layout();
+
+    // Root at the origin, both children in the next layer stacked vertically
+    assert_eq!(positions[0], (0, 0));
+    assert!(positions[1].0 > 0);
+    assert_eq!(positions[1].0, positions[2].0);
+    assert!(positions[2].1 > positions[1].1);
+}
+
+#[test]
+fn test_layout_handles_cycles() {
+    let graph = DslGraph {
+        labels: vec![String::from("A"), String::from("B")],
+        edges: vec![(0, 1), (1, 0)],
+    };
+    let positions = graph.layout();
+
+    assert_eq!(positions.len(), 2);
+    assert_eq!(positions[0], (0, 0));
+    assert!(positions[1].0 > 0);
+}
@@ -0,0 +1,93 @@
+use ratatui::style::Color;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+use crate::{
+    states::MapState,
+    utils::{
+        MapData, TmmprError, crash_report, discard_emergency_save, emergency_save,
+        emergency_save_path, find_emergency_saves_with_fs, read_emergency_save, read_json_data,
+        test_utils::MockFileSystem,
+    },
+};
+
+fn map_state_with_a_note(path: PathBuf) -> MapState {
+    let mut map_state = MapState::new_with_fs(path, &MockFileSystem::new());
+    map_state
+        .notes_state
+        .add(10, 20, String::from("Unsaved"), Color::White);
+    map_state
+}
+
+#[test]
+fn test_emergency_save_path() {
+    assert_eq!(
+        emergency_save_path(Path::new("/maps/plan.json")),
+        Path::new("/maps/plan.json.recovery")
+    );
+}
+
+#[test]
+fn test_emergency_save_writes_unsaved_changes_next_to_the_map() {
+    let temp_dir = tempdir().unwrap();
+    let map_path = temp_dir.path().join("plan.json");
+    let mut map_state = map_state_with_a_note(map_path.clone());
+    map_state.persistence.mark_dirty();
+
+    let saved = emergency_save(&mut map_state).unwrap().unwrap();
+
+    assert_eq!(saved, temp_dir.path().join("plan.json.recovery"));
+    let map_data: MapData = read_json_data(&saved).unwrap();
+    assert_eq!(map_data.notes[&0].content, "Unsaved");
+    assert!(!map_path.exists());
+    // The map still has unsaved changes; only its own file counts as saving them
+    assert!(map_state.persistence.has_unsaved_changes);
+}
+
+#[test]
+fn test_emergency_save_skips_a_saved_map() {
+    let temp_dir = tempdir().unwrap();
+    let mut map_state = map_state_with_a_note(temp_dir.path().join("plan.json"));
+    map_state.persistence.mark_clean();
+
+    assert!(emergency_save(&mut map_state).is_none());
+    assert!(!temp_dir.path().join("plan.json.recovery").exists());
+}
+
+#[test]
+fn test_crash_report_says_what_happened_to_the_changes() {
+    let saved = Ok(PathBuf::from("/maps/plan.json.recovery"));
+    assert!(crash_report(Some(&saved)).contains("saved to /maps/plan.json.recovery"));
+
+    let failed = Err(TmmprError::Validation(String::from("disk full")));
+    assert!(crash_report(Some(&failed)).contains("couldn't be saved: disk full"));
+
+    assert!(crash_report(None).contains("no unsaved changes"));
+}
+
+#[test]
+fn test_find_emergency_saves() {
+    let maps = [PathBuf::from("/maps/a.json"), PathBuf::from("/maps/b.json")];
+    let fs = MockFileSystem::new().with_existing_path(PathBuf::from("/maps/b.json.recovery"));
+
+    assert_eq!(
+        find_emergency_saves_with_fs(&maps, &fs),
+        [PathBuf::from("/maps/b.json")]
+    );
+}
+
+#[test]
+fn test_read_and_discard_emergency_save() {
+    let temp_dir = tempdir().unwrap();
+    let map_path = temp_dir.path().join("plan.json");
+    let mut map_state = map_state_with_a_note(map_path.clone());
+    map_state.persistence.mark_dirty();
+    emergency_save(&mut map_state).unwrap().unwrap();
+
+    let map_data = read_emergency_save(&map_path, None).unwrap();
+    assert_eq!(map_data.notes[&0].content, "Unsaved");
+
+    discard_emergency_save(&map_path).unwrap();
+    assert!(!emergency_save_path(&map_path).exists());
+    assert!(read_emergency_save(&map_path, None).is_err());
+}
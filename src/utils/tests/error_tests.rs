@@ -0,0 +1,77 @@
+use std::{fs, io::ErrorKind};
+use tempfile::tempdir;
+
+use crate::{
+    graph::MapBuilder,
+    states::settings::Settings,
+    utils::{
+        MapData, TmmprError, read_json_data, read_map_file, save_settings_to_file_with_fs,
+        test_utils::MockFileSystem,
+    },
+};
+
+#[test]
+fn test_missing_file_is_io_error() {
+    let temp_dir = tempdir().unwrap();
+
+    let result = read_json_data::<MapData>(&temp_dir.path().join("missing.json"));
+
+    assert!(matches!(result, Err(TmmprError::Io(err)) if err.kind() == ErrorKind::NotFound));
+}
+
+#[test]
+fn test_invalid_contents_are_parse_errors() {
+    let temp_dir = tempdir().unwrap();
+    let not_json = temp_dir.path().join("not_json.json");
+    fs::write(&not_json, "notes: none").unwrap();
+    let not_utf8 = temp_dir.path().join("not_utf8.json");
+    fs::write(&not_utf8, [0xff, 0xfe, 0x00]).unwrap();
+    let not_a_map = temp_dir.path().join("not_a_map.json");
+    fs::write(&not_a_map, r#"{"notes": {}}"#).unwrap();
+
+    assert!(matches!(
+        read_map_file(&not_json),
+        Err(TmmprError::Parse(_))
+    ));
+    assert!(matches!(
+        read_map_file(&not_utf8),
+        Err(TmmprError::Parse(_))
+    ));
+    assert!(matches!(
+        read_map_file(&not_a_map),
+        Err(TmmprError::Parse(_))
+    ));
+}
+
+#[test]
+fn test_invalid_values_name_their_field() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("map.json");
+    let mut map_data = serde_json::to_value(MapBuilder::new().note("a").note("b").build()).unwrap();
+    map_data["notes"]["1"]["color"] = 7.into();
+    fs::write(&path, serde_json::to_string_pretty(&map_data).unwrap()).unwrap();
+
+    let Err(err @ TmmprError::Field { .. }) = read_map_file(&path) else {
+        panic!("the note's color should be reported");
+    };
+    let message = err.to_string();
+    assert!(
+        message.starts_with("invalid file contents at `notes.1.color`: invalid type: integer `7`"),
+        "{message}"
+    );
+    assert!(message.contains(" at line "), "{message}");
+
+    // Trailing text after the map is still a parse error
+    map_data["notes"]["1"]["color"] = "Red".into();
+    fs::write(&path, format!("{map_data} {{}}")).unwrap();
+    assert!(matches!(read_map_file(&path), Err(TmmprError::Parse(_))));
+}
+
+#[test]
+fn test_missing_home_directory_is_validation_error() {
+    let mock_fs = MockFileSystem::new().with_home_dir(None);
+
+    let result = save_settings_to_file_with_fs(&Settings::new(), &mock_fs);
+
+    assert!(matches!(result, Err(TmmprError::Validation(_))));
+}
@@ -2,11 +2,15 @@ use ratatui::style::Color;
 
 use crate::{
     states::map::{Note, Side},
-    utils::geometry::{Point, calculate_path, get_offset_point},
+    utils::geometry::{
+        Point, calculate_parallel_path, calculate_path, calculate_path_with_waypoints,
+        connection_point, fitting_offset, get_offset_point, hyperedge_paths, hyperedge_target_side,
+        offset_ends, offset_path, path_bounds, self_loop_path,
+    },
 };
 
 // Helper functions for creating test data
-fn create_test_note(x: usize, y: usize, content: &str) -> Note {
+fn create_test_note(x: isize, y: isize, content: &str) -> Note {
     Note::new(x, y, content.to_string(), Color::White)
 }
 
@@ -133,10 +137,10 @@ fn test_s_shape_horizontal_right_to_left() {
     let end_conn = end_note.get_connection_point(Side::Left);
 
     // Verify start and end
-    assert_eq!(path[0].x, start_conn.0 as isize);
-    assert_eq!(path[0].y, start_conn.1 as isize);
-    assert_eq!(path[5].x, end_conn.0 as isize);
-    assert_eq!(path[5].y, end_conn.1 as isize);
+    assert_eq!(path[0].x, start_conn.0);
+    assert_eq!(path[0].y, start_conn.1);
+    assert_eq!(path[5].x, end_conn.0);
+    assert_eq!(path[5].y, end_conn.1);
 
     // Verify offset points (2 units from start/end)
     assert_eq!(path[1].x, path[0].x + 2);
@@ -145,7 +149,7 @@ fn test_s_shape_horizontal_right_to_left() {
     assert_eq!(path[4].y, path[5].y);
 
     // Verify middle vertical segments share x coordinate (halfway between)
-    let expected_mid_x = path[0].x + ((end_conn.0 as isize - start_conn.0 as isize) / 2);
+    let expected_mid_x = path[0].x + (end_conn.0 - start_conn.0) / 2;
     assert_eq!(path[2].x, expected_mid_x);
     assert_eq!(path[3].x, expected_mid_x);
 
@@ -169,8 +173,8 @@ fn test_c_shape_left_to_left() {
     let end_conn = end_note.get_connection_point(Side::Left);
 
     // Verify start and end
-    assert_eq!(path[0].x, start_conn.0 as isize);
-    assert_eq!(path[5].x, end_conn.0 as isize);
+    assert_eq!(path[0].x, start_conn.0);
+    assert_eq!(path[5].x, end_conn.0);
 
     // Verify it goes left (offset should be x - 2)
     assert_eq!(path[1].x, path[0].x - 2);
@@ -204,8 +208,8 @@ fn test_reverse_c_shape_right_to_right() {
     let end_conn = end_note.get_connection_point(Side::Right);
 
     // Verify start and end
-    assert_eq!(path[0].x, start_conn.0 as isize);
-    assert_eq!(path[5].x, end_conn.0 as isize);
+    assert_eq!(path[0].x, start_conn.0);
+    assert_eq!(path[5].x, end_conn.0);
 
     // Verify it goes right (offset should be x + 2)
     assert_eq!(path[1].x, path[0].x + 2);
@@ -235,10 +239,10 @@ fn test_corner_shape_right_to_top() {
     let end_conn = end_note.get_connection_point(Side::Top);
 
     // Verify start and end
-    assert_eq!(path[0].x, start_conn.0 as isize);
-    assert_eq!(path[0].y, start_conn.1 as isize);
-    assert_eq!(path[4].x, end_conn.0 as isize);
-    assert_eq!(path[4].y, end_conn.1 as isize);
+    assert_eq!(path[0].x, start_conn.0);
+    assert_eq!(path[0].y, start_conn.1);
+    assert_eq!(path[4].x, end_conn.0);
+    assert_eq!(path[4].y, end_conn.1);
 
     // Verify offset points
     assert_eq!(path[1].x, path[0].x + 2);
@@ -264,8 +268,8 @@ fn test_u_shape_bottom_to_bottom() {
     let end_conn = end_note.get_connection_point(Side::Bottom);
 
     // Verify start and end
-    assert_eq!(path[0].x, start_conn.0 as isize);
-    assert_eq!(path[5].x, end_conn.0 as isize);
+    assert_eq!(path[0].x, start_conn.0);
+    assert_eq!(path[5].x, end_conn.0);
 
     // Verify it goes down (offset should be y + 2)
     assert_eq!(path[1].y, path[0].y + 2);
@@ -299,8 +303,8 @@ fn test_upside_down_u_shape_top_to_top() {
     let end_conn = end_note.get_connection_point(Side::Top);
 
     // Verify start and end
-    assert_eq!(path[0].x, start_conn.0 as isize);
-    assert_eq!(path[5].x, end_conn.0 as isize);
+    assert_eq!(path[0].x, start_conn.0);
+    assert_eq!(path[5].x, end_conn.0);
 
     // Verify it goes up (offset should be y - 2)
     assert_eq!(path[1].y, path[0].y - 2);
@@ -329,11 +333,298 @@ fn test_sideways_s_shape_vertical() {
     let end_conn = end_note.get_connection_point(Side::Left);
 
     // Verify start and end
-    assert_eq!(path[0].y, start_conn.1 as isize);
-    assert_eq!(path[5].y, end_conn.1 as isize);
+    assert_eq!(path[0].y, start_conn.1);
+    assert_eq!(path[5].y, end_conn.1);
 
     // Verify the middle horizontal segment uses the midpoint in y
-    let expected_mid_y = path[0].y + ((end_conn.1 as isize - start_conn.1 as isize) / 2);
+    let expected_mid_y = path[0].y + (end_conn.1 - start_conn.1) / 2;
     assert_eq!(path[2].y, expected_mid_y);
     assert_eq!(path[3].y, expected_mid_y);
 }
+
+// --- Tests for calculate_path_with_waypoints ---
+
+#[test]
+fn test_path_without_waypoints_matches_automatic_path() {
+    let start_note = create_test_note(10, 10, "Start");
+    let end_note = create_test_note(50, 20, "End");
+
+    let automatic = calculate_path(&start_note, Side::Right, &end_note, Side::Left);
+    let routed =
+        calculate_path_with_waypoints(&start_note, Side::Right, &end_note, Side::Left, &[]);
+
+    assert_eq!(routed, automatic);
+}
+
+#[test]
+fn test_path_passes_through_waypoints_in_order() {
+    let start_note = create_test_note(10, 10, "Start");
+    let end_note = create_test_note(50, 10, "End");
+    let waypoints = [Point { x: 30, y: 2 }, Point { x: 40, y: 30 }];
+
+    let path =
+        calculate_path_with_waypoints(&start_note, Side::Right, &end_note, Side::Left, &waypoints);
+
+    let first = path.iter().position(|p| *p == waypoints[0]).unwrap();
+    let second = path.iter().position(|p| *p == waypoints[1]).unwrap();
+    assert!(first < second);
+
+    // Starts and ends on the notes' connection points
+    let (start_x, start_y) = start_note.get_connection_point(Side::Right);
+    let (end_x, end_y) = end_note.get_connection_point(Side::Left);
+    assert_eq!(
+        path[0],
+        Point {
+            x: start_x,
+            y: start_y
+        }
+    );
+    assert_eq!(*path.last().unwrap(), Point { x: end_x, y: end_y });
+}
+
+#[test]
+fn test_path_with_waypoints_is_orthogonal() {
+    let start_note = create_test_note(10, 10, "Start");
+    let end_note = create_test_note(60, 40, "End");
+    let waypoints = [Point { x: 33, y: 5 }, Point { x: 21, y: 27 }];
+
+    let path =
+        calculate_path_with_waypoints(&start_note, Side::Bottom, &end_note, Side::Top, &waypoints);
+
+    for pair in path.windows(2) {
+        assert!(pair[0].x == pair[1].x || pair[0].y == pair[1].y);
+    }
+}
+
+// --- Tests for path_bounds ---
+
+#[test]
+fn test_path_bounds_hold_every_path() {
+    let sides = [Side::Right, Side::Left, Side::Top, Side::Bottom];
+    let start_note = create_test_note(40, 20, "Start");
+    let end_positions = [(80, 50), (0, 0), (41, 22), (80, 0), (0, 50), (40, 60)];
+    let waypoint_sets: [&[Point]; 2] = [&[], &[Point { x: 100, y: -5 }, Point { x: 3, y: 70 }]];
+
+    for (x, y) in end_positions {
+        let end_note = create_test_note(x, y, "End\nof the path");
+        for start_side in sides {
+            for end_side in sides {
+                for waypoints in waypoint_sets {
+                    let bounds =
+                        path_bounds(&start_note, start_side, &end_note, end_side, waypoints);
+                    let path = calculate_path_with_waypoints(
+                        &start_note,
+                        start_side,
+                        &end_note,
+                        end_side,
+                        waypoints,
+                    );
+
+                    for point in path {
+                        assert!(
+                            point.x >= bounds.x
+                                && point.x < bounds.x + bounds.width
+                                && point.y >= bounds.y
+                                && point.y < bounds.y + bounds.height,
+                            "{:?} lies outside {:?} ({:?} to {:?}, end note at {}, {})",
+                            point,
+                            bounds,
+                            start_side,
+                            end_side,
+                            x,
+                            y
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_path_bounds_fit_the_path() {
+    let start_note = create_test_note(10, 10, "Start");
+    let end_note = create_test_note(50, 20, "End");
+
+    let bounds = path_bounds(&start_note, Side::Right, &end_note, Side::Left, &[]);
+    let path = calculate_path(&start_note, Side::Right, &end_note, Side::Left);
+
+    let min_x = path.iter().map(|p| p.x).min().unwrap();
+    let max_x = path.iter().map(|p| p.x).max().unwrap();
+    let min_y = path.iter().map(|p| p.y).min().unwrap();
+    let max_y = path.iter().map(|p| p.y).max().unwrap();
+    assert_eq!((bounds.x, bounds.y), (min_x, min_y));
+    assert_eq!(
+        (bounds.width, bounds.height),
+        (max_x - min_x + 1, max_y - min_y + 1)
+    );
+}
+
+// --- Tests for offset_path ---
+
+#[test]
+fn test_offset_path_moves_segments_aside() {
+    let path = [
+        Point { x: 0, y: 0 },
+        Point { x: 5, y: 0 },
+        Point { x: 5, y: 5 },
+        Point { x: 5, y: 5 },
+        Point { x: 9, y: 5 },
+    ];
+
+    assert_eq!(
+        offset_path(&path, 1),
+        [
+            Point { x: 0, y: 1 },
+            Point { x: 4, y: 1 },
+            Point { x: 4, y: 6 },
+            Point { x: 9, y: 6 },
+        ]
+    );
+    assert_eq!(offset_path(&path, 0).len(), 4);
+}
+
+#[test]
+fn test_offset_path_steps_where_it_turns_back() {
+    let path = [
+        Point { x: 0, y: 0 },
+        Point { x: 4, y: 0 },
+        Point { x: 1, y: 0 },
+    ];
+
+    let offset = offset_path(&path, 1);
+
+    assert_eq!(
+        offset,
+        [
+            Point { x: 0, y: 1 },
+            Point { x: 4, y: 1 },
+            Point { x: 4, y: -1 },
+            Point { x: 1, y: -1 },
+        ]
+    );
+}
+
+#[test]
+fn test_parallel_paths_keep_apart_on_their_edges() {
+    let sides = [Side::Right, Side::Left, Side::Top, Side::Bottom];
+    let start_note = create_test_note(40, 20, "Start\nof\nthe path");
+    let end_positions = [(80, 50), (0, 0), (41, 22), (80, 0), (0, 50), (40, 60)];
+
+    for (x, y) in end_positions {
+        let end_note = create_test_note(x, y, "End\nof\nthe path");
+        for start_side in sides {
+            for end_side in sides {
+                let (path, offset) =
+                    calculate_parallel_path(&start_note, start_side, &end_note, end_side, &[], 1);
+                assert_eq!(offset, 1);
+                // Overlapping notes aren't connected
+                if path.is_empty() {
+                    continue;
+                }
+
+                let ends = offset_ends(&start_note, start_side, &end_note, end_side, 1);
+                assert_eq!((path[0], *path.last().unwrap()), ends);
+                assert_ne!(path[0], connection_point(&start_note, start_side));
+                for pair in path.windows(2) {
+                    assert!(pair[0].x == pair[1].x || pair[0].y == pair[1].y);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_parallel_offset_fits_the_note_edge() {
+    // The side edges of a one line note have no room below their middle, the top
+    // and bottom edges are wide
+    let start_note = create_test_note(10, 10, "Start");
+    let end_note = create_test_note(50, 20, "End");
+
+    assert_eq!(
+        fitting_offset(&start_note, Side::Right, &end_note, Side::Left, 2),
+        0
+    );
+    assert_eq!(
+        fitting_offset(&start_note, Side::Bottom, &end_note, Side::Top, 2),
+        2
+    );
+}
+
+// --- Tests for self loops ---
+
+#[test]
+fn test_self_loop_leaves_and_enters_the_same_side() {
+    let note = create_test_note(10, 10, "Loop\nback");
+    let middle = connection_point(&note, Side::Right);
+
+    let path = self_loop_path(&note, Side::Right, 0);
+    assert_eq!(
+        path,
+        [
+            Point {
+                x: middle.x,
+                y: middle.y - 1
+            },
+            Point {
+                x: middle.x + 2,
+                y: middle.y - 1
+            },
+            Point {
+                x: middle.x + 2,
+                y: middle.y
+            },
+            middle,
+        ]
+    );
+
+    // Another loop on the note goes around it
+    let outer = self_loop_path(&note, Side::Right, -1);
+    assert_eq!(outer[1].x, middle.x + 4);
+    assert_eq!((outer[0], outer[3]), (path[0], path[3]));
+}
+
+// --- Tests for fan-out connections ---
+
+#[test]
+fn test_fan_out_branches_split_off_one_trunk() {
+    let source = create_test_note(10, 10, "Cause");
+    let above = create_test_note(40, 2, "Effect");
+    let ahead = create_test_note(40, 10, "Effect");
+    let behind = create_test_note(0, 20, "Effect");
+
+    let (trunk, branches) = hyperedge_paths(&source, Side::Right, &[&above, &ahead, &behind]);
+
+    let start = connection_point(&source, Side::Right);
+    let split = Point {
+        x: start.x + 4,
+        y: start.y,
+    };
+    assert_eq!(trunk, [start, split]);
+
+    // Notes ahead are reached along a line down from where the trunk splits
+    let above_end = connection_point(&above, Side::Left);
+    assert_eq!(
+        branches[0],
+        [
+            split,
+            Point {
+                x: split.x,
+                y: above_end.y
+            },
+            above_end
+        ]
+    );
+    assert_eq!(branches[1], [split, connection_point(&ahead, Side::Left)]);
+
+    // A note behind the trunk is entered from the side facing it
+    assert_eq!(
+        hyperedge_target_side(&source, Side::Right, &behind),
+        Side::Top
+    );
+    assert_eq!(branches[2][0], split);
+    assert_eq!(
+        branches[2].last(),
+        Some(&connection_point(&behind, Side::Top))
+    );
+}
@@ -0,0 +1,102 @@
+use ratatui::style::Color;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+use crate::{
+    states::map::{Connection, Note, NoteStatus, Side},
+    utils::{EncryptedText, HTML_DATA_ID, export_html, html_map_data},
+};
+
+fn create_connection(from_id: usize, to_id: usize, weight: Option<u32>) -> Connection {
+    Connection {
+        from_id,
+        from_side: Side::Right,
+        to_id,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight,
+        layer: None,
+    }
+}
+
+/// The JSON embedded in an HTML export.
+fn embedded_data(document: &str) -> Value {
+    let start_tag = format!("<script type=\"application/json\" id=\"{HTML_DATA_ID}\">");
+    let start = document.find(&start_tag).unwrap() + start_tag.len();
+    let end = start + document[start..].find("</script>").unwrap();
+    serde_json::from_str(&document[start..end]).unwrap()
+}
+
+#[test]
+fn test_export_html_embeds_map_and_viewer() {
+    let notes = HashMap::from([
+        (0, Note::new(10, 5, String::from("Plan"), Color::Green)),
+        (1, Note::new(50, 5, String::from("Ship"), Color::White)),
+    ]);
+    let connections = vec![create_connection(0, 1, Some(3))];
+
+    let document = export_html("Q3 <roadmap>", &notes, &connections);
+
+    assert!(document.starts_with("<!DOCTYPE html>"));
+    assert!(document.contains("<title>Q3 &lt;roadmap&gt;</title>"));
+    assert!(document.contains("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(document.contains("<rect id=\"note-0\""));
+    assert!(document.contains("addEventListener(\"wheel\""));
+    // Self-contained: nothing is loaded from elsewhere
+    assert!(!document.contains("src="));
+    assert!(!document.contains("href="));
+
+    assert_eq!(
+        embedded_data(&document),
+        html_map_data(&notes, &connections)
+    );
+}
+
+#[test]
+fn test_html_map_data() {
+    let mut secret = Note::new(0, 0, String::from("secret plan"), Color::White);
+    secret.encrypted = Some(EncryptedText {
+        salt: String::new(),
+        rounds: 1,
+        nonce: String::new(),
+        ciphertext: String::new(),
+    });
+    // Titles aren't encrypted
+    secret.set_title("Budget");
+    let mut idea = Note::new(4, 2, String::from("Idea"), Color::Red);
+    idea.status = Some(NoteStatus::Doing);
+    let notes = HashMap::from([(3, secret), (1, idea)]);
+    let connections = vec![
+        create_connection(1, 3, None),
+        // Leads to a note that isn't exported
+        create_connection(1, 9, Some(2)),
+    ];
+
+    assert_eq!(
+        html_map_data(&notes, &connections),
+        json!({
+            "notes": [
+                { "id": 1, "x": 4, "y": 2, "color": "Red", "title": null, "status": "doing", "content": "Idea" },
+                { "id": 3, "x": 0, "y": 0, "color": "White", "title": "Budget", "status": null, "content": null },
+            ],
+            "connections": [{ "from": 1, "to": 3, "weight": null }],
+        })
+    );
+}
+
+#[test]
+fn test_export_html_keeps_script_closed() {
+    let notes = HashMap::from([(
+        0,
+        Note::new(0, 0, String::from("</script><b>"), Color::White),
+    )]);
+
+    let document = export_html("map", &notes, &[]);
+
+    assert_eq!(document.matches("</script>").count(), 2);
+    assert_eq!(
+        embedded_data(&document)["notes"][0]["content"],
+        "</script><b>"
+    );
+}
@@ -0,0 +1,85 @@
+use std::collections::BTreeSet;
+
+use crate::utils::{CatalogEntry, Locale, catalog, tr, tr_args, tr_lines};
+
+/// Names of the `{name}` placeholders in a text.
+fn placeholders(text: &str) -> BTreeSet<&str> {
+    text.split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+        .collect()
+}
+
+#[test]
+fn test_catalogs_parse() {
+    for locale in Locale::ALL {
+        assert!(!catalog(locale).is_empty());
+    }
+}
+
+#[test]
+fn test_translations_match_english() {
+    let english = catalog(Locale::English);
+
+    for locale in Locale::ALL {
+        let translation = catalog(locale);
+        let english_keys: BTreeSet<_> = english.keys().collect();
+        let keys: BTreeSet<_> = translation.keys().collect();
+        assert_eq!(keys, english_keys, "{locale:?} has different keys");
+
+        for (key, entry) in translation {
+            match (&english[key], entry) {
+                (CatalogEntry::Text(english_text), CatalogEntry::Text(text)) => assert_eq!(
+                    placeholders(text),
+                    placeholders(english_text),
+                    "{locale:?} {key} has different placeholders"
+                ),
+                (CatalogEntry::Lines(english_lines), CatalogEntry::Lines(lines)) => assert_eq!(
+                    lines.len(),
+                    english_lines.len(),
+                    "{locale:?} {key} has a different number of lines"
+                ),
+                _ => panic!("{locale:?} {key} is a different kind of entry"),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_locale_from_lang() {
+    assert_eq!(Locale::from_lang("de_DE.UTF-8"), Some(Locale::German));
+    assert_eq!(Locale::from_lang("de"), Some(Locale::German));
+    assert_eq!(Locale::from_lang("en_US"), Some(Locale::English));
+    assert_eq!(Locale::from_lang("C"), None);
+    assert_eq!(Locale::from_lang("fr_FR.UTF-8"), None);
+    assert_eq!(Locale::from_lang(""), None);
+}
+
+#[test]
+fn test_tr() {
+    assert_eq!(tr(Locale::English, "bar.mode.normal"), "[ NORMAL ]");
+    assert_ne!(
+        tr(Locale::German, "help.page1.title"),
+        tr(Locale::English, "help.page1.title")
+    );
+    // Missing entries show their key
+    assert_eq!(tr(Locale::German, "no.such.key"), "no.such.key");
+    // Multi-line entries aren't text
+    assert_eq!(tr(Locale::English, "help.page1"), "help.page1");
+}
+
+#[test]
+fn test_tr_args() {
+    assert_eq!(
+        tr_args(Locale::English, "bar.weight", &[("weight", "3")]),
+        tr(Locale::English, "bar.weight").replace("{weight}", "3")
+    );
+    assert!(!tr_args(Locale::German, "tabs.milestone", &[("name", "v1")]).contains("{name}"));
+}
+
+#[test]
+fn test_tr_lines() {
+    assert!(!tr_lines(Locale::German, "help.text.edit").is_empty());
+    assert!(tr_lines(Locale::English, "bar.mode.normal").is_empty());
+    assert!(tr_lines(Locale::English, "no.such.key").is_empty());
+}
@@ -0,0 +1,80 @@
+use ratatui::style::Color;
+
+use crate::{
+    graph::MapBuilder,
+    states::map::{Hyperedge, Region, Side},
+    utils::{MapData, repair_map_data},
+};
+
+/// A map of three connected notes.
+fn map_data() -> MapData {
+    MapBuilder::new()
+        .note("Plan")
+        .note("Build")
+        .note("Ship")
+        .connect(0, 1)
+        .connect(1, 2)
+        .build()
+}
+
+#[test]
+fn test_repair_map_data_leaves_a_sound_map() {
+    let mut map_data = map_data();
+    let before = map_data.clone();
+
+    assert!(repair_map_data(&mut map_data).is_empty());
+    assert_eq!(map_data, before);
+}
+
+#[test]
+fn test_repair_map_data_drops_dangling_references() {
+    let mut map_data = map_data();
+    map_data.connections[1].to_id = 7;
+    map_data.hyperedges.push(Hyperedge {
+        from_id: 0,
+        from_side: Side::Right,
+        to_ids: vec![1, 9],
+        color: Color::White,
+        layer: None,
+    });
+    let mut region = Region::new(0, 0, String::from("Group"), Color::White);
+    region.members = vec![2, 5];
+    map_data.regions.push(region);
+
+    let repairs = repair_map_data(&mut map_data);
+
+    assert_eq!(
+        repairs,
+        [
+            "connections.1: leads to note 7, which doesn't exist",
+            "hyperedges.0: leads to note 9, which doesn't exist",
+            "regions.0: has note 5 as a member, which doesn't exist",
+        ]
+    );
+    assert_eq!(map_data.connections.len(), 1);
+    assert_eq!(map_data.connections[0].to_id, 1);
+    assert_eq!(map_data.hyperedges[0].to_ids, [1]);
+    assert_eq!(map_data.regions[0].members, [2]);
+}
+
+#[test]
+fn test_repair_map_data_fixes_render_order_and_counter() {
+    let mut map_data = map_data();
+    map_data.render_order = vec![2, 4, 2];
+    map_data.next_note_id_counter = 1;
+
+    let repairs = repair_map_data(&mut map_data);
+
+    assert_eq!(
+        repairs,
+        [
+            "render_order: lists note 4, which doesn't exist",
+            "render_order: lists note 2 more than once",
+            "render_order: doesn't list note 0",
+            "render_order: doesn't list note 1",
+            "next_note_id_counter: 1 isn't above note id 2",
+        ]
+    );
+    assert_eq!(map_data.render_order, [2, 0, 1]);
+    assert_eq!(map_data.next_note_id_counter, 3);
+}
@@ -0,0 +1,156 @@
+use ratatui::style::Color;
+use std::collections::HashMap;
+
+use crate::{
+    states::map::{Connection, Note, Side},
+    utils::{EncryptedText, MapChanges, MapDiff, NoteChange, diff_maps, map_changes},
+};
+
+fn create_connection(from_id: usize, to_id: usize) -> Connection {
+    Connection {
+        from_id,
+        from_side: Side::Right,
+        to_id,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    }
+}
+
+fn note(content: &str) -> Note {
+    Note::new(0, 0, String::from(content), Color::White)
+}
+
+#[test]
+fn test_diff_maps_identical() {
+    let notes = HashMap::from([(0, note("A")), (1, note("B"))]);
+    let connections = vec![create_connection(0, 1)];
+
+    let diff = diff_maps(&notes, &connections, &notes.clone(), &connections.clone());
+
+    assert!(diff.is_empty());
+    assert_eq!(diff, MapDiff::default());
+}
+
+#[test]
+fn test_diff_maps_notes() {
+    let mine = HashMap::from([
+        (0, note("Plan\nwith details")),
+        (1, note("Build")),
+        (2, note("Old")),
+    ]);
+    let mut moved = note("Build");
+    moved.x = 10;
+    let theirs = HashMap::from([
+        (0, note("Plan better\nwith details")),
+        (1, moved),
+        (3, note("New")),
+        (4, note("Newer")),
+    ]);
+
+    let diff = diff_maps(&mine, &[], &theirs, &[]);
+
+    // Listed in id order, by first line
+    assert_eq!(diff.added, vec!["New", "Newer"]);
+    assert_eq!(diff.removed, vec!["Old"]);
+    assert_eq!(diff.changed, vec!["Plan better", "Build"]);
+    assert!(!diff.is_empty());
+}
+
+#[test]
+fn test_diff_maps_connections() {
+    let notes = HashMap::from([(0, note("A")), (1, note("B")), (2, note("C"))]);
+    let mine = vec![create_connection(0, 1), create_connection(1, 2)];
+    let theirs = vec![
+        create_connection(0, 1),
+        create_connection(0, 2),
+        create_connection(2, 0),
+    ];
+
+    let diff = diff_maps(&notes, &mine, &notes, &theirs);
+
+    assert_eq!(diff.connections_added, 2);
+    assert_eq!(diff.connections_removed, 1);
+    assert!(diff.added.is_empty() && diff.changed.is_empty());
+}
+
+#[test]
+fn test_diff_maps_encrypted_notes_compare_ciphertext() {
+    let encrypted = |ciphertext: &str| EncryptedText {
+        salt: String::from("salt"),
+        rounds: 1,
+        nonce: String::from("nonce"),
+        ciphertext: String::from(ciphertext),
+    };
+
+    // Unlocked on this side: the plaintext is in `content`, the file only has ciphertext
+    let mut unlocked = note("secret");
+    unlocked.encrypted = Some(encrypted("abc"));
+    let mut locked = note("");
+    locked.encrypted = Some(encrypted("abc"));
+    let mut reencrypted = note("");
+    reencrypted.encrypted = Some(encrypted("def"));
+
+    let mine = HashMap::from([(0, unlocked)]);
+    assert!(diff_maps(&mine, &[], &HashMap::from([(0, locked)]), &[]).is_empty());
+
+    let diff = diff_maps(&mine, &[], &HashMap::from([(0, reencrypted)]), &[]);
+    assert_eq!(diff.changed, vec!["[encrypted]"]);
+}
+
+#[test]
+fn test_map_changes_kinds() {
+    let mut moved = note("Moved");
+    moved.y = 5;
+    let mut recolored = note("Recolored");
+    recolored.color = Color::Red;
+    let old = HashMap::from([
+        (0, note("Same")),
+        (1, note("Moved")),
+        (2, note("Edited")),
+        (3, note("Recolored")),
+        (4, note("Removed")),
+    ]);
+    let new = HashMap::from([
+        (0, note("Same")),
+        (1, moved),
+        (2, note("Edited twice")),
+        (3, recolored),
+        (5, note("Added")),
+    ]);
+    let old_connections = vec![create_connection(0, 1), create_connection(0, 4)];
+    let new_connections = vec![create_connection(0, 1), create_connection(0, 5)];
+
+    let changes = map_changes(&old, &old_connections, &new, &new_connections);
+
+    let modified = |moved, content, color| NoteChange::Modified {
+        moved,
+        content,
+        color,
+    };
+    assert_eq!(
+        changes.notes.into_iter().collect::<Vec<_>>(),
+        vec![
+            (1, modified(true, false, false)),
+            (2, modified(false, true, false)),
+            (3, modified(false, false, true)),
+            (4, NoteChange::Removed),
+            (5, NoteChange::Added),
+        ]
+    );
+    assert_eq!(changes.connections_added, vec![create_connection(0, 5)]);
+    assert_eq!(changes.connections_removed, vec![create_connection(0, 4)]);
+}
+
+#[test]
+fn test_map_changes_identical() {
+    let notes = HashMap::from([(0, note("A")), (1, note("B"))]);
+    let connections = vec![create_connection(0, 1)];
+
+    let changes = map_changes(&notes, &connections, &notes, &connections);
+
+    assert!(changes.is_empty());
+    assert_eq!(changes, MapChanges::default());
+}
@@ -1,18 +1,27 @@
 use ratatui::style::Color;
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 use tempfile::tempdir;
 
 use crate::{
     app::{App, Screen},
     states::{
-        MapState,
-        map::{Connection, Notification, Side},
-        start::StartState,
+        MapState, SettingsState,
+        map::{
+            Connection, ConnectionsState, Mode, Notification, Region, SearchState, Side, ViewState,
+        },
+        start::{MapPreview, StartState},
     },
     utils::{
-        IoErrorKind, MapData, create_map_file_with_fs, filesystem::test_utils::TempFileSystem,
-        load_map_file_with_fs, read_json_data, save_map_file, save_with_notification,
-        test_utils::MockFileSystem,
+        CryptoError, FileKey, IoErrorKind, JsonFormat, MapData, MapFileContents, Point,
+        create_map_file_with_fs, emergency_save, emergency_save_path,
+        filesystem::test_utils::TempFileSystem, finish_background_save, is_compressed_map_path,
+        load_map_file_unchecked_with_fs, load_map_file_with_fs, map_file_extension, map_name,
+        read_json_data, read_map_file, save_map_file, save_map_file_in_background,
+        save_with_notification, test_utils::MockFileSystem, unlock_map_data,
+        unlock_map_file_with_fs, write_json_data_buffered,
     },
 };
 
@@ -34,6 +43,7 @@ fn create_test_app_with_start_state() -> App {
     App {
         running: true,
         screen: Screen::Start(StartState::new_with_fs(&temp_fs)),
+        workspace: None,
     }
 }
 
@@ -51,11 +61,14 @@ fn create_populated_map_state(path: PathBuf) -> MapState {
     let connection = Connection {
         from_id: 0,
         from_side: Side::Right,
-        to_id: Some(1),
-        to_side: Some(Side::Left),
+        to_id: 1,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(connection);
+    map_state.connections_state.focused_connection = Some(connection.into());
     map_state.connections_state.stash_connection();
 
     map_state
@@ -253,7 +266,7 @@ fn test_save_map_file_shows_save_success_notification() {
     let file_path = temp_dir.path().join("test.json");
     let mut map_state = create_populated_map_state(file_path.clone());
 
-    map_state.ui_state.clear_notification();
+    map_state.ui_state.notifications.clear();
 
     // Save with notification enabled
     let _ = save_with_notification(
@@ -265,8 +278,8 @@ fn test_save_map_file_shows_save_success_notification() {
 
     // Verify: Success notification shown
     assert_eq!(
-        map_state.ui_state.show_notification,
-        Some(Notification::SaveSuccess)
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::SaveSuccess)
     );
 
     // Verify: Needs redraw
@@ -279,7 +292,7 @@ fn test_save_map_file_handles_write_failure() {
     let invalid_path = PathBuf::from("/invalid/path/map.json");
     let mut map_state = create_populated_map_state(invalid_path.clone());
 
-    map_state.ui_state.clear_notification();
+    map_state.ui_state.notifications.clear();
 
     // Attempt to save with notification enabled
     let _ = save_with_notification(
@@ -291,14 +304,75 @@ fn test_save_map_file_handles_write_failure() {
 
     // Verify: Save failure notification shown
     assert_eq!(
-        map_state.ui_state.show_notification,
-        Some(Notification::SaveFail)
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::SaveFail)
     );
 
     // Verify: Redraw triggered
     assert!(map_state.ui_state.needs_clear_and_redraw);
 }
 
+#[test]
+fn test_background_save_writes_file_and_notifies() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("background.json");
+    let mut map_state = create_populated_map_state(file_path.clone());
+    map_state.persistence.mark_dirty();
+    map_state.ui_state.notifications.clear();
+
+    save_map_file_in_background(&mut map_state, &file_path, true);
+
+    // Counts as saved right away, and the file isn't checked for changes meanwhile
+    assert!(!map_state.persistence.has_unsaved_changes);
+    assert!(map_state.persistence.background_save.is_some());
+    assert!(!map_state.persistence.should_check_disk());
+
+    finish_background_save(&mut map_state, true);
+
+    assert!(map_state.persistence.background_save.is_none());
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::SaveSuccess)
+    );
+    let saved: MapData = read_json_data(&file_path).unwrap();
+    assert_eq!(saved.notes.len(), 2);
+    assert_eq!(map_state.persistence.base_map, Some(saved));
+    assert!(map_state.persistence.disk_modified.is_some());
+}
+
+#[test]
+fn test_background_save_keeps_changes_made_meanwhile() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("background.json");
+    let mut map_state = create_populated_map_state(file_path.clone());
+
+    save_map_file_in_background(&mut map_state, &file_path, false);
+    map_state.add_note();
+    finish_background_save(&mut map_state, true);
+
+    assert!(map_state.persistence.has_unsaved_changes);
+    // Saves without a notification, like auto-save
+    assert!(map_state.ui_state.notifications.is_empty());
+    let saved: MapData = read_json_data(&file_path).unwrap();
+    assert_eq!(saved.notes.len(), 2);
+}
+
+#[test]
+fn test_failed_background_save_marks_map_unsaved() {
+    let invalid_path = PathBuf::from("/invalid/path/map.json");
+    let mut map_state = create_populated_map_state(invalid_path.clone());
+    map_state.ui_state.notifications.clear();
+
+    save_map_file_in_background(&mut map_state, &invalid_path, true);
+    finish_background_save(&mut map_state, true);
+
+    assert!(map_state.persistence.has_unsaved_changes);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::SaveFail)
+    );
+}
+
 #[test]
 fn test_save_map_file_overwrites_existing_file() {
     let temp_dir = tempdir().unwrap();
@@ -363,21 +437,27 @@ fn test_save_map_file_preserves_connections() {
     let conn1 = Connection {
         from_id: 0,
         from_side: Side::Right,
-        to_id: Some(1),
-        to_side: Some(Side::Left),
+        to_id: 1,
+        to_side: Side::Left,
         color: Color::Red,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(conn1);
+    map_state.connections_state.focused_connection = Some(conn1.into());
     map_state.connections_state.stash_connection();
 
     let conn2 = Connection {
         from_id: 1,
         from_side: Side::Bottom,
-        to_id: Some(0),
-        to_side: Some(Side::Bottom),
+        to_id: 0,
+        to_side: Side::Bottom,
         color: Color::Blue,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(conn2);
+    map_state.connections_state.focused_connection = Some(conn2.into());
     map_state.connections_state.stash_connection();
 
     let _ = save_map_file(&mut map_state, &file_path);
@@ -387,7 +467,7 @@ fn test_save_map_file_preserves_connections() {
     assert_eq!(loaded_data.connections.len(), 2);
     assert_eq!(loaded_data.connections[0].from_id, 0);
     assert_eq!(loaded_data.connections[0].color, Color::Red);
-    assert_eq!(loaded_data.connections[1].to_id, Some(0));
+    assert_eq!(loaded_data.connections[1].to_id, 0);
 }
 
 #[test]
@@ -488,11 +568,14 @@ fn test_load_map_file_loads_connections() {
     let conn = Connection {
         from_id: 0,
         from_side: Side::Bottom,
-        to_id: Some(1),
-        to_side: Some(Side::Top),
+        to_id: 1,
+        to_side: Side::Top,
         color: Color::Yellow,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(conn);
+    map_state.connections_state.focused_connection = Some(conn.into());
     map_state.connections_state.stash_connection();
 
     let _ = save_map_file(&mut map_state, &file_path);
@@ -505,10 +588,7 @@ fn test_load_map_file_loads_connections() {
     if let Screen::Map(loaded_state) = &app.screen {
         assert_eq!(loaded_state.connections_state.connections().len(), 1);
         assert_eq!(loaded_state.connections_state.connections()[0].from_id, 0);
-        assert_eq!(
-            loaded_state.connections_state.connections()[0].to_id,
-            Some(1)
-        );
+        assert_eq!(loaded_state.connections_state.connections()[0].to_id, 1);
         assert_eq!(
             loaded_state.connections_state.connections()[0].from_side,
             Side::Bottom
@@ -588,9 +668,13 @@ fn test_load_map_file_handles_invalid_json() {
     // Verify: App stays on Start screen
     assert!(matches!(app.screen, Screen::Start(_)));
 
-    // Verify: Error message displayed
+    // Verify: Error message displayed, with the parse error behind it
     if let Screen::Start(start_state) = &app.screen {
         assert_eq!(start_state.display_err_msg, Some(IoErrorKind::FileRead));
+        assert_eq!(
+            start_state.load_error.as_deref(),
+            Some("invalid file contents: key must be a string at line 1 column 3")
+        );
     }
 }
 
@@ -618,6 +702,118 @@ fn test_load_map_file_handles_corrupt_json() {
     }
 }
 
+#[test]
+fn test_load_map_file_warns_about_checksum_mismatch() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("synced.json");
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let mut map_state = create_populated_map_state(file_path.clone());
+    save_map_file(&mut map_state, &file_path).unwrap();
+    // A sync tool writing an older note over the new one
+    let mut map_json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&file_path).unwrap()).unwrap();
+    map_json["notes"]["0"]["content"] = "older text".into();
+    fs::write(&file_path, map_json.to_string()).unwrap();
+
+    let mut app = create_test_app_with_start_state();
+    load_map_file_with_fs(&mut app, &file_path, &fs);
+
+    let Screen::Start(start_state) = &app.screen else {
+        panic!("the map shouldn't be opened before asking");
+    };
+    assert!(start_state.dialog.is_some());
+
+    load_map_file_unchecked_with_fs(&mut app, &file_path, &fs);
+    let Screen::Map(map_state) = &app.screen else {
+        panic!("the map should be open");
+    };
+    assert_eq!(map_state.notes_state.notes()[&0].content, "older text");
+}
+
+#[test]
+fn test_load_map_file_restores_crash_changes() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("plan.json");
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let mut map_state = create_populated_map_state(file_path.clone());
+    save_map_file(&mut map_state, &file_path).unwrap();
+    map_state
+        .notes_state
+        .add(0, 0, String::from("Before the crash"), Color::White);
+    map_state.persistence.mark_dirty();
+    emergency_save(&mut map_state).unwrap().unwrap();
+
+    let mut app = create_test_app_with_start_state();
+    if let Screen::Start(start_state) = &mut app.screen {
+        start_state.pending_crash_restore = Some(file_path.clone());
+    }
+    load_map_file_with_fs(&mut app, &file_path, &fs);
+
+    let Screen::Map(map_state) = &app.screen else {
+        panic!("the map should be open");
+    };
+    assert_eq!(
+        map_state.notes_state.notes()[&2].content,
+        "Before the crash"
+    );
+    assert!(map_state.persistence.has_unsaved_changes);
+    assert!(!emergency_save_path(&file_path).exists());
+    // The map file keeps its contents until it's saved
+    assert_eq!(
+        read_json_data::<MapData>(&file_path).unwrap().notes.len(),
+        2
+    );
+}
+
+#[test]
+fn test_load_map_file_recovers_damaged_map() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("damaged.json");
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let mut map_state = create_map_state_using_mock_filesystem(file_path.clone());
+    map_state
+        .notes_state
+        .add(0, 0, String::from("kept"), Color::White);
+    map_state
+        .notes_state
+        .add(20, 0, String::from("damaged"), Color::White);
+    let mut map_json = serde_json::to_value(MapData::from_map_state(&mut map_state)).unwrap();
+    map_json["notes"]["1"]["content"] = 42.into();
+    let damaged = map_json.to_string();
+    fs::write(&file_path, &damaged).unwrap();
+
+    let mut app = create_test_app_with_start_state();
+    load_map_file_with_fs(&mut app, &file_path, &fs);
+
+    let Screen::Map(map_state) = &mut app.screen else {
+        panic!("the recovered map should be open");
+    };
+    assert_eq!(map_state.notes_state.notes().len(), 1);
+    assert_eq!(map_state.notes_state.notes()[&0].content, "kept");
+    assert!(map_state.persistence.recovered);
+    assert!(map_state.persistence.has_unsaved_changes);
+    assert!(!map_state.persistence.should_save(0));
+    let dialog = map_state.ui_state.dialog.as_ref().unwrap();
+    assert!(dialog.message[0].starts_with("notes.1: invalid type: integer `42`"));
+
+    // The damaged file is kept as it was, and only written over when saved
+    let copy_path = temp_dir.path().join("damaged.json.damaged");
+    assert_eq!(fs::read_to_string(&copy_path).unwrap(), damaged);
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), damaged);
+    save_map_file(map_state, &file_path).unwrap();
+    assert!(!map_state.persistence.recovered);
+    assert!(matches!(
+        read_map_file(&file_path),
+        Ok(MapFileContents::Plain(_))
+    ));
+}
+
 #[test]
 fn test_load_map_file_empty_map() {
     let temp_dir = tempdir().unwrap();
@@ -718,21 +914,27 @@ fn test_roundtrip_save_and_load_preserves_all_data() {
     let conn1 = Connection {
         from_id: 0,
         from_side: Side::Right,
-        to_id: Some(1),
-        to_side: Some(Side::Left),
+        to_id: 1,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    original_state.connections_state.focused_connection = Some(conn1);
+    original_state.connections_state.focused_connection = Some(conn1.into());
     original_state.connections_state.stash_connection();
 
     let conn2 = Connection {
         from_id: 2,
         from_side: Side::Bottom,
-        to_id: Some(1),
-        to_side: Some(Side::Top),
+        to_id: 1,
+        to_side: Side::Top,
         color: Color::Cyan,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    original_state.connections_state.focused_connection = Some(conn2);
+    original_state.connections_state.focused_connection = Some(conn2.into());
     original_state.connections_state.stash_connection();
 
     // Set view position
@@ -774,15 +976,9 @@ fn test_roundtrip_save_and_load_preserves_all_data() {
         // Check connections
         assert_eq!(loaded_state.connections_state.connections().len(), 2);
         assert_eq!(loaded_state.connections_state.connections()[0].from_id, 0);
-        assert_eq!(
-            loaded_state.connections_state.connections()[0].to_id,
-            Some(1)
-        );
+        assert_eq!(loaded_state.connections_state.connections()[0].to_id, 1);
         assert_eq!(loaded_state.connections_state.connections()[1].from_id, 2);
-        assert_eq!(
-            loaded_state.connections_state.connections()[1].to_id,
-            Some(1)
-        );
+        assert_eq!(loaded_state.connections_state.connections()[1].to_id, 1);
 
         // Check connection index
         assert_eq!(loaded_state.connections_state.connection_index().len(), 3);
@@ -902,21 +1098,27 @@ fn test_connection_index_roundtrip() {
     let conn1 = Connection {
         from_id: 0,
         from_side: Side::Right,
-        to_id: Some(1),
-        to_side: Some(Side::Left),
+        to_id: 1,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(conn1);
+    map_state.connections_state.focused_connection = Some(conn1.into());
     map_state.connections_state.stash_connection();
 
     let conn2 = Connection {
         from_id: 1,
         from_side: Side::Right,
-        to_id: Some(2),
-        to_side: Some(Side::Left),
+        to_id: 2,
+        to_side: Side::Left,
         color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
     };
-    map_state.connections_state.focused_connection = Some(conn2);
+    map_state.connections_state.focused_connection = Some(conn2.into());
     map_state.connections_state.stash_connection();
 
     let _ = save_map_file(&mut map_state, &file_path);
@@ -956,4 +1158,525 @@ fn test_connection_index_roundtrip() {
             1
         );
     }
+
+    // Only the connections are saved
+    let saved: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&file_path).unwrap()).unwrap();
+    assert!(saved.get("connection_index").is_none());
+}
+
+#[test]
+fn test_load_map_file_ignores_stale_connection_index() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("stale_index.json");
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let mut map_state = create_map_state_using_mock_filesystem(file_path.clone());
+    map_state
+        .notes_state
+        .add(0, 0, String::from("A"), Color::White);
+    map_state
+        .notes_state
+        .add(20, 0, String::from("B"), Color::White);
+    map_state.connections_state.add(Connection {
+        from_id: 0,
+        from_side: Side::Right,
+        to_id: 1,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    });
+    // As written by older versions, then out of step after an edit by hand
+    let mut map_json = serde_json::to_value(MapData::from_map_state(&mut map_state)).unwrap();
+    map_json["connection_index"] = serde_json::json!({"0": [3], "7": [0]});
+    fs::write(&file_path, map_json.to_string()).unwrap();
+
+    let mut app = create_test_app_with_start_state();
+    load_map_file_unchecked_with_fs(&mut app, &file_path, &fs);
+
+    let Screen::Map(map_state) = &app.screen else {
+        panic!("the map should be open");
+    };
+    let index = map_state.connections_state.connection_index();
+    assert_eq!(index.len(), 2);
+    assert_eq!(index[&0], [0]);
+    assert_eq!(index[&1], [0]);
+}
+
+#[test]
+fn test_load_map_file_repairs_dangling_connection() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("dangling.json");
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let mut map_state = create_map_state_using_mock_filesystem(file_path.clone());
+    map_state
+        .notes_state
+        .add(0, 0, String::from("A"), Color::White);
+    let mut map_data = MapData::from_map_state(&mut map_state);
+    map_data.connections.push(Connection {
+        from_id: 0,
+        from_side: Side::Right,
+        to_id: 5,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    });
+    fs::write(&file_path, serde_json::to_string(&map_data).unwrap()).unwrap();
+
+    let mut app = create_test_app_with_start_state();
+    load_map_file_with_fs(&mut app, &file_path, &fs);
+
+    let Screen::Map(map_state) = &mut app.screen else {
+        panic!("the map should be open");
+    };
+    assert!(map_state.connections_state.connections().is_empty());
+    assert!(map_state.persistence.has_unsaved_changes);
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::MapRepaired(1))
+    );
+
+    // Saved with the repairs
+    save_map_file(map_state, &file_path).unwrap();
+    let Ok(MapFileContents::Plain(saved)) = read_map_file(&file_path) else {
+        panic!("the map should be saved");
+    };
+    assert!(saved.connections.is_empty());
+}
+
+#[test]
+fn test_encrypted_note_is_locked_after_reload() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("encrypted_map.json");
+
+    let mut map_state = create_map_state_using_mock_filesystem(file_path.clone());
+    let id = map_state
+        .notes_state
+        .add(0, 0, String::from("top secret"), Color::White);
+    map_state.notes_state.select(id);
+    map_state.toggle_note_encryption();
+    map_state
+        .ui_state
+        .passphrase_prompt
+        .as_mut()
+        .unwrap()
+        .input
+        .push_str("pw");
+    map_state.submit_passphrase();
+
+    save_map_file(&mut map_state, &file_path).unwrap();
+
+    let raw = fs::read_to_string(&file_path).unwrap();
+    assert!(!raw.contains("top secret"));
+
+    let mut app = create_test_app_with_start_state();
+    load_map_file_with_fs(&mut app, &file_path, &MockFileSystem::new());
+    let Screen::Map(loaded) = &app.screen else {
+        panic!("Expected map screen");
+    };
+
+    assert!(loaded.note_is_locked(id));
+    assert_eq!(loaded.notes_state.notes()[&id].content, "");
+}
+
+#[test]
+fn test_regions_roundtrip() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("regions.json");
+
+    let mut map_state = create_map_state_using_mock_filesystem(file_path.clone());
+    map_state
+        .regions_state
+        .add(Region::new(5, 5, String::from("Backlog"), Color::Blue));
+    save_map_file(&mut map_state, &file_path).unwrap();
+
+    let mut app = create_test_app_with_start_state();
+    load_map_file_with_fs(&mut app, &file_path, &MockFileSystem::new());
+    let Screen::Map(loaded) = &app.screen else {
+        panic!("Expected map screen");
+    };
+
+    assert_eq!(
+        loaded.regions_state.regions(),
+        &[Region::new(5, 5, String::from("Backlog"), Color::Blue)]
+    );
+    // Nothing is selected after loading
+    assert_eq!(loaded.regions_state.selected_index(), None);
+}
+
+#[test]
+fn test_load_map_file_without_regions_field() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("old_format.json");
+    fs::write(
+        &file_path,
+        r#"{"view_pos":{"x":0,"y":0},"next_note_id_counter":0,"notes":{},"render_order":[],"connections":[]}"#,
+    )
+    .unwrap();
+
+    let mut app = create_test_app_with_start_state();
+    load_map_file_with_fs(&mut app, &file_path, &MockFileSystem::new());
+    let Screen::Map(loaded) = &app.screen else {
+        panic!("Expected map screen");
+    };
+
+    assert!(loaded.regions_state.regions().is_empty());
+}
+
+#[test]
+fn test_load_map_file_with_unsigned_positions() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("old_format.json");
+    fs::write(
+        &file_path,
+        r#"{"view_pos":{"x":3,"y":4},"next_note_id_counter":1,"notes":{"0":{"x":12,"y":7,"content":"Old","color":"White"}},"render_order":[0],"connections":[]}"#,
+    )
+    .unwrap();
+
+    let mut app = create_test_app_with_start_state();
+    load_map_file_with_fs(&mut app, &file_path, &MockFileSystem::new());
+    let Screen::Map(loaded) = &app.screen else {
+        panic!("Expected map screen");
+    };
+
+    let note = &loaded.notes_state.notes()[&0];
+    assert_eq!((note.x, note.y), (12, 7));
+    assert_eq!(
+        (loaded.viewport.view_pos.x, loaded.viewport.view_pos.y),
+        (3, 4)
+    );
+}
+
+#[test]
+fn test_negative_positions_roundtrip() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("negative.json");
+
+    let mut map_state = create_map_state_using_mock_filesystem(file_path.clone());
+    map_state
+        .notes_state
+        .add(-40, -12, String::from("Up and left"), Color::White);
+    map_state
+        .regions_state
+        .add(Region::new(-50, -20, String::from("West"), Color::Blue));
+    map_state.viewport.view_pos.x = -60;
+    map_state.viewport.view_pos.y = -25;
+    save_map_file(&mut map_state, &file_path).unwrap();
+
+    let mut app = create_test_app_with_start_state();
+    load_map_file_with_fs(&mut app, &file_path, &MockFileSystem::new());
+    let Screen::Map(loaded) = &app.screen else {
+        panic!("Expected map screen");
+    };
+
+    let note = &loaded.notes_state.notes()[&0];
+    assert_eq!((note.x, note.y), (-40, -12));
+    let region = &loaded.regions_state.regions()[0];
+    assert_eq!((region.x, region.y), (-50, -20));
+    assert_eq!(
+        (loaded.viewport.view_pos.x, loaded.viewport.view_pos.y),
+        (-60, -25)
+    );
+}
+
+#[test]
+fn test_view_state_roundtrip() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("view_state.json");
+
+    let mut map_state = create_populated_map_state(file_path.clone());
+    map_state.notes_state.select_many(&[1, 0]);
+    map_state.mode = Mode::Visual;
+    map_state.ui_state.search = Some(SearchState::new());
+    map_state.ui_state.search.as_mut().unwrap().push('T');
+    map_state.ui_state.show_help(3);
+    save_map_file(&mut map_state, &file_path).unwrap();
+
+    let mut app = create_test_app_with_start_state();
+    load_map_file_with_fs(&mut app, &file_path, &MockFileSystem::new());
+    let Screen::Map(loaded) = &app.screen else {
+        panic!("Expected map screen");
+    };
+
+    assert_eq!(loaded.mode, Mode::Visual);
+    assert_eq!(loaded.notes_state.selected_note_id(), Some(1));
+    assert!(loaded.notes_state.is_in_multi_selection(0));
+    let search = loaded.ui_state.search.as_ref().unwrap();
+    assert_eq!(search.query, "T");
+    assert!(!search.typing);
+    assert_eq!(loaded.ui_state.help_screen, Some(3));
+}
+
+#[test]
+fn test_view_state_skips_deleted_notes() {
+    let mut map_state = create_map_state_using_mock_filesystem(PathBuf::new());
+    // Nothing to resume: not written to the file
+    assert_eq!(map_state.view_state(), None);
+
+    map_state.restore_view_state(ViewState {
+        selection: vec![4],
+        ..ViewState::default()
+    });
+    assert_eq!(map_state.mode, Mode::Normal);
+    assert_eq!(map_state.notes_state.selected_note_id(), None);
+}
+
+#[test]
+fn test_connection_waypoints_roundtrip() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("waypoints.json");
+
+    let mut map_state = create_populated_map_state(file_path.clone());
+    let mut connection = map_state.connections_state.connections()[0].clone();
+    connection.waypoints = vec![Point { x: 30, y: 4 }, Point { x: 30, y: 40 }];
+    map_state.connections_state = ConnectionsState::from_connections(vec![connection.clone()]);
+    save_map_file(&mut map_state, &file_path).unwrap();
+
+    let loaded: MapData = read_json_data(&file_path).unwrap();
+
+    assert_eq!(loaded.connections, vec![connection]);
+}
+
+#[test]
+fn test_connection_weight_serialization() {
+    let mut connection = Connection {
+        from_id: 0,
+        from_side: Side::Right,
+        to_id: 1,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    };
+
+    // Unweighted connections are saved exactly as before
+    let json = serde_json::to_string(&connection).unwrap();
+    assert!(!json.contains("weight"));
+
+    connection.weight = Some(4);
+    let json = serde_json::to_string(&connection).unwrap();
+    let loaded: Connection = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.weight, Some(4));
+}
+
+#[test]
+fn test_save_map_file_compresses_json_gz_path() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("compressed.json.gz");
+    let mut map_state = create_populated_map_state(file_path.clone());
+
+    let _ = save_map_file(&mut map_state, &file_path);
+
+    // Written as gzip, read back transparently
+    let bytes = fs::read(&file_path).unwrap();
+    assert_eq!(&bytes[..2], &[0x1f, 0x8b]);
+    let loaded_data: MapData = read_json_data(&file_path).unwrap();
+    assert_eq!(loaded_data.notes.len(), 2);
+    assert_eq!(loaded_data.connections.len(), 1);
+}
+
+#[test]
+fn test_roundtrip_compressed_map_file() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("roundtrip.json.gz");
+    let mut map_state = create_populated_map_state(file_path.clone());
+    let _ = save_map_file(&mut map_state, &file_path);
+
+    let mut app = create_test_app_with_start_state();
+    load_map_file_with_fs(&mut app, &file_path, &MockFileSystem::new());
+    let Screen::Map(loaded) = &app.screen else {
+        panic!("Expected map screen");
+    };
+
+    assert_eq!(loaded.notes_state.notes().len(), 2);
+    assert_eq!(loaded.persistence.file_write_path, file_path);
+}
+
+#[test]
+fn test_map_name_strips_map_extensions() {
+    assert_eq!(map_name(&PathBuf::from("/maps/plans.json")), "plans");
+    assert_eq!(map_name(&PathBuf::from("/maps/plans.json.gz")), "plans");
+    assert_eq!(map_name(&PathBuf::from("/maps/v1.2.json.gz")), "v1.2");
+}
+
+#[test]
+fn test_is_compressed_map_path() {
+    assert!(is_compressed_map_path(&PathBuf::from(
+        "/maps/plans.json.gz"
+    )));
+    assert!(!is_compressed_map_path(&PathBuf::from("/maps/plans.json")));
+    assert_eq!(map_file_extension(true), "json.gz");
+    assert_eq!(map_file_extension(false), "json");
+}
+
+#[test]
+fn test_save_map_file_compact_json() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("compact.json");
+    let mut map_state = create_populated_map_state(file_path.clone());
+    map_state.settings.compact_json = true;
+
+    let _ = save_map_file(&mut map_state, &file_path);
+
+    // No indentation or line breaks, still valid map data
+    let contents = fs::read_to_string(&file_path).unwrap();
+    assert!(!contents.contains('\n'));
+    let loaded_data: MapData = read_json_data(&file_path).unwrap();
+    assert_eq!(loaded_data.notes.len(), 2);
+    assert_eq!(loaded_data.connections.len(), 1);
+}
+
+#[test]
+fn test_save_map_file_pretty_json_by_default() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("pretty.json");
+    let mut map_state = create_populated_map_state(file_path.clone());
+
+    let _ = save_map_file(&mut map_state, &file_path);
+
+    let contents = fs::read_to_string(&file_path).unwrap();
+    assert!(contents.contains("\n  \"view_pos\""));
+}
+
+#[test]
+fn test_write_json_data_buffered_reuses_buffer() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("buffered.json");
+    let mut buffer = Vec::new();
+
+    write_json_data_buffered(&file_path, &vec![1, 2, 3], JsonFormat::Pretty, &mut buffer).unwrap();
+    let capacity = buffer.capacity();
+    write_json_data_buffered(&file_path, &vec![4], JsonFormat::Compact, &mut buffer).unwrap();
+
+    // Only the last write's data, in the same allocation
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "[4]");
+    assert_eq!(buffer, b"[4]");
+    assert_eq!(buffer.capacity(), capacity);
+}
+
+/// Saves the populated sample map encrypted with the passphrase
+fn create_encrypted_map_file(file_path: &Path, passphrase: &str) {
+    let mut map_state = create_populated_map_state(file_path.to_path_buf());
+    map_state.persistence.file_key = Some(FileKey::new(passphrase));
+    save_map_file(&mut map_state, file_path).unwrap();
+}
+
+#[test]
+fn test_save_map_file_encrypted_hides_contents() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("journal.json");
+
+    create_encrypted_map_file(&file_path, "secret");
+
+    let contents = fs::read_to_string(&file_path).unwrap();
+    assert!(!contents.contains("Test Note"));
+    let Ok(MapFileContents::Encrypted(encrypted)) = read_map_file(&file_path) else {
+        panic!("Expected an encrypted map file");
+    };
+    let (map_data, _) = unlock_map_data(&encrypted, "secret").unwrap();
+    assert_eq!(map_data.notes.len(), 2);
+    assert_eq!(map_data.connections.len(), 1);
+}
+
+#[test]
+fn test_load_encrypted_map_file_asks_for_passphrase() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("journal.json");
+    create_encrypted_map_file(&file_path, "secret");
+
+    let mut app = create_test_app_with_start_state();
+    load_map_file_with_fs(&mut app, &file_path, &MockFileSystem::new());
+
+    let Screen::Start(start_state) = &app.screen else {
+        panic!("Expected to stay on the start screen");
+    };
+    let prompt = start_state.passphrase_prompt.as_ref().unwrap();
+    assert_eq!(prompt.path, file_path);
+    assert_eq!(prompt.error, None);
+    assert_eq!(start_state.display_err_msg, None);
+}
+
+#[test]
+fn test_unlock_map_file_with_wrong_passphrase_asks_again() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("journal.json");
+    create_encrypted_map_file(&file_path, "secret");
+
+    let mut app = create_test_app_with_start_state();
+    unlock_map_file_with_fs(&mut app, &file_path, "guess", &MockFileSystem::new());
+
+    let Screen::Start(start_state) = &app.screen else {
+        panic!("Expected to stay on the start screen");
+    };
+    assert_eq!(
+        start_state.passphrase_prompt.as_ref().unwrap().error,
+        Some(CryptoError::WrongPassphrase)
+    );
+}
+
+#[test]
+fn test_unlock_map_file_and_save_keeps_it_encrypted() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("journal.json");
+    create_encrypted_map_file(&file_path, "secret");
+
+    let mut app = create_test_app_with_start_state();
+    unlock_map_file_with_fs(&mut app, &file_path, "secret", &MockFileSystem::new());
+    let Screen::Map(loaded) = &mut app.screen else {
+        panic!("Expected map screen");
+    };
+    assert_eq!(loaded.notes_state.notes().len(), 2);
+    assert!(loaded.persistence.file_key.is_some());
+
+    save_map_file(loaded, &file_path).unwrap();
+
+    // Still opens with the same passphrase
+    let Ok(MapFileContents::Encrypted(encrypted)) = read_map_file(&file_path) else {
+        panic!("Expected an encrypted map file");
+    };
+    assert!(unlock_map_data(&encrypted, "secret").is_ok());
+}
+
+#[test]
+fn test_preview_of_encrypted_map_file() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("journal.json");
+    create_encrypted_map_file(&file_path, "secret");
+
+    let preview = MapPreview::load(&file_path).unwrap();
+
+    assert!(preview.encrypted);
+    assert_eq!(preview.title, "journal");
+    assert_eq!(preview.note_count, 0);
+}
+
+#[test]
+fn test_load_encrypted_map_file_from_settings_asks_on_start_screen() {
+    let temp_dir = tempdir().unwrap();
+    let file_path = temp_dir.path().join("journal.json");
+    create_encrypted_map_file(&file_path, "secret");
+    let mock_fs = MockFileSystem::new();
+
+    let mut app = App {
+        running: true,
+        screen: Screen::Settings(SettingsState::new_with_fs(file_path.clone(), &mock_fs)),
+        workspace: None,
+    };
+    load_map_file_with_fs(&mut app, &file_path, &mock_fs);
+
+    let Screen::Start(start_state) = &app.screen else {
+        panic!("Expected the start screen");
+    };
+    assert_eq!(
+        start_state.passphrase_prompt.as_ref().unwrap().path,
+        file_path
+    );
 }
@@ -0,0 +1,57 @@
+use crate::utils::{MarkdownStyle, markdown_spans};
+
+/// The text of each span with its style, easier to read than byte ranges.
+fn spans(content: &str) -> Vec<(&str, MarkdownStyle)> {
+    markdown_spans(content)
+        .into_iter()
+        .map(|(range, style)| (&content[range], style))
+        .collect()
+}
+
+#[test]
+fn test_markdown_headings_and_lists() {
+    assert_eq!(
+        spans("# Plan\n##Not a heading\n- milk\n  * eggs\n12. flour\n-not a list"),
+        vec![
+            ("# Plan", MarkdownStyle::Heading),
+            ("-", MarkdownStyle::ListMarker),
+            ("*", MarkdownStyle::ListMarker),
+            ("12.", MarkdownStyle::ListMarker),
+        ]
+    );
+    assert_eq!(spans("#######\n"), vec![]);
+}
+
+#[test]
+fn test_markdown_inline_code_and_links() {
+    assert_eq!(
+        spans("run `cargo test` and see [the docs](https://example.com)."),
+        vec![
+            ("`cargo test`", MarkdownStyle::Code),
+            ("[the docs](https://example.com)", MarkdownStyle::Link),
+        ]
+    );
+    // Links inside code aren't links, unclosed spans stay plain
+    assert_eq!(
+        spans("- `[a](b)` `open [half](done"),
+        vec![
+            ("-", MarkdownStyle::ListMarker),
+            ("`[a](b)`", MarkdownStyle::Code),
+        ]
+    );
+    // A checkbox isn't a link
+    assert_eq!(spans("[ ] todo (later)"), vec![]);
+}
+
+#[test]
+fn test_markdown_fenced_code() {
+    assert_eq!(
+        spans("```\n# not a heading\n\n```\n# Heading"),
+        vec![
+            ("```", MarkdownStyle::Code),
+            ("# not a heading", MarkdownStyle::Code),
+            ("```", MarkdownStyle::Code),
+            ("# Heading", MarkdownStyle::Heading),
+        ]
+    );
+}
@@ -0,0 +1,385 @@
+use ratatui::style::Color;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::{
+    states::map::{Connection, Note, NoteStatus, Side, ViewPos},
+    utils::{ConflictKind, MapData, MapMerge, MergeChoice, encrypt},
+};
+
+fn create_connection(from_id: usize, to_id: usize) -> Connection {
+    Connection {
+        from_id,
+        from_side: Side::Right,
+        to_id,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    }
+}
+
+fn note(content: &str) -> Note {
+    Note::new(0, 0, String::from(content), Color::White)
+}
+
+fn map(notes: Vec<(usize, Note)>, connections: Vec<Connection>) -> MapData {
+    let mut render_order: Vec<usize> = notes.iter().map(|(id, _)| *id).collect();
+    render_order.sort_unstable();
+    MapData {
+        view_pos: ViewPos { x: 0, y: 0 },
+        next_note_id_counter: render_order.last().map_or(0, |id| id + 1),
+        notes: notes.into_iter().collect(),
+        render_order,
+        connections,
+        regions: vec![],
+        hyperedges: vec![],
+        layers: vec![],
+        view_state: None,
+        trash: vec![],
+    }
+}
+
+fn contents(map: &MapData) -> HashMap<usize, &str> {
+    map.notes
+        .iter()
+        .map(|(id, note)| (*id, note.content.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_merge_takes_changes_from_both_sides() {
+    let base = map(vec![(0, note("A")), (1, note("B")), (2, note("C"))], vec![]);
+    let mut moved = note("B");
+    moved.x = 20;
+    let mine = map(
+        vec![(0, note("A mine")), (1, note("B")), (2, note("C"))],
+        vec![],
+    );
+    let theirs = map(vec![(0, note("A")), (1, moved), (2, note("C"))], vec![]);
+
+    let merge = MapMerge::new(&base, &mine, &theirs, &HashSet::new());
+    assert!(merge.conflicts.is_empty());
+
+    let merged = merge.into_map_data();
+    assert_eq!(merged.notes[&0].content, "A mine");
+    assert_eq!(merged.notes[&1].x, 20);
+    assert_eq!(merged.notes.len(), 3);
+}
+
+#[test]
+fn test_merge_separates_text_position_and_color() {
+    let base = map(vec![(0, note("A"))], vec![]);
+    let mut mine_note = note("A mine");
+    mine_note.x = 5;
+    let mut their_note = note("A");
+    their_note.x = 9;
+    their_note.color = Color::Red;
+    let mine = map(vec![(0, mine_note)], vec![]);
+    let theirs = map(vec![(0, their_note)], vec![]);
+
+    let merge = MapMerge::new(&base, &mine, &theirs, &HashSet::new());
+    let kinds: Vec<ConflictKind> = merge.conflicts.iter().map(|c| c.kind).collect();
+    assert_eq!(kinds, vec![ConflictKind::Position]);
+
+    // Mine wins the conflict by default, the rest is merged
+    let merged = merge.into_map_data();
+    assert_eq!(merged.notes[&0].content, "A mine");
+    assert_eq!(merged.notes[&0].x, 5);
+    assert_eq!(merged.notes[&0].color, Color::Red);
+}
+
+#[test]
+fn test_merge_conflict_choices() {
+    let base = map(vec![(0, note("A")), (1, note("B"))], vec![]);
+    let mine = map(vec![(0, note("A mine")), (1, note("B"))], vec![]);
+    let theirs = map(vec![(0, note("A theirs")), (1, note("B"))], vec![]);
+
+    let mut merge = MapMerge::new(&base, &mine, &theirs, &HashSet::new());
+    assert_eq!(merge.conflicts.len(), 1);
+    assert_eq!(merge.conflicts[0].kind, ConflictKind::Content);
+    assert_eq!(merge.conflicts[0].note_id, 0);
+
+    merge.conflicts[0].choice = MergeChoice::Theirs;
+    assert_eq!(merge.into_map_data().notes[&0].content, "A theirs");
+}
+
+#[test]
+fn test_merge_title_goes_with_text() {
+    let base = map(vec![(0, note("A")), (1, note("B"))], vec![]);
+    let mut titled = note("A");
+    titled.set_title("Title");
+    let mut mine_b = note("B mine");
+    mine_b.set_title("Mine");
+    let mut their_b = note("B");
+    their_b.set_title("Theirs");
+    let mine = map(vec![(0, note("A")), (1, mine_b)], vec![]);
+    let theirs = map(vec![(0, titled), (1, their_b)], vec![]);
+
+    let mut merge = MapMerge::new(&base, &mine, &theirs, &HashSet::new());
+    assert_eq!(merge.conflicts.len(), 1);
+    assert_eq!(merge.conflicts[0].kind, ConflictKind::Content);
+    assert_eq!(merge.conflicts[0].note_id, 1);
+
+    merge.conflicts[0].choice = MergeChoice::Theirs;
+    let merged = merge.into_map_data();
+    assert_eq!(merged.notes[&0].title.as_deref(), Some("Title"));
+    assert_eq!(merged.notes[&1].title.as_deref(), Some("Theirs"));
+    assert_eq!(merged.notes[&1].content, "B");
+}
+
+/// An encrypted note as stored in the map file, and as it is while unlocked with
+/// `plaintext` in its content.
+fn encrypted_note(plaintext: &str) -> (Note, Note) {
+    let mut locked = note("");
+    locked.encrypted = Some(encrypt(plaintext, "passphrase"));
+    let unlocked = Note {
+        content: String::from(plaintext),
+        ..locked.clone()
+    };
+    (locked, unlocked)
+}
+
+#[test]
+fn test_merge_keeps_edit_of_unlocked_note() {
+    let (base_a, _) = encrypted_note("A");
+    let (base_b, _) = encrypted_note("B");
+    let base = map(vec![(0, base_a.clone()), (1, base_b.clone())], vec![]);
+    let mine_a = Note {
+        content: String::from("A mine"),
+        ..base_a.clone()
+    };
+    let mine_b = Note {
+        content: String::from("B mine"),
+        ..base_b
+    };
+    let mut moved_a = base_a;
+    moved_a.x = 20;
+    let (their_b, _) = encrypted_note("B theirs");
+    let mine = map(vec![(0, mine_a), (1, mine_b)], vec![]);
+    let theirs = map(vec![(0, moved_a), (1, their_b)], vec![]);
+
+    let merge = MapMerge::new(&base, &mine, &theirs, &HashSet::from([0, 1]));
+    // Only both versions changing the text is a conflict
+    assert_eq!(merge.conflicts.len(), 1);
+    assert_eq!(merge.conflicts[0].kind, ConflictKind::Content);
+    assert_eq!(merge.conflicts[0].note_id, 1);
+
+    let merged = merge.into_map_data();
+    assert_eq!(merged.notes[&0].content, "A mine");
+    assert_eq!(merged.notes[&0].x, 20);
+    assert_eq!(merged.notes[&1].content, "B mine");
+}
+
+#[test]
+fn test_merge_unlocked_note_deleted_in_theirs() {
+    let (base_a, unlocked_a) = encrypted_note("A");
+    let (base_b, _) = encrypted_note("B");
+    let base = map(vec![(0, base_a), (1, base_b.clone())], vec![]);
+    let mine_b = Note {
+        content: String::from("B mine"),
+        ..base_b
+    };
+    let mine = map(vec![(0, unlocked_a), (1, mine_b)], vec![]);
+    let theirs = map(vec![], vec![]);
+
+    // Unlocking a note isn't a change, editing it is
+    let merge = MapMerge::new(&base, &mine, &theirs, &HashSet::from([1]));
+    assert_eq!(merge.conflicts.len(), 1);
+    assert_eq!(merge.conflicts[0].kind, ConflictKind::DeletedTheirs);
+    assert_eq!(merge.conflicts[0].note_id, 1);
+    assert_eq!(
+        contents(&merge.into_map_data()),
+        HashMap::from([(1, "B mine")])
+    );
+}
+
+#[test]
+fn test_merge_status_keeps_mine_on_conflict() {
+    let base = map(vec![(0, note("A")), (1, note("B"))], vec![]);
+    let mut their_a = note("A");
+    their_a.status = Some(NoteStatus::Done);
+    let mut mine_b = note("B");
+    mine_b.status = Some(NoteStatus::Doing);
+    let mut their_b = note("B");
+    their_b.status = Some(NoteStatus::Blocked);
+    let mine = map(vec![(0, note("A")), (1, mine_b)], vec![]);
+    let theirs = map(vec![(0, their_a), (1, their_b)], vec![]);
+
+    let merge = MapMerge::new(&base, &mine, &theirs, &HashSet::new());
+    assert!(merge.conflicts.is_empty());
+    let merged = merge.into_map_data();
+    assert_eq!(merged.notes[&0].status, Some(NoteStatus::Done));
+    assert_eq!(merged.notes[&1].status, Some(NoteStatus::Doing));
+}
+
+#[test]
+fn test_merge_deletions() {
+    let base = map(
+        vec![
+            (0, note("A")),
+            (1, note("B")),
+            (2, note("C")),
+            (3, note("D")),
+        ],
+        vec![],
+    );
+    // Mine deletes 0 (unchanged in theirs) and 2 (changed in theirs)
+    // Theirs deletes 1 (unchanged in mine) and 3 (changed in mine)
+    let mine = map(vec![(1, note("B")), (3, note("D mine"))], vec![]);
+    let theirs = map(vec![(0, note("A")), (2, note("C theirs"))], vec![]);
+
+    let merge = MapMerge::new(&base, &mine, &theirs, &HashSet::new());
+    let kinds: Vec<(usize, ConflictKind)> = merge
+        .conflicts
+        .iter()
+        .map(|c| (c.note_id, c.kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (2, ConflictKind::DeletedMine),
+            (3, ConflictKind::DeletedTheirs)
+        ]
+    );
+
+    let mut restored = MapMerge::new(&base, &mine, &theirs, &HashSet::new());
+    for conflict in restored.conflicts.iter_mut() {
+        conflict.choice = MergeChoice::Theirs;
+    }
+
+    // Mine keeps the deletion and the changed note
+    assert_eq!(
+        contents(&merge.into_map_data()),
+        HashMap::from([(3, "D mine")])
+    );
+    // Theirs restores the changed note and deletes the other
+    assert_eq!(
+        contents(&restored.into_map_data()),
+        HashMap::from([(2, "C theirs")])
+    );
+}
+
+#[test]
+fn test_merge_keeps_notes_added_on_both_sides() {
+    let base = map(vec![(0, note("A"))], vec![]);
+    let mine = map(
+        vec![(0, note("A")), (1, note("Mine"))],
+        vec![create_connection(0, 1)],
+    );
+    let theirs = map(
+        vec![
+            (0, note("A")),
+            (1, note("Theirs")),
+            (2, note("Also theirs")),
+        ],
+        vec![create_connection(0, 1), create_connection(1, 2)],
+    );
+
+    let merge = MapMerge::new(&base, &mine, &theirs, &HashSet::new());
+    assert!(merge.conflicts.is_empty());
+    let merged = merge.into_map_data();
+
+    // Their note 1 moved to a new id, together with its connections
+    assert_eq!(
+        contents(&merged),
+        HashMap::from([(0, "A"), (1, "Mine"), (2, "Also theirs"), (3, "Theirs")])
+    );
+    assert_eq!(
+        merged.connections,
+        vec![
+            create_connection(0, 1),
+            create_connection(0, 3),
+            create_connection(3, 2)
+        ]
+    );
+    assert_eq!(merged.render_order, vec![0, 1, 3, 2]);
+    assert_eq!(merged.next_note_id_counter, 4);
+}
+
+#[test]
+fn test_merge_keeps_uuids_given_on_either_side() {
+    let base = map(vec![(0, note("A")), (1, note("B"))], vec![]);
+    let mut mine_note = note("A");
+    mine_note.uuid = Some(Uuid::new_v4());
+    let mut their_note = note("B changed");
+    their_note.uuid = Some(Uuid::new_v4());
+    let mine = map(vec![(0, mine_note.clone()), (1, note("B"))], vec![]);
+    let theirs = map(vec![(0, note("A")), (1, their_note.clone())], vec![]);
+
+    let merged = MapMerge::new(&base, &mine, &theirs, &HashSet::new()).into_map_data();
+
+    assert_eq!(merged.notes[&0].uuid, mine_note.uuid);
+    assert_eq!(merged.notes[&1].uuid, their_note.uuid);
+    assert_eq!(merged.notes[&1].content, "B changed");
+}
+
+#[test]
+fn test_merge_keeps_same_notes_with_different_uuids_apart() {
+    let base = map(vec![], vec![]);
+    let mut mine_note = note("Same text");
+    mine_note.uuid = Some(Uuid::new_v4());
+    let mut their_note = note("Same text");
+    their_note.uuid = Some(Uuid::new_v4());
+
+    let merged = MapMerge::new(
+        &base,
+        &map(vec![(0, mine_note)], vec![]),
+        &map(vec![(0, their_note)], vec![]),
+        &HashSet::new(),
+    )
+    .into_map_data();
+
+    // Added separately, so both are kept even though they read the same
+    assert_eq!(merged.notes.len(), 2);
+}
+
+#[test]
+fn test_merge_connections_as_sets() {
+    let notes = vec![(0, note("A")), (1, note("B")), (2, note("C"))];
+    let base = map(
+        notes.clone(),
+        vec![create_connection(0, 1), create_connection(1, 2)],
+    );
+    // Mine removes 0 -> 1 and adds 2 -> 0, theirs adds 0 -> 2
+    let mine = map(
+        notes.clone(),
+        vec![create_connection(1, 2), create_connection(2, 0)],
+    );
+    let theirs = map(
+        notes,
+        vec![
+            create_connection(0, 1),
+            create_connection(1, 2),
+            create_connection(0, 2),
+        ],
+    );
+
+    let merged = MapMerge::new(&base, &mine, &theirs, &HashSet::new()).into_map_data();
+    assert_eq!(
+        merged.connections,
+        vec![
+            create_connection(1, 2),
+            create_connection(2, 0),
+            create_connection(0, 2)
+        ]
+    );
+}
+
+#[test]
+fn test_merge_drops_connections_to_deleted_notes() {
+    let base = map(vec![(0, note("A")), (1, note("B"))], vec![]);
+    // Theirs connects to a note mine deleted
+    let mine = map(vec![(0, note("A"))], vec![]);
+    let theirs = map(
+        vec![(0, note("A")), (1, note("B"))],
+        vec![create_connection(0, 1)],
+    );
+
+    let merged = MapMerge::new(&base, &mine, &theirs, &HashSet::new()).into_map_data();
+    assert_eq!(contents(&merged), HashMap::from([(0, "A")]));
+    assert!(merged.connections.is_empty());
+    // Deleted ids aren't handed out again
+    assert_eq!(merged.next_note_id_counter, 2);
+}
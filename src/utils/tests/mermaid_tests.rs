@@ -0,0 +1,67 @@
+use ratatui::style::Color;
+use std::collections::HashMap;
+
+use crate::{
+    states::map::{Connection, Note, Side},
+    utils::{EncryptedText, export_mermaid, mermaid_label},
+};
+
+fn create_connection(from_id: usize, to_id: usize, weight: Option<u32>) -> Connection {
+    Connection {
+        from_id,
+        from_side: Side::Right,
+        to_id,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight,
+        layer: None,
+    }
+}
+
+#[test]
+fn test_export_mermaid_flowchart() {
+    let notes = HashMap::from([
+        (1, Note::new(0, 0, String::from("Build"), Color::White)),
+        (0, Note::new(0, 0, String::from("Plan"), Color::White)),
+        (2, Note::new(0, 0, String::from("Ship"), Color::White)),
+    ]);
+    let connections = vec![
+        create_connection(0, 1, None),
+        create_connection(1, 2, Some(3)),
+        // Dangling connections are left out
+        create_connection(2, 7, None),
+    ];
+
+    assert_eq!(
+        export_mermaid(&notes, &connections),
+        "flowchart TD\n    n0[\"Plan\"]\n    n1[\"Build\"]\n    n2[\"Ship\"]\n    n0 --> n1\n    n1 -->|3| n2\n"
+    );
+}
+
+#[test]
+fn test_mermaid_label_sanitizes_content() {
+    assert_eq!(
+        mermaid_label("Say \"hi\" <now>\n#tag `x`"),
+        "Say #quot;hi#quot; #lt;now#gt;<br>#35;tag #96;x#96;"
+    );
+    assert_eq!(mermaid_label(""), " ");
+    assert_eq!(mermaid_label("a\tb"), "ab");
+}
+
+#[test]
+fn test_export_mermaid_hides_encrypted_notes() {
+    let mut note = Note::new(0, 0, String::from("secret plan"), Color::White);
+    note.encrypted = Some(EncryptedText {
+        salt: String::new(),
+        rounds: 1,
+        nonce: String::new(),
+        ciphertext: String::new(),
+    });
+    let notes = HashMap::from([(0, note)]);
+
+    let document = export_mermaid(&notes, &[]);
+
+    assert!(document.contains("n0[\"[encrypted]\"]"));
+    assert!(!document.contains("secret"));
+}
@@ -0,0 +1,85 @@
+use chrono::{Local, TimeZone};
+use ratatui::style::Color;
+use std::{fs, path::PathBuf};
+use tempfile::tempdir;
+
+use crate::{
+    states::MapState,
+    utils::{
+        MapData, Milestone, add_milestone, milestones_path, read_milestones,
+        test_utils::MockFileSystem, write_milestones,
+    },
+};
+
+fn create_milestone(name: &str, note_text: &str) -> Milestone {
+    let mut map_state =
+        MapState::new_with_fs(PathBuf::from("/test/map.json"), &MockFileSystem::new());
+    map_state
+        .notes_state
+        .add(10, 20, String::from(note_text), Color::White);
+
+    Milestone {
+        name: String::from(name),
+        created_at: Local.with_ymd_and_hms(2026, 3, 1, 12, 30, 0).unwrap(),
+        map: MapData::from_map_state(&mut map_state),
+    }
+}
+
+#[test]
+fn test_milestones_path_is_next_to_map_file() {
+    assert_eq!(
+        milestones_path(&PathBuf::from("/maps/ideas.json")),
+        PathBuf::from("/maps/ideas.json.milestones.gz")
+    );
+}
+
+#[test]
+fn test_map_without_milestones_file_has_none() {
+    let temp_dir = tempdir().unwrap();
+
+    let milestones = read_milestones(&temp_dir.path().join("map.json")).unwrap();
+
+    assert!(milestones.is_empty());
+}
+
+#[test]
+fn test_write_and_read_milestones() {
+    let temp_dir = tempdir().unwrap();
+    let map_path = temp_dir.path().join("map.json");
+    let milestones = vec![
+        create_milestone("Draft", "first"),
+        create_milestone("Review", "second"),
+    ];
+
+    write_milestones(&map_path, &milestones).unwrap();
+
+    assert_eq!(read_milestones(&map_path).unwrap(), milestones);
+    // Stored gzip-compressed
+    let bytes = fs::read(milestones_path(&map_path)).unwrap();
+    assert_eq!(&bytes[..2], &[0x1f, 0x8b]);
+}
+
+#[test]
+fn test_add_milestone_appends() {
+    let temp_dir = tempdir().unwrap();
+    let map_path = temp_dir.path().join("map.json");
+
+    add_milestone(&map_path, create_milestone("Draft", "first")).unwrap();
+    add_milestone(&map_path, create_milestone("Review", "second")).unwrap();
+
+    let names: Vec<String> = read_milestones(&map_path)
+        .unwrap()
+        .into_iter()
+        .map(|milestone| milestone.name)
+        .collect();
+    assert_eq!(names, vec!["Draft", "Review"]);
+}
+
+#[test]
+fn test_read_damaged_milestones_file_fails() {
+    let temp_dir = tempdir().unwrap();
+    let map_path = temp_dir.path().join("map.json");
+    fs::write(milestones_path(&map_path), "not gzip").unwrap();
+
+    assert!(read_milestones(&map_path).is_err());
+}
@@ -1,5 +1,33 @@
 mod backups_tests;
+mod checklist_tests;
+mod checksum_tests;
 mod colors_tests;
+mod crypto_tests;
+mod dot_tests;
+mod dsl_tests;
+mod emergency_tests;
+mod error_tests;
 mod geometry_tests;
+mod html_tests;
+mod i18n_tests;
+mod invariants_tests;
+mod map_diff_tests;
 mod map_files_tests;
+mod markdown_tests;
+mod merge_tests;
+mod mermaid_tests;
+mod milestones_tests;
+mod note_list_tests;
+mod outline_tests;
+mod plugins_tests;
+mod recovery_tests;
+mod schema_tests;
 mod settings_tests;
+mod snapshots_tests;
+mod spelling_tests;
+mod status_bar_tests;
+mod substitute_tests;
+mod svg_tests;
+mod undo_file_tests;
+mod user_commands_tests;
+mod workspace_tests;
@@ -0,0 +1,109 @@
+use ratatui::style::Color;
+
+use crate::utils::{ListedNote, NoteListError, NoteListFormat, grid_layout, parse_note_list};
+
+fn contents(notes: &[ListedNote]) -> Vec<&str> {
+    notes.iter().map(|note| note.content.as_str()).collect()
+}
+
+#[test]
+fn test_parse_lines_skips_blank_ones() {
+    let notes = parse_note_list("Buy milk\n\n  \nCall Sam  \r\n", NoteListFormat::Lines).unwrap();
+
+    assert_eq!(contents(&notes), vec!["Buy milk", "Call Sam"]);
+    assert!(notes.iter().all(|note| note.position.is_none()));
+    assert!(notes.iter().all(|note| note.color == Color::White));
+}
+
+#[test]
+fn test_parse_paragraphs() {
+    let text = "First idea\nwith details\n\n\nSecond idea\n  \nThird";
+    let notes = parse_note_list(text, NoteListFormat::Paragraphs).unwrap();
+
+    assert_eq!(
+        contents(&notes),
+        vec!["First idea\nwith details", "Second idea", "Third"]
+    );
+}
+
+#[test]
+fn test_parse_csv_with_header_positions_and_colors() {
+    let text = "content,x,y,color\n\
+                Plain\n\
+                Placed,10,20\n\
+                Colored,,,red\n\
+                \"Quoted, with \"\"quotes\"\"\nand a line break\",5,6,Blue\n";
+    let notes = parse_note_list(text, NoteListFormat::Csv).unwrap();
+
+    assert_eq!(
+        notes,
+        vec![
+            ListedNote {
+                content: String::from("Plain"),
+                position: None,
+                color: Color::White,
+            },
+            ListedNote {
+                content: String::from("Placed"),
+                position: Some((10, 20)),
+                color: Color::White,
+            },
+            ListedNote {
+                content: String::from("Colored"),
+                position: None,
+                color: Color::Red,
+            },
+            ListedNote {
+                content: String::from("Quoted, with \"quotes\"\nand a line break"),
+                position: Some((5, 6)),
+                color: Color::Blue,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_csv_reports_invalid_rows_by_line() {
+    let text = "Fine\n\"Two\nlines\",1,2\nBad,1\n";
+    assert_eq!(
+        parse_note_list(text, NoteListFormat::Csv),
+        Err(NoteListError::Csv(4))
+    );
+    assert_eq!(
+        parse_note_list("A,x,y", NoteListFormat::Csv),
+        Err(NoteListError::Csv(1))
+    );
+    assert_eq!(
+        parse_note_list("A,1,2,red,extra", NoteListFormat::Csv),
+        Err(NoteListError::Csv(1))
+    );
+    assert_eq!(
+        parse_note_list("Ok\n\"never closed", NoteListFormat::Csv),
+        Err(NoteListError::Csv(2))
+    );
+}
+
+#[test]
+fn test_parse_empty_list() {
+    for format in [
+        NoteListFormat::Lines,
+        NoteListFormat::Paragraphs,
+        NoteListFormat::Csv,
+    ] {
+        assert_eq!(parse_note_list("\n  \n", format), Err(NoteListError::Empty));
+    }
+    assert_eq!(
+        parse_note_list("content,x,y,color\n", NoteListFormat::Csv),
+        Err(NoteListError::Empty)
+    );
+}
+
+#[test]
+fn test_grid_layout_sizes_columns_and_rows() {
+    // Five notes make a 3 column grid
+    let sizes = [(10, 3), (20, 3), (5, 5), (8, 3), (12, 4)];
+    let positions = grid_layout(&sizes);
+
+    assert_eq!(positions, vec![(0, 0), (14, 0), (38, 0), (0, 7), (14, 7)]);
+    assert!(grid_layout(&[]).is_empty());
+}
@@ -0,0 +1,191 @@
+use ratatui::style::Color;
+use std::collections::HashMap;
+
+use crate::{
+    states::map::{Connection, Note, Side},
+    utils::{
+        DslGraph, OutlineEntry, OutlineError, export_freemind, export_opml, outline_tree,
+        parse_outline, xml_attribute,
+    },
+};
+
+fn create_connection(from_id: usize, to_id: usize) -> Connection {
+    Connection {
+        from_id,
+        from_side: Side::Right,
+        to_id,
+        to_side: Side::Left,
+        color: Color::White,
+        waypoints: vec![],
+        weight: None,
+        layer: None,
+    }
+}
+
+fn create_notes(labels: &[&str]) -> HashMap<usize, Note> {
+    labels
+        .iter()
+        .enumerate()
+        .map(|(id, label)| (id, Note::new(0, 0, label.to_string(), Color::White)))
+        .collect()
+}
+
+fn leaf(note_id: usize) -> OutlineEntry {
+    OutlineEntry {
+        note_id,
+        children: vec![],
+    }
+}
+
+#[test]
+fn test_parse_opml_outline() {
+    let opml = r#"<?xml version="1.0"?>
+<opml version="2.0">
+  <head><title>Plan</title></head>
+  <body>
+    <outline text="Goals">
+      <outline text="Ship &amp; celebrate"/>
+      <outline title="Rest"/>
+    </outline>
+    <outline text="Goals"/>
+  </body>
+</opml>"#;
+
+    assert_eq!(
+        parse_outline(opml),
+        Ok(DslGraph {
+            labels: vec![
+                String::from("Goals"),
+                String::from("Ship & celebrate"),
+                String::from("Rest"),
+                // Same text, separate entry
+                String::from("Goals"),
+            ],
+            edges: vec![(0, 1), (0, 2)],
+        })
+    );
+}
+
+#[test]
+fn test_parse_freemind_map() {
+    let mm = r#"<map version="1.0.1">
+  <node TEXT="Root">
+    <node TEXT="Left"><node TEXT="Leaf"/></node>
+    <node TEXT="Right"/>
+  </node>
+</map>"#;
+
+    let graph = parse_outline(mm).unwrap();
+
+    assert_eq!(graph.labels, vec!["Root", "Left", "Leaf", "Right"]);
+    assert_eq!(graph.edges, vec![(0, 1), (1, 2), (0, 3)]);
+    // Laid out as a tree: children one layer to the right of their parent
+    let layout = graph.layout();
+    assert!(layout[0].0 < layout[1].0 && layout[1].0 < layout[2].0);
+    assert_eq!(layout[1].0, layout[3].0);
+}
+
+#[test]
+fn test_parse_outline_errors() {
+    assert!(matches!(parse_outline("<opml"), Err(OutlineError::Xml(_))));
+    assert_eq!(
+        parse_outline("<html><body/></html>"),
+        Err(OutlineError::UnknownFormat)
+    );
+    assert_eq!(
+        parse_outline("<opml><body></body></opml>"),
+        Err(OutlineError::Empty)
+    );
+}
+
+#[test]
+fn test_outline_tree_from_map() {
+    // 0 -> 1 -> 2, 0 -> 2 (cross link), 3 <-> 4 (cycle without a root), 5 alone
+    let notes = create_notes(&["a", "b", "c", "d", "e", "f"]);
+    let connections = vec![
+        create_connection(0, 1),
+        create_connection(1, 2),
+        create_connection(0, 2),
+        create_connection(3, 4),
+        create_connection(4, 3),
+    ];
+
+    assert_eq!(
+        outline_tree(&notes, &connections),
+        vec![
+            OutlineEntry {
+                note_id: 0,
+                children: vec![OutlineEntry {
+                    note_id: 1,
+                    children: vec![leaf(2)],
+                }],
+            },
+            leaf(5),
+            OutlineEntry {
+                note_id: 3,
+                children: vec![leaf(4)],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_export_opml_and_freemind() {
+    let notes = create_notes(&["Root", "Say \"hi\"\nthen <go>"]);
+    let connections = vec![create_connection(0, 1)];
+
+    assert_eq!(
+        export_opml("plan", &notes, &connections),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n  <head><title>plan</title></head>\n  <body>\n    <outline text=\"Root\">\n      <outline text=\"Say &quot;hi&quot;&#10;then &lt;go&gt;\"/>\n    </outline>\n  </body>\n</opml>\n"
+    );
+    assert_eq!(
+        export_freemind("plan", &notes, &connections),
+        "<map version=\"1.0.1\">\n  <node TEXT=\"Root\">\n    <node TEXT=\"Say &quot;hi&quot;&#10;then &lt;go&gt;\"/>\n  </node>\n</map>\n"
+    );
+
+    // Exported text survives a round trip, line breaks included
+    let graph = parse_outline(&export_opml("plan", &notes, &connections)).unwrap();
+    assert_eq!(graph.labels, vec!["Root", "Say \"hi\"\nthen <go>"]);
+}
+
+#[test]
+fn test_export_outline_with_titles() {
+    let mut notes = create_notes(&["Root", "first\nsecond", ""]);
+    notes.get_mut(&1).unwrap().set_title("Steps");
+    notes.get_mut(&2).unwrap().set_title("Empty");
+    let connections = vec![create_connection(0, 1), create_connection(0, 2)];
+
+    let opml = export_opml("plan", &notes, &connections);
+    assert!(opml.contains("<outline text=\"Steps\" _note=\"first&#10;second\"/>"));
+    assert!(opml.contains("<outline text=\"Empty\"/>"));
+
+    let freemind = export_freemind("plan", &notes, &connections);
+    assert!(freemind.contains(
+        "    <node TEXT=\"Steps\">\n      <richcontent TYPE=\"NOTE\"><html><body><p>first</p><p>second</p></body></html></richcontent>\n    </node>"
+    ));
+    assert!(freemind.contains("<node TEXT=\"Empty\"/>"));
+
+    // The notes are imported by their titles
+    assert_eq!(
+        parse_outline(&freemind).unwrap().labels,
+        vec!["Root", "Steps", "Empty"]
+    );
+}
+
+#[test]
+fn test_export_freemind_adds_root_for_several_roots() {
+    let notes = create_notes(&["a", "b"]);
+
+    let document = export_freemind("plan", &notes, &[]);
+
+    assert!(document.starts_with("<map version=\"1.0.1\">\n  <node TEXT=\"plan\">\n"));
+    assert_eq!(
+        parse_outline(&document).unwrap().edges,
+        vec![(0, 1), (0, 2)]
+    );
+}
+
+#[test]
+fn test_xml_attribute() {
+    assert_eq!(xml_attribute("a & b\tc"), "a &amp; bc");
+}
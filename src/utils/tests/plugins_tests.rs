@@ -0,0 +1,136 @@
+use std::{collections::BTreeMap, fs};
+
+use tempfile::tempdir;
+
+use crate::utils::{PluginEdit, PluginMap, PluginNote, Plugins};
+
+fn plugin_map(dir: &std::path::Path) -> PluginMap {
+    let note = |content: &str| PluginNote {
+        x: 0,
+        y: 0,
+        content: content.to_string(),
+    };
+    PluginMap {
+        path: dir.join("map.json"),
+        notes: BTreeMap::from([(0, note("Plan")), (3, note("Ship"))]),
+        connections: vec![(0, 3)],
+        next_id: 4,
+        edits: vec![],
+    }
+}
+
+#[test]
+fn test_load_plugins() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("b.rhai"), "fn on_save() { }").unwrap();
+    fs::write(dir.path().join("a.rhai"), "fn on_note_created(id) { }").unwrap();
+    fs::write(dir.path().join("broken.rhai"), "fn on_save( {").unwrap();
+    fs::write(dir.path().join("notes.txt"), "not a script").unwrap();
+
+    let (plugins, errors) = Plugins::load(dir.path());
+    assert_eq!(plugins.names(), vec!["a.rhai", "b.rhai"]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].plugin, "broken.rhai");
+
+    assert!(plugins.defines("on_save", 0));
+    assert!(plugins.defines("on_note_created", 1));
+    assert!(!plugins.defines("on_note_created", 0));
+
+    // A missing directory has no plugins
+    let (plugins, errors) = Plugins::load(&dir.path().join("missing"));
+    assert!(plugins.names().is_empty());
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_plugins_edit_the_map() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("a.rhai"),
+        r#"
+        fn tidy() {
+            for note in notes() {
+                set_content(note.id, note.content.to_upper());
+            }
+            let id = add_note(10, 5, "Review");
+            connect(3, id);
+            move_note(0, 2, 2);
+        }
+        "#,
+    )
+    .unwrap();
+    // Sees the changes of the scripts before it
+    fs::write(
+        dir.path().join("b.rhai"),
+        r#"
+        fn tidy() {
+            let lines = [];
+            for c in connections() {
+                lines.push(note(c.from).content + " -> " + note(c.to).content);
+            }
+            write_file("map.txt", lines.reduce(|text, line| text + line + "\n", ""));
+        }
+        "#,
+    )
+    .unwrap();
+    let (plugins, _) = Plugins::load(dir.path());
+
+    let (map, errors) = plugins.call("tidy", &[], plugin_map(dir.path()));
+    assert!(errors.is_empty(), "{errors:?}");
+    assert_eq!(
+        map.edits,
+        vec![
+            PluginEdit::SetContent(0, String::from("PLAN")),
+            PluginEdit::SetContent(3, String::from("SHIP")),
+            PluginEdit::AddNote(
+                4,
+                PluginNote {
+                    x: 10,
+                    y: 5,
+                    content: String::from("Review")
+                }
+            ),
+            PluginEdit::Connect(3, 4),
+            PluginEdit::MoveNote(0, 2, 2),
+        ]
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("map.txt")).unwrap(),
+        "PLAN -> SHIP\nSHIP -> Review\n"
+    );
+}
+
+#[test]
+fn test_failed_plugin_keeps_no_changes() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("a.rhai"),
+        r#"
+        fn on_note_created(id) {
+            set_content(id, "Created");
+            set_content(99, "No such note");
+        }
+        "#,
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("b.rhai"),
+        "fn on_note_created(id) { loop { } }",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("c.rhai"),
+        r#"fn on_note_created(id) { set_content(id, "Kept"); }"#,
+    )
+    .unwrap();
+    let (plugins, _) = Plugins::load(dir.path());
+
+    let (map, errors) = plugins.call("on_note_created", &[3], plugin_map(dir.path()));
+    assert_eq!(
+        map.edits,
+        vec![PluginEdit::SetContent(3, String::from("Kept"))]
+    );
+    let failed: Vec<&str> = errors.iter().map(|err| err.plugin.as_str()).collect();
+    assert_eq!(failed, vec!["a.rhai", "b.rhai"]);
+    assert!(errors[0].message.contains("no note with id 99"));
+}
@@ -0,0 +1,72 @@
+use std::fs;
+use tempfile::tempdir;
+
+use crate::{
+    graph::MapBuilder,
+    utils::{TmmprError, damaged_copy_path, recover_map_data},
+};
+
+/// A map of three connected notes as JSON, for damaging.
+fn map_json() -> serde_json::Value {
+    let map_data = MapBuilder::new()
+        .note("Plan")
+        .note("Build")
+        .note("Ship")
+        .connect(0, 1)
+        .connect(1, 2)
+        .build();
+    serde_json::to_value(map_data).unwrap()
+}
+
+#[test]
+fn test_recover_map_data_keeps_what_can_be_read() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("map.json");
+    let mut map_json = map_json();
+    map_json["notes"]["1"]["x"] = "left".into();
+    map_json["connections"][1]["from_side"] = "Sideways".into();
+    map_json["regions"] = 3.into();
+    fs::write(&path, map_json.to_string()).unwrap();
+
+    let (map_data, report) = recover_map_data(&path).unwrap();
+
+    let mut ids: Vec<&usize> = map_data.notes.keys().collect();
+    ids.sort();
+    assert_eq!(ids, [&0, &2]);
+    assert_eq!(map_data.render_order, [0, 2]);
+    assert_eq!(map_data.next_note_id_counter, 3);
+    // The first connection led to the note that was left out, the second was invalid
+    assert!(map_data.connections.is_empty());
+
+    assert_eq!(report.skipped.len(), 4, "{:?}", report.skipped);
+    assert!(report.skipped[0].starts_with("notes.1: invalid type: string \"left\""));
+    assert!(
+        report
+            .skipped
+            .contains(&String::from("regions: not a list"))
+    );
+    assert!(
+        report
+            .skipped
+            .contains(&String::from("connections: 1 to notes that were left out"))
+    );
+}
+
+#[test]
+fn test_recover_map_data_needs_notes() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("map.json");
+
+    fs::write(&path, r#"{"next_note_id": 5}"#).unwrap();
+    assert!(matches!(
+        recover_map_data(&path),
+        Err(TmmprError::Validation(_))
+    ));
+    fs::write(&path, "{ this is not valid json }").unwrap();
+    assert!(matches!(recover_map_data(&path), Err(TmmprError::Parse(_))));
+
+    assert_eq!(
+        damaged_copy_path(&path),
+        dir.path().join("map.json.damaged")
+    );
+}
@@ -0,0 +1,29 @@
+use crate::utils::map_schema;
+
+/// The schema shipped with the crate
+const MAP_SCHEMA: &str = include_str!("../../../schema/map.schema.json");
+
+#[test]
+fn test_shipped_schema_is_up_to_date() {
+    assert_eq!(
+        MAP_SCHEMA,
+        map_schema(),
+        "regenerate it with `cargo run -- schema > schema/map.schema.json`"
+    );
+}
+
+#[test]
+fn test_schema_describes_map_files() {
+    let schema: serde_json::Value = serde_json::from_str(MAP_SCHEMA).unwrap();
+
+    assert_eq!(schema["title"], "tmmpr map");
+    let required = schema["required"].as_array().unwrap();
+    for field in ["view_pos", "notes", "connections"] {
+        assert!(required.iter().any(|name| name == field), "{field}");
+    }
+    // Colors are written by name
+    assert_eq!(
+        schema["$defs"]["Note"]["properties"]["color"]["type"],
+        "string"
+    );
+}
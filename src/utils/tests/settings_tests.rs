@@ -1,7 +1,16 @@
-use std::fs;
+use chrono::{Local, TimeZone};
+use std::{fs, thread};
 use tempfile::TempDir;
 
-use crate::{states::settings::Settings, utils::settings::save_settings_to_path};
+use crate::{
+    states::settings::Settings,
+    utils::{
+        read_json_data,
+        settings::{merge_settings, record_backup_date_with_fs, save_settings_to_path},
+        test_utils::TempFileSystem,
+        update_json_data,
+    },
+};
 
 #[test]
 fn test_save_settings_to_path_creates_file_with_correct_content() {
@@ -67,3 +76,89 @@ fn test_save_settings_to_path_fails_with_invalid_path() {
     // Verify the operation failed
     assert!(result.is_err());
 }
+
+#[test]
+fn test_merge_settings_keeps_backup_dates_from_disk() {
+    let earlier = Local.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+    let later = Local.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap();
+
+    let mut settings = Settings::new();
+    settings.save_interval = None;
+    settings.backup_dates.insert(String::from("ours"), earlier);
+    settings.backup_dates.insert(String::from("both"), earlier);
+
+    let mut on_disk = Settings::new();
+    on_disk.backup_dates.insert(String::from("theirs"), earlier);
+    on_disk.backup_dates.insert(String::from("both"), later);
+
+    let merged = merge_settings(&settings, Some(on_disk));
+
+    // Options come from the settings being saved
+    assert_eq!(merged.save_interval, None);
+    assert_eq!(merged.backup_dates.len(), 3);
+    assert_eq!(merged.backup_dates["both"], later);
+    assert_eq!(merge_settings(&settings, None), settings);
+}
+
+#[test]
+fn test_save_settings_keeps_other_instances_backup_dates() {
+    let temp_dir = TempDir::new().unwrap();
+    let settings_path = temp_dir.path().join("settings.json");
+    let date = Local.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+
+    let mut other_instance = Settings::new();
+    other_instance
+        .backup_dates
+        .insert(String::from("map"), date);
+    save_settings_to_path(&other_instance, &settings_path).unwrap();
+
+    save_settings_to_path(&Settings::new(), &settings_path).unwrap();
+
+    let saved: Settings = read_json_data(&settings_path).unwrap();
+    assert_eq!(saved.backup_dates.get("map"), Some(&date));
+}
+
+#[test]
+fn test_record_backup_date_keeps_other_settings() {
+    let temp_dir = TempDir::new().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let settings_path = temp_dir.path().join(".config/tmmpr/settings.json");
+    let date = Local.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+
+    // Another instance changed an option after this one loaded its settings
+    let mut changed = Settings::new();
+    changed.edit_modal = true;
+    fs::create_dir_all(settings_path.parent().unwrap()).unwrap();
+    save_settings_to_path(&changed, &settings_path).unwrap();
+
+    record_backup_date_with_fs(&Settings::new(), "map", date, &fs).unwrap();
+
+    let saved: Settings = read_json_data(&settings_path).unwrap();
+    assert!(saved.edit_modal);
+    assert_eq!(saved.backup_dates.get("map"), Some(&date));
+}
+
+#[test]
+fn test_update_json_data_serializes_concurrent_updates() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("counter.json");
+
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let path = path.clone();
+            thread::spawn(move || {
+                for _ in 0..20 {
+                    update_json_data(&path, |count: Option<usize>| count.unwrap_or(0) + 1).unwrap();
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let count: usize = read_json_data(&path).unwrap();
+    assert_eq!(count, 160);
+}
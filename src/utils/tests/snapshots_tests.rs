@@ -0,0 +1,78 @@
+use chrono::NaiveDate;
+use std::{fs, path::PathBuf};
+use tempfile::tempdir;
+
+use crate::utils::{list_snapshots, snapshot_path, snapshots_dir};
+
+#[test]
+fn test_snapshots_dir_is_next_to_map_file() {
+    assert_eq!(
+        snapshots_dir(&PathBuf::from("/maps/ideas.json")),
+        PathBuf::from("/maps/ideas.json.snapshots")
+    );
+}
+
+#[test]
+fn test_snapshot_path_has_time_and_safe_name() {
+    let created_at = NaiveDate::from_ymd_opt(2026, 3, 1)
+        .unwrap()
+        .and_hms_opt(9, 5, 30)
+        .unwrap();
+
+    assert_eq!(
+        snapshot_path(
+            &PathBuf::from("/maps/ideas.json"),
+            " before the big reorg ",
+            created_at,
+            false
+        ),
+        PathBuf::from("/maps/ideas.json.snapshots/20260301-090530_before the big reorg.json")
+    );
+    assert_eq!(
+        snapshot_path(
+            &PathBuf::from("/maps/ideas.json.gz"),
+            "v1/v2: draft",
+            created_at,
+            true
+        ),
+        PathBuf::from("/maps/ideas.json.gz.snapshots/20260301-090530_v1-v2- draft.json.gz")
+    );
+}
+
+#[test]
+fn test_list_snapshots_newest_first() {
+    let temp_dir = tempdir().unwrap();
+    let map_path = temp_dir.path().join("map.json");
+    let dir = snapshots_dir(&map_path);
+    fs::create_dir(&dir).unwrap();
+    for name in [
+        "20260301-090000_before reorg.json",
+        "20260302-180000_after_reorg.json.gz",
+        "notes.txt",
+    ] {
+        fs::write(dir.join(name), "{}").unwrap();
+    }
+
+    let snapshots = list_snapshots(&map_path).unwrap();
+
+    let names: Vec<&str> = snapshots
+        .iter()
+        .map(|snapshot| snapshot.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["after_reorg", "before reorg"]);
+    assert_eq!(
+        snapshots[1].path,
+        dir.join("20260301-090000_before reorg.json")
+    );
+}
+
+#[test]
+fn test_map_without_snapshots_dir_has_none() {
+    let temp_dir = tempdir().unwrap();
+
+    assert!(
+        list_snapshots(&temp_dir.path().join("map.json"))
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -0,0 +1,114 @@
+use std::fs;
+
+use tempfile::TempDir;
+
+use crate::utils::{Dictionary, available_dictionaries, word_at};
+
+const AFF: &str = "SET UTF-8
+TRY esianrtolcdugmphbyfvkwz
+
+PFX U Y 1
+PFX U 0 un .
+
+SFX S Y 2
+SFX S y ies [^aeiou]y
+SFX S 0 s [^y]
+
+SFX D N 1
+SFX D 0 ed .
+";
+
+const DIC: &str = "5
+map
+note/S
+story/S
+tidy/U
+Paris
+";
+
+#[test]
+fn test_dictionary_expands_affixes() {
+    let dictionary = Dictionary::from_hunspell(DIC, AFF);
+
+    for word in ["map", "note", "notes", "story", "stories", "tidy", "untidy"] {
+        assert!(dictionary.check(word), "{word}");
+    }
+    for word in ["maps", "storys", "untidyed", "paris"] {
+        assert!(!dictionary.check(word), "{word}");
+    }
+    // Capitalized and all-caps words, digits and single letters
+    assert!(dictionary.check("Notes"));
+    assert!(dictionary.check("NOTES"));
+    assert!(dictionary.check("Paris"));
+    assert!(dictionary.check("PARIS"));
+    assert!(!dictionary.check("nOtes"));
+    assert!(dictionary.check("2nd"));
+    assert!(dictionary.check("x"));
+}
+
+#[test]
+fn test_dictionary_cross_product() {
+    let aff = "PFX R Y 1\nPFX R 0 re .\nSFX D Y 1\nSFX D 0 ed .\n";
+    let dictionary = Dictionary::from_hunspell("1\nmap/RD\n", aff);
+
+    for word in ["map", "remap", "maped", "remaped"] {
+        assert!(dictionary.check(word), "{word}");
+    }
+}
+
+#[test]
+fn test_misspelled_words() {
+    let dictionary = Dictionary::from_hunspell(DIC, AFF);
+    let text = "Notes, nots and 3 storys\nof a map";
+
+    let misspelled: Vec<&str> = dictionary
+        .misspelled(text)
+        .into_iter()
+        .map(|range| &text[range])
+        .collect();
+    assert_eq!(misspelled, vec!["nots", "and", "storys", "of"]);
+}
+
+#[test]
+fn test_spelling_suggestions() {
+    let dictionary = Dictionary::from_hunspell(DIC, AFF);
+
+    assert_eq!(dictionary.suggest("ntoe"), vec!["note"]);
+    // Words two edits away only when none are one edit away
+    assert_eq!(dictionary.suggest("Storys"), vec!["Story"]);
+    assert_eq!(dictionary.suggest("NTOES"), vec!["NOTES"]);
+    assert_eq!(dictionary.suggest("untdiyy"), vec!["untidy"]);
+    // Names keep their capital
+    assert_eq!(dictionary.suggest("pari"), vec!["Paris"]);
+    assert!(dictionary.suggest("xylophone").is_empty());
+}
+
+#[test]
+fn test_word_at() {
+    let text = "one two, three";
+    assert_eq!(word_at(text, 0), Some(0..3));
+    assert_eq!(word_at(text, 3), Some(0..3));
+    assert_eq!(word_at(text, 5), Some(4..7));
+    assert_eq!(word_at(text, 8), None);
+    assert_eq!(word_at(text, 14), Some(9..14));
+}
+
+#[test]
+fn test_load_dictionary() {
+    let first = TempDir::new().unwrap();
+    let second = TempDir::new().unwrap();
+    fs::write(first.path().join("en_US.dic"), DIC).unwrap();
+    fs::write(first.path().join("en_US.aff"), AFF).unwrap();
+    // Latin-1 encoded, without an .aff file
+    fs::write(second.path().join("de_DE.dic"), b"1\nsch\xf6n\n").unwrap();
+    fs::write(second.path().join("en_US.dic"), "1\nother\n").unwrap();
+    let dirs = vec![first.path().to_path_buf(), second.path().to_path_buf()];
+
+    assert_eq!(available_dictionaries(&dirs), vec!["de_DE", "en_US"]);
+
+    let english = Dictionary::load(&dirs, "en_US").unwrap();
+    assert!(english.check("stories"));
+    assert!(!english.check("other"));
+    assert!(Dictionary::load(&dirs, "de_DE").unwrap().check("schön"));
+    assert!(Dictionary::load(&dirs, "fr_FR").is_err());
+}
@@ -0,0 +1,64 @@
+use crate::utils::{BarField, BarItem, DEFAULT_STATUS_BAR_FORMAT, StatusBarFormat};
+
+#[test]
+fn test_parse_splits_left_and_right() {
+    let format = StatusBarFormat::parse("{mode} {unsaved}|{x}, {y}  {clock}");
+
+    assert_eq!(
+        format.left,
+        vec![
+            BarItem::Field(BarField::Mode),
+            BarItem::Text(String::from(" ")),
+            BarItem::Field(BarField::Unsaved),
+        ]
+    );
+    assert_eq!(
+        format.right,
+        vec![
+            BarItem::Field(BarField::X),
+            BarItem::Text(String::from(", ")),
+            BarItem::Field(BarField::Y),
+            BarItem::Text(String::from("  ")),
+            BarItem::Field(BarField::Clock),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_without_separator_is_left_only() {
+    let format = StatusBarFormat::parse("notes: {notes}, selected: {note}");
+
+    assert_eq!(
+        format.left,
+        vec![
+            BarItem::Text(String::from("notes: ")),
+            BarItem::Field(BarField::NoteCount),
+            BarItem::Text(String::from(", selected: ")),
+            BarItem::Field(BarField::SelectedNote),
+        ]
+    );
+    assert!(format.right.is_empty());
+}
+
+#[test]
+fn test_parse_keeps_unknown_braces_as_text() {
+    let format = StatusBarFormat::parse("{date} {{mode}} {mode");
+
+    assert_eq!(
+        format.left,
+        vec![
+            BarItem::Text(String::from("{date} {")),
+            BarItem::Field(BarField::Mode),
+            BarItem::Text(String::from("} {mode")),
+        ]
+    );
+}
+
+#[test]
+fn test_contains() {
+    let format = StatusBarFormat::parse(DEFAULT_STATUS_BAR_FORMAT);
+
+    assert!(format.contains(BarField::Mode));
+    assert!(format.contains(BarField::Y));
+    assert!(!format.contains(BarField::Clock));
+}
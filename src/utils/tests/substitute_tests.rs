@@ -0,0 +1,67 @@
+use crate::utils::Substitution;
+
+fn parse(input: &str) -> Substitution {
+    Substitution::parse(input).unwrap()
+}
+
+#[test]
+fn test_parse_substitution() {
+    assert_eq!(
+        parse("/old name/new name/g"),
+        Substitution {
+            pattern: String::from("old name"),
+            replacement: String::from("new name"),
+            global: true,
+            regex: false,
+            ignore_case: false,
+        }
+    );
+
+    // Escaped delimiter, other delimiter, no flags
+    assert_eq!(parse(r"/a\/b/c").pattern, "a/b");
+    assert_eq!(parse("#a/b#c#ri").replacement, "c");
+    assert!(parse("#a/b#c#ri").regex && parse("#a/b#c#ri").ignore_case);
+    // Backslashes before other characters are kept for the regex
+    assert_eq!(parse(r"/\d+/n/r").pattern, r"\d+");
+
+    assert_eq!(Substitution::parse("//new/"), None);
+    assert_eq!(Substitution::parse("/old"), None);
+    assert_eq!(Substitution::parse("/old/new/x"), None);
+    assert_eq!(Substitution::parse("/a/b/g/extra"), None);
+    assert_eq!(Substitution::parse("aoldanewa"), None);
+}
+
+#[test]
+fn test_apply_literal() {
+    let substitution = parse("/a.b/x/");
+    let matcher = substitution.matcher().unwrap();
+
+    assert_eq!(
+        substitution.apply(&matcher, "a.b a.b acb"),
+        Some((String::from("x a.b acb"), 1))
+    );
+    assert_eq!(substitution.apply(&matcher, "acb"), None);
+
+    // `$` in a literal replacement isn't a group reference
+    let substitution = parse("/cost/$5/gi");
+    let matcher = substitution.matcher().unwrap();
+    assert_eq!(
+        substitution.apply(&matcher, "Cost and cost"),
+        Some((String::from("$5 and $5"), 2))
+    );
+}
+
+#[test]
+fn test_apply_regex() {
+    let substitution = parse(r"/(\w+)@v(\d)/${1}-v$2/gr");
+    let matcher = substitution.matcher().unwrap();
+
+    assert_eq!(
+        substitution.apply(&matcher, "api@v1, db@v2"),
+        Some((String::from("api-v1, db-v2"), 2))
+    );
+
+    assert!(parse("/(unclosed/x/r").matcher().is_err());
+    // The same pattern is fine when matched literally
+    assert!(parse("/(unclosed/x/").matcher().is_ok());
+}
@@ -0,0 +1,90 @@
+use ratatui::style::Color;
+use std::collections::HashMap;
+
+use crate::{
+    states::map::{Connection, Note, Side},
+    utils::{EncryptedText, SVG_CELL_HEIGHT, SVG_CELL_WIDTH, export_svg, svg_color, svg_escape},
+};
+
+fn create_connection(from_id: usize, to_id: usize, weight: Option<u32>) -> Connection {
+    Connection {
+        from_id,
+        from_side: Side::Right,
+        to_id,
+        to_side: Side::Left,
+        color: Color::Red,
+        waypoints: vec![],
+        weight,
+        layer: None,
+    }
+}
+
+#[test]
+fn test_export_svg_draws_notes_and_connections() {
+    let notes = HashMap::from([
+        (0, Note::new(10, 5, String::from("Plan"), Color::Green)),
+        (1, Note::new(50, 5, String::from("Ship"), Color::White)),
+    ]);
+    let connections = vec![
+        create_connection(0, 1, Some(3)),
+        // Dangling connections are left out
+        create_connection(1, 7, None),
+    ];
+
+    let document = export_svg(&notes, &connections);
+
+    assert!(document.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(document.trim_end().ends_with("</svg>"));
+    assert_eq!(document.matches("<polyline").count(), 1);
+    assert!(document.contains(&format!("stroke=\"{}\"", svg_color(Color::Red))));
+    assert!(document.contains(&format!("stroke=\"{}\"", svg_color(Color::Green))));
+    assert!(document.contains(">Plan</tspan>"));
+    assert!(document.contains(">Ship</tspan>"));
+    assert!(document.contains(">3</text>"));
+
+    // Notes span 10..71 by 5..9 cells, plus a 2 cell margin on each side
+    let (width, height) = ((61 + 4) * SVG_CELL_WIDTH, (4 + 4) * SVG_CELL_HEIGHT);
+    assert!(document.contains(&format!("width=\"{width}\" height=\"{height}\"")));
+}
+
+#[test]
+fn test_export_svg_escapes_and_hides_encrypted_notes() {
+    let mut secret = Note::new(0, 0, String::from("secret plan"), Color::White);
+    secret.encrypted = Some(EncryptedText {
+        salt: String::new(),
+        rounds: 1,
+        nonce: String::new(),
+        ciphertext: String::new(),
+    });
+    let notes = HashMap::from([
+        (0, secret),
+        (
+            1,
+            Note::new(0, 10, String::from("a < b\n& c"), Color::White),
+        ),
+    ]);
+
+    let document = export_svg(&notes, &[]);
+
+    assert!(document.contains(">[encrypted]</tspan>"));
+    assert!(!document.contains("secret"));
+    assert!(document.contains(">a &lt; b</tspan>"));
+    assert!(document.contains(">&amp; c</tspan>"));
+}
+
+#[test]
+fn test_export_svg_empty_map() {
+    let document = export_svg(&HashMap::new(), &[]);
+
+    assert!(document.contains("<svg"));
+    assert!(!document.contains("<tspan"));
+}
+
+#[test]
+fn test_svg_escape() {
+    assert_eq!(
+        svg_escape("\"x\" <y> & z"),
+        "&quot;x&quot; &lt;y&gt; &amp; z"
+    );
+    assert_eq!(svg_escape("a\tb"), "ab");
+}
@@ -0,0 +1,115 @@
+use ratatui::style::Color;
+use tempfile::tempdir;
+
+use crate::{
+    app::{App, Screen},
+    states::{
+        MapState, StartState,
+        map::{Notification, UndoStep},
+        settings::Settings,
+    },
+    utils::{
+        MapData, Substitution, encrypt, filesystem::test_utils::TempFileSystem,
+        load_map_file_with_fs, notes_fingerprint, read_undo_file, save_map_file,
+        save_settings_to_file_with_fs, undo_file_path, write_undo_file,
+    },
+};
+
+fn map_data_with_notes(contents: &[&str]) -> MapData {
+    let mut map_state = MapState::new_with_settings(Default::default(), Settings::new());
+    for content in contents {
+        map_state
+            .notes_state
+            .add(0, 0, content.to_string(), Color::White);
+    }
+    MapData::from_map_state(&mut map_state)
+}
+
+#[test]
+fn test_undo_file_round_trip() {
+    let dir = tempdir().unwrap();
+    let map_path = dir.path().join("map.json");
+    let map_data = map_data_with_notes(&["new text"]);
+    let steps = vec![UndoStep::NoteContents(vec![(0, String::from("old text"))])];
+
+    assert_eq!(
+        undo_file_path(&map_path),
+        dir.path().join("map.json.undo.gz")
+    );
+    assert_eq!(read_undo_file(&map_path, &map_data).unwrap(), vec![]);
+
+    write_undo_file(&map_path, &map_data, &steps).unwrap();
+    assert_eq!(read_undo_file(&map_path, &map_data).unwrap(), steps);
+
+    // Steps of a map changed since are dropped
+    let changed = map_data_with_notes(&["edited elsewhere"]);
+    assert_ne!(notes_fingerprint(&changed), notes_fingerprint(&map_data));
+    assert_eq!(read_undo_file(&map_path, &changed).unwrap(), vec![]);
+
+    // No steps left removes the file
+    write_undo_file(&map_path, &map_data, &[]).unwrap();
+    assert!(!undo_file_path(&map_path).exists());
+}
+
+#[test]
+fn test_undo_file_leaves_out_encrypted_notes() {
+    let dir = tempdir().unwrap();
+    let map_path = dir.path().join("map.json");
+    let mut map_data = map_data_with_notes(&["plain", "secret"]);
+    map_data.notes.get_mut(&1).unwrap().encrypted = Some(encrypt("secret", "passphrase"));
+    let steps = vec![
+        UndoStep::NoteContents(vec![(0, String::from("a")), (1, String::from("b"))]),
+        UndoStep::NoteContents(vec![(1, String::from("c"))]),
+    ];
+
+    write_undo_file(&map_path, &map_data, &steps).unwrap();
+    assert_eq!(
+        read_undo_file(&map_path, &map_data).unwrap(),
+        vec![UndoStep::NoteContents(vec![(0, String::from("a"))])]
+    );
+
+    write_undo_file(&map_path, &map_data, &steps[1..]).unwrap();
+    assert!(!undo_file_path(&map_path).exists());
+}
+
+#[test]
+fn test_undo_survives_reopening_the_map() {
+    let dir = tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: dir.path().to_path_buf(),
+    };
+    let mut settings = Settings::new();
+    settings.persist_undo = true;
+    save_settings_to_file_with_fs(&settings, &fs).unwrap();
+    let map_path = dir.path().join("map.json");
+
+    let mut map_state = MapState::new_with_settings(map_path.clone(), settings);
+    map_state
+        .notes_state
+        .add(0, 0, String::from("teh map"), Color::White);
+    map_state.substitute(&Substitution::parse("/teh/the/").unwrap());
+    save_map_file(&mut map_state, &map_path).unwrap();
+    assert!(undo_file_path(&map_path).exists());
+
+    let mut app = App {
+        running: true,
+        screen: Screen::Start(StartState::new_with_fs(&fs)),
+        workspace: None,
+    };
+    load_map_file_with_fs(&mut app, &map_path, &fs);
+    let Screen::Map(map_state) = &mut app.screen else {
+        panic!("the map should be open");
+    };
+    assert_eq!(map_state.notes_state.notes()[&0].content, "the map");
+
+    map_state.undo();
+    assert_eq!(map_state.notes_state.notes()[&0].content, "teh map");
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::Undone)
+    );
+
+    // Saving with nothing left to undo removes the file
+    save_map_file(map_state, &map_path).unwrap();
+    assert!(!undo_file_path(&map_path).exists());
+}
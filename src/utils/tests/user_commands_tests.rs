@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use crate::utils::{CommandInput, TmmprError, UserCommand, run_program};
+
+#[test]
+fn test_user_command_from_settings_file() {
+    let user_command: UserCommand =
+        serde_json::from_str(r#"{"name": "fmt", "command": "fmt -w 40"}"#).unwrap();
+    assert_eq!(
+        user_command,
+        UserCommand {
+            name: String::from("fmt"),
+            command: String::from("fmt -w 40"),
+            input: CommandInput::Note,
+            replace: false,
+        }
+    );
+
+    let user_command: UserCommand = serde_json::from_str(
+        r#"{"name": "stats", "command": "jq .notes", "input": "map", "replace": true}"#,
+    )
+    .unwrap();
+    assert_eq!(user_command.input, CommandInput::Map);
+    assert!(user_command.replace);
+}
+
+#[test]
+fn test_run_program() {
+    let map_path = Path::new("/maps/plan.json");
+
+    assert_eq!(
+        run_program("tr a-z A-Z", "shout\n", map_path).unwrap(),
+        "SHOUT\n"
+    );
+    assert_eq!(
+        run_program("echo \"$TMMPR_MAP\"", "", map_path).unwrap(),
+        "/maps/plan.json\n"
+    );
+    // Large input and output don't block on the pipes
+    let input = "line\n".repeat(100_000);
+    assert_eq!(run_program("cat", &input, map_path).unwrap(), input);
+    // Programs that don't read their input
+    assert_eq!(
+        run_program("echo done", &input, map_path).unwrap(),
+        "done\n"
+    );
+
+    match run_program("echo first >&2; echo second >&2; exit 3", "", map_path) {
+        Err(TmmprError::Validation(message)) => {
+            assert!(message.contains("exit status: 3"), "{message}");
+            assert!(message.ends_with(": first"), "{message}");
+        }
+        other => panic!("expected a failure, got {other:?}"),
+    }
+}
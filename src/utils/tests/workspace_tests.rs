@@ -0,0 +1,277 @@
+use std::{fs, path::Path};
+use tempfile::tempdir;
+
+use crate::{
+    app::{App, Screen},
+    states::{StartState, map::Notification},
+    utils::{
+        IoErrorKind, Workspace, create_map_file_with_fs, filesystem::test_utils::TempFileSystem,
+        load_map_file_with_fs, open_workspace_match_with_fs, open_workspace_with_fs,
+        switch_map_file_with_fs,
+    },
+};
+
+fn start_app(fs: &TempFileSystem) -> App {
+    App {
+        running: true,
+        screen: Screen::Start(StartState::new_with_fs(fs)),
+        workspace: None,
+    }
+}
+
+/// Creates empty map files with the given names in `dir`.
+fn create_maps(dir: &Path, names: &[&str], fs: &TempFileSystem) {
+    fs::create_dir_all(dir).unwrap();
+    for name in names {
+        create_map_file_with_fs(&mut start_app(fs), &dir.join(name), fs);
+    }
+}
+
+fn open_map_path(app: &App) -> &Path {
+    let Screen::Map(map_state) = &app.screen else {
+        panic!("expected the map screen");
+    };
+    &map_state.persistence.file_write_path
+}
+
+#[test]
+fn test_list_maps_sorted_by_name() {
+    let temp_dir = tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let dir = temp_dir.path().join("project");
+    create_maps(
+        &dir,
+        &["roadmap.json", "Backlog.json.gz", "ideas.json"],
+        &fs,
+    );
+    fs::write(dir.join("notes.txt"), "not a map").unwrap();
+    fs::create_dir_all(dir.join("ideas.json.snapshots")).unwrap();
+
+    let maps = Workspace::new(dir.clone()).list_maps().unwrap();
+
+    assert_eq!(
+        maps,
+        vec![
+            dir.join("Backlog.json.gz"),
+            dir.join("ideas.json"),
+            dir.join("roadmap.json"),
+        ]
+    );
+}
+
+#[test]
+fn test_open_workspace_loads_first_map() {
+    let temp_dir = tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let dir = temp_dir.path().join("project");
+    create_maps(&dir, &["b.json", "a.json"], &fs);
+    let mut app = start_app(&fs);
+
+    open_workspace_with_fs(&mut app, &dir, &fs);
+
+    assert_eq!(open_map_path(&app), dir.join("a.json"));
+    assert_eq!(app.workspace, Some(Workspace::new(dir.clone())));
+    let Screen::Map(map_state) = &app.screen else {
+        unreachable!()
+    };
+    assert_eq!(map_state.workspace, Some(Workspace::new(dir)));
+}
+
+#[test]
+fn test_open_workspace_without_maps_shows_error() {
+    let temp_dir = tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let dir = temp_dir.path().join("empty");
+    fs::create_dir_all(&dir).unwrap();
+    let mut app = start_app(&fs);
+
+    open_workspace_with_fs(&mut app, &dir, &fs);
+
+    let Screen::Start(start_state) = &app.screen else {
+        panic!("expected to stay on the start screen");
+    };
+    assert_eq!(start_state.display_err_msg, Some(IoErrorKind::FileRead));
+    assert_eq!(app.workspace, None);
+}
+
+#[test]
+fn test_switch_map_saves_changes_and_keeps_workspace() {
+    let temp_dir = tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let dir = temp_dir.path().join("project");
+    create_maps(&dir, &["a.json", "b.json"], &fs);
+    let mut app = start_app(&fs);
+    open_workspace_with_fs(&mut app, &dir, &fs);
+
+    if let Screen::Map(map_state) = &mut app.screen {
+        map_state.add_note();
+    }
+    switch_map_file_with_fs(&mut app, &dir.join("b.json"), &fs);
+
+    assert_eq!(open_map_path(&app), dir.join("b.json"));
+    let Screen::Map(map_state) = &app.screen else {
+        unreachable!()
+    };
+    assert_eq!(map_state.workspace, Some(Workspace::new(dir.clone())));
+
+    // The note added to the first map was saved before switching
+    switch_map_file_with_fs(&mut app, &dir.join("a.json"), &fs);
+    let Screen::Map(map_state) = &app.screen else {
+        unreachable!()
+    };
+    assert_eq!(map_state.notes_state.notes().len(), 1);
+}
+
+#[test]
+fn test_switch_to_unreadable_map_stays_on_open_map() {
+    let temp_dir = tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let dir = temp_dir.path().join("project");
+    create_maps(&dir, &["a.json"], &fs);
+    fs::write(dir.join("broken.json"), "{ not json").unwrap();
+    let mut app = start_app(&fs);
+    open_workspace_with_fs(&mut app, &dir, &fs);
+
+    switch_map_file_with_fs(&mut app, &dir.join("broken.json"), &fs);
+
+    assert_eq!(open_map_path(&app), dir.join("a.json"));
+    let Screen::Map(map_state) = &app.screen else {
+        unreachable!()
+    };
+    assert_eq!(
+        map_state.ui_state.notifications.latest(),
+        Some(&Notification::MapSwitchFail)
+    );
+}
+
+#[test]
+fn test_opening_map_outside_workspace_closes_it() {
+    let temp_dir = tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let dir = temp_dir.path().join("project");
+    create_maps(&dir, &["a.json"], &fs);
+    create_maps(temp_dir.path(), &["elsewhere.json"], &fs);
+    let mut app = start_app(&fs);
+    open_workspace_with_fs(&mut app, &dir, &fs);
+
+    load_map_file_with_fs(&mut app, &temp_dir.path().join("elsewhere.json"), &fs);
+
+    assert_eq!(app.workspace, None);
+    let Screen::Map(map_state) = &app.screen else {
+        unreachable!()
+    };
+    assert_eq!(map_state.workspace, None);
+}
+
+#[test]
+fn test_grep_workspace_lists_notes_of_every_map() {
+    let temp_dir = tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let dir = temp_dir.path().join("project");
+    create_maps(&dir, &["a.json", "b.json"], &fs);
+    let mut app = start_app(&fs);
+    open_workspace_with_fs(&mut app, &dir, &fs);
+    if let Screen::Map(map_state) = &mut app.screen {
+        map_state.paste_dsl("Launch plan -> Budget").unwrap();
+    }
+    switch_map_file_with_fs(&mut app, &dir.join("b.json"), &fs);
+
+    let Screen::Map(map_state) = &mut app.screen else {
+        unreachable!()
+    };
+    // Unsaved notes of the open map are searched too
+    map_state.paste_dsl("Launch party -> Guests").unwrap();
+    assert_eq!(map_state.grep_workspace("Launch"), None);
+
+    let results = &map_state
+        .ui_state
+        .workspace_search
+        .as_ref()
+        .unwrap()
+        .results;
+    let found: Vec<(&Path, &str)> = results
+        .iter()
+        .map(|found| (found.path.as_path(), found.line.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (dir.join("a.json").as_path(), "Launch plan"),
+            (dir.join("b.json").as_path(), "Launch party"),
+        ]
+    );
+    assert_eq!(
+        map_state.grep_workspace("nowhere"),
+        Some(Notification::PatternNotFound)
+    );
+}
+
+#[test]
+fn test_grep_without_workspace() {
+    let temp_dir = tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    create_maps(temp_dir.path(), &["a.json"], &fs);
+    let mut app = start_app(&fs);
+    load_map_file_with_fs(&mut app, &temp_dir.path().join("a.json"), &fs);
+
+    let Screen::Map(map_state) = &mut app.screen else {
+        unreachable!()
+    };
+    assert_eq!(
+        map_state.grep_workspace("plan"),
+        Some(Notification::NoWorkspace)
+    );
+}
+
+#[test]
+fn test_open_workspace_match_shows_note() {
+    let temp_dir = tempdir().unwrap();
+    let fs = TempFileSystem {
+        home_path: temp_dir.path().to_path_buf(),
+    };
+    let dir = temp_dir.path().join("project");
+    create_maps(&dir, &["a.json", "b.json"], &fs);
+    let mut app = start_app(&fs);
+    open_workspace_with_fs(&mut app, &dir, &fs);
+    if let Screen::Map(map_state) = &mut app.screen {
+        map_state.paste_dsl("Intro -> Launch plan").unwrap();
+    }
+    switch_map_file_with_fs(&mut app, &dir.join("b.json"), &fs);
+
+    let found = {
+        let Screen::Map(map_state) = &mut app.screen else {
+            unreachable!()
+        };
+        map_state.grep_workspace("plan");
+        let search = map_state.ui_state.workspace_search.take().unwrap();
+        search.selected_result().cloned().unwrap()
+    };
+    open_workspace_match_with_fs(&mut app, &found, "plan", &fs);
+
+    assert_eq!(open_map_path(&app), dir.join("a.json"));
+    let Screen::Map(map_state) = &app.screen else {
+        unreachable!()
+    };
+    let search = map_state.ui_state.search.as_ref().unwrap();
+    assert_eq!(search.query, "plan");
+    assert_eq!(
+        search.current.as_ref().map(|current| current.note_id),
+        Some(found.note_id)
+    );
+}
@@ -0,0 +1,118 @@
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    states::map::UndoStep,
+    utils::{MapData, TmmprError},
+};
+
+/// On-disk form of a map's undo history.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct UndoFile {
+    /// [`notes_fingerprint`] of the map the steps apply to
+    fingerprint: String,
+    /// Oldest first
+    steps: Vec<UndoStep>,
+}
+
+/// Path of the file keeping a map's undo history between sessions: the map file's name
+/// with `.undo.gz` appended, in the same directory.
+pub fn undo_file_path(map_path: &Path) -> PathBuf {
+    let mut file_name = map_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".undo.gz");
+    map_path.with_file_name(file_name)
+}
+
+/// Hash of the notes' ids and text, to tell whether an undo file still belongs to the
+/// map, or the map was changed without it (e.g. by another program).
+pub fn notes_fingerprint(map_data: &MapData) -> String {
+    let mut ids: Vec<&usize> = map_data.notes.keys().collect();
+    ids.sort_unstable();
+
+    let mut hasher = Sha256::new();
+    for id in ids {
+        hasher.update(id.to_le_bytes());
+        hasher.update(map_data.notes[id].content.as_bytes());
+        hasher.update([0]);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Writes the undo steps of the map saved as `map_data` next to its file, gzip-compressed,
+/// replacing the previous ones. Steps touching encrypted notes are left out so their text
+/// isn't written in the clear; without any steps left the file is removed.
+pub fn write_undo_file(
+    map_path: &Path,
+    map_data: &MapData,
+    steps: &[UndoStep],
+) -> Result<(), TmmprError> {
+    let is_encrypted = |id: &usize| {
+        map_data
+            .notes
+            .get(id)
+            .is_some_and(|note| note.encrypted.is_some())
+    };
+    let steps: Vec<UndoStep> = steps
+        .iter()
+        .filter_map(|step| match step {
+            UndoStep::NoteContents(contents) => {
+                let contents: Vec<(usize, String)> = contents
+                    .iter()
+                    .filter(|(id, _)| !is_encrypted(id))
+                    .cloned()
+                    .collect();
+                (!contents.is_empty()).then_some(UndoStep::NoteContents(contents))
+            }
+        })
+        .collect();
+
+    let path = undo_file_path(map_path);
+    if steps.is_empty() {
+        return remove_undo_file(map_path);
+    }
+
+    let undo_file = UndoFile {
+        fingerprint: notes_fingerprint(map_data),
+        steps,
+    };
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(serde_json::to_string(&undo_file)?.as_bytes())?;
+    fs::write(path, encoder.finish()?)?;
+    Ok(())
+}
+
+/// Removes the map's undo file, if it has one.
+pub fn remove_undo_file(map_path: &Path) -> Result<(), TmmprError> {
+    let path = undo_file_path(map_path);
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Reads the undo steps kept for the map loaded as `map_data`, oldest first. A map
+/// without an undo file has none, and so does one changed since the file was written.
+pub fn read_undo_file(map_path: &Path, map_data: &MapData) -> Result<Vec<UndoStep>, TmmprError> {
+    let path = undo_file_path(map_path);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut json_string = String::new();
+    GzDecoder::new(fs::File::open(path)?).read_to_string(&mut json_string)?;
+    let undo_file: UndoFile = serde_json::from_str(&json_string)?;
+    if undo_file.fingerprint != notes_fingerprint(map_data) {
+        return Ok(vec![]);
+    }
+    Ok(undo_file.steps)
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+};
+
+use crate::utils::TmmprError;
+
+/// What a user command's program gets on its standard input.
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandInput {
+    /// The selected note's content
+    #[default]
+    Note,
+    /// The whole map as JSON, in the map file's format
+    Map,
+}
+
+/// An external program run with `:run <name>`, defined in the settings file.
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
+pub struct UserCommand {
+    pub name: String,
+    /// Command line run with `sh -c`
+    pub command: String,
+    #[serde(default)]
+    pub input: CommandInput,
+    /// Put the program's output in place of the selected note's content; only used
+    /// with [`CommandInput::Note`]
+    #[serde(default)]
+    pub replace: bool,
+}
+
+/// Runs a command line with `sh -c`, giving it `input` on its standard input and the
+/// map file's path in `TMMPR_MAP`, and returns its standard output.
+///
+/// A program exiting with an error fails with the first line it wrote to its standard
+/// error.
+pub fn run_program(command: &str, input: &str, map_path: &Path) -> Result<String, TmmprError> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("TMMPR_MAP", map_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Written from another thread, so a program writing a lot of output before reading
+    // all of its input can't block on a full pipe
+    let mut stdin = child.stdin.take();
+    let input = input.to_string();
+    let writer = thread::spawn(move || {
+        if let Some(stdin) = stdin.as_mut() {
+            // A program that doesn't read its input closes the pipe early
+            let _ = stdin.write_all(input.as_bytes());
+        }
+    });
+    let output = child.wait_with_output()?;
+    let _ = writer.join();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().next().unwrap_or_default();
+        return Err(TmmprError::Validation(format!(
+            "`{command}` failed ({}): {reason}",
+            output.status
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
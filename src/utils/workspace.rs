@@ -0,0 +1,210 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    app::{App, Screen},
+    states::map::{LayersState, Note, Notification, SearchState},
+    utils::{
+        IoErrorKind, MapFileContents, RealFileSystem, TmmprError, filesystem::FileSystem,
+        finish_background_save, load_map_file_with_fs, map_name, read_map_file,
+        save_with_notification,
+    },
+};
+
+/// A note matching a workspace-wide search (`:grep`).
+#[derive(PartialEq, Clone, Debug)]
+pub struct WorkspaceMatch {
+    pub path: PathBuf,
+    pub note_id: usize,
+    /// The note's line containing the first match
+    pub line: String,
+}
+
+/// A directory of related maps opened from the Start screen, whose maps can be
+/// switched between from the map screen.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Workspace {
+    pub dir: PathBuf,
+}
+
+impl Workspace {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The directory's map files (`*.json` and `*.json.gz`), sorted by name.
+    /// Subdirectories, such as a map's snapshots, aren't searched.
+    pub fn list_maps(&self) -> Result<Vec<PathBuf>, TmmprError> {
+        let mut maps: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && is_map_file_name(path))
+            .collect();
+        maps.sort_by_key(|path| map_name(path).to_lowercase());
+        Ok(maps)
+    }
+
+    /// True if the map file is directly in the workspace directory.
+    pub fn contains(&self, path: &Path) -> bool {
+        path.parent() == Some(self.dir.as_path())
+    }
+}
+
+/// Searches the notes of every map in the workspace, in map order and then top to
+/// bottom on each map. The open map is searched as it is in memory, unsaved changes
+/// included; maps that are encrypted or can't be read are skipped, as are notes on
+/// hidden layers.
+pub fn search_workspace(
+    workspace: &Workspace,
+    search: &SearchState,
+    open_map: &Path,
+    open_notes: &HashMap<usize, Note>,
+    open_layers: &LayersState,
+) -> Vec<WorkspaceMatch> {
+    let Ok(maps) = workspace.list_maps() else {
+        return vec![];
+    };
+
+    let mut results = vec![];
+    for path in maps {
+        if path == open_map {
+            results.extend(search_notes(&path, search, open_notes, open_layers));
+        } else if let Ok(MapFileContents::Plain(map_data)) = read_map_file(&path) {
+            let layers = LayersState::from_layers(map_data.layers);
+            results.extend(search_notes(&path, search, &map_data.notes, &layers));
+        }
+    }
+    results
+}
+
+/// The notes matching the search on one map, see [`search_workspace`].
+pub fn search_notes(
+    path: &Path,
+    search: &SearchState,
+    notes: &HashMap<usize, Note>,
+    layers: &LayersState,
+) -> Vec<WorkspaceMatch> {
+    let matches = search.all_matches(notes, |id| layers.is_hidden(notes[&id].layer()));
+
+    let mut results: Vec<WorkspaceMatch> = vec![];
+    for search_match in matches {
+        // One entry per note, for its first match
+        if results
+            .last()
+            .is_some_and(|last| last.note_id == search_match.note_id)
+        {
+            continue;
+        }
+        let note = &notes[&search_match.note_id];
+        let text = match &note.title {
+            Some(title) if search_match.in_title => title,
+            _ => &note.content,
+        };
+        let line_start = text[..search_match.range.start]
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        let line = text[line_start..].lines().next().unwrap_or_default();
+        results.push(WorkspaceMatch {
+            path: path.to_path_buf(),
+            note_id: search_match.note_id,
+            line: line.trim().to_string(),
+        });
+    }
+    results
+}
+
+fn is_map_file_name(path: &Path) -> bool {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    file_name.ends_with(".json") || file_name.ends_with(".json.gz")
+}
+
+/// Opens a directory as a workspace and loads its first map.
+pub fn open_workspace(app: &mut App, dir: &Path) {
+    open_workspace_with_fs(app, dir, &RealFileSystem);
+}
+
+/// Opens a workspace with a custom filesystem (testable version).
+///
+/// A directory that can't be read or has no maps shows the read error on the Start
+/// screen.
+pub fn open_workspace_with_fs(app: &mut App, dir: &Path, fs: &impl FileSystem) {
+    let workspace = Workspace::new(dir.to_path_buf());
+
+    let first_map = workspace
+        .list_maps()
+        .ok()
+        .and_then(|maps| maps.into_iter().next());
+    let Some(first_map) = first_map else {
+        if let Screen::Start(start_state) = &mut app.screen {
+            start_state.handle_submit_error(IoErrorKind::FileRead);
+        }
+        return;
+    };
+
+    app.workspace = Some(workspace);
+    load_map_file_with_fs(app, &first_map, fs);
+}
+
+/// Opens the map of a workspace search result and shows the matching note.
+pub fn open_workspace_match(app: &mut App, found: &WorkspaceMatch, query: &str) {
+    open_workspace_match_with_fs(app, found, query, &RealFileSystem);
+}
+
+/// Opens a workspace search result with a custom filesystem (testable version).
+pub fn open_workspace_match_with_fs(
+    app: &mut App,
+    found: &WorkspaceMatch,
+    query: &str,
+    fs: &impl FileSystem,
+) {
+    switch_map_file_with_fs(app, &found.path, fs);
+
+    if let Screen::Map(map_state) = &mut app.screen
+        && map_state.persistence.file_write_path == found.path
+    {
+        map_state.show_search_match(query, found.note_id);
+    }
+}
+
+/// Switches the map screen to another map of the workspace.
+pub fn switch_map_file(app: &mut App, path: &Path) {
+    switch_map_file_with_fs(app, path, &RealFileSystem);
+}
+
+/// Switches to another map with a custom filesystem (testable version).
+///
+/// Unsaved changes to the open map are saved first; if that fails the open map stays.
+/// A map that can't be read leaves the open map too, with a notification.
+pub fn switch_map_file_with_fs(app: &mut App, path: &Path, fs: &impl FileSystem) {
+    if let Screen::Map(map_state) = &mut app.screen {
+        // A save still being written may turn out to have failed
+        finish_background_save(map_state, true);
+
+        if map_state.persistence.has_unsaved_changes {
+            let write_path = map_state.persistence.file_write_path.clone();
+            let saved = save_with_notification(
+                map_state,
+                &write_path,
+                Notification::SaveSuccess,
+                Notification::SaveFail,
+            );
+            if saved.is_err() {
+                return;
+            }
+        }
+    }
+
+    load_map_file_with_fs(app, path, fs);
+
+    if let Screen::Map(map_state) = &mut app.screen
+        && map_state.persistence.file_write_path != path
+    {
+        map_state
+            .ui_state
+            .set_notification(Notification::MapSwitchFail);
+        map_state.clear_and_redraw();
+    }
+}